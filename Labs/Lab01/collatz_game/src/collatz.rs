@@ -1,26 +1,58 @@
 pub struct Collatz;
 
 impl Collatz {
-    pub fn verify(&self, number: u64, iter: u32) -> bool {
+    /// [`Collatz::steps`] of every number, a number being verified if it is `Some`.
+    pub fn steps_many(&self, numbers: &[u64], iter: u32) -> Vec<Option<u32>> {
+        numbers.iter().map(|n| self.steps(*n, iter)).collect()
+    }
+
+    /// Number of steps `number` takes to reach 1, or `None` if it does not get
+    /// there within `iter` steps or the sequence leaves the range of `u64`.
+    pub fn steps(&self, number: u64, iter: u32) -> Option<u32> {
         let mut current = number;
-        for _ in 0..iter {
+        for steps in 0..iter {
             if self.meet_condition(current) {
-                return true;
+                return Some(steps);
             }
-            current = self.step(current);
+            current = self.step(current)?;
         }
-        self.meet_condition(current)
-    }
-
-    pub fn verify_many(&self, numbers: &[u64], iter: u32) -> Vec<bool> {
-        numbers.iter().map(|n| self.verify(*n, iter)).collect()
+        self.meet_condition(current).then_some(iter)
     }
 
-    fn step(&self, n: u64) -> u64 {
-        if n % 2 == 0 { n / 2 } else { 3 * n + 1 }
+    fn step(&self, n: u64) -> Option<u64> {
+        if n.is_multiple_of(2) {
+            Some(n / 2)
+        } else {
+            n.checked_mul(3)?.checked_add(1)
+        }
     }
 
     fn meet_condition(&self, n: u64) -> bool {
         n == 1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(Collatz.steps(1, 0), Some(0));
+        assert_eq!(Collatz.steps(6, 100), Some(8));
+        assert_eq!(Collatz.steps(27, 111), Some(111));
+        assert_eq!(Collatz.steps(27, 110), None);
+        assert_eq!(
+            Collatz.steps_many(&[1, 6, 27], 100),
+            [Some(0), Some(8), None]
+        );
+    }
+
+    #[test]
+    fn test_step_overflow() {
+        assert_eq!(Collatz.step(u64::MAX), None);
+        assert_eq!(Collatz.step(u64::MAX / 3), None);
+        assert_eq!(Collatz.step(u64::MAX - 1), Some(u64::MAX / 2));
+        assert_eq!(Collatz.steps(u64::MAX, 1_000), None);
+    }
+}
@@ -1,27 +1,137 @@
 use std::{
+    fmt::Display,
     fs::OpenOptions,
     io::{self, Write},
+    ops::RangeInclusive,
+    path::PathBuf,
 };
 
 use rand::{Rng, rngs::ThreadRng};
 
 use crate::collatz::Collatz;
 
+/// What happened during a single [`CollatzGame::play`] session.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionStats {
+    pub numbers_tested: usize,
+    pub numbers_verified: usize,
+    /// The number which took the most steps to reach 1, with the step count.
+    pub longest_sequence: Option<(u64, u32)>,
+}
+
+impl SessionStats {
+    fn record(&mut self, number: u64, steps: Option<u32>) {
+        self.numbers_tested += 1;
+
+        let Some(steps) = steps else {
+            return;
+        };
+        self.numbers_verified += 1;
+        if self
+            .longest_sequence
+            .is_none_or(|(_, longest)| steps > longest)
+        {
+            self.longest_sequence = Some((number, steps));
+        }
+    }
+}
+
+impl Display for SessionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Numbers tested: {}", self.numbers_tested)?;
+        writeln!(f, "Numbers verified: {}", self.numbers_verified)?;
+        match self.longest_sequence {
+            Some((number, steps)) => write!(f, "Longest sequence: {number} in {steps} steps"),
+            None => write!(f, "Longest sequence: none"),
+        }
+    }
+}
+
 pub struct CollatzGame {
     rng: ThreadRng,
     collatz: Collatz,
+    output_path: PathBuf,
+    increment_range: RangeInclusive<u64>,
+    power_count: usize,
+    step_limit: u32,
+    stats: SessionStats,
 }
 
-impl Default for CollatzGame {
+pub struct CollatzGameBuilder {
+    output_path: PathBuf,
+    increment_range: RangeInclusive<u64>,
+    power_count: usize,
+    step_limit: u32,
+}
+
+impl Default for CollatzGameBuilder {
     fn default() -> Self {
         Self {
+            output_path: PathBuf::from("xyz.txt"),
+            increment_range: 0..=5,
+            power_count: 10,
+            step_limit: 100,
+        }
+    }
+}
+
+impl CollatzGameBuilder {
+    /// File the results of every round are appended to.
+    pub fn output_path(mut self, output_path: impl Into<PathBuf>) -> Self {
+        self.output_path = output_path.into();
+        self
+    }
+
+    /// Range of the random number added to every number entered.
+    pub fn increment_range(mut self, increment_range: RangeInclusive<u64>) -> Self {
+        assert!(
+            !increment_range.is_empty(),
+            "the increment range must not be empty"
+        );
+        self.increment_range = increment_range;
+        self
+    }
+
+    /// How many consecutive powers of the incremented number are tested.
+    pub fn power_count(mut self, power_count: usize) -> Self {
+        self.power_count = power_count;
+        self
+    }
+
+    /// How many steps a number may take to reach 1 to count as verified.
+    pub fn step_limit(mut self, step_limit: u32) -> Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    pub fn build(self) -> CollatzGame {
+        CollatzGame {
             rng: rand::rng(),
             collatz: Collatz,
+            output_path: self.output_path,
+            increment_range: self.increment_range,
+            power_count: self.power_count,
+            step_limit: self.step_limit,
+            stats: SessionStats::default(),
         }
     }
 }
 
+impl Default for CollatzGame {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
 impl CollatzGame {
+    pub fn builder() -> CollatzGameBuilder {
+        CollatzGameBuilder::default()
+    }
+
+    pub fn stats(&self) -> &SessionStats {
+        &self.stats
+    }
+
     pub fn play(&mut self) -> io::Result<bool> {
         loop {
             println!("Enter a number:");
@@ -46,21 +156,26 @@ impl CollatzGame {
 
             println!("You entered: {x}");
 
-            let increment: u64 = self.rng.random_range(0..=5);
+            let increment: u64 = self.rng.random_range(self.increment_range.clone());
 
-            x += increment;
+            x = x.saturating_add(increment);
 
             println!("Incremented value: {x}");
 
-            let powers = self.get_powers(x, 10);
+            let powers = self.get_powers(x, self.power_count);
             println!("Generated powers: {powers:?}");
 
-            let results = self.collatz.verify_many(&powers, 100);
+            let steps = self.collatz.steps_many(&powers, self.step_limit);
+            let results: Vec<bool> = steps.iter().map(Option::is_some).collect();
             println!("Collatz results: {results:?}");
 
+            for (&power, &steps) in powers.iter().zip(&steps) {
+                self.stats.record(power, steps);
+            }
+
             match self.save_to_file(&results) {
                 Ok(()) => {
-                    println!("Results saved to xyz.txt");
+                    println!("Results saved to {}", self.output_path.display());
                 }
                 Err(e) => {
                     println!("File save error: {e}");
@@ -70,23 +185,100 @@ impl CollatzGame {
         }
     }
 
+    /// The first `size` powers of `value`, stopping early at the first one which
+    /// does not fit in a `u64`.
     fn get_powers(&self, value: u64, size: usize) -> Vec<u64> {
         let mut powers = Vec::with_capacity(size);
-        let mut current = value;
-        for _ in 0..size {
-            powers.push(current);
-            current *= value;
+        let mut current = Some(value);
+        while let Some(power) = current
+            && powers.len() < size
+        {
+            powers.push(power);
+            current = power.checked_mul(value);
         }
         powers
     }
 
-    fn save_to_file(&self, values: &Vec<bool>) -> io::Result<()> {
+    fn save_to_file(&self, values: &[bool]) -> io::Result<()> {
         let mut f = OpenOptions::new()
             .create(true)
             .append(true)
-            .open("xyz.txt")?;
+            .open(&self.output_path)?;
 
         writeln!(f, "{values:?}")?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let game = CollatzGame::builder().build();
+        assert_eq!(game.output_path, PathBuf::from("xyz.txt"));
+        assert_eq!(game.increment_range, 0..=5);
+        assert_eq!(game.power_count, 10);
+        assert_eq!(game.step_limit, 100);
+        assert_eq!(game.stats(), &SessionStats::default());
+    }
+
+    #[test]
+    fn test_builder_setters() {
+        let game = CollatzGame::builder()
+            .output_path("results.txt")
+            .increment_range(3..=3)
+            .power_count(4)
+            .step_limit(20)
+            .build();
+        assert_eq!(game.output_path, PathBuf::from("results.txt"));
+        assert_eq!(game.increment_range, 3..=3);
+        assert_eq!(game.power_count, 4);
+        assert_eq!(game.step_limit, 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "the increment range must not be empty")]
+    fn test_builder_rejects_empty_increment_range() {
+        #[allow(clippy::reversed_empty_ranges)]
+        CollatzGame::builder().increment_range(5..=0);
+    }
+
+    #[test]
+    fn test_get_powers() {
+        let game = CollatzGame::default();
+        assert_eq!(game.get_powers(3, 4), [3, 9, 27, 81]);
+        assert_eq!(game.get_powers(1, 3), [1, 1, 1]);
+        assert_eq!(game.get_powers(7, 0), []);
+    }
+
+    #[test]
+    fn test_get_powers_overflow() {
+        let game = CollatzGame::default();
+
+        let powers = game.get_powers(2, 100);
+        assert_eq!(powers.len(), 63);
+        assert_eq!(powers.last(), Some(&(1 << 63)));
+
+        assert_eq!(game.get_powers(u64::MAX, 10), [u64::MAX]);
+        assert_eq!(game.get_powers(1 << 32, 10), [1 << 32]);
+    }
+
+    #[test]
+    fn test_stats_record() {
+        let mut stats = SessionStats::default();
+        stats.record(6, Some(8));
+        stats.record(u64::MAX, None);
+        stats.record(7, Some(16));
+        stats.record(3, Some(7));
+        assert_eq!(
+            stats,
+            SessionStats {
+                numbers_tested: 4,
+                numbers_verified: 3,
+                longest_sequence: Some((7, 16)),
+            }
+        );
+    }
+}
@@ -8,11 +8,20 @@ use collatz_game::CollatzGame;
 use tuples::tuple_operations;
 
 fn main() -> io::Result<()> {
-    let mut collatz = CollatzGame::default();
-    match collatz.play()? {
-        true => println!("Game exited due to an error."),
-        false => println!("Game exited by the user."),
+    let mut collatz = CollatzGame::builder()
+        .output_path("xyz.txt")
+        .increment_range(0..=5)
+        .power_count(10)
+        .step_limit(100)
+        .build();
+    let outcome = collatz.play();
+    match outcome {
+        Ok(true) => println!("Game exited due to an error."),
+        Ok(false) => println!("Game exited by the user."),
+        Err(_) => println!("Game failed."),
     }
+    println!("{}", collatz.stats());
+    outcome?;
 
     tuple_operations();
 
@@ -2,7 +2,15 @@ mod double_string;
 mod number_with_units;
 
 use double_string::DoubleString;
-use number_with_units::{NumberWithUnits, NumericOperations, mul_vals, mul_vals_vec};
+use number_with_units::{NumberWithUnits, NumericOperations, UnitError, mul_vals, mul_vals_vec};
+
+fn checked_speed() -> Result<NumberWithUnits, UnitError> {
+    let start = NumberWithUnits::with_unit(2.0, "m".to_string());
+    let end = NumberWithUnits::with_unit(10.0, "m".to_string());
+    let time = NumberWithUnits::with_unit(4.0, "s".to_string());
+
+    (end - start)? / time
+}
 
 fn main() {
     let num1 = NumberWithUnits::unitless(12.22);
@@ -39,6 +47,22 @@ fn main() {
     println!("Product using vector: {product_vec:?}");
     println!("Product using vector again: {product_vec_again:?}");
 
+    match checked_speed() {
+        Ok(speed) => println!("Speed using std::ops: {speed:?}"),
+        Err(e) => println!("Failed to compute the speed: {e}"),
+    }
+
+    let meters = NumberWithUnits::with_unit(1.0, "m".to_string());
+    let seconds = NumberWithUnits::with_unit(1.0, "s".to_string());
+    let zero = NumberWithUnits::unitless(0.0);
+    if let Err(e) = &meters + &seconds {
+        println!("Adding meters and seconds: {e}");
+    }
+    if let Err(e) = &meters / &zero {
+        println!("Dividing by zero: {e}");
+    }
+    println!("Area: {:?}", &meters * &meters);
+
     let string = String::from("Hello");
     let str_slice = "World";
 
@@ -2,13 +2,16 @@ mod numeric_operations;
 mod in_place_numeric_operations;
 mod mul_vals;
 mod mul_vals_vec;
+mod ops;
+mod unit_error;
 
 pub use numeric_operations::NumericOperations;
 pub use in_place_numeric_operations::InPlaceNumericOperations;
 pub use mul_vals::mul_vals;
 pub use mul_vals_vec::mul_vals_vec;
+pub use unit_error::UnitError;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct NumberWithUnits {
     value: f64,
     unit: String,
@@ -34,6 +37,24 @@ impl NumberWithUnits {
     }
 }
 
+fn mul_unit(left: &str, right: &str) -> String {
+    if left.is_empty() {
+        right.to_string()
+    } else if right.is_empty() {
+        left.to_string()
+    } else {
+        format!("{left}*{right}")
+    }
+}
+
+fn div_unit(left: &str, right: &str) -> String {
+    if left == right {
+        String::new()
+    } else {
+        format!("{left}/{right}")
+    }
+}
+
 impl NumericOperations for NumberWithUnits {
     fn add(self, other: Self) -> Self {
         if self.unit == other.unit {
@@ -47,16 +68,9 @@ impl NumericOperations for NumberWithUnits {
     }
 
     fn mul(self, other: Self) -> Self {
-        let unit = if self.unit.is_empty() {
-            other.unit.clone()
-        } else if other.unit.is_empty() {
-            self.unit.clone()
-        } else {
-            format!("{}*{}", self.unit, other.unit)
-        };
         NumberWithUnits {
             value: self.value * other.value,
-            unit,
+            unit: mul_unit(&self.unit, &other.unit),
         }
     }
 
@@ -67,11 +81,7 @@ impl NumericOperations for NumberWithUnits {
 
         NumberWithUnits {
             value: self.value / other.value,
-            unit: if self.unit == other.unit {
-                String::new()
-            } else {
-                format!("{}/{}", self.unit, other.unit)
-            }
+            unit: div_unit(&self.unit, &other.unit),
         }
     }
 }
@@ -87,13 +97,7 @@ impl InPlaceNumericOperations for NumberWithUnits {
 
     fn mul_in_place(&mut self, other: &Self) {
         self.value *= other.value;
-        self.unit = if self.unit.is_empty() {
-            other.unit.clone()
-        } else if other.unit.is_empty() {
-            self.unit.clone()
-        } else {
-            format!("{}*{}", self.unit, other.unit)
-        };
+        self.unit = mul_unit(&self.unit, &other.unit);
     }
 
     fn div_in_place(&mut self, other: &Self) {
@@ -102,10 +106,6 @@ impl InPlaceNumericOperations for NumberWithUnits {
         }
 
         self.value /= other.value;
-        if self.unit == other.unit {
-            self.unit.clear();
-        } else {
-            self.unit = format!("{}/{}", self.unit, other.unit);
-        }
+        self.unit = div_unit(&self.unit, &other.unit);
     }
 }
\ No newline at end of file
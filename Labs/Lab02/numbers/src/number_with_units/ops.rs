@@ -0,0 +1,181 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use super::{NumberWithUnits, UnitError};
+
+impl NumberWithUnits {
+    fn same_unit(&self, other: &Self) -> Result<String, UnitError> {
+        if self.unit == other.unit {
+            Ok(self.unit.clone())
+        } else {
+            Err(UnitError::MismatchedUnits {
+                left: self.unit.clone(),
+                right: other.unit.clone(),
+            })
+        }
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self, UnitError> {
+        Ok(NumberWithUnits {
+            value: self.value + other.value,
+            unit: self.same_unit(other)?,
+        })
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self, UnitError> {
+        Ok(NumberWithUnits {
+            value: self.value - other.value,
+            unit: self.same_unit(other)?,
+        })
+    }
+
+    pub fn try_div(&self, other: &Self) -> Result<Self, UnitError> {
+        if other.value == 0.0 {
+            return Err(UnitError::DivisionByZero);
+        }
+
+        Ok(NumberWithUnits {
+            value: self.value / other.value,
+            unit: super::div_unit(&self.unit, &other.unit),
+        })
+    }
+}
+
+impl Add for &NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.try_add(other)
+    }
+}
+
+impl Add for NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.try_add(&other)
+    }
+}
+
+impl Sub for &NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.try_sub(other)
+    }
+}
+
+impl Sub for NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.try_sub(&other)
+    }
+}
+
+/// Multiplication cannot fail, as any two units can be combined.
+impl Mul for &NumberWithUnits {
+    type Output = NumberWithUnits;
+
+    fn mul(self, other: Self) -> Self::Output {
+        NumberWithUnits {
+            value: self.value * other.value,
+            unit: super::mul_unit(&self.unit, &other.unit),
+        }
+    }
+}
+
+impl Mul for NumberWithUnits {
+    type Output = NumberWithUnits;
+
+    fn mul(self, other: Self) -> Self::Output {
+        &self * &other
+    }
+}
+
+impl Div for &NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn div(self, other: Self) -> Self::Output {
+        self.try_div(other)
+    }
+}
+
+impl Div for NumberWithUnits {
+    type Output = Result<NumberWithUnits, UnitError>;
+
+    fn div(self, other: Self) -> Self::Output {
+        self.try_div(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meters(value: f64) -> NumberWithUnits {
+        NumberWithUnits::with_unit(value, "m".to_string())
+    }
+
+    fn seconds(value: f64) -> NumberWithUnits {
+        NumberWithUnits::with_unit(value, "s".to_string())
+    }
+
+    fn mismatched() -> UnitError {
+        UnitError::MismatchedUnits {
+            left: "m".to_string(),
+            right: "s".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_sub_same_unit() {
+        assert_eq!(meters(2.0).try_add(&meters(3.5)), Ok(meters(5.5)));
+        assert_eq!(meters(2.0).try_sub(&meters(3.5)), Ok(meters(-1.5)));
+        assert_eq!(&meters(2.0) + &meters(1.0), Ok(meters(3.0)));
+        assert_eq!(meters(2.0) - meters(1.0), Ok(meters(1.0)));
+    }
+
+    #[test]
+    fn test_add_and_sub_mismatched_units() {
+        assert_eq!(meters(1.0).try_add(&seconds(1.0)), Err(mismatched()));
+        assert_eq!(meters(1.0).try_sub(&seconds(1.0)), Err(mismatched()));
+        assert_eq!(meters(1.0) + seconds(1.0), Err(mismatched()));
+        assert_eq!(
+            &NumberWithUnits::unitless(1.0) - &meters(1.0),
+            Err(UnitError::MismatchedUnits {
+                left: String::new(),
+                right: "m".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_div() {
+        assert_eq!(
+            meters(9.0).try_div(&seconds(2.0)),
+            Ok(NumberWithUnits::with_unit(4.5, "m/s".to_string()))
+        );
+        assert_eq!(
+            meters(9.0) / meters(3.0),
+            Ok(NumberWithUnits::unitless(3.0))
+        );
+    }
+
+    #[test]
+    fn test_div_by_zero() {
+        assert_eq!(
+            meters(1.0).try_div(&seconds(0.0)),
+            Err(UnitError::DivisionByZero)
+        );
+        assert_eq!(&meters(0.0) / &meters(-0.0), Err(UnitError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(
+            &meters(2.0) * &seconds(3.0),
+            NumberWithUnits::with_unit(6.0, "m*s".to_string())
+        );
+        assert_eq!(NumberWithUnits::unitless(2.0) * meters(3.0), meters(6.0));
+    }
+}
@@ -0,0 +1,20 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    MismatchedUnits { left: String, right: String },
+    DivisionByZero,
+}
+
+impl Display for UnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MismatchedUnits { left, right } => {
+                write!(f, "cannot combine numbers in '{left}' and '{right}'")
+            }
+            Self::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
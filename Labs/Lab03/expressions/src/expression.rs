@@ -1,144 +1,444 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display, rc::Rc};
 
-use super::{Const, Var};
+use super::{
+    Const, ParseError, Var,
+    fold::{Fold, Visit, walk},
+    functions, parser,
+};
 
-#[derive(Clone, Debug)]
+/// An expression tree. Subtrees are reference counted, so cloning an expression
+/// is shallow and transformations share every subtree they leave untouched.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum E {
-    Add(Box<E>, Box<E>),
-    Neg(Box<E>),
-    Mul(Box<E>, Box<E>),
-    Inv(Box<E>),
+    Add(Rc<E>, Rc<E>),
+    Sub(Rc<E>, Rc<E>),
+    Neg(Rc<E>),
+    Mul(Rc<E>, Rc<E>),
+    Div(Rc<E>, Rc<E>),
+    Inv(Rc<E>),
+    Pow(Rc<E>, Rc<E>),
     Const(Const),
-    Func { name: String, arg: Box<E> },
+    Func { name: String, arg: Rc<E> },
     Var(Var),
 }
 
 impl E {
-    pub fn add(lhs: Box<Self>, rhs: Box<Self>) -> Box<Self> {
-        Box::new(Self::Add(lhs, rhs))
+    pub fn add(lhs: Rc<Self>, rhs: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Add(lhs, rhs))
     }
 
-    pub fn neg(expr: Box<Self>) -> Box<Self> {
-        Box::new(Self::Neg(expr))
+    pub fn sub(lhs: Rc<Self>, rhs: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Sub(lhs, rhs))
     }
 
-    pub fn mul(lhs: Box<Self>, rhs: Box<Self>) -> Box<Self> {
-        Box::new(Self::Mul(lhs, rhs))
+    pub fn neg(expr: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Neg(expr))
     }
 
-    pub fn inv(expr: Box<Self>) -> Box<Self> {
-        Box::new(Self::Inv(expr))
+    pub fn mul(lhs: Rc<Self>, rhs: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Mul(lhs, rhs))
     }
 
-    pub fn constant(constant: Const) -> Box<Self> {
-        Box::new(Self::Const(constant))
+    pub fn div(lhs: Rc<Self>, rhs: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Div(lhs, rhs))
     }
 
-    pub fn func(name: String, arg: Box<Self>) -> Box<Self> {
-        Box::new(Self::Func { name, arg })
+    pub fn inv(expr: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Inv(expr))
     }
 
-    pub fn var(variable: Var) -> Box<Self> {
-        Box::new(Self::Var(variable))
+    pub fn pow(base: Rc<Self>, exponent: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Pow(base, exponent))
     }
 
-    pub fn arg_count(&self) -> u32 {
-        match self {
-            Self::Var(_) | Self::Const(_) => 0,
-            Self::Neg(_) | Self::Inv(_) | E::Func { .. } => 1,
-            Self::Add(_, _) | Self::Mul(_, _) => 2,
-        }
+    pub fn constant(constant: Const) -> Rc<Self> {
+        Rc::new(Self::Const(constant))
     }
 
-    pub fn diff(self, by: Var) -> Box<Self> {
-        match self {
-            Self::Add(a, b) => Self::add(a.diff(by), b.diff(by)),
-            Self::Neg(a) => Self::neg(a.diff(by)),
-            Self::Mul(a, b) => Self::add(
-                Self::mul(a.clone().diff(by), b.clone()),
-                Self::mul(a, b.diff(by)),
-            ),
-            Self::Inv(a) => Self::mul(
-                Self::neg(Self::inv(Self::mul(a.clone(), a.clone()))),
-                a.clone().diff(by),
-            ),
-            Self::Const(_) => Self::constant(Const::Numeric(0)),
-            Self::Func { name, arg } => Self::mul(
-                Self::func(format!("{name}_{by}"), arg.clone()),
-                arg.diff(by),
-            ),
-            Self::Var(v) => {
-                if v == by {
-                    Self::constant(Const::Numeric(1))
-                } else {
-                    Self::constant(Const::Numeric(0))
-                }
+    pub fn func(name: String, arg: Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Func { name, arg })
+    }
+
+    pub fn var(variable: Var) -> Rc<Self> {
+        Rc::new(Self::Var(variable))
+    }
+
+    /// Parses an expression from text, see [`parser::parse`] for the accepted syntax.
+    pub fn parse(input: &str) -> Result<Rc<Self>, ParseError> {
+        parser::parse(input)
+    }
+
+    /// Number of direct operands of the expression.
+    pub fn arg_count(&self) -> u32 {
+        struct Operands(u32);
+
+        impl Visit for Operands {
+            fn visit(&mut self, _operand: &E) {
+                self.0 += 1;
             }
         }
+
+        let mut operands = Operands(0);
+        walk(&mut operands, self);
+        operands.0
     }
 
-    pub fn unpack_inv_inv(self) -> Option<Box<Self>> {
-        if let Self::Inv(frac) = self {
-            if let Self::Inv(frac) = *frac {
-                return Some(frac);
-            }
+    /// Differentiates the expression by `by`. Operands copied into the result
+    /// (such as `b` in `(a * b)' = a' * b + a * b'`) are shared, not cloned.
+    pub fn diff(self: &Rc<Self>, by: Var) -> Rc<Self> {
+        Derivative { by }.fold(self.clone())
+    }
+
+    pub fn unpack_inv_inv(&self) -> Option<Rc<Self>> {
+        if let Self::Inv(frac) = self
+            && let Self::Inv(frac) = &**frac
+        {
+            return Some(frac.clone());
         }
 
         None
     }
 
-    pub fn uninv(self: Box<Self>) -> Box<Self> {
+    pub fn uninv(self: Rc<Self>) -> Rc<Self> {
         let mut f = self;
 
-        while let Some(e) = f.clone().unpack_inv_inv() {
+        while let Some(e) = f.unpack_inv_inv() {
             f = e;
         }
 
         f
     }
 
-    pub fn unpack_neg_neg(self) -> Option<Box<Self>> {
+    pub fn unpack_neg_neg(&self) -> Option<Rc<Self>> {
         if let Self::Neg(u) = self
-            && let Self::Neg(v) = *u
+            && let Self::Neg(v) = &**u
         {
-            return Some(v);
+            return Some(v.clone());
         }
 
         None
     }
 
-    pub fn unneg(self: Box<Self>) -> Box<Self> {
+    pub fn unneg(self: Rc<Self>) -> Rc<Self> {
         let mut f = self;
 
-        while let Some(e) = f.clone().unpack_neg_neg() {
+        while let Some(e) = f.unpack_neg_neg() {
             f = e;
         }
 
         f
     }
-    pub fn substitute(self, name: &str, value: Box<Self>) -> Box<Self> {
-        match self {
-            Self::Add(lhs, rhs) => Self::add(
-                lhs.substitute(name, value.clone()),
-                rhs.substitute(name, value),
+
+    /// Replaces every occurrence of the named constant `name` with `value`.
+    /// Subtrees which do not mention `name` are returned as they are.
+    pub fn substitute(self: Rc<Self>, name: &str, value: Rc<Self>) -> Rc<Self> {
+        let mut substitution = Substitution::default();
+        substitution.constants.insert(name, value);
+        substitution.fold(self)
+    }
+
+    /// Replaces named constants and variables in a single simultaneous pass:
+    /// keys naming a variable (`X`, `Y`, `Z` in either case) replace that variable,
+    /// all other keys the named constant. Replacements are not substituted again,
+    /// so `{a: b, b: a}` swaps `a` and `b`.
+    pub fn substitute_all(self: Rc<Self>, substitutions: &HashMap<String, E>) -> Rc<Self> {
+        let mut substitution = Substitution::default();
+        for (name, value) in substitutions {
+            let value = Rc::new(value.clone());
+            match Var::from_name(name) {
+                Some(variable) => substitution.variables.insert(variable, value),
+                None => substitution.constants.insert(name, value),
+            };
+        }
+        substitution.fold(self)
+    }
+
+    /// Direct operands of the expression, left to right.
+    pub fn children(&self) -> impl Iterator<Item = &Rc<Self>> {
+        let (first, second) = match self {
+            Self::Add(lhs, rhs)
+            | Self::Sub(lhs, rhs)
+            | Self::Mul(lhs, rhs)
+            | Self::Div(lhs, rhs)
+            | Self::Pow(lhs, rhs) => (Some(lhs), Some(rhs)),
+            Self::Neg(expr) | Self::Inv(expr) | Self::Func { arg: expr, .. } => (Some(expr), None),
+            Self::Const(_) | Self::Var(_) => (None, None),
+        };
+        first.into_iter().chain(second)
+    }
+
+    fn children_mut(&mut self) -> impl Iterator<Item = &mut Rc<Self>> {
+        let (first, second) = match self {
+            Self::Add(lhs, rhs)
+            | Self::Sub(lhs, rhs)
+            | Self::Mul(lhs, rhs)
+            | Self::Div(lhs, rhs)
+            | Self::Pow(lhs, rhs) => (Some(lhs), Some(rhs)),
+            Self::Neg(expr) | Self::Inv(expr) | Self::Func { arg: expr, .. } => (Some(expr), None),
+            Self::Const(_) | Self::Var(_) => (None, None),
+        };
+        first.into_iter().chain(second)
+    }
+
+    /// Replaces the operands of the node with `children`, in order. If every
+    /// operand is unchanged the node itself is returned; otherwise the node is
+    /// written copy-on-write, so it is only copied when another expression shares it.
+    pub fn with_children(mut self: Rc<Self>, children: Vec<Rc<Self>>) -> Rc<Self> {
+        let mut replaced = [None, None];
+        for ((slot, child), new_child) in replaced.iter_mut().zip(self.children()).zip(children) {
+            if !Rc::ptr_eq(&new_child, child) {
+                *slot = Some(new_child);
+            }
+        }
+
+        if replaced.iter().all(Option::is_none) {
+            return self;
+        }
+
+        for (child, new_child) in Rc::make_mut(&mut self).children_mut().zip(replaced) {
+            if let Some(new_child) = new_child {
+                *child = new_child;
+            }
+        }
+
+        self
+    }
+}
+
+struct Derivative {
+    by: Var,
+}
+
+impl Fold for Derivative {
+    type Output = Rc<E>;
+
+    fn fold_node(&mut self, expr: Rc<E>, derivatives: Vec<Rc<E>>) -> Rc<E> {
+        match (&*expr, &derivatives[..]) {
+            (E::Add(_, _), [da, db]) => E::add(da.clone(), db.clone()),
+            (E::Sub(_, _), [da, db]) => E::sub(da.clone(), db.clone()),
+            (E::Neg(_), [da]) => E::neg(da.clone()),
+            (E::Mul(a, b), [da, db]) => {
+                E::add(E::mul(da.clone(), b.clone()), E::mul(a.clone(), db.clone()))
+            }
+            (E::Div(a, b), [da, db]) => E::div(
+                E::sub(E::mul(da.clone(), b.clone()), E::mul(a.clone(), db.clone())),
+                E::mul(b.clone(), b.clone()),
             ),
-            Self::Neg(expr) => Self::neg(expr.substitute(name, value)),
-            Self::Mul(lhs, rhs) => Self::mul(
-                lhs.substitute(name, value.clone()),
-                rhs.substitute(name, value),
+            (E::Inv(a), [da]) => E::mul(E::neg(E::inv(E::mul(a.clone(), a.clone()))), da.clone()),
+            (E::Pow(base, exponent), [dbase, _]) if exponent.is_constant() => {
+                // power rule: (b^n)' = n * b^(n - 1) * b'
+                let decremented = match exponent.as_numeric().and_then(|n| n.checked_sub(1)) {
+                    Some(n) => E::constant(Const::Numeric(n)),
+                    None => E::sub(exponent.clone(), E::constant(Const::Numeric(1))),
+                };
+                E::mul(
+                    E::mul(exponent.clone(), E::pow(base.clone(), decremented)),
+                    dbase.clone(),
+                )
+            }
+            (E::Pow(base, exponent), [dbase, dexponent]) => {
+                // (b^e)' = b^e * (e' * ln(b) + e * b' / b)
+                E::mul(
+                    expr.clone(),
+                    E::add(
+                        E::mul(dexponent.clone(), E::func("ln".to_string(), base.clone())),
+                        E::mul(exponent.clone(), E::div(dbase.clone(), base.clone())),
+                    ),
+                )
+            }
+            (E::Const(_), []) => E::constant(Const::Numeric(0)),
+            (E::Func { name, arg }, [darg]) => E::mul(
+                functions::derivative(name, arg.clone())
+                    .unwrap_or_else(|| E::func(format!("{name}_{}", self.by), arg.clone())),
+                darg.clone(),
             ),
-            Self::Inv(expr) => Self::inv(expr.substitute(name, value.clone())),
-            Self::Func { name: func, arg } => Self::func(func, arg.substitute(name, value.clone())),
-            Self::Const(constant) => match constant {
-                Const::Numeric(number) => Self::constant(Const::Numeric(number)),
-                Const::Named(constant_name) => {
-                    if constant_name == name {
-                        return value;
-                    }
-                    Self::constant(Const::Named(constant_name))
+            (E::Var(v), []) => {
+                if *v == self.by {
+                    E::constant(Const::Numeric(1))
+                } else {
+                    E::constant(Const::Numeric(0))
                 }
-            },
-            Self::Var(variable) => Self::var(variable),
+            }
+            _ => unreachable!("every operand has exactly one derivative"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Substitution<'a> {
+    constants: HashMap<&'a str, Rc<E>>,
+    variables: HashMap<Var, Rc<E>>,
+}
+
+impl Fold for Substitution<'_> {
+    type Output = Rc<E>;
+
+    fn fold_node(&mut self, expr: Rc<E>, children: Vec<Rc<E>>) -> Rc<E> {
+        let replacement = match &*expr {
+            E::Const(Const::Named(name)) => self.constants.get(name.as_str()),
+            E::Var(variable) => self.variables.get(variable),
+            _ => None,
+        };
+
+        match replacement {
+            Some(value) => value.clone(),
+            None => expr.with_children(children),
+        }
+    }
+}
+
+struct Simplification;
+
+impl Fold for Simplification {
+    type Output = Rc<E>;
+
+    fn fold_node(&mut self, expr: Rc<E>, children: Vec<Rc<E>>) -> Rc<E> {
+        expr.with_children(children).simplify_node()
+    }
+}
+
+impl E {
+    /// Simplifies the expression by constant folding and eliminating identities
+    /// (`x + 0`, `x - 0`, `x - x`, `x * 1`, `x * 0`, `x / 1`, `0 / x`, `--x`, `1/(1/x)`),
+    /// together with the power rules `x^0 -> 1`, `x^1 -> x`, `1^x -> 1`,
+    /// repeated until nothing changes.
+    pub fn simplify(self: Rc<Self>) -> Rc<Self> {
+        let mut current = self;
+
+        loop {
+            let next = Simplification.fold(current.clone());
+            if next == current {
+                return next;
+            }
+            current = next;
+        }
+    }
+
+    /// Applies a single rule to the root, assuming the operands are already simplified.
+    fn simplify_node(self: Rc<Self>) -> Rc<Self> {
+        let simplified = match &*self {
+            Self::Add(lhs, rhs) => Self::simplify_add(lhs, rhs),
+            Self::Sub(lhs, rhs) => Self::simplify_sub(lhs, rhs),
+            Self::Mul(lhs, rhs) => Self::simplify_mul(lhs, rhs),
+            Self::Div(lhs, rhs) => Self::simplify_div(lhs, rhs),
+            Self::Neg(expr) => Self::simplify_neg(expr),
+            Self::Inv(expr) => Self::simplify_inv(expr),
+            Self::Pow(base, exponent) => Self::simplify_pow(base, exponent),
+            Self::Func { .. } | Self::Const(_) | Self::Var(_) => None,
+        };
+
+        simplified.unwrap_or(self)
+    }
+
+    fn simplify_add(lhs: &Rc<Self>, rhs: &Rc<Self>) -> Option<Rc<Self>> {
+        match (lhs.as_numeric(), rhs.as_numeric()) {
+            (Some(0), _) => Some(rhs.clone()),
+            (_, Some(0)) => Some(lhs.clone()),
+            (Some(a), Some(b)) => a
+                .checked_add(b)
+                .map(|sum| Self::constant(Const::Numeric(sum))),
+            _ => None,
+        }
+    }
+
+    fn simplify_sub(lhs: &Rc<Self>, rhs: &Rc<Self>) -> Option<Rc<Self>> {
+        if lhs == rhs {
+            return Some(Self::constant(Const::Numeric(0)));
+        }
+
+        match (lhs.as_numeric(), rhs.as_numeric()) {
+            (_, Some(0)) => Some(lhs.clone()),
+            (Some(0), _) => Some(Self::neg(rhs.clone())),
+            (Some(a), Some(b)) => a
+                .checked_sub(b)
+                .map(|difference| Self::constant(Const::Numeric(difference))),
+            _ => None,
+        }
+    }
+
+    fn simplify_mul(lhs: &Rc<Self>, rhs: &Rc<Self>) -> Option<Rc<Self>> {
+        match (lhs.as_numeric(), rhs.as_numeric()) {
+            (Some(0), _) | (_, Some(0)) => Some(Self::constant(Const::Numeric(0))),
+            (Some(1), _) => Some(rhs.clone()),
+            (_, Some(1)) => Some(lhs.clone()),
+            (Some(a), Some(b)) => a
+                .checked_mul(b)
+                .map(|product| Self::constant(Const::Numeric(product))),
+            _ => None,
+        }
+    }
+
+    fn simplify_div(lhs: &Rc<Self>, rhs: &Rc<Self>) -> Option<Rc<Self>> {
+        match (lhs.as_numeric(), rhs.as_numeric()) {
+            // division by zero is left untouched
+            (_, Some(0)) => None,
+            (_, Some(1)) => Some(lhs.clone()),
+            (Some(0), _) => Some(Self::constant(Const::Numeric(0))),
+            (Some(a), Some(b)) if a.checked_rem(b) == Some(0) => {
+                Some(Self::constant(Const::Numeric(a / b)))
+            }
+            _ => None,
+        }
+    }
+
+    fn simplify_pow(base: &Rc<Self>, exponent: &Rc<Self>) -> Option<Rc<Self>> {
+        match (base.as_numeric(), exponent.as_numeric()) {
+            (_, Some(0)) | (Some(1), _) => Some(Self::constant(Const::Numeric(1))),
+            (_, Some(1)) => Some(base.clone()),
+            (Some(a), Some(n)) => u32::try_from(n)
+                .ok()
+                .and_then(|n| a.checked_pow(n))
+                .map(|power| Self::constant(Const::Numeric(power))),
+            _ => None,
+        }
+    }
+
+    fn simplify_neg(expr: &Rc<Self>) -> Option<Rc<Self>> {
+        if let Some(negated) = expr.as_numeric().and_then(i64::checked_neg) {
+            return Some(Self::constant(Const::Numeric(negated)));
+        }
+
+        match &**expr {
+            Self::Neg(inner) => Some(inner.clone()),
+            _ => None,
+        }
+    }
+
+    fn simplify_inv(expr: &Rc<Self>) -> Option<Rc<Self>> {
+        if let Some(number @ (1 | -1)) = expr.as_numeric() {
+            return Some(Self::constant(Const::Numeric(number)));
+        }
+
+        match &**expr {
+            Self::Inv(inner) => Some(inner.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expression does not depend on any variable.
+    pub fn is_constant(&self) -> bool {
+        match self {
+            Self::Var(_) => false,
+            Self::Const(_) => true,
+            Self::Neg(expr) | Self::Inv(expr) => expr.is_constant(),
+            Self::Func { arg, .. } => arg.is_constant(),
+            Self::Add(lhs, rhs)
+            | Self::Sub(lhs, rhs)
+            | Self::Mul(lhs, rhs)
+            | Self::Div(lhs, rhs)
+            | Self::Pow(lhs, rhs) => lhs.is_constant() && rhs.is_constant(),
+        }
+    }
+
+    /// Operands of `^` which would be ambiguous without parentheses.
+    fn needs_parens_in_pow(&self) -> bool {
+        matches!(self, Self::Neg(_) | Self::Inv(_) | Self::Pow(_, _))
+    }
+
+    fn as_numeric(&self) -> Option<i64> {
+        match self {
+            Self::Const(Const::Numeric(number)) => Some(*number),
+            _ => None,
         }
     }
 }
@@ -149,15 +449,33 @@ impl Display for E {
             Self::Add(a, b) => {
                 write!(f, "({a} + {b})")
             }
+            Self::Sub(a, b) => {
+                write!(f, "({a} - {b})")
+            }
             Self::Neg(a) => {
                 write!(f, "-({a})")
             }
             Self::Mul(a, b) => {
                 write!(f, "({a} * {b})")
             }
+            Self::Div(a, b) => {
+                write!(f, "({a} / {b})")
+            }
             Self::Inv(a) => {
                 write!(f, "1/({a})")
             }
+            Self::Pow(base, exponent) => {
+                if base.needs_parens_in_pow() {
+                    write!(f, "({base})")?;
+                } else {
+                    write!(f, "{base}")?;
+                }
+                if exponent.needs_parens_in_pow() {
+                    write!(f, "^({exponent})")
+                } else {
+                    write!(f, "^{exponent}")
+                }
+            }
             Self::Const(c) => {
                 write!(f, "{c}")
             }
@@ -0,0 +1,45 @@
+//! Generic traversals over [`E`], so that analyses and transformations only have to
+//! handle the variants they care about.
+
+use std::rc::Rc;
+
+use super::{Const, E, Var};
+
+/// Read-only traversal of an expression. By default every node is visited in
+/// prefix order; override [`Visit::visit`] to act on a node and call [`walk`]
+/// from it to continue into the operands.
+pub trait Visit {
+    fn visit(&mut self, expr: &E) {
+        walk(self, expr);
+    }
+
+    fn visit_const(&mut self, _constant: &Const) {}
+
+    fn visit_var(&mut self, _variable: Var) {}
+}
+
+/// Visits the operands of `expr`, or passes a leaf to the matching hook.
+pub fn walk<V: Visit + ?Sized>(visitor: &mut V, expr: &E) {
+    match expr {
+        E::Const(constant) => visitor.visit_const(constant),
+        E::Var(variable) => visitor.visit_var(*variable),
+        _ => expr.children().for_each(|child| visitor.visit(child)),
+    }
+}
+
+/// Bottom-up fold of an expression. The operands of a node are folded first and
+/// their results, in operand order, are passed to [`Fold::fold_node`] together
+/// with the node itself. Leaves receive no results.
+pub trait Fold {
+    type Output;
+
+    fn fold(&mut self, expr: Rc<E>) -> Self::Output {
+        let children = expr
+            .children()
+            .map(|child| self.fold(child.clone()))
+            .collect();
+        self.fold_node(expr, children)
+    }
+
+    fn fold_node(&mut self, expr: Rc<E>, children: Vec<Self::Output>) -> Self::Output;
+}
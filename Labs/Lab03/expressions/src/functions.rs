@@ -0,0 +1,29 @@
+use std::rc::Rc;
+
+use super::{Const, E};
+
+type Derivative = fn(Rc<E>) -> Rc<E>;
+
+/// Derivatives of built-in functions with respect to their argument, `f'(u)`.
+/// The chain rule factor `u'` is applied by [`E::diff`].
+const DERIVATIVES: &[(&str, Derivative)] = &[
+    ("sin", |u| E::func("cos".to_string(), u)),
+    ("cos", |u| E::neg(E::func("sin".to_string(), u))),
+    ("exp", |u| E::func("exp".to_string(), u)),
+    ("ln", E::inv),
+    ("sqrt", |u| {
+        E::inv(E::mul(
+            E::constant(Const::Numeric(2)),
+            E::func("sqrt".to_string(), u),
+        ))
+    }),
+];
+
+/// Returns the derivative of the built-in function `name` at `arg`,
+/// or `None` if the function is not known.
+pub fn derivative(name: &str, arg: Rc<E>) -> Option<Rc<E>> {
+    DERIVATIVES
+        .iter()
+        .find(|(function, _)| *function == name)
+        .map(|(_, derivative)| derivative(arg))
+}
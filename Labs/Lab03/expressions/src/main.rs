@@ -1,9 +1,15 @@
 mod constant;
 mod expression;
+mod fold;
+mod functions;
+mod parser;
 mod var;
 
+use std::{collections::HashMap, rc::Rc};
+
 use constant::Const;
 use expression::E;
+use parser::ParseError;
 use var::Var;
 
 fn main() {
@@ -36,7 +42,15 @@ fn main() {
     let double_neg = E::neg(E::neg(E::var(vz)));
     let unneg_result = double_neg.unneg();
 
-    let expressions: Vec<Box<E>> = vec![
+    let simplified_diff = diff_wrt_x.clone().simplify();
+
+    let parsed = E::parse("2*x + sin(y)/z").expect("valid expression");
+    let swapped = parsed.clone().substitute_all(&HashMap::from([
+        ("x".to_string(), E::Var(vy)),
+        ("y".to_string(), E::Var(vx)),
+    ]));
+
+    let expressions: Vec<Rc<E>> = vec![
         e_const,
         e_named,
         e_var_x,
@@ -48,6 +62,9 @@ fn main() {
         e_inv,
         diff_wrt_x,
         diff_wrt_y,
+        simplified_diff,
+        parsed,
+        swapped,
         substituted,
         uninv_result,
         unneg_result,
@@ -104,6 +121,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fold::{Fold, Visit, walk};
 
     #[test]
     fn test_const_to_string() {
@@ -134,6 +152,33 @@ mod tests {
         assert_eq!(expr.to_string(), "(2 + X)");
     }
 
+    #[test]
+    fn test_builder_sub() {
+        let expr = E::sub(E::var(Var::X), E::constant(Const::Numeric(2)));
+        assert_eq!(expr.to_string(), "(X - 2)");
+    }
+
+    #[test]
+    fn test_builder_div() {
+        let expr = E::div(E::var(Var::X), E::var(Var::Y));
+        assert_eq!(expr.to_string(), "(X / Y)");
+    }
+
+    #[test]
+    fn test_builder_pow() {
+        let square = E::pow(E::var(Var::X), E::constant(Const::Numeric(2)));
+        assert_eq!(square.to_string(), "X^2");
+
+        let nested = E::pow(
+            E::neg(E::var(Var::X)),
+            E::pow(E::var(Var::Y), E::var(Var::Z)),
+        );
+        assert_eq!(nested.to_string(), "(-(X))^(Y^Z)");
+
+        let sum = E::pow(E::add(E::var(Var::X), E::var(Var::Y)), E::var(Var::Z));
+        assert_eq!(sum.to_string(), "(X + Y)^Z");
+    }
+
     #[test]
     fn test_builder_neg() {
         let expr = E::neg(E::var(Var::X));
@@ -235,6 +280,104 @@ mod tests {
         assert_eq!(d.to_string(), "((1 * Y) + (X * 0))");
     }
 
+    #[test]
+    fn test_diff_sub() {
+        let expr = E::sub(E::var(Var::X), E::var(Var::Y));
+        let d = expr.diff(Var::Y);
+        assert_eq!(d.to_string(), "(0 - 1)");
+    }
+
+    #[test]
+    fn test_diff_div() {
+        let expr = E::div(E::var(Var::X), E::var(Var::Y));
+        let d = expr.diff(Var::X);
+        assert_eq!(d.to_string(), "(((1 * Y) - (X * 0)) / (Y * Y))");
+        assert_eq!(d.simplify().to_string(), "(Y / (Y * Y))");
+    }
+
+    #[test]
+    fn test_substitute_all_is_simultaneous() {
+        let expr = E::parse("a * x + b / y").unwrap();
+        let substitutions = HashMap::from([
+            ("a".to_string(), E::Const(Const::Named("b".into()))),
+            ("b".to_string(), E::Const(Const::Named("a".into()))),
+            ("X".to_string(), E::Var(Var::Y)),
+            ("y".to_string(), E::Var(Var::X)),
+        ]);
+        assert_eq!(
+            expr.substitute_all(&substitutions).to_string(),
+            "((b * Y) + (a / X))"
+        );
+    }
+
+    #[test]
+    fn test_substitute_all_shares_replacements() {
+        let expr = E::parse("c + c").unwrap();
+        let substitutions = HashMap::from([(
+            "c".to_string(),
+            Rc::unwrap_or_clone(E::parse("sin(x)").unwrap()),
+        )]);
+        let E::Add(lhs, rhs) = &*expr.substitute_all(&substitutions) else {
+            panic!("substitution should keep the sum");
+        };
+        assert!(Rc::ptr_eq(lhs, rhs));
+    }
+
+    #[test]
+    fn test_substitute_sub_div() {
+        let a = || E::constant(Const::Named("a".into()));
+        let expr = E::div(E::sub(a(), E::var(Var::X)), a());
+        let substituted = expr.substitute("a", E::constant(Const::Numeric(4)));
+        assert_eq!(substituted.to_string(), "((4 - X) / 4)");
+    }
+
+    #[test]
+    fn test_simplify_sub_div() {
+        let x = || E::var(Var::X);
+        let num = |n| E::constant(Const::Numeric(n));
+
+        assert_eq!(E::sub(x(), num(0)).simplify().to_string(), "X");
+        assert_eq!(E::sub(num(0), x()).simplify().to_string(), "-(X)");
+        assert_eq!(E::sub(x(), x()).simplify().to_string(), "0");
+        assert_eq!(E::sub(num(7), num(9)).simplify().to_string(), "-2");
+        assert_eq!(E::div(x(), num(1)).simplify().to_string(), "X");
+        assert_eq!(E::div(num(0), x()).simplify().to_string(), "0");
+        assert_eq!(E::div(num(12), num(4)).simplify().to_string(), "3");
+        assert_eq!(E::div(num(7), num(2)).simplify().to_string(), "(7 / 2)");
+        assert_eq!(E::div(x(), num(0)).simplify().to_string(), "(X / 0)");
+    }
+
+    #[test]
+    fn test_diff_pow_constant_exponent() {
+        let expr = E::pow(E::var(Var::X), E::constant(Const::Numeric(3)));
+        let d = expr.diff(Var::X);
+        assert_eq!(d.to_string(), "((3 * X^2) * 1)");
+        assert_eq!(d.simplify().to_string(), "(3 * X^2)");
+
+        let expr = E::pow(E::var(Var::X), E::constant(Const::Named("n".into())));
+        let d = expr.diff(Var::X).simplify();
+        assert_eq!(d.to_string(), "(n * X^(n - 1))");
+    }
+
+    #[test]
+    fn test_diff_pow_variable_exponent() {
+        let expr = E::pow(E::var(Var::X), E::var(Var::Y));
+        let d = expr.diff(Var::Y).simplify();
+        assert_eq!(d.to_string(), "(X^Y * ln(X))");
+    }
+
+    #[test]
+    fn test_simplify_pow() {
+        let x = || E::var(Var::X);
+        let num = |n| E::constant(Const::Numeric(n));
+
+        assert_eq!(E::pow(x(), num(1)).simplify().to_string(), "X");
+        assert_eq!(E::pow(x(), num(0)).simplify().to_string(), "1");
+        assert_eq!(E::pow(num(1), x()).simplify().to_string(), "1");
+        assert_eq!(E::pow(num(2), num(10)).simplify().to_string(), "1024");
+        assert_eq!(E::pow(num(2), num(-1)).simplify().to_string(), "2^-1");
+    }
+
     #[test]
     fn test_diff_inv() {
         let expr = E::inv(E::var(Var::X));
@@ -263,6 +406,26 @@ mod tests {
         assert_eq!(d.to_string(), "(f_X(X) * 1)");
     }
 
+    #[test]
+    fn test_diff_builtin_functions() {
+        let diff_of = |name: &str| {
+            E::func(
+                name.into(),
+                E::mul(E::constant(Const::Numeric(2)), E::var(Var::X)),
+            )
+            .diff(Var::X)
+            .simplify()
+            .to_string()
+        };
+
+        assert_eq!(diff_of("sin"), "(cos((2 * X)) * 2)");
+        assert_eq!(diff_of("cos"), "(-(sin((2 * X))) * 2)");
+        assert_eq!(diff_of("exp"), "(exp((2 * X)) * 2)");
+        assert_eq!(diff_of("ln"), "(1/((2 * X)) * 2)");
+        assert_eq!(diff_of("sqrt"), "(1/((2 * sqrt((2 * X)))) * 2)");
+        assert_eq!(diff_of("tan"), "(tan_X((2 * X)) * 2)");
+    }
+
     #[test]
     fn test_diff_var_same() {
         let d = E::var(Var::X).diff(Var::X);
@@ -299,6 +462,211 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_simplify_identities() {
+        let x = || E::var(Var::X);
+        let num = |n| E::constant(Const::Numeric(n));
+
+        assert_eq!(E::add(x(), num(0)).simplify().to_string(), "X");
+        assert_eq!(E::add(num(0), x()).simplify().to_string(), "X");
+        assert_eq!(E::mul(x(), num(1)).simplify().to_string(), "X");
+        assert_eq!(E::mul(num(1), x()).simplify().to_string(), "X");
+        assert_eq!(E::mul(x(), num(0)).simplify().to_string(), "0");
+        assert_eq!(E::neg(E::neg(x())).simplify().to_string(), "X");
+        assert_eq!(E::inv(E::inv(x())).simplify().to_string(), "X");
+    }
+
+    #[test]
+    fn test_simplify_constant_folding() {
+        let num = |n| E::constant(Const::Numeric(n));
+
+        let expr = E::add(E::mul(num(2), num(3)), E::neg(num(4)));
+        assert_eq!(expr.simplify().to_string(), "2");
+
+        assert_eq!(E::inv(num(1)).simplify().to_string(), "1");
+        assert_eq!(E::inv(num(2)).simplify().to_string(), "1/(2)");
+        assert_eq!(
+            E::add(num(i64::MAX), num(1)).simplify().to_string(),
+            format!("({} + 1)", i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_simplify_fixpoint() {
+        // (X * (0 + 1)) + -(-(0)) needs the folded children to trigger the identities above them
+        let expr = E::add(
+            E::mul(
+                E::var(Var::X),
+                E::add(
+                    E::constant(Const::Numeric(0)),
+                    E::constant(Const::Numeric(1)),
+                ),
+            ),
+            E::neg(E::neg(E::constant(Const::Numeric(0)))),
+        );
+        assert_eq!(expr.simplify().to_string(), "X");
+    }
+
+    #[test]
+    fn test_simplify_derivative() {
+        let part1 = E::add(E::var(Var::X), E::neg(E::var(Var::Y)));
+        let part2 = E::inv(E::var(Var::Z));
+        let a = E::mul(part1, part2);
+        let b = E::func("f".into(), E::mul(E::var(Var::X), E::var(Var::Y)));
+        let c = E::func("g".into(), E::inv(E::var(Var::X)));
+        let big = E::add(a, E::add(b, c));
+
+        let d = big.diff(Var::X).simplify();
+        assert_eq!(
+            d.to_string(),
+            "(1/(Z) + ((f_X((X * Y)) * Y) + (g_X(1/(X)) * -(1/((X * X))))))"
+        );
+    }
+
+    #[test]
+    fn test_diff_shares_subtrees() {
+        let a = E::func("sin".into(), E::var(Var::X));
+        let b = E::func("f".into(), E::var(Var::Y));
+        let product = E::mul(a.clone(), b.clone());
+
+        // (a * b)' = a' * b + a * b'
+        let E::Add(lhs, rhs) = &*product.diff(Var::X) else {
+            panic!("product rule should produce a sum");
+        };
+        let (E::Mul(_, shared_b), E::Mul(shared_a, _)) = (&**lhs, &**rhs) else {
+            panic!("product rule terms should be products");
+        };
+        assert!(Rc::ptr_eq(shared_a, &a));
+        assert!(Rc::ptr_eq(shared_b, &b));
+    }
+
+    #[test]
+    fn test_substitute_shares_untouched_subtrees() {
+        let untouched = E::mul(E::var(Var::X), E::var(Var::Y));
+        let expr = E::add(untouched.clone(), E::constant(Const::Named("c".into())));
+
+        let substituted = expr.clone().substitute("c", E::var(Var::Z));
+        let E::Add(lhs, _) = &*substituted else {
+            panic!("substitution should keep the sum");
+        };
+        assert!(Rc::ptr_eq(lhs, &untouched));
+        assert_eq!(substituted.to_string(), "((X * Y) + Z)");
+
+        let unchanged = expr.clone().substitute("d", E::var(Var::Z));
+        assert!(Rc::ptr_eq(&unchanged, &expr));
+    }
+
+    #[test]
+    fn test_parse_matches_builders() {
+        let parsed = E::parse("2*x + sin(y)/z").unwrap();
+        let built = E::add(
+            E::mul(E::constant(Const::Numeric(2)), E::var(Var::X)),
+            E::div(E::func("sin".into(), E::var(Var::Y)), E::var(Var::Z)),
+        );
+        assert_eq!(parsed, built);
+    }
+
+    #[test]
+    fn test_parse_precedence_and_associativity() {
+        let parse = |input| E::parse(input).unwrap().to_string();
+
+        assert_eq!(parse("x - y - z"), "((X - Y) - Z)");
+        assert_eq!(parse("x / y * z"), "((X / Y) * Z)");
+        assert_eq!(parse("x ^ y ^ z"), "X^(Y^Z)");
+        assert_eq!(parse("-x^2"), "-(X^2)");
+        assert_eq!(parse("2^-x"), "2^(-(X))");
+        assert_eq!(parse("(x + a) * --y"), "((X + a) * -(-(Y)))");
+        assert_eq!(parse("f(g(X))"), "f(g(X))");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            E::parse("x + $"),
+            Err(ParseError::UnexpectedCharacter {
+                character: '$',
+                position: 4
+            })
+        );
+        assert_eq!(E::parse("(x + y"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            E::parse("x y"),
+            Err(ParseError::UnexpectedToken {
+                token: "y".into(),
+                position: 2
+            })
+        );
+        assert_eq!(
+            E::parse("99999999999999999999"),
+            Err(ParseError::InvalidNumber {
+                literal: "99999999999999999999".into(),
+                position: 0
+            })
+        );
+        assert_eq!(E::parse(""), Err(ParseError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_visit_collects_variables() {
+        struct Variables(Vec<Var>);
+
+        impl Visit for Variables {
+            fn visit_var(&mut self, variable: Var) {
+                if !self.0.contains(&variable) {
+                    self.0.push(variable);
+                }
+            }
+        }
+
+        let expr = E::parse("x * sin(z) + x^y").unwrap();
+        let mut variables = Variables(Vec::new());
+        variables.visit(&expr);
+        assert_eq!(variables.0, vec![Var::X, Var::Z, Var::Y]);
+    }
+
+    #[test]
+    fn test_visit_depth() {
+        #[derive(Default)]
+        struct Depth {
+            current: usize,
+            max: usize,
+        }
+
+        impl Visit for Depth {
+            fn visit(&mut self, expr: &E) {
+                self.current += 1;
+                self.max = self.max.max(self.current);
+                walk(self, expr);
+                self.current -= 1;
+            }
+        }
+
+        let mut depth = Depth::default();
+        depth.visit(&E::parse("-(x + 1) * y").unwrap());
+        assert_eq!(depth.max, 4);
+    }
+
+    #[test]
+    fn test_fold_cost_model() {
+        // every operation costs 1, except functions which cost 10
+        struct Cost;
+
+        impl Fold for Cost {
+            type Output = u32;
+
+            fn fold_node(&mut self, expr: Rc<E>, children: Vec<u32>) -> u32 {
+                let own = match &*expr {
+                    E::Const(_) | E::Var(_) => 0,
+                    E::Func { .. } => 10,
+                    _ => 1,
+                };
+                own + children.iter().sum::<u32>()
+            }
+        }
+
+        assert_eq!(Cost.fold(E::parse("2 * x + sin(y)").unwrap()), 12);
+    }
+
     #[test]
     fn test_arg_count_zeroary() {
         assert_eq!(E::constant(Const::Numeric(1)).arg_count(), 0);
@@ -315,6 +683,9 @@ mod tests {
     #[test]
     fn test_arg_count_binary() {
         assert_eq!(E::add(E::var(Var::X), E::var(Var::Y)).arg_count(), 2);
+        assert_eq!(E::sub(E::var(Var::X), E::var(Var::Y)).arg_count(), 2);
+        assert_eq!(E::div(E::var(Var::X), E::var(Var::Y)).arg_count(), 2);
+        assert_eq!(E::pow(E::var(Var::X), E::var(Var::Y)).arg_count(), 2);
         assert_eq!(E::mul(E::var(Var::X), E::var(Var::Z)).arg_count(), 2);
     }
 }
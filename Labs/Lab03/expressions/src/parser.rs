@@ -0,0 +1,238 @@
+use std::{fmt::Display, rc::Rc};
+
+use super::{Const, E, Var};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedCharacter { character: char, position: usize },
+    InvalidNumber { literal: String, position: usize },
+    UnexpectedToken { token: String, position: usize },
+    UnexpectedEnd,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected character '{character}' at position {position}"
+            ),
+            Self::InvalidNumber { literal, position } => {
+                write!(f, "invalid number '{literal}' at position {position}")
+            }
+            Self::UnexpectedToken { token, position } => {
+                write!(f, "unexpected '{token}' at position {position}")
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Ident(name) => write!(f, "{name}"),
+            Self::Plus => write!(f, "+"),
+            Self::Minus => write!(f, "-"),
+            Self::Star => write!(f, "*"),
+            Self::Slash => write!(f, "/"),
+            Self::Caret => write!(f, "^"),
+            Self::LParen => write!(f, "("),
+            Self::RParen => write!(f, ")"),
+        }
+    }
+}
+
+/// Binding power of the prefix `-`: binds tighter than `*` and `/`, looser than `^`.
+const PREFIX_BINDING_POWER: u8 = 5;
+
+/// Left and right binding powers of the binary operators.
+/// `^` is right-associative, the remaining operators are left-associative.
+fn infix_binding_power(token: &Token) -> Option<(u8, u8)> {
+    match token {
+        Token::Plus | Token::Minus => Some((1, 2)),
+        Token::Star | Token::Slash => Some((3, 4)),
+        Token::Caret => Some((7, 6)),
+        _ => None,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(position, character)) = chars.peek() {
+        let token = match character {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '^' => Token::Caret,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            c if c.is_ascii_digit() => {
+                let literal = take_while(&mut chars, |c| c.is_ascii_digit());
+                let number = literal
+                    .parse()
+                    .map_err(|_| ParseError::InvalidNumber { literal, position })?;
+                tokens.push((Token::Number(number), position));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let name = take_while(&mut chars, |c| c.is_alphanumeric() || c == '_');
+                tokens.push((Token::Ident(name), position));
+                continue;
+            }
+            character => {
+                return Err(ParseError::UnexpectedCharacter {
+                    character,
+                    position,
+                });
+            }
+        };
+        chars.next();
+        tokens.push((token, position));
+    }
+
+    Ok(tokens)
+}
+
+fn take_while<I>(chars: &mut std::iter::Peekable<I>, predicate: fn(char) -> bool) -> String
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut taken = String::new();
+    while let Some(&(_, c)) = chars.peek() {
+        if !predicate(c) {
+            break;
+        }
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+/// Pratt parser turning a token stream into an expression tree.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    current: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.current).cloned();
+        self.current += 1;
+        token
+    }
+
+    fn unexpected(token: Token, position: usize) -> ParseError {
+        ParseError::UnexpectedToken {
+            token: token.to_string(),
+            position,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.advance() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((token, position)) => Err(Self::unexpected(token, position)),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self, min_binding_power: u8) -> Result<Rc<E>, ParseError> {
+        let mut lhs = self.prefix()?;
+
+        while let Some(operator) = self.peek() {
+            let Some((left, right)) = infix_binding_power(operator) else {
+                break;
+            };
+            if left < min_binding_power {
+                break;
+            }
+
+            let (operator, _) = self.advance().ok_or(ParseError::UnexpectedEnd)?;
+            let rhs = self.expression(right)?;
+            lhs = match operator {
+                Token::Plus => E::add(lhs, rhs),
+                Token::Minus => E::sub(lhs, rhs),
+                Token::Star => E::mul(lhs, rhs),
+                Token::Slash => E::div(lhs, rhs),
+                _ => E::pow(lhs, rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Rc<E>, ParseError> {
+        match self.advance() {
+            Some((Token::Number(number), _)) => Ok(E::constant(Const::Numeric(number))),
+            Some((Token::Ident(name), _)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.advance();
+                    let arg = self.expression(0)?;
+                    self.expect(Token::RParen)?;
+                    return Ok(E::func(name, arg));
+                }
+
+                Ok(match Var::from_name(&name) {
+                    Some(variable) => E::var(variable),
+                    None => E::constant(Const::Named(name)),
+                })
+            }
+            Some((Token::Minus, _)) => Ok(E::neg(self.expression(PREFIX_BINDING_POWER)?)),
+            Some((Token::LParen, _)) => {
+                let expr = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some((token, position)) => Err(Self::unexpected(token, position)),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+}
+
+/// Parses an expression such as `2*x + sin(y)/z`.
+///
+/// Identifiers `x`, `y`, `z` (in either case) are variables, identifiers followed
+/// by parentheses are function calls and any other identifier is a named constant.
+pub fn parse(input: &str) -> Result<Rc<E>, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        current: 0,
+    };
+
+    let expr = parser.expression(0)?;
+    match parser.advance() {
+        Some((token, position)) => Err(Parser::unexpected(token, position)),
+        None => Ok(expr),
+    }
+}
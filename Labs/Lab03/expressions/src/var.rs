@@ -1,12 +1,24 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Var {
     X,
     Y,
     Z,
 }
 
+impl Var {
+    /// Looks up a variable by its name, in either case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "x" | "X" => Some(Var::X),
+            "y" | "Y" => Some(Var::Y),
+            "z" | "Z" => Some(Var::Z),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Var {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
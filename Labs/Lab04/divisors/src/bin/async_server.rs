@@ -0,0 +1,314 @@
+//! Asynchronous variant of the directory server: every connection is a task of a
+//! tokio runtime instead of a job of the thread pool.
+
+use std::{
+    env,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use divisors::{
+    IP_ADDRESS, PORT,
+    directory::{Refusal, decode_path, list_dir, resolve},
+    protocol::{
+        Command, MAX_FILE_LEN, REQUEST_HEADER_LEN, RESPONSE_HEADER_LEN, Status, encode_response,
+        parse_request_header,
+    },
+};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+    task::{self, JoinSet},
+    time,
+};
+
+const DEFAULT_MAX_CONNECTIONS: usize = 64;
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+const FILE_CHUNK_LEN: usize = 64 * 1024;
+
+pub struct AsyncDirectoryServer {
+    pub address: String,
+    pub port: u16,
+    /// Only paths inside this directory are served. Relative paths sent by a
+    /// client are resolved against it.
+    pub root: PathBuf,
+    /// Clients above this limit are not accepted until a connection finishes.
+    pub max_connections: usize,
+    /// How long a client may take to send its request, or to accept a chunk of
+    /// the response, before its connection is dropped.
+    pub read_timeout: Duration,
+    /// How long the connections in progress may still take after a shutdown
+    /// signal, before they are closed.
+    pub shutdown_grace: Duration,
+}
+
+impl AsyncDirectoryServer {
+    fn new(address: &str, port: u16, root: impl Into<PathBuf>) -> Self {
+        Self {
+            address: address.to_string(),
+            port,
+            root: root.into(),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            shutdown_grace: DEFAULT_SHUTDOWN_GRACE,
+        }
+    }
+
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    pub fn with_shutdown_grace(mut self, shutdown_grace: Duration) -> Self {
+        self.shutdown_grace = shutdown_grace;
+        self
+    }
+
+    /// Serves clients until SIGINT, or SIGTERM on Unix, arrives. After that no
+    /// connection is accepted, and the ones in progress are closed unless they
+    /// finish within the shutdown grace period.
+    pub async fn run(&self) -> io::Result<()> {
+        let bind_address = format!("{}:{}", self.address, self.port);
+        let listener = TcpListener::bind(&bind_address).await?;
+        let root = Arc::new(tokio::fs::canonicalize(&self.root).await?);
+
+        println!(
+            "Async server listening on {bind_address} with at most {} connections, serving {root:?}",
+            self.max_connections
+        );
+
+        let shutdown = shutdown_signal()?;
+        tokio::pin!(shutdown);
+
+        let permits = Arc::new(Semaphore::new(self.max_connections));
+        let mut connections = JoinSet::new();
+        loop {
+            // further clients wait in the listen backlog until a connection finishes
+            let permit = tokio::select! {
+                _ = &mut shutdown => break,
+                permit = Arc::clone(&permits).acquire_owned() => {
+                    permit.expect("the semaphore is never closed")
+                }
+            };
+            let stream = tokio::select! {
+                _ = &mut shutdown => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        eprintln!("Incoming connection failed: {e}");
+                        continue;
+                    }
+                },
+            };
+
+            let root = Arc::clone(&root);
+            let read_timeout = self.read_timeout;
+            connections.spawn(async move {
+                if let Err(e) = handle_client(stream, root, read_timeout).await {
+                    eprintln!("An error occurred while handling the client: {e}");
+                }
+                drop(permit);
+            });
+            while connections.try_join_next().is_some() {}
+        }
+
+        drop(listener);
+        let finished = time::timeout(self.shutdown_grace, async {
+            while connections.join_next().await.is_some() {}
+        })
+        .await;
+        if finished.is_err() {
+            eprintln!(
+                "Closing {} connection(s) still in progress",
+                connections.len()
+            );
+            connections.shutdown().await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Completes once SIGINT arrives, or SIGTERM on Unix. The handlers are installed
+/// before this returns, so that a signal arriving in the meantime is not missed.
+#[cfg(unix)]
+fn shutdown_signal() -> io::Result<impl Future<Output = ()>> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    Ok(async move {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => println!("Received SIGINT, shutting down"),
+            _ = terminate.recv() => println!("Received SIGTERM, shutting down"),
+        }
+    })
+}
+
+#[cfg(not(unix))]
+fn shutdown_signal() -> io::Result<impl Future<Output = ()>> {
+    Ok(async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("Cannot listen for Ctrl-C: {e}");
+            std::future::pending::<()>().await;
+        }
+        println!("Received Ctrl-C, shutting down");
+    })
+}
+
+/// Reads a request from `stream` and answers it, the file system being accessed
+/// off the runtime threads.
+async fn handle_client(
+    mut stream: TcpStream,
+    root: Arc<PathBuf>,
+    read_timeout: Duration,
+) -> io::Result<()> {
+    let mut header = [0u8; REQUEST_HEADER_LEN];
+    if !read_within(&mut stream, &mut header, read_timeout).await? {
+        return write_refusal(&mut stream, Refusal::error("Bad request"), read_timeout).await;
+    }
+
+    let (command, len) = match parse_request_header(header) {
+        Ok(parsed) => parsed,
+        Err(refusal) => return write_refusal(&mut stream, refusal, read_timeout).await,
+    };
+
+    let mut path_bytes = vec![0u8; len];
+    if !read_within(&mut stream, &mut path_bytes, read_timeout).await? {
+        return write_refusal(&mut stream, Refusal::BAD_PATH, read_timeout).await;
+    }
+
+    let resolved = task::spawn_blocking(move || {
+        decode_path(path_bytes).and_then(|path| resolve(&root, &path))
+    })
+    .await
+    .map_err(io::Error::other)?;
+    let pathbuf = match resolved {
+        Ok(pathbuf) => pathbuf,
+        Err(refusal) => return write_refusal(&mut stream, refusal, read_timeout).await,
+    };
+
+    match command {
+        Command::List => {
+            let listing = task::spawn_blocking(move || list_dir(&pathbuf))
+                .await
+                .map_err(io::Error::other)?;
+            match listing {
+                Some(listing) => {
+                    let response = encode_response(Status::Ok, listing.as_bytes());
+                    write_within(&mut stream, &response, read_timeout).await
+                }
+                None => write_refusal(&mut stream, Refusal::BAD_PATH, read_timeout).await,
+            }
+        }
+        Command::Get => send_file(&mut stream, &pathbuf, read_timeout).await,
+    }
+}
+
+/// Streams the file at `pathbuf` in chunks, so that at most one chunk of it is in
+/// memory at a time.
+async fn send_file(stream: &mut TcpStream, pathbuf: &Path, timeout: Duration) -> io::Result<()> {
+    let mut file = match File::open(pathbuf).await {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Error opening file {pathbuf:?}: {e}");
+            return write_refusal(stream, Refusal::BAD_PATH, timeout).await;
+        }
+    };
+
+    let len = match file.metadata().await {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        Ok(_) => return write_refusal(stream, Refusal::error("Not a file"), timeout).await,
+        Err(e) => {
+            eprintln!("Error getting metadata for {pathbuf:?}: {e}");
+            return write_refusal(stream, Refusal::BAD_PATH, timeout).await;
+        }
+    };
+
+    if len > MAX_FILE_LEN {
+        return write_refusal(stream, Refusal::error("File too large"), timeout).await;
+    }
+
+    let mut header = [0u8; RESPONSE_HEADER_LEN];
+    header[0] = Status::Ok as u8;
+    header[1..].copy_from_slice(&len.to_be_bytes());
+    write_within(stream, &header, timeout).await?;
+
+    // the header promised `len` bytes, so a file that shrinks in the meantime
+    // can only be reported by dropping the connection
+    let mut chunk = vec![0u8; FILE_CHUNK_LEN];
+    let mut remaining = len;
+    while remaining > 0 {
+        let wanted = remaining.min(FILE_CHUNK_LEN as u64) as usize;
+        let read = file.read(&mut chunk[..wanted]).await?;
+        if read == 0 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("{pathbuf:?} shrank while being sent"),
+            ));
+        }
+        write_within(stream, &chunk[..read], timeout).await?;
+        remaining -= read as u64;
+    }
+
+    Ok(())
+}
+
+/// Fills `buf` from `stream`. Returns `false` if the client closes the connection
+/// first, and fails if it takes longer than `timeout`.
+async fn read_within(
+    stream: &mut TcpStream,
+    buf: &mut [u8],
+    timeout: Duration,
+) -> io::Result<bool> {
+    match time::timeout(timeout, stream.read_exact(buf)).await {
+        Ok(Ok(_)) => Ok(true),
+        Ok(Err(e)) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(io::Error::new(
+            ErrorKind::TimedOut,
+            "The client did not send its request in time",
+        )),
+    }
+}
+
+/// Writes all of `bytes` to `stream`, failing if the client does not accept them
+/// within `timeout`.
+async fn write_within(stream: &mut TcpStream, bytes: &[u8], timeout: Duration) -> io::Result<()> {
+    time::timeout(timeout, stream.write_all(bytes))
+        .await
+        .unwrap_or_else(|_| {
+            Err(io::Error::new(
+                ErrorKind::TimedOut,
+                "The client did not read the response in time",
+            ))
+        })
+}
+
+async fn write_refusal(
+    stream: &mut TcpStream,
+    refusal: Refusal,
+    timeout: Duration,
+) -> io::Result<()> {
+    let response = encode_response(refusal.status, refusal.message.as_bytes());
+    write_within(stream, &response, timeout).await
+}
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    let root = env::args().nth(1).unwrap_or_else(|| ".".to_string());
+    let server = AsyncDirectoryServer::new(IP_ADDRESS, PORT, root)
+        .with_max_connections(DEFAULT_MAX_CONNECTIONS)
+        .with_read_timeout(DEFAULT_READ_TIMEOUT)
+        .with_shutdown_grace(DEFAULT_SHUTDOWN_GRACE);
+    server.run().await
+}
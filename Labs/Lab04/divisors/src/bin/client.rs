@@ -1,38 +1,197 @@
-use std::env;
-use std::io::{self, Read, Write};
-use std::net::TcpStream;
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
 
+use clap::{Parser, Subcommand};
+use divisors::protocol::{Command, MAX_FILE_LEN, read_response, write_request};
 use divisors::{IP_ADDRESS, PORT};
 
-fn main() -> io::Result<()> {
-    let path = {
-        let args = env::args().skip(1).collect::<Vec<_>>();
-        if args.len() != 1 {
-            eprintln!("Usage: client <path>");
-            eprintln!("Example: cargo run -- \"/path/to/some/dir\"");
-            return Ok(());
-        }
-        args.join("")
-    };
-
-    let path_bytes = path.as_bytes();
-    if path_bytes.len() > u32::MAX as usize {
-        eprintln!("Path too long");
-        return Ok(());
+/// Client for the directory server.
+///
+/// Example: cargo run --bin client -- --json list "some/dir"
+#[derive(Parser, Debug)]
+#[command(version)]
+struct Cli {
+    /// Name or address of the server.
+    #[arg(long, default_value = IP_ADDRESS)]
+    host: String,
+
+    /// Port the server listens on.
+    #[arg(short, long, default_value_t = PORT)]
+    port: u16,
+
+    /// How many times a failed connection is retried.
+    #[arg(long, default_value_t = 3)]
+    retries: u32,
+
+    /// Delay before the first retry in milliseconds, doubled after every retry.
+    #[arg(long, default_value_t = 200)]
+    backoff_ms: u64,
+
+    /// Limit for connecting and for every read or write, in milliseconds.
+    #[arg(long, default_value_t = 5000)]
+    timeout_ms: u64,
+
+    /// Prints the result as JSON.
+    #[arg(long)]
+    json: bool,
+
+    #[command(subcommand)]
+    request: Request,
+}
+
+#[derive(Subcommand, Debug)]
+enum Request {
+    /// Lists the files in a directory.
+    List { path: String },
+    /// Downloads a file, to standard output unless a destination is given.
+    Get {
+        path: String,
+        destination: Option<String>,
+    },
+}
+
+impl Request {
+    fn command(&self) -> Command {
+        match self {
+            Self::List { .. } => Command::List,
+            Self::Get { .. } => Command::Get,
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            Self::List { path } | Self::Get { path, .. } => path,
+        }
+    }
+}
+
+/// Errors after which repeating the request may succeed. A refusal by the
+/// server is final.
+fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::WouldBlock
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::Interrupted
+    )
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(ErrorKind::NotFound, format!("No address for {host}"));
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = e,
+        }
     }
+    Err(last_error)
+}
 
-    let mut stream = TcpStream::connect((IP_ADDRESS, PORT))?;
+fn send(cli: &Cli) -> io::Result<Vec<u8>> {
+    let timeout = Duration::from_millis(cli.timeout_ms);
+    let mut backoff = Duration::from_millis(cli.backoff_ms);
+    let mut attempt = 0;
 
-    let len_be = (path_bytes.len() as u32).to_be_bytes();
+    loop {
+        let result = connect(&cli.host, cli.port, timeout).and_then(|mut stream| {
+            write_request(&mut stream, cli.request.command(), cli.request.path())?;
+            read_response(&mut stream, MAX_FILE_LEN)
+        });
 
-    stream.write_all(&len_be)?;
-    stream.write_all(path_bytes)?;
+        match result {
+            Err(e) if attempt < cli.retries && is_transient(&e) => {
+                attempt += 1;
+                eprintln!(
+                    "Attempt {attempt} failed: {e}, retrying in {} ms",
+                    backoff.as_millis()
+                );
+                thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
 
-    let mut response = Vec::new();
-    stream.read_to_end(&mut response)?;
-    let response = String::from_utf8_lossy(&response);
+fn print_response(cli: &Cli, response: Vec<u8>) -> io::Result<()> {
+    let path = json_string(cli.request.path());
 
-    println!("{response}");
+    match &cli.request {
+        Request::List { .. } => {
+            let listing = String::from_utf8_lossy(&response);
+            if cli.json {
+                let files = listing.lines().map(json_string).collect::<Vec<_>>();
+                println!("{{\"path\":{path},\"files\":[{}]}}", files.join(","));
+            } else {
+                print!("{listing}");
+            }
+        }
+        Request::Get {
+            destination: Some(destination),
+            ..
+        } => {
+            fs::write(destination, &response)?;
+            if cli.json {
+                println!(
+                    "{{\"path\":{path},\"destination\":{},\"bytes\":{}}}",
+                    json_string(destination),
+                    response.len()
+                );
+            } else {
+                println!("Saved {} bytes to {destination}", response.len());
+            }
+        }
+        Request::Get {
+            destination: None, ..
+        } => io::stdout().write_all(&response)?,
+    }
 
     Ok(())
 }
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match send(&cli).and_then(|response| print_response(&cli, response)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if cli.json {
+                println!("{{\"error\":{}}}", json_string(&e.to_string()));
+            } else {
+                eprintln!("Request failed: {e}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
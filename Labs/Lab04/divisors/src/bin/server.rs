@@ -1,39 +1,91 @@
 use std::{
-    fs, io,
+    env,
+    fs::{self, File},
+    io::{self, ErrorKind, Read},
     net::{TcpListener, TcpStream},
-    path::PathBuf,
-    str::FromStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
-use divisors::{IP_ADDRESS, PORT, bulk_read, bulk_write};
+use divisors::{
+    IP_ADDRESS, PORT, ThreadPool, bulk_read, bulk_write,
+    directory::{Refusal, decode_path, list_dir, resolve},
+    protocol::{
+        Command, MAX_FILE_LEN, REQUEST_HEADER_LEN, Status, parse_request_header, write_header,
+        write_refusal, write_response,
+    },
+};
+
+const DEFAULT_WORKERS: usize = 4;
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const FILE_CHUNK_LEN: usize = 64 * 1024;
 
+#[derive(Clone)]
 pub struct DirectoryServer {
     pub address: String,
     pub port: u16,
+    /// Only paths inside this directory are served. Relative paths sent by a
+    /// client are resolved against it.
+    pub root: PathBuf,
+    /// Number of connections handled at the same time.
+    pub workers: usize,
+    /// How long a client may stay silent before its connection is dropped.
+    pub read_timeout: Duration,
 }
 
 impl DirectoryServer {
-    fn new(address: &str, port: u16) -> Self {
+    fn new(address: &str, port: u16, root: impl Into<PathBuf>) -> Self {
         Self {
             address: address.to_string(),
             port,
+            root: root.into(),
+            workers: DEFAULT_WORKERS,
+            read_timeout: DEFAULT_READ_TIMEOUT,
         }
     }
 
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
     pub fn run(&self) -> io::Result<()> {
         let bind_address = format!("{}:{}", self.address, self.port);
         let listener = TcpListener::bind(bind_address.clone())?;
 
-        println!("Server listening on {bind_address}");
+        let root = fs::canonicalize(&self.root)?;
+        println!(
+            "Server listening on {bind_address} with {} workers, serving {root:?}",
+            self.workers
+        );
+
+        let server = Arc::new(Self {
+            root,
+            ..self.clone()
+        });
+        let pool = ThreadPool::new(self.workers);
 
         for connection_stream in listener.incoming() {
             match connection_stream {
-                Ok(stream) => match self.handle_client(stream) {
-                    Ok(()) => {}
-                    Err(e) => {
-                        eprintln!("An error occurred while handling the client: {e}");
+                Ok(stream) => {
+                    if let Err(e) = stream.set_read_timeout(Some(self.read_timeout)) {
+                        eprintln!("Failed to set the read timeout: {e}");
+                        continue;
                     }
-                },
+
+                    let server = Arc::clone(&server);
+                    pool.execute(move || {
+                        if let Err(e) = server.handle_client(stream) {
+                            eprintln!("An error occurred while handling the client: {e}");
+                        }
+                    });
+                }
                 Err(e) => {
                     eprintln!("Incoming connection failed: {e}");
                 }
@@ -43,97 +95,85 @@ impl DirectoryServer {
         Ok(())
     }
 
-    fn list_dir(&self, pathbuf: &PathBuf) -> Option<String> {
-        match fs::read_dir(pathbuf) {
-            Ok(entries) => {
-                let mut listing = String::new();
-                for dir_entry in entries {
-                    match dir_entry {
-                        Ok(entry) => {
-                            let metadata = match entry.metadata() {
-                                Ok(metadata) => metadata,
-                                Err(e) => {
-                                    eprintln!(
-                                        "Error getting metadata for entry in {pathbuf:?}: {e}"
-                                    );
-                                    return None;
-                                }
-                            };
-
-                            if !metadata.is_file() {
-                                continue;
-                            }
-
-                            let file_name = entry.file_name();
-                            let name = file_name.to_string_lossy();
-                            listing.push_str(&name);
-                            listing.push('\n');
-                        }
-                        Err(e) => {
-                            eprintln!("Error reading directory entry for {pathbuf:?}: {e}");
-                            return None;
-                        }
-                    }
-                }
-                Some(listing)
+    fn send_file(&self, stream: &mut TcpStream, pathbuf: &Path) -> io::Result<()> {
+        let mut file = match File::open(pathbuf) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Error opening file {pathbuf:?}: {e}");
+                return write_refusal(stream, Refusal::BAD_PATH);
             }
+        };
+
+        let len = match file.metadata() {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            Ok(_) => return write_refusal(stream, Refusal::error("Not a file")),
             Err(e) => {
-                eprintln!("Error opening dir {pathbuf:?}: {e}");
-                None
+                eprintln!("Error getting metadata for {pathbuf:?}: {e}");
+                return write_refusal(stream, Refusal::BAD_PATH);
             }
+        };
+
+        if len > MAX_FILE_LEN {
+            return write_refusal(stream, Refusal::error("File too large"));
         }
-    }
 
-    pub fn handle_client(&self, mut stream: TcpStream) -> io::Result<()> {
-        let len_bytes = bulk_read(&mut stream, 4)?;
-        if len_bytes.len() < 4 {
-            bulk_write(&mut stream, b"Bad path\n")?;
-            return Ok(());
+        write_header(stream, Status::Ok, len)?;
+
+        // the header promised `len` bytes, so a file that shrinks in the meantime
+        // can only be reported by dropping the connection
+        let mut chunk = vec![0u8; FILE_CHUNK_LEN];
+        let mut remaining = len;
+        while remaining > 0 {
+            let wanted = remaining.min(FILE_CHUNK_LEN as u64) as usize;
+            let read = file.read(&mut chunk[..wanted])?;
+            if read == 0 {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("{pathbuf:?} shrank while being sent"),
+                ));
+            }
+            bulk_write(stream, &chunk[..read])?;
+            remaining -= read as u64;
         }
 
-        let len =
-            u32::from_be_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+        Ok(())
+    }
 
-        const MAX_PATH_LEN: usize = 64 * 1024;
-        if len == 0 || len > MAX_PATH_LEN {
-            bulk_write(&mut stream, b"Bad path\n")?;
-            return Ok(());
-        }
+    pub fn handle_client(&self, mut stream: TcpStream) -> io::Result<()> {
+        let header = bulk_read(&mut stream, REQUEST_HEADER_LEN)?;
+        let Ok(header) = header.try_into() else {
+            return write_refusal(&mut stream, Refusal::error("Bad request"));
+        };
+
+        let (command, len) = match parse_request_header(header) {
+            Ok(parsed) => parsed,
+            Err(refusal) => return write_refusal(&mut stream, refusal),
+        };
 
         let path_bytes = bulk_read(&mut stream, len)?;
         if path_bytes.len() != len {
-            bulk_write(&mut stream, b"Bad path\n")?;
-            return Ok(());
+            return write_refusal(&mut stream, Refusal::BAD_PATH);
         }
 
-        let path_string_res = String::from_utf8(path_bytes);
-        let pathbuf = match path_string_res {
-            Ok(s) => {
-                let s = s.trim().to_string();
-                match PathBuf::from_str(&s) {
-                    Ok(pb) => pb,
-                    Err(_) => {
-                        bulk_write(&mut stream, b"Bad path\n")?;
-                        return Ok(());
-                    }
-                }
-            }
-            Err(_) => {
-                bulk_write(&mut stream, b"Bad path\n")?;
-                return Ok(());
-            }
+        let pathbuf = match decode_path(path_bytes).and_then(|path| resolve(&self.root, &path)) {
+            Ok(pathbuf) => pathbuf,
+            Err(refusal) => return write_refusal(&mut stream, refusal),
         };
 
-        match self.list_dir(&pathbuf) {
-            Some(listing) => bulk_write(&mut stream, listing.as_bytes())?,
-            None => bulk_write(&mut stream, b"Bad path\n")?,
+        match command {
+            Command::List => match list_dir(&pathbuf) {
+                Some(listing) => write_response(&mut stream, Status::Ok, listing.as_bytes()),
+                None => write_refusal(&mut stream, Refusal::BAD_PATH),
+            },
+            Command::Get => self.send_file(&mut stream, &pathbuf),
         }
-
-        Ok(())
     }
 }
 
 fn main() -> io::Result<()> {
-    let server = DirectoryServer::new(IP_ADDRESS, PORT);
+    let root = env::args().nth(1).unwrap_or_else(|| ".".to_string());
+    let server = DirectoryServer::new(IP_ADDRESS, PORT, root)
+        .with_workers(DEFAULT_WORKERS)
+        .with_read_timeout(DEFAULT_READ_TIMEOUT);
     server.run()
 }
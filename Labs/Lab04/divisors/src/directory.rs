@@ -0,0 +1,199 @@
+//! Request handling shared by the blocking and the event-driven servers.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::protocol::Status;
+
+/// Why a request cannot be served, as sent back to the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Refusal {
+    pub status: Status,
+    pub message: &'static str,
+}
+
+impl Refusal {
+    pub const BAD_PATH: Self = Self::error("Bad path");
+
+    pub const fn error(message: &'static str) -> Self {
+        Self {
+            status: Status::Error,
+            message,
+        }
+    }
+}
+
+pub fn decode_path(path_bytes: Vec<u8>) -> Result<PathBuf, Refusal> {
+    match String::from_utf8(path_bytes) {
+        Ok(s) => Ok(PathBuf::from(s.trim())),
+        Err(_) => Err(Refusal::BAD_PATH),
+    }
+}
+
+/// Resolves `requested` against `root` and follows `..` and symbolic links, so
+/// that anything ending up outside of `root` is refused. `root` itself has to be
+/// canonical already.
+pub fn resolve(root: &Path, requested: &Path) -> Result<PathBuf, Refusal> {
+    let resolved = match fs::canonicalize(root.join(requested)) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("Error resolving {requested:?}: {e}");
+            return Err(Refusal::BAD_PATH);
+        }
+    };
+
+    if !resolved.starts_with(root) {
+        eprintln!("Rejected {resolved:?} outside of {root:?}");
+        return Err(Refusal {
+            status: Status::Forbidden,
+            message: "Path outside the served directory",
+        });
+    }
+
+    Ok(resolved)
+}
+
+pub fn list_dir(pathbuf: &Path) -> Option<String> {
+    match fs::read_dir(pathbuf) {
+        Ok(entries) => {
+            let mut listing = String::new();
+            for dir_entry in entries {
+                match dir_entry {
+                    Ok(entry) => {
+                        let metadata = match entry.metadata() {
+                            Ok(metadata) => metadata,
+                            Err(e) => {
+                                eprintln!("Error getting metadata for entry in {pathbuf:?}: {e}");
+                                return None;
+                            }
+                        };
+
+                        if !metadata.is_file() {
+                            continue;
+                        }
+
+                        let file_name = entry.file_name();
+                        let name = file_name.to_string_lossy();
+                        listing.push_str(&name);
+                        listing.push('\n');
+                    }
+                    Err(e) => {
+                        eprintln!("Error reading directory entry for {pathbuf:?}: {e}");
+                        return None;
+                    }
+                }
+            }
+            Some(listing)
+        }
+        Err(e) => {
+            eprintln!("Error opening dir {pathbuf:?}: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// A canonical directory holding `served/file.txt`, `served/nested/` and
+    /// `secret.txt` next to `served`, removed when dropped.
+    struct Fixture {
+        base: PathBuf,
+        root: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let base = env::temp_dir().join(format!("divisors_{name}_{}", process::id()));
+            let _ = fs::remove_dir_all(&base);
+            fs::create_dir_all(base.join("served/nested")).unwrap();
+            fs::write(base.join("served/file.txt"), "served").unwrap();
+            fs::write(base.join("secret.txt"), "secret").unwrap();
+
+            let base = fs::canonicalize(base).unwrap();
+            let root = base.join("served");
+            Self { base, root }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.base);
+        }
+    }
+
+    fn assert_forbidden(result: Result<PathBuf, Refusal>) {
+        assert_eq!(result.unwrap_err().status, Status::Forbidden);
+    }
+
+    #[test]
+    fn test_resolve_relative_paths() {
+        let fixture = Fixture::new("relative");
+        let root = &fixture.root;
+
+        assert_eq!(
+            resolve(root, Path::new("file.txt")),
+            Ok(root.join("file.txt"))
+        );
+        assert_eq!(resolve(root, Path::new("nested")), Ok(root.join("nested")));
+        assert_eq!(
+            resolve(root, Path::new("nested/../file.txt")),
+            Ok(root.join("file.txt"))
+        );
+        assert_eq!(resolve(root, Path::new("")), Ok(root.clone()));
+    }
+
+    #[test]
+    fn test_resolve_rejects_paths_outside_root() {
+        let fixture = Fixture::new("outside");
+        let root = &fixture.root;
+
+        assert_forbidden(resolve(root, Path::new("../secret.txt")));
+        assert_forbidden(resolve(root, Path::new("nested/../../secret.txt")));
+        assert_forbidden(resolve(root, Path::new("..")));
+        assert_forbidden(resolve(root, &fixture.base.join("secret.txt")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_symlinks_outside_root() {
+        use std::os::unix::fs::symlink;
+
+        let fixture = Fixture::new("symlinks");
+        let root = &fixture.root;
+        symlink(fixture.base.join("secret.txt"), root.join("link.txt")).unwrap();
+        symlink(&fixture.base, root.join("nested/escape")).unwrap();
+        symlink(root.join("file.txt"), root.join("inside.txt")).unwrap();
+
+        assert_forbidden(resolve(root, Path::new("link.txt")));
+        assert_forbidden(resolve(root, Path::new("nested/escape/secret.txt")));
+        assert_eq!(
+            resolve(root, Path::new("inside.txt")),
+            Ok(root.join("file.txt"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_nonexistent_paths() {
+        let fixture = Fixture::new("nonexistent");
+        let root = &fixture.root;
+
+        assert_eq!(
+            resolve(root, Path::new("missing.txt")),
+            Err(Refusal::BAD_PATH)
+        );
+        assert_eq!(
+            resolve(root, Path::new("nested/missing/../file.txt")),
+            Err(Refusal::BAD_PATH)
+        );
+        assert_eq!(
+            resolve(root, Path::new("../missing.txt")),
+            Err(Refusal::BAD_PATH)
+        );
+    }
+}
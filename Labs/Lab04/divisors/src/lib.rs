@@ -1,6 +1,10 @@
 mod bulk_operations;
+pub mod directory;
+pub mod protocol;
+mod thread_pool;
 
 pub use bulk_operations::{bulk_read, bulk_write};
+pub use thread_pool::ThreadPool;
 
 pub const IP_ADDRESS: &str = "127.0.0.1";
 pub const PORT: u16 = 7878;
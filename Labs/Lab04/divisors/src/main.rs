@@ -6,6 +6,7 @@ use std::{
     vec,
 };
 
+#[allow(clippy::manual_is_multiple_of)]
 pub fn divisors(n: NonZeroU32) -> BTreeSet<NonZeroU32> {
     let n = n.get();
     let mut divisors = BTreeSet::new();
@@ -26,6 +27,146 @@ pub fn divisors(n: NonZeroU32) -> BTreeSet<NonZeroU32> {
     divisors
 }
 
+/// Numbers up to this limit may be factorized by [`divisors_batch`] with a sieve,
+/// which needs four bytes per number.
+const MAX_SIEVE_LEN: u32 = 1 << 20;
+
+/// Smallest prime factor of every number up to a limit, which factorizes any of
+/// them in a logarithmic number of divisions.
+pub struct Sieve {
+    smallest_prime_factor: Vec<u32>,
+}
+
+impl Sieve {
+    pub fn new(limit: u32) -> Self {
+        let len = limit as usize + 1;
+        let mut smallest_prime_factor = vec![0u32; len];
+        let mut primes = Vec::new();
+
+        // linear sieve: every composite is crossed out exactly once, by its
+        // smallest prime factor
+        for i in 2..len {
+            if smallest_prime_factor[i] == 0 {
+                smallest_prime_factor[i] = i as u32;
+                primes.push(i as u32);
+            }
+            for &p in &primes {
+                let multiple = i * p as usize;
+                if p > smallest_prime_factor[i] || multiple >= len {
+                    break;
+                }
+                smallest_prime_factor[multiple] = p;
+            }
+        }
+
+        Self {
+            smallest_prime_factor,
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        (self.smallest_prime_factor.len() - 1) as u32
+    }
+
+    /// Panics if `n` is above the limit of the sieve.
+    pub fn factorize(&self, n: NonZeroU32) -> Vec<(u32, u32)> {
+        assert!(
+            n.get() <= self.limit(),
+            "{n} is above the sieve limit {}",
+            self.limit()
+        );
+
+        let mut n = n.get();
+        let mut factors: Vec<(u32, u32)> = Vec::new();
+        while n > 1 {
+            let p = self.smallest_prime_factor[n as usize];
+            match factors.last_mut() {
+                Some((prime, exponent)) if *prime == p => *exponent += 1,
+                _ => factors.push((p, 1)),
+            }
+            n /= p;
+        }
+        factors
+    }
+}
+
+/// Prime factorization of `n` as `(prime, exponent)` pairs in increasing order
+/// of primes, found by trial division. `1` has no factors.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn factorize(n: NonZeroU32) -> Vec<(u32, u32)> {
+    let mut n = n.get();
+    let mut factors = Vec::new();
+
+    let mut p = 2u32;
+    while u64::from(p) * u64::from(p) <= u64::from(n) {
+        let mut exponent = 0;
+        while n % p == 0 {
+            n /= p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Number of divisors of `n`, computed from its factorization without listing them.
+pub fn divisor_count(n: NonZeroU32) -> u32 {
+    factorize(n)
+        .into_iter()
+        .map(|(_, exponent)| exponent + 1)
+        .product()
+}
+
+fn divisors_from_factors(factors: &[(u32, u32)]) -> BTreeSet<NonZeroU32> {
+    let mut divisors = vec![1u32];
+    for &(prime, exponent) in factors {
+        let count = divisors.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= prime;
+            for i in 0..count {
+                divisors.push(divisors[i] * power);
+            }
+        }
+    }
+
+    divisors
+        .into_iter()
+        .map(|d| NonZeroU32::new(d).unwrap())
+        .collect()
+}
+
+/// Divisors of every number in `ns`, in the same order. A single sieve up to the
+/// largest number is shared by all of them when it is cheaper than factorizing
+/// each of them by trial division, which takes up to the square root of the
+/// number in steps, and does not exceed `MAX_SIEVE_LEN`. A single number, or a
+/// few large ones, are therefore always factorized on their own.
+pub fn divisors_batch(ns: &[NonZeroU32]) -> Vec<BTreeSet<NonZeroU32>> {
+    let Some(max) = ns.iter().max() else {
+        return Vec::new();
+    };
+
+    let trial_division_steps = ns.len() as u64 * u64::from(max.get().isqrt());
+    if max.get() > MAX_SIEVE_LEN || u64::from(max.get()) > trial_division_steps {
+        return ns
+            .iter()
+            .map(|&n| divisors_from_factors(&factorize(n)))
+            .collect();
+    }
+
+    let sieve = Sieve::new(max.get());
+    ns.iter()
+        .map(|&n| divisors_from_factors(&sieve.factorize(n)))
+        .collect()
+}
+
 fn benchmark_divisors<F>(f: F)
 where
     F: Fn(NonZeroU32) -> BTreeSet<NonZeroU32>,
@@ -47,6 +188,32 @@ where
     println!("Average time: {:.6} ms", avg.as_secs_f64() * 1000.0);
 }
 
+/// Compares [`divisors`] called for every number up to `limit` with a single
+/// [`divisors_batch`] call, checking that both agree.
+fn benchmark_divisors_batch(limit: u32) {
+    let inputs = (1..=limit)
+        .map(|n| NonZeroU32::new(n).unwrap())
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let one_by_one = black_box(&inputs)
+        .iter()
+        .map(|&n| divisors(n))
+        .collect::<Vec<_>>();
+    let one_by_one_duration = start.elapsed();
+
+    let start = Instant::now();
+    let batched = divisors_batch(black_box(&inputs));
+    let batched_duration = start.elapsed();
+
+    assert_eq!(one_by_one, batched);
+    println!(
+        "Divisors up to {limit}: {:.3} ms one by one, {:.3} ms batched",
+        one_by_one_duration.as_secs_f64() * 1000.0,
+        batched_duration.as_secs_f64() * 1000.0
+    );
+}
+
 fn assert_sorted(buf: &[i32]) {
     for window in buf.windows(2) {
         let (first, second) = (window[0], window[1]);
@@ -71,10 +238,89 @@ fn main() {
         NonZeroU32::new(1024).unwrap(),
     ];
 
-    for input in inputs {
-        let result = divisors(input);
+    for (input, batched) in inputs.iter().zip(divisors_batch(&inputs)) {
+        let result = divisors(*input);
+        assert_eq!(result, batched);
+        assert_eq!(result.len() as u32, divisor_count(*input));
         dbg!(result);
+        println!("{input} = {:?}", factorize(*input));
     }
 
     benchmark_divisors(divisors);
+    benchmark_divisors_batch(100_000);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nz(n: u32) -> NonZeroU32 {
+        NonZeroU32::new(n).unwrap()
+    }
+
+    fn set(ns: &[u32]) -> BTreeSet<NonZeroU32> {
+        ns.iter().map(|&n| nz(n)).collect()
+    }
+
+    #[test]
+    fn test_zero_and_one() {
+        assert!(divisors_batch(&[]).is_empty());
+        assert_eq!(Sieve::new(0).limit(), 0);
+
+        assert_eq!(divisors(nz(1)), set(&[1]));
+        assert_eq!(factorize(nz(1)), []);
+        assert_eq!(Sieve::new(1).factorize(nz(1)), []);
+        assert_eq!(divisor_count(nz(1)), 1);
+        assert_eq!(divisors_batch(&[nz(1)]), [set(&[1])]);
+    }
+
+    #[test]
+    fn test_primes() {
+        let sieve = Sieve::new(100);
+        for p in [2, 3, 5, 7, 97] {
+            assert_eq!(divisors(nz(p)), set(&[1, p]));
+            assert_eq!(factorize(nz(p)), [(p, 1)]);
+            assert_eq!(sieve.factorize(nz(p)), [(p, 1)]);
+            assert_eq!(divisor_count(nz(p)), 2);
+        }
+
+        let largest = 4_294_967_291;
+        assert_eq!(factorize(nz(largest)), [(largest, 1)]);
+        assert_eq!(divisors_batch(&[nz(largest)]), [set(&[1, largest])]);
+    }
+
+    #[test]
+    fn test_prime_powers() {
+        assert_eq!(factorize(nz(1024)), [(2, 10)]);
+        assert_eq!(divisor_count(nz(1024)), 11);
+        assert_eq!(divisors(nz(81)), set(&[1, 3, 9, 27, 81]));
+        assert_eq!(Sieve::new(81).factorize(nz(81)), [(3, 4)]);
+
+        assert_eq!(factorize(nz(1 << 31)), [(2, 31)]);
+        assert_eq!(factorize(nz(59_049)), [(3, 10)]);
+        assert_eq!(divisor_count(nz(59_049)), 11);
+    }
+
+    #[test]
+    fn test_batch_agrees_with_single_numbers() {
+        let small = (1..=2_000).map(nz).collect::<Vec<_>>();
+        let sieve = Sieve::new(2_000);
+        for (&n, batched) in small.iter().zip(divisors_batch(&small)) {
+            assert_eq!(batched, divisors(n));
+            assert_eq!(batched.len() as u32, divisor_count(n));
+            assert_eq!(sieve.factorize(n), factorize(n));
+        }
+
+        // too few numbers for a sieve to pay off
+        let large = [nz(1 << 24), nz(999_999_937), nz(720_720)];
+        for (&n, batched) in large.iter().zip(divisors_batch(&large)) {
+            assert_eq!(batched, divisors(n));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "above the sieve limit")]
+    fn test_sieve_rejects_numbers_above_limit() {
+        Sieve::new(10).factorize(nz(11));
+    }
 }
@@ -0,0 +1,140 @@
+//! Wire format shared by the directory server and its client.
+//!
+//! A request is a [`Command`] byte, the length of the path as a big-endian `u32`
+//! and the UTF-8 path itself. A response is a [`Status`] byte, the length of the
+//! payload as a big-endian `u64` and the payload: a newline separated listing or
+//! the file contents on success, a message on error.
+
+use std::{
+    io::{self, ErrorKind},
+    net::TcpStream,
+};
+
+use crate::{bulk_read, bulk_write, directory::Refusal};
+
+pub const MAX_PATH_LEN: usize = 64 * 1024;
+/// Length of the command byte and the path length preceding the path.
+pub const REQUEST_HEADER_LEN: usize = 5;
+/// Length of the status byte and the payload length preceding the payload.
+pub const RESPONSE_HEADER_LEN: usize = 9;
+pub const MAX_FILE_LEN: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Command {
+    /// Lists the files in a directory.
+    List = b'L',
+    /// Downloads a single file.
+    Get = b'G',
+}
+
+impl Command {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'L' => Some(Self::List),
+            b'G' => Some(Self::Get),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Status {
+    Ok = 0,
+    Error = 1,
+    /// The path lies outside the directory served.
+    Forbidden = 2,
+}
+
+/// Decodes the command and the length of the path which follows it.
+pub fn parse_request_header(header: [u8; REQUEST_HEADER_LEN]) -> Result<(Command, usize), Refusal> {
+    let Some(command) = Command::from_byte(header[0]) else {
+        return Err(Refusal::error("Unknown command"));
+    };
+
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len == 0 || len > MAX_PATH_LEN {
+        return Err(Refusal::BAD_PATH);
+    }
+
+    Ok((command, len))
+}
+
+pub fn write_request(stream: &mut TcpStream, command: Command, path: &str) -> io::Result<()> {
+    if path.len() > MAX_PATH_LEN {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "Path too long"));
+    }
+
+    bulk_write(stream, &[command as u8])?;
+    bulk_write(stream, &(path.len() as u32).to_be_bytes())?;
+    bulk_write(stream, path.as_bytes())
+}
+
+/// Writes the status and payload length, after which exactly `len` bytes of
+/// payload have to follow.
+pub fn write_header(stream: &mut TcpStream, status: Status, len: u64) -> io::Result<()> {
+    bulk_write(stream, &[status as u8])?;
+    bulk_write(stream, &len.to_be_bytes())
+}
+
+pub fn write_response(stream: &mut TcpStream, status: Status, payload: &[u8]) -> io::Result<()> {
+    write_header(stream, status, payload.len() as u64)?;
+    bulk_write(stream, payload)
+}
+
+pub fn write_refusal(stream: &mut TcpStream, refusal: Refusal) -> io::Result<()> {
+    write_response(stream, refusal.status, refusal.message.as_bytes())
+}
+
+/// Encodes a whole response, for servers which write it out piecewise.
+pub fn encode_response(status: Status, payload: &[u8]) -> Vec<u8> {
+    let mut response = Vec::with_capacity(RESPONSE_HEADER_LEN + payload.len());
+    response.push(status as u8);
+    response.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    response.extend_from_slice(payload);
+    response
+}
+
+/// Reads a whole response, turning an error reported by the server into an
+/// [`io::Error`]. Payloads longer than `max_len` are refused without being read.
+pub fn read_response(stream: &mut TcpStream, max_len: u64) -> io::Result<Vec<u8>> {
+    let header = bulk_read(stream, RESPONSE_HEADER_LEN)?;
+    if header.len() != RESPONSE_HEADER_LEN {
+        return Err(io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "Connection closed before the response header",
+        ));
+    }
+
+    let len = u64::from_be_bytes(header[1..].try_into().expect("8 bytes"));
+    if len > max_len {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Response of {len} bytes exceeds the limit of {max_len} bytes"),
+        ));
+    }
+
+    let payload = bulk_read(stream, len as usize)?;
+    if payload.len() as u64 != len {
+        return Err(io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "Connection closed before the whole response was read",
+        ));
+    }
+
+    match header[0] {
+        status if status == Status::Ok as u8 => Ok(payload),
+        status if status == Status::Error as u8 => Err(io::Error::other(
+            String::from_utf8_lossy(&payload).into_owned(),
+        )),
+        status if status == Status::Forbidden as u8 => Err(io::Error::new(
+            ErrorKind::PermissionDenied,
+            String::from_utf8_lossy(&payload).into_owned(),
+        )),
+        status => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Unknown response status {status}"),
+        )),
+    }
+}
@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use crate::core::{Context, Expr, RuntimeError};
+
+/// How arithmetic nodes treat results which do not fit in a `u64`.
+pub trait Overflow {
+    fn add(lhs: u64, rhs: u64) -> Result<u64, RuntimeError>;
+    fn sub(lhs: u64, rhs: u64) -> Result<u64, RuntimeError>;
+    fn mul(lhs: u64, rhs: u64) -> Result<u64, RuntimeError>;
+    fn div(lhs: u64, rhs: u64) -> Result<u64, RuntimeError>;
+}
+
+/// Results wrap around modulo 2^64. Division by zero is still an error.
+pub struct Wrapping;
+
+/// Any overflow, underflow or division by zero is an error.
+pub struct Checked;
+
+fn checked(result: Option<u64>, lhs: u64, operator: char, rhs: u64) -> Result<u64, RuntimeError> {
+    result.ok_or(RuntimeError::Overflow { lhs, operator, rhs })
+}
+
+impl Overflow for Wrapping {
+    fn add(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        Ok(lhs.wrapping_add(rhs))
+    }
+
+    fn sub(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        Ok(lhs.wrapping_sub(rhs))
+    }
+
+    fn mul(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        Ok(lhs.wrapping_mul(rhs))
+    }
+
+    fn div(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        lhs.checked_div(rhs).ok_or(RuntimeError::DivisionByZero)
+    }
+}
+
+impl Overflow for Checked {
+    fn add(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        checked(lhs.checked_add(rhs), lhs, '+', rhs)
+    }
+
+    fn sub(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        checked(lhs.checked_sub(rhs), lhs, '-', rhs)
+    }
+
+    fn mul(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        checked(lhs.checked_mul(rhs), lhs, '*', rhs)
+    }
+
+    fn div(lhs: u64, rhs: u64) -> Result<u64, RuntimeError> {
+        lhs.checked_div(rhs).ok_or(RuntimeError::DivisionByZero)
+    }
+}
+
+pub struct Add<T: Expr, U: Expr, O: Overflow = Wrapping> {
+    lhs: T,
+    rhs: U,
+    overflow: PhantomData<O>,
+}
+
+impl<T: Expr, U: Expr, O: Overflow> Expr for Add<T, U, O> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let lhs = self.lhs.try_exec_expr(context)?;
+        let rhs = self.rhs.try_exec_expr(context)?;
+        O::add(lhs, rhs)
+    }
+}
+
+pub fn add<T: Expr, U: Expr>(lhs: T, rhs: U) -> Add<T, U> {
+    Add {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub fn checked_add<T: Expr, U: Expr>(lhs: T, rhs: U) -> Add<T, U, Checked> {
+    Add {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub struct Sub<T: Expr, U: Expr, O: Overflow = Wrapping> {
+    lhs: T,
+    rhs: U,
+    overflow: PhantomData<O>,
+}
+
+impl<T: Expr, U: Expr, O: Overflow> Expr for Sub<T, U, O> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let lhs = self.lhs.try_exec_expr(context)?;
+        let rhs = self.rhs.try_exec_expr(context)?;
+        O::sub(lhs, rhs)
+    }
+}
+
+pub fn sub<T: Expr, U: Expr>(lhs: T, rhs: U) -> Sub<T, U> {
+    Sub {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub fn checked_sub<T: Expr, U: Expr>(lhs: T, rhs: U) -> Sub<T, U, Checked> {
+    Sub {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub struct Mul<T: Expr, U: Expr, O: Overflow = Wrapping> {
+    lhs: T,
+    rhs: U,
+    overflow: PhantomData<O>,
+}
+
+impl<T: Expr, U: Expr, O: Overflow> Expr for Mul<T, U, O> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let lhs = self.lhs.try_exec_expr(context)?;
+        let rhs = self.rhs.try_exec_expr(context)?;
+        O::mul(lhs, rhs)
+    }
+}
+
+pub fn mul<T: Expr, U: Expr>(lhs: T, rhs: U) -> Mul<T, U> {
+    Mul {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub fn checked_mul<T: Expr, U: Expr>(lhs: T, rhs: U) -> Mul<T, U, Checked> {
+    Mul {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub struct Div<T: Expr, U: Expr, O: Overflow = Wrapping> {
+    lhs: T,
+    rhs: U,
+    overflow: PhantomData<O>,
+}
+
+impl<T: Expr, U: Expr, O: Overflow> Expr for Div<T, U, O> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let lhs = self.lhs.try_exec_expr(context)?;
+        let rhs = self.rhs.try_exec_expr(context)?;
+        O::div(lhs, rhs)
+    }
+}
+
+pub fn div<T: Expr, U: Expr>(lhs: T, rhs: U) -> Div<T, U> {
+    Div {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
+
+pub fn checked_div<T: Expr, U: Expr>(lhs: T, rhs: U) -> Div<T, U, Checked> {
+    Div {
+        lhs,
+        rhs,
+        overflow: PhantomData,
+    }
+}
@@ -1,11 +1,160 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    cell::{RefCell, RefMut},
+    collections::HashMap,
+    fmt::Display,
+    io::{self, Write},
+    rc::Rc,
+};
 
-pub type Context = HashMap<&'static str, u64>;
+use crate::func::Callable;
+
+/// Variables and functions visible to a program, together with the sink its output
+/// is written to. Clones share the sink and the functions, so nested scopes write
+/// to the same place and run the same function bodies.
+#[derive(Clone)]
+pub struct Context {
+    values: HashMap<Cow<'static, str>, u64>,
+    functions: HashMap<&'static str, Rc<RefCell<dyn Callable>>>,
+    output: Rc<RefCell<dyn Write>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            functions: HashMap::new(),
+            output: Rc::new(RefCell::new(io::stdout())),
+        }
+    }
+
+    /// Redirects program output, e.g. into an `Rc<RefCell<Vec<u8>>>` kept by the caller.
+    pub fn with_output(mut self, output: Rc<RefCell<dyn Write>>) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&u64> {
+        self.values.get(name)
+    }
+
+    /// Sets the variable `name`, returning its previous value.
+    pub fn insert(&mut self, name: impl Into<Cow<'static, str>>, value: u64) -> Option<u64> {
+        self.values.insert(name.into(), value)
+    }
+
+    pub fn define(&mut self, name: &'static str, function: impl Callable + 'static) {
+        self.functions.insert(name, Rc::new(RefCell::new(function)));
+    }
+
+    pub fn function(&self, name: &str) -> Option<Rc<RefCell<dyn Callable>>> {
+        self.functions.get(name).cloned()
+    }
+
+    pub fn output(&self) -> RefMut<'_, dyn Write> {
+        self.output.borrow_mut()
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> From<[(&'static str, u64); N]> for Context {
+    fn from(values: [(&'static str, u64); N]) -> Self {
+        Self {
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+            ..Self::new()
+        }
+    }
+}
+
+/// Why a program stopped before it finished.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeError {
+    DivisionByZero,
+    /// A checked operation whose result does not fit in a `u64`.
+    Overflow {
+        lhs: u64,
+        operator: char,
+        rhs: u64,
+    },
+    UndefinedVariable(String),
+    UndefinedFunction(String),
+    /// A function called while it is already running.
+    Recursion(String),
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The output sink of the [`Context`] could not be written to.
+    Output(String),
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow {
+                lhs,
+                operator: '-',
+                rhs,
+            } => write!(f, "{lhs} - {rhs} underflows u64"),
+            Self::Overflow { lhs, operator, rhs } => {
+                write!(f, "{lhs} {operator} {rhs} overflows u64")
+            }
+            Self::UndefinedVariable(name) => write!(f, "variable `{name}` is not defined"),
+            Self::UndefinedFunction(name) => write!(f, "function `{name}` is not defined"),
+            Self::Recursion(name) => write!(f, "function `{name}` cannot call itself"),
+            Self::ArgumentCount {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "function `{function}` takes {expected} arguments, but {found} were given"
+            ),
+            Self::Output(error) => write!(f, "failed to write program output: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
 
 pub trait Expr {
-    fn exec_expr(&mut self, context: &Context) -> u64;
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError>;
+
+    /// Like [`Expr::try_exec_expr`], but panics if the evaluation fails.
+    fn exec_expr(&mut self, context: &mut Context) -> u64 {
+        self.try_exec_expr(context)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
 }
 
 pub trait Stmt {
-    fn exec_stmt(&mut self, context: &Context);
-}
\ No newline at end of file
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError>;
+
+    /// Like [`Stmt::try_exec_stmt`], but panics if the execution fails.
+    fn exec_stmt(&mut self, context: &mut Context) {
+        self.try_exec_stmt(context)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Expr + ?Sized> Expr for Box<T> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        (**self).try_exec_expr(context)
+    }
+}
+
+impl<T: Stmt + ?Sized> Stmt for Box<T> {
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        (**self).try_exec_stmt(context)
+    }
+}
@@ -1,8 +1,10 @@
-use crate::core::{Context, Expr, Stmt};
+use std::borrow::Cow;
+
+use crate::core::{Context, Expr, RuntimeError, Stmt};
 
 impl Expr for u64 {
-    fn exec_expr(&mut self, _context: &Context) -> u64 {
-        *self
+    fn try_exec_expr(&mut self, _context: &mut Context) -> Result<u64, RuntimeError> {
+        Ok(*self)
     }
 }
 
@@ -13,10 +15,10 @@ pub struct When<T: Expr, U: Expr, W: Expr> {
 }
 
 impl<T: Expr, U: Expr, W: Expr> Expr for When<T, U, W> {
-    fn exec_expr(&mut self, context: &Context) -> u64 {
-        match self.condition.exec_expr(context) {
-            0 => self.if_false.exec_expr(context),
-            _ => self.if_true.exec_expr(context),
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        match self.condition.try_exec_expr(context)? {
+            0 => self.if_false.try_exec_expr(context),
+            _ => self.if_true.try_exec_expr(context),
         }
     }
 }
@@ -34,10 +36,11 @@ pub struct Repeat<const N: u32, T: Stmt> {
 }
 
 impl<const N: u32, T: Stmt> Stmt for Repeat<N, T> {
-    fn exec_stmt(&mut self, context: &Context) {
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
         for _ in 1..=N {
-            self.inner.exec_stmt(context);
+            self.inner.try_exec_stmt(context)?;
         }
+        Ok(())
     }
 }
 
@@ -45,13 +48,36 @@ pub fn repeat<const N: u32, T: Stmt>(inner: T) -> Repeat<N, T> {
     Repeat { inner }
 }
 
+/// Like [`Repeat`], but with the number of iterations computed by `count`
+/// every time the loop starts.
+pub struct RepeatBy<T: Expr, U: Stmt> {
+    pub count: T,
+    pub inner: U,
+}
+
+impl<T: Expr, U: Stmt> Stmt for RepeatBy<T, U> {
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        for _ in 0..self.count.try_exec_expr(context)? {
+            self.inner.try_exec_stmt(context)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn repeat_by<T: Expr, U: Stmt>(count: T, inner: U) -> RepeatBy<T, U> {
+    RepeatBy { count, inner }
+}
+
 pub struct Constant {
     name: &'static str,
 }
 
 impl Expr for Constant {
-    fn exec_expr(&mut self, context: &Context) -> u64 {
-        *context.get(self.name).unwrap()
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        context
+            .get(self.name)
+            .copied()
+            .ok_or_else(|| RuntimeError::UndefinedVariable(self.name.to_string()))
     }
 }
 
@@ -59,13 +85,59 @@ pub fn constant(name: &'static str) -> Constant {
     Constant { name }
 }
 
+/// Reads the variable `name` from the context.
+pub struct Read {
+    name: Cow<'static, str>,
+}
+
+impl Expr for Read {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        context
+            .get(&self.name)
+            .copied()
+            .ok_or_else(|| RuntimeError::UndefinedVariable(self.name.to_string()))
+    }
+}
+
+pub fn read(name: impl Into<Cow<'static, str>>) -> Read {
+    Read { name: name.into() }
+}
+
+/// Stores the value of `inner` in the variable `name` of the context, defining it
+/// if needed. Usable both as a statement and as an expression yielding the value.
+pub struct Assign<T: Expr> {
+    name: Cow<'static, str>,
+    inner: T,
+}
+
+impl<T: Expr> Expr for Assign<T> {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let value = self.inner.try_exec_expr(context)?;
+        context.insert(self.name.clone(), value);
+        Ok(value)
+    }
+}
+
+impl<T: Expr> Stmt for Assign<T> {
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        self.try_exec_expr(context).map(|_| ())
+    }
+}
+
+pub fn assign<T: Expr>(name: impl Into<Cow<'static, str>>, inner: T) -> Assign<T> {
+    Assign {
+        name: name.into(),
+        inner,
+    }
+}
+
 pub struct ReadFrom<'a> {
     pub variable: &'a u64,
 }
 
 impl<'a> Expr for ReadFrom<'a> {
-    fn exec_expr(&mut self, _context: &Context) -> u64 {
-        *self.variable
+    fn try_exec_expr(&mut self, _context: &mut Context) -> Result<u64, RuntimeError> {
+        Ok(*self.variable)
     }
 }
 
@@ -79,10 +151,10 @@ pub struct SaveIn<'a, T: Expr> {
 }
 
 impl<'a, T: Expr> Expr for SaveIn<'a, T> {
-    fn exec_expr(&mut self, context: &Context) -> u64 {
-        let value = self.inner.exec_expr(context);
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let value = self.inner.try_exec_expr(context)?;
         *self.destination = value;
-        value
+        Ok(value)
     }
 }
 
@@ -97,15 +169,15 @@ pub struct Volatile<'a, T: Expr> {
 }
 
 impl<'a, T: Expr> Expr for Volatile<'a, T> {
-    fn exec_expr(&mut self, context: &Context) -> u64 {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
         let mut volatile_context = context.clone();
         volatile_context.insert(self.name, *self.destination);
 
-        let value = self.inner.exec_expr(&volatile_context);
+        let value = self.inner.try_exec_expr(&mut volatile_context)?;
 
         *self.destination = value;
 
-        value
+        Ok(value)
     }
 }
 
@@ -119,4 +191,4 @@ pub fn volatile<'a, T: Expr>(
         name,
         inner,
     }
-}
\ No newline at end of file
+}
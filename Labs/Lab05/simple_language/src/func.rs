@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+
+use crate::core::{Context, Expr, RuntimeError};
+
+/// A function which can be stored in a [`Context`] and invoked with [`Call`].
+pub trait Callable {
+    fn params(&self) -> &[&'static str];
+
+    /// Runs the body in `scope`, in which the parameters are already bound.
+    fn call(&mut self, scope: &mut Context) -> Result<u64, RuntimeError>;
+}
+
+/// A sub-program computing `body` from the values of its `N` parameters.
+pub struct Func<const N: usize, T: Expr> {
+    params: [&'static str; N],
+    body: T,
+}
+
+impl<const N: usize, T: Expr> Callable for Func<N, T> {
+    fn params(&self) -> &[&'static str] {
+        &self.params
+    }
+
+    fn call(&mut self, scope: &mut Context) -> Result<u64, RuntimeError> {
+        self.body.try_exec_expr(scope)
+    }
+}
+
+pub fn func<const N: usize, T: Expr>(params: [&'static str; N], body: T) -> Func<N, T> {
+    Func { params, body }
+}
+
+/// Calls the function `name` defined in the context. Arguments are evaluated in the
+/// caller's context and bound to the parameters in a child scope, which sees the
+/// caller's names where they are not shadowed by a parameter.
+pub struct Call {
+    name: Cow<'static, str>,
+    args: Vec<Box<dyn Expr>>,
+}
+
+impl Expr for Call {
+    fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+        let function = context
+            .function(&self.name)
+            .ok_or_else(|| RuntimeError::UndefinedFunction(self.name.to_string()))?;
+
+        let args = self
+            .args
+            .iter_mut()
+            .map(|arg| arg.try_exec_expr(context))
+            .collect::<Result<Vec<u64>, _>>()?;
+
+        // a function currently running is already borrowed
+        let mut function = function
+            .try_borrow_mut()
+            .map_err(|_| RuntimeError::Recursion(self.name.to_string()))?;
+
+        let params = function.params();
+        if params.len() != args.len() {
+            return Err(RuntimeError::ArgumentCount {
+                function: self.name.to_string(),
+                expected: params.len(),
+                found: args.len(),
+            });
+        }
+
+        let mut scope = context.clone();
+        for (param, arg) in params.iter().zip(args) {
+            scope.insert(*param, arg);
+        }
+
+        function.call(&mut scope)
+    }
+}
+
+pub fn call(name: impl Into<Cow<'static, str>>, args: Vec<Box<dyn Expr>>) -> Call {
+    Call {
+        name: name.into(),
+        args,
+    }
+}
+
+/// Builds a [`Call`] from any expressions: `call!("f", constant("x"), 1u64)`.
+#[macro_export]
+macro_rules! call {
+    ($name:expr $(, $arg:expr)* $(,)?) => {
+        $crate::call(
+            $name,
+            vec![$(Box::new($arg) as Box<dyn $crate::Expr>),*],
+        )
+    };
+}
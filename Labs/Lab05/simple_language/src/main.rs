@@ -1,69 +1,134 @@
+mod arith;
 mod core;
 mod expr;
+mod func;
+mod parser;
 mod stmt;
 
-pub use core::{Context, Expr, Stmt};
+pub use arith::*;
+pub use core::{Context, Expr, RuntimeError, Stmt};
 pub use expr::*;
+pub use func::*;
+pub use parser::{ParseError, Position, RunError, parse, run};
 pub use stmt::*;
 
 fn main() {
-    let ctx1 = Context::from([("x", 5), ("y", 10)]);
+    if let Some(path) = std::env::args().nth(1) {
+        run_file(&path);
+        return;
+    }
+
+    let mut ctx1 = Context::from([("x", 5), ("y", 10)]);
     let mut program1 = seq(print(1u64), print(2u64));
     println!("Running: seq(print(1), print(2))");
-    program1.exec_stmt(&ctx1);
+    program1.exec_stmt(&mut ctx1);
 
     let mut program2 = repeat::<3, _>(print(constant("x")));
     println!("\nRunning: repeat::<3, _>(print(constant(\"x\"))) (gdzie x=5)");
-    program2.exec_stmt(&ctx1);
+    program2.exec_stmt(&mut ctx1);
 
     let mut program3 = seq(print(100u64), nothing());
     println!("\nRunning: seq(print(100), nothing())");
-    program3.exec_stmt(&ctx1);
+    program3.exec_stmt(&mut ctx1);
+
+    let mut program4 = print(add(mul(constant("x"), 2u64), constant("y")));
+    println!("\nRunning: print(add(mul(constant(\"x\"), 2), constant(\"y\"))) (gdzie x=5, y=10)");
+    program4.exec_stmt(&mut ctx1);
+
+    let mut program5 = block![print(1u64), print(2u64), print(3u64), nothing()];
+    println!("\nRunning: block![print(1), print(2), print(3), nothing()]");
+    program5.exec_stmt(&mut ctx1);
+
+    let mut ctx4 = Context::from([("x", 6)]);
+    ctx4.define("square", func(["n"], mul(constant("n"), constant("n"))));
+    ctx4.define(
+        "sum_of_squares",
+        func(
+            ["a", "b"],
+            add(
+                call!("square", constant("a")),
+                call!("square", constant("b")),
+            ),
+        ),
+    );
+    let mut program6 = print(call!("sum_of_squares", constant("x"), 2u64));
+    println!("\nRunning: print(call!(\"sum_of_squares\", constant(\"x\"), 2)) (gdzie x=6)");
+    program6.exec_stmt(&mut ctx4);
+
+    let mut ctx5 = Context::from([("c", 3)]);
+    let mut program7 = block![
+        assign("x", 0u64),
+        repeat::<10, _>(assign("x", add(read("x"), constant("c")))),
+        print(read("x")),
+    ];
+    println!("\nRunning: x = 0; repeat 10 {{ x = x + c; }} print x (gdzie c=3)");
+    program7.exec_stmt(&mut ctx5);
+
+    let source = "x = 0; repeat 10 { x = x + c; } print x;";
+    println!("\nRunning source: {source} (gdzie c=3)");
+    if let Err(error) = run(source, &mut Context::from([("c", 3)])) {
+        println!("Error: {error}");
+    }
 
-    let ctx2 = Context::from([("is_active", 1), ("counter", 4)]);
+    let mut ctx2 = Context::from([("is_active", 1), ("counter", 4)]);
     let mut a: u64 = 0;
     let b: u64 = 42;
     let mut expr1 = when(constant("is_active"), 111u64, 222u64);
-    let val1 = expr1.exec_expr(&ctx2);
+    let val1 = expr1.exec_expr(&mut ctx2);
     println!("Result 'when(1, 111, 222)': {val1}");
 
     let mut expr2 = save_in(&mut a, when(constant("counter"), 99u64, 88u64));
     println!("\nRunning: save_in(&mut a, when(constant(\"counter\"), 99, 88))");
-    let val2 = expr2.exec_expr(&ctx2);
+    let val2 = expr2.exec_expr(&mut ctx2);
     println!("Result 'save_in': {val2}");
     println!("New value 'a': {a}");
 
     let mut expr3 = read_from(&b);
-    let val3 = expr3.exec_expr(&ctx2);
+    let val3 = expr3.exec_expr(&mut ctx2);
     println!("\nResult 'read_from(&b)' (b=42): {val3}");
 
     let mut stmt4 = print(read_from(&a));
     println!("\nRunning: print(read_from(&a)) (a=99)");
-    stmt4.exec_stmt(&ctx2);
+    stmt4.exec_stmt(&mut ctx2);
 
-    let ctx3 = Context::from([("y", 10)]);
+    let mut ctx3 = Context::from([("y", 10)]);
     let mut v: u64 = 0;
 
     let mut expr_v1 = volatile(&mut v, "y", when(constant("y"), 7u64, 8u64));
 
     println!("Running volatile (v=0, ctx[y]=10), shadowing 'y' with value 'v'");
-    let res1 = expr_v1.exec_expr(&ctx3);
+    let res1 = expr_v1.exec_expr(&mut ctx3);
     println!("Result volatile 1: {res1}");
     println!("New value 'v': {v}");
 
     let mut expr_v2 = volatile(&mut v, "y", when(constant("y"), 7u64, 8u64));
 
     println!("\nRunning volatile again (v=8, ctx[y]=10), shadowing 'y' with value 'v'");
-    let res2 = expr_v2.exec_expr(&ctx3);
+    let res2 = expr_v2.exec_expr(&mut ctx3);
     println!("Result volatile 2: {res2}");
     println!("New value 'v': {v}");
 }
 
+/// Interprets the program stored in the file at `path`.
+fn run_file(path: &str) {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("cannot read {path}: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(error) = run(&source, &mut Context::new()) {
+        eprintln!("{path}: {error}");
+        std::process::exit(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::cell::RefCell;
-    use std::collections::HashMap;
     use std::rc::Rc;
 
     // Ta struktura zapamiętuje `label` dla każdego wywałania siebie i tych,
@@ -73,8 +138,9 @@ mod tests {
         log: Rc<RefCell<Vec<&'static str>>>,
     }
     impl Stmt for Recorder {
-        fn exec_stmt(&mut self, _context: &Context) {
+        fn try_exec_stmt(&mut self, _context: &mut Context) -> Result<(), RuntimeError> {
             self.log.borrow_mut().push(self.label);
+            Ok(())
         }
     }
 
@@ -84,35 +150,65 @@ mod tests {
         value: u64,
     }
     impl Expr for CounterExpr {
-        fn exec_expr(&mut self, _context: &Context) -> u64 {
+        fn try_exec_expr(&mut self, _context: &mut Context) -> Result<u64, RuntimeError> {
             *self.calls.borrow_mut() += 1;
-            self.value
+            Ok(self.value)
         }
     }
 
     #[test]
     fn print_struct_executes_inner_once() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let calls = Rc::new(RefCell::new(0u32));
         let ce = CounterExpr {
             calls: calls.clone(),
             value: 123,
         };
         let mut p = print(ce);
-        p.exec_stmt(&ctx);
+        p.exec_stmt(&mut ctx);
         assert_eq!(*calls.borrow(), 1);
     }
 
+    fn captured(context: Context) -> (Context, Rc<RefCell<Vec<u8>>>) {
+        let output = Rc::new(RefCell::new(Vec::new()));
+        (context.with_output(output.clone()), output)
+    }
+
+    #[test]
+    fn print_struct_writes_to_context_output() {
+        let (mut ctx, output) = captured(Context::from([("x", 5)]));
+        let mut program = seq(print(constant("x")), print(add(constant("x"), 1u64)));
+        program.exec_stmt(&mut ctx);
+        assert_eq!(&*output.borrow(), b"5\n6\n");
+    }
+
+    #[test]
+    fn print_inside_volatile_shares_output() {
+        struct PrintInside<T: Stmt>(T);
+        impl<T: Stmt> Expr for PrintInside<T> {
+            fn try_exec_expr(&mut self, context: &mut Context) -> Result<u64, RuntimeError> {
+                self.0.try_exec_stmt(context)?;
+                Ok(0)
+            }
+        }
+
+        let (mut ctx, output) = captured(Context::from([("y", 1)]));
+        let mut a: u64 = 3;
+        let mut program = volatile(&mut a, "y", PrintInside(print(constant("y"))));
+        program.exec_expr(&mut ctx);
+        assert_eq!(&*output.borrow(), b"3\n");
+    }
+
     #[test]
     fn nothing_struct_does_nothing() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let mut n = Nothing;
-        n.exec_stmt(&ctx);
+        n.exec_stmt(&mut ctx);
     }
 
     #[test]
     fn seq_struct_executes_in_order() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let r1 = Recorder {
             label: "first",
@@ -123,13 +219,13 @@ mod tests {
             log: log.clone(),
         };
         let mut s = seq(r1, r2);
-        s.exec_stmt(&ctx);
+        s.exec_stmt(&mut ctx);
         assert_eq!(&*log.borrow(), &["first", "second"]);
     }
 
     #[test]
     fn seq_shorten_1_discards_trailing_nothing_and_returns_first() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let r = Recorder {
             label: "A",
@@ -138,13 +234,13 @@ mod tests {
         let s = seq(r, nothing());
         // shorten_1 should return the first statement (Recorder)
         let mut first_only = s.shorten_1();
-        first_only.exec_stmt(&ctx);
+        first_only.exec_stmt(&mut ctx);
         assert_eq!(&*log.borrow(), &["A"]);
     }
 
     #[test]
     fn seq_shorten_2_discards_leading_nothing_and_returns_second() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let r = Recorder {
             label: "B",
@@ -153,7 +249,7 @@ mod tests {
         let s = seq(nothing(), r);
         // shorten_2 should return the second statement (Recorder)
         let mut second_only = s.shorten_2();
-        second_only.exec_stmt(&ctx);
+        second_only.exec_stmt(&mut ctx);
         assert_eq!(&*log.borrow(), &["B"]);
     }
 
@@ -162,18 +258,132 @@ mod tests {
         let _collapsed: Nothing = seq(nothing(), nothing()).collapse();
     }
 
+    #[test]
+    fn block_macro_executes_in_order() {
+        let mut ctx = Context::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let recorder = |label| Recorder {
+            label,
+            log: log.clone(),
+        };
+        let mut program = block![
+            recorder("first"),
+            repeat::<2, _>(recorder("second")),
+            recorder("third"),
+            recorder("fourth"),
+        ];
+        program.exec_stmt(&mut ctx);
+        assert_eq!(
+            &*log.borrow(),
+            &["first", "second", "second", "third", "fourth"]
+        );
+    }
+
+    #[test]
+    fn block_macro_small_blocks() {
+        let _empty: Nothing = block![];
+
+        let (mut ctx, output) = captured(Context::new());
+        let mut single = block![print(7u64)];
+        single.exec_stmt(&mut ctx);
+        assert_eq!(&*output.borrow(), b"7\n");
+    }
+
+    #[test]
+    fn call_binds_arguments_in_child_scope() {
+        let mut ctx = Context::from([("x", 3), ("n", 100)]);
+        ctx.define("square", func(["n"], mul(constant("n"), constant("n"))));
+        ctx.define("plus_x", func(["n"], add(constant("n"), constant("x"))));
+
+        assert_eq!(call!("square", constant("x")).exec_expr(&mut ctx), 9);
+        assert_eq!(call!("plus_x", 4u64).exec_expr(&mut ctx), 7);
+        assert_eq!(
+            call!("square", call!("square", constant("x"))).exec_expr(&mut ctx),
+            81
+        );
+        // the parameter only shadows `n` inside the call
+        assert_eq!(constant("n").exec_expr(&mut ctx), 100);
+    }
+
+    #[test]
+    fn call_function_without_params() {
+        let mut ctx = Context::new();
+        ctx.define("answer", func([], 42u64));
+        assert_eq!(call!("answer").exec_expr(&mut ctx), 42);
+    }
+
+    #[test]
+    #[should_panic(expected = "takes 2 arguments")]
+    fn call_checks_arity() {
+        let mut ctx = Context::new();
+        ctx.define("add", func(["a", "b"], add(constant("a"), constant("b"))));
+        call!("add", 1u64).exec_expr(&mut ctx);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not defined")]
+    fn call_undefined_function_panics() {
+        call!("missing").exec_expr(&mut Context::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot call itself")]
+    fn call_rejects_recursion() {
+        let mut ctx = Context::new();
+        ctx.define("loop", func([], call!("loop")));
+        call!("loop").exec_expr(&mut ctx);
+    }
+
+    #[test]
+    fn assign_and_read_share_variables() {
+        let (mut ctx, output) = captured(Context::from([("step", 2)]));
+        let mut program = block![
+            assign("x", 1u64),
+            assign("y", read("x")),
+            repeat::<3, _>(assign("x", add(read("x"), constant("step")))),
+            assign("y", add(read("y"), read("x"))),
+            print(read("x")),
+            print(read("y")),
+        ];
+        program.exec_stmt(&mut ctx);
+        assert_eq!(&*output.borrow(), b"7\n8\n");
+        assert_eq!(ctx.get("x"), Some(&7));
+    }
+
+    #[test]
+    fn assign_expr_returns_value() {
+        let mut ctx = Context::new();
+        let mut expr = when(assign("flag", 0u64), 1u64, 2u64);
+        assert_eq!(expr.exec_expr(&mut ctx), 2);
+        assert_eq!(read("flag").exec_expr(&mut ctx), 0);
+    }
+
+    #[test]
+    fn assign_inside_call_stays_local() {
+        let mut ctx = Context::from([("x", 1)]);
+        ctx.define("clobber", func([], assign("x", 99u64)));
+        assert_eq!(call!("clobber").exec_expr(&mut ctx), 99);
+        assert_eq!(read("x").exec_expr(&mut ctx), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "variable `x` is not defined")]
+    fn read_undefined_variable_panics() {
+        read("x").exec_expr(&mut Context::new());
+    }
+
     #[test]
     fn when_struct_branches() {
-        let ctx = HashMap::new();
+        let mut ctx = Context::new();
         let mut expr0 = when(0, 7u64, 8u64);
         let mut expr1 = when(1, 7u64, 8u64);
-        assert_eq!(expr0.exec_expr(&ctx), 8);
-        assert_eq!(expr1.exec_expr(&ctx), 7);
+        assert_eq!(expr0.exec_expr(&mut ctx), 8);
+        assert_eq!(expr1.exec_expr(&mut ctx), 7);
     }
 
     #[test]
     fn repeat_struct_runs_n_times() {
-        let ctx = HashMap::new();
+        let mut ctx = Context::new();
         let log = Rc::new(RefCell::new(Vec::new()));
         let r = Recorder {
             label: "tick",
@@ -181,70 +391,294 @@ mod tests {
         };
 
         let mut rep = repeat::<3, _>(r);
-        rep.exec_stmt(&ctx);
+        rep.exec_stmt(&mut ctx);
         assert_eq!(&*log.borrow(), &["tick", "tick", "tick"]);
     }
 
     #[test]
     fn constant_struct_reads_value() {
-        let ctx = HashMap::from([("k", 123u64)]);
+        let mut ctx = Context::from([("k", 123u64)]);
         let mut program = constant("k");
-        assert_eq!(program.exec_expr(&ctx), 123);
+        assert_eq!(program.exec_expr(&mut ctx), 123);
     }
 
     #[test]
     fn readfrom_struct_returns_value() {
-        let ctx = HashMap::new();
+        let mut ctx = Context::new();
         let x: u64 = 99;
         let mut program = read_from(&x);
-        assert_eq!(program.exec_expr(&ctx), 99);
+        assert_eq!(program.exec_expr(&mut ctx), 99);
     }
 
     #[test]
     fn savein_struct_writes_and_returns() {
-        let ctx = HashMap::new();
+        let mut ctx = Context::new();
         let mut dst: u64 = 0;
         let mut program = save_in(&mut dst, 123u64);
-        let out = program.exec_expr(&ctx);
+        let out = program.exec_expr(&mut ctx);
         assert_eq!(dst, 123);
         assert_eq!(out, 123);
     }
 
     #[test]
     fn volatile_struct_shadows_and_updates() {
-        let ctx = HashMap::from([("y", 10)]);
+        let mut ctx = Context::from([("y", 10)]);
         let mut a: u64 = 0;
 
         let mut v1 = volatile(&mut a, "y", when(constant("y"), 7u64, 8u64));
-        let out1 = v1.exec_expr(&ctx);
+        let out1 = v1.exec_expr(&mut ctx);
         assert_eq!(out1, 8);
         assert_eq!(a, 8);
 
         let mut v2 = volatile(&mut a, "y", when(constant("y"), 7u64, 8u64));
-        let out2 = v2.exec_expr(&ctx);
+        let out2 = v2.exec_expr(&mut ctx);
         assert_eq!(out2, 7);
         assert_eq!(a, 7);
     }
 
+    #[test]
+    fn arithmetic_structs_compute() {
+        let mut ctx = Context::from([("x", 7u64), ("y", 2u64)]);
+        assert_eq!(add(constant("x"), constant("y")).exec_expr(&mut ctx), 9);
+        assert_eq!(sub(constant("x"), constant("y")).exec_expr(&mut ctx), 5);
+        assert_eq!(mul(constant("x"), constant("y")).exec_expr(&mut ctx), 14);
+        assert_eq!(div(constant("x"), constant("y")).exec_expr(&mut ctx), 3);
+        assert_eq!(
+            checked_div(constant("x"), constant("y")).exec_expr(&mut ctx),
+            3
+        );
+    }
+
+    #[test]
+    fn arithmetic_structs_wrap_by_default() {
+        let mut ctx = Context::new();
+        assert_eq!(add(u64::MAX, 2u64).exec_expr(&mut ctx), 1);
+        assert_eq!(sub(0u64, 1u64).exec_expr(&mut ctx), u64::MAX);
+        assert_eq!(mul(1u64 << 63, 2u64).exec_expr(&mut ctx), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn checked_add_panics_on_overflow() {
+        checked_add(u64::MAX, 1u64).exec_expr(&mut Context::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "underflows u64")]
+    fn checked_sub_panics_on_underflow() {
+        checked_sub(0u64, 1u64).exec_expr(&mut Context::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn checked_mul_panics_on_overflow() {
+        checked_mul(u64::MAX, 2u64).exec_expr(&mut Context::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_panics_on_zero() {
+        div(1u64, 0u64).exec_expr(&mut Context::new());
+    }
+
+    #[test]
+    fn parse_and_run_program() {
+        let (mut ctx, output) = captured(Context::from([("c", 3)]));
+        let source = "
+            x = 0;
+            repeat 10 { x = x + c; }  # x = 30
+            print x;
+            print when(x - 30, 1, (x + 2) * 2 / 4);
+            { y = x; print y; }
+        ";
+        run(source, &mut ctx).unwrap();
+        assert_eq!(&*output.borrow(), b"30\n16\n30\n");
+        assert_eq!(ctx.get("y"), Some(&30));
+    }
+
+    #[test]
+    fn parse_calls_and_nested_repeat() {
+        let (mut ctx, output) = captured(Context::new());
+        ctx.define("square", func(["n"], mul(constant("n"), constant("n"))));
+        let source = "n = 0; repeat 2 { repeat 3 { n = n + 1; } } print square(n) - 1;";
+        run(source, &mut ctx).unwrap();
+        assert_eq!(&*output.borrow(), b"35\n");
+    }
+
+    #[test]
+    fn parse_precedence_is_left_associative() {
+        let (mut ctx, output) = captured(Context::new());
+        run("print 20 - 5 - 3 * 2; print 100 / 10 / 5;", &mut ctx).unwrap();
+        assert_eq!(&*output.borrow(), b"9\n2\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let at = |line, column| Position { line, column };
+        assert_eq!(
+            parse("x = 1").err(),
+            Some(ParseError::UnexpectedEnd { expected: "';'" })
+        );
+        assert_eq!(
+            parse("{ x = 1;").err(),
+            Some(ParseError::UnexpectedEnd { expected: "'}'" })
+        );
+        assert_eq!(
+            parse("x = $;").err(),
+            Some(ParseError::UnexpectedCharacter {
+                character: '$',
+                at: at(1, 5),
+            })
+        );
+        assert_eq!(
+            parse("x = 99999999999999999999;").err(),
+            Some(ParseError::InvalidNumber {
+                literal: "99999999999999999999".into(),
+                at: at(1, 5),
+            })
+        );
+        assert_eq!(
+            parse("repeat 2 x = 1;").err(),
+            Some(ParseError::Expected {
+                expected: "'{'",
+                found: "x".into(),
+                at: at(1, 10),
+            })
+        );
+        assert_eq!(
+            parse("print when(1, 2);").err(),
+            Some(ParseError::Expected {
+                expected: "','",
+                found: ")".into(),
+                at: at(1, 16),
+            })
+        );
+        assert_eq!(
+            parse("x = 1;  # comment\nprint x\n}").err(),
+            Some(ParseError::Expected {
+                expected: "';'",
+                found: "}".into(),
+                at: at(3, 1),
+            })
+        );
+        assert_eq!(
+            parse("print = 1;").err(),
+            Some(ParseError::Expected {
+                expected: "an expression",
+                found: "=".into(),
+                at: at(1, 7),
+            })
+        );
+    }
+
+    #[test]
+    fn run_reports_runtime_errors() {
+        let (mut ctx, output) = captured(Context::new());
+        assert_eq!(
+            run("print y;", &mut ctx),
+            Err(RunError::Runtime(RuntimeError::UndefinedVariable(
+                "y".into()
+            )))
+        );
+        assert_eq!(
+            run("x = 5; print x; print x / 0; print 1;", &mut ctx),
+            Err(RunError::Runtime(RuntimeError::DivisionByZero))
+        );
+        // the statements before the failure have run
+        assert_eq!(&*output.borrow(), b"5\n");
+        assert_eq!(ctx.get("x"), Some(&5));
+
+        assert_eq!(
+            run("print missing(1);", &mut ctx),
+            Err(RunError::Runtime(RuntimeError::UndefinedFunction(
+                "missing".into()
+            )))
+        );
+
+        ctx.define("loop", func([], call!("loop")));
+        assert_eq!(
+            run("print loop();", &mut ctx),
+            Err(RunError::Runtime(RuntimeError::Recursion("loop".into())))
+        );
+
+        ctx.define("square", func(["n"], mul(constant("n"), constant("n"))));
+        assert_eq!(
+            run("print square(1, 2);", &mut ctx),
+            Err(RunError::Runtime(RuntimeError::ArgumentCount {
+                function: "square".into(),
+                expected: 1,
+                found: 2,
+            }))
+        );
+
+        assert_eq!(
+            run("print 1 +;", &mut ctx).map_err(|error| error.to_string()),
+            Err("syntax error: 1:10: expected an expression, found ';'".into())
+        );
+        assert_eq!(
+            run("print 1 / 0;", &mut ctx).map_err(|error| error.to_string()),
+            Err("runtime error: division by zero".into())
+        );
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_errors() {
+        let mut ctx = Context::new();
+        assert_eq!(
+            checked_add(u64::MAX, 1u64).try_exec_expr(&mut ctx),
+            Err(RuntimeError::Overflow {
+                lhs: u64::MAX,
+                operator: '+',
+                rhs: 1,
+            })
+        );
+        assert_eq!(
+            checked_sub(0u64, 1u64).try_exec_expr(&mut ctx),
+            Err(RuntimeError::Overflow {
+                lhs: 0,
+                operator: '-',
+                rhs: 1,
+            })
+        );
+        assert_eq!(
+            checked_div(1u64, 0u64).try_exec_expr(&mut ctx),
+            Err(RuntimeError::DivisionByZero)
+        );
+        assert_eq!(
+            constant("c").try_exec_expr(&mut ctx),
+            Err(RuntimeError::UndefinedVariable("c".into()))
+        );
+    }
+
+    #[test]
+    fn parsed_names_are_owned() {
+        let (mut ctx, output) = captured(Context::new());
+        let name = String::from("local");
+        let source = format!("{name} = 7; print {name} * {name};");
+        run(&source, &mut ctx).unwrap();
+        assert_eq!(&*output.borrow(), b"49\n");
+        assert_eq!(ctx.get(&name), Some(&7));
+    }
+
     // Nesting tests
     #[test]
     fn nesting_when_inside_when_structs() {
-        let ctx1 = HashMap::from([("x", 1), ("y", 1)]);
-        let ctx2 = HashMap::from([("x", 1), ("y", 0)]);
-        let ctx3 = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx1 = Context::from([("x", 1), ("y", 1)]);
+        let mut ctx2 = Context::from([("x", 1), ("y", 0)]);
+        let mut ctx3 = Context::from([("x", 0), ("y", 0)]);
         let mut nested = when(
             when(constant("y"), 1u64, 0u64),
             10u64,
             when(constant("x"), 20u64, 30u64),
         );
-        assert_eq!(nested.exec_expr(&ctx1), 10);
-        assert_eq!(nested.exec_expr(&ctx2), 20);
-        assert_eq!(nested.exec_expr(&ctx3), 30);
+        assert_eq!(nested.exec_expr(&mut ctx1), 10);
+        assert_eq!(nested.exec_expr(&mut ctx2), 20);
+        assert_eq!(nested.exec_expr(&mut ctx3), 30);
     }
 
     #[test]
     fn nesting_seq_repeat_order_structs() {
-        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut ctx = Context::from([("x", 0), ("y", 0)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let r_a = Recorder {
             label: "A",
@@ -255,17 +689,17 @@ mod tests {
             log: log.clone(),
         };
         let mut program = seq(repeat::<2, _>(r_a), repeat::<3, _>(r_b));
-        program.exec_stmt(&ctx);
+        program.exec_stmt(&mut ctx);
         assert_eq!(&*log.borrow(), &["A", "A", "B", "B", "B"]);
     }
 
     #[test]
     fn nesting_savein_then_volatile_structs() {
-        let ctx = HashMap::from([("y", 0)]);
+        let mut ctx = Context::from([("y", 0)]);
         let mut a: u64 = 0;
         let mut b: u64 = 0;
         let mut set_a = save_in(&mut a, 5u64);
-        assert_eq!(set_a.exec_expr(&ctx), 5);
+        assert_eq!(set_a.exec_expr(&mut ctx), 5);
         let mut expr = save_in(
             &mut b,
             when(
@@ -274,7 +708,7 @@ mod tests {
                 10u64,
             ),
         );
-        let out = expr.exec_expr(&ctx);
+        let out = expr.exec_expr(&mut ctx);
         assert_eq!(out, 9);
         assert_eq!(b, 9);
         assert_eq!(a, 1);
@@ -283,7 +717,7 @@ mod tests {
     // Two integration tests that exercise everything
     #[test]
     fn integration_full_flow_1() {
-        let ctx = HashMap::from([("x", 0), ("y", 10)]);
+        let mut ctx = Context::from([("x", 0), ("y", 10)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let mut a: u64 = 0;
         let b: u64 = 0;
@@ -293,13 +727,13 @@ mod tests {
             print(when(constant("y"), 1u64, 2u64)),
             print(when(constant("x"), 1u64, 2u64)),
         );
-        part1.exec_stmt(&ctx);
+        part1.exec_stmt(&mut ctx);
 
         // part2: save into a, then read a in a separate step to avoid borrow conflicts
         let mut part2a = print(save_in(&mut a, when(constant("y"), 7u64, 8u64)));
-        part2a.exec_stmt(&ctx);
+        part2a.exec_stmt(&mut ctx);
         let mut part2b = print(read_from(&a));
-        part2b.exec_stmt(&ctx);
+        part2b.exec_stmt(&mut ctx);
 
         // part3
         let mut part3 = seq(
@@ -310,7 +744,7 @@ mod tests {
             // Use `a` (currently 7) to shadow `y`, so branch -> 100
             print(volatile(&mut a, "y", when(constant("y"), 100u64, 200u64))),
         );
-        part3.exec_stmt(&ctx);
+        part3.exec_stmt(&mut ctx);
 
         assert_eq!(a, 100);
         assert_eq!(b, 0);
@@ -319,13 +753,13 @@ mod tests {
 
     #[test]
     fn integration_full_flow_2() {
-        let ctx = HashMap::from([("x", 1), ("y", 0)]);
+        let mut ctx = Context::from([("x", 1), ("y", 0)]);
         let log = Rc::new(RefCell::new(Vec::new()));
         let mut a: u64 = 0;
         let mut b: u64 = 0;
 
         let mut a_set = save_in(&mut a, when(constant("x"), 9u64, 10u64));
-        assert_eq!(a_set.exec_expr(&ctx), 9);
+        assert_eq!(a_set.exec_expr(&mut ctx), 9);
         let mut b_set = save_in(
             &mut b,
             when(
@@ -334,7 +768,7 @@ mod tests {
                 456u64,
             ),
         );
-        assert_eq!(b_set.exec_expr(&ctx), 123);
+        assert_eq!(b_set.exec_expr(&mut ctx), 123);
 
         let mut program = seq(
             repeat::<2, _>(Recorder {
@@ -346,7 +780,7 @@ mod tests {
                 log: log.clone(),
             }),
         );
-        program.exec_stmt(&ctx);
+        program.exec_stmt(&mut ctx);
 
         assert_eq!(a, 1);
         assert_eq!(b, 123);
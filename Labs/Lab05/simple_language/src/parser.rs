@@ -0,0 +1,402 @@
+//! Text front-end turning source code into the statements and expressions of the
+//! crate. The grammar, with `#` starting a comment running to the end of the line:
+//!
+//! ```text
+//! program   = statement*
+//! statement = "print" expr ";" | "repeat" expr block | block | name "=" expr ";"
+//! block     = "{" statement* "}"
+//! expr      = term (("+" | "-") term)*
+//! term      = factor (("*" | "/") factor)*
+//! factor    = number | name | name "(" (expr ("," expr)*)? ")"
+//!           | "when" "(" expr "," expr "," expr ")" | "(" expr ")"
+//! ```
+
+use std::{fmt::Display, iter::Peekable, str::Chars};
+
+use crate::{
+    add, assign, call,
+    core::{Context, Expr, RuntimeError, Stmt},
+    div, mul, print, read, repeat_by, sub, when,
+};
+
+/// Location in the source, with lines and columns counted from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedCharacter {
+        character: char,
+        at: Position,
+    },
+    InvalidNumber {
+        literal: String,
+        at: Position,
+    },
+    Expected {
+        expected: &'static str,
+        found: String,
+        at: Position,
+    },
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedCharacter { character, at } => {
+                write!(f, "{at}: unexpected character '{character}'")
+            }
+            Self::InvalidNumber { literal, at } => write!(f, "{at}: invalid number '{literal}'"),
+            Self::Expected {
+                expected,
+                found,
+                at,
+            } => write!(f, "{at}: expected {expected}, found '{found}'"),
+            Self::UnexpectedEnd { expected } => {
+                write!(f, "expected {expected}, found the end of input")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why [`run`] failed: the program could not be parsed, or stopped part-way.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RunError {
+    Parse(ParseError),
+    Runtime(RuntimeError),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "syntax error: {error}"),
+            Self::Runtime(error) => write!(f, "runtime error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ParseError> for RunError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<RuntimeError> for RunError {
+    fn from(error: RuntimeError) -> Self {
+        Self::Runtime(error)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(u64),
+    Name(String),
+    Print,
+    Repeat,
+    When,
+    /// One of `+ - * / = , ; ( ) { }`.
+    Symbol(char),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Print => write!(f, "print"),
+            Self::Repeat => write!(f, "repeat"),
+            Self::When => write!(f, "when"),
+            Self::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+/// Splits the source into tokens on demand, keeping track of where they start.
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    position: Position,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            position: Position { line: 1, column: 1 },
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn take_while(&mut self, predicate: fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some(c) = self.chars.next_if(|&c| predicate(c)) {
+            self.position.column += 1;
+            taken.push(c);
+        }
+        taken
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == '#' {
+                self.take_while(|c| c != '\n');
+            } else if c.is_whitespace() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, Position)>, ParseError> {
+        self.skip_whitespace_and_comments();
+        let at = self.position;
+        let Some(&c) = self.chars.peek() else {
+            return Ok(None);
+        };
+
+        let token = if c.is_ascii_digit() {
+            let literal = self.take_while(|c| c.is_ascii_digit());
+            match literal.parse() {
+                Ok(number) => Token::Number(number),
+                Err(_) => return Err(ParseError::InvalidNumber { literal, at }),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            match self.take_while(|c| c.is_alphanumeric() || c == '_') {
+                word if word == "print" => Token::Print,
+                word if word == "repeat" => Token::Repeat,
+                word if word == "when" => Token::When,
+                name => Token::Name(name),
+            }
+        } else if "+-*/=,;(){}".contains(c) {
+            self.bump();
+            Token::Symbol(c)
+        } else {
+            return Err(ParseError::UnexpectedCharacter { character: c, at });
+        };
+
+        Ok(Some((token, at)))
+    }
+}
+
+/// Recursive descent parser with one token of lookahead, one method per rule of
+/// the grammar.
+struct Parser<'a> {
+    lexer: Lexer<'a>,
+    current: Option<(Token, Position)>,
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> ParseResult<Self> {
+        let mut lexer = Lexer::new(source);
+        let current = lexer.next_token()?;
+        Ok(Self { lexer, current })
+    }
+
+    fn advance(&mut self) -> ParseResult<Option<(Token, Position)>> {
+        let next = self.lexer.next_token()?;
+        Ok(std::mem::replace(&mut self.current, next))
+    }
+
+    fn at_symbol(&self, symbol: char) -> bool {
+        matches!(&self.current, Some((Token::Symbol(c), _)) if *c == symbol)
+    }
+
+    /// Consumes the current token if it is `symbol`.
+    fn eat(&mut self, symbol: char) -> ParseResult<bool> {
+        if !self.at_symbol(symbol) {
+            return Ok(false);
+        }
+        self.advance()?;
+        Ok(true)
+    }
+
+    fn expect(&mut self, symbol: char, expected: &'static str) -> ParseResult<()> {
+        if self.eat(symbol)? {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Reports the current token as not being what the grammar allows here.
+    fn error(&self, expected: &'static str) -> ParseError {
+        match &self.current {
+            Some((token, at)) => ParseError::Expected {
+                expected,
+                found: token.to_string(),
+                at: *at,
+            },
+            None => ParseError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn program(&mut self) -> ParseResult<Vec<Box<dyn Stmt>>> {
+        let mut statements = Vec::new();
+        while self.current.is_some() {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn block(&mut self) -> ParseResult<Vec<Box<dyn Stmt>>> {
+        self.expect('{', "'{'")?;
+        let mut statements = Vec::new();
+        while !self.eat('}')? {
+            if self.current.is_none() {
+                return Err(self.error("'}'"));
+            }
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> ParseResult<Box<dyn Stmt>> {
+        if self.at_symbol('{') {
+            return Ok(Box::new(self.block()?));
+        }
+
+        match self.current.clone() {
+            Some((Token::Print, _)) => {
+                self.advance()?;
+                let value = self.expr()?;
+                self.expect(';', "';'")?;
+                Ok(Box::new(print(value)))
+            }
+            Some((Token::Repeat, _)) => {
+                self.advance()?;
+                let count = self.expr()?;
+                let body = self.block()?;
+                Ok(Box::new(repeat_by(count, body)))
+            }
+            Some((Token::Name(name), _)) => {
+                self.advance()?;
+                self.expect('=', "'='")?;
+                let value = self.expr()?;
+                self.expect(';', "';'")?;
+                Ok(Box::new(assign(name, value)))
+            }
+            _ => Err(self.error("a statement")),
+        }
+    }
+
+    fn expr(&mut self) -> ParseResult<Box<dyn Expr>> {
+        let mut lhs = self.term()?;
+        loop {
+            lhs = if self.eat('+')? {
+                Box::new(add(lhs, self.term()?))
+            } else if self.eat('-')? {
+                Box::new(sub(lhs, self.term()?))
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn term(&mut self) -> ParseResult<Box<dyn Expr>> {
+        let mut lhs = self.factor()?;
+        loop {
+            lhs = if self.eat('*')? {
+                Box::new(mul(lhs, self.factor()?))
+            } else if self.eat('/')? {
+                Box::new(div(lhs, self.factor()?))
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn factor(&mut self) -> ParseResult<Box<dyn Expr>> {
+        if self.eat('(')? {
+            let inner = self.expr()?;
+            self.expect(')', "')'")?;
+            return Ok(inner);
+        }
+
+        match self.current.clone() {
+            Some((Token::Number(number), _)) => {
+                self.advance()?;
+                Ok(Box::new(number))
+            }
+            Some((Token::When, _)) => {
+                self.advance()?;
+                self.expect('(', "'('")?;
+                let condition = self.expr()?;
+                self.expect(',', "','")?;
+                let if_true = self.expr()?;
+                self.expect(',', "','")?;
+                let if_false = self.expr()?;
+                self.expect(')', "')'")?;
+                Ok(Box::new(when(condition, if_true, if_false)))
+            }
+            Some((Token::Name(name), _)) => {
+                self.advance()?;
+                if self.eat('(')? {
+                    let args = self.arguments()?;
+                    return Ok(Box::new(call(name, args)));
+                }
+                Ok(Box::new(read(name)))
+            }
+            _ => Err(self.error("an expression")),
+        }
+    }
+
+    /// Arguments of a call, after its opening parenthesis.
+    fn arguments(&mut self) -> ParseResult<Vec<Box<dyn Expr>>> {
+        let mut args = Vec::new();
+        if self.eat(')')? {
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expr()?);
+            if !self.eat(',')? {
+                self.expect(')', "',' or ')'")?;
+                return Ok(args);
+            }
+        }
+    }
+}
+
+/// Parses a program such as `x = 0; repeat 10 { x = x + c; } print x;`, following
+/// the grammar described in the module documentation. Arithmetic wraps on
+/// overflow, `when` evaluates only the chosen branch and calls refer to functions
+/// defined in the [`Context`] the program runs in.
+pub fn parse(source: &str) -> Result<Box<dyn Stmt>, ParseError> {
+    let program = Parser::new(source)?.program()?;
+    Ok(Box::new(program))
+}
+
+/// Parses and runs a program in `context`. A program failing at runtime keeps
+/// the effects of the statements executed before the failure.
+pub fn run(source: &str, context: &mut Context) -> Result<(), RunError> {
+    parse(source)?.try_exec_stmt(context)?;
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use crate::core::{Context, Expr, Stmt};
+use crate::core::{Context, Expr, RuntimeError, Stmt};
 
+/// Writes the value of `inner` to the output sink of the [`Context`].
 pub struct Print<T: Expr> {
     inner: T,
 }
@@ -9,16 +10,18 @@ pub fn print<T: Expr>(inner: T) -> Print<T> {
 }
 
 impl<T: Expr> Stmt for Print<T> {
-    fn exec_stmt(&mut self, context: &Context) {
-        let value = self.inner.exec_expr(context);
-        println!("{value}");
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        let value = self.inner.try_exec_expr(context)?;
+        writeln!(context.output(), "{value}").map_err(|e| RuntimeError::Output(e.to_string()))
     }
 }
 
 pub struct Nothing;
 
 impl Stmt for Nothing {
-    fn exec_stmt(&mut self, _context: &Context) {}
+    fn try_exec_stmt(&mut self, _context: &mut Context) -> Result<(), RuntimeError> {
+        Ok(())
+    }
 }
 
 pub fn nothing() -> Nothing {
@@ -31,9 +34,9 @@ pub struct Seq<T: Stmt, U: Stmt> {
 }
 
 impl<T: Stmt, U: Stmt> Stmt for Seq<T, U> {
-    fn exec_stmt(&mut self, context: &Context) {
-        self.first.exec_stmt(context);
-        self.second.exec_stmt(context);
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        self.first.try_exec_stmt(context)?;
+        self.second.try_exec_stmt(context)
     }
 }
 
@@ -57,4 +60,27 @@ impl Seq<Nothing, Nothing> {
 
 pub fn seq<T: Stmt, U: Stmt>(first: T, second: U) -> Seq<T, U> {
     Seq { first, second }
-}
\ No newline at end of file
+}
+
+/// Runs the statements in order, for sequences whose length is only known at runtime.
+impl<T: Stmt> Stmt for Vec<T> {
+    fn try_exec_stmt(&mut self, context: &mut Context) -> Result<(), RuntimeError> {
+        self.iter_mut()
+            .try_for_each(|stmt| stmt.try_exec_stmt(context))
+    }
+}
+
+/// Runs any number of statements in order, nesting them into [`Seq`]s:
+/// `block![a, b, c]` is `seq(a, seq(b, c))` and `block![]` is [`Nothing`].
+#[macro_export]
+macro_rules! block {
+    () => {
+        $crate::nothing()
+    };
+    ($stmt:expr $(,)?) => {
+        $stmt
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::seq($first, $crate::block!($($rest),+))
+    };
+}
@@ -1,5 +1,6 @@
 use itertools::Itertools;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
 pub fn wrap_call(f1: impl Fn(u32) -> u32, f2: impl FnOnce(u32, u32) -> u32) -> u32 {
     let f1_rename = f1;
@@ -32,7 +33,7 @@ pub fn sum_squares_odd(list: &[u32]) -> u32 {
         .sum()
 }
 
-pub fn vertices_loop(edges: &[(u32, u32)]) -> Vec<u32> {
+pub fn vertices_loop<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> Vec<T> {
     let mut hash = HashSet::new();
     let mut result = Vec::new();
     for (u, v) in edges {
@@ -49,7 +50,7 @@ pub fn vertices_loop(edges: &[(u32, u32)]) -> Vec<u32> {
     result
 }
 
-pub fn vertices(edges: &[(u32, u32)]) -> Vec<u32> {
+pub fn vertices<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> Vec<T> {
     edges
         .iter()
         .flat_map(|(a, b)| [*a, *b])
@@ -58,7 +59,7 @@ pub fn vertices(edges: &[(u32, u32)]) -> Vec<u32> {
         .collect()
 }
 
-pub fn cycles_2_loop(edges: &[(u32, u32)]) -> Vec<u32> {
+pub fn cycles_2_loop<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> Vec<T> {
     let mut hash = HashSet::new();
     let mut result = Vec::new();
     for (a, b) in edges {
@@ -79,7 +80,7 @@ pub fn cycles_2_loop(edges: &[(u32, u32)]) -> Vec<u32> {
     result
 }
 
-pub fn cycles_2(edges: &[(u32, u32)]) -> Vec<u32> {
+pub fn cycles_2<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> Vec<T> {
     let cycles = edges
         .iter()
         .cartesian_product(edges.iter())
@@ -89,6 +90,131 @@ pub fn cycles_2(edges: &[(u32, u32)]) -> Vec<u32> {
     vertices(&cycles)
 }
 
+/// Distinct successors of every vertex, in increasing order.
+fn adjacency<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> HashMap<T, Vec<T>> {
+    let mut adjacency: HashMap<T, Vec<T>> = HashMap::new();
+    for &(a, b) in edges {
+        adjacency.entry(a).or_default().push(b);
+    }
+    for successors in adjacency.values_mut() {
+        successors.sort();
+        successors.dedup();
+    }
+    adjacency
+}
+
+/// Vertices lying on a directed cycle through exactly `k` distinct vertices, so
+/// that `cycles_k(edges, 2) == cycles_2(edges)` and `k = 1` finds self-loops.
+///
+/// Every cycle is searched for only from its smallest vertex, with paths cut off
+/// after `k` vertices, which keeps small `k` fast even on thousands of edges.
+pub fn cycles_k<T: Copy + Ord + Hash>(edges: &[(T, T)], k: usize) -> Vec<T> {
+    let adjacency = adjacency(edges);
+    let mut result = HashSet::new();
+
+    if k == 0 {
+        return Vec::new();
+    }
+
+    for &start in adjacency.keys() {
+        let mut path = vec![start];
+        let mut on_path = HashSet::from([start]);
+        // index of the next successor to try for every vertex of the path
+        let mut next = vec![0];
+
+        while let Some(i) = next.last_mut() {
+            let successors = &adjacency[path.last().unwrap()];
+            let Some(&successor) = successors.get(*i) else {
+                next.pop();
+                on_path.remove(&path.pop().unwrap());
+                continue;
+            };
+            *i += 1;
+
+            if successor == start && path.len() == k {
+                result.extend(path.iter().copied());
+            } else if path.len() < k
+                && successor > start
+                && adjacency.contains_key(&successor)
+                && on_path.insert(successor)
+            {
+                path.push(successor);
+                next.push(0);
+            }
+        }
+    }
+
+    result.into_iter().sorted().collect()
+}
+
+/// Vertices lying on any directed cycle, self-loops included. These are the
+/// vertices of strongly connected components with more than one vertex, found
+/// with an iterative version of Tarjan's algorithm in linear time.
+pub fn vertices_in_any_cycle<T: Copy + Ord + Hash>(edges: &[(T, T)]) -> Vec<T> {
+    let adjacency = adjacency(edges);
+    let no_successors = Vec::new();
+    let successors = |v: &T| adjacency.get(v).unwrap_or(&no_successors);
+
+    let mut index = HashMap::new();
+    let mut low_link = HashMap::new();
+    let mut stack = Vec::new();
+    let mut on_stack = HashSet::new();
+    let mut result = Vec::new();
+
+    for root in vertices(edges) {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        let mut calls = vec![(root, 0)];
+        index.insert(root, index.len());
+        low_link.insert(root, index[&root]);
+        stack.push(root);
+        on_stack.insert(root);
+
+        while let Some(&(v, i)) = calls.last() {
+            if let Some(&w) = successors(&v).get(i) {
+                calls.last_mut().unwrap().1 += 1;
+                if !index.contains_key(&w) {
+                    index.insert(w, index.len());
+                    low_link.insert(w, index[&w]);
+                    stack.push(w);
+                    on_stack.insert(w);
+                    calls.push((w, 0));
+                } else if on_stack.contains(&w) {
+                    let low = low_link[&v].min(index[&w]);
+                    low_link.insert(v, low);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                let low = low_link[&parent].min(low_link[&v]);
+                low_link.insert(parent, low);
+            }
+
+            if low_link[&v] == index[&v] {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack.remove(&w);
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 || successors(&v).contains(&v) {
+                    result.extend(component);
+                }
+            }
+        }
+    }
+
+    result.sort();
+    result
+}
+
 pub fn primes_loop(n: u32) -> Vec<u32> {
     let mut result = Vec::new();
 
@@ -113,22 +239,22 @@ pub fn primes(n: u32) -> Vec<u32> {
         .collect()
 }
 
-pub fn run_length_encode_loop(list: &[u32]) -> Vec<(u32, usize)> {
+pub fn run_length_encode_loop<T: PartialEq + Clone>(list: &[T]) -> Vec<(T, usize)> {
     let mut result = Vec::new();
 
     if list.is_empty() {
         return result;
     }
 
-    let mut cur = list[0];
+    let mut cur = list[0].clone();
     let mut count = 1;
 
-    for &el in &list[1..] {
-        if el == cur {
+    for el in &list[1..] {
+        if *el == cur {
             count += 1;
         } else {
             result.push((cur, count));
-            cur = el;
+            cur = el.clone();
             count = 1;
         }
     }
@@ -137,9 +263,9 @@ pub fn run_length_encode_loop(list: &[u32]) -> Vec<(u32, usize)> {
     result
 }
 
-pub fn run_length_encode(list: &[u32]) -> Vec<(u32, usize)> {
+pub fn run_length_encode<T: PartialEq + Clone>(list: &[T]) -> Vec<(T, usize)> {
     list.chunk_by(|a, b| a == b)
-        .map(|c| (c[0], c.len()))
+        .map(|c| (c[0].clone(), c.len()))
         .collect()
 }
 
@@ -324,4 +450,107 @@ mod tests {
             assert_eq!(c1(x), c2(x));
         }
     }
+
+    #[test]
+    fn rle_generic_elements() {
+        let words = ["a", "a", "b", "a"].map(String::from);
+        let expect = vec![
+            ("a".to_string(), 2),
+            ("b".to_string(), 1),
+            ("a".to_string(), 1),
+        ];
+        assert_eq!(run_length_encode_loop(&words), expect);
+        assert_eq!(run_length_encode(&words), expect);
+
+        let chars = "aaabcc".chars().collect::<Vec<_>>();
+        assert_eq!(
+            run_length_encode(&chars),
+            vec![('a', 3), ('b', 1), ('c', 2)]
+        );
+    }
+
+    #[test]
+    fn vertices_and_cycles_generic_nodes() {
+        let edges = [('a', 'b'), ('b', 'a'), ('c', 'a'), ('d', 'd')];
+        assert_eq!(vertices_loop(&edges), vec!['a', 'b', 'c', 'd']);
+        assert_eq!(vertices(&edges), vec!['a', 'b', 'c', 'd']);
+        assert_eq!(cycles_2_loop(&edges), vec!['a', 'b']);
+        assert_eq!(cycles_2(&edges), vec!['a', 'b']);
+
+        let edges = [("x", "y"), ("y", "x"), ("y", "z")];
+        assert_eq!(cycles_2(&edges), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn cycles_k_small_graphs() {
+        let edges = [(1, 2), (2, 1), (3, 4), (4, 3), (5, 5), (2, 3)];
+        assert_eq!(cycles_k(&edges, 0), Vec::<i32>::new());
+        assert_eq!(cycles_k(&edges, 1), vec![5]);
+        assert_eq!(cycles_k(&edges, 2), cycles_2(&edges));
+        assert_eq!(cycles_k(&edges, 3), Vec::<i32>::new());
+
+        // a triangle sharing vertex 1 with a square
+        let edges = [
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (1, 4),
+            (4, 5),
+            (5, 6),
+            (6, 1),
+            (7, 1),
+        ];
+        assert_eq!(cycles_k(&edges, 3), vec![1, 2, 3]);
+        assert_eq!(cycles_k(&edges, 4), vec![1, 4, 5, 6]);
+        assert_eq!(cycles_k(&edges, 5), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn cycles_k_ignores_non_simple_walks() {
+        // 1 -> 2 -> 1 -> 2 -> 1 is a closed walk of length 4, but not a cycle
+        let edges = [(1, 2), (2, 1)];
+        assert_eq!(cycles_k(&edges, 4), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn vertices_in_any_cycle_examples() {
+        let edges = [(1, 2), (2, 1), (3, 4), (4, 3), (5, 5), (2, 3), (6, 7)];
+        assert_eq!(vertices_in_any_cycle(&edges), vec![1, 2, 3, 4, 5]);
+
+        let edges = [(1, 2), (2, 3), (3, 4), (4, 2), (4, 5)];
+        assert_eq!(vertices_in_any_cycle(&edges), vec![2, 3, 4]);
+
+        let empty: [(u32, u32); 0] = [];
+        assert_eq!(vertices_in_any_cycle(&empty), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn cycles_on_thousands_of_edges() {
+        // pseudo-random graph, so that the results can be checked against cycles_2
+        let mut seed = 12345u64;
+        let mut edges = Vec::new();
+        for _ in 0..3000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = (seed >> 33) % 1500;
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let b = (seed >> 33) % 1500;
+            edges.push((a, b));
+        }
+        assert_eq!(cycles_k(&edges, 2), cycles_2(&edges));
+        let in_any = vertices_in_any_cycle(&edges);
+        assert!(cycles_k(&edges, 3).iter().all(|v| in_any.contains(v)));
+
+        let ring = (0..5000u32)
+            .map(|i| (i, (i + 1) % 5000))
+            .collect::<Vec<_>>();
+        assert_eq!(cycles_k(&ring, 3), Vec::<u32>::new());
+        assert_eq!(vertices_in_any_cycle(&ring).len(), 5000);
+
+        let ring = (0..50u32).map(|i| (i, (i + 1) % 50)).collect::<Vec<_>>();
+        assert_eq!(cycles_k(&ring, 50), (0..50).collect::<Vec<_>>());
+    }
 }
@@ -1,5 +1,7 @@
 use std::{
     cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
     rc::{Rc, Weak},
 };
 
@@ -28,6 +30,35 @@ impl Vertex {
         self.out_edges.push(Rc::downgrade(other));
     }
 
+    /// Removes every edge, owned or weak, from this vertex to `target`. Returns
+    /// whether there was any.
+    pub fn unlink(&mut self, target: &Rc<RefCell<Vertex>>) -> bool {
+        let edges = self.out_edge_owned.len() + self.out_edges.len();
+
+        self.out_edge_owned
+            .retain(|neighbour| !Rc::ptr_eq(neighbour, target));
+        self.out_edges
+            .retain(|neighbour| !std::ptr::eq(neighbour.as_ptr(), Rc::as_ptr(target)));
+
+        self.out_edge_owned.len() + self.out_edges.len() != edges
+    }
+
+    /// Removes the owned edge at `index` and hands the neighbour over to the
+    /// caller, or returns `None` if there is no such edge. The neighbour is freed
+    /// once the returned `Rc` is dropped, unless it is owned elsewhere too.
+    pub fn remove_owned_neighbour(&mut self, index: usize) -> Option<Rc<RefCell<Vertex>>> {
+        (index < self.out_edge_owned.len()).then(|| self.out_edge_owned.remove(index))
+    }
+
+    /// Drops the weak edges whose targets were already freed and returns how many
+    /// there were.
+    pub fn prune_dead_edges(&mut self) -> usize {
+        let edges = self.out_edges.len();
+        self.out_edges
+            .retain(|neighbour| neighbour.strong_count() > 0);
+        edges - self.out_edges.len()
+    }
+
     pub fn all_neighbours(&self) -> Vec<Weak<RefCell<Vertex>>> {
         let mut neighbours = Vec::new();
 
@@ -42,6 +73,85 @@ impl Vertex {
         neighbours
     }
 
+    /// Neighbours which are still alive, owned ones first.
+    fn live_neighbours(&self) -> Vec<Rc<RefCell<Vertex>>> {
+        self.all_neighbours()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// Vertices reachable from `start` in breadth-first order, each listed once.
+    /// Vertices are told apart by identity, not by their data.
+    pub fn bfs(start: &Rc<RefCell<Vertex>>) -> Vec<Rc<RefCell<Vertex>>> {
+        let mut visited = HashSet::from([Rc::as_ptr(start)]);
+        let mut queue = VecDeque::from([Rc::clone(start)]);
+        let mut order = Vec::new();
+
+        while let Some(vertex) = queue.pop_front() {
+            for neighbour in vertex.borrow().live_neighbours() {
+                if visited.insert(Rc::as_ptr(&neighbour)) {
+                    queue.push_back(neighbour);
+                }
+            }
+            order.push(vertex);
+        }
+
+        order
+    }
+
+    /// Vertices reachable from `start` in depth-first preorder, each listed once.
+    pub fn dfs(start: &Rc<RefCell<Vertex>>) -> Vec<Rc<RefCell<Vertex>>> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![Rc::clone(start)];
+        let mut order = Vec::new();
+
+        while let Some(vertex) = stack.pop() {
+            if !visited.insert(Rc::as_ptr(&vertex)) {
+                continue;
+            }
+
+            // pushed in reverse, so that the first neighbour is visited first
+            stack.extend(vertex.borrow().live_neighbours().into_iter().rev());
+            order.push(vertex);
+        }
+
+        order
+    }
+
+    /// Whether a cycle is reachable from `start`, following both owned and weak
+    /// edges. Every vertex is expanded at most once, so cycles do not make the
+    /// search loop forever.
+    pub fn has_cycle(start: &Rc<RefCell<Vertex>>) -> bool {
+        enum State {
+            OnPath,
+            Finished,
+        }
+
+        let mut states = HashMap::from([(Rc::as_ptr(start), State::OnPath)]);
+        let mut path = vec![(Rc::clone(start), start.borrow().live_neighbours())];
+
+        while let Some((vertex, neighbours)) = path.last_mut() {
+            let Some(neighbour) = neighbours.pop() else {
+                states.insert(Rc::as_ptr(vertex), State::Finished);
+                path.pop();
+                continue;
+            };
+
+            match states.get(&Rc::as_ptr(&neighbour)) {
+                Some(State::OnPath) => return true,
+                Some(State::Finished) => {}
+                None => {
+                    states.insert(Rc::as_ptr(&neighbour), State::OnPath);
+                    let neighbours = neighbour.borrow().live_neighbours();
+                    path.push((neighbour, neighbours));
+                }
+            }
+        }
+
+        false
+    }
+
     pub fn cycle(n: usize) -> Rc<RefCell<Vertex>> {
         let first = Rc::new(RefCell::new(Vertex::new(0)));
         let mut current = Rc::clone(&first);
@@ -55,18 +165,189 @@ impl Vertex {
     }
 }
 
+/// A vertex of an [`AdjacencyList`], with its edges given as ids of other vertices.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdjacencyEntry {
+    pub data: i32,
+    pub owned: Vec<usize>,
+    pub linked: Vec<usize>,
+}
+
+/// Vertices of a [`Graph`] indexed by id, the root having id 0.
+pub type AdjacencyList = Vec<AdjacencyEntry>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdjacencyError {
+    Empty,
+    UnknownVertex {
+        from: usize,
+        to: usize,
+    },
+    /// Nothing owns the vertex, so it would be freed right away.
+    NotOwned(usize),
+    /// Owned edges forming a cycle would keep each other alive forever.
+    OwnershipCycle,
+}
+
+impl Display for AdjacencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "a graph needs at least a root"),
+            Self::UnknownVertex { from, to } => {
+                write!(f, "vertex {from} has an edge to unknown vertex {to}")
+            }
+            Self::NotOwned(id) => write!(f, "vertex {id} is not owned by any vertex"),
+            Self::OwnershipCycle => write!(f, "owned edges form a cycle"),
+        }
+    }
+}
+
+impl std::error::Error for AdjacencyError {}
+
+/// The vertices reachable from `root`, which owns the graph.
+pub struct Graph {
+    pub root: Rc<RefCell<Vertex>>,
+}
+
+impl Graph {
+    pub fn new(root: Rc<RefCell<Vertex>>) -> Self {
+        Graph { root }
+    }
+
+    /// Describes the graph with ids assigned in [`Vertex::bfs`] order, so graphs
+    /// of the same shape and data give equal lists. Weak edges to freed vertices
+    /// are left out.
+    pub fn to_adjacency_list(&self) -> AdjacencyList {
+        let vertices = Vertex::bfs(&self.root);
+        let ids: HashMap<_, _> = vertices
+            .iter()
+            .enumerate()
+            .map(|(id, vertex)| (Rc::as_ptr(vertex), id))
+            .collect();
+
+        vertices
+            .iter()
+            .map(|vertex| {
+                let vertex = vertex.borrow();
+                AdjacencyEntry {
+                    data: vertex.data,
+                    owned: vertex
+                        .out_edge_owned
+                        .iter()
+                        .map(|neighbour| ids[&Rc::as_ptr(neighbour)])
+                        .collect(),
+                    linked: vertex
+                        .out_edges
+                        .iter()
+                        .filter(|neighbour| neighbour.strong_count() > 0)
+                        .map(|neighbour| ids[&neighbour.as_ptr()])
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Rebuilds a graph from its description. Every vertex other than the root has
+    /// to be owned by another one, without owned edges forming a cycle.
+    /// Rebuilding the result of [`Graph::to_adjacency_list`] gives back the same list.
+    pub fn from_adjacency_list(list: &[AdjacencyEntry]) -> Result<Self, AdjacencyError> {
+        if list.is_empty() {
+            return Err(AdjacencyError::Empty);
+        }
+
+        let mut owners = vec![0usize; list.len()];
+        for (from, entry) in list.iter().enumerate() {
+            for &to in entry.owned.iter().chain(&entry.linked) {
+                if to >= list.len() {
+                    return Err(AdjacencyError::UnknownVertex { from, to });
+                }
+            }
+            for &to in &entry.owned {
+                owners[to] += 1;
+            }
+        }
+
+        if owners[0] > 0 {
+            return Err(AdjacencyError::OwnershipCycle);
+        }
+        if let Some(id) = (1..list.len()).find(|&id| owners[id] == 0) {
+            return Err(AdjacencyError::NotOwned(id));
+        }
+
+        // every vertex is owned, so the owned edges form a tree-like DAG rooted
+        // at 0 exactly when all vertices are reached from it
+        let mut remaining_owners = owners;
+        let mut queue = VecDeque::from([0]);
+        let mut reached = 0;
+        while let Some(id) = queue.pop_front() {
+            reached += 1;
+            for &to in &list[id].owned {
+                remaining_owners[to] -= 1;
+                if remaining_owners[to] == 0 {
+                    queue.push_back(to);
+                }
+            }
+        }
+        if reached != list.len() {
+            return Err(AdjacencyError::OwnershipCycle);
+        }
+
+        let vertices: Vec<_> = list
+            .iter()
+            .map(|entry| Rc::new(RefCell::new(Vertex::new(entry.data))))
+            .collect();
+
+        for (vertex, entry) in vertices.iter().zip(list) {
+            let mut vertex = vertex.borrow_mut();
+            for &to in &entry.owned {
+                vertex.out_edge_owned.push(Rc::clone(&vertices[to]));
+            }
+            for &to in &entry.linked {
+                vertex.link_to(&vertices[to]);
+            }
+        }
+
+        Ok(Graph::new(Rc::clone(&vertices[0])))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{AdjacencyEntry, AdjacencyError, Graph, Vertex};
 
-    use super::Vertex;
+    fn vertex(data: i32) -> Rc<RefCell<Vertex>> {
+        Rc::new(RefCell::new(Vertex::new(data)))
+    }
+
+    fn data(vertices: &[Rc<RefCell<Vertex>>]) -> Vec<i32> {
+        vertices.iter().map(|v| v.borrow().data).collect()
+    }
+
+    /// 0 owns 1 and 2, 1 owns 3, and 2 links weakly to 3 and 4, where 4 is owned
+    /// by 3.
+    fn diamond() -> (Rc<RefCell<Vertex>>, Rc<RefCell<Vertex>>) {
+        let root = vertex(0);
+        let one = root.borrow_mut().create_neighbour();
+        one.borrow_mut().data = 1;
+        let two = root.borrow_mut().create_neighbour();
+        two.borrow_mut().data = 2;
+        let three = one.borrow_mut().create_neighbour();
+        three.borrow_mut().data = 3;
+        let four = three.borrow_mut().create_neighbour();
+        four.borrow_mut().data = 4;
+        two.borrow_mut().link_to(&three);
+        two.borrow_mut().link_to(&four);
+        (root, four)
+    }
 
     #[test]
     fn test_vertex_cycle() {
         let cycle_start = Vertex::cycle(5);
         let mut current = Rc::clone(&cycle_start);
         for i in 0..5 {
-            assert_eq!(current.borrow().data, i as i32);
+            assert_eq!(current.borrow().data, i);
             let neighbours = current.borrow().all_neighbours();
             assert_eq!(neighbours.len(), 1);
             let next_weak = &neighbours[0];
@@ -75,4 +356,243 @@ mod tests {
         }
         assert!(Rc::ptr_eq(&current, &cycle_start));
     }
+
+    #[test]
+    fn test_bfs_visits_by_levels() {
+        let (root, _) = diamond();
+        assert_eq!(data(&Vertex::bfs(&root)), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_dfs_visits_depth_first() {
+        let (root, _) = diamond();
+        assert_eq!(data(&Vertex::dfs(&root)), vec![0, 1, 3, 4, 2]);
+    }
+
+    #[test]
+    fn test_traversals_of_cycle_visit_each_vertex_once() {
+        let start = Vertex::cycle(4);
+        assert_eq!(data(&Vertex::bfs(&start)), vec![0, 1, 2, 3]);
+        assert_eq!(data(&Vertex::dfs(&start)), vec![0, 1, 2, 3]);
+
+        let second = start.borrow().out_edge_owned[0].clone();
+        assert_eq!(data(&Vertex::dfs(&second)), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_traversals_tell_vertices_apart_by_identity() {
+        let root = vertex(0);
+        root.borrow_mut().create_neighbour();
+        root.borrow_mut().create_neighbour();
+        assert_eq!(Vertex::bfs(&root).len(), 3);
+        assert_eq!(Vertex::dfs(&root).len(), 3);
+    }
+
+    #[test]
+    fn test_traversals_skip_dropped_vertices() {
+        let root = vertex(0);
+        {
+            let dropped = vertex(1);
+            root.borrow_mut().link_to(&dropped);
+        }
+        assert_eq!(data(&Vertex::bfs(&root)), vec![0]);
+        assert!(!Vertex::has_cycle(&root));
+    }
+
+    #[test]
+    fn test_has_cycle() {
+        assert!(Vertex::has_cycle(&Vertex::cycle(1)));
+        assert!(Vertex::has_cycle(&Vertex::cycle(5)));
+
+        let (root, _) = diamond();
+        assert!(!Vertex::has_cycle(&root));
+    }
+
+    #[test]
+    fn test_has_cycle_through_weak_back_edge() {
+        let (root, four) = diamond();
+        four.borrow_mut().link_to(&root);
+        assert!(Vertex::has_cycle(&root));
+    }
+
+    #[test]
+    fn test_has_cycle_only_considers_reachable_vertices() {
+        let start = Vertex::cycle(3);
+        let outside = vertex(10);
+        outside.borrow_mut().link_to(&start);
+        assert!(Vertex::has_cycle(&outside));
+
+        let (root, four) = diamond();
+        assert!(!Vertex::has_cycle(&four));
+        assert!(!Vertex::has_cycle(&root));
+    }
+
+    #[test]
+    fn test_has_cycle_on_long_cycle() {
+        assert!(Vertex::has_cycle(&Vertex::cycle(1_000)));
+    }
+
+    #[test]
+    fn test_unlink_owned_edge() {
+        let (root, _) = diamond();
+        let one = root.borrow().out_edge_owned[0].clone();
+
+        assert!(root.borrow_mut().unlink(&one));
+        assert_eq!(data(&Vertex::bfs(&root)), vec![0, 2, 3, 4]);
+        assert!(!root.borrow_mut().unlink(&one));
+    }
+
+    #[test]
+    fn test_unlink_weak_edge() {
+        let start = Vertex::cycle(3);
+        let last = Vertex::bfs(&start).pop().unwrap();
+
+        assert!(last.borrow_mut().unlink(&start));
+        assert!(last.borrow().out_edges.is_empty());
+        assert!(!Vertex::has_cycle(&start));
+    }
+
+    #[test]
+    fn test_unlink_removes_every_edge_to_target() {
+        let root = vertex(0);
+        let target = root.borrow_mut().create_neighbour();
+        root.borrow_mut().link_to(&target);
+        root.borrow_mut().link_to(&target);
+
+        assert!(root.borrow_mut().unlink(&target));
+        assert!(root.borrow().all_neighbours().is_empty());
+    }
+
+    #[test]
+    fn test_remove_owned_neighbour() {
+        let (root, _) = diamond();
+        let four = Rc::downgrade(&Vertex::bfs(&root)[4]);
+
+        let one = root.borrow_mut().remove_owned_neighbour(0).unwrap();
+        assert_eq!(one.borrow().data, 1);
+        assert_eq!(data(&Vertex::bfs(&root)), vec![0, 2, 3, 4]);
+        assert!(root.borrow_mut().remove_owned_neighbour(1).is_none());
+
+        // 3 and 4 were only owned through 1
+        drop(one);
+        assert!(four.upgrade().is_none());
+        assert_eq!(data(&Vertex::bfs(&root)), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_prune_dead_edges() {
+        let root = vertex(0);
+        let alive = vertex(1);
+        root.borrow_mut().link_to(&alive);
+        {
+            let dropped = vertex(2);
+            root.borrow_mut().link_to(&dropped);
+            root.borrow_mut().link_to(&dropped);
+        }
+
+        assert_eq!(root.borrow_mut().prune_dead_edges(), 2);
+        assert_eq!(root.borrow().out_edges.len(), 1);
+        assert_eq!(root.borrow_mut().prune_dead_edges(), 0);
+    }
+
+    fn entry(data: i32, owned: &[usize], linked: &[usize]) -> AdjacencyEntry {
+        AdjacencyEntry {
+            data,
+            owned: owned.to_vec(),
+            linked: linked.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_to_adjacency_list_of_cycle() {
+        let graph = Graph::new(Vertex::cycle(3));
+        assert_eq!(
+            graph.to_adjacency_list(),
+            vec![
+                entry(0, &[1], &[]),
+                entry(1, &[2], &[]),
+                entry(2, &[], &[0])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_adjacency_list_of_diamond() {
+        let (root, _) = diamond();
+        assert_eq!(
+            Graph::new(root).to_adjacency_list(),
+            vec![
+                entry(0, &[1, 2], &[]),
+                entry(1, &[3], &[]),
+                entry(2, &[], &[3, 4]),
+                entry(3, &[4], &[]),
+                entry(4, &[], &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_adjacency_list_skips_dead_edges() {
+        let root = vertex(7);
+        {
+            let dropped = vertex(1);
+            root.borrow_mut().link_to(&dropped);
+        }
+        assert_eq!(
+            Graph::new(root).to_adjacency_list(),
+            vec![entry(7, &[], &[])]
+        );
+    }
+
+    #[test]
+    fn test_adjacency_list_round_trip() {
+        let (root, four) = diamond();
+        four.borrow_mut().link_to(&root);
+        let list = Graph::new(root).to_adjacency_list();
+
+        let rebuilt = Graph::from_adjacency_list(&list).unwrap();
+        assert_eq!(rebuilt.to_adjacency_list(), list);
+        assert_eq!(data(&Vertex::dfs(&rebuilt.root)), vec![0, 1, 3, 4, 2]);
+        assert!(Vertex::has_cycle(&rebuilt.root));
+    }
+
+    #[test]
+    fn test_from_adjacency_list_keeps_vertices_alive() {
+        let list = vec![
+            entry(0, &[1], &[]),
+            entry(1, &[2], &[0]),
+            entry(2, &[], &[]),
+        ];
+        let graph = Graph::from_adjacency_list(&list).unwrap();
+        assert_eq!(data(&Vertex::bfs(&graph.root)), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_from_adjacency_list_errors() {
+        assert_eq!(
+            Graph::from_adjacency_list(&[]).err(),
+            Some(AdjacencyError::Empty)
+        );
+        assert_eq!(
+            Graph::from_adjacency_list(&[entry(0, &[], &[3])]).err(),
+            Some(AdjacencyError::UnknownVertex { from: 0, to: 3 })
+        );
+        assert_eq!(
+            Graph::from_adjacency_list(&[entry(0, &[], &[1]), entry(1, &[], &[])]).err(),
+            Some(AdjacencyError::NotOwned(1))
+        );
+        assert_eq!(
+            Graph::from_adjacency_list(&[entry(0, &[1], &[]), entry(1, &[0], &[])]).err(),
+            Some(AdjacencyError::OwnershipCycle)
+        );
+        assert_eq!(
+            Graph::from_adjacency_list(&[
+                entry(0, &[], &[]),
+                entry(1, &[2], &[]),
+                entry(2, &[1], &[]),
+            ])
+            .err(),
+            Some(AdjacencyError::OwnershipCycle)
+        );
+    }
 }
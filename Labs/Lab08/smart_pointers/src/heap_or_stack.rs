@@ -1,10 +1,73 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+};
 
+/// Size in bytes up to which [`HeapOrStack::new`] keeps a value inline.
+pub const DEFAULT_STACK_LIMIT: usize = 64;
+
+/// A value stored either inline or in a [`Box`], accessed the same way through
+/// `Deref` in both cases. Small values stay inline and large ones are boxed, so
+/// moving the wrapper around never copies more than the limit.
+#[derive(Debug, Clone, PartialEq)]
 pub enum HeapOrStack<T> {
     Stack(T),
     Heap(Box<T>),
 }
 
+impl<T> HeapOrStack<T> {
+    /// Stores `value` inline if it fits in [`DEFAULT_STACK_LIMIT`] bytes.
+    pub fn new(value: T) -> Self {
+        Self::with_stack_limit(value, DEFAULT_STACK_LIMIT)
+    }
+
+    /// Stores `value` inline if it fits in `stack_limit` bytes, otherwise boxes it.
+    pub fn with_stack_limit(value: T, stack_limit: usize) -> Self {
+        if mem::size_of::<T>() <= stack_limit {
+            Self::Stack(value)
+        } else {
+            Self::Heap(Box::new(value))
+        }
+    }
+
+    pub fn is_heap(&self) -> bool {
+        matches!(self, Self::Heap(_))
+    }
+
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Stack(value) => value,
+            Self::Heap(boxed_value) => *boxed_value,
+        }
+    }
+
+    /// Moves the value into a box, if it is not there already.
+    pub fn promote_to_heap(self) -> Self {
+        match self {
+            Self::Stack(value) => Self::Heap(Box::new(value)),
+            heap => heap,
+        }
+    }
+
+    /// Moves the value out of its box if it fits in `stack_limit` bytes. Returns
+    /// the value unchanged as the error otherwise.
+    pub fn try_demote(self, stack_limit: usize) -> Result<Self, Self> {
+        match self {
+            Self::Heap(boxed_value) if mem::size_of::<T>() <= stack_limit => {
+                Ok(Self::Stack(*boxed_value))
+            }
+            Self::Heap(boxed_value) => Err(Self::Heap(boxed_value)),
+            stack => Ok(stack),
+        }
+    }
+}
+
+impl<T> From<T> for HeapOrStack<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
 impl<T> Deref for HeapOrStack<T> {
     type Target = T;
 
@@ -27,7 +90,7 @@ impl<T> DerefMut for HeapOrStack<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::HeapOrStack;
+    use super::{DEFAULT_STACK_LIMIT, HeapOrStack};
 
     #[test]
     fn test_heap_or_stack() {
@@ -43,4 +106,33 @@ mod tests {
         assert_eq!(*stack_value, 15);
         assert_eq!(*heap_value, 25);
     }
+
+    #[test]
+    fn test_placement_by_size() {
+        assert!(!HeapOrStack::new(1u64).is_heap());
+        assert!(HeapOrStack::new([0u8; DEFAULT_STACK_LIMIT + 1]).is_heap());
+        assert!(HeapOrStack::with_stack_limit(1u64, 4).is_heap());
+        assert!(!HeapOrStack::from([0u8; DEFAULT_STACK_LIMIT]).is_heap());
+    }
+
+    #[test]
+    fn test_promote_and_demote() {
+        let value = HeapOrStack::new(String::from("abc")).promote_to_heap();
+        assert!(value.is_heap());
+        assert_eq!(*value, "abc");
+
+        let value = value.try_demote(0).unwrap_err();
+        assert!(value.is_heap());
+
+        let value = value.try_demote(DEFAULT_STACK_LIMIT).unwrap();
+        assert!(!value.is_heap());
+        assert_eq!(value.into_inner(), "abc");
+    }
+
+    #[test]
+    fn test_deref_mut_through_box() {
+        let mut value = HeapOrStack::new(vec![1, 2]).promote_to_heap();
+        value.push(3);
+        assert_eq!(value.into_inner(), vec![1, 2, 3]);
+    }
 }
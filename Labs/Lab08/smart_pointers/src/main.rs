@@ -6,7 +6,7 @@ mod heap_or_stack;
 
 use austro_hungarian_greeter::AustroHungarianGreeter;
 use cannon_head::canon_head;
-use graphs::Vertex;
+use graphs::{Graph, Vertex};
 use heap_or_stack::HeapOrStack;
 
 use std::{borrow::Cow, cell::RefCell, collections::VecDeque, rc::Rc};
@@ -27,6 +27,12 @@ fn main() {
     println!("Stack value: {}", *stack_value);
     println!("Heap value: {}", *heap_value);
 
+    let mut buffer = HeapOrStack::from([0u8; 256]);
+    buffer[0] = 1;
+    println!("256-byte buffer boxed: {}", buffer.is_heap());
+    let buffer = buffer.try_demote(usize::MAX).unwrap().promote_to_heap();
+    println!("First byte: {}", buffer.into_inner()[0]);
+
     // Part 3
     let xs = VecDeque::from(vec![2, 4, 5, 6]);
     let result = canon_head(&xs).unwrap();
@@ -87,4 +93,37 @@ fn main() {
             println!(" -> Found neighbor: {}", rc.borrow().data);
         }
     }
+
+    println!("\n--- Testing bfs(), dfs() and has_cycle() methods ---");
+    let data = |vertices: Vec<Rc<RefCell<Vertex>>>| {
+        vertices.iter().map(|v| v.borrow().data).collect::<Vec<_>>()
+    };
+    println!("BFS from Node 0: {:?}", data(Vertex::bfs(&node_0)));
+    println!("DFS from Node 0: {:?}", data(Vertex::dfs(&node_0)));
+    println!("Cycle from Node 100: {}", Vertex::has_cycle(&observer));
+    println!("Cycle from Node 99: {}", Vertex::has_cycle(&branch_node));
+
+    println!("\n--- Testing unlink(), remove_owned_neighbour() and prune_dead_edges() ---");
+    node_2.borrow_mut().unlink(&node_0);
+    println!(
+        "Cycle from Node 0 after unlinking 2 -> 0: {}",
+        Vertex::has_cycle(&node_0)
+    );
+    let removed = node_1.borrow_mut().remove_owned_neighbour(0);
+    drop(removed);
+    drop(node_2);
+    let pruned = observer.borrow_mut().prune_dead_edges();
+    println!("Removed Node 2, which left {pruned} dead edge(s) at Node 100");
+
+    println!("\n--- Testing to_adjacency_list() and from_adjacency_list() ---");
+    let graph = Graph::new(Vertex::cycle(3));
+    let list = graph.to_adjacency_list();
+    println!("Adjacency list of a 3-cycle: {list:?}");
+    match Graph::from_adjacency_list(&list) {
+        Ok(rebuilt) => println!(
+            "Rebuilt graph matches: {}",
+            rebuilt.to_adjacency_list() == list
+        ),
+        Err(e) => println!("Failed to rebuild the graph: {e}"),
+    }
 }
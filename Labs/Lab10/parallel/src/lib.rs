@@ -1,14 +1,23 @@
 #![allow(dead_code)]
 
 mod shapes;
+mod thread_pool;
 mod transformations;
 
 pub use shapes::*;
+pub use thread_pool::ThreadPool;
 pub use transformations::*;
 
 use std::{
-    sync::{Arc, Mutex, mpsc},
+    any::Any,
+    panic,
+    sync::{
+        Mutex,
+        atomic::{AtomicI64, AtomicUsize, Ordering},
+        mpsc,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 pub fn spawn_sum(v: Vec<i32>) {
@@ -17,87 +26,317 @@ pub fn spawn_sum(v: Vec<i32>) {
 }
 
 pub fn sum_scoped(parts: &[&[i32]]) -> i32 {
+    if parts.is_empty() {
+        return 0;
+    }
+
+    let (tx, rx) = mpsc::channel();
+
     thread::scope(|s| {
-        let mut handles = Vec::with_capacity(parts.len());
+        let pool = ThreadPool::scoped(s, parts.len().min(default_threads()));
         for part in parts {
-            handles.push(s.spawn(move || part.iter().copied().sum::<i32>()));
+            let tx = tx.clone();
+            pool.execute(move || {
+                // a panicking job drops its sender without sending
+                let _ = tx.send(part.iter().copied().sum::<i32>());
+            });
         }
+    });
+    drop(tx);
 
-        let mut total = 0;
-        for h in handles {
-            total += h.join().expect("Failed to compute partial sum...");
-        }
+    let partial_sums: Vec<i32> = rx.iter().collect();
+    assert_eq!(
+        partial_sums.len(),
+        parts.len(),
+        "Failed to compute partial sum..."
+    );
+    partial_sums.into_iter().sum()
+}
+
+/// Number of threads used by helpers which pick it themselves.
+fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Splits `len` items into at most `n_threads` contiguous chunks of nearly equal length.
+fn chunk_len(len: usize, n_threads: usize) -> usize {
+    len.div_ceil(n_threads.max(1)).max(1)
+}
+
+/// Applies `f` to every item on up to `n_threads` scoped threads, each handling
+/// one contiguous chunk. The results keep the order of `items`.
+pub fn parallel_map<T, U, F>(items: &[T], f: F, n_threads: usize) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    let f = &f;
+    thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk_len(items.len(), n_threads))
+            .map(|chunk| s.spawn(move || chunk.iter().map(f).collect::<Vec<U>>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// Combines all items with `f` on one scoped thread per available core.
+/// `f` must be associative and `identity` neutral for it, since every chunk
+/// is reduced separately starting from `identity` before the partial results are combined.
+pub fn parallel_reduce<T, F>(items: &[T], identity: T, f: F) -> T
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> T + Sync,
+{
+    let (f, identity) = (&f, &identity);
+    let partials: Vec<T> = thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk_len(items.len(), default_threads()))
+            .map(|chunk| {
+                s.spawn(move || {
+                    chunk
+                        .iter()
+                        .fold(identity.clone(), |acc, item| f(&acc, item))
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+
+    partials
+        .iter()
+        .fold(identity.clone(), |acc, partial| f(&acc, partial))
+}
+
+/// Smallest chunk handed to a thread by [`parallel_sum`]: 32 KiB of `i64`s,
+/// which fits in the L1 data cache of common CPUs.
+const SUM_CHUNK_LEN: usize = 4096;
+
+/// Chunks per thread in [`parallel_sum`], so that threads which finish early
+/// can take over the remaining work of slower ones.
+const SUM_CHUNKS_PER_THREAD: usize = 4;
+
+/// Sums `data` on all available cores. The input is cut into chunks which threads
+/// claim one at a time through a shared atomic index, so no thread sits idle while
+/// work remains. Inputs too small to be worth splitting are summed in place.
+pub fn parallel_sum(data: &[i64]) -> i64 {
+    let threads = default_threads();
+    let chunk = SUM_CHUNK_LEN.max(data.len().div_ceil(threads * SUM_CHUNKS_PER_THREAD));
+    let chunks = data.len().div_ceil(chunk);
+    if chunks <= 1 || threads == 1 {
+        return data.iter().sum();
+    }
 
-        total
+    let next_chunk = AtomicUsize::new(0);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads.min(chunks))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut total = 0;
+                    loop {
+                        let start = next_chunk.fetch_add(1, Ordering::Relaxed) * chunk;
+                        if start >= data.len() {
+                            return total;
+                        }
+                        let end = (start + chunk).min(data.len());
+                        total += data[start..end].iter().sum::<i64>();
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .sum()
     })
 }
 
 pub fn parallel_increment(n_threads: usize, iters: usize) -> i64 {
-    let counter = Arc::new(Mutex::new(0_i64));
+    parallel_increment_with(n_threads, iters, |i, j| j == iters - 1 && i % 2 == 0)
+}
 
-    let mut handles = Vec::with_capacity(n_threads);
+/// Like [`parallel_increment`], but thread `i` panics at iteration `j` (while holding
+/// the lock, poisoning it) exactly when `should_panic(i, j)` holds.
+pub fn parallel_increment_with<F>(n_threads: usize, iters: usize, should_panic: F) -> i64
+where
+    F: Fn(usize, usize) -> bool + Sync,
+{
+    let counter = Mutex::new(0_i64);
 
-    for i in 0..n_threads {
-        let counter = Arc::clone(&counter);
-        let handle = thread::spawn(move || {
-            for j in 0..iters {
-                let mut num = counter.lock().unwrap_or_else(|e| e.into_inner());
-                if j == iters - 1 && i % 2 == 0 {
-                    panic!("Thread {i} panicked on last iteration");
-                }
-                *num += 1;
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..n_threads)
+            .map(|i| {
+                let (counter, should_panic) = (&counter, &should_panic);
+                s.spawn(move || {
+                    for j in 0..iters {
+                        let mut num = counter.lock().unwrap_or_else(|e| e.into_inner());
+                        if should_panic(i, j) {
+                            panic!("Thread {i} panicked on iteration {j}");
+                        }
+                        *num += 1;
+                    }
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            if handle.join().is_err() {
+                print!("Thread {i} panicked!");
             }
-        });
+        }
+    });
 
-        handles.push(handle);
-    }
+    counter.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Lock-free counterpart of [`parallel_increment`], with the same panic policy.
+pub fn parallel_increment_atomic(n_threads: usize, iters: usize) -> i64 {
+    parallel_increment_atomic_with(n_threads, iters, |i, j| j == iters - 1 && i % 2 == 0)
+}
 
-    for (i, handle) in handles.into_iter().enumerate() {
-        if handle.join().is_err() {
-            print!("Thread {i} panicked!");
+/// Like [`parallel_increment_with`], but counting with an [`AtomicI64`].
+/// Panicking threads are joined silently.
+pub fn parallel_increment_atomic_with<F>(n_threads: usize, iters: usize, should_panic: F) -> i64
+where
+    F: Fn(usize, usize) -> bool + Sync,
+{
+    let counter = AtomicI64::new(0);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..n_threads)
+            .map(|i| {
+                let (counter, should_panic) = (&counter, &should_panic);
+                s.spawn(move || {
+                    for j in 0..iters {
+                        if should_panic(i, j) {
+                            panic!("Thread {i} panicked on iteration {j}");
+                        }
+                        counter.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            // the panic has already been reported by the panic hook
+            let _ = handle.join();
         }
+    });
+
+    counter.into_inner()
+}
+
+/// Final values and wall-clock time of the mutex and atomic counters for the
+/// same workload.
+#[derive(Debug, Clone, Copy)]
+pub struct IncrementTimings {
+    pub mutex: Duration,
+    pub mutex_total: i64,
+    pub atomic: Duration,
+    pub atomic_total: i64,
+}
+
+/// Runs both counters with `n_threads` threads doing `iters` increments each
+/// (without injected panics) and measures how long each one takes.
+pub fn compare_increments(n_threads: usize, iters: usize) -> IncrementTimings {
+    let never = |_, _| false;
+
+    let start = Instant::now();
+    let mutex_total = parallel_increment_with(n_threads, iters, never);
+    let mutex = start.elapsed();
+
+    let start = Instant::now();
+    let atomic_total = parallel_increment_atomic_with(n_threads, iters, never);
+    let atomic = start.elapsed();
+
+    IncrementTimings {
+        mutex,
+        mutex_total,
+        atomic,
+        atomic_total,
     }
+}
 
-    *counter.lock().unwrap_or_else(|e| e.into_inner())
+/// A part of [`try_for_each_parallel`] whose closure panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicInfo {
+    /// Index of the part in the input.
+    pub chunk: usize,
+    pub message: String,
 }
 
-pub fn pipeline(n: i32, threads: usize) -> i32 {
-    let (tx, rx) = mpsc::channel::<u32>();
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("non-string panic payload")
+    }
+}
 
-    let sum_handle = thread::spawn(move || {
-        let mut total = 0;
-        for v in rx {
-            total += v;
-        }
-        total
+/// Runs `f` on every part on its own scoped thread. Every part is processed even if
+/// some of them panic; the panics are reported in the order of the parts.
+pub fn try_for_each_parallel<T, F>(parts: &[&[T]], f: F) -> Result<(), Vec<PanicInfo>>
+where
+    T: Sync,
+    F: Fn(&[T]) + Sync,
+{
+    let f = &f;
+    let panics: Vec<PanicInfo> = thread::scope(|s| {
+        let handles: Vec<_> = parts.iter().map(|part| s.spawn(move || f(part))).collect();
+
+        handles
+            .into_iter()
+            .enumerate()
+            .filter_map(|(chunk, handle)| {
+                let payload = handle.join().err()?;
+                Some(PanicInfo {
+                    chunk,
+                    message: panic_message(payload.as_ref()),
+                })
+            })
+            .collect()
     });
 
-    let mut handles = Vec::with_capacity(threads);
+    if panics.is_empty() {
+        Ok(())
+    } else {
+        Err(panics)
+    }
+}
+
+pub fn pipeline(n: i32, threads: usize) -> i32 {
+    let (tx, rx) = mpsc::channel::<u32>();
 
+    let pool = ThreadPool::new(threads.max(1));
     for _ in 0..threads {
-        let tx_clone = tx.clone();
-        let handle = thread::spawn(move || {
+        let tx = tx.clone();
+        pool.execute(move || {
             for i in 1..=n as u32 {
-                tx_clone
-                    .send(i)
-                    .expect("Receiver was dropped while sending");
+                tx.send(i).expect("Receiver was dropped while sending");
             }
         });
-        handles.push(handle);
     }
-
     drop(tx);
+    assert_eq!(pool.join(), 0, "Sender job panicked");
 
-    for handle in handles {
-        handle.join().expect("Sender thread panicked");
-    }
-
-    sum_handle.join().expect("Sum thread panicked") as i32
+    rx.iter().sum::<u32>() as i32
 }
 
 #[cfg(test)]
 mod tests {
-    use std::any::Any;
+    use std::sync::Arc;
 
     use super::*;
 
@@ -131,6 +370,80 @@ mod tests {
         assert!(approx_eq(total, expected, 1e-12));
     }
 
+    #[test]
+    fn test_triangle_area_and_perimeter() {
+        let triangle = Triangle {
+            a: (0.0, 0.0),
+            b: (3.0, 0.0),
+            c: (0.0, 4.0),
+        };
+        assert!(approx_eq(triangle.area(), 6.0, 1e-12));
+        assert!(approx_eq(triangle.perimeter(), 12.0, 1e-12));
+
+        let clockwise = Triangle {
+            a: triangle.a,
+            b: triangle.c,
+            c: triangle.b,
+        };
+        assert!(approx_eq(clockwise.area(), 6.0, 1e-12));
+    }
+
+    #[test]
+    fn test_polygon_area_and_perimeter() {
+        // an L made of three unit squares
+        let polygon = Polygon {
+            vertices: vec![
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (2.0, 1.0),
+                (1.0, 1.0),
+                (1.0, 2.0),
+                (0.0, 2.0),
+            ],
+        };
+        assert!(approx_eq(polygon.area(), 3.0, 1e-12));
+        assert!(approx_eq(polygon.perimeter(), 8.0, 1e-12));
+
+        let empty = Polygon { vertices: vec![] };
+        assert!(approx_eq(empty.area(), 0.0, 1e-12));
+        assert!(approx_eq(empty.perimeter(), 0.0, 1e-12));
+    }
+
+    #[test]
+    fn test_perimeters() {
+        assert!(approx_eq(Rect { w: 3.0, h: 4.0 }.perimeter(), 14.0, 1e-12));
+        let circle = Circle { r: 2.0 };
+        assert!(approx_eq(
+            circle.perimeter(),
+            4.0 * std::f64::consts::PI,
+            1e-12
+        ));
+    }
+
+    #[test]
+    fn test_total_area_parallel_matches_sequential() {
+        let items: Vec<Box<dyn Shape + Sync>> = (0..50_000)
+            .map(|i| -> Box<dyn Shape + Sync> {
+                match i % 3 {
+                    0 => Box::new(Rect { w: 1.0, h: 2.0 }),
+                    1 => Box::new(Triangle {
+                        a: (0.0, 0.0),
+                        b: (1.0, 0.0),
+                        c: (0.0, 1.0),
+                    }),
+                    _ => Box::new(Polygon {
+                        vertices: vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+                    }),
+                }
+            })
+            .collect();
+
+        let expected = 16_667.0 * 2.0 + 16_667.0 * 0.5 + 16_666.0;
+        assert!(approx_eq(total_area_parallel(&items), expected, 1e-6));
+        assert!(approx_eq(total_area_parallel(&items[..3]), 3.5, 1e-12));
+        assert!(approx_eq(total_area_parallel(&[]), 0.0, 1e-12));
+    }
+
     #[test]
     fn test_transform_add_and_apply_all_dyn() {
         // After making Transform object-safe with apply(&self, f64) -> f64
@@ -192,6 +505,184 @@ mod tests {
         assert_eq!(total, expected);
     }
 
+    #[test]
+    fn test_thread_pool_runs_all_jobs() {
+        let counter = Arc::new(Mutex::new(0));
+        let pool = ThreadPool::new(3);
+        assert_eq!(pool.size(), 3);
+
+        for i in 1..=100 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move || *counter.lock().unwrap() += i);
+        }
+
+        // dropping the pool waits for the queued jobs
+        drop(pool);
+        assert_eq!(*counter.lock().unwrap(), 5050);
+    }
+
+    #[test]
+    fn test_thread_pool_isolates_panics() {
+        let (tx, rx) = mpsc::channel();
+        let pool = ThreadPool::new(1);
+
+        pool.execute(|| panic!("job failed"));
+        for i in 0..3 {
+            let tx = tx.clone();
+            pool.execute(move || tx.send(i).unwrap());
+        }
+        pool.execute(|| panic!("job failed again"));
+
+        assert_eq!(pool.join(), 2);
+        drop(tx);
+        assert_eq!(rx.iter().collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_thread_pool_scoped_borrows() {
+        let data: Vec<i32> = (1..=10).collect();
+        let total = Mutex::new(0);
+
+        thread::scope(|s| {
+            let pool = ThreadPool::scoped(s, 2);
+            for chunk in data.chunks(3) {
+                let total = &total;
+                pool.execute(move || *total.lock().unwrap() += chunk.iter().sum::<i32>());
+            }
+        });
+
+        assert_eq!(total.into_inner().unwrap(), 55);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_thread_pool_requires_workers() {
+        ThreadPool::new(0);
+    }
+
+    #[test]
+    fn test_sum_scoped_empty() {
+        assert_eq!(sum_scoped(&[]), 0);
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<i32> = (0..103).collect();
+        for n_threads in [0, 1, 4, 200] {
+            let squares = parallel_map(&items, |x| i64::from(*x) * i64::from(*x), n_threads);
+            let expected: Vec<i64> = (0..103).map(|x| x * x).collect();
+            assert_eq!(squares, expected);
+        }
+        assert_eq!(parallel_map(&[] as &[i32], |x| *x, 4), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_parallel_map_strings() {
+        let words = ["a", "bb", "ccc"];
+        assert_eq!(parallel_map(&words, |w| w.len(), 2), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parallel_reduce() {
+        let items: Vec<i64> = (1..=10_000).collect();
+        assert_eq!(parallel_reduce(&items, 0, |a, b| a + b), 50_005_000);
+        assert_eq!(parallel_reduce(&items, i64::MIN, |a, b| *a.max(b)), 10_000);
+        assert_eq!(parallel_reduce(&[] as &[i64], 7, |a, b| a + b), 7);
+
+        let words: Vec<String> = ["x", "y", "z"].map(String::from).to_vec();
+        let joined = parallel_reduce(&words, String::new(), |a, b| format!("{a}{b}"));
+        assert_eq!(joined, "xyz");
+    }
+
+    #[test]
+    #[should_panic(expected = "odd item")]
+    fn test_parallel_map_propagates_panics() {
+        parallel_map(&[2, 4, 5], |x| assert!(x % 2 == 0, "odd item"), 3);
+    }
+
+    #[test]
+    fn test_parallel_sum() {
+        assert_eq!(parallel_sum(&[]), 0);
+        assert_eq!(parallel_sum(&[1, -2, 3]), 2);
+
+        // large enough to be split into many chunks, with a length that is not a multiple
+        let data: Vec<i64> = (1..=1_000_003).collect();
+        assert_eq!(parallel_sum(&data), 1_000_003 * 1_000_004 / 2);
+
+        let alternating: Vec<i64> = (0..100_000)
+            .map(|i| if i % 2 == 0 { 5 } else { -5 })
+            .collect();
+        assert_eq!(parallel_sum(&alternating), 0);
+    }
+
+    #[test]
+    fn test_parallel_increment_atomic_matches_mutex() {
+        assert_eq!(parallel_increment_atomic(4, 5), 4 * 5 - 2);
+        assert_eq!(parallel_increment_atomic(4, 5), parallel_increment(4, 5));
+    }
+
+    #[test]
+    fn test_parallel_increment_custom_panic_policy() {
+        // thread 1 panics straight away, thread 2 after 3 increments
+        let policy = |i, j| (i == 1 && j == 0) || (i == 2 && j == 3);
+        assert_eq!(parallel_increment_with(3, 10, policy), 10 + 3);
+        assert_eq!(parallel_increment_atomic_with(3, 10, policy), 10 + 3);
+
+        assert_eq!(parallel_increment_atomic_with(3, 10, |_, _| false), 30);
+        assert_eq!(parallel_increment_atomic(0, 10), 0);
+    }
+
+    #[test]
+    fn test_compare_increments() {
+        let timings = compare_increments(4, 1_000);
+        assert_eq!(timings.mutex_total, 4_000);
+        assert_eq!(timings.atomic_total, 4_000);
+        assert_eq!(compare_increments(0, 1_000).atomic_total, 0);
+    }
+
+    #[test]
+    fn test_try_for_each_parallel_ok() {
+        let total = AtomicI64::new(0);
+        let (a, b) = ([1, 2, 3], [4, 5]);
+        let result = try_for_each_parallel(&[&a[..], &b[..]], |part| {
+            total.fetch_add(part.iter().sum::<i64>(), Ordering::Relaxed);
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(total.into_inner(), 15);
+    }
+
+    #[test]
+    fn test_try_for_each_parallel_reports_panics() {
+        let done = AtomicUsize::new(0);
+        let parts: Vec<&[i32]> = vec![&[1], &[-2], &[3], &[-4, 5]];
+        let result = try_for_each_parallel(&parts, |part| {
+            if let Some(negative) = part.iter().find(|x| **x < 0) {
+                panic!("negative value {negative}");
+            }
+            assert!(part[0] != 3, "three");
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(
+            result,
+            Err(vec![
+                PanicInfo {
+                    chunk: 1,
+                    message: "negative value -2".into()
+                },
+                PanicInfo {
+                    chunk: 2,
+                    message: "three".into()
+                },
+                PanicInfo {
+                    chunk: 3,
+                    message: "negative value -4".into()
+                },
+            ])
+        );
+        assert_eq!(done.into_inner(), 1);
+    }
+
     #[test]
     fn test_pipeline_small() {
         let s = pipeline(3, 2);
@@ -1,13 +1,26 @@
 mod circle;
+mod polygon;
 mod rect;
+mod triangle;
+
+use std::thread;
 
 pub use circle::Circle;
+pub use polygon::Polygon;
 pub use rect::Rect;
+pub use triangle::Triangle;
 
 pub trait Shape {
     fn area(&self) -> f64;
 }
 
+pub trait Perimeter {
+    fn perimeter(&self) -> f64;
+}
+
+/// Collections shorter than this are not worth spawning threads for.
+const PARALLEL_AREA_THRESHOLD: usize = 10_000;
+
 pub fn total_area_generic<T: Shape>(items: &[T]) -> f64 {
     items.iter().fold(0_f64, |acc, cur| acc + cur.area())
 }
@@ -15,3 +28,27 @@ pub fn total_area_generic<T: Shape>(items: &[T]) -> f64 {
 pub fn total_area_dyn(items: &[Box<dyn Shape>]) -> f64 {
     items.iter().fold(0_f64, |acc, cur| acc + cur.area())
 }
+
+/// Like [`total_area_dyn`], but large collections are split into one chunk per
+/// available thread and summed on scoped threads.
+pub fn total_area_parallel(items: &[Box<dyn Shape + Sync>]) -> f64 {
+    if items.len() < PARALLEL_AREA_THRESHOLD {
+        return items.iter().map(|item| item.area()).sum();
+    }
+
+    thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(crate::chunk_len(items.len(), crate::default_threads()))
+            .map(|chunk| s.spawn(move || chunk.iter().map(|item| item.area()).sum::<f64>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .sum()
+    })
+}
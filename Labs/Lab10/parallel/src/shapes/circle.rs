@@ -1,4 +1,4 @@
-use super::Shape;
+use super::{Perimeter, Shape};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Circle {
@@ -10,3 +10,9 @@ impl Shape for Circle {
         std::f64::consts::PI * self.r * self.r
     }
 }
+
+impl Perimeter for Circle {
+    fn perimeter(&self) -> f64 {
+        2.0 * std::f64::consts::PI * self.r
+    }
+}
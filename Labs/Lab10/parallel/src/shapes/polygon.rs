@@ -0,0 +1,36 @@
+use super::{Perimeter, Shape};
+
+/// A simple polygon given by its vertices in order, either clockwise or
+/// counterclockwise. The last vertex connects back to the first one.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<(f64, f64)>,
+}
+
+impl Polygon {
+    fn edges(&self) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+}
+
+impl Shape for Polygon {
+    /// Shoelace formula.
+    fn area(&self) -> f64 {
+        let doubled: f64 = self
+            .edges()
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum();
+        doubled.abs() / 2.0
+    }
+}
+
+impl Perimeter for Polygon {
+    fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| (x1 - x2).hypot(y1 - y2))
+            .sum()
+    }
+}
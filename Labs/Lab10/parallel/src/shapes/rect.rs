@@ -1,4 +1,4 @@
-use super::Shape;
+use super::{Perimeter, Shape};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rect {
@@ -11,3 +11,9 @@ impl Shape for Rect {
         self.w * self.h
     }
 }
+
+impl Perimeter for Rect {
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.w + self.h)
+    }
+}
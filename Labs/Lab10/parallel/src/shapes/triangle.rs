@@ -0,0 +1,23 @@
+use super::{Perimeter, Shape};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Triangle {
+    pub a: (f64, f64),
+    pub b: (f64, f64),
+    pub c: (f64, f64),
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        let (ab_x, ab_y) = (self.b.0 - self.a.0, self.b.1 - self.a.1);
+        let (ac_x, ac_y) = (self.c.0 - self.a.0, self.c.1 - self.a.1);
+        (ab_x * ac_y - ab_y * ac_x).abs() / 2.0
+    }
+}
+
+impl Perimeter for Triangle {
+    fn perimeter(&self) -> f64 {
+        let distance = |p: (f64, f64), q: (f64, f64)| (p.0 - q.0).hypot(p.1 - q.1);
+        distance(self.a, self.b) + distance(self.b, self.c) + distance(self.c, self.a)
+    }
+}
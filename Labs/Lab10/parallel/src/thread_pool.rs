@@ -0,0 +1,120 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+type Job<'scope> = Box<dyn FnOnce() + Send + 'scope>;
+
+enum Worker<'scope> {
+    Owned(thread::JoinHandle<()>),
+    Scoped(thread::ScopedJoinHandle<'scope, ()>),
+}
+
+/// A fixed number of worker threads executing jobs from a shared queue.
+///
+/// A job which panics does not take its worker down: the panic is counted and the
+/// worker moves on to the next job. Dropping the pool lets the workers finish every
+/// queued job and then joins them.
+pub struct ThreadPool<'scope> {
+    sender: Option<mpsc::Sender<Job<'scope>>>,
+    workers: Vec<Worker<'scope>>,
+    panicked_jobs: Arc<AtomicUsize>,
+}
+
+impl ThreadPool<'static> {
+    pub fn new(size: usize) -> Self {
+        Self::with_spawner(size, |work| Worker::Owned(thread::spawn(work)))
+    }
+}
+
+impl<'scope> ThreadPool<'scope> {
+    /// Creates a pool whose workers run inside `scope`, so that jobs may borrow
+    /// anything which outlives the scope.
+    pub fn scoped<'env>(scope: &'scope thread::Scope<'scope, 'env>, size: usize) -> Self {
+        Self::with_spawner(size, |work| Worker::Scoped(scope.spawn(work)))
+    }
+
+    fn with_spawner(size: usize, mut spawn: impl FnMut(Job<'scope>) -> Worker<'scope>) -> Self {
+        assert!(size > 0, "a thread pool needs at least one worker");
+
+        let (sender, receiver) = mpsc::channel::<Job<'scope>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let panicked_jobs = Arc::new(AtomicUsize::new(0));
+
+        let workers = (0..size)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let panicked_jobs = Arc::clone(&panicked_jobs);
+                spawn(Box::new(move || {
+                    loop {
+                        // the lock is released before the job runs
+                        let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                        let Ok(job) = job else {
+                            break;
+                        };
+                        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            panicked_jobs.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }))
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+            panicked_jobs,
+        }
+    }
+
+    /// Queues `job` to run on the first idle worker.
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'scope,
+    {
+        self.sender
+            .as_ref()
+            .expect("the pool is running until dropped")
+            .send(Box::new(job))
+            .expect("workers run until the pool is dropped");
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Number of jobs which panicked so far.
+    pub fn panicked_jobs(&self) -> usize {
+        self.panicked_jobs.load(Ordering::Relaxed)
+    }
+
+    /// Waits for every queued job to finish and returns how many of them panicked.
+    pub fn join(mut self) -> usize {
+        self.shutdown();
+        self.panicked_jobs()
+    }
+
+    fn shutdown(&mut self) {
+        // closing the queue makes each worker exit once it is empty
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..) {
+            // job panics are caught, so workers themselves never panic
+            let _ = match worker {
+                Worker::Owned(handle) => handle.join(),
+                Worker::Scoped(handle) => handle.join(),
+            };
+        }
+    }
+}
+
+impl Drop for ThreadPool<'_> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 
 #[macro_export]
@@ -52,6 +52,211 @@ where
     }
 }
 
+/// A nondeterministic machine: a state may have any number of successors.
+pub trait NfaStateMachine<S> {
+    fn step(&self, state: S) -> HashSet<S>;
+}
+
+impl<S> NfaStateMachine<S> for HashMap<S, Vec<S>>
+where
+    S: Clone + Eq + Hash,
+{
+    fn step(&self, state: S) -> HashSet<S> {
+        self.get(&state)
+            .map(|successors| successors.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Deterministic view of a nondeterministic machine through the powerset construction.
+/// A state is the set of states the machine may be in and a step moves to the union
+/// of their successors; once no successor is left the machine stops.
+#[derive(Debug)]
+pub struct Powerset<M> {
+    nfa: M,
+}
+
+impl<M> Powerset<M> {
+    pub fn new(nfa: M) -> Self {
+        Self { nfa }
+    }
+}
+
+impl<S, M> StateMachine<BTreeSet<S>> for Powerset<M>
+where
+    S: Clone + Ord,
+    M: NfaStateMachine<S>,
+{
+    fn step(&self, states: BTreeSet<S>) -> Option<BTreeSet<S>> {
+        let successors: BTreeSet<S> = states
+            .into_iter()
+            .flat_map(|state| self.nfa.step(state))
+            .collect();
+
+        (!successors.is_empty()).then_some(successors)
+    }
+}
+
+struct Transition<S, C> {
+    from: S,
+    to: S,
+    guard: Box<dyn Fn(&C) -> bool>,
+    action: Box<dyn Fn(&mut C)>,
+}
+
+/// A machine whose transitions are enabled by a guard over a context `C`, typically
+/// holding the current input, and fire an action which may update the context,
+/// e.g. to record outputs as in a Mealy machine.
+pub struct GuardedMachine<S, C> {
+    transitions: Vec<Transition<S, C>>,
+}
+
+impl<S: Eq, C> GuardedMachine<S, C> {
+    pub fn builder() -> GuardedMachineBuilder<S, C> {
+        GuardedMachineBuilder {
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Fires the first transition out of `state`, in the order they were added, whose
+    /// guard accepts `context`. Returns its target, or `None` if no transition is enabled.
+    pub fn fire(&self, state: S, context: &mut C) -> Option<S>
+    where
+        S: Clone,
+    {
+        let transition = self
+            .transitions
+            .iter()
+            .find(|transition| transition.from == state && (transition.guard)(context))?;
+
+        (transition.action)(context);
+        Some(transition.to.clone())
+    }
+}
+
+/// Machines without a context are ordinary deterministic machines.
+impl<S: Clone + Eq> StateMachine<S> for GuardedMachine<S, ()> {
+    fn step(&self, state: S) -> Option<S> {
+        self.fire(state, &mut ())
+    }
+}
+
+/// Builds a [`GuardedMachine`]; [`guard`](Self::guard) and [`action`](Self::action)
+/// apply to the most recently added transition.
+pub struct GuardedMachineBuilder<S, C> {
+    transitions: Vec<Transition<S, C>>,
+}
+
+impl<S, C> GuardedMachineBuilder<S, C> {
+    /// Adds an unconditional transition without an action.
+    pub fn transition(mut self, from: S, to: S) -> Self {
+        self.transitions.push(Transition {
+            from,
+            to,
+            guard: Box::new(|_| true),
+            action: Box::new(|_| {}),
+        });
+        self
+    }
+
+    pub fn guard(mut self, guard: impl Fn(&C) -> bool + 'static) -> Self {
+        self.last_transition("guard").guard = Box::new(guard);
+        self
+    }
+
+    pub fn action(mut self, action: impl Fn(&mut C) + 'static) -> Self {
+        self.last_transition("action").action = Box::new(action);
+        self
+    }
+
+    pub fn build(self) -> GuardedMachine<S, C> {
+        GuardedMachine {
+            transitions: self.transitions,
+        }
+    }
+
+    fn last_transition(&mut self, method: &str) -> &mut Transition<S, C> {
+        self.transitions
+            .last_mut()
+            .unwrap_or_else(|| panic!("`{method}` must follow a `transition`"))
+    }
+}
+
+/// Builds a [`GuardedMachine`] from transitions `from -> to`, each optionally followed
+/// by `if guard` and `=> action`.
+#[macro_export]
+macro_rules! guarded_machine {
+    [ $( $from:tt -> $to:tt $(if $guard:expr)? $(=> $action:expr)? );* $(;)? ] => {
+        $crate::GuardedMachine::builder()
+            $(
+                .transition($from, $to)
+                $(.guard($guard))?
+                $(.action($action))?
+            )*
+            .build()
+    };
+}
+
+/// Number of steps after which [`run`] gives up.
+pub const DEFAULT_MAX_STEPS: usize = 10_000;
+
+/// How a run of a machine ended, together with the visited states in order,
+/// starting with the initial state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunResult<S> {
+    /// The machine has no successor for the last state of `path`.
+    Halted { path: Vec<S> },
+    /// The successor of the last state of `path` is `path[cycle_start]`.
+    Cycle { path: Vec<S>, cycle_start: usize },
+    /// The machine was still running after the allowed number of steps.
+    StepLimit { path: Vec<S> },
+}
+
+impl<S> RunResult<S> {
+    pub fn path(&self) -> &[S] {
+        match self {
+            Self::Halted { path } | Self::Cycle { path, .. } | Self::StepLimit { path } => path,
+        }
+    }
+}
+
+/// Steps `machine` from `start` until it halts or revisits a state,
+/// for at most [`DEFAULT_MAX_STEPS`] steps.
+pub fn run<S, M>(machine: &M, start: S) -> RunResult<S>
+where
+    S: Clone + Eq + Hash,
+    M: StateMachine<S> + ?Sized,
+{
+    run_with_limit(machine, start, DEFAULT_MAX_STEPS)
+}
+
+/// Like [`run`], but stops after `max_steps` steps.
+pub fn run_with_limit<S, M>(machine: &M, start: S, max_steps: usize) -> RunResult<S>
+where
+    S: Clone + Eq + Hash,
+    M: StateMachine<S> + ?Sized,
+{
+    let mut visited = HashMap::from([(start.clone(), 0)]);
+    let mut path = vec![start.clone()];
+    let mut state = start;
+
+    for _ in 0..max_steps {
+        let Some(next) = machine.step(state) else {
+            return RunResult::Halted { path };
+        };
+
+        if let Some(&cycle_start) = visited.get(&next) {
+            return RunResult::Cycle { path, cycle_start };
+        }
+
+        visited.insert(next.clone(), path.len());
+        path.push(next.clone());
+        state = next;
+    }
+
+    RunResult::StepLimit { path }
+}
+
 pub fn join_machines<S, M1, M2>(x: M1, y: M2) -> Vec<Box<dyn StateMachine<S>>>
 where
     S: 'static,
@@ -99,6 +304,210 @@ mod tests {
         assert_eq!(map.step("STOP"), None);
     }
 
+    #[test]
+    fn test_nfa_hashmap_implementation() {
+        let nfa = HashMap::from([(1, vec![2, 3]), (2, vec![4]), (3, vec![4, 4])]);
+
+        assert_eq!(nfa.step(1), HashSet::from([2, 3]));
+        assert_eq!(nfa.step(3), HashSet::from([4]));
+        assert_eq!(nfa.step(4), HashSet::new());
+    }
+
+    #[test]
+    fn test_powerset_construction() {
+        let nfa = HashMap::from([("a", vec!["a", "b"]), ("b", vec!["c"]), ("c", vec![])]);
+        let dfa = Powerset::new(nfa);
+
+        let start = BTreeSet::from(["a"]);
+        let second = dfa.step(start).unwrap();
+        assert_eq!(second, BTreeSet::from(["a", "b"]));
+        let third = dfa.step(second).unwrap();
+        assert_eq!(third, BTreeSet::from(["a", "b", "c"]));
+
+        assert_eq!(dfa.step(BTreeSet::from(["c"])), None);
+        assert_eq!(dfa.step(BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn test_powerset_joins_with_deterministic_machines() {
+        let nfa = HashMap::from([(1, vec![2, 3])]);
+        let dfa = HashMap::from([(BTreeSet::from([1]), BTreeSet::from([2]))]);
+
+        let joined = join_machines(Powerset::new(nfa), dfa);
+
+        assert_eq!(
+            joined[0].step(BTreeSet::from([1])),
+            Some(BTreeSet::from([2, 3]))
+        );
+        assert_eq!(
+            joined[1].step(BTreeSet::from([1])),
+            Some(BTreeSet::from([2]))
+        );
+    }
+
+    #[test]
+    fn test_run_until_halt() {
+        impl_state_machine!(Chain, [ 1 -> 2; 2 -> 3; 3 -> END ]);
+
+        assert_eq!(
+            run(&Chain::new(), 1),
+            RunResult::Halted {
+                path: vec![1, 2, 3, -1]
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_detects_cycle() {
+        let map = HashMap::from([("a", "b"), ("b", "c"), ("c", "b")]);
+
+        let result = run(&map, "a");
+        assert_eq!(
+            result,
+            RunResult::Cycle {
+                path: vec!["a", "b", "c"],
+                cycle_start: 1
+            }
+        );
+        assert_eq!(result.path(), ["a", "b", "c"]);
+
+        let self_loop = HashMap::from([(0, 0)]);
+        assert_eq!(
+            run(&self_loop, 0),
+            RunResult::Cycle {
+                path: vec![0],
+                cycle_start: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_step_limit() {
+        struct Counter;
+        impl StateMachine<u64> for Counter {
+            fn step(&self, state: u64) -> Option<u64> {
+                Some(state + 1)
+            }
+        }
+
+        assert_eq!(
+            run_with_limit(&Counter, 0, 3),
+            RunResult::StepLimit {
+                path: vec![0, 1, 2, 3]
+            }
+        );
+        assert_eq!(run(&Counter, 0).path().len(), DEFAULT_MAX_STEPS + 1);
+    }
+
+    #[test]
+    fn test_run_boxed_and_powerset_machines() {
+        let joined = join_machines(
+            Powerset::new(HashMap::from([(1, vec![2, 3]), (2, vec![4])])),
+            HashMap::from([(BTreeSet::from([1]), BTreeSet::from([1]))]),
+        );
+
+        assert_eq!(
+            run(joined[0].as_ref(), BTreeSet::from([1])),
+            RunResult::Halted {
+                path: vec![
+                    BTreeSet::from([1]),
+                    BTreeSet::from([2, 3]),
+                    BTreeSet::from([4]),
+                ]
+            }
+        );
+        assert!(matches!(
+            run(joined[1].as_ref(), BTreeSet::from([1])),
+            RunResult::Cycle { cycle_start: 0, .. }
+        ));
+    }
+
+    #[derive(Default)]
+    struct Turnstile {
+        input: &'static str,
+        coins: u32,
+        output: Vec<&'static str>,
+    }
+
+    fn turnstile() -> GuardedMachine<&'static str, Turnstile> {
+        GuardedMachine::builder()
+            .transition("locked", "unlocked")
+            .guard(|t: &Turnstile| t.input == "coin")
+            .action(|t| {
+                t.coins += 1;
+                t.output.push("unlock");
+            })
+            .transition("unlocked", "locked")
+            .guard(|t| t.input == "push")
+            .action(|t| t.output.push("lock"))
+            .transition("unlocked", "unlocked")
+            .guard(|t| t.input == "coin")
+            .action(|t| t.output.push("refund"))
+            .build()
+    }
+
+    #[test]
+    fn test_guarded_machine_builder() {
+        let machine = turnstile();
+        let mut context = Turnstile::default();
+        let mut state = "locked";
+
+        for input in ["push", "coin", "coin", "push"] {
+            context.input = input;
+            state = machine.fire(state, &mut context).unwrap_or(state);
+        }
+
+        assert_eq!(state, "locked");
+        assert_eq!(context.coins, 1);
+        assert_eq!(context.output, ["unlock", "refund", "lock"]);
+    }
+
+    #[test]
+    fn test_guarded_machine_first_enabled_transition_fires() {
+        let machine = GuardedMachine::builder()
+            .transition(0, 1)
+            .guard(|n: &i32| *n > 10)
+            .transition(0, 2)
+            .transition(0, 3)
+            .build();
+
+        assert_eq!(machine.fire(0, &mut 20), Some(1));
+        assert_eq!(machine.fire(0, &mut 5), Some(2));
+        assert_eq!(machine.fire(1, &mut 5), None);
+    }
+
+    #[test]
+    fn test_guarded_machine_macro_and_run() {
+        let machine: GuardedMachine<i32, ()> = guarded_machine![
+            1 -> 2;
+            2 -> 3 if |_| false;
+            2 -> 4;
+        ];
+        assert_eq!(
+            run(&machine, 1),
+            RunResult::Halted {
+                path: vec![1, 2, 4]
+            }
+        );
+
+        let counter = guarded_machine![
+            "even" -> "odd" if |n: &u32| *n < 3 => |n: &mut u32| *n += 1;
+            "odd" -> "even" => |n: &mut u32| *n += 1;
+        ];
+        let mut n = 0;
+        let mut state = "even";
+        while let Some(next) = counter.fire(state, &mut n) {
+            state = next;
+        }
+        assert_eq!((state, n), ("even", 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "`guard` must follow a `transition`")]
+    fn test_guarded_machine_builder_requires_transition() {
+        let _ = GuardedMachine::<i32, ()>::builder().guard(|_| true);
+    }
+
     #[test]
     fn test_join_machines() {
         impl_state_machine!(MachineA, [ 1 -> 2 ]);
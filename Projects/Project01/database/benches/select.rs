@@ -0,0 +1,87 @@
+//! Measures the cost of projecting the records of a `SELECT`.
+//!
+//! Run with `cargo bench --bench select`. The projection shares the values of
+//! the table's records; copying the records that way is compared against
+//! deep-cloning their values, as the projection did before records held their
+//! values in `Arc`s.
+
+use std::{borrow::Cow, collections::HashMap, hint::black_box, time::Instant};
+
+use database::{
+    QueryParser,
+    core::{DataValue, Database, Record},
+    execution::{ExecutionResult, build_execute_command},
+};
+
+/// The number of records in the benchmarked table.
+const ROWS: usize = 20_000;
+/// The length of the `STRING` and `BYTES` values of every record.
+const VALUE_LEN: usize = 256;
+/// The number of times each measurement is repeated.
+const ITERATIONS: u32 = 20;
+
+fn execute(database: &mut Database<i64>, query: &str) -> ExecutionResult {
+    let ast = QueryParser.parse_query(query).unwrap();
+    build_execute_command(database, ast)
+        .and_then(|mut command| command.execute())
+        .unwrap()
+}
+
+fn setup() -> Database<i64> {
+    let mut database = Database::default();
+    execute(
+        &mut database,
+        "CREATE docs KEY id FIELDS id: INT, title: STRING, body: BYTES;",
+    );
+
+    let table = database.tables.get_mut("docs").unwrap();
+    for id in 0..ROWS as i64 {
+        let fields = HashMap::from([
+            ("id".to_string(), DataValue::Int(id)),
+            (
+                "title".to_string(),
+                DataValue::String("t".repeat(VALUE_LEN)),
+            ),
+            ("body".to_string(), DataValue::Bytes(vec![0xAB; VALUE_LEN])),
+        ]);
+        table.insert_row(id, Record::new(fields)).unwrap();
+    }
+    database
+}
+
+/// Runs `f` `ITERATIONS` times and prints the average duration.
+fn measure(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let average = start.elapsed() / ITERATIONS;
+    println!("{name:<40} {average:>12.2?}");
+}
+
+fn main() {
+    let mut database = setup();
+    println!("Projecting {ROWS} record(s) with {VALUE_LEN}-byte values:");
+
+    measure("SELECT (shared values)", || {
+        black_box(execute(&mut database, "SELECT id, title, body FROM docs;"));
+    });
+
+    let rows = &database.tables["docs"].rows;
+    measure("copy of the records, sharing values", || {
+        black_box(rows.values().map(Cow::into_owned).collect::<Vec<_>>());
+    });
+    measure("copy of the records, cloning values", || {
+        let records = rows
+            .values()
+            .map(|record| {
+                record
+                    .fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.as_ref().clone()))
+                    .collect::<HashMap<_, _>>()
+            })
+            .collect::<Vec<_>>();
+        black_box(records);
+    });
+}
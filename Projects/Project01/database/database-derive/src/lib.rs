@@ -0,0 +1,72 @@
+//! The derive macros of the `database` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input};
+
+/// Derives `database::core::FromRecord` for a struct with named fields.
+///
+/// Every field is read with `Record::get_as` from the column of the same name,
+/// or of the name given with `#[record(rename = "...")]`.
+#[proc_macro_derive(FromRecord, attributes(record))]
+pub fn derive_from_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_record(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates the `FromRecord` implementation of `input`.
+fn from_record(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "FromRecord can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "FromRecord can only be derived for structs with named fields",
+        ));
+    };
+
+    let fields = fields
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            let column = column_name(field)?.unwrap_or_else(|| ident.to_string());
+            Ok(quote! { #ident: record.get_as(#column)? })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::database::core::FromRecord for #name #type_generics #where_clause {
+            fn from_record(
+                record: &::database::core::Record,
+            ) -> ::database::DatabaseResult<Self> {
+                Ok(Self { #(#fields),* })
+            }
+        }
+    })
+}
+
+/// Returns the column given with `#[record(rename = "...")]` to `field`, if any.
+fn column_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut column = None;
+    for attribute in field.attrs.iter().filter(|a| a.path().is_ident("record")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                column = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(column)
+}
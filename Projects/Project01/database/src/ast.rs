@@ -25,18 +25,56 @@ pub use statements::*;
 pub enum Command {
     /// A `CREATE TABLE` statement.
     Create(CreateStmt),
+    /// A `CREATE_INDEX` (secondary index) statement.
+    CreateIndex(CreateIndexStmt),
     /// An `INSERT INTO` statement.
     Insert(InsertStmt),
     /// A `DELETE FROM` statement.
     Delete(DeleteStmt),
+    /// A `GENERATE` (random records) statement.
+    Generate(GenerateStmt),
+    /// A `LOAD_CSV` (records from a CSV file) statement.
+    LoadCsv(LoadCsvStmt),
     /// A `SELECT` statement.
     Select(SelectStmt),
+    /// `SELECT` statements combined with `UNION`, `INTERSECT` or `EXCEPT`.
+    CompoundSelect(CompoundSelectStmt),
+    /// An `EXPLAIN` statement describing the plan of a `SELECT`.
+    Explain(ExplainStmt),
+    /// An `ANALYZE` (table statistics) statement.
+    Analyze(AnalyzeStmt),
+    /// A `SHOW TABLES` (list of tables) statement.
+    ShowTables(ShowTablesStmt),
+    /// An `UPDATE` statement.
+    Update(UpdateStmt),
+    /// A `TRUNCATE` (clear table) statement.
+    Truncate(TruncateStmt),
+    /// A `RENAME` (table) statement.
+    Rename(RenameStmt),
     /// A `SAVE_AS` (history) statement.
     SaveAs(SaveAsStmt),
     /// A `READ_FROM` (script) statement.
     ReadFrom(ReadFromStmt),
+    /// A `MIGRATE` (numbered scripts) statement.
+    Migrate(MigrateStmt),
     /// A `DUMP_TO` (binary) statement.
     DumpTo(DumpToStmt),
     /// A `LOAD_FROM` (binary) statement.
     LoadFrom(LoadFromStmt),
+    /// A `VERIFY` (dump checksum) statement.
+    Verify(VerifyStmt),
+    /// A `DUMP_TABLE` (binary, single table) statement.
+    DumpTable(DumpTableStmt),
+    /// A `LOAD_TABLE` (binary, single table) statement.
+    LoadTable(LoadTableStmt),
+    /// An `EXPORT_JSON` (JSON document) statement.
+    ExportJson(ExportJsonStmt),
+    /// An `IMPORT_JSON` (JSON document) statement.
+    ImportJson(ImportJsonStmt),
+    /// A `BEGIN`, `COMMIT` or `ROLLBACK` (transaction) statement.
+    Transaction(TransactionStmt),
+    /// A `CREATE_DATABASE` (named database) statement.
+    CreateDatabase(CreateDatabaseStmt),
+    /// A `USE` (switch database) statement.
+    Use(UseStmt),
 }
@@ -10,6 +10,18 @@ pub enum AstError {
     #[error("Unknown order: `{order}`")]
     UnknownOrder { order: String },
 
+    #[error("Unknown conflict action: `{action}`")]
+    UnknownConflictAction { action: String },
+
+    #[error("Unknown aggregate function: `{function}`")]
+    UnknownAggregate { function: String },
+
+    #[error("Unknown function: `{function}`")]
+    UnknownFunction { function: String },
+
+    #[error("Unknown set operator: `{operator}`")]
+    UnknownSetOperator { operator: String },
+
     #[error("Unknown operator: `{operator}`")]
     UnknownOperator { operator: String },
 
@@ -1,3 +1,7 @@
+mod dump_source;
+mod on_conflict;
 mod order_by;
 
+pub use dump_source::DumpSource;
+pub use on_conflict::ConflictAction;
 pub use order_by::{OrderBy, OrderDirection};
@@ -0,0 +1,9 @@
+/// Where a `LOAD_FROM` or `LOAD_TABLE` statement reads its snapshot from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpSource {
+    /// The path of a file written by `DUMP_TO` or `DUMP_TABLE`.
+    File(String),
+    /// The content of such a file, given as a bytes literal. The write-ahead log
+    /// records the snapshots that cannot be loaded again from a file this way.
+    Bytes(Vec<u8>),
+}
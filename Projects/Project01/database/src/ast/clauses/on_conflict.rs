@@ -0,0 +1,35 @@
+use crate::ast::AstError;
+
+/// Specifies what an `INSERT ... ON CONFLICT` does when a record with the
+/// same key already exists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictAction {
+    /// Replaces the existing record with the inserted one.
+    Replace,
+    /// Overwrites only the assigned fields of the existing record.
+    Update,
+}
+
+/// Enables parsing a `ConflictAction` from a string slice.
+///
+/// This is used by the parser to convert the "REPLACE" or "UPDATE" tokens
+/// into the corresponding enum variant.
+impl TryFrom<&str> for ConflictAction {
+    type Error = AstError;
+
+    /// Attempts to parse a string slice into a `ConflictAction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AstError::UnknownConflictAction` if the string is not
+    /// precisely "REPLACE" or "UPDATE".
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "REPLACE" => Ok(ConflictAction::Replace),
+            "UPDATE" => Ok(ConflictAction::Update),
+            action => Err(AstError::UnknownConflictAction {
+                action: action.to_string(),
+            }),
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use crate::ast::AstError;
+use std::fmt;
 
-/// Represents an `ORDER BY` clause, specifying a column and a sort direction.
+use crate::ast::{AstError, expressions::Expression};
+
+/// Represents an `ORDER BY` clause, specifying a sort key and a sort direction.
 #[derive(Debug, Clone, PartialEq)]
 pub struct OrderBy {
-    /// The name of the column to sort by.
-    pub column: String,
+    /// The expression computing the sort key of every record, e.g. a column name.
+    pub expression: Expression,
     /// The direction of the sort (Ascending or Descending).
     pub direction: OrderDirection,
 }
@@ -46,3 +48,12 @@ impl TryFrom<&str> for OrderDirection {
         Ok(order)
     }
 }
+
+impl fmt::Display for OrderDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Asc => write!(f, "ASC"),
+            Self::Desc => write!(f, "DESC"),
+        }
+    }
+}
@@ -1,9 +1,13 @@
 mod assignment;
 mod comparison;
 mod condition;
+mod expression;
 mod field;
+mod projection;
 
 pub use assignment::Assignment;
 pub use comparison::{Comparison, Operator};
 pub use condition::Condition;
+pub use expression::{ArithmeticOperator, Expression, ScalarFunction};
 pub use field::Field;
+pub use projection::{Aggregate, AggregateFunction, Projection, SelectItem};
@@ -1,9 +1,13 @@
+use std::fmt;
+
+use bincode::{Decode, Encode};
+
 use crate::{ast::AstError, core::DataValue};
 
 /// Represents an atomic comparison in a `WHERE` clause.
 ///
 /// e.g., `age > 21` or `name = "John"`
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub struct Comparison {
     /// The name of the column (field) on the left side of the comparison.
     pub field: String,
@@ -14,7 +18,7 @@ pub struct Comparison {
 }
 
 /// Represents a comparison operator (e.g., `=`, `!=`, `<`).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub enum Operator {
     /// Equals (`=`)
     Eq,
@@ -28,6 +32,9 @@ pub enum Operator {
     LtEq,
     /// Greater Than or Equals (`>=`)
     GtEq,
+    /// Pattern match on strings (`LIKE`), where `%` matches any sequence of
+    /// characters and `_` matches exactly one character.
+    Like,
 }
 
 impl Operator {
@@ -42,6 +49,7 @@ impl Operator {
     ///
     /// # Returns
     /// `true` if the comparison is successful, `false` otherwise.
+    /// `LIKE` only applies to strings (see [`Operator::like`]), so it is always `false` here.
     pub fn evaluate<T: PartialEq + PartialOrd>(&self, left: &T, right: &T) -> bool {
         match self {
             Self::Eq => left == right,
@@ -50,7 +58,44 @@ impl Operator {
             Self::Gt => left > right,
             Self::LtEq => left <= right,
             Self::GtEq => left >= right,
+            Self::Like => false,
+        }
+    }
+
+    /// Checks whether `value` matches a `LIKE` `pattern`.
+    ///
+    /// `%` matches any (possibly empty) sequence of characters and `_` matches
+    /// exactly one character. All other characters match themselves, case-sensitively.
+    pub fn like(value: &str, pattern: &str) -> bool {
+        let value = value.chars().collect::<Vec<_>>();
+        let pattern = pattern.chars().collect::<Vec<_>>();
+
+        let (mut v, mut p) = (0, 0);
+        // the position of the last `%` in the pattern and of the value character it is matched up to
+        let mut backtrack = None;
+
+        while v < value.len() {
+            match pattern.get(p) {
+                Some('%') => {
+                    backtrack = Some((p, v));
+                    p += 1;
+                }
+                Some(c) if *c == '_' || *c == value[v] => {
+                    v += 1;
+                    p += 1;
+                }
+                _ => match backtrack {
+                    Some((percent, matched)) => {
+                        backtrack = Some((percent, matched + 1));
+                        p = percent + 1;
+                        v = matched + 1;
+                    }
+                    None => return false,
+                },
+            }
         }
+
+        pattern[p..].iter().all(|c| *c == '%')
     }
 }
 
@@ -75,6 +120,7 @@ impl TryFrom<&str> for Operator {
             ">" => Self::Gt,
             "<=" => Self::LtEq,
             ">=" => Self::GtEq,
+            "LIKE" => Self::Like,
             // Handle any unrecognized operator string
             _ => {
                 return Err(AstError::UnknownOperator {
@@ -85,3 +131,18 @@ impl TryFrom<&str> for Operator {
         Ok(operator)
     }
 }
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::Eq => "=",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::LtEq => "<=",
+            Self::GtEq => ">=",
+            Self::Like => "LIKE",
+        };
+        write!(f, "{symbol}")
+    }
+}
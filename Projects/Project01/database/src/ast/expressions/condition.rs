@@ -1,11 +1,17 @@
+use std::fmt;
+
+use bincode::{Decode, Encode};
+
+use crate::core::DataValue;
+
 use super::Comparison;
 
 /// Represents a recursive condition for a `WHERE` clause.
 ///
 /// This enum forms a tree structure that allows for combining simple comparisons
-/// using logical `AND` and `OR` operators, enabling complex filtering logic
-/// like `(age > 21 OR name = "John") AND id < 100`.
-#[derive(Debug, Clone, PartialEq)]
+/// using logical `NOT`, `AND` and `OR` operators, enabling complex filtering logic
+/// like `(age > 21 OR name = "John") AND NOT id < 100`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub enum Condition {
     /// Represents a logical `OR` operation between two sub-conditions.
     ///
@@ -25,8 +31,57 @@ pub enum Condition {
         /// The right-hand side of the `AND` operation.
         right: Box<Condition>,
     },
+    /// Represents a logical `NOT` of a sub-condition.
+    ///
+    /// The condition is true only if the inner condition is false.
+    Not(Box<Condition>),
     /// A leaf node in the condition tree, representing a single, atomic comparison.
     ///
     /// e.g., `age > 21`
     Comparison(Comparison),
+    /// A leaf node testing whether a field is equal to any value in a list.
+    ///
+    /// e.g., `status IN ("active", "pending")`
+    In {
+        /// The name of the column (field) being tested.
+        field: String,
+        /// The literal values the field is compared against.
+        values: Vec<DataValue>,
+    },
+}
+
+impl Condition {
+    /// Returns the names of all columns the condition refers to.
+    pub fn columns(&self) -> Vec<&String> {
+        match self {
+            Self::Or { left, right } | Self::And { left, right } => {
+                let mut columns = left.columns();
+                columns.extend(right.columns());
+                columns
+            }
+            Self::Not(condition) => condition.columns(),
+            Self::Comparison(comparison) => vec![&comparison.field],
+            Self::In { field, .. } => vec![field],
+        }
+    }
+}
+
+/// Renders the condition in query syntax, parenthesizing every `AND` and `OR`.
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Or { left, right } => write!(f, "({left} OR {right})"),
+            Self::And { left, right } => write!(f, "({left} AND {right})"),
+            Self::Not(condition) => write!(f, "NOT {condition}"),
+            Self::Comparison(Comparison { field, op, value }) => write!(f, "{field} {op} {value}"),
+            Self::In { field, values } => {
+                let values = values
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "{field} IN ({values})")
+            }
+        }
+    }
 }
@@ -0,0 +1,192 @@
+use std::fmt;
+
+use crate::{
+    ast::{AstError, Condition},
+    core::DataValue,
+};
+
+/// Represents a scalar expression computed for every record, e.g. `age * -1`,
+/// `LENGTH(name)` or `CASE WHEN age >= 18 THEN "adult" ELSE "minor" END`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// The value of a column of the record.
+    Column(String),
+    /// A literal value.
+    Literal(DataValue),
+    /// An arithmetic operation on two sub-expressions.
+    Binary {
+        /// The left-hand side of the operation.
+        left: Box<Expression>,
+        /// The arithmetic operator.
+        op: ArithmeticOperator,
+        /// The right-hand side of the operation.
+        right: Box<Expression>,
+    },
+    /// A scalar function applied to a sub-expression.
+    Function {
+        /// The function to apply.
+        function: ScalarFunction,
+        /// The argument of the function.
+        argument: Box<Expression>,
+    },
+    /// A conditional expression taking the result of the first `WHEN` branch
+    /// whose condition holds, or of the `ELSE` branch if none does.
+    Case {
+        /// The `WHEN` conditions with their `THEN` results, in query order.
+        branches: Vec<(Condition, Expression)>,
+        /// The `ELSE` result.
+        otherwise: Box<Expression>,
+    },
+}
+
+impl Expression {
+    /// Returns the names of all columns the expression refers to.
+    pub fn columns(&self) -> Vec<&String> {
+        match self {
+            Self::Column(name) => vec![name],
+            Self::Literal(_) => Vec::new(),
+            Self::Binary { left, right, .. } => {
+                let mut columns = left.columns();
+                columns.extend(right.columns());
+                columns
+            }
+            Self::Function { argument, .. } => argument.columns(),
+            Self::Case {
+                branches,
+                otherwise,
+            } => {
+                let mut columns = Vec::new();
+                for (condition, result) in branches {
+                    columns.extend(condition.columns());
+                    columns.extend(result.columns());
+                }
+                columns.extend(otherwise.columns());
+                columns
+            }
+        }
+    }
+}
+
+/// Represents an arithmetic operator (e.g., `+`, `*`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArithmeticOperator {
+    /// Addition (`+`)
+    Add,
+    /// Subtraction (`-`)
+    Sub,
+    /// Multiplication (`*`)
+    Mul,
+    /// Division (`/`)
+    Div,
+}
+
+/// Specifies the scalar functions available in expressions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalarFunction {
+    /// The number of characters of a `String`, or of bytes of `Bytes`, as an `Int`.
+    Length,
+    /// The absolute value of an `Int` or a `Float`.
+    Abs,
+    /// A `String` converted to upper case.
+    Upper,
+    /// A `String` converted to lower case.
+    Lower,
+}
+
+/// Enables parsing an `ArithmeticOperator` from a raw string slice.
+impl TryFrom<&str> for ArithmeticOperator {
+    type Error = AstError;
+
+    /// Attempts to parse a string slice (e.g., `"+"`) into an `ArithmeticOperator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AstError::UnknownOperator` if the string is not one of the
+    /// known operators.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let operator = match value {
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            _ => {
+                return Err(AstError::UnknownOperator {
+                    operator: value.to_string(),
+                });
+            }
+        };
+        Ok(operator)
+    }
+}
+
+/// Enables parsing a `ScalarFunction` from a string slice.
+impl TryFrom<&str> for ScalarFunction {
+    type Error = AstError;
+
+    /// Attempts to parse a string slice (e.g., `"LENGTH"`) into a `ScalarFunction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AstError::UnknownFunction` if the string is not one of the
+    /// known function names.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let function = match value {
+            "LENGTH" => Self::Length,
+            "ABS" => Self::Abs,
+            "UPPER" => Self::Upper,
+            "LOWER" => Self::Lower,
+            function => {
+                return Err(AstError::UnknownFunction {
+                    function: function.to_string(),
+                });
+            }
+        };
+        Ok(function)
+    }
+}
+
+impl fmt::Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl fmt::Display for ScalarFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Length => "LENGTH",
+            Self::Abs => "ABS",
+            Self::Upper => "UPPER",
+            Self::Lower => "LOWER",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Renders the expression in query syntax, parenthesizing every binary operation.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Column(name) => write!(f, "{name}"),
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Binary { left, op, right } => write!(f, "({left} {op} {right})"),
+            Self::Function { function, argument } => write!(f, "{function}({argument})"),
+            Self::Case {
+                branches,
+                otherwise,
+            } => {
+                write!(f, "CASE")?;
+                for (condition, result) in branches {
+                    write!(f, " WHEN {condition} THEN {result}")?;
+                }
+                write!(f, " ELSE {otherwise} END")
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
-use crate::core::DataType;
+use crate::core::{DataType, DataValue};
 
 /// Represents a field definition within a table's schema.
 ///
-/// e.g., `name: STRING` or `id: INT`
+/// e.g., `name: STRING`, `age: INT DEFAULT 0` or `status: ENUM("new", "done")`
 #[derive(Debug, Clone, PartialEq)]
 pub struct Field {
     /// The name of the column (field).
     pub name: String,
     /// The data type (e.g., `INT`, `STRING`) associated with this field.
+    /// An `ENUM` field is a `STRING` field with `variants`.
     pub data_type: DataType,
+    /// The values allowed by an `ENUM` field, or `None` for other fields.
+    pub variants: Option<Vec<String>>,
+    /// The optional value used by `INSERT` when the field is not assigned.
+    pub default: Option<DataValue>,
 }
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::ast::{AstError, Expression};
+
+/// Represents a single item of the `SELECT` list together with its optional alias.
+///
+/// e.g., `name`, `name AS username` or `AVG(age) AS average_age`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectItem {
+    /// The selected column, aggregate or expression.
+    pub projection: Projection,
+    /// The optional `AS` alias naming the resulting column.
+    pub alias: Option<String>,
+}
+
+impl SelectItem {
+    /// Returns the name of the resulting column: the alias if there is one,
+    /// otherwise the projection as written in the query.
+    pub fn output_name(&self) -> String {
+        match &self.alias {
+            Some(alias) => alias.clone(),
+            None => self.projection.to_string(),
+        }
+    }
+}
+
+/// Creates an item without an alias.
+impl From<Projection> for SelectItem {
+    fn from(projection: Projection) -> Self {
+        Self {
+            projection,
+            alias: None,
+        }
+    }
+}
+
+/// Represents the column, aggregate or expression of a `SELECT` list item.
+///
+/// e.g., `name`, `AVG(age)` or `CASE WHEN age >= 18 THEN "adult" ELSE "minor" END`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Projection {
+    /// A column copied from every selected record.
+    Field(String),
+    /// An aggregate folding all selected records into a single value.
+    Aggregate(Aggregate),
+    /// An expression computed for every selected record.
+    Expression(Expression),
+}
+
+/// Represents an aggregate function applied to a column, e.g. `SUM(age)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    /// The function computing the result.
+    pub function: AggregateFunction,
+    /// The column the function is applied to, or `None` for `COUNT(*)`.
+    pub field: Option<String>,
+}
+
+/// Specifies the aggregate functions available in a `SELECT` list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggregateFunction {
+    /// The number of records, as an `Int`.
+    Count,
+    /// The sum of a numeric column, of the column's type.
+    Sum,
+    /// The mean of a numeric column, as a `Float`.
+    Avg,
+    /// The smallest value of a column.
+    Min,
+    /// The largest value of a column.
+    Max,
+}
+
+/// Enables parsing an `AggregateFunction` from a string slice.
+///
+/// This is used by the parser to convert the "COUNT", "SUM", "AVG", "MIN"
+/// or "MAX" tokens into the corresponding enum variant.
+impl TryFrom<&str> for AggregateFunction {
+    type Error = AstError;
+
+    /// Attempts to parse a string slice into an `AggregateFunction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AstError::UnknownAggregate` if the string is not one of the
+    /// known function names.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let function = match value {
+            "COUNT" => Self::Count,
+            "SUM" => Self::Sum,
+            "AVG" => Self::Avg,
+            "MIN" => Self::Min,
+            "MAX" => Self::Max,
+            function => {
+                return Err(AstError::UnknownAggregate {
+                    function: function.to_string(),
+                });
+            }
+        };
+
+        Ok(function)
+    }
+}
+
+impl fmt::Display for AggregateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Count => "COUNT",
+            Self::Sum => "SUM",
+            Self::Avg => "AVG",
+            Self::Min => "MIN",
+            Self::Max => "MAX",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Renders the projection the way it was written in the query, which is also
+/// the name of the resulting column (e.g. `COUNT(*)`).
+impl fmt::Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(name) => write!(f, "{name}"),
+            Self::Aggregate(Aggregate { function, field }) => {
+                write!(f, "{function}({})", field.as_deref().unwrap_or("*"))
+            }
+            Self::Expression(expression) => write!(f, "{expression}"),
+        }
+    }
+}
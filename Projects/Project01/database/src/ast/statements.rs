@@ -1,17 +1,55 @@
+mod analyze;
+mod compound_select;
 mod create;
+mod create_database;
+mod create_index;
 mod delete;
+mod dump_table;
 mod dump_to;
+mod explain;
+mod export_json;
+mod generate;
+mod import_json;
 mod insert;
+mod load_csv;
 mod load_from;
+mod load_table;
+mod migrate;
 mod read_from;
+mod rename;
 mod save_as;
 mod select;
+mod show_tables;
+mod transaction;
+mod truncate;
+mod update;
+mod use_database;
+mod verify;
 
+pub use analyze::AnalyzeStmt;
+pub use compound_select::{CompoundSelectStmt, SetOperator};
 pub use create::CreateStmt;
+pub use create_database::CreateDatabaseStmt;
+pub use create_index::CreateIndexStmt;
 pub use delete::DeleteStmt;
+pub use dump_table::DumpTableStmt;
 pub use dump_to::DumpToStmt;
+pub use explain::ExplainStmt;
+pub use export_json::ExportJsonStmt;
+pub use generate::GenerateStmt;
+pub use import_json::ImportJsonStmt;
 pub use insert::InsertStmt;
+pub use load_csv::LoadCsvStmt;
 pub use load_from::LoadFromStmt;
+pub use load_table::LoadTableStmt;
+pub use migrate::MigrateStmt;
 pub use read_from::ReadFromStmt;
+pub use rename::RenameStmt;
 pub use save_as::SaveAsStmt;
 pub use select::{OptionalClauses, SelectStmt};
+pub use show_tables::ShowTablesStmt;
+pub use transaction::TransactionStmt;
+pub use truncate::TruncateStmt;
+pub use update::UpdateStmt;
+pub use use_database::UseStmt;
+pub use verify::VerifyStmt;
@@ -0,0 +1,11 @@
+/// Represents an `ANALYZE` statement.
+///
+/// This struct holds the information required to compute the statistics
+/// of a table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeStmt {
+    /// The name of the table to analyze.
+    pub table_name: String,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
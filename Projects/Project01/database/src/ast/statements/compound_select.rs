@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::ast::{AstError, SelectStmt};
+
+/// Represents `SELECT` statements combined with set operators.
+///
+/// e.g., `SELECT id FROM admins UNION SELECT id FROM users WHERE age > 30`
+///
+/// The operators are applied from left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompoundSelectStmt {
+    /// The leftmost `SELECT` statement.
+    pub first: SelectStmt,
+    /// The remaining `SELECT` statements, each with the operator combining it
+    /// with the result of the statements before it.
+    pub rest: Vec<(SetOperator, SelectStmt)>,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
+
+/// Specifies how the records of two `SELECT` statements are combined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOperator {
+    /// The records returned by either statement.
+    Union,
+    /// The records returned by both statements.
+    Intersect,
+    /// The records returned by the left statement but not by the right one.
+    Except,
+}
+
+/// Enables parsing a `SetOperator` from a string slice.
+///
+/// This is used by the parser to convert the "UNION", "INTERSECT" or "EXCEPT"
+/// tokens into the corresponding enum variant.
+impl TryFrom<&str> for SetOperator {
+    type Error = AstError;
+
+    /// Attempts to parse a string slice into a `SetOperator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AstError::UnknownSetOperator` if the string is not one of the
+    /// known operators.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "UNION" => Ok(Self::Union),
+            "INTERSECT" => Ok(Self::Intersect),
+            "EXCEPT" => Ok(Self::Except),
+            operator => Err(AstError::UnknownSetOperator {
+                operator: operator.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Union => "UNION",
+            Self::Intersect => "INTERSECT",
+            Self::Except => "EXCEPT",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -1,9 +1,13 @@
-use crate::ast::expressions::Field;
+use crate::{
+    ast::expressions::{Condition, Field},
+    core::TableStorage,
+};
 
 /// Represents a `CREATE TABLE` SQL statement.
 ///
 /// This struct holds all the parsed information required to create a new table,
-/// including its name, the designated primary key, and the list of all its fields.
+/// including its name, the designated primary key, the list of all its fields,
+/// its `CHECK` constraints and where its records are stored.
 #[derive(Debug, Clone, PartialEq)]
 pub struct CreateStmt {
     /// The name of the table to be created.
@@ -12,6 +16,12 @@ pub struct CreateStmt {
     pub key_field: String,
     /// A vector of `Field` definitions specifying the schema (all columns and their types).
     pub fields: Vec<Field>,
+    /// The `CHECK` conditions every record of the table must satisfy.
+    /// e.g., `CHECK (age >= 0 AND age < 150)`
+    pub checks: Vec<Condition>,
+    /// Where the records are stored, chosen with `STORAGE DISK` or `STORAGE MEMORY`
+    /// (the default).
+    pub storage: TableStorage,
     /// The raw, original query string that was parsed to create this statement.
     pub query: String,
 }
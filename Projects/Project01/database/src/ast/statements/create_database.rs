@@ -0,0 +1,10 @@
+/// Represents a `CREATE_DATABASE` statement.
+///
+/// This struct holds the name of the database to add to the catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateDatabaseStmt {
+    /// The name of the new database.
+    pub name: String,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
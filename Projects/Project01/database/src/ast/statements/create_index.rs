@@ -0,0 +1,19 @@
+use crate::core::IndexKind;
+
+/// Represents a `CREATE_INDEX` statement.
+///
+/// This struct holds the information required to build a secondary index
+/// over one column of an existing table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateIndexStmt {
+    /// The name of the new index.
+    pub index_name: String,
+    /// The name of the table to index.
+    pub table_name: String,
+    /// The name of the indexed column.
+    pub field: String,
+    /// The data structure backing the index, `IndexKind::Hash` with `USING HASH`.
+    pub kind: IndexKind,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
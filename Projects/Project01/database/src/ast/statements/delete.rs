@@ -10,6 +10,9 @@ pub struct DeleteStmt {
     pub table_name: String,
     /// The primary key value of the record to be deleted.
     pub key_value: DataValue,
+    /// The optional `RETURNING` fields. When present, the deleted record is
+    /// returned with these fields instead of a row count.
+    pub returning: Option<Vec<String>>,
     /// The raw, original query string that was parsed to create this statement.
     pub query: String,
 }
@@ -0,0 +1,11 @@
+/// Represents a `DUMP_TABLE` statement.
+///
+/// This struct holds the name of the table whose binary snapshot should be
+/// saved and the file path it is saved to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpTableStmt {
+    /// The name of the table to dump.
+    pub table_name: String,
+    /// The target file path for the table dump.
+    pub path: String,
+}
@@ -1,9 +1,11 @@
 /// Represents a `DUMP_TO` statement.
 ///
 /// This struct holds the file path where the database's binary snapshot
-/// should be saved.
+/// should be saved, and the passphrase it is encrypted with, if any.
 #[derive(Debug, Clone, PartialEq)]
 pub struct DumpToStmt {
     /// The target file path for the database dump.
     pub path: String,
+    /// The passphrase given with `ENCRYPTED WITH`, or `None` for a plain dump.
+    pub passphrase: Option<String>,
 }
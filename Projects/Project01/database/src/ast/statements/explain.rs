@@ -0,0 +1,13 @@
+use crate::ast::statements::SelectStmt;
+
+/// Represents an `EXPLAIN` statement.
+///
+/// This struct holds the `SELECT` statement whose execution plan is described
+/// instead of running it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainStmt {
+    /// The `SELECT` statement to explain.
+    pub select: SelectStmt,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -0,0 +1,9 @@
+/// Represents an `EXPORT_JSON` statement.
+///
+/// This struct holds the file path where the database's JSON document
+/// should be saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportJsonStmt {
+    /// The target file path for the JSON document.
+    pub path: String,
+}
@@ -0,0 +1,15 @@
+/// Represents a `GENERATE` statement.
+///
+/// This struct holds the information required to fill a table with random
+/// records conforming to its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerateStmt {
+    /// The name of the table to fill.
+    pub table_name: String,
+    /// The number of records to generate.
+    pub count: usize,
+    /// The optional `SEED` making the generated records reproducible.
+    pub seed: Option<u64>,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -0,0 +1,9 @@
+/// Represents an `IMPORT_JSON` statement.
+///
+/// This struct holds the file path of the JSON document from which the
+/// database should be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportJsonStmt {
+    /// The source file path of the JSON document.
+    pub path: String,
+}
@@ -1,4 +1,4 @@
-use crate::ast::expressions::Assignment;
+use crate::ast::{clauses::ConflictAction, expressions::Assignment};
 
 /// Represents an `INSERT INTO` SQL statement.
 ///
@@ -12,6 +12,12 @@ pub struct InsertStmt {
     /// A vector of `Assignment` structs (e.g., `name = "John"`)
     /// specifying the values for the new record.
     pub assignments: Vec<Assignment>,
+    /// The optional `ON CONFLICT` action taken when the key is already in use.
+    /// Without it, inserting a duplicate key is an error.
+    pub on_conflict: Option<ConflictAction>,
+    /// The optional `RETURNING` fields. When present, the inserted record is
+    /// returned with these fields instead of a row count.
+    pub returning: Option<Vec<String>>,
     /// The raw, original query string that was parsed to create this statement.
     pub query: String,
 }
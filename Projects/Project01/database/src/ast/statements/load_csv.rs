@@ -0,0 +1,13 @@
+/// Represents a `LOAD_CSV` statement.
+///
+/// This struct holds the information required to insert the records of a
+/// CSV file into an existing table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadCsvStmt {
+    /// The path of the CSV file, whose first row names the columns.
+    pub path: String,
+    /// The name of the table to insert the records into.
+    pub table_name: String,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -1,9 +1,14 @@
+use crate::ast::clauses::DumpSource;
+
 /// Represents a `LOAD_FROM` statement.
 ///
-/// This struct holds the file path from which a binary database snapshot
-/// should be loaded, replacing the current database state.
+/// This struct holds the source from which a binary database snapshot
+/// should be loaded, replacing the current database state, and the passphrase
+/// an encrypted snapshot is decrypted with.
 #[derive(Debug, Clone, PartialEq)]
 pub struct LoadFromStmt {
-    /// The source file path for the database snapshot.
-    pub path: String,
+    /// The source file path, or the content, of the database snapshot.
+    pub source: DumpSource,
+    /// The passphrase given with `WITH`, required for an encrypted snapshot.
+    pub passphrase: Option<String>,
 }
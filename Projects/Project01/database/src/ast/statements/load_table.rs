@@ -0,0 +1,16 @@
+use crate::ast::clauses::{ConflictAction, DumpSource};
+
+/// Represents a `LOAD_TABLE` statement.
+///
+/// This struct holds the information required to add a table saved by
+/// `DUMP_TABLE` to the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadTableStmt {
+    /// Where the table dump is read from.
+    pub source: DumpSource,
+    /// The optional `ON CONFLICT` action taken when a table with the same name
+    /// already exists. Without it, loading such a table is an error.
+    pub on_conflict: Option<ConflictAction>,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -0,0 +1,9 @@
+/// Represents a `MIGRATE` statement.
+///
+/// This struct holds the path of the directory containing the numbered
+/// migration scripts to be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrateStmt {
+    /// The directory holding the migration scripts.
+    pub path: String,
+}
@@ -0,0 +1,12 @@
+/// Represents a `RENAME` statement.
+///
+/// This struct holds the information required to give an existing table a new name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameStmt {
+    /// The current name of the table.
+    pub table_name: String,
+    /// The name the table will have afterwards.
+    pub new_name: String,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -1,19 +1,22 @@
-use crate::ast::{clauses::OrderBy, expressions::Condition};
+use crate::ast::{
+    clauses::OrderBy,
+    expressions::{Condition, SelectItem},
+};
 
 /// Represents a `SELECT` SQL statement.
 ///
 /// This struct holds all parsed information for a query, including the columns
 /// to return (`fields`), the table to query (`table_name`), and any
-/// optional clauses like `WHERE`, `ORDER BY`, or `LIMIT`.
+/// optional clauses like `WHERE`, `ORDER BY`, `LIMIT` or `OFFSET`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SelectStmt {
-    /// A vector of column names to be selected.
-    /// e.g., `["id", "name"]`
-    pub fields: Vec<String>,
+    /// A vector of the columns or aggregates to be selected, with their aliases.
+    /// e.g., `id, name AS username`
+    pub fields: Vec<SelectItem>,
     /// The name of the table to query.
     /// e.g., `"people"`
     pub table_name: String,
-    /// A struct containing all optional clauses (`WHERE`, `ORDER BY`, `LIMIT`).
+    /// A struct containing all optional clauses (`WHERE`, `ORDER BY`, `LIMIT`, `OFFSET`).
     pub optional_clauses: OptionalClauses,
     /// The raw, original query string that was parsed to create this statement.
     pub query: String,
@@ -28,4 +31,7 @@ pub struct OptionalClauses {
     pub order_by: Option<OrderBy>,
     /// The optional `LIMIT` clause, specifying the maximum number of rows to return.
     pub limit: Option<i64>,
+    /// The optional `OFFSET` clause, specifying the number of rows to skip
+    /// before `LIMIT` is applied.
+    pub offset: Option<i64>,
 }
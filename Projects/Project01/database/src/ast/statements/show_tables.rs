@@ -0,0 +1,6 @@
+/// Represents a `SHOW TABLES` statement.
+///
+/// The statement lists every table of the current database, so it holds no
+/// arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTablesStmt;
@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// Represents a statement controlling a transaction.
+///
+/// The queries executed between a `BEGIN` and a `COMMIT` are applied together,
+/// or not at all if the transaction ends with a `ROLLBACK`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStmt {
+    /// `BEGIN`, which starts a transaction.
+    Begin,
+    /// `COMMIT`, which ends the transaction and keeps its changes.
+    Commit,
+    /// `ROLLBACK`, which ends the transaction and undoes its changes.
+    Rollback,
+}
+
+impl fmt::Display for TransactionStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            Self::Begin => "BEGIN",
+            Self::Commit => "COMMIT",
+            Self::Rollback => "ROLLBACK",
+        };
+        write!(f, "{keyword};")
+    }
+}
@@ -0,0 +1,11 @@
+/// Represents a `TRUNCATE` statement.
+///
+/// This struct holds the information required to remove every record from a
+/// table while keeping its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncateStmt {
+    /// The name of the table to clear.
+    pub table_name: String,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
@@ -0,0 +1,22 @@
+use crate::ast::expressions::{Assignment, Condition};
+
+/// Represents an `UPDATE` SQL statement.
+///
+/// This struct holds all the information required to modify existing records,
+/// including the target table, the new column values and an optional filter
+/// selecting the records to modify.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateStmt {
+    /// The name of the table whose records are updated.
+    pub table_name: String,
+    /// A vector of `Assignment` structs (e.g., `age = 22`)
+    /// specifying the new values of the modified columns.
+    pub assignments: Vec<Assignment>,
+    /// The optional `WHERE` clause. Without it every record is updated.
+    pub where_clause: Option<Condition>,
+    /// The optional `RETURNING` fields. When present, the updated records are
+    /// returned with these fields instead of a row count.
+    pub returning: Option<Vec<String>>,
+    /// The raw, original query string that was parsed to create this statement.
+    pub query: String,
+}
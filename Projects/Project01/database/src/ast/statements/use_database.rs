@@ -0,0 +1,18 @@
+use std::fmt;
+
+/// Represents a `USE` statement.
+///
+/// This struct holds the name of the database the following queries of the
+/// session are executed on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseStmt {
+    /// The name of the database to switch to.
+    pub name: String,
+}
+
+/// Formats the statement as a query, as it is recorded in the write-ahead log.
+impl fmt::Display for UseStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "USE {};", self.name)
+    }
+}
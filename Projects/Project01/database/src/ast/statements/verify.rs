@@ -0,0 +1,11 @@
+/// Represents a `VERIFY` statement.
+///
+/// This struct holds the path of the dump to be checked, and the passphrase
+/// it is encrypted with, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyStmt {
+    /// The file path of the dump written by `DUMP_TO` or `DUMP_TABLE`.
+    pub path: String,
+    /// The passphrase given with `WITH`, or `None` for a plain dump.
+    pub passphrase: Option<String>,
+}
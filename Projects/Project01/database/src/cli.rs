@@ -1,7 +1,58 @@
-use clap::{Arg, Command, value_parser};
+mod config;
+
+pub use config::ConfigError;
+
+use std::{ffi::OsString, path::PathBuf};
+
+use clap::{Arg, ArgMatches, Command, ValueEnum, parser::ValueSource, value_parser};
 
 use crate::core::DatabaseType;
 
+use config::Config;
+
+/// The port used by `serve` when none is given on the command line or in the config file.
+pub const DEFAULT_PORT: u16 = 5544;
+
+/// Specifies how the file given with `--load` is read at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LoadFormat {
+    /// A binary snapshot, as written by `DUMP_TO`.
+    Binary,
+    /// A script of queries, as written by `SAVE_AS`.
+    Script,
+}
+
+/// The frontend selected with a subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frontend {
+    /// The interactive Read-Eval-Print Loop in the terminal.
+    Repl,
+    /// The `ratatui` Text User Interface.
+    Tui,
+    /// Executes the queries from a script and exits.
+    Exec { file: PathBuf },
+    /// Accepts queries from network clients on the given port.
+    Serve { port: u16 },
+}
+
+/// The result of parsing the command-line arguments.
+///
+/// Values missing from the command line are taken from the `--config` file
+/// and then from the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The primary key type of the database.
+    pub database_type: DatabaseType,
+    /// A file loaded into the database before the frontend starts.
+    pub load: Option<PathBuf>,
+    /// The format of the `load` file.
+    pub format: LoadFormat,
+    /// The write-ahead log replayed at startup and extended by every change.
+    pub wal: Option<PathBuf>,
+    /// The selected frontend, or `None` if no subcommand was given.
+    pub frontend: Option<Frontend>,
+}
+
 /// A utility struct responsible for building and parsing command-line
 /// interface (CLI) arguments using [`clap`].
 #[derive(Debug)]
@@ -15,31 +66,281 @@ impl Default for Cli {
 }
 
 impl Cli {
-    /// Builds the CLI, parses the command-line arguments, and returns the selected `DatabaseType`.
-    pub fn get_type(&self) -> DatabaseType {
-        let cli = self.build_cli();
-        let matches = cli.get_matches();
-        *matches.get_one::<DatabaseType>(Self::ARG_TYPE).unwrap()
+    /// Builds the CLI and parses the arguments of the current process.
+    ///
+    /// Exits the process if the arguments are invalid or help was requested.
+    ///
+    /// # Errors
+    /// Returns `ConfigError` if the file given with `--config` cannot be read or parsed.
+    pub fn parse(&self) -> Result<Options, ConfigError> {
+        let matches = self.build_cli().get_matches();
+        self.options_from(&matches)
+    }
+
+    /// Parses the given arguments, the first of which is the binary name.
+    ///
+    /// # Errors
+    /// Returns a `clap` error rendered as a diagnostic if the arguments are invalid,
+    /// or a `ConfigError` if the config file cannot be read or parsed.
+    pub fn try_parse_from<I, T>(&self, args: I) -> miette::Result<Options>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = self
+            .build_cli()
+            .try_get_matches_from(args)
+            .map_err(|e| miette::miette!("{e}"))?;
+        Ok(self.options_from(&matches)?)
     }
 
     const ARG_TYPE: &'static str = "type";
+    const ARG_LOAD: &'static str = "load";
+    const ARG_FORMAT: &'static str = "format";
+    const ARG_WAL: &'static str = "wal";
+    const ARG_CONFIG: &'static str = "config";
+    const ARG_FILE: &'static str = "file";
+    const ARG_PORT: &'static str = "port";
+
+    const CMD_REPL: &'static str = "repl";
+    const CMD_TUI: &'static str = "tui";
+    const CMD_EXEC: &'static str = "exec";
+    const CMD_SERVE: &'static str = "serve";
+
+    /// Combines the parsed arguments with the config file into `Options`.
+    fn options_from(&self, matches: &ArgMatches) -> Result<Options, ConfigError> {
+        let config = match matches.get_one::<PathBuf>(Self::ARG_CONFIG) {
+            Some(path) => Config::read(path)?,
+            None => Config::default(),
+        };
+
+        let database_type = Self::explicit(matches, Self::ARG_TYPE)
+            .or(config.database_type)
+            .unwrap_or_else(|| *matches.get_one(Self::ARG_TYPE).unwrap());
+        let load = matches
+            .get_one::<PathBuf>(Self::ARG_LOAD)
+            .cloned()
+            .or(config.load);
+        let format = Self::explicit(matches, Self::ARG_FORMAT)
+            .or(config.format)
+            .unwrap_or_else(|| *matches.get_one(Self::ARG_FORMAT).unwrap());
+        let wal = matches
+            .get_one::<PathBuf>(Self::ARG_WAL)
+            .cloned()
+            .or(config.wal);
+
+        let frontend = match matches.subcommand() {
+            Some((Self::CMD_REPL, _)) => Some(Frontend::Repl),
+            Some((Self::CMD_TUI, _)) => Some(Frontend::Tui),
+            Some((Self::CMD_EXEC, sub_matches)) => Some(Frontend::Exec {
+                file: sub_matches
+                    .get_one::<PathBuf>(Self::ARG_FILE)
+                    .unwrap()
+                    .clone(),
+            }),
+            Some((Self::CMD_SERVE, sub_matches)) => Some(Frontend::Serve {
+                port: sub_matches
+                    .get_one::<u16>(Self::ARG_PORT)
+                    .copied()
+                    .or(config.port)
+                    .unwrap_or(DEFAULT_PORT),
+            }),
+            _ => None,
+        };
+
+        Ok(Options {
+            database_type,
+            load,
+            format,
+            wal,
+            frontend,
+        })
+    }
+
+    /// Returns the value of `id` only if it was given on the command line, so that
+    /// the config file takes precedence over the built-in default.
+    fn explicit<T: Clone + Send + Sync + 'static>(matches: &ArgMatches, id: &str) -> Option<T> {
+        match matches.value_source(id) {
+            Some(ValueSource::CommandLine) => matches.get_one::<T>(id).cloned(),
+            _ => None,
+        }
+    }
 
     /// Defines the application's command-line interface.
     ///
-    /// It specifies the app's metadata (name, version) and defines
-    /// the `--type` argument, which determines the database's key type.
+    /// It specifies the app's metadata (name, version), the shared flags
+    /// (`--type`, `--load`, `--format`, `--wal`, `--config`) and one subcommand per frontend.
     fn build_cli(&self) -> Command {
         Command::new("database")
             .about("A lightweight, simple database implementation written in Rust.")
             .version("1.0.0")
             .arg(
-                Arg::new("type")
+                Arg::new(Self::ARG_TYPE)
                     .short('t')
                     .long("type")
                     .value_name("TYPE")
                     .value_parser(value_parser!(DatabaseType))
                     .default_value("int")
+                    .global(true)
                     .help("Specifies the database type"),
             )
+            .arg(
+                Arg::new(Self::ARG_LOAD)
+                    .short('l')
+                    .long("load")
+                    .value_name("PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .global(true)
+                    .help("Loads a dump or a script into the database at startup"),
+            )
+            .arg(
+                Arg::new(Self::ARG_FORMAT)
+                    .short('f')
+                    .long("format")
+                    .value_name("FORMAT")
+                    .value_parser(value_parser!(LoadFormat))
+                    .default_value("binary")
+                    .global(true)
+                    .help("Specifies the format of the file given with --load"),
+            )
+            .arg(
+                Arg::new(Self::ARG_WAL)
+                    .short('w')
+                    .long("wal")
+                    .value_name("PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .global(true)
+                    .help("Replays a write-ahead log at startup and records every change in it"),
+            )
+            .arg(
+                Arg::new(Self::ARG_CONFIG)
+                    .short('c')
+                    .long("config")
+                    .value_name("PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .global(true)
+                    .help("Reads default option values from a `key = value` file"),
+            )
+            .subcommand(Command::new(Self::CMD_REPL).about("Runs the interactive REPL (default)"))
+            .subcommand(Command::new(Self::CMD_TUI).about("Runs the Text User Interface"))
+            .subcommand(
+                Command::new(Self::CMD_EXEC)
+                    .about("Executes the queries from a script and exits")
+                    .arg(
+                        Arg::new(Self::ARG_FILE)
+                            .value_name("FILE")
+                            .value_parser(value_parser!(PathBuf))
+                            .required(true),
+                    ),
+            )
+            .subcommand(
+                Command::new(Self::CMD_SERVE)
+                    .about("Serves queries over TCP")
+                    .arg(
+                        Arg::new(Self::ARG_PORT)
+                            .short('p')
+                            .long("port")
+                            .value_name("PORT")
+                            .value_parser(value_parser!(u16))
+                            .help("Specifies the port to listen on [default: 5544]"),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Options {
+        Cli.try_parse_from(std::iter::once("database").chain(args.iter().copied()))
+            .unwrap_or_else(|e| panic!("Failed to parse {args:?}: {e}"))
+    }
+
+    #[test]
+    fn test_no_subcommand_uses_defaults() {
+        let options = parse(&[]);
+        assert_eq!(
+            options,
+            Options {
+                database_type: DatabaseType::Int,
+                load: None,
+                format: LoadFormat::Binary,
+                wal: None,
+                frontend: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_subcommands() {
+        assert_eq!(parse(&["repl"]).frontend, Some(Frontend::Repl));
+        assert_eq!(parse(&["tui"]).frontend, Some(Frontend::Tui));
+        assert_eq!(
+            parse(&["exec", "script.sql"]).frontend,
+            Some(Frontend::Exec {
+                file: PathBuf::from("script.sql")
+            })
+        );
+        assert_eq!(
+            parse(&["serve"]).frontend,
+            Some(Frontend::Serve { port: DEFAULT_PORT })
+        );
+        assert_eq!(
+            parse(&["serve", "--port", "6000"]).frontend,
+            Some(Frontend::Serve { port: 6000 })
+        );
+    }
+
+    #[test]
+    fn test_shared_flags_before_and_after_subcommand() {
+        let before = parse(&[
+            "--type", "string", "--load", "db.sql", "-f", "script", "--wal", "db.wal", "tui",
+        ]);
+        let after = parse(&[
+            "tui", "--type", "string", "--load", "db.sql", "-f", "script", "-w", "db.wal",
+        ]);
+
+        for options in [before, after] {
+            assert_eq!(options.database_type, DatabaseType::String);
+            assert_eq!(options.load, Some(PathBuf::from("db.sql")));
+            assert_eq!(options.format, LoadFormat::Script);
+            assert_eq!(options.wal, Some(PathBuf::from("db.wal")));
+            assert_eq!(options.frontend, Some(Frontend::Tui));
+        }
+    }
+
+    #[test]
+    fn test_uuid_key_type() {
+        assert_eq!(parse(&["--type", "uuid"]).database_type, DatabaseType::Uuid);
+    }
+
+    #[test]
+    fn test_exec_requires_file() {
+        assert!(Cli.try_parse_from(["database", "exec"]).is_err());
+    }
+
+    #[test]
+    fn test_config_fills_missing_values_only() {
+        let path = std::env::temp_dir().join(format!("database_cli_{}.conf", std::process::id()));
+        fs::write(
+            &path,
+            "# defaults\ntype = string\nload = \"snapshot.bin\"\nport = 7000\n",
+        )
+        .unwrap();
+        let config = path.to_str().unwrap();
+
+        let options = parse(&["--config", config, "serve"]);
+        assert_eq!(options.database_type, DatabaseType::String);
+        assert_eq!(options.load, Some(PathBuf::from("snapshot.bin")));
+        assert_eq!(options.frontend, Some(Frontend::Serve { port: 7000 }));
+
+        let options = parse(&["--config", config, "-t", "int", "serve", "-p", "8000"]);
+        assert_eq!(options.database_type, DatabaseType::Int);
+        assert_eq!(options.frontend, Some(Frontend::Serve { port: 8000 }));
+
+        fs::remove_file(path).unwrap();
     }
 }
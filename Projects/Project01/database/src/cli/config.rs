@@ -0,0 +1,143 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::{cli::LoadFormat, core::DatabaseType};
+
+/// Represents errors that can occur while reading the file given with `--config`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum ConfigError {
+    #[error("Failed to read config file '{path}'")]
+    Io {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Line {line} of the config file is not of the form `key = value`")]
+    Syntax { line: usize },
+
+    #[error("Unknown key '{key}' on line {line} of the config file")]
+    UnknownKey { line: usize, key: String },
+
+    #[error("Invalid value '{value}' for '{key}' on line {line} of the config file")]
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+    },
+}
+
+/// Default option values read from a config file.
+///
+/// The file consists of `key = value` lines, where the value may be enclosed in
+/// double quotes. Empty lines and lines starting with `#` are ignored. The keys
+/// are `type`, `load`, `format`, `wal` and `port`, matching the command-line flags.
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    pub database_type: Option<DatabaseType>,
+    pub load: Option<PathBuf>,
+    pub format: Option<LoadFormat>,
+    pub wal: Option<PathBuf>,
+    pub port: Option<u16>,
+}
+
+impl Config {
+    /// Reads and parses the config file at `path`.
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let source = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&source)
+    }
+
+    /// Parses the contents of a config file.
+    pub fn parse(source: &str) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ConfigError::Syntax { line: line_number })?;
+            let key = key.trim();
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+
+            let invalid = || ConfigError::InvalidValue {
+                line: line_number,
+                key: key.to_string(),
+                value: value.to_string(),
+            };
+
+            match key {
+                "type" => {
+                    config.database_type =
+                        Some(DatabaseType::from_str(value, true).map_err(|_| invalid())?);
+                }
+                "load" => config.load = Some(PathBuf::from(value)),
+                "format" => {
+                    config.format = Some(LoadFormat::from_str(value, true).map_err(|_| invalid())?);
+                }
+                "wal" => config.wal = Some(PathBuf::from(value)),
+                "port" => config.port = Some(value.parse().map_err(|_| invalid())?),
+                _ => {
+                    return Err(ConfigError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let source = "# comment\n\ntype = String\nload = \"dump.bin\"\nformat=script\nwal = db.wal\nport = 6000\n";
+        let expected = Config {
+            database_type: Some(DatabaseType::String),
+            load: Some(PathBuf::from("dump.bin")),
+            format: Some(LoadFormat::Script),
+            wal: Some(PathBuf::from("db.wal")),
+            port: Some(6000),
+        };
+        assert_eq!(Config::parse(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        assert!(matches!(
+            Config::parse("type string"),
+            Err(ConfigError::Syntax { line: 1 })
+        ));
+        assert!(matches!(
+            Config::parse("\ncolour = red"),
+            Err(ConfigError::UnknownKey { line: 2, .. })
+        ));
+        assert!(matches!(
+            Config::parse("port = 70000"),
+            Err(ConfigError::InvalidValue { line: 1, .. })
+        ));
+    }
+}
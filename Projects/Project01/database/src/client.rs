@@ -0,0 +1,110 @@
+//! The `client` module.
+//!
+//! This module lets other Rust programs talk to a server started with `serve`,
+//! without reimplementing the wire format of the `protocol` module.
+//!
+//! ```no_run
+//! use database::client::Connection;
+//!
+//! let mut connection = Connection::connect(("127.0.0.1", database::DEFAULT_PORT))?;
+//! let result = connection.query("SELECT id FROM users;")?;
+//! println!("{result}");
+//! # Ok::<(), database::core::DatabaseError>(())
+//! ```
+
+use std::{
+    io::{self, BufReader, BufWriter},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+use crate::{
+    DatabaseResult,
+    core::DatabaseError,
+    execution::ExecutionResult,
+    protocol::{self, MAX_RESPONSE_LEN},
+};
+
+/// A connection to a database server.
+///
+/// The queries are executed in the session of the connection, so a transaction
+/// started with `BEGIN` lasts until its `COMMIT` or `ROLLBACK`, or until the
+/// connection is dropped, which rolls it back.
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+}
+
+impl Connection {
+    /// Connects to the server listening on `addr`.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the connection cannot be established.
+    pub fn connect(addr: impl ToSocketAddrs) -> DatabaseResult<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+        })
+    }
+
+    /// Sends `query` to the server and waits for its result.
+    ///
+    /// # Errors
+    /// * `DatabaseError::Remote` - The server failed to parse or execute the query.
+    /// * `DatabaseError::Io` - The connection failed or was closed by the server.
+    /// * `DatabaseError::Decode` - The response of the server is malformed.
+    pub fn query(&mut self, query: &str) -> DatabaseResult<ExecutionResult> {
+        protocol::write_frame(&mut self.writer, query.as_bytes())?;
+
+        let payload =
+            protocol::read_frame(&mut self.reader, MAX_RESPONSE_LEN)?.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the server closed the connection",
+                )
+            })?;
+        protocol::decode_response(&payload)?.map_err(|message| DatabaseError::Remote { message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, SocketAddr, TcpListener},
+        thread,
+    };
+
+    use super::*;
+    use crate::{core::Catalog, frontend::serve};
+
+    fn start() -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Catalog::<i64>::default(), None));
+        addr
+    }
+
+    #[test]
+    fn test_connection() {
+        let mut connection = Connection::connect(start()).unwrap();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+        ] {
+            connection.query(query).unwrap();
+        }
+
+        let Ok(ExecutionResult::Data(records)) = connection.query("SELECT name FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert_eq!(records.len(), 1);
+
+        let Err(DatabaseError::Remote { message }) = connection.query("SELECT name FROM orders;")
+        else {
+            panic!("Expected Remote");
+        };
+        assert!(message.contains("orders"));
+        assert!(connection.query("SELECT name FROM users;").is_ok());
+    }
+}
@@ -1,12 +1,36 @@
 //! The `core` module, defining the fundamental data structures for the database.
 //!
 //! This module contains the database's primary models (like [Database], [Table], [Record]),
-//! error types ([DatabaseError]), and data types ([DataType], [DataValue]).
+//! error types ([DatabaseError]), data types ([DataType], [DataValue]), the
+//! [WriteAheadLog] used for crash recovery and the [StorageBackend] the database
+//! is persisted to.
 
 mod errors;
 mod models;
+mod storage;
+mod suggestion;
 mod types;
+mod uuid;
+mod wal;
 
-pub use errors::{CreateError, DatabaseError, DatabaseResult, InsertError, SelectError};
-pub use models::{Database, DatabaseKey, DatabaseType, Record, Table};
+pub use errors::{
+    CatalogError, CreateError, CsvError, DatabaseError, DatabaseResult, EncryptionError,
+    InsertError, JsonError, LoadTableError, MigrationError, RecordError, RenameError, SelectError,
+    TransactionError, TriggerError, UpdateError,
+};
+pub use models::{
+    AfterTrigger, BeforeTrigger, Catalog, ColumnSchema, ColumnStatistics, DEFAULT_DATABASE,
+    Database, DatabaseKey, DatabaseType, FromDataValue, FromRecord, Index, IndexEntries, IndexKind,
+    Record, Rows, Table, TableSchema, TableStatistics, TableStorage, Trigger, TriggerAction,
+    TriggerEvent,
+};
+pub use storage::{
+    FileSystemStorage, InMemoryStorage, StorageBackend, StorageReader, StorageWriter,
+};
 pub use types::{DataType, DataValue};
+pub use uuid::{ParseUuidError, Uuid};
+pub use wal::WriteAheadLog;
+
+pub(crate) use suggestion::did_you_mean;
+
+pub use database_derive::FromRecord;
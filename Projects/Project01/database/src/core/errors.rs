@@ -1,10 +1,32 @@
+mod catalog_error;
 mod create_error;
+mod csv_error;
+mod encryption_error;
 mod insert_error;
+mod json_error;
+mod load_table_error;
+mod migration_error;
+mod record_error;
+mod rename_error;
 mod select_error;
+mod transaction_error;
+mod trigger_error;
+mod update_error;
 
+pub use catalog_error::CatalogError;
 pub use create_error::CreateError;
+pub use csv_error::CsvError;
+pub use encryption_error::EncryptionError;
 pub use insert_error::InsertError;
+pub use json_error::JsonError;
+pub use load_table_error::LoadTableError;
+pub use migration_error::MigrationError;
+pub use record_error::RecordError;
+pub use rename_error::RenameError;
 pub use select_error::SelectError;
+pub use transaction_error::TransactionError;
+pub use trigger_error::TriggerError;
+pub use update_error::UpdateError;
 
 use std::io;
 
@@ -12,7 +34,11 @@ use bincode::error::{DecodeError, EncodeError};
 use miette::Diagnostic;
 use thiserror::Error;
 
-use crate::{ast::AstError, core::DataType, parsing::ParsingError};
+use crate::{
+    ast::AstError,
+    core::{DataType, Record, did_you_mean},
+    parsing::ParsingError,
+};
 
 /// A specialized `Result` type for all database operations.
 ///
@@ -35,10 +61,14 @@ pub enum DatabaseError {
     #[error(transparent)]
     Decode(#[from] DecodeError),
 
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
     #[error(transparent)]
     Ast(#[from] AstError),
 
     #[error(transparent)]
+    #[diagnostic(transparent)]
     Parse(#[from] Box<ParsingError>),
 
     #[error(transparent)]
@@ -50,11 +80,53 @@ pub enum DatabaseError {
     #[error(transparent)]
     Select(#[from] SelectError),
 
+    #[error(transparent)]
+    Update(#[from] UpdateError),
+
+    #[error(transparent)]
+    Rename(#[from] RenameError),
+
+    #[error(transparent)]
+    JsonDocument(#[from] JsonError),
+
+    #[error(transparent)]
+    Csv(#[from] CsvError),
+
+    #[error(transparent)]
+    LoadTable(#[from] LoadTableError),
+
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+
+    #[error(transparent)]
+    Catalog(#[from] CatalogError),
+
+    #[error(transparent)]
+    Trigger(#[from] TriggerError),
+
+    #[error(transparent)]
+    Record(#[from] RecordError),
+
     #[error("Table '{name}' not found")]
-    TableNotFound { name: String },
+    TableNotFound {
+        name: String,
+        #[help]
+        help: Option<String>,
+    },
 
     #[error("Key field '{field}' not found in schema for table '{table}'")]
-    FieldNotFound { table: String, field: String },
+    FieldNotFound {
+        table: String,
+        field: String,
+        #[help]
+        help: Option<String>,
+    },
 
     #[error(
         "Type mismatch for field '{field}' in table '{table}': expected {expected:?}, found {found:?}"
@@ -66,6 +138,21 @@ pub enum DatabaseError {
         found: DataType,
     },
 
+    #[error("Value {value} of field '{field}' in table '{table}' is not one of {variants:?}")]
+    InvalidEnumValue {
+        table: String,
+        field: String,
+        value: String,
+        variants: Vec<String>,
+    },
+
+    #[error("Record {record} violates the constraint CHECK ({check}) of table '{table}'")]
+    ConstraintViolation {
+        table: String,
+        check: String,
+        record: Record,
+    },
+
     #[error("Duplicate column name '{column}' in table definition '{table}'")]
     DuplicateColumn { table: String, column: String },
 
@@ -74,4 +161,46 @@ pub enum DatabaseError {
 
     #[error("Unknown data type: {data_type}")]
     UnknownDataType { data_type: String },
+
+    #[error(
+        "Dump '{path}' has format version {version}, only versions up to {supported} are supported"
+    )]
+    UnsupportedDumpVersion {
+        path: String,
+        version: u32,
+        supported: u32,
+    },
+
+    #[error(
+        "Checksum of dump '{path}' does not match its content, the file is corrupted or truncated"
+    )]
+    ChecksumMismatch { path: String },
+
+    #[error("The server failed to execute the query: {message}")]
+    Remote { message: String },
+}
+
+impl DatabaseError {
+    /// Returns a `TableNotFound` error for `name`, suggesting the closest of the
+    /// existing `tables`.
+    pub fn table_not_found<'a>(name: &str, tables: impl IntoIterator<Item = &'a str>) -> Self {
+        Self::TableNotFound {
+            name: name.to_string(),
+            help: did_you_mean(name, tables),
+        }
+    }
+
+    /// Returns a `FieldNotFound` error for the column `field` of `table`,
+    /// suggesting the closest of its `columns`.
+    pub fn field_not_found<'a>(
+        table: &str,
+        field: &str,
+        columns: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        Self::FieldNotFound {
+            table: table.to_string(),
+            field: field.to_string(),
+            help: did_you_mean(field, columns),
+        }
+    }
 }
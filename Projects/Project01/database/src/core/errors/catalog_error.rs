@@ -0,0 +1,15 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Represents errors that can occur while executing `CREATE_DATABASE` and `USE`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum CatalogError {
+    #[error("Database '{name}' already exists")]
+    AlreadyExists { name: String },
+
+    #[error("Database '{name}' not found")]
+    NotFound { name: String },
+
+    #[error("{statement} cannot be executed by READ_FROM or MIGRATE")]
+    Unsupported { statement: String },
+}
@@ -6,4 +6,7 @@ use thiserror::Error;
 pub enum CreateError {
     #[error("Table '{name}' already exists")]
     TableAlreadyExists { name: String },
+
+    #[error("Index '{name}' already exists on table '{table}'")]
+    IndexAlreadyExists { table: String, name: String },
 }
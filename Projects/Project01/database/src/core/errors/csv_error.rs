@@ -0,0 +1,27 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::core::DataType;
+
+/// Represents errors that can occur while reading a CSV file for `LOAD_CSV`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum CsvError {
+    #[error("CSV file '{path}' has no header row")]
+    MissingHeader { path: String },
+
+    #[error("Column '{column}' appears more than once in the header of '{path}'")]
+    DuplicateColumn { path: String, column: String },
+
+    #[error("Quoted cell is not terminated")]
+    UnterminatedQuote,
+
+    #[error("Expected {expected} cell(s), found {found}")]
+    CellCount { expected: usize, found: usize },
+
+    #[error("Cell '{value}' of column '{field}' is not a valid {expected:?}")]
+    InvalidValue {
+        field: String,
+        expected: DataType,
+        value: String,
+    },
+}
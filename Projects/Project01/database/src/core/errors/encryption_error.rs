@@ -0,0 +1,31 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Represents errors that can occur while writing or reading an encrypted
+/// snapshot with `DUMP_TO ... ENCRYPTED WITH` and `LOAD_FROM ... WITH`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum EncryptionError {
+    #[error("Wrong passphrase for '{path}', or the file has been tampered with")]
+    Decryption { path: String },
+
+    #[error("Snapshot '{path}' is encrypted, load it with a passphrase")]
+    PassphraseRequired { path: String },
+
+    #[error("Snapshot '{path}' is not encrypted, but a passphrase was given")]
+    NotEncrypted { path: String },
+
+    #[error("Snapshot '{path}' is too short to be an encrypted snapshot")]
+    Truncated { path: String },
+
+    #[error("Failed to derive a key from the passphrase: {reason}")]
+    KeyDerivation { reason: String },
+
+    #[error("Failed to encrypt the snapshot")]
+    Encryption,
+
+    #[error(
+        "Snapshot '{path}' is encrypted and cannot be loaded while a write-ahead log is kept, \
+         as the log would hold its decrypted content"
+    )]
+    Logged { path: String },
+}
@@ -0,0 +1,34 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::core::DataType;
+
+/// Represents errors that can occur while converting a database to or from
+/// the JSON document of `EXPORT_JSON` and `IMPORT_JSON`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum JsonError {
+    #[error("Value {value} of field '{field}' in table '{table}' is not a valid {expected:?}")]
+    InvalidValue {
+        table: String,
+        field: String,
+        expected: DataType,
+        value: String,
+    },
+
+    #[error("Field '{field}' in table '{table}' holds {value}, which JSON cannot represent")]
+    NonFiniteFloat {
+        table: String,
+        field: String,
+        value: f64,
+    },
+
+    #[error("Record {index} of table '{table}' is missing the field '{field}'")]
+    MissingField {
+        table: String,
+        index: usize,
+        field: String,
+    },
+
+    #[error("More than one record of table '{table}' has the key '{key}'")]
+    DuplicateKey { table: String, key: String },
+}
@@ -0,0 +1,19 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Represents errors that can occur during the execution of a `LOAD_TABLE` statement.
+#[derive(Error, Debug, Diagnostic)]
+pub enum LoadTableError {
+    #[error("'{path}' is not a table dump written by DUMP_TABLE")]
+    NotATableDump { path: String },
+
+    #[error(
+        "Table '{name}' already exists, load it with ON CONFLICT REPLACE or ON CONFLICT UPDATE"
+    )]
+    TableAlreadyExists { name: String },
+
+    #[error(
+        "Cannot merge the dumped table '{name}': its key or schema differs from the existing table"
+    )]
+    SchemaMismatch { name: String },
+}
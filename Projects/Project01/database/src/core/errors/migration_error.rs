@@ -0,0 +1,24 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use super::DatabaseError;
+
+/// Represents errors that can occur during the execution of a `MIGRATE` statement.
+#[derive(Error, Debug, Diagnostic)]
+pub enum MigrationError {
+    #[error("Migration script '{file}' does not start with a version number")]
+    InvalidFileName { file: String },
+
+    #[error("Migration scripts '{first}' and '{second}' have the same version {version}")]
+    DuplicateVersion {
+        version: i64,
+        first: String,
+        second: String,
+    },
+
+    #[error("Migration '{file}' failed, the database was left as before it: {source}")]
+    Failed {
+        file: String,
+        source: Box<DatabaseError>,
+    },
+}
@@ -0,0 +1,22 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::core::DataType;
+
+/// Represents errors that can occur while converting records into Rust types
+/// (see `FromRecord`).
+#[derive(Error, Debug, Diagnostic)]
+pub enum RecordError {
+    #[error("The record has no field '{field}'")]
+    MissingField { field: String },
+
+    #[error("Field '{field}' of the record holds a {found:?} value, expected {expected:?}")]
+    TypeMismatch {
+        field: String,
+        expected: DataType,
+        found: DataType,
+    },
+
+    #[error("Expected the records of a query, but the query returned {result}")]
+    NotData { result: String },
+}
@@ -0,0 +1,9 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Represents errors that can occur during the execution of a `RENAME` statement.
+#[derive(Error, Debug, Diagnostic)]
+pub enum RenameError {
+    #[error("Cannot rename table '{from}' to '{to}': table '{to}' already exists")]
+    TableAlreadyExists { from: String, to: String },
+}
@@ -1,9 +1,68 @@
 use miette::Diagnostic;
 use thiserror::Error;
 
+use crate::{
+    ast::{AggregateFunction, ArithmeticOperator, ScalarFunction, SetOperator},
+    core::DataType,
+};
+
 /// Represents errors that can occur during the execution of a `SELECT` statement.
 #[derive(Error, Debug, Diagnostic)]
 pub enum SelectError {
     #[error("Invalid LIMIT value: {limit}. Value must be non-negative.")]
     InvalidLimit { limit: i64 },
+
+    #[error("Invalid OFFSET value: {offset}. Value must be non-negative.")]
+    InvalidOffset { offset: i64 },
+
+    #[error("Alias '{alias}' names more than one column of the result")]
+    DuplicateAlias { alias: String },
+
+    #[error("Column '{field}' cannot be selected together with aggregate functions")]
+    MixedProjection { field: String },
+
+    #[error("{function} cannot be applied to field '{field}' of type {data_type:?}")]
+    InvalidAggregateType {
+        function: AggregateFunction,
+        field: String,
+        data_type: DataType,
+    },
+
+    #[error("{function}({field}) has no value, as no record matches the query")]
+    EmptyAggregate {
+        function: AggregateFunction,
+        field: String,
+    },
+
+    #[error("{function}({field}) overflows a 64-bit integer")]
+    AggregateOverflow {
+        function: AggregateFunction,
+        field: String,
+    },
+
+    #[error("Operator '{op}' cannot be applied to {left:?} and {right:?}")]
+    InvalidArithmetic {
+        op: ArithmeticOperator,
+        left: DataType,
+        right: DataType,
+    },
+
+    #[error("{function} cannot be applied to a value of type {dtype:?}")]
+    InvalidFunctionArgument {
+        function: ScalarFunction,
+        dtype: DataType,
+    },
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("{expression} overflows a 64-bit integer")]
+    ArithmeticOverflow { expression: String },
+
+    #[error("{operator} requires both SELECT statements to return the same columns")]
+    IncompatibleProjections {
+        operator: SetOperator,
+        left: Vec<String>,
+        right: Vec<String>,
+    },
 }
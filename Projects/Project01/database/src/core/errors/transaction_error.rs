@@ -0,0 +1,24 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use super::DatabaseError;
+
+/// Represents errors that can occur while executing `BEGIN`, `COMMIT` and
+/// `ROLLBACK` and the queries inside a transaction.
+#[derive(Error, Debug, Diagnostic)]
+pub enum TransactionError {
+    #[error("A transaction is already in progress, COMMIT or ROLLBACK it first")]
+    AlreadyInProgress,
+
+    #[error("There is no transaction in progress to {statement}")]
+    NotInProgress { statement: String },
+
+    #[error("{statement} cannot be executed inside a transaction")]
+    NotAllowed { statement: String },
+
+    #[error("BEGIN, COMMIT and ROLLBACK cannot be executed by MIGRATE")]
+    Unsupported,
+
+    #[error("A query failed, the transaction was rolled back: {source}")]
+    RolledBack { source: Box<DatabaseError> },
+}
@@ -0,0 +1,22 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+use super::DatabaseError;
+
+/// Represents errors raised by the triggers run by `INSERT` and `DELETE`.
+#[derive(Error, Debug, Diagnostic)]
+pub enum TriggerError {
+    #[error("Trigger '{trigger}' rejected the change to table '{table}': {reason}")]
+    Rejected {
+        trigger: String,
+        table: String,
+        reason: String,
+    },
+
+    #[error("Trigger '{trigger}' failed after the change to table '{table}': {source}")]
+    Failed {
+        trigger: String,
+        table: String,
+        source: Box<DatabaseError>,
+    },
+}
@@ -0,0 +1,12 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Represents errors that can occur during the execution of an `UPDATE` statement.
+#[derive(Error, Debug, Diagnostic)]
+pub enum UpdateError {
+    #[error("Field '{field}' specified more than once in UPDATE statement for table '{table}'")]
+    DuplicateAssignment { table: String, field: String },
+
+    #[error("Updating the key would give more than one record the key '{key}' in table '{table}'")]
+    DuplicateKey { table: String, key: String },
+}
@@ -1,9 +1,24 @@
+mod catalog;
 mod database;
+mod from_record;
+mod generator;
+mod index;
 mod key;
 mod record;
+mod rows;
+mod schema;
+mod statistics;
 mod table;
+mod trigger;
 
+pub use catalog::{Catalog, DEFAULT_DATABASE};
 pub use database::{Database, DatabaseType};
+pub use from_record::{FromDataValue, FromRecord};
+pub use index::{Index, IndexEntries, IndexKind};
 pub use key::DatabaseKey;
 pub use record::Record;
+pub use rows::{Rows, TableStorage};
+pub use schema::{ColumnSchema, TableSchema};
+pub use statistics::{ColumnStatistics, TableStatistics};
 pub use table::Table;
+pub use trigger::{AfterTrigger, BeforeTrigger, Trigger, TriggerAction, TriggerEvent};
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    DatabaseResult,
+    core::{CatalogError, Database, DatabaseKey},
+};
+
+/// The name of the database every session starts in.
+pub const DEFAULT_DATABASE: &str = "main";
+
+/// The named databases of a process, created with `CREATE_DATABASE` and
+/// selected with `USE`.
+///
+/// The catalog always holds the [`DEFAULT_DATABASE`], and its databases cannot
+/// be removed.
+#[derive(Debug)]
+pub struct Catalog<K: DatabaseKey> {
+    /// The databases, by name.
+    databases: BTreeMap<String, Database<K>>,
+    /// The database the queries last appended to the write-ahead log apply to.
+    logged: String,
+}
+
+/// Creates a catalog holding an empty default database.
+impl<K: DatabaseKey> Default for Catalog<K> {
+    fn default() -> Self {
+        Self::new(Database::default())
+    }
+}
+
+impl<K: DatabaseKey> Catalog<K> {
+    /// Creates a catalog holding `database` as its [`DEFAULT_DATABASE`].
+    pub fn new(database: Database<K>) -> Self {
+        Self {
+            databases: BTreeMap::from([(DEFAULT_DATABASE.to_string(), database)]),
+            logged: DEFAULT_DATABASE.to_string(),
+        }
+    }
+
+    /// Returns the database called `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Database<K>> {
+        self.databases.get(name)
+    }
+
+    /// Returns the database called `name` mutably, if any.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Database<K>> {
+        self.databases.get_mut(name)
+    }
+
+    /// Returns the names of the databases, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.databases.keys().map(String::as_str)
+    }
+
+    /// Adds an empty database called `name`, persisted to the storage of the
+    /// default database.
+    ///
+    /// # Errors
+    /// Returns `CatalogError::AlreadyExists` if there is a database with that name.
+    pub fn create(&mut self, name: &str) -> DatabaseResult<()> {
+        if self.databases.contains_key(name) {
+            return Err(CatalogError::AlreadyExists {
+                name: name.to_string(),
+            }
+            .into());
+        }
+
+        let database = self.databases[DEFAULT_DATABASE].empty_sibling();
+        self.databases.insert(name.to_string(), database);
+        Ok(())
+    }
+
+    /// Returns the number of databases.
+    pub(crate) fn len(&self) -> usize {
+        self.databases.len()
+    }
+
+    /// Returns the database the queries last appended to the log apply to.
+    pub(crate) fn logged_database(&self) -> &str {
+        &self.logged
+    }
+
+    /// Records that the queries appended to the log from now on apply to `name`.
+    pub(crate) fn set_logged_database(&mut self, name: &str) {
+        self.logged = name.to_string();
+    }
+}
@@ -1,50 +1,141 @@
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::sync::Arc;
 
-use bincode::{Decode, Encode};
+use bincode::{
+    Decode, Encode,
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+};
 use clap::ValueEnum;
 
 use crate::DatabaseResult;
-use crate::core::{DatabaseError, errors::CreateError};
+use crate::core::{
+    DatabaseError, FileSystemStorage, StorageBackend,
+    errors::{CreateError, RenameError},
+};
 
-use super::{key::DatabaseKey, table::Table};
+use super::{
+    key::DatabaseKey,
+    schema::TableSchema,
+    statistics::TableStatistics,
+    table::Table,
+    trigger::{Trigger, TriggerEvent},
+};
 
 /// Specifies the database variant based on its primary key type.
 ///
 /// This is used by `clap` to parse command-line arguments.
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum DatabaseType {
     /// Use `i64` as the primary key type.
     Int,
     /// Use `String` as the primary key type.
     String,
+    /// Use `Uuid` as the primary key type.
+    Uuid,
 }
 
+/// The prefix of the names of the system tables, which the database maintains
+/// itself (such as the history of `MIGRATE`) and which are not listed with the
+/// user's tables.
+const SYSTEM_TABLE_PREFIX: &str = "__";
+
 /// Represents the top-level database instance.
 ///
 /// It is generic over the primary key type `K` and holds all tables
 /// and a history of executed commands.
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug)]
 pub struct Database<K: DatabaseKey> {
     /// A map of table names to their corresponding `Table` structs.
     pub tables: HashMap<String, Table<K>>,
     /// A log of all successfully executed query strings, used for `SAVE_AS`.
     history: Vec<String>,
+    /// The storage the snapshots of the database are written to and read from.
+    storage: Arc<dyn StorageBackend>,
+    /// The callbacks run by the changes to the tables, which are not persisted.
+    triggers: Vec<Trigger<K>>,
 }
 
 /// Creates a new, empty `Database` instance.
 ///
-/// Initializes an empty table map and an empty command history.
+/// Initializes an empty table map and an empty command history, persisted to
+/// the file system.
 impl<K: DatabaseKey> Default for Database<K> {
     fn default() -> Self {
+        Self::with_storage(Arc::new(FileSystemStorage))
+    }
+}
+
+/// Encodes the tables and the history of the database, but not its storage or
+/// its triggers.
+impl<K: DatabaseKey> Encode for Database<K> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.tables.encode(encoder)?;
+        self.history.encode(encoder)
+    }
+}
+
+/// Decodes a database persisted to the file system, see [`Database::replace`] to
+/// keep the storage of an existing one.
+impl<K: DatabaseKey> Decode<()> for Database<K> {
+    fn decode<D: Decoder<Context = ()>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            tables: Decode::decode(decoder)?,
+            history: Decode::decode(decoder)?,
+            ..Self::default()
+        })
+    }
+}
+
+impl<K: DatabaseKey> Database<K> {
+    /// Creates a new, empty `Database` persisted to `storage`.
+    pub fn with_storage(storage: Arc<dyn StorageBackend>) -> Self {
         Self {
             tables: Default::default(),
             history: Default::default(),
+            storage,
+            triggers: Vec::new(),
         }
     }
-}
 
-impl<K: DatabaseKey> Database<K> {
+    /// Creates a new, empty `Database` persisted to the storage of this one.
+    pub fn empty_sibling(&self) -> Self {
+        Self::with_storage(Arc::clone(&self.storage))
+    }
+
+    /// Returns the storage the database is persisted to.
+    pub fn storage(&self) -> &dyn StorageBackend {
+        self.storage.as_ref()
+    }
+
+    /// Copies the database, such as to restore it when a transaction is rolled back.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the records of an on-disk table cannot be
+    /// copied (see [`Table::try_clone`]).
+    pub fn try_clone(&self) -> DatabaseResult<Self> {
+        let tables = self
+            .tables
+            .iter()
+            .map(|(name, table)| Ok((name.clone(), table.try_clone()?)))
+            .collect::<DatabaseResult<_>>()?;
+        Ok(Self {
+            tables,
+            history: self.history.clone(),
+            storage: Arc::clone(&self.storage),
+            triggers: self.triggers.clone(),
+        })
+    }
+
+    /// Replaces the tables and the history with those of `database` (such as one
+    /// loaded from a snapshot), keeping the storage and the triggers of this database.
+    pub fn replace(&mut self, database: Database<K>) {
+        self.tables = database.tables;
+        self.history = database.history;
+    }
+
     /// Adds a new table to the database.
     ///
     /// # Arguments
@@ -75,13 +166,149 @@ impl<K: DatabaseKey> Database<K> {
     /// # Errors
     /// Returns `DatabaseError::TableNotFound` if no table with that name exists.
     pub fn get_table(&mut self, table_name: &str) -> DatabaseResult<&mut Table<K>> {
+        if !self.tables.contains_key(table_name) {
+            return Err(DatabaseError::table_not_found(
+                table_name,
+                self.tables.keys().map(String::as_str),
+            ));
+        }
+        Ok(self.tables.get_mut(table_name).unwrap())
+    }
+
+    /// Renames the table `table_name` to `new_name`, updating both its key in
+    /// the table map and [`Table::name`].
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::TableNotFound` if no table with that name exists.
+    /// Returns `DatabaseError` (wrapping `RenameError::TableAlreadyExists`) if another
+    /// table is already called `new_name`.
+    pub fn rename_table(&mut self, table_name: &str, new_name: &str) -> DatabaseResult<()> {
+        if !self.tables.contains_key(table_name) {
+            return Err(DatabaseError::table_not_found(
+                table_name,
+                self.tables.keys().map(String::as_str),
+            ));
+        }
+        if table_name == new_name {
+            return Ok(());
+        }
+        if self.tables.contains_key(new_name) {
+            return Err(RenameError::TableAlreadyExists {
+                from: table_name.to_string(),
+                to: new_name.to_string(),
+            }
+            .into());
+        }
+
+        let mut table = self.tables.remove(table_name).unwrap();
+        table.name = new_name.to_string();
+        self.tables.insert(new_name.to_string(), table);
+        for trigger in &mut self.triggers {
+            if trigger.table == table_name {
+                trigger.table = new_name.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers `trigger`, to be run by the `INSERT` or `DELETE` statements
+    /// changing its table, which then follows the table when it is renamed.
+    ///
+    /// The triggers are run in the order they were added. As they are not
+    /// persisted, they must be added again after a restart, before recovering the
+    /// database from its write-ahead log, so that replaying the log runs them too.
+    /// An AFTER trigger changing its own table runs itself again.
+    ///
+    /// # Returns
+    /// The trigger with the same name it replaces, if any.
+    pub fn add_trigger(&mut self, trigger: Trigger<K>) -> Option<Trigger<K>> {
+        match self.triggers.iter_mut().find(|t| t.name == trigger.name) {
+            Some(existing) => Some(std::mem::replace(existing, trigger)),
+            None => {
+                self.triggers.push(trigger);
+                None
+            }
+        }
+    }
+
+    /// Unregisters the trigger called `name`.
+    ///
+    /// # Returns
+    /// The removed trigger, if there was one.
+    pub fn remove_trigger(&mut self, name: &str) -> Option<Trigger<K>> {
+        let position = self.triggers.iter().position(|t| t.name == name)?;
+        Some(self.triggers.remove(position))
+    }
+
+    /// Returns the registered triggers, in the order they are run.
+    pub fn triggers(&self) -> &[Trigger<K>] {
+        &self.triggers
+    }
+
+    /// Returns the triggers run by `event` on the table `table_name`.
+    pub fn triggers_on(&self, table_name: &str, event: TriggerEvent) -> Vec<Trigger<K>> {
+        self.triggers
+            .iter()
+            .filter(|t| t.table == table_name && t.event == event)
+            .cloned()
+            .collect()
+    }
+
+    /// Inserts `count` random records conforming to the schema of the table `table_name`.
+    ///
+    /// See [`Table::generate_rows`] for how the values and keys are chosen.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::TableNotFound` if no table with that name exists.
+    pub fn generate_rows(
+        &mut self,
+        table_name: &str,
+        count: usize,
+        seed: u64,
+    ) -> DatabaseResult<usize> {
+        self.get_table(table_name)?.generate_rows(count, seed)
+    }
+
+    /// Computes and stores the statistics of the table `table_name`, as `ANALYZE` does.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::TableNotFound` if no table with that name exists.
+    pub fn analyze(&mut self, table_name: &str) -> DatabaseResult<&TableStatistics> {
+        Ok(self.get_table(table_name)?.analyze())
+    }
+
+    /// Returns the statistics of the table `table_name` computed by its last `ANALYZE`,
+    /// or `None` if it has never been analyzed.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::TableNotFound` if no table with that name exists.
+    pub fn statistics(&self, table_name: &str) -> DatabaseResult<Option<&TableStatistics>> {
         self.tables
-            .get_mut(table_name)
-            .ok_or_else(|| DatabaseError::TableNotFound {
-                name: table_name.to_string(),
+            .get(table_name)
+            .map(|table| table.statistics.as_ref())
+            .ok_or_else(|| {
+                DatabaseError::table_not_found(table_name, self.tables.keys().map(String::as_str))
             })
     }
 
+    /// Returns an iterator over the user's tables, leaving out the system tables.
+    pub fn user_tables(&self) -> impl Iterator<Item = (&String, &Table<K>)> {
+        self.tables
+            .iter()
+            .filter(|(name, _)| !name.starts_with(SYSTEM_TABLE_PREFIX))
+    }
+
+    /// Returns the schemas of the user's tables, sorted by table name, leaving out
+    /// the system tables as [`Database::user_tables`] does.
+    pub fn schemas(&self) -> Vec<TableSchema<'_>> {
+        let mut schemas = self
+            .user_tables()
+            .map(|(_, table)| TableSchema::of(table))
+            .collect::<Vec<_>>();
+        schemas.sort_by_key(|schema| schema.name);
+        schemas
+    }
+
     /// Appends a raw query string to the command history.
     ///
     /// This is typically called after a command has been successfully executed.
@@ -0,0 +1,204 @@
+use crate::{
+    DatabaseResult,
+    core::{DataType, DataValue, Record, Uuid},
+};
+
+/// A Rust type a field of a [`Record`] can be read as (see [`Record::get_as`]).
+///
+/// It is implemented for the type held by every `DataValue` variant, and for
+/// `Option<T>`, which reads a field the record does not hold as `None`.
+pub trait FromDataValue: Sized {
+    /// Returns the `DataType` of the values this type is read from.
+    fn data_type() -> DataType;
+
+    /// Converts `value` into this type, or returns `None` if it is of another type.
+    fn from_datavalue(value: &DataValue) -> Option<Self>;
+
+    /// Returns the value of a field the record does not hold, or `None` if the
+    /// field is required.
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+/// A type a whole [`Record`] can be converted into, such as a struct with a
+/// field for every column.
+///
+/// It is usually derived, reading every field of the struct from the column of
+/// the same name, or of the name given with `#[record(rename = "...")]`:
+///
+/// ```
+/// use database::core::FromRecord;
+///
+/// #[derive(FromRecord)]
+/// struct User {
+///     id: i64,
+///     #[record(rename = "full_name")]
+///     name: String,
+///     email: Option<String>,
+/// }
+/// ```
+///
+/// The records of a query are converted with [`ExecutionResult::into_records`].
+///
+/// [`ExecutionResult::into_records`]: crate::execution::ExecutionResult::into_records
+pub trait FromRecord: Sized {
+    /// Converts `record` into this type.
+    ///
+    /// # Errors
+    /// * `RecordError::MissingField` - A required column is not in the record.
+    /// * `RecordError::TypeMismatch` - A column holds a value of another type.
+    fn from_record(record: &Record) -> DatabaseResult<Self>;
+}
+
+macro_rules! impl_from_datavalue {
+    ($type:ty, $variant:ident) => {
+        impl FromDataValue for $type {
+            fn data_type() -> DataType {
+                DataType::$variant
+            }
+
+            fn from_datavalue(value: &DataValue) -> Option<Self> {
+                match value {
+                    DataValue::$variant(value) => Some(value.clone()),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_from_datavalue!(bool, Boolean);
+impl_from_datavalue!(String, String);
+impl_from_datavalue!(i64, Int);
+impl_from_datavalue!(f64, Float);
+impl_from_datavalue!(Vec<u8>, Bytes);
+impl_from_datavalue!(Uuid, Uuid);
+
+impl<T: FromDataValue> FromDataValue for Option<T> {
+    fn data_type() -> DataType {
+        T::data_type()
+    }
+
+    fn from_datavalue(value: &DataValue) -> Option<Self> {
+        T::from_datavalue(value).map(Some)
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Database, DatabaseError, FromRecord, RecordError},
+        execution::{ExecutionResult, build_execute_command},
+    };
+
+    #[derive(Debug, PartialEq, FromRecord)]
+    struct User {
+        id: i64,
+        #[record(rename = "full_name")]
+        name: String,
+        score: f64,
+        active: bool,
+        email: Option<String>,
+    }
+
+    fn record(fields: &[(&str, DataValue)]) -> Record {
+        Record::new(
+            fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_derived_from_record() {
+        let mut fields = vec![
+            ("id", DataValue::Int(1)),
+            ("full_name", DataValue::String("Alice".to_string())),
+            ("score", DataValue::Float(4.5)),
+            ("active", DataValue::Boolean(true)),
+        ];
+        let user = User::from_record(&record(&fields)).unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 1,
+                name: "Alice".to_string(),
+                score: 4.5,
+                active: true,
+                email: None,
+            }
+        );
+
+        fields.push(("email", DataValue::String("a@example.com".to_string())));
+        let user = User::from_record(&record(&fields)).unwrap();
+        assert_eq!(user.email.as_deref(), Some("a@example.com"));
+
+        fields[1] = ("name", DataValue::String("Alice".to_string()));
+        assert!(matches!(
+            User::from_record(&record(&fields)),
+            Err(DatabaseError::Record(RecordError::MissingField { field })) if field == "full_name"
+        ));
+
+        fields[1] = ("full_name", DataValue::Int(7));
+        assert!(matches!(
+            User::from_record(&record(&fields)),
+            Err(DatabaseError::Record(RecordError::TypeMismatch {
+                expected: DataType::String,
+                found: DataType::Int,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_into_records() {
+        #[derive(Debug, PartialEq, FromRecord)]
+        struct Row {
+            id: i64,
+            name: String,
+        }
+
+        let mut db = Database::<i64>::default();
+        let mut execute = |query: &str| {
+            let command = QueryParser.parse_query(query).unwrap();
+            build_execute_command(&mut db, command)
+                .and_then(|mut command| command.execute())
+                .unwrap()
+        };
+        execute("CREATE users KEY id FIELDS id: INT, name: STRING;");
+        execute("INSERT id = 1, name = \"Alice\" INTO users;");
+        execute("INSERT id = 2, name = \"Bob\" INTO users;");
+
+        let rows = execute("SELECT id, name FROM users ORDER_BY id ASC;")
+            .into_records::<Row>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    id: 1,
+                    name: "Alice".to_string()
+                },
+                Row {
+                    id: 2,
+                    name: "Bob".to_string()
+                },
+            ]
+        );
+
+        assert!(matches!(
+            ExecutionResult::RowsAffected(2).into_records::<Row>(),
+            Err(DatabaseError::Record(RecordError::NotData { .. }))
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::{
+    DatabaseResult,
+    core::{DataType, DataValue, Uuid},
+};
+
+use super::{key::DatabaseKey, record::Record, table::Table};
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bob", "Charlie", "Diana", "Edward", "Fiona", "George", "Hannah", "Ivan", "Julia",
+    "Karol", "Laura", "Marek", "Natalia", "Oskar", "Paula", "Robert", "Sofia", "Tomasz", "Zofia",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Nowak",
+    "Kowalski",
+    "Smith",
+    "Johnson",
+    "Wiśniewski",
+    "Brown",
+    "Lewandowski",
+    "Garcia",
+    "Zieliński",
+    "Miller",
+    "Kamiński",
+    "Davis",
+    "Wójcik",
+    "Wilson",
+    "Kowalczyk",
+    "Taylor",
+];
+
+const CITIES: &[&str] = &[
+    "Warsaw", "Kraków", "Gdańsk", "Wrocław", "Poznań", "Berlin", "Prague", "Vienna", "Paris",
+    "London", "Madrid", "Rome",
+];
+
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "delta", "echo", "golf", "hotel", "kilo", "lima", "oscar", "papa", "romeo",
+    "sierra", "tango", "victor", "whiskey", "zulu",
+];
+
+/// Produces random values for the columns of one table.
+///
+/// The kind of value is picked from the column type and, for strings and
+/// integers, from hints in the column name (e.g. `name`, `email`, `age`).
+struct RowGenerator {
+    rng: StdRng,
+    /// The next candidate for a sequential integer key.
+    next_int_key: i64,
+}
+
+impl RowGenerator {
+    fn value(&mut self, column: &str, data_type: DataType) -> DataValue {
+        let column = column.to_lowercase();
+        match data_type {
+            DataType::Boolean => DataValue::Boolean(self.rng.random_bool(0.5)),
+            DataType::Int => DataValue::Int(self.int(&column)),
+            DataType::Float => {
+                let cents = self.rng.random_range(0..=100_000);
+                DataValue::Float(cents as f64 / 100.0)
+            }
+            DataType::String => DataValue::String(self.string(&column)),
+            DataType::Bytes => {
+                let length = self.rng.random_range(4..=16);
+                DataValue::Bytes((0..length).map(|_| self.rng.random()).collect())
+            }
+            DataType::Uuid => DataValue::Uuid(Uuid::new_v4(&mut self.rng)),
+        }
+    }
+
+    fn int(&mut self, column: &str) -> i64 {
+        if column.contains("age") {
+            self.rng.random_range(18..=90)
+        } else if column.contains("year") {
+            self.rng.random_range(1950..=2025)
+        } else {
+            self.rng.random_range(0..=1000)
+        }
+    }
+
+    fn string(&mut self, column: &str) -> String {
+        let first = *FIRST_NAMES.choose(&mut self.rng).unwrap();
+        let last = *LAST_NAMES.choose(&mut self.rng).unwrap();
+
+        if column.contains("email") || column.contains("mail") {
+            let number = self.rng.random_range(1..1000);
+            format!(
+                "{}.{}{number}@example.com",
+                first.to_lowercase(),
+                last.to_lowercase()
+            )
+        } else if column.contains("first") {
+            first.to_string()
+        } else if column.contains("last") || column.contains("surname") {
+            last.to_string()
+        } else if column.contains("name") {
+            format!("{first} {last}")
+        } else if column.contains("city") || column.contains("town") {
+            CITIES.choose(&mut self.rng).unwrap().to_string()
+        } else {
+            let word = *WORDS.choose(&mut self.rng).unwrap();
+            format!("{word}-{}", self.rng.random_range(0..10_000))
+        }
+    }
+
+    /// Returns a key of type `K` that is not yet used in `rows`.
+    ///
+    /// Integer keys are sequential, skipping the ones already taken.
+    /// UUID keys are random (version 4) and string keys are random identifiers.
+    fn key<K: DatabaseKey>(&mut self, table: &Table<K>) -> (K, DataValue) {
+        loop {
+            let value = match K::key_type() {
+                DataType::Int => {
+                    let key = self.next_int_key;
+                    self.next_int_key += 1;
+                    DataValue::Int(key)
+                }
+                DataType::Uuid => DataValue::Uuid(Uuid::new_v4(&mut self.rng)),
+                _ => DataValue::String(format!("{}-{:08x}", table.name, self.rng.random::<u32>())),
+            };
+
+            let key = K::from_datavalue(&value).unwrap();
+            if !table.rows.contains_key(&key) {
+                return (key, value);
+            }
+        }
+    }
+}
+
+impl<K: DatabaseKey> Table<K> {
+    /// Inserts `count` records with random values conforming to the schema.
+    ///
+    /// The same `seed` always produces the same records for the same table.
+    /// Integer keys continue sequentially from 1, skipping keys already present;
+    /// string keys are random identifiers prefixed with the table name.
+    /// `ENUM` columns take one of their variants.
+    ///
+    /// # Returns
+    /// The number of inserted records.
+    pub fn generate_rows(&mut self, count: usize, seed: u64) -> DatabaseResult<usize> {
+        let mut generator = RowGenerator {
+            rng: StdRng::seed_from_u64(seed),
+            next_int_key: 1,
+        };
+
+        // the schema is a `HashMap`, so its order has to be fixed for the seed to be reproducible
+        let mut columns: Vec<(String, DataType)> = self
+            .schema
+            .iter()
+            .filter(|(name, _)| **name != self.key_field)
+            .map(|(name, data_type)| (name.clone(), *data_type))
+            .collect();
+        columns.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for _ in 0..count {
+            let (key, key_value) = generator.key(self);
+
+            let mut fields = HashMap::with_capacity(self.schema.len());
+            fields.insert(self.key_field.clone(), key_value);
+            for (name, data_type) in &columns {
+                let value = match self.enums.get(name) {
+                    Some(variants) => {
+                        DataValue::String(variants.choose(&mut generator.rng).unwrap().clone())
+                    }
+                    None => generator.value(name, *data_type),
+                };
+                fields.insert(name.clone(), value);
+            }
+
+            self.insert_row(key, Record::new(fields))?;
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users_table<K: DatabaseKey>(key_type: DataType) -> Table<K> {
+        let schema = HashMap::from([
+            ("id".to_string(), key_type),
+            ("name".to_string(), DataType::String),
+            ("email".to_string(), DataType::String),
+            ("age".to_string(), DataType::Int),
+            ("score".to_string(), DataType::Float),
+            ("active".to_string(), DataType::Boolean),
+        ]);
+        Table::new("users", "id", schema).unwrap()
+    }
+
+    #[test]
+    fn test_generated_rows_conform_to_schema() {
+        let mut table = users_table::<i64>(DataType::Int);
+        assert_eq!(table.generate_rows(100, 7).unwrap(), 100);
+        assert_eq!(table.rows.len(), 100);
+
+        for (key, record) in &table.rows {
+            assert_eq!(record.fields.len(), table.schema.len());
+            for (name, value) in &record.fields {
+                assert_eq!(value.get_type(), table.schema[name]);
+            }
+            assert_eq!(*record.fields["id"], DataValue::Int(*key));
+            match record.fields["age"].as_ref() {
+                DataValue::Int(age) => assert!((18..=90).contains(age)),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_int_keys_are_sequential_and_skip_existing() {
+        let mut table = users_table::<i64>(DataType::Int);
+        table.generate_rows(3, 1).unwrap();
+        table.rows.remove(&2);
+        table.generate_rows(2, 1).unwrap();
+
+        assert_eq!(table.rows.keys().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_enum_columns_take_variants() {
+        let mut table = users_table::<i64>(DataType::Int);
+        table.enums.insert(
+            "name".to_string(),
+            vec!["Alice".to_string(), "Bob".to_string()],
+        );
+        table.generate_rows(20, 3).unwrap();
+
+        assert!(table.rows.values().all(|record| {
+            matches!(record.fields["name"].as_ref(), DataValue::String(name) if name == "Alice" || name == "Bob")
+        }));
+    }
+
+    #[test]
+    fn test_same_seed_same_rows() {
+        let mut first = users_table::<String>(DataType::String);
+        let mut second = users_table::<String>(DataType::String);
+        first.generate_rows(50, 42).unwrap();
+        second.generate_rows(50, 42).unwrap();
+
+        assert_eq!(first.rows.len(), 50);
+        assert_eq!(first.rows, second.rows);
+    }
+}
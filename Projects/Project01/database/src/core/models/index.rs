@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::core::DataValue;
+
+use super::{key::DatabaseKey, record::Record};
+
+/// Specifies the data structure backing an [`Index`].
+///
+/// It is serialized under its name in the query language (e.g., `"BTREE"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum IndexKind {
+    /// An ordered `BTreeMap`, serving both equality and range lookups (the default).
+    #[default]
+    BTree,
+    /// A `HashMap`, serving equality lookups in constant time (`USING HASH`).
+    Hash,
+}
+
+/// The values of the indexed column mapped to the keys of the records holding them.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum IndexEntries<K: DatabaseKey> {
+    /// The entries of an `IndexKind::BTree` index, ordered by value.
+    BTree(BTreeMap<DataValue, Vec<K>>),
+    /// The entries of an `IndexKind::Hash` index.
+    Hash(HashMap<DataValue, Vec<K>>),
+}
+
+/// Represents a secondary index over one column of a table, created with `CREATE_INDEX`.
+///
+/// The index maps every value of the column to the keys of the records holding it.
+/// It is kept up to date by the [`Table`](super::table::Table) methods that modify rows.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Index<K: DatabaseKey> {
+    /// The name of the indexed column.
+    pub field: String,
+    /// The keys of the records, grouped by the value of the indexed column.
+    pub entries: IndexEntries<K>,
+}
+
+impl<K: DatabaseKey> Index<K> {
+    /// Creates an empty index of the given kind over the column `field`.
+    pub fn new(field: &str, kind: IndexKind) -> Self {
+        let entries = match kind {
+            IndexKind::BTree => IndexEntries::BTree(BTreeMap::new()),
+            IndexKind::Hash => IndexEntries::Hash(HashMap::new()),
+        };
+        Self {
+            field: field.to_string(),
+            entries,
+        }
+    }
+
+    /// Returns the kind of data structure backing the index.
+    pub fn kind(&self) -> IndexKind {
+        match self.entries {
+            IndexEntries::BTree(_) => IndexKind::BTree,
+            IndexEntries::Hash(_) => IndexKind::Hash,
+        }
+    }
+
+    /// Adds the record stored under `key` to the index.
+    pub fn insert(&mut self, key: &K, record: &Record) {
+        let Some(value) = record.fields.get(&self.field) else {
+            return;
+        };
+        let keys = match &mut self.entries {
+            IndexEntries::BTree(entries) => entries.entry(value.as_ref().clone()).or_default(),
+            IndexEntries::Hash(entries) => entries.entry(value.as_ref().clone()).or_default(),
+        };
+        keys.push(key.clone());
+    }
+
+    /// Removes the record stored under `key` from the index, dropping the
+    /// value once no record holds it.
+    pub fn remove(&mut self, key: &K, record: &Record) {
+        let Some(value) = record.fields.get(&self.field) else {
+            return;
+        };
+        let keys = match &mut self.entries {
+            IndexEntries::BTree(entries) => entries.get_mut(value),
+            IndexEntries::Hash(entries) => entries.get_mut(value),
+        };
+        let Some(keys) = keys else {
+            return;
+        };
+
+        keys.retain(|k| k != key);
+        if keys.is_empty() {
+            match &mut self.entries {
+                IndexEntries::BTree(entries) => entries.remove(value),
+                IndexEntries::Hash(entries) => entries.remove(value),
+            };
+        }
+    }
+
+    /// Reserves room for `additional` more values in a hash index, so that adding
+    /// many records at once does not grow it repeatedly. An ordered index does
+    /// not need it.
+    pub fn reserve(&mut self, additional: usize) {
+        if let IndexEntries::Hash(entries) = &mut self.entries {
+            entries.reserve(additional);
+        }
+    }
+
+    /// Removes every entry from the index.
+    pub fn clear(&mut self) {
+        match &mut self.entries {
+            IndexEntries::BTree(entries) => entries.clear(),
+            IndexEntries::Hash(entries) => entries.clear(),
+        }
+    }
+
+    /// Returns the keys of the records whose indexed column equals `value`.
+    pub fn get(&self, value: &DataValue) -> &[K] {
+        let keys = match &self.entries {
+            IndexEntries::BTree(entries) => entries.get(value),
+            IndexEntries::Hash(entries) => entries.get(value),
+        };
+        keys.map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of distinct values in the index.
+    pub fn len(&self) -> usize {
+        match &self.entries {
+            IndexEntries::BTree(entries) => entries.len(),
+            IndexEntries::Hash(entries) => entries.len(),
+        }
+    }
+
+    /// Returns `true` if no record is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(age: DataValue) -> Record {
+        Record::new(HashMap::from([("age".to_string(), age)]))
+    }
+
+    #[test]
+    fn test_index_kinds_agree() {
+        for kind in [IndexKind::BTree, IndexKind::Hash] {
+            let mut index = Index::<i64>::new("age", kind);
+            assert_eq!(index.kind(), kind);
+
+            index.insert(&1, &record(DataValue::Int(30)));
+            index.insert(&2, &record(DataValue::Int(30)));
+            index.insert(&3, &record(DataValue::Int(25)));
+            assert_eq!(index.get(&DataValue::Int(30)), [1, 2]);
+            assert_eq!(index.len(), 2);
+
+            index.remove(&3, &record(DataValue::Int(25)));
+            assert!(index.get(&DataValue::Int(25)).is_empty());
+            assert_eq!(index.len(), 1);
+
+            index.clear();
+            assert!(index.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_hash_index_float_zero() {
+        let mut index = Index::<i64>::new("age", IndexKind::Hash);
+        index.insert(&1, &record(DataValue::Float(-0.0)));
+        assert_eq!(index.get(&DataValue::Float(0.0)), [1]);
+    }
+}
@@ -3,7 +3,7 @@ use std::hash::Hash;
 
 use bincode::{Decode, Encode};
 
-use crate::core::{DataType, DataValue};
+use crate::core::{DataType, DataValue, Uuid};
 
 /// Defines the contract for types that can be used as a primary key in a table.
 ///
@@ -60,3 +60,20 @@ impl DatabaseKey for i64 {
         }
     }
 }
+
+/// Implements the `DatabaseKey` contract for `Uuid`.
+impl DatabaseKey for Uuid {
+    /// The `DataType` equivalent for a `Uuid` key is `DataType::Uuid`.
+    fn key_type() -> DataType {
+        DataType::Uuid
+    }
+
+    /// Converts a `DataValue::Uuid` into a `Uuid`.
+    /// Returns `None` for any other `DataValue` variant.
+    fn from_datavalue(value: &DataValue) -> Option<Self> {
+        match value {
+            DataValue::Uuid(uuid) => Some(*uuid),
+            _ => None,
+        }
+    }
+}
@@ -1,17 +1,77 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use bincode::{Decode, Encode};
 
-use crate::core::DataValue;
+use crate::{
+    DatabaseResult,
+    core::{DataValue, FromDataValue, RecordError},
+};
 
 /// Represents a single record or row within a table.
 ///
 /// It stores the actual data as a map where the key is the column (field) name
 /// and the value is the corresponding `DataValue`.
+///
+/// The values are shared through `Arc`s, so that cloning a record (e.g. when a
+/// `SELECT` projects the rows of a table) does not copy strings or bytes.
+/// They are encoded as plain `DataValue`s.
 #[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub struct Record {
     /// A map holding the data for this record, associating column names with their values.
-    pub fields: HashMap<String, DataValue>,
+    pub fields: HashMap<String, Arc<DataValue>>,
+}
+
+impl Record {
+    /// Creates a record from plain column values.
+    pub fn new(fields: HashMap<String, DataValue>) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(name, value)| (name, Arc::new(value)))
+            .collect();
+        Record { fields }
+    }
+
+    /// Returns the value of `field`, or `None` if the record does not hold it.
+    pub fn get(&self, field: &str) -> Option<&DataValue> {
+        self.fields.get(field).map(Arc::as_ref)
+    }
+
+    /// Returns the value of `field` as the Rust type `T`.
+    ///
+    /// # Errors
+    /// * `RecordError::MissingField` - The record does not hold `field`, and `T`
+    ///   is not an `Option`.
+    /// * `RecordError::TypeMismatch` - The value is not of the `DataType` of `T`.
+    pub fn get_as<T: FromDataValue>(&self, field: &str) -> DatabaseResult<T> {
+        let Some(value) = self.get(field) else {
+            return T::missing().ok_or_else(|| {
+                RecordError::MissingField {
+                    field: field.to_string(),
+                }
+                .into()
+            });
+        };
+        T::from_datavalue(value).ok_or_else(|| {
+            RecordError::TypeMismatch {
+                field: field.to_string(),
+                expected: T::data_type(),
+                found: value.get_type(),
+            }
+            .into()
+        })
+    }
+
+    /// Returns a new record holding only the given `fields` of this one,
+    /// sharing their values.
+    ///
+    /// Fields that are not in the record are skipped.
+    pub fn project(&self, fields: &[String]) -> Record {
+        let fields = fields
+            .iter()
+            .filter_map(|field| Some((field.clone(), self.fields.get(field)?.clone())))
+            .collect();
+        Record { fields }
+    }
 }
 
 impl fmt::Display for Record {
@@ -0,0 +1,699 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, btree_map},
+    fmt,
+    fs::{File, OpenOptions},
+    io,
+    ops::RangeBounds,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bincode::{
+    BorrowDecode, Decode, Encode, config,
+    de::{BorrowDecoder, Decoder},
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{key::DatabaseKey, record::Record};
+
+/// The smallest size of the file of an on-disk table, grown as records are added.
+#[cfg(unix)]
+const MIN_FILE_CAPACITY: u64 = 64 * 1024;
+/// The number of bytes of removed records above which the file of an on-disk
+/// table is compacted, once they outnumber the bytes of the stored records.
+const COMPACTION_THRESHOLD: u64 = 1024 * 1024;
+
+/// Specifies where the records of a table are stored.
+///
+/// It is serialized under its name in the query language (e.g., `"DISK"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TableStorage {
+    /// In memory, as a `BTreeMap` from the keys to the records (the default).
+    #[default]
+    Memory,
+    /// In a memory-mapped temporary file, with only a `BTreeMap` from the keys to
+    /// the positions of the records kept in memory (`STORAGE DISK`).
+    Disk,
+}
+
+/// The records of a table, ordered by their unique keys.
+///
+/// Records stored in memory are borrowed by the accessors, while those of an
+/// on-disk table are decoded from its file on every access, hence the `Cow`s.
+pub struct Rows<K: DatabaseKey>(Repr<K>);
+
+enum Repr<K: DatabaseKey> {
+    Memory(BTreeMap<K, Record>),
+    Disk(DiskRows<K>),
+}
+
+/// The records of an on-disk table: an append-only file of encoded records and
+/// the position of the current record of each key.
+///
+/// Replacing or removing a record leaves its bytes in the file until it is
+/// compacted.
+struct DiskRows<K: DatabaseKey> {
+    slots: BTreeMap<K, Slot>,
+    file: RowFile,
+    /// The number of bytes of the file held by replaced or removed records.
+    garbage: u64,
+}
+
+/// The position of an encoded record in the file of an on-disk table.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    offset: u64,
+    len: u64,
+}
+
+impl<K: DatabaseKey> Rows<K> {
+    /// Creates an empty set of records stored in `storage`.
+    ///
+    /// # Errors
+    /// Returns the error of creating the file of an on-disk table.
+    pub fn new(storage: TableStorage) -> io::Result<Self> {
+        Ok(Self(match storage {
+            TableStorage::Memory => Repr::Memory(BTreeMap::new()),
+            TableStorage::Disk => Repr::Disk(DiskRows {
+                slots: BTreeMap::new(),
+                file: RowFile::create()?,
+                garbage: 0,
+            }),
+        }))
+    }
+
+    /// Returns where the records are stored.
+    pub fn storage(&self) -> TableStorage {
+        match &self.0 {
+            Repr::Memory(_) => TableStorage::Memory,
+            Repr::Disk(_) => TableStorage::Disk,
+        }
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Memory(records) => records.len(),
+            Repr::Disk(disk) => disk.slots.len(),
+        }
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if a record is stored under `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        match &self.0 {
+            Repr::Memory(records) => records.contains_key(key),
+            Repr::Disk(disk) => disk.slots.contains_key(key),
+        }
+    }
+
+    /// Returns the record stored under `key`.
+    pub fn get(&self, key: &K) -> Option<Cow<'_, Record>> {
+        match &self.0 {
+            Repr::Memory(records) => records.get(key).map(Cow::Borrowed),
+            Repr::Disk(disk) => disk
+                .slots
+                .get(key)
+                .map(|slot| Cow::Owned(disk.file.record(*slot))),
+        }
+    }
+
+    /// Returns an iterator over the keys and records, in key order.
+    pub fn iter(&self) -> Iter<'_, K> {
+        self.range(..)
+    }
+
+    /// Returns an iterator over the keys and records whose keys are in `range`,
+    /// in key order.
+    pub fn range(&self, range: impl RangeBounds<K>) -> Iter<'_, K> {
+        Iter(match &self.0 {
+            Repr::Memory(records) => IterRepr::Memory(records.range(range)),
+            Repr::Disk(disk) => IterRepr::Disk(disk.slots.range(range), &disk.file),
+        })
+    }
+
+    /// Returns an iterator over the keys, in order, without reading the records.
+    pub fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        match &self.0 {
+            Repr::Memory(records) => Box::new(records.keys()),
+            Repr::Disk(disk) => Box::new(disk.slots.keys()),
+        }
+    }
+
+    /// Returns an iterator over the records, in key order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = Cow<'_, Record>> {
+        self.iter().map(|(_, record)| record)
+    }
+
+    /// Stores `record` under `key`.
+    ///
+    /// # Errors
+    /// Returns the error of writing the record to the file of an on-disk table.
+    ///
+    /// # Returns
+    /// The record previously stored under `key`, if any.
+    pub fn insert(&mut self, key: K, record: Record) -> io::Result<Option<Record>> {
+        let disk = match &mut self.0 {
+            Repr::Memory(records) => return Ok(records.insert(key, record)),
+            Repr::Disk(disk) => disk,
+        };
+
+        let bytes =
+            bincode::encode_to_vec(&record, config::standard()).map_err(io::Error::other)?;
+        let slot = Slot {
+            offset: disk.file.append(&bytes)?,
+            len: bytes.len() as u64,
+        };
+        let previous = disk.slots.insert(key, slot).map(|previous| {
+            disk.garbage += previous.len;
+            disk.file.record(previous)
+        });
+        disk.compact_if_needed()?;
+        Ok(previous)
+    }
+
+    /// Removes the record stored under `key`.
+    ///
+    /// # Returns
+    /// The removed record, if any.
+    pub fn remove(&mut self, key: &K) -> Option<Record> {
+        match &mut self.0 {
+            Repr::Memory(records) => records.remove(key),
+            Repr::Disk(disk) => {
+                let slot = disk.slots.remove(key)?;
+                disk.garbage += slot.len;
+                Some(disk.file.record(slot))
+            }
+        }
+    }
+
+    /// Copies the records; the copy of an on-disk table gets its own file.
+    ///
+    /// # Errors
+    /// Returns the error of creating or writing the file of the copy of an on-disk table.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self(match &self.0 {
+            Repr::Memory(records) => Repr::Memory(records.clone()),
+            Repr::Disk(disk) => Repr::Disk(disk.copy()?),
+        }))
+    }
+
+    /// Removes every record.
+    pub fn clear(&mut self) {
+        match &mut self.0 {
+            Repr::Memory(records) => records.clear(),
+            Repr::Disk(disk) => {
+                disk.slots.clear();
+                disk.file.clear();
+                disk.garbage = 0;
+            }
+        }
+    }
+}
+
+impl<K: DatabaseKey> DiskRows<K> {
+    /// Rewrites the file with only the current records once the removed ones take
+    /// most of it.
+    fn compact_if_needed(&mut self) -> io::Result<()> {
+        if self.garbage < COMPACTION_THRESHOLD || self.garbage < self.file.len - self.garbage {
+            return Ok(());
+        }
+        *self = self.copy()?;
+        Ok(())
+    }
+
+    /// Copies the current records into a new file.
+    fn copy(&self) -> io::Result<Self> {
+        let mut file = RowFile::create()?;
+        let mut slots = BTreeMap::new();
+        for (key, slot) in &self.slots {
+            let offset = file.append(self.file.bytes(*slot).as_ref())?;
+            slots.insert(key.clone(), Slot { offset, ..*slot });
+        }
+        Ok(Self {
+            slots,
+            file,
+            garbage: 0,
+        })
+    }
+}
+
+impl<K: DatabaseKey> Default for Rows<K> {
+    fn default() -> Self {
+        Self(Repr::Memory(BTreeMap::new()))
+    }
+}
+
+impl<K: DatabaseKey> From<BTreeMap<K, Record>> for Rows<K> {
+    fn from(records: BTreeMap<K, Record>) -> Self {
+        Self(Repr::Memory(records))
+    }
+}
+
+/// Compares the keys and records, wherever they are stored.
+impl<K: DatabaseKey> PartialEq for Rows<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: DatabaseKey> fmt::Debug for Rows<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Encodes the storage of the records, followed by their number and every key
+/// and record in key order.
+impl<K: DatabaseKey> Encode for Rows<K> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.storage().encode(encoder)?;
+        (self.len() as u64).encode(encoder)?;
+        for (key, record) in self {
+            key.encode(encoder)?;
+            record.encode(encoder)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the records one by one, so that those of an on-disk table are written
+/// to its file as they are read.
+impl<K: DatabaseKey + Decode<Context>, Context> Decode<Context> for Rows<K> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Self::decode_with(decoder, K::decode)
+    }
+}
+
+impl<'de, K: DatabaseKey + BorrowDecode<'de, Context>, Context> BorrowDecode<'de, Context>
+    for Rows<K>
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        Self::decode_with(decoder, K::borrow_decode)
+    }
+}
+
+impl<K: DatabaseKey> Rows<K> {
+    /// Decodes the records, decoding the keys with `decode_key`.
+    fn decode_with<D: Decoder>(
+        decoder: &mut D,
+        mut decode_key: impl FnMut(&mut D) -> Result<K, DecodeError>,
+    ) -> Result<Self, DecodeError> {
+        let io_error = |inner| DecodeError::Io {
+            inner,
+            additional: 0,
+        };
+
+        let storage = TableStorage::decode(decoder)?;
+        let len = u64::decode(decoder)?;
+        let mut rows = Self::new(storage).map_err(io_error)?;
+        for _ in 0..len {
+            let key = decode_key(decoder)?;
+            let record = Record::decode(decoder)?;
+            rows.insert(key, record).map_err(io_error)?;
+        }
+        Ok(rows)
+    }
+}
+
+impl<'a, K: DatabaseKey> IntoIterator for &'a Rows<K> {
+    type Item = (&'a K, Cow<'a, Record>);
+    type IntoIter = Iter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Moves the keys and records out, in key order.
+impl<K: DatabaseKey> IntoIterator for Rows<K> {
+    type Item = (K, Record);
+    type IntoIter = IntoIter<K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(match self.0 {
+            Repr::Memory(records) => IntoIterRepr::Memory(records.into_iter()),
+            Repr::Disk(disk) => IntoIterRepr::Disk(disk.slots.into_iter(), disk.file),
+        })
+    }
+}
+
+/// An iterator moving the keys and records out of a [`Rows`], in key order.
+pub struct IntoIter<K: DatabaseKey>(IntoIterRepr<K>);
+
+enum IntoIterRepr<K: DatabaseKey> {
+    Memory(btree_map::IntoIter<K, Record>),
+    Disk(btree_map::IntoIter<K, Slot>, RowFile),
+}
+
+impl<K: DatabaseKey> Iterator for IntoIter<K> {
+    type Item = (K, Record);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterRepr::Memory(records) => records.next(),
+            IntoIterRepr::Disk(slots, file) => {
+                slots.next().map(|(key, slot)| (key, file.record(slot)))
+            }
+        }
+    }
+}
+
+/// An iterator over the keys and records of a [`Rows`], in key order.
+pub struct Iter<'a, K: DatabaseKey>(IterRepr<'a, K>);
+
+enum IterRepr<'a, K: DatabaseKey> {
+    Memory(btree_map::Range<'a, K, Record>),
+    Disk(btree_map::Range<'a, K, Slot>, &'a RowFile),
+}
+
+impl<'a, K: DatabaseKey> Iterator for Iter<'a, K> {
+    type Item = (&'a K, Cow<'a, Record>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterRepr::Memory(range) => range
+                .next()
+                .map(|(key, record)| (key, Cow::Borrowed(record))),
+            IterRepr::Disk(range, file) => range
+                .next()
+                .map(|(key, slot)| (key, Cow::Owned(file.record(*slot)))),
+        }
+    }
+}
+
+impl<'a, K: DatabaseKey> DoubleEndedIterator for Iter<'a, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterRepr::Memory(range) => range
+                .next_back()
+                .map(|(key, record)| (key, Cow::Borrowed(record))),
+            IterRepr::Disk(range, file) => range
+                .next_back()
+                .map(|(key, slot)| (key, Cow::Owned(file.record(*slot)))),
+        }
+    }
+}
+
+/// A temporary file the records of an on-disk table are appended to, which is
+/// removed when it is dropped.
+///
+/// On Unix, the file is mapped into memory, so that records are read straight
+/// from the page cache and written by copying them into the mapping; the file
+/// is grown (and remapped) by doubling its size. On Windows, records are read
+/// and written with positioned file operations.
+struct RowFile {
+    file: File,
+    path: PathBuf,
+    /// The number of bytes of the file holding records.
+    len: u64,
+    #[cfg(unix)]
+    map: mapping::Mapping,
+}
+
+impl RowFile {
+    /// Creates a new, empty file in the temporary directory.
+    fn create() -> io::Result<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("minirust_table_{}_{id}.rows", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        Ok(Self {
+            file,
+            path,
+            len: 0,
+            #[cfg(unix)]
+            map: mapping::Mapping::default(),
+        })
+    }
+
+    /// Appends `bytes` to the file.
+    ///
+    /// # Returns
+    /// The offset the bytes were written at.
+    fn append(&mut self, bytes: &[u8]) -> io::Result<u64> {
+        let offset = self.len;
+        self.write_at(offset, bytes)?;
+        self.len += bytes.len() as u64;
+        Ok(offset)
+    }
+
+    /// Forgets every record, keeping the size of the file.
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Decodes the record at `slot`.
+    ///
+    /// # Panics
+    /// Panics if the file cannot be read or the record cannot be decoded, which
+    /// only happens if the file was modified by another process.
+    fn record(&self, slot: Slot) -> Record {
+        bincode::decode_from_slice(&self.bytes(slot), config::standard())
+            .map(|(record, _)| record)
+            .expect("the file of an on-disk table holds the records written to it")
+    }
+
+    #[cfg(unix)]
+    fn write_at(&mut self, offset: u64, bytes: &[u8]) -> io::Result<()> {
+        let end = offset + bytes.len() as u64;
+        if end > self.map.len() as u64 {
+            let capacity = end.max(2 * self.map.len() as u64).max(MIN_FILE_CAPACITY);
+            self.file.set_len(capacity)?;
+            self.map = mapping::Mapping::new(&self.file, capacity as usize)?;
+        }
+        self.map.as_mut_slice()[offset as usize..end as usize].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn bytes(&self, slot: Slot) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.map.as_slice()[slot.offset as usize..(slot.offset + slot.len) as usize])
+    }
+
+    #[cfg(windows)]
+    fn write_at(&mut self, mut offset: u64, mut bytes: &[u8]) -> io::Result<()> {
+        use std::os::windows::fs::FileExt;
+
+        while !bytes.is_empty() {
+            let written = self.file.seek_write(bytes, offset)?;
+            bytes = &bytes[written..];
+            offset += written as u64;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn bytes(&self, slot: Slot) -> Cow<'_, [u8]> {
+        use std::os::windows::fs::FileExt;
+
+        let mut bytes = vec![0; slot.len as usize];
+        let mut read = 0;
+        while read < bytes.len() {
+            match self
+                .file
+                .seek_read(&mut bytes[read..], slot.offset + read as u64)
+            {
+                Ok(0) | Err(_) => panic!("the file of an on-disk table can be read"),
+                Ok(n) => read += n,
+            }
+        }
+        Cow::Owned(bytes)
+    }
+}
+
+impl Drop for RowFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+mod mapping {
+    use std::{fs::File, io, os::fd::AsRawFd, ptr};
+
+    /// A shared, writable mapping of the start of a file.
+    pub struct Mapping {
+        ptr: *mut u8,
+        len: usize,
+    }
+
+    // SAFETY: the mapping is owned by a single `RowFile`, and is only written
+    // through `&mut self`.
+    unsafe impl Send for Mapping {}
+    unsafe impl Sync for Mapping {}
+
+    impl Mapping {
+        /// Maps the first `len` bytes of `file`, which must be at least that long.
+        pub fn new(file: &File, len: usize) -> io::Result<Self> {
+            // SAFETY: the file is open for reading and writing, and is a temporary
+            // file created with a unique name that only its `RowFile` changes, so
+            // the mapped bytes are not truncated away while they are mapped.
+            let ptr = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                ptr: ptr.cast(),
+                len,
+            })
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn as_slice(&self) -> &[u8] {
+            if self.len == 0 {
+                return &[];
+            }
+            // SAFETY: `ptr` points to `len` mapped bytes, valid while `self` lives.
+            unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+        }
+
+        pub fn as_mut_slice(&mut self) -> &mut [u8] {
+            if self.len == 0 {
+                return &mut [];
+            }
+            // SAFETY: as in `as_slice`, and `&mut self` makes the access exclusive.
+            unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+        }
+    }
+
+    impl Default for Mapping {
+        fn default() -> Self {
+            Self {
+                ptr: ptr::null_mut(),
+                len: 0,
+            }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            if self.len > 0 {
+                // SAFETY: `ptr` and `len` describe a mapping created by `new`.
+                unsafe { libc::munmap(self.ptr.cast(), self.len) };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::core::DataValue;
+
+    fn record(name: &str) -> Record {
+        Record::new(HashMap::from([(
+            "name".to_string(),
+            DataValue::String(name.to_string()),
+        )]))
+    }
+
+    fn name(record: &Record) -> &DataValue {
+        &record.fields["name"]
+    }
+
+    #[test]
+    fn test_disk_rows() {
+        let mut rows = Rows::<i64>::new(TableStorage::Disk).unwrap();
+        assert_eq!(rows.storage(), TableStorage::Disk);
+        for (key, value) in [(3, "Carol"), (1, "Alice"), (2, "Bob")] {
+            assert!(rows.insert(key, record(value)).unwrap().is_none());
+        }
+
+        let previous = rows.insert(2, record("Robert")).unwrap().unwrap();
+        assert_eq!(*name(&previous), DataValue::String("Bob".into()));
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            *name(&rows.get(&2).unwrap()),
+            DataValue::String("Robert".into())
+        );
+        assert_eq!(rows.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(
+            rows.range(2..)
+                .rev()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>(),
+            [3, 2]
+        );
+
+        let removed = rows.remove(&1).unwrap();
+        assert_eq!(*name(&removed), DataValue::String("Alice".into()));
+        assert!(rows.remove(&1).is_none());
+        assert!(!rows.contains_key(&1));
+
+        let copy = rows.try_clone().unwrap();
+        rows.clear();
+        assert!(rows.is_empty());
+        assert_eq!(copy.len(), 2);
+        assert_eq!(
+            *name(&copy.get(&3).unwrap()),
+            DataValue::String("Carol".into())
+        );
+    }
+
+    #[test]
+    fn test_disk_rows_match_memory_rows() {
+        let mut memory = Rows::<i64>::default();
+        let mut disk = Rows::<i64>::new(TableStorage::Disk).unwrap();
+        for key in 0..1_000 {
+            memory.insert(key, record(&format!("user{key}"))).unwrap();
+            disk.insert(key, record(&format!("user{key}"))).unwrap();
+        }
+        assert_eq!(memory, disk);
+
+        let bytes = bincode::encode_to_vec(&disk, config::standard()).unwrap();
+        let (decoded, _): (Rows<i64>, _) =
+            bincode::decode_from_slice(&bytes, config::standard()).unwrap();
+        assert_eq!(decoded.storage(), TableStorage::Disk);
+        assert_eq!(decoded, memory);
+    }
+
+    #[test]
+    fn test_disk_rows_compaction() {
+        let mut rows = Rows::<i64>::new(TableStorage::Disk).unwrap();
+        let value = "x".repeat(1024);
+        for round in 0..2_000 {
+            rows.insert(round % 4, record(&value)).unwrap();
+        }
+
+        let Repr::Disk(disk) = &rows.0 else {
+            panic!("Expected an on-disk table");
+        };
+        assert!(disk.file.len < 2 * COMPACTION_THRESHOLD);
+        assert_eq!(rows.len(), 4);
+        assert!(
+            rows.values()
+                .all(|record| *name(&record) == DataValue::String(value.clone()))
+        );
+    }
+}
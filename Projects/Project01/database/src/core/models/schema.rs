@@ -0,0 +1,61 @@
+use crate::core::{DataType, DataValue};
+
+use super::{key::DatabaseKey, table::Table};
+
+/// A read-only view of a column of a table, see [`TableSchema`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSchema<'a> {
+    /// The name of the column.
+    pub name: &'a str,
+    /// The type of the values of the column.
+    pub data_type: DataType,
+    /// The value declared with `DEFAULT`, if any.
+    pub default: Option<&'a DataValue>,
+    /// The allowed values of a column declared as `ENUM`, if it is one.
+    pub variants: Option<&'a [String]>,
+}
+
+/// A read-only view of the schema of a table, for frontends and tooling
+/// listing the tables of a database (see [`Database::schemas`]).
+///
+/// [`Database::schemas`]: crate::core::Database::schemas
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSchema<'a> {
+    /// The name of the table.
+    pub name: &'a str,
+    /// The name of the primary key column.
+    pub key_field: &'a str,
+    /// The columns of the table, sorted by name.
+    pub columns: Vec<ColumnSchema<'a>>,
+    /// The number of records currently in the table.
+    pub row_count: usize,
+}
+
+impl<'a> TableSchema<'a> {
+    /// Returns the schema of `table`.
+    pub fn of<K: DatabaseKey>(table: &'a Table<K>) -> Self {
+        let mut columns = table
+            .schema
+            .iter()
+            .map(|(name, data_type)| ColumnSchema {
+                name,
+                data_type: *data_type,
+                default: table.defaults.get(name),
+                variants: table.enums.get(name).map(Vec::as_slice),
+            })
+            .collect::<Vec<_>>();
+        columns.sort_by_key(|column| column.name);
+
+        Self {
+            name: &table.name,
+            key_field: &table.key_field,
+            columns,
+            row_count: table.rows.len(),
+        }
+    }
+
+    /// Returns the column `name`, if the table has one.
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema<'a>> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet};
+
+use bincode::{Decode, Encode};
+
+use crate::core::DataValue;
+
+use super::{key::DatabaseKey, table::Table};
+
+/// The statistics of one column, computed by `ANALYZE`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ColumnStatistics {
+    /// The number of distinct values in the column.
+    pub distinct_count: usize,
+    /// The smallest value of the column, or `None` if the table is empty.
+    pub min: Option<DataValue>,
+    /// The largest value of the column, or `None` if the table is empty.
+    pub max: Option<DataValue>,
+}
+
+/// The statistics of a table, computed by `ANALYZE`.
+///
+/// They describe the table at the time of the last `ANALYZE` and are not updated
+/// by later modifications, so they are only used as estimates.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct TableStatistics {
+    /// The number of records in the table.
+    pub row_count: usize,
+    /// The statistics of every column, by column name.
+    pub columns: HashMap<String, ColumnStatistics>,
+}
+
+impl TableStatistics {
+    /// Computes the statistics of the current contents of `table`.
+    pub fn compute<K: DatabaseKey>(table: &Table<K>) -> Self {
+        let columns = table
+            .schema
+            .keys()
+            .map(|column| {
+                let values = table
+                    .rows
+                    .values()
+                    .filter_map(|record| record.fields.get(column).cloned())
+                    .collect::<HashSet<_>>();
+                let statistics = ColumnStatistics {
+                    distinct_count: values.len(),
+                    min: values.iter().min().map(|value| value.as_ref().clone()),
+                    max: values.iter().max().map(|value| value.as_ref().clone()),
+                };
+                (column.clone(), statistics)
+            })
+            .collect();
+
+        Self {
+            row_count: table.rows.len(),
+            columns,
+        }
+    }
+
+    /// Estimates how many records hold one given value of `column`, assuming the
+    /// values are spread evenly.
+    pub fn rows_per_value(&self, column: &str) -> f64 {
+        match self.columns.get(column) {
+            Some(statistics) if statistics.distinct_count > 0 => {
+                self.row_count as f64 / statistics.distinct_count as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Estimates the fraction of records whose `column` lies between `lower` and
+    /// `upper` (`None` meaning unbounded), interpolating between the column's
+    /// minimum and maximum for numbers and assuming a third otherwise.
+    pub fn range_selectivity(
+        &self,
+        column: &str,
+        lower: Option<&DataValue>,
+        upper: Option<&DataValue>,
+    ) -> f64 {
+        const DEFAULT_SELECTIVITY: f64 = 1.0 / 3.0;
+
+        let Some(ColumnStatistics {
+            min: Some(min),
+            max: Some(max),
+            ..
+        }) = self.columns.get(column)
+        else {
+            return 0.0;
+        };
+        let (Some(min), Some(max)) = (as_number(min), as_number(max)) else {
+            return DEFAULT_SELECTIVITY;
+        };
+        let lower = lower.and_then(as_number).unwrap_or(min).max(min);
+        let upper = upper.and_then(as_number).unwrap_or(max).min(max);
+
+        if upper < lower {
+            0.0
+        } else if max == min {
+            1.0
+        } else {
+            (upper - lower) / (max - min)
+        }
+    }
+}
+
+fn as_number(value: &DataValue) -> Option<f64> {
+    match value {
+        DataValue::Int(v) => Some(*v as f64),
+        DataValue::Float(v) if !v.is_nan() => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataType, Record};
+
+    #[test]
+    fn test_compute_statistics() {
+        let schema = HashMap::from([
+            ("id".to_string(), DataType::Int),
+            ("age".to_string(), DataType::Int),
+        ]);
+        let mut table = Table::<i64>::new("users", "id", schema).unwrap();
+        assert_eq!(TableStatistics::compute(&table).columns["age"].min, None);
+
+        for (id, age) in [(1, 30), (2, 25), (3, 30), (4, 50)] {
+            let fields = HashMap::from([
+                ("id".to_string(), DataValue::Int(id)),
+                ("age".to_string(), DataValue::Int(age)),
+            ]);
+            table.insert_row(id, Record::new(fields)).unwrap();
+        }
+
+        let statistics = TableStatistics::compute(&table);
+        assert_eq!(statistics.row_count, 4);
+        assert_eq!(
+            statistics.columns["age"],
+            ColumnStatistics {
+                distinct_count: 3,
+                min: Some(DataValue::Int(25)),
+                max: Some(DataValue::Int(50)),
+            }
+        );
+        assert_eq!(statistics.rows_per_value("id"), 1.0);
+        assert_eq!(
+            statistics.range_selectivity("age", Some(&DataValue::Int(25)), None),
+            1.0
+        );
+        assert_eq!(
+            statistics.range_selectivity("age", Some(&DataValue::Int(40)), None),
+            0.4
+        );
+        assert_eq!(
+            statistics.range_selectivity("age", Some(&DataValue::Int(60)), None),
+            0.0
+        );
+    }
+}
@@ -1,19 +1,26 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 
 use bincode::{Decode, Encode};
 
 use crate::{
     DatabaseResult,
-    core::{DataType, DatabaseError},
+    ast::Condition,
+    core::{CreateError, DataType, DataValue, DatabaseError},
 };
 
-use super::{key::DatabaseKey, record::Record};
+use super::{
+    index::{Index, IndexKind},
+    key::DatabaseKey,
+    record::Record,
+    rows::{Rows, TableStorage},
+    statistics::TableStatistics,
+};
 
 /// Represents a single table within the database.
 ///
 /// A table is generic over its primary key type `K` and stores both its
 /// schema (column definitions) and its data (rows).
-#[derive(Debug, Clone, Encode, Decode)]
+#[derive(Debug, Encode, Decode)]
 pub struct Table<K: DatabaseKey> {
     /// The name of the table.
     pub name: String,
@@ -21,11 +28,28 @@ pub struct Table<K: DatabaseKey> {
     pub key_field: String,
     /// The table's schema, mapping column names to their respective `DataType`.
     pub schema: HashMap<String, DataType>,
+    /// The default values of the columns declared with `DEFAULT`, used for
+    /// fields that an `INSERT` does not assign.
+    pub defaults: HashMap<String, DataValue>,
+    /// The allowed values of the `STRING` columns declared as `ENUM`.
+    pub enums: HashMap<String, Vec<String>>,
+    /// The `CHECK` conditions that `INSERT` and `UPDATE` require every written
+    /// record to satisfy. `GENERATE` does not enforce them.
+    pub checks: Vec<Condition>,
+    /// The secondary indexes created with `CREATE_INDEX`, by index name.
+    ///
+    /// They are kept consistent with `rows` as long as the rows are modified
+    /// through [`Table::insert_row`], [`Table::remove_row`] and [`Table::clear_rows`].
+    pub indexes: HashMap<String, Index<K>>,
+    /// The statistics computed by the last `ANALYZE`, or `None` if the table
+    /// has never been analyzed. Later modifications do not update them.
+    pub statistics: Option<TableStatistics>,
     /// The actual data stored as rows.
     ///
-    /// A `BTreeMap` is used to store rows, mapping the unique primary key (of type `K`)
-    /// to the full `Record` object. This ensures keys are sorted and unique.
-    pub rows: BTreeMap<K, Record>,
+    /// The rows map the unique primary key (of type `K`) to the full `Record`
+    /// object, sorted by key. They are kept in memory, or in a memory-mapped file
+    /// for a table created with `STORAGE DISK` (see [`TableStorage`]).
+    pub rows: Rows<K>,
 }
 
 impl<K: DatabaseKey> Table<K> {
@@ -52,13 +76,9 @@ impl<K: DatabaseKey> Table<K> {
         key_field: &str,
         schema: HashMap<String, DataType>,
     ) -> DatabaseResult<Self> {
-        let key_schema_type =
-            schema
-                .get(key_field)
-                .ok_or_else(|| DatabaseError::FieldNotFound {
-                    table: name.to_string(),
-                    field: key_field.to_string(),
-                })?;
+        let key_schema_type = schema.get(key_field).ok_or_else(|| {
+            DatabaseError::field_not_found(name, key_field, schema.keys().map(String::as_str))
+        })?;
 
         let generic_key_type = K::key_type();
 
@@ -75,7 +95,233 @@ impl<K: DatabaseKey> Table<K> {
             name: name.to_string(),
             key_field: key_field.to_string(),
             schema,
-            rows: BTreeMap::new(),
+            defaults: HashMap::new(),
+            enums: HashMap::new(),
+            checks: Vec::new(),
+            indexes: HashMap::new(),
+            statistics: None,
+            rows: Rows::default(),
+        })
+    }
+
+    /// Checks that every name in `fields` is a column of the table.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::FieldNotFound` for the first name that is not in the schema.
+    pub fn check_fields<'f>(
+        &self,
+        fields: impl IntoIterator<Item = &'f String>,
+    ) -> DatabaseResult<()> {
+        match fields
+            .into_iter()
+            .find(|field| !self.schema.contains_key(*field))
+        {
+            Some(field) => Err(self.field_not_found(field)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a `FieldNotFound` error for `field`, suggesting the closest of the
+    /// table's columns.
+    pub fn field_not_found(&self, field: &str) -> DatabaseError {
+        DatabaseError::field_not_found(&self.name, field, self.schema.keys().map(String::as_str))
+    }
+
+    /// Checks that `value` can be stored in the column `field`.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::FieldNotFound` if the column is not in the schema.
+    /// Returns `DatabaseError::TypeMismatch` if the value does not match the column's type.
+    /// Returns `DatabaseError::InvalidEnumValue` if the column is an `ENUM` and the
+    /// value is not one of its variants.
+    pub fn check_value(&self, field: &str, value: &DataValue) -> DatabaseResult<()> {
+        let expected = self
+            .schema
+            .get(field)
+            .ok_or_else(|| self.field_not_found(field))?;
+
+        if *expected != value.get_type() {
+            return Err(DatabaseError::TypeMismatch {
+                table: self.name.clone(),
+                field: field.to_string(),
+                expected: *expected,
+                found: value.get_type(),
+            });
+        }
+
+        if let (Some(variants), DataValue::String(s)) = (self.enums.get(field), value)
+            && !variants.contains(s)
+        {
+            return Err(DatabaseError::InvalidEnumValue {
+                table: self.name.clone(),
+                field: field.to_string(),
+                value: value.to_string(),
+                variants: variants.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Sets the allowed values of the table's `ENUM` columns.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::FieldNotFound` if variants are given for a column
+    /// that is not in the schema.
+    /// Returns `DatabaseError::TypeMismatch` if such a column is not a `STRING` column.
+    pub fn with_enums(mut self, enums: HashMap<String, Vec<String>>) -> DatabaseResult<Self> {
+        for field in enums.keys() {
+            let data_type = self
+                .schema
+                .get(field)
+                .ok_or_else(|| self.field_not_found(field))?;
+
+            if *data_type != DataType::String {
+                return Err(DatabaseError::TypeMismatch {
+                    table: self.name.clone(),
+                    field: field.clone(),
+                    expected: *data_type,
+                    found: DataType::String,
+                });
+            }
+        }
+
+        self.enums = enums;
+        Ok(self)
+    }
+
+    /// Sets the `CHECK` conditions of the table.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::FieldNotFound` if a condition refers to a column
+    /// that is not in the schema.
+    pub fn with_checks(mut self, checks: Vec<Condition>) -> DatabaseResult<Self> {
+        self.check_fields(checks.iter().flat_map(Condition::columns))?;
+
+        self.checks = checks;
+        Ok(self)
+    }
+
+    /// Sets the default values of the table's columns.
+    ///
+    /// # Errors
+    /// Returns the error of [`Table::check_value`] for the first default that
+    /// cannot be stored in its column.
+    pub fn with_defaults(mut self, defaults: HashMap<String, DataValue>) -> DatabaseResult<Self> {
+        for (field, value) in &defaults {
+            self.check_value(field, value)?;
+        }
+
+        self.defaults = defaults;
+        Ok(self)
+    }
+
+    /// Sets where the records of the table are stored, moving the records
+    /// already stored.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the file of an on-disk table cannot be
+    /// created or written.
+    pub fn with_storage(mut self, storage: TableStorage) -> DatabaseResult<Self> {
+        if self.rows.storage() == storage {
+            return Ok(self);
+        }
+
+        let mut rows = Rows::new(storage)?;
+        for (key, record) in std::mem::take(&mut self.rows) {
+            rows.insert(key, record)?;
+        }
+        self.rows = rows;
+        Ok(self)
+    }
+
+    /// Creates the secondary index `name` of the given kind over the column `field`
+    /// and fills it with the records already stored in the table.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::FieldNotFound` if the column is not in the schema.
+    /// Returns `DatabaseError` (wrapping `CreateError::IndexAlreadyExists`) if the
+    /// table already has an index called `name`.
+    pub fn create_index(&mut self, name: &str, field: &str, kind: IndexKind) -> DatabaseResult<()> {
+        self.check_fields([&field.to_string()])?;
+        if self.indexes.contains_key(name) {
+            return Err(CreateError::IndexAlreadyExists {
+                table: self.name.clone(),
+                name: name.to_string(),
+            }
+            .into());
+        }
+
+        let mut index = Index::new(field, kind);
+        for (key, record) in &self.rows {
+            index.insert(key, &record);
+        }
+        self.indexes.insert(name.to_string(), index);
+        Ok(())
+    }
+
+    /// Stores `record` under `key`, updating the indexes.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the record cannot be written to the file of
+    /// an on-disk table, leaving the table unchanged.
+    ///
+    /// # Returns
+    /// The record previously stored under `key`, if any.
+    pub fn insert_row(&mut self, key: K, record: Record) -> DatabaseResult<Option<Record>> {
+        let previous = self.rows.insert(key.clone(), record.clone())?;
+        if let Some(previous) = &previous {
+            for index in self.indexes.values_mut() {
+                index.remove(&key, previous);
+            }
+        }
+        for index in self.indexes.values_mut() {
+            index.insert(&key, &record);
+        }
+        Ok(previous)
+    }
+
+    /// Removes the record stored under `key`, updating the indexes.
+    ///
+    /// # Returns
+    /// The removed record, if any.
+    pub fn remove_row(&mut self, key: &K) -> Option<Record> {
+        let record = self.rows.remove(key)?;
+        for index in self.indexes.values_mut() {
+            index.remove(key, &record);
+        }
+        Some(record)
+    }
+
+    /// Removes every record, keeping the (emptied) indexes.
+    pub fn clear_rows(&mut self) {
+        self.rows.clear();
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
+    }
+
+    /// Computes the statistics of the current contents of the table and stores
+    /// them in [`Table::statistics`].
+    pub fn analyze(&mut self) -> &TableStatistics {
+        self.statistics.insert(TableStatistics::compute(self))
+    }
+
+    /// Copies the table, see [`Rows::try_clone`].
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the records of an on-disk table cannot be copied.
+    pub fn try_clone(&self) -> DatabaseResult<Self> {
+        Ok(Self {
+            name: self.name.clone(),
+            key_field: self.key_field.clone(),
+            schema: self.schema.clone(),
+            defaults: self.defaults.clone(),
+            enums: self.enums.clone(),
+            checks: self.checks.clone(),
+            indexes: self.indexes.clone(),
+            statistics: self.statistics.clone(),
+            rows: self.rows.try_clone()?,
         })
     }
 }
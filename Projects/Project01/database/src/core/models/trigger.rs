@@ -0,0 +1,95 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    DatabaseResult,
+    core::{Database, DatabaseKey, Record},
+};
+
+/// The row changes a [`Trigger`] runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    /// A record inserted (or merged by `ON CONFLICT`) by `INSERT`.
+    Insert,
+    /// A record removed by `DELETE`.
+    Delete,
+}
+
+/// A callback run before a record is inserted or removed, which rejects the
+/// change by returning the reason.
+pub type BeforeTrigger = Arc<dyn Fn(&Record) -> Result<(), String> + Send + Sync>;
+
+/// A callback run after a statement inserted or removed a record, which may
+/// change the database in turn (for example, to fill an audit table).
+pub type AfterTrigger<K> =
+    Arc<dyn Fn(&mut Database<K>, &Record) -> DatabaseResult<()> + Send + Sync>;
+
+/// What a [`Trigger`] runs.
+#[derive(Clone)]
+pub enum TriggerAction<K: DatabaseKey> {
+    /// Runs before every record is changed, and can reject the change.
+    Before(BeforeTrigger),
+    /// Runs for every changed record once the statement has changed them all.
+    After(AfterTrigger<K>),
+}
+
+/// A callback registered with [`Database::add_trigger`], run by the `INSERT` or
+/// `DELETE` statements changing the records of a table.
+#[derive(Clone)]
+pub struct Trigger<K: DatabaseKey> {
+    /// The name identifying the trigger in its database.
+    pub name: String,
+    /// The name of the table whose changes run the trigger.
+    pub table: String,
+    /// The changes that run the trigger.
+    pub event: TriggerEvent,
+    /// The callback of the trigger.
+    pub action: TriggerAction<K>,
+}
+
+impl<K: DatabaseKey> Trigger<K> {
+    /// Creates a trigger running `action` before every `event` on `table`.
+    pub fn before(
+        name: impl Into<String>,
+        table: impl Into<String>,
+        event: TriggerEvent,
+        action: impl Fn(&Record) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            event,
+            action: TriggerAction::Before(Arc::new(action)),
+        }
+    }
+
+    /// Creates a trigger running `action` after every `event` on `table`.
+    pub fn after(
+        name: impl Into<String>,
+        table: impl Into<String>,
+        event: TriggerEvent,
+        action: impl Fn(&mut Database<K>, &Record) -> DatabaseResult<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            event,
+            action: TriggerAction::After(Arc::new(action)),
+        }
+    }
+}
+
+/// Formats the trigger without its callback.
+impl<K: DatabaseKey> fmt::Debug for Trigger<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timing = match self.action {
+            TriggerAction::Before(_) => "Before",
+            TriggerAction::After(_) => "After",
+        };
+        f.debug_struct("Trigger")
+            .field("name", &self.name)
+            .field("table", &self.table)
+            .field("event", &self.event)
+            .field("timing", &timing)
+            .finish()
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Cursor, Read, Seek, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A stream opened for reading by a [`StorageBackend`].
+pub trait StorageReader: Read + Seek {}
+
+impl<T: Read + Seek> StorageReader for T {}
+
+/// A stream opened for writing by [`StorageBackend::create`]. The written bytes
+/// only take the place of the object once the writer is committed; a writer
+/// dropped before that leaves the previous object untouched.
+pub trait StorageWriter: Write {
+    /// Stores the written bytes durably and atomically replaces the object with
+    /// them, so that a crash leaves either the old or the new object in place.
+    fn commit(self: Box<Self>) -> io::Result<()>;
+}
+
+/// The storage the database persists its snapshots and its [`WriteAheadLog`]
+/// to, addressed by paths.
+///
+/// `DUMP_TO`, `LOAD_FROM`, `DUMP_TABLE`, `LOAD_TABLE`, `VERIFY`, `EXPORT_JSON`,
+/// `IMPORT_JSON` and the log go through the backend of their database, so that
+/// another storage (such as an object store) can be used by implementing this
+/// trait and creating the database with [`Database::with_storage`]. Scripts, CSV
+/// files and migrations are inputs provided by the user and are always read from
+/// the file system.
+///
+/// [`WriteAheadLog`]: crate::core::WriteAheadLog
+/// [`Database::with_storage`]: crate::core::Database::with_storage
+pub trait StorageBackend: Debug + Send + Sync {
+    /// Opens the object at `path` for reading.
+    ///
+    /// # Errors
+    /// Returns an error of kind `io::ErrorKind::NotFound` if there is no such object.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn StorageReader>>;
+
+    /// Creates the object at `path` for writing, replacing an existing one once
+    /// the writer is committed.
+    fn create(&self, path: &Path) -> io::Result<Box<dyn StorageWriter>>;
+
+    /// Appends `bytes` to the object at `path`, creating it if it does not exist,
+    /// and returns once they are stored durably.
+    fn append(&self, path: &Path, bytes: &[u8]) -> io::Result<()>;
+
+    /// Replaces the object at `path` with `bytes` atomically, so that a crash
+    /// leaves either the old or the new object in place.
+    fn replace(&self, path: &Path, bytes: &[u8]) -> io::Result<()>;
+
+    /// Shortens the object at `path` to its first `len` bytes.
+    fn truncate(&self, path: &Path, len: u64) -> io::Result<()>;
+
+    /// Reads the whole object at `path`.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.open(path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// The backend storing every object as a file at its path.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystemStorage;
+
+impl StorageBackend for FileSystemStorage {
+    fn open(&self, path: &Path) -> io::Result<Box<dyn StorageReader>> {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+
+    /// Writes to a file next to `path`, which is synced and renamed over it when
+    /// the writer is committed, and removed if the writer is dropped before that.
+    fn create(&self, path: &Path) -> io::Result<Box<dyn StorageWriter>> {
+        let temp_path = temp_path(path);
+        Ok(Box::new(FileWriter {
+            file: Some(BufWriter::new(File::create(&temp_path)?)),
+            temp_path,
+            path: path.to_path_buf(),
+        }))
+    }
+
+    fn append(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(bytes)?;
+        file.sync_data()
+    }
+
+    /// Writes `bytes` next to the file and renames the new file over it.
+    fn replace(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        let temp_path = temp_path(path);
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(bytes)?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+
+    fn truncate(&self, path: &Path, len: u64) -> io::Result<()> {
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(len)?;
+        file.sync_data()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+}
+
+/// The path of the file an object at `path` is written to before it is renamed
+/// over it.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.to_path_buf().into_os_string();
+    temp_path.push(".tmp");
+    PathBuf::from(temp_path)
+}
+
+/// A writer created by [`FileSystemStorage`], writing to a temporary file.
+struct FileWriter {
+    /// The temporary file, until the writer is committed.
+    file: Option<BufWriter<File>>,
+    temp_path: PathBuf,
+    path: PathBuf,
+}
+
+impl FileWriter {
+    fn file(&mut self) -> &mut BufWriter<File> {
+        self.file.as_mut().expect("the writer is not committed yet")
+    }
+}
+
+impl Write for FileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl StorageWriter for FileWriter {
+    fn commit(mut self: Box<Self>) -> io::Result<()> {
+        let file = self.file.take().expect("the writer is committed once");
+        let file = file.into_inner().map_err(|error| error.into_error())?;
+        file.sync_all()?;
+        fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// The backend keeping every object in memory, which is lost when the last
+/// clone of the backend is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStorage {
+    /// The content of every object, by its path.
+    objects: Arc<Mutex<HashMap<PathBuf, Vec<u8>>>>,
+}
+
+impl InMemoryStorage {
+    /// Locks the objects, recovering them if another thread panicked while
+    /// holding the lock.
+    fn objects(&self) -> MutexGuard<'_, HashMap<PathBuf, Vec<u8>>> {
+        self.objects
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Returns an error of kind `io::ErrorKind::NotFound` for `path`.
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no object at '{}'", path.display()),
+        )
+    }
+}
+
+impl StorageBackend for InMemoryStorage {
+    /// Opens a copy of the object, which is not affected by later changes to it.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn StorageReader>> {
+        Ok(Box::new(Cursor::new(self.read(path)?)))
+    }
+
+    /// Collects the written bytes, which replace the object when the writer is
+    /// committed.
+    fn create(&self, path: &Path) -> io::Result<Box<dyn StorageWriter>> {
+        Ok(Box::new(InMemoryWriter {
+            storage: self.clone(),
+            path: path.to_path_buf(),
+            bytes: Vec::new(),
+        }))
+    }
+
+    fn append(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        self.objects()
+            .entry(path.to_path_buf())
+            .or_default()
+            .extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn replace(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        self.objects().insert(path.to_path_buf(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn truncate(&self, path: &Path, len: u64) -> io::Result<()> {
+        let mut objects = self.objects();
+        let object = objects.get_mut(path).ok_or_else(|| Self::not_found(path))?;
+        object.truncate(len as usize);
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.objects()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Self::not_found(path))
+    }
+}
+
+/// A writer created by [`InMemoryStorage`].
+struct InMemoryWriter {
+    storage: InMemoryStorage,
+    path: PathBuf,
+    bytes: Vec<u8>,
+}
+
+impl Write for InMemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl StorageWriter for InMemoryWriter {
+    fn commit(self: Box<Self>) -> io::Result<()> {
+        self.storage.replace(&self.path, &self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_storage() {
+        let storage = InMemoryStorage::default();
+        let path = Path::new("db.bin");
+        assert_eq!(
+            storage.open(path).err().map(|error| error.kind()),
+            Some(io::ErrorKind::NotFound)
+        );
+
+        let mut writer = storage.create(path).unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.write_all(b" world").unwrap();
+        assert!(storage.read(path).is_err());
+        writer.commit().unwrap();
+        assert_eq!(storage.read(path).unwrap(), b"hello world");
+
+        let mut writer = storage.create(path).unwrap();
+        writer.write_all(b"lost").unwrap();
+        drop(writer);
+        assert_eq!(storage.read(path).unwrap(), b"hello world");
+
+        let mut reader = storage.open(path).unwrap();
+        storage.append(path, b"!").unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "hello world");
+
+        storage.truncate(path, 5).unwrap();
+        assert_eq!(storage.clone().read(path).unwrap(), b"hello");
+        storage.replace(path, b"bye").unwrap();
+        assert_eq!(storage.read(path).unwrap(), b"bye");
+    }
+
+    #[test]
+    fn test_file_system_storage_commits_atomically() {
+        let path = std::env::temp_dir().join(format!("storage_atomic_{}.bin", std::process::id()));
+        let storage = FileSystemStorage;
+        storage.replace(&path, b"old").unwrap();
+
+        let mut writer = storage.create(&path).unwrap();
+        writer.write_all(b"new, but never committed").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        drop(writer);
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        assert!(!temp_path(&path).exists());
+
+        let mut writer = storage.create(&path).unwrap();
+        writer.write_all(b"new").unwrap();
+        writer.commit().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
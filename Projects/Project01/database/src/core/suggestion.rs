@@ -0,0 +1,76 @@
+//! "Did you mean" suggestions for misspelled keywords, tables and columns.
+
+/// Returns the candidate closest to `name` by edit distance, ignoring case, if
+/// it is close enough to be a likely misspelling of it.
+///
+/// A candidate other than `name` itself is close enough when at most a third of
+/// the characters of `name` (and at least one) have to be inserted, deleted or
+/// substituted to spell it. Of equally close candidates, the first one is returned.
+pub(crate) fn closest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let lowercase = name.to_lowercase();
+    let max_distance = (lowercase.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| {
+            let distance = edit_distance(&lowercase, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the help suggesting the candidate closest to `name`, as `closest`
+/// finds it.
+pub(crate) fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    closest(name, candidates).map(|candidate| format!("Did you mean '{candidate}'?"))
+}
+
+/// Returns the Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("name", "name"), 0);
+        assert_eq!(edit_distance("nme", "name"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest() {
+        let columns = ["id", "name", "email", "age"];
+        assert_eq!(closest("nme", columns), Some("name"));
+        assert_eq!(closest("EMAL", columns), Some("email"));
+        assert_eq!(closest("name", columns), None);
+        assert_eq!(closest("Age", columns), Some("age"));
+        assert_eq!(closest("address", columns), None);
+        assert_eq!(
+            did_you_mean("SELCT", ["SELECT", "DELETE"]),
+            Some("Did you mean 'SELECT'?".to_string())
+        );
+    }
+}
@@ -1,19 +1,24 @@
 use std::{
     cmp::Ordering,
     fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
 };
 
 use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     DatabaseResult,
     ast::{AstError, Operator},
 };
 
-use super::errors::DatabaseError;
+use super::{errors::DatabaseError, uuid::Uuid};
 
 /// Represents the set of all possible data types for a column in a table's schema.
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Encode, Decode)]
+///
+/// It is serialized under its name in the query language (e.g., `"INT"`).
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum DataType {
     /// A boolean value (`true` or `false`).
     Boolean,
@@ -23,6 +28,10 @@ pub enum DataType {
     Int,
     /// A 64-bit floating-point number.
     Float,
+    /// An arbitrary sequence of bytes.
+    Bytes,
+    /// A 128-bit universally unique identifier.
+    Uuid,
 }
 
 /// Enables parsing a `DataType` from a string slice (e.g., from the parser).
@@ -39,6 +48,8 @@ impl TryFrom<&str> for DataType {
             "FLOAT" => Self::Float,
             "STRING" => Self::String,
             "BOOLEAN" => Self::Boolean,
+            "BYTES" => Self::Bytes,
+            "UUID" => Self::Uuid,
             other => {
                 return Err(DatabaseError::UnknownDataType {
                     data_type: other.to_string(),
@@ -49,6 +60,21 @@ impl TryFrom<&str> for DataType {
     }
 }
 
+/// Formats a `DataType` as its name in the query language (e.g., `INT`).
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Boolean => "BOOLEAN",
+            Self::String => "STRING",
+            Self::Int => "INT",
+            Self::Float => "FLOAT",
+            Self::Bytes => "BYTES",
+            Self::Uuid => "UUID",
+        };
+        write!(f, "{name}")
+    }
+}
+
 /// Represents a single, concrete value of any type supported by the database.
 #[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub enum DataValue {
@@ -60,6 +86,10 @@ pub enum DataValue {
     Int(i64),
     /// A 64-bit floating-point number.
     Float(f64),
+    /// An arbitrary sequence of bytes, written as a hex literal (e.g., `0xdeadbeef`).
+    Bytes(Vec<u8>),
+    /// A universally unique identifier, written as `u"550e8400-e29b-41d4-a716-446655440000"`.
+    Uuid(Uuid),
 }
 
 impl DataValue {
@@ -70,6 +100,42 @@ impl DataValue {
             Self::Float(_) => DataType::Float,
             Self::String(_) => DataType::String,
             Self::Boolean(_) => DataType::Boolean,
+            Self::Bytes(_) => DataType::Bytes,
+            Self::Uuid(_) => DataType::Uuid,
+        }
+    }
+
+    /// Reads a value of type `data_type` from its plain text form, as found in
+    /// files such as CSV or JSON (a string is taken as-is, without quotes).
+    ///
+    /// A `BOOLEAN` is `true` or `false` (of any case), `BYTES` is a hex literal
+    /// (e.g., `0xdeadbeef`) and a `UUID` is in its canonical form. A `FLOAT` may
+    /// be written without a fraction.
+    ///
+    /// # Returns
+    /// `None` if `text` is not a valid value of that type.
+    pub fn parse_as(data_type: DataType, text: &str) -> Option<Self> {
+        match data_type {
+            DataType::Boolean => match text.to_ascii_lowercase().as_str() {
+                "true" => Some(Self::Boolean(true)),
+                "false" => Some(Self::Boolean(false)),
+                _ => None,
+            },
+            DataType::String => Some(Self::String(text.to_string())),
+            DataType::Int => text.parse().ok().map(Self::Int),
+            DataType::Float => text.parse().ok().map(Self::Float),
+            DataType::Bytes => {
+                let digits = text.strip_prefix("0x")?;
+                if digits.len() % 2 != 0 {
+                    return None;
+                }
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<_>>()
+                    .map(Self::Bytes)
+            }
+            DataType::Uuid => text.parse().ok().map(Self::Uuid),
         }
     }
 
@@ -80,12 +146,28 @@ impl DataValue {
     ///
     /// * `DatabaseError::ComparisonTypeMismatch` if the two values are of different types.
     /// * `AstError::InvalidOperatorForType` if the operator is not valid for the type
-    ///   (e.g., `>` on a `Boolean`).
+    ///   (e.g., `>` on a `Boolean` or `Bytes`, or `LIKE` on an `Int`).
     pub fn compare(&self, op: &Operator, right: &Self) -> DatabaseResult<bool> {
+        if *op == Operator::Like {
+            return match (self, right) {
+                (Self::String(l), Self::String(r)) => Ok(Operator::like(l, r)),
+                (Self::String(_), r) => Err(DatabaseError::ComparisonTypeMismatch {
+                    expected: DataType::String,
+                    found: r.get_type(),
+                }),
+                (l, _) => Err(AstError::InvalidOperatorForType {
+                    operator: Operator::Like,
+                    dtype: l.get_type(),
+                }
+                .into()),
+            };
+        }
+
         match (self, right) {
             (Self::Int(l), Self::Int(r)) => Ok(op.evaluate(l, r)),
             (Self::Float(l), Self::Float(r)) => Ok(op.evaluate(l, r)),
             (Self::String(l), Self::String(r)) => Ok(op.evaluate(l, r)),
+            (Self::Uuid(l), Self::Uuid(r)) => Ok(op.evaluate(l, r)),
 
             (Self::Boolean(l), Self::Boolean(r)) => match op {
                 Operator::Eq => Ok(l == r),
@@ -96,6 +178,15 @@ impl DataValue {
                 }
                 .into()),
             },
+            (Self::Bytes(l), Self::Bytes(r)) => match op {
+                Operator::Eq => Ok(l == r),
+                Operator::NotEq => Ok(l != r),
+                _ => Err(AstError::InvalidOperatorForType {
+                    operator: op.clone(),
+                    dtype: DataType::Bytes,
+                }
+                .into()),
+            },
 
             (l, r) => Err(DatabaseError::ComparisonTypeMismatch {
                 expected: l.get_type(),
@@ -111,11 +202,18 @@ impl Display for DataValue {
         match self {
             Self::Boolean(v) => write!(f, "{v}"),
             Self::String(v) => {
-                let escaped = v.replace('"', "\\\"");
+                let escaped = v.replace('\\', "\\\\").replace('"', "\\\"");
                 write!(f, "\"{escaped}\"")
             }
             Self::Int(v) => write!(f, "{v}"),
+            // a whole float keeps its fraction, so that it is still read back as a `FLOAT`
+            Self::Float(v) if v.is_finite() && v.fract() == 0.0 => write!(f, "{v:.1}"),
             Self::Float(v) => write!(f, "{v}"),
+            Self::Bytes(v) => {
+                write!(f, "0x")?;
+                v.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+            Self::Uuid(v) => write!(f, "u\"{v}\""),
         }
     }
 }
@@ -125,6 +223,26 @@ impl Display for DataValue {
 /// This is required because `f64` does not implement `Eq`.
 impl Eq for DataValue {}
 
+/// Implements hashing consistent with equality, so that values can key a `HashMap`.
+///
+/// `0.0` and `-0.0` are equal and therefore hash the same.
+impl Hash for DataValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Boolean(v) => v.hash(state),
+            Self::String(v) => v.hash(state),
+            Self::Int(v) => v.hash(state),
+            Self::Float(v) => {
+                let v = if *v == 0.0 { 0.0 } else { *v };
+                v.to_bits().hash(state)
+            }
+            Self::Bytes(v) => v.hash(state),
+            Self::Uuid(v) => v.hash(state),
+        }
+    }
+}
+
 /// Implements partial ordering for `DataValue`.
 ///
 /// This implementation only provides an ordering for values of the *same* type.
@@ -141,7 +259,7 @@ impl PartialOrd for DataValue {
 /// order between different types and provides a total order for `f64`
 /// by handling `NaN` values.
 ///
-/// The defined cross-type order is: `Boolean < Int < Float < String`.
+/// The defined cross-type order is: `Boolean < Int < Float < String < Bytes < Uuid`.
 /// `NaN` is treated as the smallest possible `Float` value.
 impl Ord for DataValue {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -149,6 +267,8 @@ impl Ord for DataValue {
             (Self::Boolean(l), Self::Boolean(r)) => l.cmp(r),
             (Self::Int(l), Self::Int(r)) => l.cmp(r),
             (Self::String(l), Self::String(r)) => l.cmp(r),
+            (Self::Bytes(l), Self::Bytes(r)) => l.cmp(r),
+            (Self::Uuid(l), Self::Uuid(r)) => l.cmp(r),
             (Self::Float(l), Self::Float(r)) => {
                 if l.is_nan() && r.is_nan() {
                     Ordering::Equal
@@ -161,7 +281,7 @@ impl Ord for DataValue {
                 }
             }
 
-            // Order: Boolean < Int < Float < String
+            // Order: Boolean < Int < Float < String < Bytes < Uuid
             (Self::Boolean(_), _) => Ordering::Less,
             (_, Self::Boolean(_)) => Ordering::Greater,
 
@@ -170,6 +290,12 @@ impl Ord for DataValue {
 
             (Self::Float(_), _) => Ordering::Less,
             (_, Self::Float(_)) => Ordering::Greater,
+
+            (Self::String(_), _) => Ordering::Less,
+            (_, Self::String(_)) => Ordering::Greater,
+
+            (Self::Bytes(_), _) => Ordering::Less,
+            (_, Self::Bytes(_)) => Ordering::Greater,
         }
     }
 }
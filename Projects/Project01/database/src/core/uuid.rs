@@ -0,0 +1,128 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use bincode::{Decode, Encode};
+use miette::Diagnostic;
+use rand::Rng;
+use thiserror::Error;
+
+/// The positions of the hyphens in the canonical text form of a `Uuid`.
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// A 128-bit universally unique identifier.
+///
+/// It is written in the canonical `8-4-4-4-12` hexadecimal form, e.g.
+/// `550e8400-e29b-41d4-a716-446655440000`, and ordered by its bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Encode, Decode)]
+pub struct Uuid([u8; 16]);
+
+/// Represents an error raised when a string is not a UUID in the canonical form.
+#[derive(Error, Debug, Diagnostic)]
+#[error("Invalid UUID: `{value}`")]
+pub struct ParseUuidError {
+    pub value: String,
+}
+
+impl Uuid {
+    /// Creates a `Uuid` from its 16 bytes.
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the 16 bytes of the `Uuid`.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Creates a random (version 4) `Uuid` using `rng`.
+    pub fn new_v4<R: Rng>(rng: &mut R) -> Self {
+        let mut bytes: [u8; 16] = rng.random();
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+}
+
+/// Parses the canonical `8-4-4-4-12` form; hex digits may be of either case.
+impl FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseUuidError {
+            value: s.to_string(),
+        };
+
+        let is_canonical = s.len() == 36
+            && s.char_indices().all(|(i, c)| {
+                if HYPHENS.contains(&i) {
+                    c == '-'
+                } else {
+                    c.is_ascii_hexdigit()
+                }
+            });
+        if !is_canonical {
+            return Err(error());
+        }
+
+        let digits = s.replace('-', "");
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).map_err(|_| error())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// Renders the `Uuid` in the canonical lowercase form.
+impl Display for Uuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        let text = "550e8400-e29b-41d4-a716-446655440000";
+        let uuid = text.parse::<Uuid>().unwrap();
+        assert_eq!(uuid.as_bytes()[..4], [0x55, 0x0e, 0x84, 0x00]);
+        assert_eq!(uuid.to_string(), text);
+        assert_eq!(
+            "550E8400-E29B-41D4-A716-446655440000"
+                .parse::<Uuid>()
+                .unwrap(),
+            uuid
+        );
+
+        for invalid in [
+            "",
+            "550e8400e29b41d4a716446655440000",
+            "550e8400-e29b-41d4-a716-44665544000g",
+            "550e840-0e29b-41d4-a716-446655440000",
+        ] {
+            assert!(invalid.parse::<Uuid>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_new_v4_sets_version_and_variant() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let uuid = Uuid::new_v4(&mut rng);
+        assert_eq!(uuid.as_bytes()[6] >> 4, 4);
+        assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+        assert_ne!(uuid, Uuid::new_v4(&mut rng));
+    }
+}
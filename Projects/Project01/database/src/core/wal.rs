@@ -0,0 +1,259 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    DatabaseResult,
+    core::{FileSystemStorage, InMemoryStorage, StorageBackend},
+};
+
+/// The size of the little-endian length that precedes every entry.
+const LENGTH_SIZE: usize = size_of::<u32>();
+
+/// An append-only log of the queries that modified a database, used to
+/// rebuild it after an unexpected exit.
+///
+/// Every entry is a query string written as its length (a little-endian `u32`)
+/// followed by its UTF-8 bytes, and is stored durably before
+/// [`WriteAheadLog::append`] returns. An entry cut short by a crash is
+/// discarded when the log is opened again.
+#[derive(Debug)]
+pub struct WriteAheadLog {
+    /// The path of the log.
+    path: PathBuf,
+    /// The storage the log is written to.
+    storage: Arc<dyn StorageBackend>,
+    /// Whether a snapshot of the database may replace the whole log, which is
+    /// not the case once the log records several databases.
+    compaction: bool,
+    /// Whether the log holds the queries of a transaction in progress, which are
+    /// appended to the log of the database once the transaction is committed.
+    transaction: bool,
+}
+
+impl WriteAheadLog {
+    /// Opens the log file at `path`, creating an empty one if it does not exist.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the file cannot be opened, read or truncated.
+    pub fn open(path: impl Into<PathBuf>) -> DatabaseResult<Self> {
+        Self::open_with(path, Arc::new(FileSystemStorage))
+    }
+
+    /// Opens the log at `path` of `storage`, creating an empty one if it does
+    /// not exist.
+    ///
+    /// An incomplete entry at the end of the log is removed.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the log cannot be created, read or truncated.
+    pub fn open_with(
+        path: impl Into<PathBuf>,
+        storage: Arc<dyn StorageBackend>,
+    ) -> DatabaseResult<Self> {
+        let path = path.into();
+        storage.append(&path, &[])?;
+
+        let bytes = storage.read(&path)?;
+        let (_, valid_len) = Self::decode(&bytes);
+        if valid_len < bytes.len() {
+            storage.truncate(&path, valid_len as u64)?;
+        }
+
+        Ok(Self {
+            path,
+            storage,
+            compaction: true,
+            transaction: false,
+        })
+    }
+
+    /// Opens an empty in-memory log holding the queries of a transaction.
+    pub(crate) fn for_transaction() -> DatabaseResult<Self> {
+        let mut log = Self::open_with("transaction", Arc::new(InMemoryStorage::default()))?;
+        log.transaction = true;
+        Ok(log)
+    }
+
+    /// Returns `true` if the log holds the queries of a transaction in progress
+    /// (see [`WriteAheadLog::for_transaction`]).
+    pub(crate) fn is_transaction(&self) -> bool {
+        self.transaction
+    }
+
+    /// Returns the path of the log.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `true` if a snapshot may replace the whole log (see
+    /// [`WriteAheadLog::checkpoint`]).
+    pub fn compaction(&self) -> bool {
+        self.compaction
+    }
+
+    /// Allows or forbids a snapshot to replace the whole log, which only holds
+    /// every change logged so far while the log records a single database.
+    pub fn set_compaction(&mut self, enabled: bool) {
+        self.compaction = enabled;
+    }
+
+    /// Appends `query` to the log and stores it durably.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the entry cannot be written.
+    pub fn append(&mut self, query: &str) -> DatabaseResult<()> {
+        self.storage.append(&self.path, &Self::encode(query)?)?;
+        Ok(())
+    }
+
+    /// Appends every query of `queries` to the log with a single write and stores
+    /// them durably.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the entries cannot be written.
+    pub fn append_all<'q>(
+        &mut self,
+        queries: impl IntoIterator<Item = &'q String>,
+    ) -> DatabaseResult<()> {
+        let mut bytes = Vec::new();
+        for query in queries {
+            bytes.extend(Self::encode(query)?);
+        }
+        self.storage.append(&self.path, &bytes)?;
+        Ok(())
+    }
+
+    /// Replaces the whole log with the single entry `query`, such as a
+    /// `LOAD_FROM` of a snapshot that holds every change logged so far.
+    ///
+    /// The log is replaced atomically (see [`StorageBackend::replace`]), so a
+    /// crash leaves either the old or the new log in place.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the new log cannot be written.
+    pub fn checkpoint(&mut self, query: &str) -> DatabaseResult<()> {
+        self.storage.replace(&self.path, &Self::encode(query)?)?;
+        Ok(())
+    }
+
+    /// Reads every entry of the log, oldest first.
+    ///
+    /// # Errors
+    /// Returns `DatabaseError::Io` if the log cannot be read.
+    pub fn entries(&self) -> DatabaseResult<Vec<String>> {
+        let bytes = self.storage.read(&self.path)?;
+        Ok(Self::decode(&bytes).0)
+    }
+
+    /// Encodes `query` as a single entry.
+    fn encode(query: &str) -> io::Result<Vec<u8>> {
+        let len = u32::try_from(query.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "query is too long"))?;
+        let mut entry = Vec::with_capacity(LENGTH_SIZE + query.len());
+        entry.extend_from_slice(&len.to_le_bytes());
+        entry.extend_from_slice(query.as_bytes());
+        Ok(entry)
+    }
+
+    /// Decodes the entries of `bytes` up to the first incomplete or invalid one.
+    ///
+    /// # Returns
+    /// The decoded entries and the number of bytes they take.
+    fn decode(bytes: &[u8]) -> (Vec<String>, usize) {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while let Some(len) = bytes.get(offset..offset + LENGTH_SIZE) {
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let start = offset + LENGTH_SIZE;
+            let Some(Ok(query)) = bytes
+                .get(start..start + len)
+                .map(|query| std::str::from_utf8(query))
+            else {
+                break;
+            };
+            entries.push(query.to_string());
+            offset = start + len;
+        }
+        (entries, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::core::InMemoryStorage;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.wal", std::process::id()))
+    }
+
+    #[test]
+    fn test_append_and_reopen() {
+        let path = temp_path("wal_append");
+        let _ = fs::remove_file(&path);
+
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        assert!(wal.entries().unwrap().is_empty());
+        wal.append("CREATE t KEY id FIELDS id: INT;").unwrap();
+        wal.append("INSERT id = 1\nINTO t;").unwrap();
+        drop(wal);
+
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        wal.append("DELETE 1 FROM t;").unwrap();
+        assert_eq!(
+            wal.entries().unwrap(),
+            [
+                "CREATE t KEY id FIELDS id: INT;",
+                "INSERT id = 1\nINTO t;",
+                "DELETE 1 FROM t;"
+            ]
+        );
+
+        wal.checkpoint("LOAD_FROM \"db.bin\";").unwrap();
+        wal.append("TRUNCATE t;").unwrap();
+        assert_eq!(
+            wal.entries().unwrap(),
+            ["LOAD_FROM \"db.bin\";", "TRUNCATE t;"]
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_discards_torn_entry() {
+        let path = temp_path("wal_torn");
+        let _ = fs::remove_file(&path);
+
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        wal.append("TRUNCATE t;").unwrap();
+        drop(wal);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.extend_from_slice(&100u32.to_le_bytes());
+        bytes.extend_from_slice(b"DELETE");
+        fs::write(&path, bytes).unwrap();
+
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        wal.append("RENAME t TO u;").unwrap();
+        assert_eq!(wal.entries().unwrap(), ["TRUNCATE t;", "RENAME t TO u;"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_log_in_memory() {
+        let storage = InMemoryStorage::default();
+        let mut wal = WriteAheadLog::open_with("db.wal", Arc::new(storage.clone())).unwrap();
+        wal.append("TRUNCATE t;").unwrap();
+        wal.append("RENAME t TO u;").unwrap();
+        storage.append(Path::new("db.wal"), &[7, 0]).unwrap();
+
+        let mut wal = WriteAheadLog::open_with("db.wal", Arc::new(storage.clone())).unwrap();
+        assert_eq!(wal.entries().unwrap(), ["TRUNCATE t;", "RENAME t TO u;"]);
+        wal.checkpoint("LOAD_FROM \"db.bin\";").unwrap();
+        assert_eq!(wal.entries().unwrap(), ["LOAD_FROM \"db.bin\";"]);
+        assert!(!Path::new("db.wal").exists());
+    }
+}
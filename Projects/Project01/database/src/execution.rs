@@ -1,14 +1,32 @@
 //! The `execution` module.
 //!
 //! This module contains all logic for the **command execution phase**.
-//! It defines the `Execute` trait, holds the concrete implementation for each
-//! command (e.g., `Create`, `Select`), and provides the `build_execute_command`
-//! factory function to bridge the AST and the executor.
+//! It defines the `Execute` and `ExecuteStream` traits, holds the concrete
+//! implementation for each command (e.g., `Create`, `Select`), and provides the
+//! `build_execute_command` and `build_stream_command` factory functions to bridge
+//! the AST and the executor. `Database::execute` and `Session::execute_query` run
+//! a raw query through the whole pipeline, reusing the ASTs of repeated queries. The `recovery` functions execute commands while
+//! recording them in a write-ahead log, and replay such a log after a crash,
+//! while the `TransactionManager` executes the commands of a session inside its
+//! `BEGIN` ... `COMMIT` transactions. A `Session` routes every command to the
+//! database of the `Catalog` selected with `USE`.
 
 mod commands;
 mod execute;
+mod execute_query;
+mod execute_stream;
 mod execution_result;
+mod planner;
+mod recovery;
+mod session;
+mod transaction;
 
-pub use commands::build_execute_command;
+pub(crate) use commands::build_logged_command;
+pub use commands::{build_execute_command, build_stream_command};
 pub use execute::Execute;
+pub(crate) use execute_query::parse_cached;
+pub use execute_stream::{ExecuteStream, RecordStream};
 pub use execution_result::ExecutionResult;
+pub use recovery::{execute_logged, recover};
+pub use session::Session;
+pub use transaction::TransactionManager;
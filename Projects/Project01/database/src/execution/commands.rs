@@ -1,26 +1,67 @@
+mod analyze;
+mod compound_select;
 mod create;
+mod create_index;
 mod delete;
+mod dump_format;
+mod dump_table;
 mod dump_to;
+mod encryption;
+mod explain;
+mod export_json;
+mod generate;
+mod import_json;
 mod insert;
+mod insert_many;
+mod json_document;
+mod load_csv;
 mod load_from;
+mod load_table;
+mod migrate;
 mod read_from;
+mod rename;
 mod save_as;
 mod select;
+mod show_tables;
+mod triggered;
+mod truncate;
+mod update;
+mod verify;
 
+use analyze::Analyze;
+use compound_select::CompoundSelect;
 use create::Create;
+use create_index::CreateIndex;
 use delete::Delete;
+use dump_table::DumpTable;
 use dump_to::DumpTo;
+use explain::Explain;
+use export_json::ExportJson;
+use generate::Generate;
+use import_json::ImportJson;
 use insert::Insert;
+use load_csv::LoadCsv;
 use load_from::LoadFrom;
+use load_table::LoadTable;
+use migrate::Migrate;
 use read_from::ReadFrom;
+use rename::Rename;
 use save_as::SaveAs;
 use select::Select;
+use show_tables::ShowTables;
+use triggered::{RowTriggers, Triggered, TriggeredStmt};
+use truncate::Truncate;
+use update::Update;
+use verify::Verify;
 
 use crate::{
     DatabaseResult,
-    ast::Command,
-    core::{Database, DatabaseKey},
-    execution::Execute,
+    ast::{Command, SelectStmt},
+    core::{
+        CatalogError, Database, DatabaseError, DatabaseKey, Record, Table, TransactionError,
+        TriggerEvent, WriteAheadLog,
+    },
+    execution::{Execute, ExecuteStream, ExecutionResult},
 };
 
 /// The primary factory function for creating executable commands.
@@ -35,7 +76,10 @@ use crate::{
 /// # Errors
 ///
 /// Returns `DatabaseError::TableNotFound` if an operation (like `SELECT` or `INSERT`)
-/// targets a table that does not exist.
+/// targets a table that does not exist, `TransactionError::Unsupported` for
+/// `BEGIN`, `COMMIT` and `ROLLBACK`, which are executed by a `TransactionManager`,
+/// or `CatalogError::Unsupported` for `CREATE_DATABASE` and `USE`, which are
+/// executed by a `Session`.
 pub fn build_execute_command<'a, K: DatabaseKey>(
     database: &'a mut Database<K>,
     command: Command,
@@ -48,21 +92,100 @@ pub fn build_execute_command<'a, K: DatabaseKey>(
                 database,
             })
         }
-        Command::Delete(stmt) => {
+        Command::CreateIndex(stmt) => {
             database.push_to_history(&stmt.query);
             let table = database.get_table(&stmt.table_name)?;
-            Box::new(Delete { table, ast: stmt })
+            Box::new(CreateIndex { table, ast: stmt })
+        }
+        Command::Delete(stmt) => {
+            database.push_to_history(&stmt.query);
+            let triggers = RowTriggers::new(database, &stmt.table_name, TriggerEvent::Delete);
+            if triggers.is_empty() {
+                let table = database.get_table(&stmt.table_name)?;
+                Box::new(Delete {
+                    table,
+                    ast: stmt,
+                    triggers: None,
+                })
+            } else {
+                Box::new(Triggered {
+                    database,
+                    ast: TriggeredStmt::Delete(stmt),
+                    triggers,
+                })
+            }
         }
         Command::Insert(stmt) => {
+            database.push_to_history(&stmt.query);
+            let triggers = RowTriggers::new(database, &stmt.table_name, TriggerEvent::Insert);
+            if triggers.is_empty() {
+                let table = database.get_table(&stmt.table_name)?;
+                Box::new(Insert {
+                    table,
+                    ast: stmt,
+                    triggers: None,
+                })
+            } else {
+                Box::new(Triggered {
+                    database,
+                    ast: TriggeredStmt::Insert(stmt),
+                    triggers,
+                })
+            }
+        }
+        Command::Generate(stmt) => {
             database.push_to_history(&stmt.query);
             let table = database.get_table(&stmt.table_name)?;
-            Box::new(Insert { table, ast: stmt })
+            Box::new(Generate { table, ast: stmt })
+        }
+        Command::LoadCsv(stmt) => {
+            database.push_to_history(&stmt.query);
+            let table = database.get_table(&stmt.table_name)?;
+            Box::new(LoadCsv {
+                table,
+                ast: stmt,
+                wal: None,
+            })
         }
         Command::Select(stmt) => {
             database.push_to_history(&stmt.query);
             let table = database.get_table(&stmt.table_name)?;
             Box::new(Select { table, ast: stmt })
         }
+        Command::CompoundSelect(stmt) => {
+            database.push_to_history(&stmt.query);
+            Box::new(CompoundSelect {
+                database,
+                ast: stmt,
+            })
+        }
+        Command::Explain(stmt) => {
+            let table = database.get_table(&stmt.select.table_name)?;
+            Box::new(Explain { table, ast: stmt })
+        }
+        Command::ShowTables(_) => Box::new(ShowTables { database }),
+        Command::Analyze(stmt) => {
+            database.push_to_history(&stmt.query);
+            let table = database.get_table(&stmt.table_name)?;
+            Box::new(Analyze { table })
+        }
+        Command::Update(stmt) => {
+            database.push_to_history(&stmt.query);
+            let table = database.get_table(&stmt.table_name)?;
+            Box::new(Update { table, ast: stmt })
+        }
+        Command::Truncate(stmt) => {
+            database.push_to_history(&stmt.query);
+            let table = database.get_table(&stmt.table_name)?;
+            Box::new(Truncate { table })
+        }
+        Command::Rename(stmt) => {
+            database.push_to_history(&stmt.query);
+            Box::new(Rename {
+                ast: stmt,
+                database,
+            })
+        }
         Command::DumpTo(stmt) => Box::new(DumpTo {
             ast: stmt,
             database,
@@ -70,6 +193,31 @@ pub fn build_execute_command<'a, K: DatabaseKey>(
         Command::LoadFrom(stmt) => Box::new(LoadFrom {
             ast: stmt,
             database,
+            logged: false,
+        }),
+        Command::Verify(stmt) => Box::new(Verify::<K>::new(database.storage(), stmt)),
+        Command::DumpTable(stmt) => {
+            database.get_table(&stmt.table_name)?;
+            Box::new(DumpTable {
+                ast: stmt,
+                database,
+            })
+        }
+        Command::LoadTable(stmt) => {
+            database.push_to_history(&stmt.query);
+            Box::new(LoadTable {
+                ast: stmt,
+                database,
+                wal: None,
+            })
+        }
+        Command::ExportJson(stmt) => Box::new(ExportJson {
+            ast: stmt,
+            database,
+        }),
+        Command::ImportJson(stmt) => Box::new(ImportJson {
+            ast: stmt,
+            database,
         }),
         Command::SaveAs(stmt) => Box::new(SaveAs {
             ast: stmt,
@@ -78,8 +226,134 @@ pub fn build_execute_command<'a, K: DatabaseKey>(
         Command::ReadFrom(stmt) => Box::new(ReadFrom {
             ast: stmt,
             database,
+            wal: None,
+        }),
+        Command::Migrate(stmt) => Box::new(Migrate {
+            ast: stmt,
+            database,
+            wal: None,
+        }),
+        Command::Transaction(_) => return Err(TransactionError::Unsupported.into()),
+        Command::CreateDatabase(_) => {
+            return Err(CatalogError::Unsupported {
+                statement: "CREATE_DATABASE".to_string(),
+            }
+            .into());
+        }
+        Command::Use(_) => {
+            return Err(CatalogError::Unsupported {
+                statement: "USE".to_string(),
+            }
+            .into());
+        }
+    };
+
+    Ok(executable)
+}
+
+/// The factory function for commands recording their changes in the write-ahead
+/// log themselves, as the queries they amount to, rather than being recorded as
+/// the query that was executed. A `READ_FROM` or `MIGRATE` records the queries of
+/// its scripts, a `LOAD_CSV` the records it inserted and a `LOAD_TABLE` the dump
+/// it loaded, so that replaying the log does not depend on the files they read.
+/// An encrypted or inline `LOAD_FROM` records the plain snapshot it loaded, so
+/// that the log never holds a passphrase.
+///
+/// Other commands are built by `build_execute_command`, without the log.
+///
+/// # Errors
+///
+/// Returns the same errors as `build_execute_command`.
+pub(crate) fn build_logged_command<'a, K: DatabaseKey>(
+    database: &'a mut Database<K>,
+    command: Command,
+    wal: &'a mut WriteAheadLog,
+) -> DatabaseResult<Box<dyn Execute + 'a>> {
+    let executable: Box<dyn Execute> = match command {
+        Command::ReadFrom(stmt) => Box::new(ReadFrom {
+            ast: stmt,
+            database,
+            wal: Some(wal),
+        }),
+        Command::LoadCsv(stmt) => {
+            database.push_to_history(&stmt.query);
+            let table = database.get_table(&stmt.table_name)?;
+            Box::new(LoadCsv {
+                table,
+                ast: stmt,
+                wal: Some(wal),
+            })
+        }
+        Command::LoadTable(stmt) => {
+            database.push_to_history(&stmt.query);
+            Box::new(LoadTable {
+                ast: stmt,
+                database,
+                wal: Some(wal),
+            })
+        }
+        Command::Migrate(stmt) => Box::new(Migrate {
+            ast: stmt,
+            database,
+            wal: Some(wal),
         }),
+        Command::LoadFrom(stmt) => Box::new(LoadFrom {
+            ast: stmt,
+            database,
+            logged: true,
+        }),
+        command => return build_execute_command(database, command),
     };
 
     Ok(executable)
 }
+
+/// The factory function for `SELECT` commands whose records are produced lazily.
+///
+/// The statement is recorded in the history, as with `build_execute_command`.
+///
+/// # Errors
+///
+/// Returns `DatabaseError::TableNotFound` if the queried table does not exist.
+pub fn build_stream_command<'a, K: DatabaseKey>(
+    database: &'a mut Database<K>,
+    stmt: SelectStmt,
+) -> DatabaseResult<Box<dyn ExecuteStream + 'a>> {
+    database.push_to_history(&stmt.query);
+    let table = database.get_table(&stmt.table_name)?;
+    Ok(Box::new(Select { table, ast: stmt }))
+}
+
+/// Builds the result of a mutating statement from the records it affected.
+///
+/// With a `RETURNING` clause the records are returned, holding only the listed
+/// fields; otherwise only their count is.
+fn affected_records(returning: Option<&[String]>, records: &[&Record]) -> ExecutionResult {
+    match returning {
+        Some(fields) => ExecutionResult::Data(
+            records
+                .iter()
+                .map(|record| record.project(fields))
+                .collect(),
+        ),
+        None => ExecutionResult::RowsAffected(records.len()),
+    }
+}
+
+/// Checks that `record` satisfies every `CHECK` condition of `table`.
+///
+/// # Errors
+/// Returns `DatabaseError::ConstraintViolation` for the first condition the record
+/// does not satisfy, or the error raised while evaluating a condition.
+fn check_constraints<K: DatabaseKey>(table: &Table<K>, record: &Record) -> DatabaseResult<()> {
+    for check in &table.checks {
+        if !Select::<K>::evaluate_condition(record, check, &table.name)? {
+            return Err(DatabaseError::ConstraintViolation {
+                table: table.name.clone(),
+                check: check.to_string(),
+                record: record.clone(),
+            });
+        }
+    }
+    Ok(())
+}
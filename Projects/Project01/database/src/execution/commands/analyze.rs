@@ -0,0 +1,115 @@
+use crate::{
+    DatabaseResult,
+    core::{ColumnStatistics, DatabaseKey, Table},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `ANALYZE` command.
+///
+/// The parsed `AnalyzeStmt` only names the table, so this struct holds just
+/// a mutable reference to the `Table` whose statistics will be computed.
+pub struct Analyze<'a, K: DatabaseKey> {
+    /// A mutable reference to the table being analyzed.
+    pub table: &'a mut Table<K>,
+}
+
+impl<'a, K: DatabaseKey> Execute for Analyze<'a, K> {
+    /// Executes the `ANALYZE` command.
+    ///
+    /// The statistics are stored on the table, replacing those of a previous
+    /// `ANALYZE`, and returned as one message for the table and one per column.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let table_name = self.table.name.clone();
+        let statistics = self.table.analyze();
+
+        let mut messages = vec![format!(
+            "Table '{table_name}': {} record(s)",
+            statistics.row_count
+        )];
+        let mut columns = statistics.columns.iter().collect::<Vec<_>>();
+        columns.sort_by(|a, b| a.0.cmp(b.0));
+        for (
+            column,
+            ColumnStatistics {
+                distinct_count,
+                min,
+                max,
+            },
+        ) in columns
+        {
+            let mut message = format!("Column '{column}': {distinct_count} distinct value(s)");
+            if let (Some(min), Some(max)) = (min, max) {
+                message.push_str(&format!(", min {min}, max {max}"));
+            }
+            messages.push(message);
+        }
+
+        Ok(ExecutionResult::Messages(messages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DataValue, Database, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    #[test]
+    fn test_execute_analyze() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "INSERT id = 1, name = \"Bob\" INTO users;",
+            "INSERT id = 2, name = \"Alice\" INTO users;",
+            "INSERT id = 3, name = \"Bob\" INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        assert_eq!(db.statistics("users").unwrap(), None);
+
+        let ExecutionResult::Messages(messages) = execute(&mut db, "ANALYZE users;").unwrap()
+        else {
+            panic!("Expected ExecutionResult::Messages");
+        };
+        assert_eq!(
+            messages,
+            [
+                "Table 'users': 3 record(s)",
+                "Column 'id': 3 distinct value(s), min 1, max 3",
+                "Column 'name': 2 distinct value(s), min \"Alice\", max \"Bob\"",
+            ]
+        );
+
+        let statistics = db.statistics("users").unwrap().unwrap();
+        assert_eq!(statistics.row_count, 3);
+        assert_eq!(
+            statistics.columns["name"].max,
+            Some(DataValue::String("Bob".to_string()))
+        );
+
+        // the statistics describe the table at the time of the `ANALYZE`
+        execute(&mut db, "DELETE 1 FROM users;").unwrap();
+        assert_eq!(db.statistics("users").unwrap().unwrap().row_count, 3);
+    }
+
+    #[test]
+    fn test_execute_analyze_unknown_table() {
+        let mut db = Database::<i64>::default();
+        assert!(matches!(
+            execute(&mut db, "ANALYZE users;"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+        assert!(matches!(
+            db.statistics("users"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
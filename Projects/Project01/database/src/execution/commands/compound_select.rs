@@ -0,0 +1,210 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{
+    DatabaseResult,
+    ast::{CompoundSelectStmt, SelectStmt, SetOperator},
+    core::{DataValue, Database, DatabaseError, DatabaseKey, Record, SelectError},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::Select;
+
+/// A record with its fields in a fixed order, so that whole records can be
+/// compared and deduplicated using the `Ord` on `DataValue`.
+type OrderedRecord = BTreeMap<String, Arc<DataValue>>;
+
+/// Represents an executable `SELECT` combined with `UNION`, `INTERSECT` or `EXCEPT`.
+///
+/// The statements may query different tables, so this struct holds a read-only
+/// reference to the whole `Database`.
+pub struct CompoundSelect<'a, K: DatabaseKey> {
+    /// A read-only reference to the database holding the queried tables.
+    pub database: &'a Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the compound statement.
+    pub ast: CompoundSelectStmt,
+}
+
+impl<'a, K: DatabaseKey> CompoundSelect<'a, K> {
+    /// Runs a single `SELECT` statement and returns its distinct records.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::TableNotFound` if the queried table does not exist.
+    /// * Any error raised by the `SELECT` itself.
+    fn run(&self, stmt: &SelectStmt) -> DatabaseResult<BTreeSet<OrderedRecord>> {
+        let table = self.database.tables.get(&stmt.table_name).ok_or_else(|| {
+            DatabaseError::table_not_found(
+                &stmt.table_name,
+                self.database.tables.keys().map(String::as_str),
+            )
+        })?;
+
+        let mut select = Select {
+            table,
+            ast: stmt.clone(),
+        };
+        let ExecutionResult::Data(records) = select.execute()? else {
+            unreachable!("a SELECT always returns records");
+        };
+
+        Ok(records
+            .into_iter()
+            .map(|record| record.fields.into_iter().collect())
+            .collect())
+    }
+
+    /// Returns the sorted names of the columns returned by `stmt`.
+    fn column_names(stmt: &SelectStmt) -> Vec<String> {
+        let names = stmt
+            .fields
+            .iter()
+            .map(|item| item.output_name())
+            .collect::<BTreeSet<_>>();
+        names.into_iter().collect()
+    }
+}
+
+impl<'a, K: DatabaseKey> Execute for CompoundSelect<'a, K> {
+    /// Executes the compound statement.
+    ///
+    /// The statements are combined from left to right. As with sets, every
+    /// returned record is distinct; the records are ordered by their field
+    /// values, compared in the order of the field names.
+    ///
+    /// # Errors
+    ///
+    /// * `SelectError::IncompatibleProjections` - Two statements return differently named columns.
+    /// * `DatabaseError::TableNotFound` - A queried table does not exist.
+    /// * Any error raised by one of the `SELECT` statements.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let columns = Self::column_names(&self.ast.first);
+        for (operator, stmt) in &self.ast.rest {
+            let other_columns = Self::column_names(stmt);
+            if other_columns != columns {
+                return Err(SelectError::IncompatibleProjections {
+                    operator: *operator,
+                    left: columns,
+                    right: other_columns,
+                }
+                .into());
+            }
+        }
+
+        let mut records = self.run(&self.ast.first)?;
+        for (operator, stmt) in &self.ast.rest {
+            let other = self.run(stmt)?;
+            match operator {
+                SetOperator::Union => records.extend(other),
+                SetOperator::Intersect => records.retain(|record| other.contains(record)),
+                SetOperator::Except => records.retain(|record| !other.contains(record)),
+            }
+        }
+
+        let records = records
+            .into_iter()
+            .map(|fields| Record {
+                fields: fields.into_iter().collect(),
+            })
+            .collect();
+        Ok(ExecutionResult::Data(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QueryParser, execution::build_execute_command};
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_db() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT;",
+            "INSERT id = 1, name = \"Alice\", age = 30 INTO users;",
+            "INSERT id = 2, name = \"Bob\", age = 25 INTO users;",
+            "INSERT id = 3, name = \"Charlie\", age = 40 INTO users;",
+            "CREATE admins KEY id FIELDS id: INT, name: STRING;",
+            "INSERT id = 1, name = \"Alice\" INTO admins;",
+            "INSERT id = 7, name = \"Eve\" INTO admins;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    fn names(result: DatabaseResult<ExecutionResult>) -> Vec<String> {
+        let ExecutionResult::Data(records) = result.unwrap() else {
+            panic!("Expected ExecutionResult::Data");
+        };
+        records
+            .into_iter()
+            .map(|record| match record.fields["name"].as_ref() {
+                DataValue::String(name) => name.clone(),
+                value => panic!("Expected a string, got {value:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_execute_set_operators() {
+        let mut db = setup_db();
+
+        let union = execute(
+            &mut db,
+            "SELECT id, name FROM users UNION SELECT id, name FROM admins;",
+        );
+        assert_eq!(names(union), ["Alice", "Bob", "Charlie", "Eve"]);
+
+        let intersect = execute(
+            &mut db,
+            "SELECT name FROM users INTERSECT SELECT name FROM admins;",
+        );
+        assert_eq!(names(intersect), ["Alice"]);
+
+        let except = execute(
+            &mut db,
+            "SELECT name FROM users WHERE age < 40 EXCEPT SELECT name FROM admins;",
+        );
+        assert_eq!(names(except), ["Bob"]);
+
+        let chained = execute(
+            &mut db,
+            "SELECT name FROM admins UNION SELECT name FROM users EXCEPT SELECT name FROM users WHERE age > 28;",
+        );
+        assert_eq!(names(chained), ["Bob", "Eve"]);
+    }
+
+    #[test]
+    fn test_execute_fail_incompatible_projections() {
+        let mut db = setup_db();
+
+        match execute(
+            &mut db,
+            "SELECT id FROM users UNION SELECT name FROM admins;",
+        )
+        .err()
+        .unwrap()
+        {
+            DatabaseError::Select(SelectError::IncompatibleProjections {
+                operator,
+                left,
+                right,
+            }) => {
+                assert_eq!(operator, SetOperator::Union);
+                assert_eq!(left, ["id"]);
+                assert_eq!(right, ["name"]);
+            }
+            e => panic!("Expected IncompatibleProjections error, got {e:?}"),
+        }
+
+        let result = execute(&mut db, "SELECT id FROM users UNION SELECT id FROM guests;");
+        assert!(matches!(result, Err(DatabaseError::TableNotFound { .. })));
+    }
+}
@@ -27,8 +27,12 @@ impl<'a, K: DatabaseKey> Execute for Create<'a, K> {
     /// This function will return an error if:
     /// * `DatabaseError::DuplicateColumn` - A column name is repeated in the `FIELDS` list.
     /// * `DatabaseError::FieldNotFound` - The specified `key_field` does not exist.
-    /// * `DatabaseError::TypeMismatch` - The `key_field`'s type doesn't match `K`.
+    /// * `DatabaseError::TypeMismatch` - The `key_field`'s type doesn't match `K`,
+    ///   or a `DEFAULT` value doesn't match its column's type.
+    /// * `DatabaseError::InvalidEnumValue` - A `DEFAULT` value is not a variant of its `ENUM`.
+    /// * `DatabaseError::FieldNotFound` - A `CHECK` condition refers to an unknown column.
     /// * `CreateError::TableAlreadyExists` - A table with the same name already exists.
+    /// * `DatabaseError::Io` - The file of a `STORAGE DISK` table cannot be created.
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
         let mut schema: HashMap<String, DataType> = HashMap::with_capacity(self.ast.fields.len());
         for field_def in self.ast.fields.iter() {
@@ -43,7 +47,31 @@ impl<'a, K: DatabaseKey> Execute for Create<'a, K> {
             }
         }
 
-        let table = Table::new(&self.ast.table_name, &self.ast.key_field, schema)?;
+        let defaults = self
+            .ast
+            .fields
+            .iter()
+            .filter_map(|field_def| {
+                let default = field_def.default.clone()?;
+                Some((field_def.name.clone(), default))
+            })
+            .collect();
+
+        let enums = self
+            .ast
+            .fields
+            .iter()
+            .filter_map(|field_def| {
+                let variants = field_def.variants.clone()?;
+                Some((field_def.name.clone(), variants))
+            })
+            .collect();
+
+        let table = Table::new(&self.ast.table_name, &self.ast.key_field, schema)?
+            .with_enums(enums)?
+            .with_defaults(defaults)?
+            .with_checks(self.ast.checks.clone())?
+            .with_storage(self.ast.storage)?;
 
         self.database.add_table(table)?;
 
@@ -55,8 +83,8 @@ impl<'a, K: DatabaseKey> Execute for Create<'a, K> {
 mod tests {
     use super::*;
     use crate::{
-        ast::{CreateStmt, Field},
-        core::CreateError,
+        ast::{Comparison, Condition, CreateStmt, Field, Operator},
+        core::{CreateError, DataValue, TableStorage},
     };
 
     fn mock_users_ast() -> CreateStmt {
@@ -67,12 +95,18 @@ mod tests {
                 Field {
                     name: "id".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "name".to_string(),
                     data_type: DataType::String,
+                    variants: None,
+                    default: None,
                 },
             ],
+            checks: Vec::new(),
+            storage: TableStorage::default(),
             query: "CREATE...".to_string(), // unused
         }
     }
@@ -106,6 +140,8 @@ mod tests {
         ast.fields.push(Field {
             name: "id".to_string(),
             data_type: DataType::Int,
+            variants: None,
+            default: None,
         });
 
         let mut cmd = Create {
@@ -140,7 +176,7 @@ mod tests {
 
         assert!(result.is_err());
         match result.err().unwrap() {
-            DatabaseError::FieldNotFound { table, field } => {
+            DatabaseError::FieldNotFound { table, field, .. } => {
                 assert_eq!(table, "users");
                 assert_eq!(field, "uuid");
             }
@@ -149,6 +185,102 @@ mod tests {
         assert!(db.tables.is_empty());
     }
 
+    #[test]
+    fn test_execute_create_with_defaults() {
+        let mut db = Database::<i64>::default();
+        let mut ast = mock_users_ast();
+        ast.fields[1].default = Some(DataValue::String("anonymous".to_string()));
+
+        let mut cmd = Create {
+            database: &mut db,
+            ast,
+        };
+        cmd.execute().unwrap();
+
+        let defaults = &db.tables["users"].defaults;
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults["name"], DataValue::String("anonymous".to_string()));
+    }
+
+    #[test]
+    fn test_execute_create_with_enum() {
+        let mut db = Database::<i64>::default();
+        let mut ast = mock_users_ast();
+        ast.fields[1].variants = Some(vec!["Alice".to_string(), "Bob".to_string()]);
+        ast.fields[1].default = Some(DataValue::String("Bob".to_string()));
+
+        let mut cmd = Create {
+            database: &mut db,
+            ast,
+        };
+        cmd.execute().unwrap();
+        assert_eq!(db.tables["users"].enums["name"], ["Alice", "Bob"]);
+
+        let mut ast = mock_users_ast();
+        ast.table_name = "admins".to_string();
+        ast.fields[1].variants = Some(vec!["Alice".to_string()]);
+        ast.fields[1].default = Some(DataValue::String("Eve".to_string()));
+        let mut cmd = Create {
+            database: &mut db,
+            ast,
+        };
+        match cmd.execute().err().unwrap() {
+            DatabaseError::InvalidEnumValue { field, value, .. } => {
+                assert_eq!(field, "name");
+                assert_eq!(value, "\"Eve\"");
+            }
+            e => panic!("Expected InvalidEnumValue error, got {e:?}"),
+        }
+        assert!(!db.tables.contains_key("admins"));
+    }
+
+    #[test]
+    fn test_execute_fail_check_unknown_column() {
+        let mut db = Database::<i64>::default();
+        let mut ast = mock_users_ast();
+        ast.checks.push(Condition::Comparison(Comparison {
+            field: "age".to_string(),
+            op: Operator::GtEq,
+            value: DataValue::Int(0),
+        }));
+
+        let mut cmd = Create {
+            database: &mut db,
+            ast,
+        };
+        match cmd.execute().err().unwrap() {
+            DatabaseError::FieldNotFound { field, .. } => assert_eq!(field, "age"),
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+        assert!(db.tables.is_empty());
+    }
+
+    #[test]
+    fn test_execute_fail_default_type_mismatch() {
+        let mut db = Database::<i64>::default();
+        let mut ast = mock_users_ast();
+        ast.fields[1].default = Some(DataValue::Int(0));
+
+        let mut cmd = Create {
+            database: &mut db,
+            ast,
+        };
+        match cmd.execute().err().unwrap() {
+            DatabaseError::TypeMismatch {
+                field,
+                expected,
+                found,
+                ..
+            } => {
+                assert_eq!(field, "name");
+                assert_eq!(expected, DataType::String);
+                assert_eq!(found, DataType::Int);
+            }
+            e => panic!("Expected TypeMismatch error, got {e:?}"),
+        }
+        assert!(db.tables.is_empty());
+    }
+
     #[test]
     fn test_execute_fail_key_type_mismatch() {
         let mut db = Database::<String>::default();
@@ -0,0 +1,170 @@
+use crate::{
+    DatabaseResult,
+    ast::CreateIndexStmt,
+    core::{DatabaseKey, Table},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `CREATE_INDEX` command.
+///
+/// This struct couples the parsed `CreateIndexStmt` (the "what") with a
+/// mutable reference to the specific `Table` (the "where") that will hold the index.
+pub struct CreateIndex<'a, K: DatabaseKey> {
+    /// A mutable reference to the table being indexed.
+    pub table: &'a mut Table<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `CREATE_INDEX` statement.
+    pub ast: CreateIndexStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for CreateIndex<'a, K> {
+    /// Executes the `CREATE_INDEX` command.
+    ///
+    /// The index is built from the records already in the table.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::FieldNotFound` - The indexed column is not in the schema.
+    /// * `CreateError::IndexAlreadyExists` - The table already has an index with that name.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        self.table
+            .create_index(&self.ast.index_name, &self.ast.field, self.ast.kind)?;
+
+        Ok(ExecutionResult::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{CreateError, DataValue, Database, DatabaseError, IndexKind},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_db_i64_with_data() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT;",
+            "INSERT id = 1, name = \"Alice\", age = 30 INTO users;",
+            "INSERT id = 2, name = \"Bob\", age = 25 INTO users;",
+            "INSERT id = 3, name = \"Charlie\", age = 30 INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    fn keys_with_age(db: &Database<i64>, age: i64) -> Vec<i64> {
+        let mut keys = db.tables["users"].indexes["idx"]
+            .get(&DataValue::Int(age))
+            .to_vec();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_execute_create_index_on_existing_rows() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(&mut db, "CREATE_INDEX idx ON users(age);").unwrap();
+        assert!(matches!(result, ExecutionResult::Success));
+
+        let index = &db.tables["users"].indexes["idx"];
+        assert_eq!(index.field, "age");
+        assert_eq!(index.kind(), IndexKind::BTree);
+        assert_eq!(index.len(), 2);
+        assert_eq!(keys_with_age(&db, 30), [1, 3]);
+        assert_eq!(keys_with_age(&db, 25), [2]);
+    }
+
+    #[test]
+    fn test_index_follows_modifications() {
+        let mut db = setup_db_i64_with_data();
+        execute(&mut db, "CREATE_INDEX idx ON users(age);").unwrap();
+
+        for query in [
+            "INSERT id = 4, name = \"Diana\", age = 25 INTO users;",
+            "INSERT id = 2, age = 41 INTO users ON CONFLICT UPDATE;",
+            "DELETE 1 FROM users;",
+            "UPDATE users SET id = 5 WHERE id = 3;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        assert_eq!(keys_with_age(&db, 25), [4]);
+        assert_eq!(keys_with_age(&db, 30), [5]);
+        assert_eq!(keys_with_age(&db, 41), [2]);
+        assert_eq!(db.tables["users"].indexes["idx"].len(), 3);
+
+        execute(&mut db, "TRUNCATE users;").unwrap();
+        assert!(db.tables["users"].indexes["idx"].is_empty());
+        execute(&mut db, "GENERATE 10 INTO users SEED 1;").unwrap();
+        let table = &db.tables["users"];
+        assert!(table.rows.iter().all(|(key, record)| {
+            table.indexes["idx"]
+                .get(&record.fields["age"])
+                .contains(key)
+        }));
+    }
+
+    #[test]
+    fn test_execute_create_hash_index() {
+        let mut db = setup_db_i64_with_data();
+        execute(&mut db, "CREATE_INDEX idx ON users(age) USING HASH;").unwrap();
+        assert_eq!(db.tables["users"].indexes["idx"].kind(), IndexKind::Hash);
+        assert_eq!(keys_with_age(&db, 30), [1, 3]);
+
+        execute(&mut db, "UPDATE users SET age = 25 WHERE name = \"Alice\";").unwrap();
+        assert_eq!(keys_with_age(&db, 25), [1, 2]);
+        assert_eq!(keys_with_age(&db, 30), [3]);
+    }
+
+    #[test]
+    fn test_index_is_dumped() {
+        let mut db = setup_db_i64_with_data();
+        execute(&mut db, "CREATE_INDEX idx ON users(age);").unwrap();
+        execute(&mut db, "CREATE_INDEX by_name ON users(name) USING HASH;").unwrap();
+
+        let path = std::env::temp_dir().join("create_index_dump.bin");
+        let path = path.to_string_lossy().replace('\\', "/");
+        execute(&mut db, &format!("DUMP_TO \"{path}\";")).unwrap();
+
+        let mut loaded = Database::<i64>::default();
+        execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.tables["users"].indexes, db.tables["users"].indexes);
+        execute(&mut loaded, "DELETE 3 FROM users;").unwrap();
+        assert_eq!(keys_with_age(&loaded, 30), [1]);
+    }
+
+    #[test]
+    fn test_execute_create_index_fails() {
+        let mut db = setup_db_i64_with_data();
+        execute(&mut db, "CREATE_INDEX idx ON users(age);").unwrap();
+
+        match execute(&mut db, "CREATE_INDEX idx ON users(name);")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Create(CreateError::IndexAlreadyExists { table, name }) => {
+                assert_eq!(table, "users");
+                assert_eq!(name, "idx");
+            }
+            e => panic!("Expected IndexAlreadyExists error, got {e:?}"),
+        }
+        assert!(matches!(
+            execute(&mut db, "CREATE_INDEX by_email ON users(email);"),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+        assert!(matches!(
+            execute(&mut db, "CREATE_INDEX idx ON orders(age);"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
@@ -5,6 +5,8 @@ use crate::{
     execution::{Execute, ExecutionResult},
 };
 
+use super::{affected_records, triggered::RowTriggers};
+
 /// Represents an executable `DELETE FROM` command.
 ///
 /// This struct couples the parsed `DeleteStmt` (the "what") with a
@@ -15,6 +17,8 @@ pub struct Delete<'a, K: DatabaseKey> {
     pub table: &'a mut Table<K>,
     /// The parsed AST (Abstract Syntax Tree) for the `DELETE` statement.
     pub ast: DeleteStmt,
+    /// The triggers of the table, if it has any.
+    pub triggers: Option<&'a mut RowTriggers<K>>,
 }
 
 impl<'a, K: DatabaseKey> Execute for Delete<'a, K> {
@@ -24,7 +28,13 @@ impl<'a, K: DatabaseKey> Execute for Delete<'a, K> {
     ///
     /// Returns `DatabaseError::TypeMismatch` if the provided key value's type
     /// does not match the table's primary key type.
+    /// Returns `DatabaseError::FieldNotFound` if a `RETURNING` field is not in the schema.
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let returning = self.ast.returning.as_deref();
+        if let Some(fields) = returning {
+            self.table.check_fields(fields)?;
+        }
+
         let key_to_delete =
             K::from_datavalue(&self.ast.key_value).ok_or_else(|| DatabaseError::TypeMismatch {
                 table: self.table.name.clone(),
@@ -33,11 +43,22 @@ impl<'a, K: DatabaseKey> Execute for Delete<'a, K> {
                 found: self.ast.key_value.get_type(),
             })?;
 
-        let removed_record = self.table.rows.remove(&key_to_delete);
+        if let Some(triggers) = &self.triggers
+            && let Some(record) = self.table.rows.get(&key_to_delete)
+        {
+            triggers.before(&record)?;
+        }
+
+        let removed_record = self.table.remove_row(&key_to_delete);
+        if let Some(triggers) = &mut self.triggers
+            && let Some(record) = &removed_record
+        {
+            triggers.changed(record);
+        }
 
         match removed_record {
-            Some(_) => Ok(ExecutionResult::RowsAffected(1)),
-            None => Ok(ExecutionResult::RowsAffected(0)),
+            Some(record) => Ok(affected_records(returning, &[&record])),
+            None => Ok(affected_records(returning, &[])),
         }
     }
 }
@@ -47,7 +68,7 @@ mod tests {
     use super::*;
     use crate::{
         ast::{CreateStmt, DeleteStmt, Field},
-        core::{DataType, DataValue, Database, Record},
+        core::{DataType, DataValue, Database, Record, TableStorage},
         execution::{Execute, commands::create::Create},
     };
     use std::collections::HashMap;
@@ -62,16 +83,22 @@ mod tests {
                 Field {
                     name: "id".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "name".to_string(),
                     data_type: DataType::String,
+                    variants: None,
+                    default: None,
                 },
             ];
             let ast = CreateStmt {
                 table_name: "users".to_string(),
                 key_field: "id".to_string(),
                 fields,
+                checks: Vec::new(),
+                storage: TableStorage::default(),
                 query: "Create...".to_string(), // unused
             };
             let mut cmd = Create {
@@ -83,8 +110,13 @@ mod tests {
             let mut fields = HashMap::new();
             fields.insert("id".to_string(), DataValue::Int(10));
             fields.insert("name".to_string(), DataValue::String("Alice".to_string()));
-            let record = Record { fields };
-            db.tables.get_mut("users").unwrap().rows.insert(10, record);
+            let record = Record::new(fields);
+            db.tables
+                .get_mut("users")
+                .unwrap()
+                .rows
+                .insert(10, record)
+                .unwrap();
 
             db
         }
@@ -95,11 +127,15 @@ mod tests {
             let fields = vec![Field {
                 name: "sku".to_string(),
                 data_type: DataType::String,
+                variants: None,
+                default: None,
             }];
             let ast = CreateStmt {
                 table_name: "products".to_string(),
                 key_field: "sku".to_string(),
                 fields,
+                checks: Vec::new(),
+                storage: TableStorage::default(),
                 query: "Create...".to_string(),
             };
             let mut cmd = Create {
@@ -110,12 +146,13 @@ mod tests {
 
             let mut fields = HashMap::new();
             fields.insert("sku".to_string(), DataValue::String("A123".to_string()));
-            let record = Record { fields };
+            let record = Record::new(fields);
             db.tables
                 .get_mut("products")
                 .unwrap()
                 .rows
-                .insert("A123".to_string(), record);
+                .insert("A123".to_string(), record)
+                .unwrap();
 
             db
         }
@@ -129,12 +166,14 @@ mod tests {
         let ast = DeleteStmt {
             table_name: "users".to_string(),
             key_value: DataValue::Int(10),
+            returning: None,
             query: "Delete...".to_string(), // unused
         };
         let table_ref = db.tables.get_mut("users").unwrap();
         let mut cmd = Delete {
             table: table_ref,
             ast,
+            triggers: None,
         };
 
         let result = cmd.execute();
@@ -155,12 +194,14 @@ mod tests {
         let ast = DeleteStmt {
             table_name: "products".to_string(),
             key_value: DataValue::String("B456".to_string()),
+            returning: None,
             query: "Delete...".to_string(),
         };
         let table_ref = db.tables.get_mut("products").unwrap();
         let mut cmd = Delete {
             table: table_ref,
             ast,
+            triggers: None,
         };
 
         let result = cmd.execute();
@@ -181,12 +222,14 @@ mod tests {
         let ast = DeleteStmt {
             table_name: "users".to_string(),
             key_value: DataValue::String("10".to_string()),
+            returning: None,
             query: "Delete...".to_string(),
         };
         let table_ref = db.tables.get_mut("users").unwrap();
         let mut cmd = Delete {
             table: table_ref,
             ast,
+            triggers: None,
         };
 
         let result = cmd.execute();
@@ -208,4 +251,87 @@ mod tests {
         }
         assert_eq!(db.tables["users"].rows.len(), 1);
     }
+
+    #[test]
+    fn test_execute_delete_returning() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let ast = DeleteStmt {
+            table_name: "users".to_string(),
+            key_value: DataValue::Int(10),
+            returning: Some(vec!["name".to_string()]),
+            query: "Delete...".to_string(),
+        };
+        let table = db.tables.get_mut("users").unwrap();
+        let result = Delete {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+
+        match result {
+            ExecutionResult::Data(records) => {
+                assert_eq!(records.len(), 1);
+                assert_eq!(
+                    records[0],
+                    Record::new(HashMap::from([(
+                        "name".to_string(),
+                        DataValue::String("Alice".to_string())
+                    )]))
+                );
+            }
+            _ => panic!("Expected ExecutionResult::Data"),
+        }
+        assert!(db.tables["users"].rows.is_empty());
+    }
+
+    #[test]
+    fn test_execute_fail_delete_returning_unknown_field() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let ast = DeleteStmt {
+            table_name: "users".to_string(),
+            key_value: DataValue::Int(10),
+            returning: Some(vec!["email".to_string()]),
+            query: "Delete...".to_string(),
+        };
+        let table = db.tables.get_mut("users").unwrap();
+        let result = Delete {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute();
+
+        assert!(matches!(result, Err(DatabaseError::FieldNotFound { .. })));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_delete_uuid_key() {
+        use crate::{QueryParser, core::Uuid, execution::build_execute_command};
+
+        let mut db = Database::<Uuid>::default();
+        for query in [
+            "CREATE sessions KEY id FIELDS id: UUID, user: STRING;",
+            "INSERT id = u\"550e8400-e29b-41d4-a716-446655440000\", user = \"Alice\" INTO sessions;",
+            "GENERATE 3 INTO sessions SEED 5;",
+            "DELETE u\"550E8400-E29B-41D4-A716-446655440000\" FROM sessions;",
+        ] {
+            let ast = QueryParser.parse_query(query).unwrap();
+            build_execute_command(&mut db, ast)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let rows = &db.tables["sessions"].rows;
+        assert_eq!(rows.len(), 3);
+        assert!(
+            rows.iter()
+                .all(|(key, record)| *record.fields["id"] == DataValue::Uuid(*key))
+        );
+    }
 }
@@ -0,0 +1,374 @@
+//! The versioned layout of the snapshots written by `DUMP_TO` and `DUMP_TABLE`.
+//!
+//! A snapshot starts with [`MAGIC`] (or [`TABLE_MAGIC`] for a single table) and
+//! the little-endian `u32` version of its layout, followed by the `bincode`
+//! encoding of the `Database` (or `Table`) and the SHA-256 checksum of that
+//! encoding. Database snapshots written before the header was introduced hold
+//! only the encoding and are read as version 1; snapshots of version 2 have no
+//! checksum.
+//!
+//! Snapshots are encoded into and decoded from streams, table by table and record
+//! by record, so that dumping or loading a database does not need a second copy of
+//! it in memory. The checksum is computed while the encoding is written or read.
+//!
+//! When the layout of `Database`, `Table` or `Record` changes, [`CURRENT_VERSION`]
+//! is increased and [`decode_from`] keeps reading the previous layouts by decoding
+//! them into their own types and upgrading those to the current ones.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Read, Seek, Write},
+};
+
+use bincode::{Decode, Encode, config, decode_from_std_read, encode_into_std_write};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    DatabaseResult,
+    ast::Condition,
+    core::{
+        DataType, DataValue, Database, DatabaseError, DatabaseKey, Index, LoadTableError, Record,
+        Table, TableStatistics,
+    },
+};
+
+/// The bytes every versioned database snapshot starts with.
+const MAGIC: &[u8; 8] = b"MNRDBDMP";
+/// The bytes every table snapshot starts with.
+const TABLE_MAGIC: &[u8; 8] = b"MNRDBTBL";
+/// The layout version written by `DUMP_TO`.
+pub const CURRENT_VERSION: u32 = 4;
+/// The version of the snapshots without a header, whose layout is the same as
+/// that of version 2.
+const HEADERLESS_VERSION: u32 = 1;
+/// The first version with table snapshots.
+const FIRST_TABLE_VERSION: u32 = 2;
+/// The first version whose encoding is followed by its checksum.
+const FIRST_CHECKSUM_VERSION: u32 = 3;
+/// The first version recording where the rows of each table are stored.
+const FIRST_STORAGE_VERSION: u32 = 4;
+
+/// The content of a snapshot read by [`decode_any_from`].
+pub enum Snapshot<K: DatabaseKey> {
+    /// A snapshot written by `DUMP_TO`.
+    Database(Database<K>),
+    /// A snapshot written by `DUMP_TABLE`.
+    Table(Box<Table<K>>),
+}
+
+/// Writes `database` as a snapshot of the current version to `writer`.
+///
+/// # Errors
+/// Returns `DatabaseError::Io` if the header or the checksum cannot be written, or
+/// `DatabaseError::Encode` if `bincode` fails to serialize the database.
+pub fn encode_into<K: DatabaseKey>(
+    database: &Database<K>,
+    writer: &mut impl Write,
+) -> DatabaseResult<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&CURRENT_VERSION.to_le_bytes())?;
+    encode_checksummed(database, writer)
+}
+
+/// Writes `table` as a table snapshot of the current version to `writer`.
+///
+/// # Errors
+/// Returns `DatabaseError::Io` if the header or the checksum cannot be written, or
+/// `DatabaseError::Encode` if `bincode` fails to serialize the table.
+pub fn encode_table_into<K: DatabaseKey>(
+    table: &Table<K>,
+    writer: &mut impl Write,
+) -> DatabaseResult<()> {
+    writer.write_all(TABLE_MAGIC)?;
+    writer.write_all(&CURRENT_VERSION.to_le_bytes())?;
+    encode_checksummed(table, writer)
+}
+
+/// Reads a snapshot from `reader`, read from `path`, upgrading an older layout
+/// to the current one.
+///
+/// The records are decoded as they are read, so the snapshot is never held in
+/// memory next to the database built from it.
+///
+/// # Errors
+/// * `DatabaseError::Io` - The reader fails.
+/// * `DatabaseError::UnsupportedDumpVersion` - The snapshot was written by a newer
+///   (or unknown) version.
+/// * `DatabaseError::Decode` - `bincode` fails to deserialize the database.
+/// * `DatabaseError::ChecksumMismatch` - The checksum does not match the content.
+pub fn decode_from<K: DatabaseKey>(
+    mut reader: impl Read + Seek,
+    path: &str,
+) -> DatabaseResult<Database<K>> {
+    let version = match read_header(&mut reader, MAGIC)? {
+        Some(version) => version,
+        None => {
+            reader.rewind()?;
+            HEADERLESS_VERSION
+        }
+    };
+
+    match version {
+        HEADERLESS_VERSION..FIRST_STORAGE_VERSION => {
+            decode_payload::<LegacyDatabase<K>>(&mut reader, version, path).map(Database::from)
+        }
+        FIRST_STORAGE_VERSION..=CURRENT_VERSION => decode_payload(&mut reader, version, path),
+        version => Err(unsupported_version(path, version)),
+    }
+}
+
+/// Reads a table snapshot from `reader`, read from `path`.
+///
+/// # Errors
+/// * `DatabaseError::Io` - The reader fails.
+/// * `LoadTableError::NotATableDump` - The bytes are not a table snapshot.
+/// * `DatabaseError::UnsupportedDumpVersion` - The snapshot was written by a newer
+///   (or unknown) version.
+/// * `DatabaseError::Decode` - `bincode` fails to deserialize the table.
+/// * `DatabaseError::ChecksumMismatch` - The checksum does not match the content.
+pub fn decode_table_from<K: DatabaseKey>(
+    mut reader: impl Read,
+    path: &str,
+) -> DatabaseResult<Table<K>> {
+    let version =
+        read_header(&mut reader, TABLE_MAGIC)?.ok_or_else(|| LoadTableError::NotATableDump {
+            path: path.to_string(),
+        })?;
+
+    match version {
+        FIRST_TABLE_VERSION..FIRST_STORAGE_VERSION => {
+            decode_payload::<LegacyTable<K>>(&mut reader, version, path).map(Table::from)
+        }
+        FIRST_STORAGE_VERSION..=CURRENT_VERSION => decode_payload(&mut reader, version, path),
+        version => Err(unsupported_version(path, version)),
+    }
+}
+
+/// Reads a database or table snapshot from `reader`, read from `path`.
+///
+/// # Errors
+/// Returns the errors of [`decode_from`] and [`decode_table_from`].
+///
+/// # Returns
+/// The snapshot and the version of its layout.
+pub fn decode_any_from<K: DatabaseKey>(
+    mut reader: impl Read + Seek,
+    path: &str,
+) -> DatabaseResult<(Snapshot<K>, u32)> {
+    if let Some(version) = read_header(&mut reader, TABLE_MAGIC)? {
+        reader.rewind()?;
+        let table = decode_table_from(reader, path)?;
+        return Ok((Snapshot::Table(Box::new(table)), version));
+    }
+
+    reader.rewind()?;
+    let version = read_header(&mut reader, MAGIC)?.unwrap_or(HEADERLESS_VERSION);
+    reader.rewind()?;
+    let database = decode_from(reader, path)?;
+    Ok((Snapshot::Database(database), version))
+}
+
+/// Returns `true` if snapshots of `version` hold a checksum of their content.
+pub fn has_checksum(version: u32) -> bool {
+    version >= FIRST_CHECKSUM_VERSION
+}
+
+/// Reads the first `len` bytes of `reader` (fewer if it ends earlier) and moves
+/// back to its start.
+///
+/// # Errors
+/// Returns the error of the reader.
+pub fn peek(reader: &mut (impl Read + Seek), len: usize) -> io::Result<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(len);
+    reader.by_ref().take(len as u64).read_to_end(&mut prefix)?;
+    reader.rewind()?;
+    Ok(prefix)
+}
+
+/// Reads the header starting with `magic` from `reader`.
+///
+/// # Returns
+/// The version of the snapshot, or `None` if `reader` does not start with `magic`.
+fn read_header(reader: &mut impl Read, magic: &[u8]) -> io::Result<Option<u32>> {
+    let len = magic.len() + size_of::<u32>();
+    let mut header = Vec::with_capacity(len);
+    reader.by_ref().take(len as u64).read_to_end(&mut header)?;
+
+    Ok(header
+        .strip_prefix(magic)
+        .and_then(|version| version.try_into().ok())
+        .map(u32::from_le_bytes))
+}
+
+/// Returns the encoding of `database` in the layout of versions 1 to 3, without
+/// a header or a checksum.
+#[cfg(test)]
+pub fn encode_legacy<K: DatabaseKey>(database: &Database<K>) -> Vec<u8> {
+    let legacy = LegacyDatabase {
+        tables: database
+            .tables
+            .iter()
+            .map(|(name, table)| {
+                let table = LegacyTable {
+                    name: table.name.clone(),
+                    key_field: table.key_field.clone(),
+                    schema: table.schema.clone(),
+                    defaults: table.defaults.clone(),
+                    enums: table.enums.clone(),
+                    checks: table.checks.clone(),
+                    indexes: table.indexes.clone(),
+                    statistics: table.statistics.clone(),
+                    rows: table
+                        .rows
+                        .iter()
+                        .map(|(key, record)| (key.clone(), record.into_owned()))
+                        .collect(),
+                };
+                (name.clone(), table)
+            })
+            .collect(),
+        history: database.iter_history().cloned().collect(),
+    };
+    bincode::encode_to_vec(&legacy, config::standard()).unwrap()
+}
+
+/// Writes the encoding of `value` followed by its checksum.
+fn encode_checksummed(value: &impl Encode, writer: &mut impl Write) -> DatabaseResult<()> {
+    let mut hashing = HashingWriter {
+        inner: &mut *writer,
+        hasher: Sha256::new(),
+    };
+    encode_into_std_write(value, &mut hashing, config::standard())?;
+    let checksum = hashing.hasher.finalize();
+    writer.write_all(&checksum)?;
+    Ok(())
+}
+
+/// Reads the encoding of a snapshot of `version` and, if the version has one,
+/// verifies its checksum.
+fn decode_payload<T: Decode<()>>(
+    reader: &mut impl Read,
+    version: u32,
+    path: &str,
+) -> DatabaseResult<T> {
+    if !has_checksum(version) {
+        return Ok(decode_from_std_read(reader, config::standard())?);
+    }
+
+    let mut hashing = HashingReader {
+        inner: &mut *reader,
+        hasher: Sha256::new(),
+    };
+    let value = decode_from_std_read(&mut hashing, config::standard())?;
+    let computed = hashing.hasher.finalize();
+
+    let mut stored = [0; 32];
+    match reader.read_exact(&mut stored) {
+        Ok(()) if stored[..] == computed[..] => Ok(value),
+        Ok(()) => Err(checksum_mismatch(path)),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Err(checksum_mismatch(path)),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Builds the error reported for a snapshot of an unsupported `version`.
+fn unsupported_version(path: &str, version: u32) -> DatabaseError {
+    DatabaseError::UnsupportedDumpVersion {
+        path: path.to_string(),
+        version,
+        supported: CURRENT_VERSION,
+    }
+}
+
+/// Builds the error reported for a snapshot whose checksum is wrong or missing.
+fn checksum_mismatch(path: &str) -> DatabaseError {
+    DatabaseError::ChecksumMismatch {
+        path: path.to_string(),
+    }
+}
+
+/// A writer hashing the bytes written through it.
+struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader hashing the bytes read through it.
+struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// The layout of a `Database` before version 4.
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+struct LegacyDatabase<K: DatabaseKey> {
+    tables: HashMap<String, LegacyTable<K>>,
+    history: Vec<String>,
+}
+
+impl<K: DatabaseKey> From<LegacyDatabase<K>> for Database<K> {
+    fn from(legacy: LegacyDatabase<K>) -> Self {
+        let mut database = Database::default();
+        database.tables = legacy
+            .tables
+            .into_iter()
+            .map(|(name, table)| (name, table.into()))
+            .collect();
+        for command in &legacy.history {
+            database.push_to_history(command);
+        }
+        database
+    }
+}
+
+/// The layout of a `Table` before version 4, whose rows were always held in memory.
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+struct LegacyTable<K: DatabaseKey> {
+    name: String,
+    key_field: String,
+    schema: HashMap<String, DataType>,
+    defaults: HashMap<String, DataValue>,
+    enums: HashMap<String, Vec<String>>,
+    checks: Vec<Condition>,
+    indexes: HashMap<String, Index<K>>,
+    statistics: Option<TableStatistics>,
+    rows: BTreeMap<K, Record>,
+}
+
+impl<K: DatabaseKey> From<LegacyTable<K>> for Table<K> {
+    fn from(legacy: LegacyTable<K>) -> Self {
+        Self {
+            name: legacy.name,
+            key_field: legacy.key_field,
+            schema: legacy.schema,
+            defaults: legacy.defaults,
+            enums: legacy.enums,
+            checks: legacy.checks,
+            indexes: legacy.indexes,
+            statistics: legacy.statistics,
+            rows: legacy.rows.into(),
+        }
+    }
+}
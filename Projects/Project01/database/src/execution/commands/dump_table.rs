@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use crate::{
+    DatabaseResult,
+    ast::DumpTableStmt,
+    core::{Database, DatabaseKey},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::dump_format;
+
+/// Represents an executable `DUMP_TABLE` command.
+///
+/// This struct couples the parsed `DumpTableStmt` (the "what") with a
+/// read-only reference to the `Database` (the "where") holding the table
+/// and the storage it is written to.
+pub struct DumpTable<'a, K: DatabaseKey> {
+    /// A read-only reference to the database holding the table that will be
+    /// serialized, which is known to exist.
+    pub database: &'a Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `DUMP_TABLE` statement.
+    pub ast: DumpTableStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for DumpTable<'a, K> {
+    /// Executes the `DUMP_TABLE` command.
+    ///
+    /// The dump holds the whole table (its schema, `DEFAULT` values, `ENUM`
+    /// variants, `CHECK` conditions, indexes and rows) and starts with the version
+    /// of its layout (see the `dump_format` module).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * `DatabaseError::Io` - The file cannot be created or written to.
+    /// * `DatabaseError::Encode` - `bincode` fails to serialize the table.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let table = &self.database.tables[&self.ast.table_name];
+        let mut writer = self.database.storage().create(Path::new(&self.ast.path))?;
+        dump_format::encode_table_into(table, &mut writer)?;
+        writer.commit()?;
+
+        Ok(ExecutionResult::Success)
+    }
+}
@@ -1,5 +1,4 @@
-use bincode::{config, encode_into_std_write};
-use std::{fs::File, io::BufWriter};
+use std::{io::Write, path::Path};
 
 use crate::{
     DatabaseResult,
@@ -8,6 +7,8 @@ use crate::{
     execution::{Execute, ExecutionResult},
 };
 
+use super::{dump_format, encryption};
+
 /// Represents an executable `DUMP_TO` command.
 ///
 /// This struct couples the parsed `DumpToStmt` (the "what") with a
@@ -23,20 +24,38 @@ pub struct DumpTo<'a, K: DatabaseKey> {
 impl<'a, K: DatabaseKey> Execute for DumpTo<'a, K> {
     /// Executes the `DUMP_TO` command.
     ///
+    /// The dump starts with the version of its layout (see the `dump_format`
+    /// module) and is encoded straight into the file, table by table and record
+    /// by record. With `ENCRYPTED WITH`, it is encoded in memory and encrypted as a
+    /// whole with a key derived from the passphrase (see the `encryption` module
+    /// for the file format). The dump is written to the storage of the database,
+    /// and only replaces an existing snapshot at its path once it is complete and
+    /// stored durably, so that a crash while dumping leaves the previous one, which
+    /// the write-ahead log may load.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// * `DatabaseError::Io` - The file cannot be created or written to.
     /// * `DatabaseError::Encode` - `bincode` fails to serialize the database.
+    /// * `DatabaseError::Encryption` - The dump cannot be encrypted.
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
-        let file = File::create(&self.ast.path)?;
-        let mut writer = BufWriter::new(file);
-
-        let config = config::standard();
+        let path = Path::new(&self.ast.path);
+        if let Some(passphrase) = &self.ast.passphrase {
+            let mut bytes = Vec::new();
+            dump_format::encode_into(self.database, &mut bytes)?;
+            let encrypted = encryption::encrypt(&bytes, passphrase)?;
 
-        match encode_into_std_write(self.database, &mut writer, config) {
-            Ok(_) => Ok(ExecutionResult::Success),
-            Err(e) => Err(e.into()),
+            let mut writer = self.database.storage().create(path)?;
+            writer.write_all(&encrypted)?;
+            writer.commit()?;
+            return Ok(ExecutionResult::Success);
         }
+
+        let mut writer = self.database.storage().create(path)?;
+        dump_format::encode_into(self.database, &mut writer)?;
+        writer.commit()?;
+
+        Ok(ExecutionResult::Success)
     }
 }
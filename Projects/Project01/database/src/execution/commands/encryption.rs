@@ -0,0 +1,163 @@
+//! The format of the snapshots written by `DUMP_TO ... ENCRYPTED WITH`.
+//!
+//! An encrypted snapshot starts with [`MAGIC`], followed by the random salt the
+//! key is derived from (with Argon2id), the random AES-256-GCM nonce, and the
+//! encrypted `bincode` dump ending with its authentication tag. A wrong passphrase
+//! and a modified file are therefore both detected when decrypting.
+//!
+//! [`open_snapshot`] gives the commands reading snapshots their unencrypted content,
+//! whether the file is encrypted or not.
+
+use std::{
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use argon2::Argon2;
+
+use crate::{
+    DatabaseResult,
+    core::{EncryptionError, StorageBackend, StorageReader},
+};
+
+use super::dump_format;
+
+/// The bytes every encrypted snapshot starts with.
+const MAGIC: &[u8; 8] = b"MNRDBENC";
+/// The length of the salt of the key derivation.
+const SALT_LEN: usize = 16;
+/// The length of an AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+/// The unencrypted content of a snapshot file opened by [`open_snapshot`].
+pub enum SnapshotReader {
+    /// A plain snapshot, read from the storage as it is decoded.
+    Plain(Box<dyn StorageReader>),
+    /// An encrypted snapshot, read and decrypted as a whole, as its
+    /// authentication tag covers all of it.
+    Decrypted(Cursor<Vec<u8>>),
+}
+
+impl Read for SnapshotReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(reader) => reader.read(buf),
+            Self::Decrypted(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl Seek for SnapshotReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Self::Plain(reader) => reader.seek(pos),
+            Self::Decrypted(reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Returns `true` if `bytes` are an encrypted snapshot.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Opens the snapshot at `path` of `storage`, decrypting it with `passphrase` if
+/// it is encrypted.
+///
+/// # Errors
+/// * `DatabaseError::Io` - The file cannot be opened or read.
+/// * `EncryptionError::PassphraseRequired` - The snapshot is encrypted, but no
+///   passphrase was given.
+/// * `EncryptionError::NotEncrypted` - A passphrase was given for a plain snapshot.
+/// * The errors of [`decrypt`].
+pub fn open_snapshot(
+    storage: &dyn StorageBackend,
+    path: &str,
+    passphrase: Option<&str>,
+) -> DatabaseResult<SnapshotReader> {
+    let mut reader = storage.open(Path::new(path))?;
+    let prefix = dump_format::peek(&mut reader, MAGIC.len())?;
+
+    match (passphrase, is_encrypted(&prefix)) {
+        (Some(passphrase), true) => {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let plaintext = decrypt(&bytes, passphrase, path)?;
+            Ok(SnapshotReader::Decrypted(Cursor::new(plaintext)))
+        }
+        (Some(_), false) => Err(EncryptionError::NotEncrypted {
+            path: path.to_string(),
+        }
+        .into()),
+        (None, true) => Err(EncryptionError::PassphraseRequired {
+            path: path.to_string(),
+        }
+        .into()),
+        (None, false) => Ok(SnapshotReader::Plain(reader)),
+    }
+}
+
+/// Encrypts the serialized database `plaintext` with a key derived from `passphrase`.
+///
+/// # Errors
+/// Returns `EncryptionError::KeyDerivation` or `EncryptionError::Encryption` if
+/// the encryption fails.
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> DatabaseResult<Vec<u8>> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&Nonce::from(nonce), plaintext)
+        .map_err(|_| EncryptionError::Encryption)?;
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&salt);
+    bytes.extend_from_slice(&nonce);
+    bytes.extend_from_slice(&ciphertext);
+    Ok(bytes)
+}
+
+/// Decrypts the encrypted snapshot `bytes`, read from `path`, with a key derived
+/// from `passphrase`.
+///
+/// # Errors
+/// * `EncryptionError::Truncated` - The snapshot is too short to hold its header.
+/// * `EncryptionError::Decryption` - The passphrase is wrong or the file was modified.
+/// * `EncryptionError::KeyDerivation` - The key cannot be derived.
+pub fn decrypt(bytes: &[u8], passphrase: &str, path: &str) -> DatabaseResult<Vec<u8>> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if bytes.len() < header_len {
+        return Err(EncryptionError::Truncated {
+            path: path.to_string(),
+        }
+        .into());
+    }
+    let (salt, rest) = bytes[MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::from(<[u8; NONCE_LEN]>::try_from(nonce).unwrap());
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt)?);
+    cipher.decrypt(&nonce, ciphertext).map_err(|_| {
+        EncryptionError::Decryption {
+            path: path.to_string(),
+        }
+        .into()
+    })
+}
+
+/// Derives the AES-256 key from `passphrase` and `salt` with Argon2id.
+fn derive_key(passphrase: &str, salt: &[u8]) -> DatabaseResult<Key<Aes256Gcm>> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| EncryptionError::KeyDerivation {
+            reason: e.to_string(),
+        })?;
+    Ok(key)
+}
@@ -0,0 +1,195 @@
+use crate::{
+    DatabaseResult,
+    ast::{ExplainStmt, Projection},
+    core::{DatabaseKey, Table},
+    execution::{Execute, ExecutionResult, planner},
+};
+
+/// Represents an executable `EXPLAIN` command.
+///
+/// This struct couples the parsed `ExplainStmt` (the "what") with a
+/// read-only reference to the `Table` (the "where") the explained `SELECT` queries.
+pub struct Explain<'a, K: DatabaseKey> {
+    /// A read-only reference to the table queried by the `SELECT`.
+    pub table: &'a Table<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `EXPLAIN` statement.
+    pub ast: ExplainStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for Explain<'a, K> {
+    /// Executes the `EXPLAIN` command.
+    ///
+    /// The `SELECT` is planned but not run. The returned messages describe, in
+    /// execution order, the access path, the `WHERE` filter, the aggregation or
+    /// projection, the sort and the pagination.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::FieldNotFound` if the `SELECT` refers to a column that
+    /// is not in the schema.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let select = &self.ast.select;
+        let clauses = &select.optional_clauses;
+
+        let mut columns = Vec::new();
+        for item in &select.fields {
+            match &item.projection {
+                Projection::Field(field) => columns.push(field),
+                Projection::Aggregate(aggregate) => columns.extend(&aggregate.field),
+                Projection::Expression(expression) => columns.extend(expression.columns()),
+            }
+        }
+        if let Some(condition) = &clauses.where_clause {
+            columns.extend(condition.columns());
+        }
+        if let Some(order_by) = &clauses.order_by {
+            columns.extend(order_by.expression.columns());
+        }
+        self.table.check_fields(columns)?;
+
+        let access_path = planner::plan(self.table, clauses.where_clause.as_ref());
+        let mut messages = vec![format!(
+            "Access path: {access_path} of table '{}' ({} record(s))",
+            self.table.name,
+            self.table.rows.len()
+        )];
+
+        if let Some(estimate) = planner::estimate_rows(self.table, &access_path) {
+            messages.push(format!("Estimated records read: {}", estimate.round()));
+        }
+
+        if let Some(condition) = &clauses.where_clause {
+            messages.push(format!("Filter: {condition}"));
+        }
+
+        let outputs = select
+            .fields
+            .iter()
+            .map(|item| match &item.alias {
+                Some(alias) => format!("{} AS {alias}", item.projection),
+                None => item.projection.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let is_aggregate = select
+            .fields
+            .iter()
+            .any(|item| matches!(item.projection, Projection::Aggregate(_)));
+        if is_aggregate {
+            messages.push(format!("Aggregate: {outputs}"));
+        } else {
+            if let Some(order_by) = &clauses.order_by {
+                messages.push(format!(
+                    "Sort: {} {}",
+                    order_by.expression, order_by.direction
+                ));
+            }
+            messages.push(format!("Projection: {outputs}"));
+        }
+
+        if let Some(offset) = clauses.offset {
+            messages.push(format!("Offset: {offset}"));
+        }
+        if let Some(limit) = clauses.limit {
+            messages.push(format!("Limit: {limit}"));
+        }
+
+        Ok(ExecutionResult::Messages(messages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Database, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn explain(db: &mut Database<i64>, query: &str) -> Vec<String> {
+        match execute(db, query).unwrap() {
+            ExecutionResult::Messages(messages) => messages,
+            result => panic!("Expected ExecutionResult::Messages, got {result:?}"),
+        }
+    }
+
+    fn setup_db_i64_with_data() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT;",
+            "GENERATE 5 INTO users SEED 2;",
+            "CREATE_INDEX by_age ON users(age);",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_explain_select() {
+        let mut db = setup_db_i64_with_data();
+
+        let messages = explain(
+            &mut db,
+            "EXPLAIN SELECT id, name AS n FROM users WHERE age >= 18 AND name LIKE \"A%\" ORDER_BY age DESC LIMIT 2 OFFSET 1;",
+        );
+        assert_eq!(
+            messages,
+            [
+                "Access path: index range scan on 'by_age' (age >= 18) of table 'users' (5 record(s))",
+                "Filter: (age >= 18 AND name LIKE \"A%\")",
+                "Sort: age DESC",
+                "Projection: id, name AS n",
+                "Offset: 1",
+                "Limit: 2",
+            ]
+        );
+
+        let messages = explain(
+            &mut db,
+            "EXPLAIN SELECT COUNT(*) FROM users WHERE id IN (1, 2);",
+        );
+        assert_eq!(
+            messages,
+            [
+                "Access path: primary key lookup (id IN (1, 2)) of table 'users' (5 record(s))",
+                "Filter: id IN (1, 2)",
+                "Aggregate: COUNT(*)",
+            ]
+        );
+
+        let messages = explain(&mut db, "EXPLAIN SELECT name FROM users;");
+        assert_eq!(
+            messages,
+            [
+                "Access path: full scan of table 'users' (5 record(s))",
+                "Projection: name",
+            ]
+        );
+
+        execute(&mut db, "ANALYZE users;").unwrap();
+        let messages = explain(&mut db, "EXPLAIN SELECT name FROM users WHERE id = 3;");
+        assert_eq!(messages[1], "Estimated records read: 1");
+    }
+
+    #[test]
+    fn test_explain_does_not_execute() {
+        let mut db = setup_db_i64_with_data();
+        execute(&mut db, "EXPLAIN SELECT id FROM users LIMIT -1;").unwrap();
+
+        assert!(matches!(
+            execute(&mut db, "EXPLAIN SELECT id FROM users WHERE email = \"a\";"),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+        assert!(matches!(
+            execute(&mut db, "EXPLAIN SELECT id FROM orders;"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use crate::{
+    DatabaseResult,
+    ast::ExportJsonStmt,
+    core::{Database, DatabaseKey},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::json_document::DatabaseDocument;
+
+/// Represents an executable `EXPORT_JSON` command.
+///
+/// This struct couples the parsed `ExportJsonStmt` (the "what") with a
+/// read-only reference to the `Database` (the "where") that is written
+/// as a human-readable JSON document.
+pub struct ExportJson<'a, K: DatabaseKey> {
+    /// A read-only reference to the database instance that will be exported.
+    pub database: &'a Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `EXPORT_JSON` statement.
+    pub ast: ExportJsonStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for ExportJson<'a, K> {
+    /// Executes the `EXPORT_JSON` command.
+    ///
+    /// The document is written to the storage of the database.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * `DatabaseError::JsonDocument` - A `FLOAT` value is `NaN` or infinite.
+    /// * `DatabaseError::Io` - The file cannot be created or written to.
+    /// * `DatabaseError::Json` - `serde_json` fails to write the document.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let document = DatabaseDocument::from_database(self.database)?;
+
+        let mut writer = self.database.storage().create(Path::new(&self.ast.path))?;
+        serde_json::to_writer_pretty(&mut writer, &document)?;
+        writer.commit()?;
+
+        Ok(ExecutionResult::Success)
+    }
+}
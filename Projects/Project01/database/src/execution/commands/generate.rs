@@ -0,0 +1,61 @@
+use crate::{
+    DatabaseResult,
+    ast::GenerateStmt,
+    core::{DatabaseKey, Table},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `GENERATE` command.
+///
+/// This struct couples the parsed `GenerateStmt` (the "what") with a
+/// mutable reference to the specific `Table` (the "where") that will be
+/// filled with random records.
+pub struct Generate<'a, K: DatabaseKey> {
+    /// A mutable reference to the table receiving the generated records.
+    pub table: &'a mut Table<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `GENERATE` statement.
+    pub ast: GenerateStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for Generate<'a, K> {
+    /// Executes the `GENERATE` command.
+    ///
+    /// Without a `SEED` clause a random seed is used, so every execution
+    /// produces different records.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let seed = self.ast.seed.unwrap_or_else(rand::random);
+        let inserted = self.table.generate_rows(self.ast.count, seed)?;
+
+        Ok(ExecutionResult::RowsAffected(inserted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{QueryParser, core::Database, execution::build_execute_command};
+
+    #[test]
+    fn test_execute_generate() {
+        let mut db = Database::<i64>::default();
+        let parser = QueryParser;
+
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT;",
+            "GENERATE 250 INTO users SEED 1;",
+        ] {
+            let ast = parser.parse_query(query).unwrap();
+            build_execute_command(&mut db, ast)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        assert_eq!(db.tables["users"].rows.len(), 250);
+    }
+
+    #[test]
+    fn test_generate_rows_unknown_table() {
+        let mut db = Database::<i64>::default();
+        assert!(db.generate_rows("missing", 10, 0).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use std::path::Path;
+
+use crate::{
+    DatabaseResult,
+    ast::ImportJsonStmt,
+    core::{Database, DatabaseKey},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::json_document::DatabaseDocument;
+
+/// Represents an executable `IMPORT_JSON` command.
+///
+/// This struct couples the parsed `ImportJsonStmt` (the "what") with a
+/// mutable reference to the `Database` (the "where") that is replaced
+/// by the database read from a JSON document.
+pub struct ImportJson<'a, K: DatabaseKey> {
+    /// A mutable reference to the database instance that will be
+    /// replaced by the imported data.
+    pub database: &'a mut Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `IMPORT_JSON` statement.
+    pub ast: ImportJsonStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for ImportJson<'a, K> {
+    /// Executes the `IMPORT_JSON` command.
+    ///
+    /// The document is read from the storage of the database and validated as a
+    /// whole before the database is replaced, so a failed import leaves the current
+    /// database untouched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * `DatabaseError::Io` - The file cannot be opened or read.
+    /// * `DatabaseError::Json` - The file is not a valid JSON document.
+    /// * Any error raised while rebuilding the tables, see
+    ///   [`DatabaseDocument::into_database`].
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let reader = self.database.storage().open(Path::new(&self.ast.path))?;
+
+        let document: DatabaseDocument = serde_json::from_reader(reader)?;
+
+        let imported = document.into_database()?;
+        self.database.replace(imported);
+        Ok(ExecutionResult::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DataValue, DatabaseError, JsonError, Table},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{name}_{}.json", std::process::id()));
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    fn setup_db() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE items KEY id FIELDS id: INT, name: STRING, price: FLOAT DEFAULT 1.0, \
+             status: ENUM(\"new\", \"sold\") DEFAULT \"new\", data: BYTES, tag: UUID, \
+             visible: BOOLEAN CHECK (price >= 0.0 AND name != \"\");",
+            "CREATE empty KEY id FIELDS id: INT;",
+            "CREATE_INDEX by_status ON items(status) USING HASH;",
+            "CREATE_INDEX by_price ON items(price);",
+            "INSERT id = 1, name = \"Lamp\", price = 20.0, data = 0xdeadbeef, \
+             tag = u\"67e55044-10b1-426f-9247-bb680e5fe0c8\", visible = true INTO items;",
+            "INSERT id = 2, name = \"Chair\", status = \"sold\", data = 0x, \
+             tag = u\"00000000-0000-0000-0000-000000000000\", visible = false INTO items;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    fn import(path: &str) -> DatabaseResult<Database<i64>> {
+        let mut db = Database::<i64>::default();
+        execute(&mut db, &format!("IMPORT_JSON \"{path}\";"))?;
+        Ok(db)
+    }
+
+    fn import_document(name: &str, document: &str) -> DatabaseResult<Database<i64>> {
+        let path = temp_path(name);
+        std::fs::write(&path, document).unwrap();
+        let result = import(&path);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut db = setup_db();
+        let path = temp_path("json_round_trip");
+        let result = execute(&mut db, &format!("EXPORT_JSON \"{path}\";")).unwrap();
+        assert!(matches!(result, ExecutionResult::Success));
+
+        let mut loaded = import(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.tables.len(), 2);
+        for (name, table) in &db.tables {
+            let imported = &loaded.tables[name];
+            assert_eq!(imported.key_field, table.key_field);
+            assert_eq!(imported.schema, table.schema);
+            assert_eq!(imported.defaults, table.defaults);
+            assert_eq!(imported.enums, table.enums);
+            assert_eq!(imported.indexes, table.indexes);
+            assert_eq!(imported.rows, table.rows);
+            let checks =
+                |t: &Table<i64>| t.checks.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(checks(imported), checks(table));
+        }
+        assert_eq!(
+            loaded.iter_history().collect::<Vec<_>>(),
+            db.iter_history().collect::<Vec<_>>()
+        );
+
+        let insert = |name: &str| {
+            format!(
+                "INSERT id = 3, name = \"{name}\", data = 0x, \
+                 tag = u\"00000000-0000-0000-0000-000000000000\", visible = true INTO items;"
+            )
+        };
+        assert!(matches!(
+            execute(&mut loaded, &insert("")),
+            Err(DatabaseError::ConstraintViolation { .. })
+        ));
+        execute(&mut loaded, &insert("Desk")).unwrap();
+        let desk = &loaded.tables["items"].rows.get(&3).unwrap();
+        assert_eq!(*desk.fields["price"], DataValue::Float(1.0));
+        assert_eq!(
+            loaded.tables["items"].indexes["by_status"].get(&DataValue::String("new".into())),
+            [1, 3]
+        );
+    }
+
+    #[test]
+    fn test_export_is_readable() {
+        let mut db = setup_db();
+        let path = temp_path("json_readable");
+        execute(&mut db, &format!("EXPORT_JSON \"{path}\";")).unwrap();
+        let document: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let items = &document["tables"][1];
+        assert_eq!(items["name"], "items");
+        assert_eq!(items["columns"][0]["name"], "data");
+        assert_eq!(items["columns"][0]["type"], "BYTES");
+        assert_eq!(items["indexes"][0]["kind"], "BTREE");
+        assert_eq!(items["rows"][0]["price"], 20.0);
+        assert_eq!(items["rows"][0]["data"], "0xdeadbeef");
+        assert_eq!(
+            items["rows"][0]["tag"],
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        );
+        assert_eq!(items["rows"][0]["name"], "Lamp");
+    }
+
+    #[test]
+    fn test_import_accepts_integer_floats() {
+        let db = import_document(
+            "json_integer_float",
+            r#"{"tables": [{"name": "t", "key_field": "id",
+                "columns": [{"name": "id", "type": "INT"}, {"name": "x", "type": "FLOAT"}],
+                "rows": [{"id": 1, "x": 2}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            *db.tables["t"].rows.get(&1).unwrap().fields["x"],
+            DataValue::Float(2.0)
+        );
+    }
+
+    #[test]
+    fn test_import_fails() {
+        let document = |rows: &str| {
+            format!(
+                r#"{{"tables": [{{"name": "t", "key_field": "id",
+                "columns": [{{"name": "id", "type": "INT"}}, {{"name": "b", "type": "BYTES"}}],
+                "rows": [{rows}]}}]}}"#
+            )
+        };
+
+        match import_document("json_invalid", &document(r#"{"id": 1, "b": "0xabc"}"#)) {
+            Err(DatabaseError::JsonDocument(JsonError::InvalidValue { field, value, .. })) => {
+                assert_eq!(field, "b");
+                assert_eq!(value, "\"0xabc\"");
+            }
+            other => panic!("Expected InvalidValue error, got {other:?}"),
+        }
+        match import_document(
+            "json_missing",
+            &document(r#"{"id": 1, "b": "0x"}, {"id": 2}"#),
+        ) {
+            Err(DatabaseError::JsonDocument(JsonError::MissingField { index, field, .. })) => {
+                assert_eq!(index, 1);
+                assert_eq!(field, "b");
+            }
+            other => panic!("Expected MissingField error, got {other:?}"),
+        }
+        assert!(matches!(
+            import_document(
+                "json_duplicate",
+                &document(r#"{"id": 1, "b": "0x"}, {"id": 1, "b": "0x00"}"#)
+            ),
+            Err(DatabaseError::JsonDocument(JsonError::DuplicateKey { .. }))
+        ));
+        assert!(matches!(
+            import_document("json_unknown", &document(r#"{"id": 1, "b": "0x", "c": 1}"#)),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+        assert!(matches!(
+            import_document("json_syntax", "{\"tables\": ["),
+            Err(DatabaseError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_failed_import_keeps_database() {
+        let mut db = setup_db();
+        let path = temp_path("json_failed");
+        std::fs::write(&path, "[]").unwrap();
+        assert!(execute(&mut db, &format!("IMPORT_JSON \"{path}\";")).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(db.tables.len(), 2);
+    }
+
+    #[test]
+    fn test_export_fails_for_non_finite_float() {
+        let mut db = setup_db();
+        let table = db.get_table("items").unwrap();
+        let mut record = table.rows.get(&1).unwrap().into_owned();
+        record
+            .fields
+            .insert("price".into(), DataValue::Float(f64::INFINITY).into());
+        table.insert_row(1, record).unwrap();
+        let path = temp_path("json_non_finite");
+        assert!(matches!(
+            execute(&mut db, &format!("EXPORT_JSON \"{path}\";")),
+            Err(DatabaseError::JsonDocument(
+                JsonError::NonFiniteFloat { .. }
+            ))
+        ));
+        assert!(!std::path::Path::new(&path).exists());
+    }
+}
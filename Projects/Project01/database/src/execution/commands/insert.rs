@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     DatabaseResult,
-    ast::{Assignment, InsertStmt},
-    core::{DatabaseError, DatabaseKey, InsertError, Record, Table},
+    ast::{Assignment, ConflictAction, InsertStmt},
+    core::{DatabaseKey, InsertError, Record, Table},
     execution::{Execute, ExecutionResult},
 };
 
+use super::{affected_records, check_constraints, triggered::RowTriggers};
+
 /// Represents an executable `INSERT INTO` command.
 ///
 /// This struct couples the parsed `InsertStmt` (the "what") with a
@@ -17,45 +19,48 @@ pub struct Insert<'a, K: DatabaseKey> {
     pub table: &'a mut Table<K>,
     /// The parsed AST (Abstract Syntax Tree) for the `INSERT` statement.
     pub ast: InsertStmt,
+    /// The triggers of the table, if it has any.
+    pub triggers: Option<&'a mut RowTriggers<K>>,
 }
 
 impl<'a, K: DatabaseKey> Execute for Insert<'a, K> {
     /// Executes the `INSERT INTO` command.
     ///
+    /// Fields that are not assigned take their `DEFAULT` value from the table, if any.
+    ///
+    /// If a record with the same key exists, `ON CONFLICT REPLACE` replaces it with
+    /// the new record and `ON CONFLICT UPDATE` overwrites only the assigned fields,
+    /// keeping the others (so it does not need every field to be assigned).
+    ///
     /// # Errors
     ///
     /// Returns an error if any validation step fails:
     /// * `DatabaseError::FieldNotFound`
     /// * `DatabaseError::TypeMismatch`
+    /// * `DatabaseError::InvalidEnumValue`
     /// * `InsertError::DuplicateAssignment`
     /// * `InsertError::MissingField`
-    /// * `InsertError::DuplicateKey`
+    /// * `InsertError::DuplicateKey` - Only without an `ON CONFLICT` clause.
+    /// * `DatabaseError::ConstraintViolation` - The written record fails a `CHECK`.
+    /// * `DatabaseError::Io` - The record cannot be written to the file of an
+    ///   on-disk table.
+    ///
+    /// A `RETURNING` field that is not in the schema is reported as
+    /// `DatabaseError::FieldNotFound` before anything is inserted.
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
         let table_name = self.table.name.clone();
+        let returning = self.ast.returning.as_deref();
+        if let Some(fields) = returning {
+            self.table.check_fields(fields)?;
+        }
+
         let mut record_fields = HashMap::with_capacity(self.table.schema.len());
 
         for Assignment { field_name, value } in &self.ast.assignments {
-            let expected_type =
-                self.table
-                    .schema
-                    .get(field_name)
-                    .ok_or_else(|| DatabaseError::FieldNotFound {
-                        table: table_name.clone(),
-                        field: field_name.clone(),
-                    })?;
-
-            let actual_type = value.get_type();
-            if *expected_type != actual_type {
-                return Err(DatabaseError::TypeMismatch {
-                    table: table_name.clone(),
-                    field: field_name.clone(),
-                    expected: *expected_type,
-                    found: actual_type,
-                });
-            }
+            self.table.check_value(field_name, value)?;
 
             if record_fields
-                .insert(field_name.clone(), value.clone())
+                .insert(field_name.clone(), Arc::new(value.clone()))
                 .is_some()
             {
                 return Err(InsertError::DuplicateAssignment {
@@ -66,6 +71,33 @@ impl<'a, K: DatabaseKey> Execute for Insert<'a, K> {
             }
         }
 
+        if self.ast.on_conflict == Some(ConflictAction::Update)
+            && let Some(key_datavalue) = record_fields.get(&self.table.key_field)
+        {
+            let key = K::from_datavalue(key_datavalue).unwrap();
+            if let Some(existing) = self.table.rows.get(&key) {
+                let mut merged = existing.into_owned();
+                merged.fields.extend(record_fields);
+                check_constraints(self.table, &merged)?;
+                if let Some(triggers) = &self.triggers {
+                    triggers.before(&merged)?;
+                }
+
+                let result = affected_records(returning, &[&merged]);
+                if let Some(triggers) = &mut self.triggers {
+                    triggers.changed(&merged);
+                }
+                self.table.insert_row(key, merged)?;
+                return Ok(result);
+            }
+        }
+
+        for (field_name, default) in &self.table.defaults {
+            record_fields
+                .entry(field_name.clone())
+                .or_insert_with(|| Arc::new(default.clone()));
+        }
+
         if record_fields.len() != self.table.schema.len() {
             let missing_field = self
                 .table
@@ -84,7 +116,7 @@ impl<'a, K: DatabaseKey> Execute for Insert<'a, K> {
         let key_datavalue = record_fields.get(&self.table.key_field).unwrap();
         let key = K::from_datavalue(key_datavalue).unwrap();
 
-        if self.table.rows.contains_key(&key) {
+        if self.ast.on_conflict.is_none() && self.table.rows.contains_key(&key) {
             return Err(InsertError::DuplicateKey {
                 table: table_name,
                 key: format!("{key:?}"),
@@ -95,9 +127,17 @@ impl<'a, K: DatabaseKey> Execute for Insert<'a, K> {
         let record = Record {
             fields: record_fields,
         };
-        self.table.rows.insert(key, record);
+        check_constraints(self.table, &record)?;
+        if let Some(triggers) = &self.triggers {
+            triggers.before(&record)?;
+        }
+        let result = affected_records(returning, &[&record]);
+        if let Some(triggers) = &mut self.triggers {
+            triggers.changed(&record);
+        }
+        self.table.insert_row(key, record)?;
 
-        Ok(ExecutionResult::RowsAffected(1))
+        Ok(result)
     }
 }
 
@@ -106,7 +146,7 @@ mod tests {
     use super::*;
     use crate::{
         ast::{Assignment, CreateStmt, Field, InsertStmt},
-        core::{DataType, DataValue, Database, DatabaseError, InsertError},
+        core::{DataType, DataValue, Database, DatabaseError, InsertError, TableStorage},
         execution::Execute,
     };
 
@@ -121,20 +161,28 @@ mod tests {
                 Field {
                     name: "id".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "name".to_string(),
                     data_type: DataType::String,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "age".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
             ];
             let ast = CreateStmt {
                 table_name: "users".to_string(),
                 key_field: "id".to_string(),
                 fields,
+                checks: Vec::new(),
+                storage: TableStorage::default(),
                 query: "".to_string(),
             };
             let mut cmd = Create {
@@ -163,6 +211,8 @@ mod tests {
                     value: DataValue::Int(30),
                 },
             ],
+            on_conflict: None,
+            returning: None,
             query: "Insert...".to_string(), // unused
         }
     }
@@ -172,7 +222,11 @@ mod tests {
         let mut db = common::setup_db_i64();
         let ast = create_valid_ast();
         let table = db.tables.get_mut("users").unwrap();
-        let mut cmd = Insert { table, ast };
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
 
         let result = cmd.execute();
         assert!(
@@ -188,7 +242,7 @@ mod tests {
         assert_eq!(db.tables["users"].rows.len(), 1);
         let record = db.tables["users"].rows.get(&1).unwrap();
         assert_eq!(
-            record.fields.get("name"),
+            record.get("name"),
             Some(&DataValue::String("Alice".to_string()))
         );
     }
@@ -203,13 +257,17 @@ mod tests {
         });
 
         let table = db.tables.get_mut("users").unwrap();
-        let mut cmd = Insert { table, ast };
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
 
         let result = cmd.execute();
         assert!(result.is_err());
 
         match result.err().unwrap() {
-            DatabaseError::FieldNotFound { table, field } => {
+            DatabaseError::FieldNotFound { table, field, .. } => {
                 assert_eq!(table, "users");
                 assert_eq!(field, "email");
             }
@@ -228,7 +286,11 @@ mod tests {
         };
 
         let table = db.tables.get_mut("users").unwrap();
-        let mut cmd = Insert { table, ast };
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
 
         let result = cmd.execute();
         assert!(result.is_err());
@@ -260,7 +322,11 @@ mod tests {
         });
 
         let table = db.tables.get_mut("users").unwrap();
-        let mut cmd = Insert { table, ast };
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
 
         let result = cmd.execute();
         assert!(result.is_err());
@@ -275,6 +341,95 @@ mod tests {
         assert_eq!(db.tables["users"].rows.len(), 0);
     }
 
+    #[test]
+    fn test_execute_insert_fills_defaults() {
+        let mut db = common::setup_db_i64();
+        let mut ast = create_valid_ast();
+        ast.assignments.pop();
+
+        let table = db.tables.get_mut("users").unwrap();
+        table.defaults.insert("age".to_string(), DataValue::Int(18));
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
+        cmd.execute().unwrap();
+
+        let mut ast = create_valid_ast();
+        ast.assignments[0].value = DataValue::Int(2);
+        let table = db.tables.get_mut("users").unwrap();
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
+        cmd.execute().unwrap();
+
+        let rows = &db.tables["users"].rows;
+        assert_eq!(*rows.get(&1).unwrap().fields["age"], DataValue::Int(18));
+        assert_eq!(*rows.get(&2).unwrap().fields["age"], DataValue::Int(30));
+    }
+
+    #[test]
+    fn test_execute_fail_value_outside_enum() {
+        let mut db = common::setup_db_i64();
+        let table = db.tables.get_mut("users").unwrap();
+        table
+            .enums
+            .insert("name".to_string(), vec!["Alice".to_string()]);
+
+        let mut ast = create_valid_ast();
+        ast.assignments[1].value = DataValue::String("Mallory".to_string());
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
+        match cmd.execute().err().unwrap() {
+            DatabaseError::InvalidEnumValue {
+                field, variants, ..
+            } => {
+                assert_eq!(field, "name");
+                assert_eq!(variants, ["Alice"]);
+            }
+            e => panic!("Expected InvalidEnumValue error, got {e:?}"),
+        }
+        assert!(db.tables["users"].rows.is_empty());
+    }
+
+    #[test]
+    fn test_execute_fail_check_constraint() {
+        let mut db = Database::<i64>::default();
+        let execute = |db: &mut Database<i64>, query: &str| {
+            let ast = crate::QueryParser.parse_query(query)?;
+            crate::execution::build_execute_command(db, ast)?.execute()
+        };
+        execute(
+            &mut db,
+            "CREATE users KEY id FIELDS id: INT, age: INT CHECK (age >= 0) CHECK (age < 150);",
+        )
+        .unwrap();
+        execute(&mut db, "INSERT id = 1, age = 30 INTO users;").unwrap();
+
+        for query in [
+            "INSERT id = 2, age = -1 INTO users;",
+            "INSERT id = 1, age = 150 INTO users ON CONFLICT UPDATE;",
+            "INSERT id = 1, age = 200 INTO users ON CONFLICT REPLACE;",
+        ] {
+            match execute(&mut db, query).err().unwrap() {
+                DatabaseError::ConstraintViolation { record, .. } => {
+                    assert_eq!(record.fields.len(), 2);
+                }
+                e => panic!("Expected ConstraintViolation error, got {e:?}"),
+            }
+        }
+
+        let rows = &db.tables["users"].rows;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(*rows.get(&1).unwrap().fields["age"], DataValue::Int(30));
+    }
+
     #[test]
     fn test_execute_fail_missing_field() {
         let mut db = common::setup_db_i64();
@@ -282,7 +437,11 @@ mod tests {
         ast.assignments.pop();
 
         let table = db.tables.get_mut("users").unwrap();
-        let mut cmd = Insert { table, ast };
+        let mut cmd = Insert {
+            table,
+            ast,
+            triggers: None,
+        };
 
         let result = cmd.execute();
         assert!(result.is_err());
@@ -307,6 +466,7 @@ mod tests {
             let mut cmd1 = Insert {
                 table: table1,
                 ast: ast1,
+                triggers: None,
             };
 
             let result1 = cmd1.execute();
@@ -321,6 +481,7 @@ mod tests {
             let mut cmd2 = Insert {
                 table: table2,
                 ast: ast2,
+                triggers: None,
             };
 
             let result2 = cmd2.execute();
@@ -340,4 +501,116 @@ mod tests {
 
         assert_eq!(db.tables["users"].rows.len(), 1);
     }
+
+    #[test]
+    fn test_execute_insert_on_conflict() {
+        let mut db = common::setup_db_i64();
+        let table = db.tables.get_mut("users").unwrap();
+        Insert {
+            table,
+            ast: create_valid_ast(),
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+
+        let mut ast = create_valid_ast();
+        ast.assignments[1].value = DataValue::String("Alicia".to_string());
+        ast.assignments.pop();
+        ast.on_conflict = Some(ConflictAction::Update);
+        let table = db.tables.get_mut("users").unwrap();
+        let result = Insert {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+        assert!(matches!(result, ExecutionResult::RowsAffected(1)));
+
+        let record = &db.tables["users"].rows.get(&1).unwrap();
+        assert_eq!(
+            *record.fields["name"],
+            DataValue::String("Alicia".to_string())
+        );
+        assert_eq!(*record.fields["age"], DataValue::Int(30));
+
+        let mut ast = create_valid_ast();
+        ast.assignments[2].value = DataValue::Int(31);
+        ast.on_conflict = Some(ConflictAction::Replace);
+        let table = db.tables.get_mut("users").unwrap();
+        Insert {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+
+        let record = &db.tables["users"].rows.get(&1).unwrap();
+        assert_eq!(
+            *record.fields["name"],
+            DataValue::String("Alice".to_string())
+        );
+        assert_eq!(*record.fields["age"], DataValue::Int(31));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_fail_on_conflict_replace_missing_field() {
+        let mut db = common::setup_db_i64();
+        let table = db.tables.get_mut("users").unwrap();
+        Insert {
+            table,
+            ast: create_valid_ast(),
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+
+        let mut ast = create_valid_ast();
+        ast.assignments.pop();
+        ast.on_conflict = Some(ConflictAction::Replace);
+        let table = db.tables.get_mut("users").unwrap();
+        let result = Insert {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute();
+        assert!(matches!(
+            result,
+            Err(DatabaseError::Insert(InsertError::MissingField { .. }))
+        ));
+        assert_eq!(
+            *db.tables["users"].rows.get(&1).unwrap().fields["age"],
+            DataValue::Int(30)
+        );
+    }
+
+    #[test]
+    fn test_execute_insert_returning() {
+        let mut db = common::setup_db_i64();
+        let mut ast = create_valid_ast();
+        ast.returning = Some(vec!["id".to_string(), "age".to_string()]);
+
+        let table = db.tables.get_mut("users").unwrap();
+        let result = Insert {
+            table,
+            ast,
+            triggers: None,
+        }
+        .execute()
+        .unwrap();
+
+        match result {
+            ExecutionResult::Data(records) => {
+                assert_eq!(records.len(), 1);
+                assert_eq!(records[0].fields.len(), 2);
+                assert_eq!(*records[0].fields["age"], DataValue::Int(30));
+            }
+            _ => panic!("Expected ExecutionResult::Data"),
+        }
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
 }
@@ -0,0 +1,201 @@
+//! Inserting many records into a table at once, as `LOAD_CSV` does.
+//!
+//! Every record is validated before any of them is stored, so that a batch is
+//! either inserted as a whole or not at all, without building and executing an
+//! `INSERT` per record.
+
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    DatabaseResult,
+    core::{DatabaseKey, InsertError, Record, Table},
+};
+
+use super::check_constraints;
+
+impl<K: DatabaseKey> Table<K> {
+    /// Inserts `records`, as many `INSERT` statements without an `ON CONFLICT`
+    /// clause would, but either all of them or none.
+    ///
+    /// The fields a record does not hold take their `DEFAULT` value. Every record
+    /// is validated, and its key checked against the stored records and the other
+    /// records of the batch, before any of them is stored. The triggers of the
+    /// table are not run.
+    ///
+    /// # Errors
+    /// Returns the error of the first invalid record, inserting nothing:
+    /// * `DatabaseError::FieldNotFound`
+    /// * `DatabaseError::TypeMismatch`
+    /// * `DatabaseError::InvalidEnumValue`
+    /// * `InsertError::MissingField`
+    /// * `InsertError::DuplicateKey` - The key is stored, or held by an earlier record.
+    /// * `DatabaseError::ConstraintViolation` - The record fails a `CHECK`.
+    ///
+    /// Returns `DatabaseError::Io` if a record cannot be written to the file of an
+    /// on-disk table, in which case the records already stored are removed again.
+    ///
+    /// # Returns
+    /// The number of inserted records.
+    pub fn insert_many(&mut self, records: Vec<Record>) -> DatabaseResult<usize> {
+        let mut keys = HashSet::with_capacity(records.len());
+        let batch = records
+            .into_iter()
+            .map(|record| self.prepare_insert(record, &mut keys))
+            .collect::<DatabaseResult<Vec<_>>>()?;
+        self.apply_inserts(batch)
+    }
+
+    /// Validates `record` for [`Table::insert_many`], filling in the defaults.
+    ///
+    /// `keys` holds the keys of the records of the batch prepared before, to which
+    /// the key of `record` is added.
+    ///
+    /// # Returns
+    /// The key and the completed record.
+    pub(super) fn prepare_insert(
+        &self,
+        mut record: Record,
+        keys: &mut HashSet<K>,
+    ) -> DatabaseResult<(K, Record)> {
+        for (field, value) in &record.fields {
+            self.check_value(field, value)?;
+        }
+        for (field, default) in &self.defaults {
+            record
+                .fields
+                .entry(field.clone())
+                .or_insert_with(|| Arc::new(default.clone()));
+        }
+        if let Some(missing_field) = self
+            .schema
+            .keys()
+            .find(|field| !record.fields.contains_key(*field))
+        {
+            return Err(InsertError::MissingField {
+                table: self.name.clone(),
+                field: missing_field.clone(),
+            }
+            .into());
+        }
+
+        let key = K::from_datavalue(&record.fields[&self.key_field]).unwrap();
+        if self.rows.contains_key(&key) || keys.contains(&key) {
+            return Err(InsertError::DuplicateKey {
+                table: self.name.clone(),
+                key: format!("{key:?}"),
+            }
+            .into());
+        }
+        check_constraints(self, &record)?;
+
+        keys.insert(key.clone());
+        Ok((key, record))
+    }
+
+    /// Stores the records prepared by [`Table::prepare_insert`], all of them or,
+    /// if one cannot be written, none.
+    ///
+    /// # Returns
+    /// The number of stored records.
+    pub(super) fn apply_inserts(&mut self, batch: Vec<(K, Record)>) -> DatabaseResult<usize> {
+        for index in self.indexes.values_mut() {
+            index.reserve(batch.len());
+        }
+
+        let mut inserted = Vec::with_capacity(batch.len());
+        for (key, record) in batch {
+            if let Err(error) = self.insert_row(key.clone(), record) {
+                for key in &inserted {
+                    self.remove_row(key);
+                }
+                return Err(error);
+            }
+            inserted.push(key);
+        }
+        Ok(inserted.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::core::{DataValue, Database, DatabaseError, IndexKind};
+
+    use super::*;
+
+    fn user(id: i64, age: Option<i64>) -> Record {
+        let mut fields = HashMap::from([("id".to_string(), DataValue::Int(id))]);
+        if let Some(age) = age {
+            fields.insert("age".to_string(), DataValue::Int(age));
+        }
+        Record::new(fields)
+    }
+
+    fn setup() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, age: INT, name: STRING DEFAULT \"anonymous\" \
+             CHECK (age >= 0);",
+            "INSERT id = 1, age = 30 INTO users;",
+        ] {
+            db.execute(query).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let mut db = setup();
+        let table = db.get_table("users").unwrap();
+        table
+            .create_index("by_age", "age", IndexKind::Hash)
+            .unwrap();
+
+        let records = (2..=100).map(|id| user(id, Some(id % 10))).collect();
+        assert_eq!(table.insert_many(records).unwrap(), 99);
+        assert_eq!(table.rows.len(), 100);
+        assert_eq!(
+            *table.rows.get(&2).unwrap().fields["name"],
+            DataValue::String("anonymous".to_string())
+        );
+        assert_eq!(table.indexes["by_age"].get(&DataValue::Int(7)).len(), 10);
+    }
+
+    #[test]
+    fn test_insert_many_is_atomic() {
+        let mut db = setup();
+        let table = db.get_table("users").unwrap();
+
+        for (records, expected) in [
+            (vec![user(2, Some(1)), user(1, Some(2))], "DuplicateKey"),
+            (vec![user(2, Some(1)), user(2, Some(2))], "DuplicateKey"),
+            (vec![user(2, Some(1)), user(3, None)], "MissingField"),
+            (
+                vec![user(2, Some(1)), user(3, Some(-1))],
+                "ConstraintViolation",
+            ),
+        ] {
+            let error = table.insert_many(records).unwrap_err();
+            let found = match error {
+                DatabaseError::Insert(InsertError::DuplicateKey { .. }) => "DuplicateKey",
+                DatabaseError::Insert(InsertError::MissingField { .. }) => "MissingField",
+                DatabaseError::ConstraintViolation { .. } => "ConstraintViolation",
+                other => panic!("Unexpected error {other:?}"),
+            };
+            assert_eq!(found, expected);
+            assert_eq!(table.rows.len(), 1);
+        }
+
+        let mut record = user(2, Some(1));
+        record.fields.insert(
+            "email".to_string(),
+            Arc::new(DataValue::String("a@example.com".to_string())),
+        );
+        assert!(matches!(
+            table.insert_many(vec![record]),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+        assert_eq!(table.rows.len(), 1);
+    }
+}
@@ -0,0 +1,301 @@
+//! The JSON document written by `EXPORT_JSON` and read by `IMPORT_JSON`.
+//!
+//! Every table is stored with its columns, `CHECK` conditions (in query syntax),
+//! index definitions, storage (`"MEMORY"` or `"DISK"`) and rows. Each row is a JSON object mapping column names to
+//! plain JSON values, which are read back according to the column's type:
+//!
+//! * `BOOLEAN`, `INT` and `FLOAT` values are JSON booleans and numbers,
+//! * `STRING` and `UUID` values are JSON strings,
+//! * `BYTES` values are strings holding their hex literal (e.g., `"0xdeadbeef"`).
+//!
+//! Indexes are rebuilt from the rows and table statistics are not stored.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    DatabaseResult, QueryParser,
+    core::{
+        DataType, DataValue, Database, DatabaseKey, IndexKind, JsonError, Record, Table,
+        TableStorage,
+    },
+};
+
+use super::check_constraints;
+
+/// The whole database: its tables, ordered by name, and its command history.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseDocument {
+    pub tables: Vec<TableDocument>,
+    #[serde(default)]
+    pub history: Vec<String>,
+}
+
+/// A single table, with its columns ordered by name and its rows by key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableDocument {
+    pub name: String,
+    pub key_field: String,
+    pub columns: Vec<ColumnDocument>,
+    #[serde(default)]
+    pub checks: Vec<String>,
+    #[serde(default)]
+    pub indexes: Vec<IndexDocument>,
+    #[serde(default)]
+    pub storage: TableStorage,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+/// A column definition, as given to `CREATE`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnDocument {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: DataType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+}
+
+/// An index definition, as given to `CREATE_INDEX`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexDocument {
+    pub name: String,
+    pub field: String,
+    pub kind: IndexKind,
+}
+
+impl DatabaseDocument {
+    /// Describes `database` as a JSON document.
+    ///
+    /// # Errors
+    /// Returns `JsonError::NonFiniteFloat` for a `FLOAT` value that JSON cannot represent.
+    pub fn from_database<K: DatabaseKey>(database: &Database<K>) -> DatabaseResult<Self> {
+        let mut tables = database
+            .tables
+            .values()
+            .map(TableDocument::from_table)
+            .collect::<DatabaseResult<Vec<_>>>()?;
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Self {
+            tables,
+            history: database.iter_history().cloned().collect(),
+        })
+    }
+
+    /// Builds the database described by the document.
+    ///
+    /// # Errors
+    /// Returns the first error raised while rebuilding a table, see
+    /// [`TableDocument::into_table`].
+    pub fn into_database<K: DatabaseKey>(self) -> DatabaseResult<Database<K>> {
+        let mut database = Database::default();
+        for table in self.tables {
+            database.add_table(table.into_table()?)?;
+        }
+        for query in &self.history {
+            database.push_to_history(query);
+        }
+        Ok(database)
+    }
+}
+
+impl TableDocument {
+    /// Describes `table` as a JSON document.
+    fn from_table<K: DatabaseKey>(table: &Table<K>) -> DatabaseResult<Self> {
+        let mut columns = table
+            .schema
+            .iter()
+            .map(|(name, data_type)| {
+                let default = table
+                    .defaults
+                    .get(name)
+                    .map(|value| to_json(&table.name, name, value))
+                    .transpose()?;
+                Ok(ColumnDocument {
+                    name: name.clone(),
+                    data_type: *data_type,
+                    default,
+                    variants: table.enums.get(name).cloned(),
+                })
+            })
+            .collect::<DatabaseResult<Vec<_>>>()?;
+        columns.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut indexes = table
+            .indexes
+            .iter()
+            .map(|(name, index)| IndexDocument {
+                name: name.clone(),
+                field: index.field.clone(),
+                kind: index.kind(),
+            })
+            .collect::<Vec<_>>();
+        indexes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let rows = table
+            .rows
+            .values()
+            .map(|record| {
+                record
+                    .fields
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), to_json(&table.name, name, value)?)))
+                    .collect()
+            })
+            .collect::<DatabaseResult<Vec<_>>>()?;
+
+        Ok(Self {
+            name: table.name.clone(),
+            key_field: table.key_field.clone(),
+            columns,
+            checks: table.checks.iter().map(ToString::to_string).collect(),
+            indexes,
+            storage: table.rows.storage(),
+            rows,
+        })
+    }
+
+    /// Builds the table described by the document, validating it as `CREATE`,
+    /// `CREATE_INDEX` and `INSERT` would.
+    ///
+    /// # Errors
+    /// * The errors of [`Table::new`], [`Table::with_enums`], [`Table::with_checks`],
+    ///   [`Table::with_defaults`], [`Table::with_storage`] and [`Table::create_index`].
+    /// * `DatabaseError::Parse` if a `CHECK` condition is not valid query syntax.
+    /// * `DatabaseError::FieldNotFound` if a row holds a field that is not a column.
+    /// * `JsonError::InvalidValue` if a value does not match its column's type.
+    /// * `JsonError::MissingField` if a row does not hold every column.
+    /// * `JsonError::DuplicateKey` if two rows have the same key.
+    /// * `DatabaseError::ConstraintViolation` if a row fails a `CHECK`.
+    fn into_table<K: DatabaseKey>(self) -> DatabaseResult<Table<K>> {
+        let schema = self
+            .columns
+            .iter()
+            .map(|column| (column.name.clone(), column.data_type))
+            .collect::<HashMap<_, _>>();
+        let enums = self
+            .columns
+            .iter()
+            .filter_map(|column| Some((column.name.clone(), column.variants.clone()?)))
+            .collect();
+        let checks = self
+            .checks
+            .iter()
+            .map(|check| QueryParser.parse_condition(check))
+            .collect::<DatabaseResult<Vec<_>>>()?;
+        let defaults = self
+            .columns
+            .iter()
+            .filter_map(|column| {
+                let value = column.default.as_ref()?;
+                let value = from_json(&self.name, &column.name, column.data_type, value);
+                Some(value.map(|value| (column.name.clone(), value)))
+            })
+            .collect::<DatabaseResult<HashMap<_, _>>>()?;
+
+        let mut table = Table::new(&self.name, &self.key_field, schema)?
+            .with_enums(enums)?
+            .with_checks(checks)?
+            .with_defaults(defaults)?
+            .with_storage(self.storage)?;
+        for index in &self.indexes {
+            table.create_index(&index.name, &index.field, index.kind)?;
+        }
+
+        for (index, row) in self.rows.into_iter().enumerate() {
+            let mut fields = HashMap::with_capacity(row.len());
+            for (field, value) in row {
+                let data_type = *table
+                    .schema
+                    .get(&field)
+                    .ok_or_else(|| table.field_not_found(&field))?;
+                let value = from_json(&self.name, &field, data_type, &value)?;
+                table.check_value(&field, &value)?;
+                fields.insert(field, value);
+            }
+            if let Some(field) = table
+                .schema
+                .keys()
+                .find(|field| !fields.contains_key(*field))
+            {
+                return Err(JsonError::MissingField {
+                    table: self.name,
+                    index,
+                    field: field.clone(),
+                }
+                .into());
+            }
+
+            let record = Record::new(fields);
+            check_constraints(&table, &record)?;
+            let key = K::from_datavalue(record.get(&table.key_field).unwrap()).unwrap();
+            if table.rows.contains_key(&key) {
+                return Err(JsonError::DuplicateKey {
+                    table: self.name,
+                    key: format!("{key:?}"),
+                }
+                .into());
+            }
+            table.insert_row(key, record)?;
+        }
+
+        Ok(table)
+    }
+}
+
+/// Converts a value of the column `field` of `table` into its JSON form.
+///
+/// # Errors
+/// Returns `JsonError::NonFiniteFloat` for a `NaN` or infinite `FLOAT`.
+fn to_json(table: &str, field: &str, value: &DataValue) -> DatabaseResult<Value> {
+    Ok(match value {
+        DataValue::Boolean(v) => Value::Bool(*v),
+        DataValue::Int(v) => Value::from(*v),
+        DataValue::Float(v) => {
+            Value::Number(
+                Number::from_f64(*v).ok_or_else(|| JsonError::NonFiniteFloat {
+                    table: table.to_string(),
+                    field: field.to_string(),
+                    value: *v,
+                })?,
+            )
+        }
+        DataValue::String(v) => Value::String(v.clone()),
+        DataValue::Bytes(_) => Value::String(value.to_string()),
+        DataValue::Uuid(v) => Value::String(v.to_string()),
+    })
+}
+
+/// Reads the JSON form of a value of the column `field` of `table`, whose type is `data_type`.
+///
+/// # Errors
+/// Returns `JsonError::InvalidValue` if `json` is not a valid value of that type.
+fn from_json(
+    table: &str,
+    field: &str,
+    data_type: DataType,
+    json: &Value,
+) -> DatabaseResult<DataValue> {
+    let value = match (data_type, json) {
+        (DataType::Boolean, Value::Bool(v)) => Some(DataValue::Boolean(*v)),
+        (DataType::Int, Value::Number(v)) => v.as_i64().map(DataValue::Int),
+        (DataType::Float, Value::Number(v)) => v.as_f64().map(DataValue::Float),
+        (DataType::String, Value::String(v)) => Some(DataValue::String(v.clone())),
+        (DataType::Bytes | DataType::Uuid, Value::String(v)) => DataValue::parse_as(data_type, v),
+        _ => None,
+    };
+    value.ok_or_else(|| {
+        JsonError::InvalidValue {
+            table: table.to_string(),
+            field: field.to_string(),
+            expected: data_type,
+            value: json.to_string(),
+        }
+        .into()
+    })
+}
@@ -0,0 +1,380 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    sync::Arc,
+};
+
+use crate::{
+    DatabaseResult,
+    ast::LoadCsvStmt,
+    core::{CsvError, DataValue, DatabaseKey, Record, Table, WriteAheadLog},
+    execution::{Execute, ExecutionResult, transaction::append_committed},
+};
+
+/// Represents an executable `LOAD_CSV` command.
+///
+/// This struct couples the parsed `LoadCsvStmt` (the "what") with a
+/// mutable reference to the specific `Table` (the "where") that the
+/// records of the CSV file are inserted into.
+pub struct LoadCsv<'a, K: DatabaseKey> {
+    /// A mutable reference to the table where the records will be inserted.
+    pub table: &'a mut Table<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `LOAD_CSV` statement.
+    pub ast: LoadCsvStmt,
+    /// The write-ahead log recording the inserted records, if the database is
+    /// logged.
+    pub wal: Option<&'a mut WriteAheadLog>,
+}
+
+/// A single cell of a CSV line, remembering whether it was quoted.
+struct Cell {
+    text: String,
+    quoted: bool,
+}
+
+impl<'a, K: DatabaseKey> Execute for LoadCsv<'a, K> {
+    /// Executes the `LOAD_CSV` command.
+    ///
+    /// The first line of the file names the columns the cells of the following
+    /// lines are stored in, in any order. Cells are separated by commas and may be
+    /// quoted with `"` (a quote inside a quoted cell is written as `""`); unquoted
+    /// cells are trimmed of surrounding whitespace. Each cell is converted to its
+    /// column's type as described in [`DataValue::parse_as`]. An empty, unquoted
+    /// cell leaves its field unassigned, so that it takes the column's `DEFAULT`
+    /// value; `""` is an empty `STRING`. Blank lines are skipped.
+    ///
+    /// Every line is inserted as by `INSERT` without an `ON CONFLICT` clause. A line
+    /// that cannot be read or inserted is skipped and reported, the other lines are
+    /// still inserted. The lines are all validated first and then inserted at once,
+    /// as by [`Table::insert_many`].
+    ///
+    /// With a log, the inserted records are then recorded in it as one `INSERT`
+    /// query each, in a transaction, so that replaying the log does not depend on
+    /// the file. If they cannot be recorded, they are removed again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, inserting nothing, if the header cannot be used:
+    /// * `DatabaseError::Io` - The file cannot be opened or read.
+    /// * `CsvError::MissingHeader` - The file has no non-blank line.
+    /// * `CsvError::UnterminatedQuote` - The header has an unterminated quoted cell.
+    /// * `CsvError::DuplicateColumn` - The header names a column twice.
+    /// * `DatabaseError::FieldNotFound` - The header names a column that is not in the schema.
+    ///
+    /// Returns `DatabaseError::Io`, inserting nothing, if a record cannot be written
+    /// to the file of an on-disk table or to the log.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ExecutionResult::Messages(report))`, holding the number of
+    /// inserted records followed by the error of each skipped line.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let path = self.ast.path.clone();
+        let content = fs::read_to_string(&path)?;
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| CsvError::MissingHeader { path: path.clone() })?;
+        let columns = split_line(header)?
+            .into_iter()
+            .map(|cell| cell.text)
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        if let Some(column) = columns.iter().find(|column| !seen.insert(*column)) {
+            return Err(CsvError::DuplicateColumn {
+                path: path.clone(),
+                column: column.clone(),
+            }
+            .into());
+        }
+        self.table.check_fields(&columns)?;
+
+        let mut keys = HashSet::new();
+        let mut batch = Vec::new();
+        let mut errors = Vec::new();
+        for (number, line) in lines {
+            match self
+                .read_line(&columns, line)
+                .and_then(|record| self.table.prepare_insert(record, &mut keys))
+            {
+                Ok(entry) => batch.push(entry),
+                Err(error) => errors.push(format!("Line {number}: {error}")),
+            }
+        }
+        let logged = self.wal.is_some().then(|| {
+            batch
+                .iter()
+                .map(|(key, record)| (key.clone(), insert_query(&self.table.name, record)))
+                .collect::<Vec<_>>()
+        });
+        let inserted = self.table.apply_inserts(batch)?;
+        if let (Some(wal), Some(logged)) = (self.wal.as_deref_mut(), logged) {
+            let (keys, queries): (Vec<_>, Vec<_>) = logged.into_iter().unzip();
+            if let Err(error) = append_committed(wal, &queries) {
+                for key in &keys {
+                    self.table.remove_row(key);
+                }
+                return Err(error);
+            }
+        }
+
+        let mut report = vec![format!(
+            "Inserted {inserted} of {} record(s) from '{path}' into '{}'.",
+            inserted + errors.len(),
+            self.table.name
+        )];
+        report.extend(errors);
+        Ok(ExecutionResult::Messages(report))
+    }
+}
+
+impl<'a, K: DatabaseKey> LoadCsv<'a, K> {
+    /// Converts the cells of `line` to the types of `columns`, as a record to be
+    /// inserted.
+    fn read_line(&self, columns: &[String], line: &str) -> DatabaseResult<Record> {
+        let cells = split_line(line)?;
+        if cells.len() != columns.len() {
+            return Err(CsvError::CellCount {
+                expected: columns.len(),
+                found: cells.len(),
+            }
+            .into());
+        }
+
+        let mut fields = HashMap::with_capacity(self.table.schema.len());
+        for (field_name, cell) in columns.iter().zip(cells) {
+            if cell.text.is_empty() && !cell.quoted {
+                continue;
+            }
+            let data_type = self.table.schema[field_name];
+            let value = DataValue::parse_as(data_type, &cell.text).ok_or_else(|| {
+                CsvError::InvalidValue {
+                    field: field_name.clone(),
+                    expected: data_type,
+                    value: cell.text.clone(),
+                }
+            })?;
+            fields.insert(field_name.clone(), Arc::new(value));
+        }
+        Ok(Record { fields })
+    }
+}
+
+/// Returns the `INSERT` query storing `record` into `table_name`, assigning its
+/// fields in alphabetical order.
+fn insert_query(table_name: &str, record: &Record) -> String {
+    let mut fields = record.fields.iter().collect::<Vec<_>>();
+    fields.sort_unstable_by_key(|(field, _)| *field);
+    let assignments = fields
+        .into_iter()
+        .map(|(field, value)| format!("{field} = {value}"))
+        .collect::<Vec<_>>();
+    format!("INSERT {} INTO {table_name};", assignments.join(", "))
+}
+
+/// Splits a CSV line into its cells.
+///
+/// # Errors
+/// Returns `CsvError::UnterminatedQuote` if a quoted cell is not closed.
+fn split_line(line: &str) -> Result<Vec<Cell>, CsvError> {
+    let mut cells = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut cell = Cell {
+            text: String::new(),
+            quoted: false,
+        };
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            cell.quoted = true;
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        cell.text.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => cell.text.push(c),
+                    None => return Err(CsvError::UnterminatedQuote),
+                }
+            }
+        }
+        while let Some(c) = chars.next_if(|c| *c != ',') {
+            cell.text.push(c);
+        }
+        if !cell.quoted {
+            cell.text = cell.text.trim().to_string();
+        }
+        cells.push(cell);
+        if chars.next().is_none() {
+            return Ok(cells);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Database, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_db() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            "CREATE users KEY id FIELDS id: INT, name: STRING, score: FLOAT DEFAULT 0.0, \
+             active: BOOLEAN, avatar: BYTES CHECK (score >= 0.0);",
+        )
+        .unwrap();
+        execute(
+            &mut db,
+            "INSERT id = 1, name = \"Alice\", active = true, avatar = 0x INTO users;",
+        )
+        .unwrap();
+        db
+    }
+
+    fn load_csv(db: &mut Database<i64>, name: &str, content: &str) -> DatabaseResult<Vec<String>> {
+        let path = std::env::temp_dir().join(format!("{name}_{}.csv", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        std::fs::write(&path, content).unwrap();
+        let result = execute(db, &format!("LOAD_CSV \"{path}\" INTO users;"));
+        std::fs::remove_file(&path).unwrap();
+        match result? {
+            ExecutionResult::Messages(messages) => Ok(messages),
+            other => panic!("Expected Messages, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_load_csv() {
+        let mut db = setup_db();
+        let messages = load_csv(
+            &mut db,
+            "load_csv",
+            "name,id,active,score,avatar\n\
+             Bob, 2, TRUE, 1.5, 0xff\n\
+             \n\
+             \"Smith, \"\"Jr\"\"\",3,false,,0x\n\
+             \"\",4,false,7,0x0102\n",
+        )
+        .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("Inserted 3 of 3 record(s)"));
+
+        let rows = &db.tables["users"].rows;
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            *rows.get(&2).unwrap().fields["name"],
+            DataValue::String("Bob".into())
+        );
+        assert_eq!(
+            *rows.get(&2).unwrap().fields["active"],
+            DataValue::Boolean(true)
+        );
+        assert_eq!(
+            *rows.get(&2).unwrap().fields["avatar"],
+            DataValue::Bytes(vec![0xff])
+        );
+        assert_eq!(
+            *rows.get(&3).unwrap().fields["name"],
+            DataValue::String("Smith, \"Jr\"".into())
+        );
+        assert_eq!(
+            *rows.get(&3).unwrap().fields["score"],
+            DataValue::Float(0.0)
+        );
+        assert_eq!(
+            *rows.get(&4).unwrap().fields["name"],
+            DataValue::String(String::new())
+        );
+        assert_eq!(
+            *rows.get(&4).unwrap().fields["score"],
+            DataValue::Float(7.0)
+        );
+    }
+
+    #[test]
+    fn test_load_csv_reports_failed_lines() {
+        let mut db = setup_db();
+        let messages = load_csv(
+            &mut db,
+            "load_csv_report",
+            "id,name,active,avatar,score\n\
+             1,Duplicate,true,0x,1\n\
+             2,Bob,yes,0x,1\n\
+             3,Carol,true,0x\n\
+             4,\"Dave,true,0x,1\n\
+             5,Eve,true,0x,-1\n\
+             6,,true,0x,1\n\
+             7,Frank,false,0xab,2.5\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            messages[0].split(" from ").next().unwrap(),
+            "Inserted 1 of 7 record(s)"
+        );
+        assert_eq!(messages.len(), 7);
+        assert!(messages[1].starts_with("Line 2: "));
+        assert!(messages[2].starts_with("Line 3: Cell 'yes' of column 'active'"));
+        assert_eq!(messages[3], "Line 4: Expected 5 cell(s), found 4");
+        assert_eq!(messages[4], "Line 5: Quoted cell is not terminated");
+        assert!(messages[5].starts_with("Line 6: "));
+        assert!(messages[6].starts_with("Line 7: "));
+
+        let rows = &db.tables["users"].rows;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            *rows.get(&1).unwrap().fields["name"],
+            DataValue::String("Alice".into())
+        );
+        assert_eq!(
+            *rows.get(&7).unwrap().fields["avatar"],
+            DataValue::Bytes(vec![0xab])
+        );
+    }
+
+    #[test]
+    fn test_load_csv_fails() {
+        let mut db = setup_db();
+        assert!(matches!(
+            load_csv(&mut db, "load_csv_empty", "\n  \n"),
+            Err(DatabaseError::Csv(CsvError::MissingHeader { .. }))
+        ));
+        match load_csv(&mut db, "load_csv_duplicate", "id,name,id\n2,Bob,2\n") {
+            Err(DatabaseError::Csv(CsvError::DuplicateColumn { column, .. })) => {
+                assert_eq!(column, "id");
+            }
+            other => panic!("Expected DuplicateColumn error, got {other:?}"),
+        }
+        assert!(matches!(
+            load_csv(&mut db, "load_csv_unknown", "id,email\n2,bob@example.com\n"),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+
+        assert!(matches!(
+            execute(&mut db, "LOAD_CSV \"missing.csv\" INTO users;"),
+            Err(DatabaseError::Io(_))
+        ));
+        assert!(matches!(
+            execute(&mut db, "LOAD_CSV \"missing.csv\" INTO orders;"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
@@ -1,14 +1,14 @@
-use std::{fs::File, io::BufReader};
-
-use bincode::{config, decode_from_std_read};
+use std::io::Cursor;
 
 use crate::{
     DatabaseResult,
-    ast::LoadFromStmt,
-    core::{Database, DatabaseKey},
+    ast::{DumpSource, LoadFromStmt},
+    core::{Database, DatabaseKey, EncryptionError},
     execution::{Execute, ExecutionResult},
 };
 
+use super::{dump_format, encryption};
+
 /// Represents an executable `LOAD_FROM` command.
 ///
 /// This struct couples the parsed `LoadFromStmt` (the "what") with a
@@ -20,25 +20,299 @@ pub struct LoadFrom<'a, K: DatabaseKey> {
     pub database: &'a mut Database<K>,
     /// The parsed AST (Abstract Syntax Tree) for the `LOAD_FROM` statement.
     pub ast: LoadFromStmt,
+    /// Whether the database is recorded in a write-ahead log, which an encrypted
+    /// snapshot must not be loaded into.
+    pub logged: bool,
 }
 
 impl<'a, K: DatabaseKey> Execute for LoadFrom<'a, K> {
     /// Executes the `LOAD_FROM` command.
     ///
+    /// An encrypted snapshot is decrypted with the passphrase given with `WITH`.
+    /// A snapshot of an older layout is upgraded to the current one.
+    ///
+    /// A plain snapshot is decoded while it is read from the file. An encrypted
+    /// one is read and decrypted as a whole first, as its authentication tag
+    /// covers all of it.
+    ///
+    /// An encrypted snapshot is refused while the database is logged, as the log
+    /// could only record it with its passphrase or decrypted.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// * `DatabaseError::Io` - The file cannot be opened or read.
     /// * `DatabaseError::Decode` - `bincode` fails to deserialize the database,
     ///   (e.g., due to corrupt data or a type mismatch).
+    /// * `EncryptionError::PassphraseRequired` - The snapshot is encrypted, but no
+    ///   passphrase was given.
+    /// * `EncryptionError::NotEncrypted` - A passphrase was given for a plain snapshot.
+    /// * `EncryptionError::Decryption` - The passphrase is wrong or the encrypted
+    ///   snapshot was modified.
+    /// * `DatabaseError::UnsupportedDumpVersion` - The snapshot was written with a
+    ///   newer layout.
+    /// * `DatabaseError::ChecksumMismatch` - The snapshot was modified or truncated.
+    /// * `EncryptionError::Logged` - A passphrase was given while the database is logged.
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
-        let file = File::open(&self.ast.path)?;
-        let mut reader = BufReader::new(file);
-        let config = config::standard();
-
-        let loaded: Database<K> = decode_from_std_read(&mut reader, config)?;
+        if let (true, Some(_), DumpSource::File(path)) =
+            (self.logged, &self.ast.passphrase, &self.ast.source)
+        {
+            return Err(EncryptionError::Logged { path: path.clone() }.into());
+        }
 
-        *self.database = loaded;
+        let loaded = match &self.ast.source {
+            DumpSource::File(path) => {
+                let reader = encryption::open_snapshot(
+                    self.database.storage(),
+                    path,
+                    self.ast.passphrase.as_deref(),
+                )?;
+                dump_format::decode_from(reader, path)?
+            }
+            DumpSource::Bytes(bytes) => {
+                dump_format::decode_from(Cursor::new(bytes.as_slice()), "the inline snapshot")?
+            }
+        };
+        self.database.replace(loaded);
         Ok(ExecutionResult::Success)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Arc};
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{
+            DataValue, DatabaseError, EncryptionError, InMemoryStorage, StorageBackend,
+            TableStorage,
+        },
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_dump(name: &str, encrypted_with: Option<&str>) -> (Database<i64>, String) {
+        let mut db = Database::<i64>::default();
+        execute(&mut db, "CREATE users KEY id FIELDS id: INT, name: STRING;").unwrap();
+        execute(&mut db, "INSERT id = 1, name = \"Alice\" INTO users;").unwrap();
+
+        let path = std::env::temp_dir().join(format!("{name}_{}.bin", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        let query = match encrypted_with {
+            Some(passphrase) => format!("DUMP_TO \"{path}\" ENCRYPTED WITH \"{passphrase}\";"),
+            None => format!("DUMP_TO \"{path}\";"),
+        };
+        execute(&mut db, &query).unwrap();
+        (db, path)
+    }
+
+    #[test]
+    fn test_encrypted_dump_round_trip() {
+        let (db, path) = setup_dump("load_from_encrypted", Some("s3cret"));
+        let bytes = fs::read(&path).unwrap();
+        assert!(encryption::is_encrypted(&bytes));
+        assert!(!bytes.windows(5).any(|window| window == b"Alice"));
+
+        let mut loaded = Database::<i64>::default();
+        execute(
+            &mut loaded,
+            &format!("LOAD_FROM \"{path}\" WITH \"s3cret\";"),
+        )
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(
+            loaded.iter_history().collect::<Vec<_>>(),
+            db.iter_history().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_dump_and_load_through_storage() {
+        let storage = InMemoryStorage::default();
+        let mut db = Database::<i64>::with_storage(Arc::new(storage.clone()));
+        execute(&mut db, "CREATE users KEY id FIELDS id: INT, name: STRING;").unwrap();
+        execute(&mut db, "INSERT id = 1, name = \"Alice\" INTO users;").unwrap();
+        execute(&mut db, "DUMP_TO \"memory/db.bin\";").unwrap();
+        assert!(!Path::new("memory/db.bin").exists());
+
+        let mut loaded = Database::<i64>::with_storage(Arc::new(storage.clone()));
+        execute(&mut loaded, "LOAD_FROM \"memory/db.bin\";").unwrap();
+        assert_eq!(loaded.tables["users"].rows, db.tables["users"].rows);
+
+        execute(&mut loaded, "EXPORT_JSON \"memory/db.json\";").unwrap();
+        assert!(storage.read(Path::new("memory/db.json")).is_ok());
+        assert!(matches!(
+            execute(
+                &mut Database::<i64>::default(),
+                "LOAD_FROM \"memory/db.bin\";"
+            ),
+            Err(DatabaseError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_dump_and_load_disk_table() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING STORAGE DISK;",
+            "GENERATE 50 INTO users SEED 7;",
+            "UPDATE users SET name = \"Alice\" WHERE id = 1;",
+            "DELETE 2 FROM users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        let Ok(ExecutionResult::Data(records)) =
+            execute(&mut db, "SELECT name FROM users WHERE id < 3;")
+        else {
+            panic!("Expected Data");
+        };
+        assert_eq!(
+            *records[0].fields["name"],
+            DataValue::String("Alice".into())
+        );
+        assert_eq!(records.len(), 1);
+
+        let path = std::env::temp_dir().join(format!("load_from_disk_{}.bin", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        execute(&mut db, &format!("DUMP_TO \"{path}\";")).unwrap();
+
+        let mut loaded = Database::<i64>::default();
+        execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.tables["users"].rows.storage(), TableStorage::Disk);
+        assert_eq!(loaded.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(loaded.tables["users"].rows.len(), 49);
+    }
+
+    #[test]
+    fn test_load_encrypted_dump_fails() {
+        let (_, path) = setup_dump("load_from_wrong_key", Some("s3cret"));
+        let mut loaded = Database::<i64>::default();
+
+        assert!(matches!(
+            execute(
+                &mut loaded,
+                &format!("LOAD_FROM \"{path}\" WITH \"secret\";")
+            ),
+            Err(DatabaseError::Encryption(
+                EncryptionError::Decryption { .. }
+            ))
+        ));
+        assert!(matches!(
+            execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")),
+            Err(DatabaseError::Encryption(
+                EncryptionError::PassphraseRequired { .. }
+            ))
+        ));
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            execute(
+                &mut loaded,
+                &format!("LOAD_FROM \"{path}\" WITH \"s3cret\";")
+            ),
+            Err(DatabaseError::Encryption(
+                EncryptionError::Decryption { .. }
+            ))
+        ));
+
+        fs::write(&path, &bytes[..20]).unwrap();
+        assert!(matches!(
+            execute(
+                &mut loaded,
+                &format!("LOAD_FROM \"{path}\" WITH \"s3cret\";")
+            ),
+            Err(DatabaseError::Encryption(EncryptionError::Truncated { .. }))
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.tables.is_empty());
+    }
+
+    #[test]
+    fn test_load_plain_dump_with_passphrase_fails() {
+        let (db, path) = setup_dump("load_from_plain", None);
+        let mut loaded = Database::<i64>::default();
+
+        assert!(matches!(
+            execute(
+                &mut loaded,
+                &format!("LOAD_FROM \"{path}\" WITH \"s3cret\";")
+            ),
+            Err(DatabaseError::Encryption(
+                EncryptionError::NotEncrypted { .. }
+            ))
+        ));
+        execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.tables["users"].rows, db.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_load_legacy_dump() {
+        let (db, path) = setup_dump("load_from_legacy", None);
+        let legacy = dump_format::encode_legacy(&db);
+        assert_ne!(fs::read(&path).unwrap(), legacy);
+        fs::write(&path, &legacy).unwrap();
+
+        let mut loaded = Database::<i64>::default();
+        execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.tables["users"].rows, db.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_load_truncated_dump_fails() {
+        let (_, path) = setup_dump("load_from_truncated", None);
+        let bytes = fs::read(&path).unwrap();
+        let mut loaded = Database::<i64>::default();
+        execute(&mut loaded, "CREATE orders KEY id FIELDS id: INT;").unwrap();
+
+        for len in [bytes.len() - 40, 10, 0] {
+            fs::write(&path, &bytes[..len]).unwrap();
+            assert!(matches!(
+                execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")),
+                Err(DatabaseError::Decode(_))
+            ));
+        }
+        fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(
+            execute(&mut loaded, &format!("LOAD_FROM \"{path}\";")),
+            Err(DatabaseError::ChecksumMismatch { .. })
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.tables.contains_key("orders"));
+    }
+
+    #[test]
+    fn test_load_unsupported_dump_version_fails() {
+        let (_, path) = setup_dump("load_from_version", None);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[8..12].copy_from_slice(&(dump_format::CURRENT_VERSION + 1).to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+
+        let mut loaded = Database::<i64>::default();
+        let result = execute(&mut loaded, &format!("LOAD_FROM \"{path}\";"));
+        fs::remove_file(&path).unwrap();
+        match result {
+            Err(DatabaseError::UnsupportedDumpVersion {
+                version, supported, ..
+            }) => {
+                assert_eq!(version, dump_format::CURRENT_VERSION + 1);
+                assert_eq!(supported, dump_format::CURRENT_VERSION);
+            }
+            other => panic!("Expected UnsupportedDumpVersion error, got {other:?}"),
+        }
+        assert!(loaded.tables.is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+use std::{borrow::Cow, fmt::Write, io::Read, path::Path};
+
+use crate::{
+    DatabaseResult,
+    ast::{ConflictAction, DumpSource, LoadTableStmt},
+    core::{Database, DatabaseKey, LoadTableError, Table, WriteAheadLog},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::{check_constraints, dump_format};
+
+/// Represents an executable `LOAD_TABLE` command.
+///
+/// This struct couples the parsed `LoadTableStmt` (the "what") with a
+/// mutable reference to the `Database` (the "where") the loaded table
+/// is added to.
+pub struct LoadTable<'a, K: DatabaseKey> {
+    /// A mutable reference to the database instance receiving the table.
+    pub database: &'a mut Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `LOAD_TABLE` statement.
+    pub ast: LoadTableStmt,
+    /// The write-ahead log recording the loaded dump, if the database is logged.
+    pub wal: Option<&'a mut WriteAheadLog>,
+}
+
+impl<'a, K: DatabaseKey> Execute for LoadTable<'a, K> {
+    /// Executes the `LOAD_TABLE` command.
+    ///
+    /// The table written by `DUMP_TABLE` is read from the storage of the database
+    /// and added to it under its original name. If a table with that name exists, `ON CONFLICT REPLACE`
+    /// replaces it with the loaded table, and `ON CONFLICT UPDATE` merges the loaded
+    /// records into it: a loaded record replaces the existing record with the same
+    /// key, and the other existing records are kept. A merge keeps the `DEFAULT`
+    /// values, `ENUM` variants, `CHECK` conditions and indexes of the existing
+    /// table, and inserts nothing if any loaded record violates them.
+    ///
+    /// With a log, the dump is recorded in it as a `LOAD_TABLE` from a bytes
+    /// literal before it is loaded, so that replaying the log does not depend on
+    /// the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * `DatabaseError::Io` - The file cannot be opened or read, or the log cannot
+    ///   be written.
+    /// * `LoadTableError::NotATableDump` - The file was not written by `DUMP_TABLE`.
+    /// * `DatabaseError::UnsupportedDumpVersion` - The dump was written with a
+    ///   newer layout.
+    /// * `DatabaseError::Decode` - `bincode` fails to deserialize the table.
+    /// * `LoadTableError::TableAlreadyExists` - The table exists and there is no
+    ///   `ON CONFLICT` clause.
+    /// * `LoadTableError::SchemaMismatch` - With `ON CONFLICT UPDATE`, the key field
+    ///   or the schema of the loaded table differs from those of the existing one.
+    /// * `DatabaseError::InvalidEnumValue` or `DatabaseError::ConstraintViolation` -
+    ///   With `ON CONFLICT UPDATE`, a loaded record violates the existing table.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ExecutionResult::RowsAffected(count))`, the number of loaded records.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let (dump, path) = match &self.ast.source {
+            DumpSource::File(path) => {
+                let mut dump = Vec::new();
+                let mut reader = self.database.storage().open(Path::new(path))?;
+                reader.read_to_end(&mut dump)?;
+                (Cow::Owned(dump), path.as_str())
+            }
+            DumpSource::Bytes(dump) => (Cow::Borrowed(dump.as_slice()), "the inline dump"),
+        };
+        let table: Table<K> = dump_format::decode_table_from(dump.as_ref(), path)?;
+        let loaded = table.rows.len();
+        if let Some(wal) = self.wal.as_deref_mut() {
+            wal.append(&logged_query(&dump, self.ast.on_conflict))?;
+        }
+
+        let Some(existing) = self.database.tables.get_mut(&table.name) else {
+            self.database.add_table(table)?;
+            return Ok(ExecutionResult::RowsAffected(loaded));
+        };
+
+        match self.ast.on_conflict {
+            None => {
+                return Err(LoadTableError::TableAlreadyExists { name: table.name }.into());
+            }
+            Some(ConflictAction::Replace) => *existing = table,
+            Some(ConflictAction::Update) => merge(existing, table)?,
+        }
+
+        Ok(ExecutionResult::RowsAffected(loaded))
+    }
+}
+
+/// Returns the `LOAD_TABLE` query loading `dump` from a bytes literal, with the
+/// `ON CONFLICT` clause of `on_conflict`.
+fn logged_query(dump: &[u8], on_conflict: Option<ConflictAction>) -> String {
+    let mut query = String::with_capacity(2 * dump.len() + 48);
+    query.push_str("LOAD_TABLE FROM 0x");
+    for byte in dump {
+        let _ = write!(query, "{byte:02x}");
+    }
+    query.push_str(match on_conflict {
+        Some(ConflictAction::Replace) => " ON CONFLICT REPLACE;",
+        Some(ConflictAction::Update) => " ON CONFLICT UPDATE;",
+        None => ";",
+    });
+    query
+}
+
+/// Inserts the records of `loaded` into `existing`, replacing those with the
+/// same key, once every record has been validated against `existing`.
+fn merge<K: DatabaseKey>(existing: &mut Table<K>, loaded: Table<K>) -> DatabaseResult<()> {
+    if existing.key_field != loaded.key_field || existing.schema != loaded.schema {
+        return Err(LoadTableError::SchemaMismatch { name: loaded.name }.into());
+    }
+
+    for record in loaded.rows.values() {
+        for (field, value) in &record.fields {
+            existing.check_value(field, value)?;
+        }
+        check_constraints(existing, &record)?;
+    }
+
+    for (key, record) in loaded.rows {
+        existing.insert_row(key, record)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DataValue, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{name}_{}.bin", std::process::id()));
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    fn setup_db(names: &[(i64, &str)]) -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT DEFAULT 18 CHECK (age >= 0);",
+        )
+        .unwrap();
+        execute(&mut db, "CREATE_INDEX users_name ON users (name);").unwrap();
+        for (id, name) in names {
+            execute(
+                &mut db,
+                &format!("INSERT id = {id}, name = \"{name}\" INTO users;"),
+            )
+            .unwrap();
+        }
+        db
+    }
+
+    fn name(db: &Database<i64>, id: i64) -> Arc<DataValue> {
+        db.tables["users"].rows.get(&id).unwrap().fields["name"].clone()
+    }
+
+    #[test]
+    fn test_dump_and_load_table() {
+        let path = temp_path("load_table");
+        let mut source = setup_db(&[(1, "Alice"), (2, "Bob")]);
+        execute(&mut source, "CREATE orders KEY id FIELDS id: INT;").unwrap();
+        execute(&mut source, &format!("DUMP_TABLE users TO \"{path}\";")).unwrap();
+
+        let mut db = Database::<i64>::default();
+        execute(&mut db, "CREATE orders KEY id FIELDS id: INT;").unwrap();
+        let result = execute(&mut db, &format!("LOAD_TABLE FROM \"{path}\";")).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, ExecutionResult::RowsAffected(2)));
+        assert_eq!(db.tables.len(), 2);
+        let users = &db.tables["users"];
+        assert_eq!(users.rows, source.tables["users"].rows);
+        assert_eq!(users.indexes["users_name"].len(), 2);
+        assert_eq!(users.checks.len(), 1);
+        execute(&mut db, "INSERT id = 3, name = \"Carol\" INTO users;").unwrap();
+        assert_eq!(
+            *db.tables["users"].rows.get(&3).unwrap().fields["age"],
+            DataValue::Int(18)
+        );
+    }
+
+    #[test]
+    fn test_load_table_on_conflict() {
+        let path = temp_path("load_table_conflict");
+        let mut source = setup_db(&[(1, "Alice"), (2, "Bob")]);
+        execute(&mut source, &format!("DUMP_TABLE users TO \"{path}\";")).unwrap();
+
+        let mut db = setup_db(&[(2, "Robert"), (3, "Carol")]);
+        let load = format!("LOAD_TABLE FROM \"{path}\"");
+        match execute(&mut db, &format!("{load};")) {
+            Err(DatabaseError::LoadTable(LoadTableError::TableAlreadyExists { name })) => {
+                assert_eq!(name, "users");
+            }
+            other => panic!("Expected TableAlreadyExists error, got {other:?}"),
+        }
+        assert_eq!(*name(&db, 2), DataValue::String("Robert".into()));
+
+        execute(&mut db, &format!("{load} ON CONFLICT UPDATE;")).unwrap();
+        let users = &db.tables["users"];
+        assert_eq!(users.rows.len(), 3);
+        assert_eq!(*name(&db, 2), DataValue::String("Bob".into()));
+        assert_eq!(*name(&db, 3), DataValue::String("Carol".into()));
+        assert_eq!(users.indexes["users_name"].len(), 3);
+        assert!(
+            users.indexes["users_name"]
+                .get(&DataValue::String("Robert".into()))
+                .is_empty()
+        );
+
+        execute(&mut db, &format!("{load} ON CONFLICT REPLACE;")).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(db.tables["users"].rows, source.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_load_table_fails() {
+        let path = temp_path("load_table_fails");
+        let mut source = setup_db(&[(1, "Alice")]);
+        execute(&mut source, &format!("DUMP_TABLE users TO \"{path}\";")).unwrap();
+        let load = format!("LOAD_TABLE FROM \"{path}\" ON CONFLICT UPDATE;");
+
+        let mut db = Database::<i64>::default();
+        execute(&mut db, "CREATE users KEY id FIELDS id: INT, name: STRING;").unwrap();
+        assert!(matches!(
+            execute(&mut db, &load),
+            Err(DatabaseError::LoadTable(
+                LoadTableError::SchemaMismatch { .. }
+            ))
+        ));
+
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT CHECK (age > 30);",
+        )
+        .unwrap();
+        execute(
+            &mut db,
+            "INSERT id = 2, name = \"Bob\", age = 40 INTO users;",
+        )
+        .unwrap();
+        assert!(matches!(
+            execute(&mut db, &load),
+            Err(DatabaseError::ConstraintViolation { .. })
+        ));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+
+        execute(&mut source, &format!("DUMP_TO \"{path}\";")).unwrap();
+        assert!(matches!(
+            execute(&mut db, &load),
+            Err(DatabaseError::LoadTable(
+                LoadTableError::NotATableDump { .. }
+            ))
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            execute(&mut db, "DUMP_TABLE orders TO \"orders.bin\";"),
+            Err(DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
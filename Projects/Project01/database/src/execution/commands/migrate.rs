@@ -0,0 +1,408 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    DatabaseResult, QueryParser,
+    ast::MigrateStmt,
+    core::{DataType, DataValue, Database, DatabaseKey, MigrationError, Uuid, WriteAheadLog},
+    execution::{
+        Execute, ExecutionResult, execute_logged, parse_cached, transaction::append_committed,
+    },
+};
+
+/// The system table recording the applied migrations, keyed by their version.
+const MIGRATIONS_TABLE: &str = "__migrations";
+
+/// Represents an executable `MIGRATE` command.
+///
+/// This struct couples the parsed `MigrateStmt` (the "what") with a
+/// mutable reference to the `Database` (the "where") that the migration
+/// scripts are applied to.
+pub struct Migrate<'a, K: DatabaseKey> {
+    /// A mutable reference to the database instance, which will be modified
+    /// by the commands within the scripts.
+    pub database: &'a mut Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `MIGRATE` statement,
+    /// which contains the directory path.
+    pub ast: MigrateStmt,
+    /// The write-ahead log recording the queries of the applied scripts, if the
+    /// database is logged.
+    pub wal: Option<&'a mut WriteAheadLog>,
+}
+
+/// A migration script found in the migrations directory.
+struct Script {
+    /// The version, given by the leading digits of the file name.
+    version: i64,
+    /// The file name, recorded in the migration table.
+    file: String,
+    /// The path of the file.
+    path: PathBuf,
+}
+
+impl<'a, K: DatabaseKey> Execute for Migrate<'a, K> {
+    /// Executes the `MIGRATE` command.
+    ///
+    /// Every `.sql` file of the directory is a migration script whose name starts
+    /// with its version number (e.g., `001_create_users.sql`); other files are
+    /// ignored. The scripts that have not been applied yet are executed in the
+    /// order of their versions, as by `READ_FROM`, and each applied version is
+    /// recorded in the `__migrations` system table. As it is a table, the record
+    /// of the applied migrations is saved with the rest of the database by
+    /// `DUMP_TO`, so migrating a loaded dump applies only the newer scripts.
+    ///
+    /// With a log, the queries of each applied script, and the one recording it
+    /// in `__migrations`, are recorded in it as a transaction once the script has
+    /// succeeded, so that replaying the log does not depend on the scripts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, applying nothing, if the scripts cannot be listed:
+    /// * `DatabaseError::Io` - The directory cannot be read.
+    /// * `MigrationError::InvalidFileName` - A script does not start with a version number.
+    /// * `MigrationError::DuplicateVersion` - Two scripts have the same version.
+    ///
+    /// Returns `MigrationError::Failed` if a query of a script fails. The changes
+    /// made by that script are undone, while the migrations applied before it are
+    /// kept.
+    ///
+    /// Returns `DatabaseError::Io`, keeping the migrations applied so far, if the
+    /// snapshot taken before a script cannot copy an on-disk table.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ExecutionResult::Messages(log))` listing the applied migrations.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let scripts = list_scripts(&self.ast.path)?;
+
+        let mut log_messages = vec![format!(
+            "Migrating with the scripts in '{}'...",
+            self.ast.path
+        )];
+
+        if !self.database.tables.contains_key(MIGRATIONS_TABLE) {
+            let query = format!(
+                "CREATE {MIGRATIONS_TABLE} KEY version FIELDS version: {}, script: STRING;",
+                K::key_type()
+            );
+            execute_logged(
+                self.database,
+                parse_cached(&query)?,
+                self.wal.as_deref_mut(),
+            )?;
+        }
+
+        let mut applied = 0;
+        for script in &scripts {
+            if self.is_applied(script.version) {
+                continue;
+            }
+
+            let snapshot = self.database.try_clone()?;
+            match self.apply(script) {
+                Ok(count) => {
+                    applied += 1;
+                    log_messages.push(format!(
+                        "[Migration {}] Applied '{}' ({count} quer{}).",
+                        script.version,
+                        script.file,
+                        if count == 1 { "y" } else { "ies" }
+                    ));
+                }
+                Err(error) => {
+                    *self.database = snapshot;
+                    return Err(MigrationError::Failed {
+                        file: script.file.clone(),
+                        source: Box::new(error),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        log_messages.push(format!(
+            "Applied {applied} migration(s), {} already applied.",
+            scripts.len() - applied
+        ));
+        Ok(ExecutionResult::Messages(log_messages))
+    }
+}
+
+impl<'a, K: DatabaseKey> Migrate<'a, K> {
+    /// Returns `true` if the migration `version` is recorded in the migration table.
+    fn is_applied(&self, version: i64) -> bool {
+        let key = K::from_datavalue(&version_key::<K>(version)).unwrap();
+        self.database
+            .tables
+            .get(MIGRATIONS_TABLE)
+            .is_some_and(|table| table.rows.contains_key(&key))
+    }
+
+    /// Executes the queries of `script` and records it in the migration table.
+    ///
+    /// # Returns
+    /// The number of executed queries of the script.
+    fn apply(&mut self, script: &Script) -> DatabaseResult<usize> {
+        let file_content = fs::read_to_string(&script.path)?;
+        let commands = QueryParser.parse_script(&file_content)?;
+        let count = commands.len();
+
+        // the queries are only logged once the whole script has succeeded
+        let mut log = match self.wal {
+            Some(_) => Some(WriteAheadLog::for_transaction()?),
+            None => None,
+        };
+        for command in commands {
+            execute_logged(self.database, command, log.as_mut())?;
+        }
+
+        let query = format!(
+            "INSERT version = {}, script = {} INTO {MIGRATIONS_TABLE};",
+            version_key::<K>(script.version),
+            DataValue::String(script.file.clone())
+        );
+        execute_logged(self.database, parse_cached(&query)?, log.as_mut())?;
+
+        if let (Some(wal), Some(log)) = (self.wal.as_deref_mut(), log) {
+            append_committed(wal, &log.entries()?)?;
+        }
+        Ok(count)
+    }
+}
+
+/// Lists the migration scripts of the directory `path`, ordered by version.
+///
+/// # Errors
+/// Returns `DatabaseError::Io`, `MigrationError::InvalidFileName` or
+/// `MigrationError::DuplicateVersion`, as described in [`Migrate::execute`].
+fn list_scripts(path: &str) -> DatabaseResult<Vec<Script>> {
+    let mut scripts = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "sql") {
+            continue;
+        }
+
+        let file = path.file_name().unwrap().to_string_lossy().into_owned();
+        let digits = file.bytes().take_while(u8::is_ascii_digit).count();
+        let version = file[..digits]
+            .parse()
+            .map_err(|_| MigrationError::InvalidFileName { file: file.clone() })?;
+        scripts.push(Script {
+            version,
+            file,
+            path,
+        });
+    }
+
+    scripts.sort_by(|a, b| (a.version, &a.file).cmp(&(b.version, &b.file)));
+    if let Some([first, second]) = scripts
+        .array_windows()
+        .find(|[first, second]| first.version == second.version)
+    {
+        return Err(MigrationError::DuplicateVersion {
+            version: first.version,
+            first: first.file.clone(),
+            second: second.file.clone(),
+        }
+        .into());
+    }
+
+    Ok(scripts)
+}
+
+/// Returns the key of the migration `version` in the migration table, whose key
+/// column has the type of the keys of the database.
+fn version_key<K: DatabaseKey>(version: i64) -> DataValue {
+    match K::key_type() {
+        DataType::String => DataValue::String(version.to_string()),
+        DataType::Uuid => DataValue::Uuid(Uuid::from_bytes((version as u128).to_be_bytes())),
+        _ => DataValue::Int(version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QueryParser, core::DatabaseError, execution::build_execute_command};
+
+    fn execute<K: DatabaseKey>(
+        db: &mut Database<K>,
+        query: &str,
+    ) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    /// Creates an empty migrations directory, returning its path.
+    fn setup_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn migrate<K: DatabaseKey>(
+        db: &mut Database<K>,
+        dir: &std::path::Path,
+    ) -> DatabaseResult<Vec<String>> {
+        let path = dir.to_string_lossy().replace('\\', "/");
+        match execute(db, &format!("MIGRATE \"{path}\";"))? {
+            ExecutionResult::Messages(messages) => Ok(messages),
+            other => panic!("Expected Messages, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_migrate_applies_pending_scripts() {
+        let dir = setup_dir("migrate_pending");
+        fs::write(
+            dir.join("2_add_users.sql"),
+            "INSERT id = 1, name = \"Alice\" INTO users;\nINSERT id = 2, name = \"Bob\" INTO users;",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("001_create_users.sql"),
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not a migration").unwrap();
+
+        let mut db = Database::<i64>::default();
+        let messages = migrate(&mut db, &dir).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages[1],
+            "[Migration 1] Applied '001_create_users.sql' (1 query)."
+        );
+        assert_eq!(
+            messages[2],
+            "[Migration 2] Applied '2_add_users.sql' (2 queries)."
+        );
+        assert_eq!(db.tables["users"].rows.len(), 2);
+        assert_eq!(db.user_tables().count(), 1);
+
+        fs::write(
+            dir.join("3_add_carol.sql"),
+            "INSERT id = 3, name = \"Carol\" INTO users;",
+        )
+        .unwrap();
+        let messages = migrate(&mut db, &dir).unwrap();
+        assert_eq!(
+            messages[1],
+            "[Migration 3] Applied '3_add_carol.sql' (1 query)."
+        );
+        assert_eq!(messages[2], "Applied 1 migration(s), 2 already applied.");
+        assert_eq!(db.tables["users"].rows.len(), 3);
+
+        let messages = migrate(&mut db, &dir).unwrap();
+        assert_eq!(messages[1], "Applied 0 migration(s), 3 already applied.");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let migrations = &db.tables[MIGRATIONS_TABLE];
+        assert_eq!(migrations.rows.len(), 3);
+        assert_eq!(
+            *migrations.rows.get(&2).unwrap().fields["script"],
+            DataValue::String("2_add_users.sql".into())
+        );
+    }
+
+    #[test]
+    fn test_migrate_undoes_failed_script() {
+        let dir = setup_dir("migrate_failed");
+        fs::write(
+            dir.join("1_create.sql"),
+            "CREATE users KEY id FIELDS id: INT;",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("2_broken.sql"),
+            "CREATE orders KEY id FIELDS id: INT;\nINSERT id = 1 INTO users;\nINSERT id = 1 INTO users;",
+        )
+        .unwrap();
+
+        let mut db = Database::<i64>::default();
+        match migrate(&mut db, &dir) {
+            Err(DatabaseError::Migration(MigrationError::Failed { file, source })) => {
+                assert_eq!(file, "2_broken.sql");
+                assert!(matches!(*source, DatabaseError::Insert(_)));
+            }
+            other => panic!("Expected Failed error, got {other:?}"),
+        }
+        assert!(db.tables["users"].rows.is_empty());
+        assert!(!db.tables.contains_key("orders"));
+        assert_eq!(db.tables[MIGRATIONS_TABLE].rows.len(), 1);
+        assert!(!db.iter_history().any(|query| query.contains("orders")));
+
+        fs::write(dir.join("2_broken.sql"), "INSERT id = 1 INTO users;").unwrap();
+        migrate(&mut db, &dir).unwrap();
+        assert_eq!(db.tables["users"].rows.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_rejects_invalid_scripts() {
+        let dir = setup_dir("migrate_invalid");
+        fs::write(
+            dir.join("1_create.sql"),
+            "CREATE users KEY id FIELDS id: INT;",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("create_orders.sql"),
+            "CREATE orders KEY id FIELDS id: INT;",
+        )
+        .unwrap();
+
+        let mut db = Database::<i64>::default();
+        match migrate(&mut db, &dir) {
+            Err(DatabaseError::Migration(MigrationError::InvalidFileName { file })) => {
+                assert_eq!(file, "create_orders.sql");
+            }
+            other => panic!("Expected InvalidFileName error, got {other:?}"),
+        }
+
+        fs::rename(
+            dir.join("create_orders.sql"),
+            dir.join("01_create_orders.sql"),
+        )
+        .unwrap();
+        match migrate(&mut db, &dir) {
+            Err(DatabaseError::Migration(MigrationError::DuplicateVersion {
+                version,
+                first,
+                second,
+            })) => {
+                assert_eq!(version, 1);
+                assert_eq!(first, "01_create_orders.sql");
+                assert_eq!(second, "1_create.sql");
+            }
+            other => panic!("Expected DuplicateVersion error, got {other:?}"),
+        }
+        assert!(db.tables.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(migrate(&mut db, &dir), Err(DatabaseError::Io(_))));
+    }
+
+    #[test]
+    fn test_migrate_with_string_keys() {
+        let dir = setup_dir("migrate_string_keys");
+        fs::write(
+            dir.join("1_create.sql"),
+            "CREATE users KEY id FIELDS id: STRING;",
+        )
+        .unwrap();
+
+        let mut db = Database::<String>::default();
+        migrate(&mut db, &dir).unwrap();
+        let messages = migrate(&mut db, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(messages[1], "Applied 0 migration(s), 1 already applied.");
+        assert!(
+            db.tables[MIGRATIONS_TABLE]
+                .rows
+                .contains_key(&"1".to_string())
+        );
+    }
+}
@@ -3,8 +3,8 @@ use std::fs;
 use crate::{
     DatabaseResult, QueryParser,
     ast::ReadFromStmt,
-    core::{Database, DatabaseKey},
-    execution::{Execute, ExecutionResult, build_execute_command},
+    core::{Database, DatabaseKey, WriteAheadLog},
+    execution::{Execute, ExecutionResult, TransactionManager},
 };
 
 /// Represents an executable `READ_FROM` command.
@@ -19,16 +19,24 @@ pub struct ReadFrom<'a, K: DatabaseKey> {
     /// The parsed AST (Abstract Syntax Tree) for the `READ_FROM` statement,
     /// which contains the file path.
     pub ast: ReadFromStmt,
+    /// The write-ahead log recording the queries of the script that modify the
+    /// database, if the database is logged.
+    pub wal: Option<&'a mut WriteAheadLog>,
 }
 
 impl<'a, K: DatabaseKey> Execute for ReadFrom<'a, K> {
     /// Executes the `READ_FROM` command.
     ///
+    /// The whole script is parsed before any of its queries is executed. The
+    /// script may hold transactions; one that it leaves open, or that a failing
+    /// query stops, is rolled back. With a log, the queries are recorded in it as
+    /// they are executed by `execute_logged`, and a transaction once it is committed.
+    ///
     /// # Errors
     ///
-    /// This function will stop and return an error immediately if *any*
-    /// operation in the script fails (e.g., file I/O, parsing, validation,
-    /// or execution of a sub-command).
+    /// This function will return an error, executing nothing, if the script cannot
+    /// be read or parsed, and stop and return an error immediately if *any* of
+    /// its queries fails (e.g., validation or execution of a sub-command).
     ///
     /// # Returns
     ///
@@ -37,27 +45,29 @@ impl<'a, K: DatabaseKey> Execute for ReadFrom<'a, K> {
     fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
         let path = &self.ast.path;
         let file_content = fs::read_to_string(path)?;
-        let queries = file_content
-            .split_inclusive(";")
-            .map(|q| q.trim())
-            .filter(|q| !q.is_empty());
+        let statements = QueryParser.parse_statements(&file_content)?;
 
-        let parser = QueryParser;
+        let mut transactions = TransactionManager::default();
 
         let mut log_messages = Vec::new();
 
         log_messages.push(format!("Reading queries from '{path}'..."));
 
-        for (index, query) in queries.enumerate() {
+        for (index, (query, ast)) in statements.into_iter().enumerate() {
             log_messages.push(format!("[Query {}] Executing: {query}...", index + 1));
 
-            let ast = parser.parse_query(query)?;
-
-            let mut executable_command = build_execute_command(self.database, ast)?;
-            let result = executable_command.execute()?;
+            let result = transactions
+                .execute(self.database, ast, self.wal.as_deref_mut())
+                .inspect_err(|_| {
+                    transactions.abort(self.database);
+                })?;
             log_messages.push(format!("[Query {}] ...Success: {result:?}", index + 1));
         }
 
+        if transactions.abort(self.database) {
+            log_messages
+                .push("The transaction left open by the script was rolled back.".to_string());
+        }
         log_messages.push("Script executed successfully.".to_string());
 
         Ok(ExecutionResult::Messages(log_messages))
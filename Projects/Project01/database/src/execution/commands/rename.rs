@@ -0,0 +1,91 @@
+use crate::{
+    DatabaseResult,
+    ast::RenameStmt,
+    core::{Database, DatabaseKey},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `RENAME` command.
+///
+/// This struct couples the parsed `RenameStmt` (the "what") with the
+/// mutable reference to the `Database` (the "where") whose table map is updated.
+pub struct Rename<'a, K: DatabaseKey> {
+    /// A mutable reference to the database instance containing the table.
+    pub database: &'a mut Database<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `RENAME` statement.
+    pub ast: RenameStmt,
+}
+
+impl<'a, K: DatabaseKey> Execute for Rename<'a, K> {
+    /// Executes the `RENAME` command.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::TableNotFound` - The table to rename does not exist.
+    /// * `RenameError::TableAlreadyExists` - Another table already has the new name.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        self.database
+            .rename_table(&self.ast.table_name, &self.ast.new_name)?;
+
+        Ok(ExecutionResult::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DatabaseError, RenameError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_db() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "CREATE orders KEY id FIELDS id: INT;",
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_execute_rename() {
+        let mut db = setup_db();
+
+        let result = execute(&mut db, "RENAME users TO customers;").unwrap();
+        assert!(matches!(result, ExecutionResult::Success));
+        assert!(!db.tables.contains_key("users"));
+        assert_eq!(db.tables["customers"].name, "customers");
+        assert_eq!(db.tables["customers"].rows.len(), 1);
+
+        execute(&mut db, "INSERT id = 2, name = \"Bob\" INTO customers;").unwrap();
+    }
+
+    #[test]
+    fn test_execute_fail_rename() {
+        let mut db = setup_db();
+
+        match execute(&mut db, "RENAME users TO orders;").err().unwrap() {
+            DatabaseError::Rename(RenameError::TableAlreadyExists { from, to }) => {
+                assert_eq!(from, "users");
+                assert_eq!(to, "orders");
+            }
+            e => panic!("Expected TableAlreadyExists error, got {e:?}"),
+        }
+        match execute(&mut db, "RENAME products TO items;").err().unwrap() {
+            DatabaseError::TableNotFound { name, .. } => assert_eq!(name, "products"),
+            e => panic!("Expected TableNotFound error, got {e:?}"),
+        }
+        assert_eq!(db.tables.len(), 2);
+        assert_eq!(db.tables["users"].name, "users");
+    }
+}
@@ -1,10 +1,17 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use crate::{
     DatabaseResult,
-    ast::{Condition, OrderDirection, SelectStmt},
-    core::{DatabaseError, DatabaseKey, Record, SelectError, Table},
-    execution::{Execute, ExecutionResult},
+    ast::{
+        Aggregate, AggregateFunction, ArithmeticOperator, Condition, Expression, Operator,
+        OrderDirection, Projection, ScalarFunction, SelectStmt,
+    },
+    core::{DataType, DataValue, DatabaseError, DatabaseKey, Record, SelectError, Table},
+    execution::{Execute, ExecuteStream, ExecutionResult, RecordStream, planner},
 };
 
 /// Represents an executable `SELECT` command.
@@ -31,7 +38,7 @@ impl<'a, K: DatabaseKey> Select<'a, K> {
     ///   exist in the record.
     /// * `DatabaseError::TypeMismatch` if a comparison is attempted between
     ///   incompatible types (e.g., `String` and `Int`).
-    fn evaluate_condition(
+    pub(super) fn evaluate_condition(
         record: &Record,
         condition: &Condition,
         table_name: &str,
@@ -45,124 +52,506 @@ impl<'a, K: DatabaseKey> Select<'a, K> {
                 Ok(Self::evaluate_condition(record, left, table_name)?
                     || Self::evaluate_condition(record, right, table_name)?)
             }
-            Condition::Comparison(comparison) => {
-                let record_value = record.fields.get(&comparison.field).ok_or_else(|| {
-                    DatabaseError::FieldNotFound {
-                        table: table_name.to_string(),
-                        field: comparison.field.clone(),
+            Condition::Not(condition) => {
+                Ok(!Self::evaluate_condition(record, condition, table_name)?)
+            }
+            Condition::Comparison(comparison) => Self::compare_field(
+                record,
+                &comparison.field,
+                &comparison.op,
+                &comparison.value,
+                table_name,
+            ),
+            Condition::In { field, values } => {
+                // every value is compared, so a mistyped one is reported even after a match
+                values.iter().try_fold(false, |found, value| {
+                    let equal =
+                        Self::compare_field(record, field, &Operator::Eq, value, table_name)?;
+                    Ok(found || equal)
+                })
+            }
+        }
+    }
+
+    /// Compares the value of `field` in `record` against `value` using `op`.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::FieldNotFound` if the field does not exist in the record.
+    /// * `DatabaseError::TypeMismatch` if `value` is not of the field's type.
+    fn compare_field(
+        record: &Record,
+        field: &str,
+        op: &Operator,
+        value: &DataValue,
+        table_name: &str,
+    ) -> DatabaseResult<bool> {
+        let record_value = record.fields.get(field).ok_or_else(|| {
+            DatabaseError::field_not_found(
+                table_name,
+                field,
+                record.fields.keys().map(String::as_str),
+            )
+        })?;
+
+        record_value.compare(op, value).map_err(|e| match e {
+            DatabaseError::ComparisonTypeMismatch { expected, found } => {
+                DatabaseError::TypeMismatch {
+                    table: table_name.to_string(),
+                    field: field.to_string(),
+                    expected,
+                    found,
+                }
+            }
+            _ => e,
+        })
+    }
+
+    /// Computes the value of `expression` for a single `Record`.
+    ///
+    /// Arithmetic on two `Int` values yields an `Int`; if either side is a `Float`,
+    /// the other one is promoted and the result is a `Float`.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::FieldNotFound` if a column does not exist in the record.
+    /// * `DatabaseError::TypeMismatch` if a `WHEN` condition compares incompatible types.
+    /// * `SelectError::InvalidArithmetic` if an operand is not a number.
+    /// * `SelectError::InvalidFunctionArgument` if a function gets a value of the wrong type.
+    /// * `SelectError::DivisionByZero` if an `Int` is divided by zero.
+    /// * `SelectError::ArithmeticOverflow` if an `Int` operation overflows.
+    pub(super) fn evaluate_expression(
+        record: &Record,
+        expression: &Expression,
+        table_name: &str,
+    ) -> DatabaseResult<DataValue> {
+        match expression {
+            Expression::Column(name) => record.get(name).cloned().ok_or_else(|| {
+                DatabaseError::field_not_found(
+                    table_name,
+                    name,
+                    record.fields.keys().map(String::as_str),
+                )
+            }),
+            Expression::Literal(value) => Ok(value.clone()),
+            Expression::Binary { left, op, right } => {
+                let left = Self::evaluate_expression(record, left, table_name)?;
+                let right = Self::evaluate_expression(record, right, table_name)?;
+                Self::apply_arithmetic(*op, &left, &right)?.ok_or_else(|| {
+                    SelectError::ArithmeticOverflow {
+                        expression: expression.to_string(),
                     }
-                })?;
-
-                record_value
-                    .compare(&comparison.op, &comparison.value)
-                    .map_err(|e| match e {
-                        DatabaseError::ComparisonTypeMismatch { expected, found } => {
-                            DatabaseError::TypeMismatch {
-                                table: table_name.to_string(),
-                                field: comparison.field.clone(),
-                                expected,
-                                found,
+                    .into()
+                })
+            }
+            Expression::Case {
+                branches,
+                otherwise,
+            } => {
+                for (condition, result) in branches {
+                    if Self::evaluate_condition(record, condition, table_name)? {
+                        return Self::evaluate_expression(record, result, table_name);
+                    }
+                }
+                Self::evaluate_expression(record, otherwise, table_name)
+            }
+            Expression::Function { function, argument } => {
+                let argument = Self::evaluate_expression(record, argument, table_name)?;
+                let value = match (function, &argument) {
+                    (ScalarFunction::Length, DataValue::String(s)) => {
+                        DataValue::Int(s.chars().count() as i64)
+                    }
+                    (ScalarFunction::Length, DataValue::Bytes(b)) => DataValue::Int(b.len() as i64),
+                    (ScalarFunction::Abs, DataValue::Int(v)) => {
+                        DataValue::Int(v.checked_abs().ok_or_else(|| {
+                            SelectError::ArithmeticOverflow {
+                                expression: expression.to_string(),
                             }
+                        })?)
+                    }
+                    (ScalarFunction::Abs, DataValue::Float(v)) => DataValue::Float(v.abs()),
+                    (ScalarFunction::Upper, DataValue::String(s)) => {
+                        DataValue::String(s.to_uppercase())
+                    }
+                    (ScalarFunction::Lower, DataValue::String(s)) => {
+                        DataValue::String(s.to_lowercase())
+                    }
+                    (function, value) => {
+                        return Err(SelectError::InvalidFunctionArgument {
+                            function: *function,
+                            dtype: value.get_type(),
                         }
-                        _ => e,
-                    })
+                        .into());
+                    }
+                };
+                Ok(value)
             }
         }
     }
-}
 
-impl<'a, K: DatabaseKey> Execute for Select<'a, K> {
-    /// Executes the `SELECT` query.
+    /// Applies an arithmetic operator to two evaluated operands.
     ///
-    /// This method performs the full query execution pipeline in the standard
-    /// SQL logical order:
+    /// Returns `Ok(None)` if an `Int` operation overflows.
+    fn apply_arithmetic(
+        op: ArithmeticOperator,
+        left: &DataValue,
+        right: &DataValue,
+    ) -> DatabaseResult<Option<DataValue>> {
+        match (left, right) {
+            (DataValue::Int(l), DataValue::Int(r)) => {
+                let result = match op {
+                    ArithmeticOperator::Add => l.checked_add(*r),
+                    ArithmeticOperator::Sub => l.checked_sub(*r),
+                    ArithmeticOperator::Mul => l.checked_mul(*r),
+                    ArithmeticOperator::Div if *r == 0 => {
+                        return Err(SelectError::DivisionByZero.into());
+                    }
+                    ArithmeticOperator::Div => l.checked_div(*r),
+                };
+                Ok(result.map(DataValue::Int))
+            }
+            (DataValue::Int(_) | DataValue::Float(_), DataValue::Int(_) | DataValue::Float(_)) => {
+                let (l, r) = (Self::as_float(left), Self::as_float(right));
+                let result = match op {
+                    ArithmeticOperator::Add => l + r,
+                    ArithmeticOperator::Sub => l - r,
+                    ArithmeticOperator::Mul => l * r,
+                    ArithmeticOperator::Div => l / r,
+                };
+                Ok(Some(DataValue::Float(result)))
+            }
+            (l, r) => Err(SelectError::InvalidArithmetic {
+                op,
+                left: l.get_type(),
+                right: r.get_type(),
+            }
+            .into()),
+        }
+    }
+
+    /// Returns the number of records skipped by `OFFSET` and the maximum number
+    /// of records kept by `LIMIT`.
     ///
-    /// 1.  **(Validation):** Checks if all fields in `SELECT` and `ORDER BY` clauses
-    ///     exist in the table's schema.
-    /// 2.  **(FROM):** Retrieves all records from the table.
-    /// 3.  **(WHERE):** Filters the records based on the `where_clause`.
-    /// 4.  **(ORDER BY):** Sorts the filtered records.
-    /// 5.  **(LIMIT):** Takes the top `N` records.
-    /// 6.  **(SELECT):** Projects the final set of records, creating new `Record`
-    ///     objects containing only the requested fields.
+    /// # Errors
+    ///
+    /// * `SelectError::InvalidLimit` if the `LIMIT` value is negative.
+    /// * `SelectError::InvalidOffset` if the `OFFSET` value is negative.
+    fn page_bounds(&self) -> DatabaseResult<(usize, usize)> {
+        let clauses = &self.ast.optional_clauses;
+
+        let limit = match clauses.limit {
+            Some(limit) if limit < 0 => return Err(SelectError::InvalidLimit { limit }.into()),
+            Some(limit) => limit as usize,
+            None => usize::MAX,
+        };
+        let offset = match clauses.offset {
+            Some(offset) if offset < 0 => {
+                return Err(SelectError::InvalidOffset { offset }.into());
+            }
+            Some(offset) => offset as usize,
+            None => 0,
+        };
+
+        Ok((offset, limit))
+    }
+
+    /// Creates the record returned for `row`, holding the selected fields named
+    /// after their aliases. Selected columns share their values with `row`.
+    fn project(&self, row: &Record) -> DatabaseResult<Record> {
+        let mut fields = HashMap::new();
+        for item in &self.ast.fields {
+            let value = match &item.projection {
+                Projection::Field(field_name) => row.fields.get(field_name).unwrap().clone(),
+                Projection::Expression(expression) => Arc::new(Self::evaluate_expression(
+                    row,
+                    expression,
+                    &self.table.name,
+                )?),
+                Projection::Aggregate(_) => continue,
+            };
+            fields.insert(item.output_name(), value);
+        }
+        Ok(Record { fields })
+    }
+
+    /// Checks that the statement only refers to columns of the table and that
+    /// its `SELECT` list is consistent.
     ///
     /// # Errors
     ///
-    /// This function will return an error if:
     /// * `DatabaseError::FieldNotFound` - A field in the `SELECT` or `ORDER BY`
     ///   clause does not exist in the schema.
-    /// * An error occurs during `WHERE` clause evaluation (see `evaluate_condition`).
-    /// * `SelectError::InvalidLimit` - The `LIMIT` value is negative.
-    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
-        let table_name = self.table.name.clone();
+    /// * `SelectError::DuplicateAlias` - An alias names the same column as another item.
+    /// * `SelectError::MixedProjection` - Columns are selected together with aggregates.
+    fn validate(&self) -> DatabaseResult<()> {
         let all_columns = self.table.schema.keys().collect::<HashSet<_>>();
         let not_found_fields = self
             .ast
             .fields
             .iter()
+            .flat_map(|item| match &item.projection {
+                Projection::Field(field) => vec![field],
+                Projection::Aggregate(aggregate) => aggregate.field.iter().collect(),
+                Projection::Expression(expression) => expression.columns(),
+            })
             .filter(|field| !all_columns.contains(field))
             .collect::<Vec<_>>();
 
         if !not_found_fields.is_empty() {
-            return Err(DatabaseError::FieldNotFound {
-                table: table_name,
-                field: not_found_fields.first().unwrap().to_string(),
-            });
+            return Err(self
+                .table
+                .field_not_found(not_found_fields.first().unwrap()));
+        }
+
+        // repeating a plain column is harmless, but an alias must not hide another column
+        let mut output_names: HashMap<String, (usize, bool)> = HashMap::new();
+        for item in &self.ast.fields {
+            let (count, aliased) = output_names.entry(item.output_name()).or_default();
+            *count += 1;
+            *aliased |= item.alias.is_some();
+        }
+        if let Some((alias, _)) = output_names
+            .into_iter()
+            .find(|(_, (count, aliased))| *count > 1 && *aliased)
+        {
+            return Err(SelectError::DuplicateAlias { alias }.into());
         }
 
         if let Some(order_by) = &self.ast.optional_clauses.order_by
-            && !all_columns.contains(&order_by.column)
+            && let Some(column) = order_by
+                .expression
+                .columns()
+                .into_iter()
+                .find(|column| !all_columns.contains(column))
         {
-            return Err(DatabaseError::FieldNotFound {
-                table: table_name,
-                field: order_by.column.clone(),
-            });
+            return Err(self.table.field_not_found(column));
         }
 
-        let all_rows = self.table.rows.values().collect::<Vec<_>>();
+        if self.is_aggregate()
+            && let Some(projection) = self
+                .ast
+                .fields
+                .iter()
+                .map(|item| &item.projection)
+                .find(|projection| !matches!(projection, Projection::Aggregate(_)))
+        {
+            return Err(SelectError::MixedProjection {
+                field: projection.to_string(),
+            }
+            .into());
+        }
 
-        let mut filtered_rows: Vec<&Record> = match &self.ast.optional_clauses.where_clause {
-            Some(condition) => all_rows.into_iter().try_fold(Vec::new(), |mut acc, row| {
-                if Self::evaluate_condition(row, condition, &table_name)? {
-                    acc.push(row);
-                }
-                Ok::<Vec<&Record>, DatabaseError>(acc)
-            })?,
-            None => all_rows,
-        };
+        Ok(())
+    }
 
-        let sorted_rows = match &self.ast.optional_clauses.order_by {
-            Some(order_by) => {
-                filtered_rows.sort_by_key(|row| row.fields.get(&order_by.column));
-                if order_by.direction == OrderDirection::Desc {
-                    filtered_rows.reverse();
+    /// Checks if the `SELECT` list contains an aggregate.
+    fn is_aggregate(&self) -> bool {
+        self.ast
+            .fields
+            .iter()
+            .any(|item| matches!(item.projection, Projection::Aggregate(_)))
+    }
+
+    /// Folds the filtered `rows` into a single record holding one field per
+    /// aggregate, named after its alias or the aggregate itself (e.g. `AVG(age)`).
+    ///
+    /// `AVG`, `MIN` and `MAX` have no value for an empty set of rows, for which
+    /// they fail (see `evaluate_aggregate`), so every aggregate has a field.
+    fn aggregate(&self, rows: &[&Record]) -> DatabaseResult<Record> {
+        let mut fields = HashMap::new();
+        for item in &self.ast.fields {
+            if let Projection::Aggregate(aggregate) = &item.projection {
+                let value = self.evaluate_aggregate(aggregate, rows)?;
+                fields.insert(item.output_name(), value);
+            }
+        }
+        Ok(Record::new(fields))
+    }
+
+    /// Computes a single aggregate over `rows`.
+    ///
+    /// # Errors
+    ///
+    /// * `SelectError::InvalidAggregateType` if `SUM` or `AVG` is applied to a
+    ///   non-numeric column.
+    /// * `SelectError::AggregateOverflow` if the `SUM` of an `Int` column overflows.
+    /// * `SelectError::EmptyAggregate` if `AVG`, `MIN` or `MAX` is computed over no
+    ///   rows, as there is no `NULL` value to return.
+    fn evaluate_aggregate(
+        &self,
+        aggregate: &Aggregate,
+        rows: &[&Record],
+    ) -> DatabaseResult<DataValue> {
+        let Some(field) = &aggregate.field else {
+            return Ok(DataValue::Int(rows.len() as i64));
+        };
+        let mut values = rows.iter().map(|row| row.get(field).unwrap());
+        let data_type = self.table.schema[field];
+
+        let value = match (aggregate.function, data_type) {
+            (AggregateFunction::Count, _) => Some(DataValue::Int(rows.len() as i64)),
+            (AggregateFunction::Min, _) => values.min().cloned(),
+            (AggregateFunction::Max, _) => values.max().cloned(),
+            (AggregateFunction::Sum, DataType::Int) => {
+                let sum = values
+                    .try_fold(0i64, |sum, value| match value {
+                        DataValue::Int(value) => sum.checked_add(*value),
+                        _ => unreachable!("values match the schema"),
+                    })
+                    .ok_or_else(|| SelectError::AggregateOverflow {
+                        function: aggregate.function,
+                        field: field.clone(),
+                    })?;
+                Some(DataValue::Int(sum))
+            }
+            (AggregateFunction::Sum, DataType::Float) => {
+                Some(DataValue::Float(values.map(Self::as_float).sum()))
+            }
+            (AggregateFunction::Avg, DataType::Int | DataType::Float) => {
+                let sum: f64 = values.map(Self::as_float).sum();
+                (!rows.is_empty()).then(|| DataValue::Float(sum / rows.len() as f64))
+            }
+            (function, data_type) => {
+                return Err(SelectError::InvalidAggregateType {
+                    function,
+                    field: field.clone(),
+                    data_type,
                 }
-                filtered_rows
+                .into());
             }
-            None => filtered_rows,
         };
 
-        let limited_rows = match &self.ast.optional_clauses.limit {
-            Some(limit) if *limit < 0 => {
-                return Err(SelectError::InvalidLimit { limit: *limit }.into());
+        value.ok_or_else(|| {
+            SelectError::EmptyAggregate {
+                function: aggregate.function,
+                field: field.clone(),
             }
-            Some(limit) => sorted_rows.into_iter().take(*limit as usize).collect(),
-            None => sorted_rows,
-        };
+            .into()
+        })
+    }
+
+    /// Converts a numeric value to `f64` for `SUM` and `AVG`.
+    fn as_float(value: &DataValue) -> f64 {
+        match value {
+            DataValue::Int(value) => *value as f64,
+            DataValue::Float(value) => *value,
+            _ => unreachable!("only numeric columns are summed"),
+        }
+    }
+}
 
-        let projected_rows = limited_rows
+impl<'a, K: DatabaseKey> ExecuteStream for Select<'a, K> {
+    /// Executes the `SELECT` query, producing the returned records one at a time.
+    ///
+    /// This method performs the full query execution pipeline in the standard
+    /// SQL logical order:
+    ///
+    /// 1.  **(Validation):** Checks if all fields in `SELECT` and `ORDER BY` clauses
+    ///     exist in the table's schema.
+    /// 2.  **(FROM):** Retrieves the records from the table, through the primary key
+    ///     or an index when the `WHERE` clause allows it (see [`planner::plan`]).
+    /// 3.  **(WHERE):** Filters the records based on the `where_clause`.
+    /// 4.  **(ORDER BY):** Sorts the filtered records.
+    /// 5.  **(OFFSET, LIMIT):** Skips the first `M` records and takes the next `N`.
+    /// 6.  **(SELECT):** Projects the final set of records, creating new `Record`
+    ///     objects containing only the requested fields, named after their aliases.
+    ///
+    /// Without an `ORDER BY`, the records are filtered and projected only as the
+    /// stream is consumed, so records past the `LIMIT` are never evaluated. Sorting
+    /// needs every filtered record, but only references to them are collected.
+    ///
+    /// If the `SELECT` list consists of aggregates, the filtered records are
+    /// instead folded into a single record, to which `OFFSET` and `LIMIT` are then applied.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * `DatabaseError::FieldNotFound` - A field in the `SELECT` or `ORDER BY`
+    ///   clause does not exist in the schema.
+    /// * `SelectError::InvalidLimit` - The `LIMIT` value is negative.
+    /// * `SelectError::InvalidOffset` - The `OFFSET` value is negative.
+    /// * `SelectError::MixedProjection` - Columns are selected together with aggregates.
+    /// * `SelectError::DuplicateAlias` - An alias names the same column as another item.
+    /// * An error occurs while computing an aggregate (see `evaluate_aggregate`).
+    ///
+    /// An error during `WHERE` clause evaluation (see `evaluate_condition`) or
+    /// projection is yielded by the stream for the record that raised it.
+    fn execute_stream(&self) -> DatabaseResult<RecordStream<'_>> {
+        self.validate()?;
+        let (offset, limit) = self.page_bounds()?;
+        let table_name = &self.table.name;
+
+        let access_path =
+            planner::plan(self.table, self.ast.optional_clauses.where_clause.as_ref());
+        let all_rows = planner::candidate_rows(self.table, &access_path);
+
+        let where_clause = self.ast.optional_clauses.where_clause.as_ref();
+        let filtered_rows = all_rows
             .into_iter()
-            .map(|row| {
-                let mut fields = HashMap::new();
-                for field_name in &self.ast.fields {
-                    let value = row.fields.get(field_name).unwrap().clone();
-                    fields.insert(field_name.clone(), value);
+            .filter_map(move |row| match where_clause {
+                Some(condition) => match Self::evaluate_condition(&row, condition, table_name) {
+                    Ok(true) => Some(Ok(row)),
+                    Ok(false) => None,
+                    Err(e) => Some(Err(e)),
+                },
+                None => Some(Ok(row)),
+            });
+
+        if self.is_aggregate() {
+            let rows = filtered_rows.collect::<DatabaseResult<Vec<_>>>()?;
+            let record = self.aggregate(&rows.iter().map(AsRef::as_ref).collect::<Vec<_>>())?;
+            return Ok(Box::new(
+                std::iter::once(Ok(record)).skip(offset).take(limit),
+            ));
+        }
+
+        let sorted_rows: Box<dyn Iterator<Item = DatabaseResult<Cow<'_, Record>>>> =
+            match &self.ast.optional_clauses.order_by {
+                Some(order_by) => {
+                    // the keys are computed up front, so an invalid expression fails the query
+                    let mut keyed_rows = filtered_rows
+                        .map(|row| {
+                            let row = row?;
+                            let key =
+                                Self::evaluate_expression(&row, &order_by.expression, table_name)?;
+                            Ok((key, row))
+                        })
+                        .collect::<DatabaseResult<Vec<_>>>()?;
+                    keyed_rows.sort_by(|(a, _), (b, _)| a.cmp(b));
+                    if order_by.direction == OrderDirection::Desc {
+                        keyed_rows.reverse();
+                    }
+                    Box::new(keyed_rows.into_iter().map(|(_, row)| Ok(row)))
                 }
-                Ok(Record { fields })
+                None => Box::new(filtered_rows),
+            };
+
+        // an error is yielded even while skipping, so that it is not lost to the `OFFSET`
+        let mut skipped = 0;
+        let paginated_rows = sorted_rows
+            .filter(move |row| {
+                let skip = row.is_ok() && skipped < offset;
+                skipped += usize::from(skip);
+                !skip
             })
-            .collect::<DatabaseResult<Vec<Record>>>()?;
+            .take(limit);
 
-        Ok(ExecutionResult::Data(projected_rows))
+        Ok(Box::new(
+            paginated_rows.map(|row| row.and_then(|row| self.project(&row))),
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> Execute for Select<'a, K> {
+    /// Executes the `SELECT` query, collecting the records of
+    /// [`Select::execute_stream`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error raised while producing the records.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let records = self.execute_stream()?.collect::<DatabaseResult<Vec<_>>>()?;
+        Ok(ExecutionResult::Data(records))
     }
 }
 
@@ -171,11 +560,12 @@ mod tests {
     use super::*;
     use crate::{
         ast::{
-            Assignment, Comparison, Condition, CreateStmt, Field, InsertStmt, Operator,
-            OptionalClauses, OrderBy, OrderDirection, SelectStmt,
+            Assignment, AstError, Command, Comparison, Condition, CreateStmt, Field, InsertStmt,
+            Operator, OptionalClauses, OrderBy, OrderDirection, SelectStmt,
         },
-        core::{DataType, DataValue},
-        execution::Execute,
+        core::{DataType, DataValue, Database, TableStorage},
+        execution::{Execute, build_execute_command, build_stream_command},
+        parsing::QueryParser,
     };
 
     mod common {
@@ -193,24 +583,34 @@ mod tests {
                 Field {
                     name: "id".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "name".to_string(),
                     data_type: DataType::String,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "age".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "active".to_string(),
                     data_type: DataType::Boolean,
+                    variants: None,
+                    default: None,
                 },
             ];
             let ast = CreateStmt {
                 table_name: "users".to_string(),
                 key_field: "id".to_string(),
                 fields,
+                checks: Vec::new(),
+                storage: TableStorage::default(),
                 query: "Create...".to_string(), // unused
             };
             let mut cmd = Create {
@@ -272,10 +672,16 @@ mod tests {
             let ast = InsertStmt {
                 table_name: "users".to_string(),
                 assignments,
+                on_conflict: None,
+                returning: None,
                 query: "Insert...".to_string(), //unused
             };
             let table = db.tables.get_mut("users").unwrap();
-            let mut cmd = Insert { table, ast };
+            let mut cmd = Insert {
+                table,
+                ast,
+                triggers: None,
+            };
             cmd.execute().unwrap();
         }
     }
@@ -293,7 +699,10 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["id".to_string(), "name".to_string()],
+            fields: vec![
+                Projection::Field("id".to_string()).into(),
+                Projection::Field("name".to_string()).into(),
+            ],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses::default(),
             query: "Select...".to_string(), // unused
@@ -322,7 +731,7 @@ mod tests {
         });
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(condition),
@@ -338,11 +747,11 @@ mod tests {
         let data = get_data_from_result(result);
         assert_eq!(data.len(), 2);
         assert_eq!(
-            data[0].fields.get("name"),
+            data[0].get("name"),
             Some(&DataValue::String("Alice".to_string()))
         );
         assert_eq!(
-            data[1].fields.get("name"),
+            data[1].get("name"),
             Some(&DataValue::String("Charlie".to_string()))
         );
     }
@@ -366,7 +775,7 @@ mod tests {
         };
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(condition),
@@ -382,11 +791,11 @@ mod tests {
         let data = get_data_from_result(result);
         assert_eq!(data.len(), 2);
         assert_eq!(
-            data[0].fields.get("name"),
+            data[0].get("name"),
             Some(&DataValue::String("Alice".to_string()))
         );
         assert_eq!(
-            data[1].fields.get("name"),
+            data[1].get("name"),
             Some(&DataValue::String("Bob".to_string()))
         );
     }
@@ -397,11 +806,14 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string(), "age".to_string()],
+            fields: vec![
+                Projection::Field("name".to_string()).into(),
+                Projection::Field("age".to_string()).into(),
+            ],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 order_by: Some(OrderBy {
-                    column: "age".to_string(),
+                    expression: Expression::Column("age".to_string()),
                     direction: OrderDirection::Desc,
                 }),
                 ..Default::default()
@@ -416,15 +828,15 @@ mod tests {
         let data = get_data_from_result(result);
         assert_eq!(data.len(), 3);
         assert_eq!(
-            data[0].fields.get("name"),
+            data[0].get("name"),
             Some(&DataValue::String("Charlie".to_string()))
         );
         assert_eq!(
-            data[1].fields.get("name"),
+            data[1].get("name"),
             Some(&DataValue::String("Alice".to_string()))
         );
         assert_eq!(
-            data[2].fields.get("name"),
+            data[2].get("name"),
             Some(&DataValue::String("Bob".to_string()))
         );
     }
@@ -435,7 +847,7 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["id".to_string()],
+            fields: vec![Projection::Field("id".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 limit: Some(2),
@@ -464,15 +876,16 @@ mod tests {
         });
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(condition),
                 order_by: Some(OrderBy {
-                    column: "age".to_string(),
+                    expression: Expression::Column("age".to_string()),
                     direction: OrderDirection::Asc,
                 }),
                 limit: Some(1),
+                offset: None,
             },
             query: "Select...".to_string(), // unused
         };
@@ -484,7 +897,7 @@ mod tests {
         let data = get_data_from_result(result);
         assert_eq!(data.len(), 1);
         assert_eq!(
-            data[0].fields.get("name"),
+            data[0].get("name"),
             Some(&DataValue::String("Bob".to_string()))
         );
     }
@@ -495,7 +908,10 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["id".to_string(), "email".to_string()],
+            fields: vec![
+                Projection::Field("id".to_string()).into(),
+                Projection::Field("email".to_string()).into(),
+            ],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses::default(),
             query: "Select...".to_string(), // unused
@@ -506,7 +922,7 @@ mod tests {
         assert!(result.is_err());
 
         match result.err().unwrap() {
-            DatabaseError::FieldNotFound { table, field } => {
+            DatabaseError::FieldNotFound { table, field, .. } => {
                 assert_eq!(table, "users");
                 assert_eq!(field, "email");
             }
@@ -520,11 +936,11 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["id".to_string()],
+            fields: vec![Projection::Field("id".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 order_by: Some(OrderBy {
-                    column: "salary".to_string(),
+                    expression: Expression::Column("salary".to_string()),
                     direction: OrderDirection::Asc,
                 }),
                 ..Default::default()
@@ -537,7 +953,7 @@ mod tests {
         assert!(result.is_err());
 
         match result.err().unwrap() {
-            DatabaseError::FieldNotFound { table, field } => {
+            DatabaseError::FieldNotFound { table, field, .. } => {
                 assert_eq!(table, "users");
                 assert_eq!(field, "salary");
             }
@@ -557,7 +973,7 @@ mod tests {
         });
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(condition),
@@ -571,7 +987,7 @@ mod tests {
         assert!(result.is_err());
 
         match result.err().unwrap() {
-            DatabaseError::FieldNotFound { table, field } => {
+            DatabaseError::FieldNotFound { table, field, .. } => {
                 assert_eq!(table, "users");
                 assert_eq!(field, "location");
             }
@@ -591,7 +1007,7 @@ mod tests {
         });
 
         let ast = SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(condition),
@@ -626,7 +1042,7 @@ mod tests {
         let table = db.tables.get_mut("users").unwrap();
 
         let ast = SelectStmt {
-            fields: vec!["id".to_string()],
+            fields: vec![Projection::Field("id".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 limit: Some(-5),
@@ -646,4 +1062,576 @@ mod tests {
             _ => panic!("Expected InvalidLimit error"),
         }
     }
+
+    #[test]
+    fn test_execute_select_with_not() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id FROM users WHERE NOT active = true OR NOT name IN (\"Alice\", \"Charlie\") ORDER_BY id ASC;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 2);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(2));
+        assert_eq!(*data[1].fields["id"], DataValue::Int(3));
+    }
+
+    #[test]
+    fn test_like_patterns() {
+        assert!(Operator::like("Alice", "Alice"));
+        assert!(Operator::like("Alice", "A%"));
+        assert!(Operator::like("Alice", "%ce"));
+        assert!(Operator::like("Alice", "%li%"));
+        assert!(Operator::like("Alice", "A_i_e"));
+        assert!(Operator::like("Alice", "%%A%e%"));
+        assert!(Operator::like("", "%"));
+        assert!(Operator::like("aXbXc", "a%X%c"));
+        assert!(!Operator::like("Alice", "alice"));
+        assert!(!Operator::like("Alice", "A_ce"));
+        assert!(!Operator::like("Alice", "%x%"));
+        assert!(!Operator::like("", "_"));
+    }
+
+    #[test]
+    fn test_execute_select_with_like() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id FROM users WHERE name LIKE \"%li%\" ORDER_BY id ASC;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 2);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(1));
+        assert_eq!(*data[1].fields["id"], DataValue::Int(3));
+    }
+
+    #[test]
+    fn test_execute_fail_like_on_non_string() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT id FROM users WHERE age LIKE \"3%\";")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Ast(AstError::InvalidOperatorForType { operator, dtype }) => {
+                assert_eq!(operator, Operator::Like);
+                assert_eq!(dtype, DataType::Int);
+            }
+            e => panic!("Expected InvalidOperatorForType error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_select_with_in() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id FROM users WHERE name IN (\"Alice\", \"Charlie\", \"Zoe\") ORDER_BY id ASC;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 2);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(1));
+        assert_eq!(*data[1].fields["id"], DataValue::Int(3));
+
+        let result = execute_query(&mut db, "SELECT id FROM users WHERE age IN (1, 2);");
+        assert!(get_data_from_result(result).is_empty());
+    }
+
+    #[test]
+    fn test_execute_fail_in_type_mismatch() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT id FROM users WHERE age IN (30, \"old\");")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::TypeMismatch {
+                field,
+                expected,
+                found,
+                ..
+            } => {
+                assert_eq!(field, "age");
+                assert_eq!(expected, DataType::Int);
+                assert_eq!(found, DataType::String);
+            }
+            e => panic!("Expected TypeMismatch error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_select_with_aliases() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id, name AS username FROM users WHERE id = 2;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].fields.len(), 2);
+        assert_eq!(
+            *data[0].fields["username"],
+            DataValue::String("Bob".to_string())
+        );
+
+        let result = execute_query(&mut db, "SELECT COUNT(*) AS total, AVG(age) FROM users;");
+        let data = get_data_from_result(result);
+        assert_eq!(*data[0].fields["total"], DataValue::Int(3));
+        assert!(data[0].fields.contains_key("AVG(age)"));
+    }
+
+    #[test]
+    fn test_execute_fail_duplicate_alias() {
+        let mut db = common::setup_db_i64_with_data();
+
+        for query in [
+            "SELECT name AS id, id FROM users;",
+            "SELECT id, name AS id FROM users;",
+            "SELECT name AS x, age AS x FROM users;",
+        ] {
+            match execute_query(&mut db, query).err().unwrap() {
+                DatabaseError::Select(SelectError::DuplicateAlias { alias }) => {
+                    assert!(alias == "id" || alias == "x")
+                }
+                e => panic!("Expected DuplicateAlias error, got {e:?}"),
+            }
+        }
+
+        let result = execute_query(&mut db, "SELECT id, id FROM users;");
+        assert_eq!(get_data_from_result(result).len(), 3);
+    }
+
+    #[test]
+    fn test_execute_select_with_offset() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id FROM users ORDER_BY age ASC LIMIT 1 OFFSET 1;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(1));
+
+        let result = execute_query(&mut db, "SELECT id FROM users ORDER_BY age ASC OFFSET 2;");
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(3));
+
+        let result = execute_query(&mut db, "SELECT id FROM users OFFSET 10;");
+        assert!(get_data_from_result(result).is_empty());
+
+        let result = execute_query(&mut db, "SELECT COUNT(*) FROM users OFFSET 1;");
+        assert!(get_data_from_result(result).is_empty());
+    }
+
+    #[test]
+    fn test_execute_fail_invalid_offset() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT id FROM users OFFSET -1;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Select(SelectError::InvalidOffset { offset }) => {
+                assert_eq!(offset, -1)
+            }
+            e => panic!("Expected InvalidOffset error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_select_order_by_expression() {
+        let mut db = common::setup_db_i64_with_data();
+        let ids = |data: Vec<Record>| {
+            data.into_iter()
+                .map(|record| record.get("id").unwrap().clone())
+                .collect::<Vec<_>>()
+        };
+        let expected = [DataValue::Int(3), DataValue::Int(1), DataValue::Int(2)];
+
+        for query in [
+            "SELECT id FROM users ORDER_BY age * -1 ASC;",
+            "SELECT id FROM users ORDER_BY LENGTH(name) DESC;",
+            "SELECT id FROM users ORDER_BY (age + 0.5) / 2 DESC;",
+        ] {
+            let data = get_data_from_result(execute_query(&mut db, query));
+            assert_eq!(ids(data), expected, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_execute_fail_order_by_expression() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT id FROM users ORDER_BY LENGTH(email) ASC;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::FieldNotFound { field, .. } => assert_eq!(field, "email"),
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+        assert!(matches!(
+            execute_query(&mut db, "SELECT id FROM users ORDER_BY age / 0 ASC;"),
+            Err(DatabaseError::Select(SelectError::DivisionByZero))
+        ));
+        assert!(matches!(
+            execute_query(&mut db, "SELECT id FROM users ORDER_BY name + 1 ASC;"),
+            Err(DatabaseError::Select(SelectError::InvalidArithmetic {
+                op: ArithmeticOperator::Add,
+                left: DataType::String,
+                right: DataType::Int,
+            }))
+        ));
+        assert!(matches!(
+            execute_query(&mut db, "SELECT id FROM users ORDER_BY ABS(name) ASC;"),
+            Err(DatabaseError::Select(
+                SelectError::InvalidFunctionArgument {
+                    function: ScalarFunction::Abs,
+                    dtype: DataType::String,
+                }
+            ))
+        ));
+        assert!(matches!(
+            execute_query(
+                &mut db,
+                "SELECT id FROM users ORDER_BY age * 9223372036854775807 ASC;"
+            ),
+            Err(DatabaseError::Select(
+                SelectError::ArithmeticOverflow { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_execute_select_case() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id, CASE WHEN age >= 30 AND active = true THEN \"senior\" WHEN age >= 30 THEN \"inactive\" ELSE LOWER(name) END AS category FROM users ORDER_BY id ASC;",
+        );
+        let categories = get_data_from_result(result)
+            .into_iter()
+            .map(|record| record.get("category").unwrap().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            ["senior", "bob", "inactive"].map(|s| DataValue::String(s.to_string()))
+        );
+
+        let result = execute_query(
+            &mut db,
+            "SELECT id FROM users ORDER_BY CASE WHEN name = \"Bob\" THEN 0 ELSE age END DESC;",
+        );
+        let ids = get_data_from_result(result)
+            .into_iter()
+            .map(|record| record.get("id").unwrap().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [3, 1, 2].map(DataValue::Int));
+    }
+
+    #[test]
+    fn test_execute_fail_case() {
+        let mut db = common::setup_db_i64_with_data();
+
+        for (query, missing) in [
+            (
+                "SELECT CASE WHEN email = 1 THEN 1 ELSE 0 END FROM users;",
+                "email",
+            ),
+            (
+                "SELECT CASE WHEN age = 1 THEN 1 ELSE score END FROM users;",
+                "score",
+            ),
+        ] {
+            match execute_query(&mut db, query).err().unwrap() {
+                DatabaseError::FieldNotFound { field, .. } => assert_eq!(field, missing),
+                e => panic!("Expected FieldNotFound error, got {e:?}"),
+            }
+        }
+        assert!(matches!(
+            execute_query(
+                &mut db,
+                "SELECT CASE WHEN age = \"x\" THEN 1 ELSE 0 END FROM users;"
+            ),
+            Err(DatabaseError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            execute_query(
+                &mut db,
+                "SELECT COUNT(*), CASE WHEN age = 1 THEN 1 ELSE 0 END FROM users;"
+            ),
+            Err(DatabaseError::Select(SelectError::MixedProjection { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_execute_select_bytes() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE files KEY id FIELDS id: INT, data: BYTES;",
+            "INSERT id = 1, data = 0xcafe INTO files;",
+            "INSERT id = 2, data = 0x00 INTO files;",
+        ] {
+            execute_query(&mut db, query).unwrap();
+        }
+
+        let result = execute_query(&mut db, "SELECT id FROM files WHERE data = 0xCAFE;");
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(1));
+
+        let result = execute_query(&mut db, "SELECT id FROM files ORDER_BY LENGTH(data) ASC;");
+        let data = get_data_from_result(result);
+        assert_eq!(*data[0].fields["id"], DataValue::Int(2));
+
+        match execute_query(&mut db, "SELECT id FROM files WHERE data > 0x00;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Ast(AstError::InvalidOperatorForType { dtype, .. }) => {
+                assert_eq!(dtype, DataType::Bytes)
+            }
+            e => panic!("Expected InvalidOperatorForType error, got {e:?}"),
+        }
+    }
+
+    fn execute_query(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    #[test]
+    fn test_not_found_errors_suggest_names() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT nme FROM users;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::FieldNotFound { field, help, .. } => {
+                assert_eq!(field, "nme");
+                assert_eq!(help.as_deref(), Some("Did you mean 'name'?"));
+            }
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+        match execute_query(&mut db, "SELECT name FROM user;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::TableNotFound { help, .. } => {
+                assert_eq!(help.as_deref(), Some("Did you mean 'users'?"));
+            }
+            e => panic!("Expected TableNotFound error, got {e:?}"),
+        }
+        match execute_query(&mut db, "SELECT address FROM users;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::FieldNotFound { help, .. } => assert_eq!(help, None),
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_select_aggregates() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT COUNT(*), COUNT(id), SUM(age), AVG(age), MIN(name), MAX(age) FROM users;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+
+        let fields = &data[0].fields;
+        assert_eq!(fields.len(), 6);
+        assert_eq!(*fields["COUNT(*)"], DataValue::Int(3));
+        assert_eq!(*fields["COUNT(id)"], DataValue::Int(3));
+        assert_eq!(*fields["SUM(age)"], DataValue::Int(95));
+        assert_eq!(*fields["AVG(age)"], DataValue::Float(95.0 / 3.0));
+        assert_eq!(*fields["MIN(name)"], DataValue::String("Alice".to_string()));
+        assert_eq!(*fields["MAX(age)"], DataValue::Int(40));
+    }
+
+    #[test]
+    fn test_execute_select_aggregates_with_where() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let result = execute_query(
+            &mut db,
+            "SELECT COUNT(*), AVG(age) FROM users WHERE active = true;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(*data[0].fields["COUNT(*)"], DataValue::Int(2));
+        assert_eq!(*data[0].fields["AVG(age)"], DataValue::Float(27.5));
+
+        let result = execute_query(
+            &mut db,
+            "SELECT COUNT(*), SUM(age) FROM users WHERE age > 100;",
+        );
+        let data = get_data_from_result(result);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].fields.len(), 2);
+        assert_eq!(*data[0].fields["COUNT(*)"], DataValue::Int(0));
+        assert_eq!(*data[0].fields["SUM(age)"], DataValue::Int(0));
+
+        for function in ["AVG", "MIN", "MAX"] {
+            let query = format!("SELECT COUNT(*), {function}(age) FROM users WHERE age > 100;");
+            match execute_query(&mut db, &query) {
+                Err(DatabaseError::Select(SelectError::EmptyAggregate { function: f, field })) => {
+                    assert_eq!(f.to_string(), function);
+                    assert_eq!(field, "age");
+                }
+                other => panic!("Expected EmptyAggregate error, got {other:?}"),
+            }
+        }
+
+        execute_query(&mut db, "TRUNCATE users;").unwrap();
+        assert!(matches!(
+            execute_query(&mut db, "SELECT MIN(name) FROM users;"),
+            Err(DatabaseError::Select(SelectError::EmptyAggregate { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_execute_fail_invalid_aggregates() {
+        let mut db = common::setup_db_i64_with_data();
+
+        match execute_query(&mut db, "SELECT name, COUNT(*) FROM users;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Select(SelectError::MixedProjection { field }) => {
+                assert_eq!(field, "name")
+            }
+            e => panic!("Expected MixedProjection error, got {e:?}"),
+        }
+        match execute_query(&mut db, "SELECT AVG(name) FROM users;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Select(SelectError::InvalidAggregateType {
+                function,
+                field,
+                data_type,
+            }) => {
+                assert_eq!(function, AggregateFunction::Avg);
+                assert_eq!(field, "name");
+                assert_eq!(data_type, DataType::String);
+            }
+            e => panic!("Expected InvalidAggregateType error, got {e:?}"),
+        }
+        match execute_query(&mut db, "SELECT MAX(email) FROM users;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::FieldNotFound { field, .. } => assert_eq!(field, "email"),
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_fail_sum_overflow() {
+        let mut db = common::setup_db_i64_with_data();
+        execute_query(
+            &mut db,
+            &format!(
+                "INSERT id = 4, name = \"Dan\", age = {}, active = true INTO users;",
+                i64::MAX
+            ),
+        )
+        .unwrap();
+
+        let result = execute_query(&mut db, "SELECT SUM(age) FROM users;");
+        assert!(matches!(
+            result,
+            Err(DatabaseError::Select(SelectError::AggregateOverflow { .. }))
+        ));
+    }
+
+    fn stream_query(db: &mut Database<i64>, query: &str) -> Vec<DatabaseResult<Record>> {
+        let Command::Select(stmt) = QueryParser.parse_query(query).unwrap() else {
+            panic!("Expected a SELECT statement");
+        };
+        build_stream_command(db, stmt)
+            .unwrap()
+            .execute_stream()
+            .unwrap()
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_select_matches_execute() {
+        let mut db = common::setup_db_i64_with_data();
+
+        for query in [
+            "SELECT name FROM users;",
+            "SELECT name, age FROM users WHERE active = true ORDER_BY age DESC;",
+            "SELECT id FROM users LIMIT 1 OFFSET 1;",
+            "SELECT COUNT(*) FROM users WHERE age > 26;",
+        ] {
+            let streamed = stream_query(&mut db, query)
+                .into_iter()
+                .collect::<DatabaseResult<Vec<_>>>()
+                .unwrap();
+            assert_eq!(
+                streamed,
+                get_data_from_result(execute_query(&mut db, query))
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream_select_is_lazy() {
+        let mut db = common::setup_db_i64_with_data();
+        let table = db.tables.get_mut("users").unwrap();
+
+        let ast = match QueryParser
+            .parse_query("SELECT id, CASE WHEN id = 2 THEN age / 0 ELSE age END AS x FROM users;")
+            .unwrap()
+        {
+            Command::Select(stmt) => stmt,
+            _ => unreachable!(),
+        };
+        let cmd = Select { table, ast };
+
+        // only the second record divides by zero, so the first one is still produced
+        let mut stream = cmd.execute_stream().unwrap();
+        assert!(stream.next().unwrap().is_ok());
+        assert!(matches!(
+            stream.next(),
+            Some(Err(DatabaseError::Select(SelectError::DivisionByZero)))
+        ));
+
+        // an invalid statement fails before any record is produced
+        let mut db = common::setup_db_i64_with_data();
+        let ast = match QueryParser.parse_query("SELECT email FROM users;").unwrap() {
+            Command::Select(stmt) => stmt,
+            _ => unreachable!(),
+        };
+        assert!(matches!(
+            build_stream_command(&mut db, ast).unwrap().execute_stream(),
+            Err(DatabaseError::FieldNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_projection_shares_values() {
+        let mut db = common::setup_db_i64_with_data();
+
+        let data = get_data_from_result(execute_query(
+            &mut db,
+            "SELECT name, CASE WHEN age > 0 THEN name ELSE name END AS copy FROM users;",
+        ));
+        let row = &db.tables["users"].rows.get(&1).unwrap();
+        assert!(Arc::ptr_eq(&data[0].fields["name"], &row.fields["name"]));
+        assert!(!Arc::ptr_eq(&data[0].fields["copy"], &row.fields["name"]));
+        assert_eq!(data[0].fields["copy"], row.fields["name"]);
+    }
 }
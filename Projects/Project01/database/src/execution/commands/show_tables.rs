@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use crate::{
+    DatabaseResult,
+    core::{DataValue, Database, DatabaseKey, Record},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `SHOW TABLES` command.
+///
+/// The parsed `ShowTablesStmt` holds nothing, so this struct holds just a
+/// read-only reference to the `Database` whose tables are listed.
+pub struct ShowTables<'a, K: DatabaseKey> {
+    /// A read-only reference to the database whose tables are listed.
+    pub database: &'a Database<K>,
+}
+
+impl<'a, K: DatabaseKey> Execute for ShowTables<'a, K> {
+    /// Executes the `SHOW TABLES` command.
+    ///
+    /// Returns one record per table of the user, sorted by name, holding its
+    /// `name` and its `row_count`, as listed by [`Database::schemas`].
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let records = self
+            .database
+            .schemas()
+            .into_iter()
+            .map(|schema| {
+                Record::new(HashMap::from([
+                    (
+                        "name".to_string(),
+                        DataValue::String(schema.name.to_string()),
+                    ),
+                    (
+                        "row_count".to_string(),
+                        DataValue::Int(schema.row_count as i64),
+                    ),
+                ]))
+            })
+            .collect();
+        Ok(ExecutionResult::Data(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DataType;
+
+    #[test]
+    fn test_execute_show_tables() {
+        let mut db = Database::<i64>::default();
+        let Ok(ExecutionResult::Data(records)) = db.execute("SHOW TABLES;") else {
+            panic!("Expected ExecutionResult::Data");
+        };
+        assert!(records.is_empty());
+
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING DEFAULT \"anonymous\";",
+            "CREATE orders KEY id FIELDS id: INT, status: ENUM(\"new\", \"paid\");",
+            "INSERT id = 1 INTO users;",
+            "INSERT id = 2 INTO users;",
+            "CREATE __internal KEY id FIELDS id: INT;",
+        ] {
+            db.execute(query).unwrap();
+        }
+
+        let Ok(ExecutionResult::Data(records)) = db.execute("SHOW TABLES;") else {
+            panic!("Expected ExecutionResult::Data");
+        };
+        let tables = records
+            .iter()
+            .map(|record| {
+                (
+                    record.get("name").unwrap(),
+                    record.get("row_count").unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            [
+                (&DataValue::String("orders".to_string()), &DataValue::Int(0)),
+                (&DataValue::String("users".to_string()), &DataValue::Int(2)),
+            ]
+        );
+
+        let schemas = db.schemas();
+        assert_eq!(schemas[1].name, "users");
+        assert_eq!(schemas[1].key_field, "id");
+        assert_eq!(
+            schemas[1]
+                .columns
+                .iter()
+                .map(|column| column.name)
+                .collect::<Vec<_>>(),
+            ["id", "name"]
+        );
+        let name = schemas[1].column("name").unwrap();
+        assert_eq!(name.data_type, DataType::String);
+        assert_eq!(
+            name.default,
+            Some(&DataValue::String("anonymous".to_string()))
+        );
+        let status = schemas[0].column("status").unwrap();
+        assert_eq!(
+            status.variants,
+            Some(["new".to_string(), "paid".to_string()].as_slice())
+        );
+    }
+}
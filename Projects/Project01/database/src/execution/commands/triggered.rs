@@ -0,0 +1,261 @@
+use crate::{
+    DatabaseResult,
+    ast::{DeleteStmt, InsertStmt},
+    core::{Database, DatabaseKey, Record, Trigger, TriggerAction, TriggerError, TriggerEvent},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::{delete::Delete, insert::Insert};
+
+/// The triggers run by a single `INSERT` or `DELETE`.
+///
+/// The executor of the statement calls [`RowTriggers::before`] before changing a
+/// record and [`RowTriggers::changed`] once it is changed; the AFTER triggers are
+/// run for the changed records by [`RowTriggers::after`].
+pub struct RowTriggers<K: DatabaseKey> {
+    /// The name of the changed table.
+    table: String,
+    /// The triggers of the table for the event of the statement.
+    triggers: Vec<Trigger<K>>,
+    /// The records changed by the statement, in order.
+    changed: Vec<Record>,
+}
+
+impl<K: DatabaseKey> RowTriggers<K> {
+    /// Collects the triggers run by `event` on the table `table` of `database`.
+    pub fn new(database: &Database<K>, table: &str, event: TriggerEvent) -> Self {
+        Self {
+            table: table.to_string(),
+            triggers: database.triggers_on(table, event),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the table has no triggers for the event.
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    /// Runs the BEFORE triggers for `record`, which is about to be changed.
+    ///
+    /// # Errors
+    /// Returns `TriggerError::Rejected` for the first trigger rejecting the change.
+    pub fn before(&self, record: &Record) -> DatabaseResult<()> {
+        for trigger in &self.triggers {
+            if let TriggerAction::Before(action) = &trigger.action {
+                action(record).map_err(|reason| TriggerError::Rejected {
+                    trigger: trigger.name.clone(),
+                    table: self.table.clone(),
+                    reason,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records that `record` was changed, for the AFTER triggers.
+    pub fn changed(&mut self, record: &Record) {
+        let has_after = self
+            .triggers
+            .iter()
+            .any(|trigger| matches!(trigger.action, TriggerAction::After(_)));
+        if has_after {
+            self.changed.push(record.clone());
+        }
+    }
+
+    /// Runs the AFTER triggers for every changed record, on `database`.
+    ///
+    /// # Errors
+    /// Returns `TriggerError::Failed` for the first trigger returning an error,
+    /// after which no further triggers run.
+    pub fn after(&mut self, database: &mut Database<K>) -> DatabaseResult<()> {
+        for record in std::mem::take(&mut self.changed) {
+            for trigger in &self.triggers {
+                if let TriggerAction::After(action) = &trigger.action {
+                    action(database, &record).map_err(|error| TriggerError::Failed {
+                        trigger: trigger.name.clone(),
+                        table: self.table.clone(),
+                        source: Box::new(error),
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A statement changing the records of a table with triggers.
+pub enum TriggeredStmt {
+    Insert(InsertStmt),
+    Delete(DeleteStmt),
+}
+
+/// Executes an `INSERT` or `DELETE` running the triggers of its table.
+///
+/// The AFTER triggers run once the statement has changed the table, so an error
+/// they return leaves that change in place (unless it is made inside a
+/// transaction, which the error rolls back).
+pub struct Triggered<'a, K: DatabaseKey> {
+    pub database: &'a mut Database<K>,
+    pub ast: TriggeredStmt,
+    pub triggers: RowTriggers<K>,
+}
+
+impl<'a, K: DatabaseKey> Execute for Triggered<'a, K> {
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let result = match &self.ast {
+            TriggeredStmt::Insert(ast) => Insert {
+                table: self.database.get_table(&ast.table_name)?,
+                ast: ast.clone(),
+                triggers: Some(&mut self.triggers),
+            }
+            .execute()?,
+            TriggeredStmt::Delete(ast) => Delete {
+                table: self.database.get_table(&ast.table_name)?,
+                ast: ast.clone(),
+                triggers: Some(&mut self.triggers),
+            }
+            .execute()?,
+        };
+
+        self.triggers.after(self.database)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DataValue, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "CREATE audit KEY id FIELDS id: INT, event: STRING, user_id: INT;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    /// Records every `event` on `users` in the `audit` table.
+    fn audit(event: TriggerEvent, name: &str) -> Trigger<i64> {
+        Trigger::after(name, "users", event, move |db, record| {
+            let id = db.tables["audit"].rows.len() + 1;
+            let query = format!(
+                "INSERT id = {id}, event = \"{event:?}\", user_id = {} INTO audit;",
+                record.fields["id"]
+            );
+            execute(db, &query).map(|_| ())
+        })
+    }
+
+    #[test]
+    fn test_after_triggers_fill_audit_table() {
+        let mut db = setup();
+        db.add_trigger(audit(TriggerEvent::Insert, "audit_insert"));
+        db.add_trigger(audit(TriggerEvent::Delete, "audit_delete"));
+
+        for query in [
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+            "INSERT id = 2, name = \"Bob\" INTO users;",
+            "DELETE 1 FROM users;",
+            "DELETE 7 FROM users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+
+        let audit = &db.tables["audit"];
+        assert_eq!(audit.rows.len(), 3);
+        let last = audit.rows.get(&3).unwrap();
+        assert_eq!(
+            *last.fields["event"],
+            DataValue::String("Delete".to_string())
+        );
+        assert_eq!(*last.fields["user_id"], DataValue::Int(1));
+
+        db.rename_table("users", "people").unwrap();
+        assert!(
+            db.triggers()
+                .iter()
+                .all(|trigger| trigger.table == "people")
+        );
+        assert!(db.remove_trigger("audit_insert").is_some());
+        assert!(db.remove_trigger("audit_insert").is_none());
+    }
+
+    #[test]
+    fn test_before_trigger_rejects_change() {
+        let mut db = setup();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        db.add_trigger(Trigger::before(
+            "no_bots",
+            "users",
+            TriggerEvent::Insert,
+            move |record| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                match &*record.fields["name"] {
+                    DataValue::String(name) if name.ends_with("bot") => {
+                        Err(format!("{name} is a bot"))
+                    }
+                    _ => Ok(()),
+                }
+            },
+        ));
+
+        execute(&mut db, "INSERT id = 1, name = \"Alice\" INTO users;").unwrap();
+        let result = execute(&mut db, "INSERT id = 2, name = \"spambot\" INTO users;");
+        assert!(matches!(
+            result,
+            Err(DatabaseError::Trigger(TriggerError::Rejected { ref reason, .. }))
+                if reason == "spambot is a bot"
+        ));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        // a trigger with the same name replaces the previous one
+        let replaced = db.add_trigger(Trigger::before(
+            "no_bots",
+            "users",
+            TriggerEvent::Delete,
+            |_| Err("users cannot be deleted".to_string()),
+        ));
+        assert!(replaced.is_some());
+        assert!(execute(&mut db, "DELETE 1 FROM users;").is_err());
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_failing_after_trigger() {
+        let mut db = setup();
+        db.add_trigger(Trigger::after(
+            "broken",
+            "users",
+            TriggerEvent::Insert,
+            |db, _| execute(db, "INSERT id = 1 INTO missing;").map(|_| ()),
+        ));
+
+        assert!(matches!(
+            execute(&mut db, "INSERT id = 1, name = \"Alice\" INTO users;"),
+            Err(DatabaseError::Trigger(TriggerError::Failed { .. }))
+        ));
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
+}
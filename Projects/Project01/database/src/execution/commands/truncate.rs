@@ -0,0 +1,63 @@
+use crate::{
+    DatabaseResult,
+    core::{DatabaseKey, Table},
+    execution::{Execute, ExecutionResult},
+};
+
+/// Represents an executable `TRUNCATE` command.
+///
+/// The parsed `TruncateStmt` only names the table, so this struct holds just
+/// a mutable reference to the `Table` that will be cleared.
+pub struct Truncate<'a, K: DatabaseKey> {
+    /// A mutable reference to the table whose records will be removed.
+    pub table: &'a mut Table<K>,
+}
+
+impl<'a, K: DatabaseKey> Execute for Truncate<'a, K> {
+    /// Executes the `TRUNCATE` command.
+    ///
+    /// All records are removed at once; the schema, key field, defaults
+    /// and indexes of the table are kept.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let removed = self.table.rows.len();
+        self.table.clear_rows();
+
+        Ok(ExecutionResult::RowsAffected(removed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QueryParser, core::Database, execution::build_execute_command};
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    #[test]
+    fn test_execute_truncate() {
+        let mut db = Database::<i64>::default();
+        execute(&mut db, "CREATE users KEY id FIELDS id: INT, name: STRING;").unwrap();
+        execute(&mut db, "GENERATE 25 INTO users SEED 3;").unwrap();
+
+        let result = execute(&mut db, "TRUNCATE users;").unwrap();
+        assert!(matches!(result, ExecutionResult::RowsAffected(25)));
+        assert!(db.tables["users"].rows.is_empty());
+        assert_eq!(db.tables["users"].schema.len(), 2);
+
+        execute(&mut db, "INSERT id = 1, name = \"Alice\" INTO users;").unwrap();
+        assert_eq!(db.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_truncate_unknown_table() {
+        let mut db = Database::<i64>::default();
+        let result = execute(&mut db, "TRUNCATE users;");
+        assert!(matches!(
+            result,
+            Err(crate::core::DatabaseError::TableNotFound { .. })
+        ));
+    }
+}
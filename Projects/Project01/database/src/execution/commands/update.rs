@@ -0,0 +1,339 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    DatabaseResult,
+    ast::{Assignment, UpdateStmt},
+    core::{DataValue, DatabaseKey, Table, UpdateError},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::{Select, affected_records, check_constraints};
+
+/// Represents an executable `UPDATE` command.
+///
+/// This struct couples the parsed `UpdateStmt` (the "what") with a
+/// mutable reference to the specific `Table` (the "where") needed to
+/// modify the records.
+pub struct Update<'a, K: DatabaseKey> {
+    /// A mutable reference to the table whose records will be modified.
+    pub table: &'a mut Table<K>,
+    /// The parsed AST (Abstract Syntax Tree) for the `UPDATE` statement.
+    pub ast: UpdateStmt,
+}
+
+impl<'a, K: DatabaseKey> Update<'a, K> {
+    /// Checks every assignment against the schema and returns the new values by field name,
+    /// to be shared by every updated record.
+    ///
+    /// # Errors
+    ///
+    /// * `DatabaseError::FieldNotFound` if an assigned field is not in the schema.
+    /// * `DatabaseError::TypeMismatch` if a value does not match the field's type.
+    /// * `DatabaseError::InvalidEnumValue` if a value is not a variant of the field's `ENUM`.
+    /// * `UpdateError::DuplicateAssignment` if a field is assigned more than once.
+    fn validate_assignments(&self) -> DatabaseResult<HashMap<String, Arc<DataValue>>> {
+        let table_name = &self.table.name;
+        let mut new_values = HashMap::with_capacity(self.ast.assignments.len());
+
+        for Assignment { field_name, value } in &self.ast.assignments {
+            self.table.check_value(field_name, value)?;
+
+            if new_values
+                .insert(field_name.clone(), Arc::new(value.clone()))
+                .is_some()
+            {
+                return Err(UpdateError::DuplicateAssignment {
+                    table: table_name.clone(),
+                    field: field_name.clone(),
+                }
+                .into());
+            }
+        }
+
+        Ok(new_values)
+    }
+}
+
+impl<'a, K: DatabaseKey> Execute for Update<'a, K> {
+    /// Executes the `UPDATE` command.
+    ///
+    /// The assignments, the `WHERE` clause and the table's `CHECK` constraints are
+    /// checked for every record before any of them is modified, so a failing
+    /// statement leaves the table unchanged.
+    /// Assigning the key field moves the record to the new key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any validation step fails:
+    /// * `DatabaseError::FieldNotFound`
+    /// * `DatabaseError::TypeMismatch`
+    /// * `DatabaseError::InvalidEnumValue`
+    /// * `UpdateError::DuplicateAssignment`
+    /// * `UpdateError::DuplicateKey` - The new key is already used by another record,
+    ///   or more than one record would receive it.
+    /// * `DatabaseError::FieldNotFound` - A `RETURNING` field is not in the schema.
+    /// * `DatabaseError::ConstraintViolation` - An updated record fails a `CHECK`.
+    /// * `DatabaseError::Io` - An updated record cannot be written to the file of an
+    ///   on-disk table; the records updated before it keep their new values.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let new_values = self.validate_assignments()?;
+        let table_name = self.table.name.clone();
+        let returning = self.ast.returning.as_deref();
+        if let Some(fields) = returning {
+            self.table.check_fields(fields)?;
+        }
+
+        let mut matching_keys = Vec::new();
+        for (key, record) in &self.table.rows {
+            let matches = match &self.ast.where_clause {
+                Some(condition) => {
+                    Select::<K>::evaluate_condition(&record, condition, &table_name)?
+                }
+                None => true,
+            };
+            if matches {
+                matching_keys.push(key.clone());
+            }
+        }
+
+        let new_key = new_values
+            .get(&self.table.key_field)
+            .map(|value| K::from_datavalue(value).unwrap());
+
+        if let Some(new_key) = &new_key {
+            let key_taken = match matching_keys.as_slice() {
+                [] => false,
+                [old_key] => old_key != new_key && self.table.rows.contains_key(new_key),
+                _ => true,
+            };
+            if key_taken {
+                return Err(UpdateError::DuplicateKey {
+                    table: table_name,
+                    key: format!("{new_key:?}"),
+                }
+                .into());
+            }
+        }
+
+        let mut updated_records = Vec::with_capacity(matching_keys.len());
+        for key in &matching_keys {
+            let mut record = self.table.rows.get(key).unwrap().into_owned();
+            record.fields.extend(new_values.clone());
+            check_constraints(self.table, &record)?;
+            updated_records.push(record);
+        }
+
+        for (key, record) in matching_keys.iter().zip(&updated_records) {
+            self.table.remove_row(key);
+            let key = new_key.clone().unwrap_or_else(|| key.clone());
+            self.table.insert_row(key, record.clone())?;
+        }
+
+        let updated_records = updated_records.iter().collect::<Vec<_>>();
+        Ok(affected_records(returning, &updated_records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Database, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn setup_db_i64_with_data() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT;",
+            "INSERT id = 1, name = \"Alice\", age = 30 INTO users;",
+            "INSERT id = 2, name = \"Bob\", age = 25 INTO users;",
+            "INSERT id = 3, name = \"Charlie\", age = 40 INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        db
+    }
+
+    fn age_of(db: &Database<i64>, id: i64) -> DataValue {
+        (*db.tables["users"].rows.get(&id).unwrap().fields["age"]).clone()
+    }
+
+    #[test]
+    fn test_execute_update_with_where() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(&mut db, "UPDATE users SET age = 50 WHERE age >= 30;").unwrap();
+        assert!(matches!(result, ExecutionResult::RowsAffected(2)));
+
+        assert_eq!(age_of(&db, 1), DataValue::Int(50));
+        assert_eq!(age_of(&db, 2), DataValue::Int(25));
+        assert_eq!(age_of(&db, 3), DataValue::Int(50));
+    }
+
+    #[test]
+    fn test_execute_update_all_rows() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(&mut db, "UPDATE users SET age = 1, name = \"X\";").unwrap();
+        assert!(matches!(result, ExecutionResult::RowsAffected(3)));
+        assert!(db.tables["users"].rows.values().all(|record| {
+            *record.fields["age"] == DataValue::Int(1)
+                && *record.fields["name"] == DataValue::String("X".to_string())
+        }));
+    }
+
+    #[test]
+    fn test_execute_update_key() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(&mut db, "UPDATE users SET id = 10 WHERE name = \"Bob\";").unwrap();
+        assert!(matches!(result, ExecutionResult::RowsAffected(1)));
+
+        let rows = &db.tables["users"].rows;
+        assert!(!rows.contains_key(&2));
+        assert_eq!(*rows.get(&10).unwrap().fields["id"], DataValue::Int(10));
+        assert_eq!(
+            *rows.get(&10).unwrap().fields["name"],
+            DataValue::String("Bob".to_string())
+        );
+    }
+
+    #[test]
+    fn test_execute_fail_key_conflict() {
+        let mut db = setup_db_i64_with_data();
+
+        for query in [
+            "UPDATE users SET id = 1 WHERE id = 2;",
+            "UPDATE users SET id = 7 WHERE age > 20;",
+        ] {
+            match execute(&mut db, query).err().unwrap() {
+                DatabaseError::Update(UpdateError::DuplicateKey { table, .. }) => {
+                    assert_eq!(table, "users");
+                }
+                e => panic!("Expected DuplicateKey error, got {e:?}"),
+            }
+        }
+        assert_eq!(
+            db.tables["users"].rows.keys().copied().collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_execute_fail_invalid_assignments() {
+        let mut db = setup_db_i64_with_data();
+
+        match execute(&mut db, "UPDATE users SET email = \"a@b.c\";")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::FieldNotFound { field, .. } => assert_eq!(field, "email"),
+            e => panic!("Expected FieldNotFound error, got {e:?}"),
+        }
+        match execute(&mut db, "UPDATE users SET age = \"old\";")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::TypeMismatch { field, .. } => assert_eq!(field, "age"),
+            e => panic!("Expected TypeMismatch error, got {e:?}"),
+        }
+        match execute(&mut db, "UPDATE users SET age = 1, age = 2;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::Update(UpdateError::DuplicateAssignment { field, .. }) => {
+                assert_eq!(field, "age")
+            }
+            e => panic!("Expected DuplicateAssignment error, got {e:?}"),
+        }
+        assert_eq!(age_of(&db, 1), DataValue::Int(30));
+    }
+
+    #[test]
+    fn test_execute_fail_check_leaves_table_unchanged() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, age: INT CHECK (age >= 0 AND age < 150);",
+            "INSERT id = 1, age = 30 INTO users;",
+            "INSERT id = 2, age = 140 INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+
+        match execute(&mut db, "UPDATE users SET age = 150;")
+            .err()
+            .unwrap()
+        {
+            DatabaseError::ConstraintViolation { table, check, .. } => {
+                assert_eq!(table, "users");
+                assert_eq!(check, "(age >= 0 AND age < 150)");
+            }
+            e => panic!("Expected ConstraintViolation error, got {e:?}"),
+        }
+        assert_eq!(age_of(&db, 1), DataValue::Int(30));
+        assert_eq!(age_of(&db, 2), DataValue::Int(140));
+    }
+
+    #[test]
+    fn test_execute_update_enum() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE tasks KEY id FIELDS id: INT, status: ENUM(\"new\", \"done\") DEFAULT \"new\";",
+            "INSERT id = 1 INTO tasks;",
+            "UPDATE tasks SET status = \"done\";",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+        assert_eq!(
+            *db.tables["tasks"].rows.get(&1).unwrap().fields["status"],
+            DataValue::String("done".to_string())
+        );
+
+        let result = execute(&mut db, "UPDATE tasks SET status = \"lost\";");
+        assert!(matches!(
+            result,
+            Err(DatabaseError::InvalidEnumValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_execute_fail_where_type_mismatch_leaves_table_unchanged() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(&mut db, "UPDATE users SET age = 0 WHERE name > 5;");
+        assert!(matches!(result, Err(DatabaseError::TypeMismatch { .. })));
+        assert_eq!(age_of(&db, 1), DataValue::Int(30));
+    }
+
+    #[test]
+    fn test_execute_update_returning() {
+        let mut db = setup_db_i64_with_data();
+
+        let result = execute(
+            &mut db,
+            "UPDATE users SET age = 41 WHERE age >= 30 RETURNING id, age;",
+        )
+        .unwrap();
+        let ExecutionResult::Data(mut records) = result else {
+            panic!("Expected ExecutionResult::Data");
+        };
+        records.sort_by_key(|record| record.fields["id"].clone());
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].fields.len(), 2);
+        assert_eq!(*records[0].fields["id"], DataValue::Int(1));
+        assert_eq!(*records[1].fields["id"], DataValue::Int(3));
+        assert!(
+            records
+                .iter()
+                .all(|r| *r.fields["age"] == DataValue::Int(41))
+        );
+    }
+}
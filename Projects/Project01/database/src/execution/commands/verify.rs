@@ -0,0 +1,215 @@
+use std::marker::PhantomData;
+
+use crate::{
+    DatabaseResult,
+    ast::VerifyStmt,
+    core::{DatabaseKey, StorageBackend, Table},
+    execution::{Execute, ExecutionResult},
+};
+
+use super::{
+    dump_format::{self, Snapshot},
+    encryption,
+};
+
+/// Represents an executable `VERIFY` command.
+///
+/// Unlike the other commands, it only accesses the storage of the database: the
+/// dump is decoded on its own, with the key type `K` of the database.
+pub struct Verify<'a, K: DatabaseKey> {
+    /// The storage the dump is read from.
+    pub storage: &'a dyn StorageBackend,
+    /// The parsed AST (Abstract Syntax Tree) for the `VERIFY` statement.
+    pub ast: VerifyStmt,
+    key: PhantomData<K>,
+}
+
+impl<'a, K: DatabaseKey> Verify<'a, K> {
+    /// Creates the command verifying the dump of `ast` in `storage`.
+    pub fn new(storage: &'a dyn StorageBackend, ast: VerifyStmt) -> Self {
+        Self {
+            storage,
+            ast,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: DatabaseKey> Execute for Verify<'a, K> {
+    /// Executes the `VERIFY` command.
+    ///
+    /// The dump (written by `DUMP_TO` or `DUMP_TABLE`) is decoded and its checksum
+    /// is compared with its content, without changing the database. Dumps of the
+    /// format versions that predate checksums are only decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `LOAD_FROM` (or `LOAD_TABLE`) reading the dump, in
+    /// particular `DatabaseError::ChecksumMismatch` if it was modified or truncated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ExecutionResult::Messages(report))`, holding the format version
+    /// of the dump followed by the number of records of each of its tables.
+    fn execute(&mut self) -> DatabaseResult<ExecutionResult> {
+        let path = &self.ast.path;
+        let reader = encryption::open_snapshot(self.storage, path, self.ast.passphrase.as_deref())?;
+        let (snapshot, version) = dump_format::decode_any_from::<K>(reader, path)?;
+
+        let checksum = if dump_format::has_checksum(version) {
+            "checksum verified"
+        } else {
+            "no checksum to verify"
+        };
+
+        let (kind, mut tables): (_, Vec<&Table<K>>) = match &snapshot {
+            Snapshot::Database(database) => ("Dump", database.tables.values().collect()),
+            Snapshot::Table(table) => ("Table dump", vec![&**table]),
+        };
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut report = vec![format!(
+            "{kind} '{path}' is valid (format version {version}, {checksum})."
+        )];
+        report.extend(
+            tables
+                .iter()
+                .map(|table| format!("Table '{}': {} record(s).", table.name, table.rows.len())),
+        );
+        Ok(ExecutionResult::Messages(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Database, DatabaseError},
+        execution::build_execute_command,
+    };
+
+    fn execute(db: &mut Database<i64>, query: &str) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        build_execute_command(db, ast)?.execute()
+    }
+
+    fn verify(db: &mut Database<i64>, path: &str) -> DatabaseResult<Vec<String>> {
+        match execute(db, &format!("VERIFY \"{path}\";"))? {
+            ExecutionResult::Messages(messages) => Ok(messages),
+            other => panic!("Expected Messages, got {other:?}"),
+        }
+    }
+
+    fn setup_dump(name: &str) -> (Database<i64>, String) {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "CREATE orders KEY id FIELDS id: INT;",
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+            "INSERT id = 2, name = \"Bob\" INTO users;",
+        ] {
+            execute(&mut db, query).unwrap();
+        }
+
+        let path = std::env::temp_dir().join(format!("{name}_{}.bin", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        execute(&mut db, &format!("DUMP_TO \"{path}\";")).unwrap();
+        (db, path)
+    }
+
+    #[test]
+    fn test_verify_dump() {
+        let (_, path) = setup_dump("verify_dump");
+        let mut db = Database::<i64>::default();
+        execute(&mut db, "CREATE items KEY id FIELDS id: INT;").unwrap();
+
+        let report = verify(&mut db, &path).unwrap();
+        assert_eq!(
+            report[0],
+            format!("Dump '{path}' is valid (format version 4, checksum verified).")
+        );
+        assert_eq!(
+            report[1..],
+            [
+                "Table 'orders': 0 record(s).",
+                "Table 'users': 2 record(s)."
+            ]
+        );
+        assert_eq!(db.tables.len(), 1);
+        assert!(db.tables.contains_key("items"));
+
+        let mut source = Database::<i64>::default();
+        execute(&mut source, "CREATE users KEY id FIELDS id: INT;").unwrap();
+        execute(&mut source, "INSERT id = 7 INTO users;").unwrap();
+        execute(&mut source, &format!("DUMP_TABLE users TO \"{path}\";")).unwrap();
+        let report = verify(&mut db, &path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            report,
+            [
+                format!("Table dump '{path}' is valid (format version 4, checksum verified)."),
+                "Table 'users': 1 record(s).".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let (mut db, path) = setup_dump("verify_corrupted");
+        let bytes = fs::read(&path).unwrap();
+
+        let mut corrupted = bytes.clone();
+        let alice = corrupted.windows(5).position(|w| w == b"Alice").unwrap();
+        corrupted[alice] = b'B';
+        fs::write(&path, &corrupted).unwrap();
+        assert!(matches!(
+            verify(&mut db, &path),
+            Err(DatabaseError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            execute(&mut db, &format!("LOAD_FROM \"{path}\";")),
+            Err(DatabaseError::ChecksumMismatch { .. })
+        ));
+
+        fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(
+            verify(&mut db, &path),
+            Err(DatabaseError::ChecksumMismatch { .. })
+        ));
+        assert_eq!(db.tables["users"].rows.len(), 2);
+
+        // a dump of version 2, which has no checksum
+        let mut legacy = bytes[..8].to_vec();
+        legacy.extend_from_slice(&2u32.to_le_bytes());
+        legacy.extend(dump_format::encode_legacy(&db));
+        fs::write(&path, &legacy).unwrap();
+        let report = verify(&mut db, &path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(report[0].ends_with("(format version 2, no checksum to verify)."));
+    }
+
+    #[test]
+    fn test_verify_encrypted_dump() {
+        let (mut db, path) = setup_dump("verify_encrypted");
+        execute(
+            &mut db,
+            &format!("DUMP_TO \"{path}\" ENCRYPTED WITH \"s3cret\";"),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            verify(&mut db, &path),
+            Err(DatabaseError::Encryption(_))
+        ));
+        let result = execute(&mut db, &format!("VERIFY \"{path}\" WITH \"s3cret\";")).unwrap();
+        fs::remove_file(&path).unwrap();
+        let ExecutionResult::Messages(report) = result else {
+            panic!("Expected Messages");
+        };
+        assert_eq!(report.len(), 3);
+        assert!(report[0].ends_with("checksum verified)."));
+    }
+}
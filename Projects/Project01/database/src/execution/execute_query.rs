@@ -0,0 +1,80 @@
+//! Executing raw queries, parsed with a [`QueryCache`] shared by every database
+//! and session, so that a query repeated anywhere is only parsed once.
+
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use crate::{
+    DatabaseResult, QueryCache,
+    ast::Command,
+    core::{Database, DatabaseKey},
+    execution::{ExecutionResult, build_execute_command},
+};
+
+/// The parser of the queries executed by [`Database::execute`] and
+/// [`Session::execute_query`](crate::execution::Session::execute_query).
+static QUERY_CACHE: LazyLock<Mutex<QueryCache>> = LazyLock::new(Default::default);
+
+/// Parses `query` with the shared [`QueryCache`].
+///
+/// # Errors
+/// Returns the parsing error of `query`, see `QueryParser::parse_query`.
+pub(crate) fn parse_cached(query: &str) -> DatabaseResult<Command> {
+    QUERY_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .parse_query(query)
+}
+
+impl<K: DatabaseKey> Database<K> {
+    /// Parses, validates and executes `query`, recording it in the history.
+    ///
+    /// This is the same as executing the command built by `build_execute_command`
+    /// from the parsed query, which is reused if the same query was executed
+    /// before. The statements of a session (`BEGIN`, `COMMIT`, `ROLLBACK`, `USE`
+    /// and `CREATE_DATABASE`) are executed with [`Session::execute_query`] instead.
+    ///
+    /// [`Session::execute_query`]: crate::execution::Session::execute_query
+    ///
+    /// # Errors
+    /// Returns the parsing error of `query`, or the error of `build_execute_command`
+    /// or of the command.
+    pub fn execute(&mut self, query: &str) -> DatabaseResult<ExecutionResult> {
+        let command = parse_cached(query)?;
+        build_execute_command(self, command)?.execute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataValue, DatabaseError, TransactionError};
+
+    #[test]
+    fn test_database_execute() {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+        ] {
+            db.execute(query).unwrap();
+        }
+
+        let Ok(ExecutionResult::Data(records)) = db.execute("SELECT name FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert_eq!(
+            records[0].get("name"),
+            Some(&DataValue::String("Alice".to_string()))
+        );
+        assert_eq!(db.iter_history().count(), 3);
+
+        assert!(matches!(
+            db.execute("SELECT name FROM;"),
+            Err(DatabaseError::Parse(_))
+        ));
+        assert!(matches!(
+            db.execute("BEGIN;"),
+            Err(DatabaseError::Transaction(TransactionError::Unsupported))
+        ));
+    }
+}
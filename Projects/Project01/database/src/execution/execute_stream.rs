@@ -0,0 +1,20 @@
+use crate::{DatabaseResult, core::Record};
+
+/// A lazily computed sequence of records, each one either a returned record
+/// or the error raised while computing it.
+pub type RecordStream<'s> = Box<dyn Iterator<Item = DatabaseResult<Record>> + 's>;
+
+/// Defines an interface for commands whose records can be produced one at a time.
+///
+/// Unlike [`Execute`](super::Execute), which returns every record at once, this
+/// lets a frontend page through a large result set without materializing it.
+pub trait ExecuteStream {
+    /// Starts the execution of the command, returning a stream of its records.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DatabaseError` if the command is invalid before any record is
+    /// produced (e.g., an unknown column). Errors raised while computing a record
+    /// are yielded by the stream instead.
+    fn execute_stream(&self) -> DatabaseResult<RecordStream<'_>>;
+}
@@ -1,12 +1,17 @@
 use std::fmt;
 
-use crate::core::Record;
+use bincode::{Decode, Encode};
+
+use crate::{
+    DatabaseResult,
+    core::{FromRecord, Record, RecordError},
+};
 
 /// Represents the successful result of executing any database command.
 ///
 /// This enum standardizes the different types of successful outcomes
 /// from various commands.
-#[derive(Debug)]
+#[derive(Debug, Encode, Decode)]
 pub enum ExecutionResult {
     /// Indicates successful execution for commands that do not return data
     /// or modify rows (e.g., `CREATE`, `DUMP_TO`, `SAVE_AS`).
@@ -21,6 +26,24 @@ pub enum ExecutionResult {
     Messages(Vec<String>),
 }
 
+impl ExecutionResult {
+    /// Converts the records of a query into the Rust type `T`, in their order.
+    ///
+    /// # Errors
+    /// * `RecordError::NotData` - The result does not hold records.
+    /// * The errors of [`FromRecord::from_record`], for the first record which
+    ///   cannot be converted.
+    pub fn into_records<T: FromRecord>(self) -> DatabaseResult<Vec<T>> {
+        let result = match self {
+            ExecutionResult::Data(records) => return records.iter().map(T::from_record).collect(),
+            ExecutionResult::Success => "Success".to_string(),
+            ExecutionResult::RowsAffected(n) => format!("{n} row(s) affected"),
+            ExecutionResult::Messages(_) => "messages".to_string(),
+        };
+        Err(RecordError::NotData { result }.into())
+    }
+}
+
 impl fmt::Display for ExecutionResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
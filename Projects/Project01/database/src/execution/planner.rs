@@ -0,0 +1,536 @@
+use std::{borrow::Cow, cmp::Ordering, fmt, ops::Bound};
+
+use crate::{
+    ast::{Comparison, Condition, Operator},
+    core::{DataType, DataValue, DatabaseKey, IndexEntries, IndexKind, Record, Table},
+};
+
+/// The values of a column that an [`AccessPath`] reads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRange {
+    /// The listed values, from `field = value` or `field IN (...)`.
+    Values(Vec<DataValue>),
+    /// The values between two bounds, from `<`, `<=`, `>` and `>=` comparisons.
+    Range {
+        /// The lower bound of the range.
+        lower: Bound<DataValue>,
+        /// The upper bound of the range.
+        upper: Bound<DataValue>,
+    },
+}
+
+/// Describes how the records that a `WHERE` clause may match are fetched from a table.
+///
+/// Whatever the path, the whole `WHERE` clause is still evaluated on every fetched
+/// record; the path only narrows down which records are looked at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessPath {
+    /// Every record of the table is read.
+    FullScan,
+    /// The records are looked up by their primary key.
+    PrimaryKey {
+        /// The name of the key field.
+        field: String,
+        /// The keys to read.
+        range: ValueRange,
+    },
+    /// The records are looked up through a secondary index.
+    Index {
+        /// The name of the index.
+        name: String,
+        /// The name of the indexed column.
+        field: String,
+        /// The values of the indexed column to read.
+        range: ValueRange,
+    },
+}
+
+/// Picks the access path for a query on `table` filtered by `condition`.
+///
+/// Only the comparisons joined by `AND` at the top of the condition are considered.
+/// An equality (or `IN`) on the primary key is preferred, then an equality on an
+/// indexed column, then a range on the primary key and finally a range on an
+/// ordered (`BTREE`) index. Once the table has been analyzed, the path expected to
+/// read the fewest records is picked instead, falling back on that order for ties.
+/// Comparisons whose value does not match the column's type are left to the full
+/// scan, which reports them.
+pub fn plan<K: DatabaseKey>(table: &Table<K>, condition: Option<&Condition>) -> AccessPath {
+    let Some(condition) = condition else {
+        return AccessPath::FullScan;
+    };
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(condition, &mut conjuncts);
+
+    let mut best: Option<(f64, u8, AccessPath)> = None;
+    let mut consider = |rank: u8, path: AccessPath| {
+        let estimate = estimate_rows(table, &path).unwrap_or(0.0);
+        if best.as_ref().is_none_or(|(best_estimate, best_rank, _)| {
+            (estimate, rank) < (*best_estimate, *best_rank)
+        }) {
+            best = Some((estimate, rank, path));
+        }
+    };
+
+    if let Some(range) = column_range(table, &table.key_field, &conjuncts) {
+        let rank = if matches!(range, ValueRange::Values(_)) {
+            0
+        } else {
+            2
+        };
+        consider(
+            rank,
+            AccessPath::PrimaryKey {
+                field: table.key_field.clone(),
+                range,
+            },
+        );
+    }
+
+    let mut indexes = table.indexes.iter().collect::<Vec<_>>();
+    indexes.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, index) in indexes {
+        let Some(range) = column_range(table, &index.field, &conjuncts) else {
+            continue;
+        };
+        let rank = match (&range, index.kind()) {
+            (ValueRange::Values(_), _) => 1,
+            (ValueRange::Range { .. }, IndexKind::BTree) => 3,
+            (ValueRange::Range { .. }, IndexKind::Hash) => continue,
+        };
+        consider(
+            rank,
+            AccessPath::Index {
+                name: name.clone(),
+                field: index.field.clone(),
+                range,
+            },
+        );
+    }
+
+    best.map_or(AccessPath::FullScan, |(_, _, path)| path)
+}
+
+/// Estimates how many records `path` reads from `table`, from the statistics
+/// of its last `ANALYZE`.
+///
+/// # Returns
+/// `None` if the table has not been analyzed.
+pub fn estimate_rows<K: DatabaseKey>(table: &Table<K>, path: &AccessPath) -> Option<f64> {
+    let statistics = table.statistics.as_ref()?;
+    let range_rows = |field: &str, lower: &Bound<DataValue>, upper: &Bound<DataValue>| {
+        let value = |bound: &Bound<DataValue>| match bound {
+            Bound::Included(value) | Bound::Excluded(value) => Some(value.clone()),
+            Bound::Unbounded => None,
+        };
+        let (lower, upper) = (value(lower), value(upper));
+        statistics.row_count as f64
+            * statistics.range_selectivity(field, lower.as_ref(), upper.as_ref())
+    };
+
+    let estimate = match path {
+        AccessPath::FullScan => statistics.row_count as f64,
+        AccessPath::PrimaryKey { field, range } => match range {
+            ValueRange::Values(values) => values.len() as f64,
+            ValueRange::Range { lower, upper } => range_rows(field, lower, upper),
+        },
+        AccessPath::Index { field, range, .. } => match range {
+            ValueRange::Values(values) => values.len() as f64 * statistics.rows_per_value(field),
+            ValueRange::Range { lower, upper } => range_rows(field, lower, upper),
+        },
+    };
+    Some(estimate)
+}
+
+/// The records fetched by [`candidate_rows`].
+pub type CandidateRows<'t> = Box<dyn Iterator<Item = Cow<'t, Record>> + 't>;
+
+/// Fetches the records of `table` selected by `path`, in the order of their keys.
+///
+/// The records are fetched lazily, so those of an on-disk table are only read from
+/// its file (see [`Rows::get`](crate::core::Rows::get)) as the iterator is advanced.
+pub fn candidate_rows<'t, K: DatabaseKey>(
+    table: &'t Table<K>,
+    path: &AccessPath,
+) -> CandidateRows<'t> {
+    match path {
+        AccessPath::FullScan => Box::new(table.rows.values()),
+        AccessPath::PrimaryKey { range, .. } => match range {
+            ValueRange::Values(values) => {
+                let keys = values.iter().filter_map(K::from_datavalue).collect();
+                rows_by_keys(table, keys)
+            }
+            ValueRange::Range { lower, upper } => {
+                if is_empty_range(lower, upper) {
+                    return Box::new(std::iter::empty());
+                }
+                let to_key = |bound: &Bound<DataValue>| {
+                    bound
+                        .as_ref()
+                        .map(|value| K::from_datavalue(value).unwrap())
+                };
+                Box::new(
+                    table
+                        .rows
+                        .range((to_key(lower), to_key(upper)))
+                        .map(|(_, record)| record),
+                )
+            }
+        },
+        AccessPath::Index { name, range, .. } => {
+            let index = &table.indexes[name];
+            let keys = match (range, &index.entries) {
+                (ValueRange::Values(values), _) => values
+                    .iter()
+                    .flat_map(|value| index.get(value))
+                    .cloned()
+                    .collect(),
+                (ValueRange::Range { lower, upper }, IndexEntries::BTree(entries)) => {
+                    if is_empty_range(lower, upper) {
+                        return Box::new(std::iter::empty());
+                    }
+                    entries
+                        .range((lower.clone(), upper.clone()))
+                        .flat_map(|(_, keys)| keys)
+                        .cloned()
+                        .collect()
+                }
+                (ValueRange::Range { .. }, IndexEntries::Hash(_)) => {
+                    return Box::new(table.rows.values());
+                }
+            };
+            rows_by_keys(table, keys)
+        }
+    }
+}
+
+impl ValueRange {
+    /// Renders the range as a condition on the column `field`, e.g. `age >= 18 AND age < 30`.
+    fn describe(&self, field: &str) -> String {
+        match self {
+            Self::Values(values) => match values.as_slice() {
+                [value] => format!("{field} = {value}"),
+                values => {
+                    let values = values
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{field} IN ({values})")
+                }
+            },
+            Self::Range { lower, upper } => {
+                let lower = match lower {
+                    Bound::Included(value) => Some(format!("{field} >= {value}")),
+                    Bound::Excluded(value) => Some(format!("{field} > {value}")),
+                    Bound::Unbounded => None,
+                };
+                let upper = match upper {
+                    Bound::Included(value) => Some(format!("{field} <= {value}")),
+                    Bound::Excluded(value) => Some(format!("{field} < {value}")),
+                    Bound::Unbounded => None,
+                };
+                lower
+                    .into_iter()
+                    .chain(upper)
+                    .collect::<Vec<_>>()
+                    .join(" AND ")
+            }
+        }
+    }
+}
+
+/// Describes the access path for `EXPLAIN`, e.g. `index range scan on 'by_age' (age >= 18)`.
+impl fmt::Display for AccessPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = |range: &ValueRange| match range {
+            ValueRange::Values(_) => "lookup",
+            ValueRange::Range { .. } => "range scan",
+        };
+        match self {
+            Self::FullScan => write!(f, "full scan"),
+            Self::PrimaryKey { field, range } => {
+                write!(f, "primary key {} ({})", kind(range), range.describe(field))
+            }
+            Self::Index { name, field, range } => write!(
+                f,
+                "index {} on '{name}' ({})",
+                kind(range),
+                range.describe(field)
+            ),
+        }
+    }
+}
+
+/// Returns the records stored under `keys`, in key order and without duplicates.
+fn rows_by_keys<K: DatabaseKey>(table: &Table<K>, mut keys: Vec<K>) -> CandidateRows<'_> {
+    keys.sort();
+    keys.dedup();
+    Box::new(keys.into_iter().filter_map(|key| table.rows.get(&key)))
+}
+
+/// Flattens the `AND` operations at the top of `condition`.
+fn collect_conjuncts<'c>(condition: &'c Condition, conjuncts: &mut Vec<&'c Condition>) {
+    match condition {
+        Condition::And { left, right } => {
+            collect_conjuncts(left, conjuncts);
+            collect_conjuncts(right, conjuncts);
+        }
+        condition => conjuncts.push(condition),
+    }
+}
+
+/// Combines the conjuncts restricting the column `field` into the values to read,
+/// or returns `None` if none of them can be served by a lookup.
+///
+/// The shortest list of values wins over any range; otherwise the bounds of all
+/// range comparisons are intersected.
+fn column_range<K: DatabaseKey>(
+    table: &Table<K>,
+    field: &str,
+    conjuncts: &[&Condition],
+) -> Option<ValueRange> {
+    let data_type = *table.schema.get(field)?;
+    let matches_type = |value: &DataValue| value.get_type() == data_type;
+    let orderable = matches!(
+        data_type,
+        DataType::Int | DataType::Float | DataType::String | DataType::Uuid
+    );
+
+    let mut values: Option<&[DataValue]> = None;
+    let mut lower = Bound::Unbounded;
+    let mut upper = Bound::Unbounded;
+
+    for conjunct in conjuncts {
+        match conjunct {
+            Condition::Comparison(Comparison {
+                field: f,
+                op,
+                value,
+            }) if f == field && matches_type(value) => match op {
+                Operator::Eq if values.is_none_or(|values| values.len() > 1) => {
+                    values = Some(std::slice::from_ref(value));
+                }
+                Operator::Gt | Operator::GtEq if orderable => {
+                    let bound = if *op == Operator::Gt {
+                        Bound::Excluded(value.clone())
+                    } else {
+                        Bound::Included(value.clone())
+                    };
+                    lower = tighter(lower, bound, Ordering::Greater);
+                }
+                Operator::Lt | Operator::LtEq if orderable => {
+                    let bound = if *op == Operator::Lt {
+                        Bound::Excluded(value.clone())
+                    } else {
+                        Bound::Included(value.clone())
+                    };
+                    upper = tighter(upper, bound, Ordering::Less);
+                }
+                _ => {}
+            },
+            Condition::In {
+                field: f,
+                values: list,
+            } if f == field
+                && list.iter().all(matches_type)
+                && values.is_none_or(|values| values.len() > list.len()) =>
+            {
+                values = Some(list);
+            }
+            _ => {}
+        }
+    }
+
+    match (values, &lower, &upper) {
+        (Some(values), _, _) => Some(ValueRange::Values(values.to_vec())),
+        (None, Bound::Unbounded, Bound::Unbounded) => None,
+        (None, _, _) => Some(ValueRange::Range { lower, upper }),
+    }
+}
+
+/// Returns the stricter of two bounds on the same side of a range, where `stricter`
+/// is the ordering of the stricter value (`Greater` for lower bounds, `Less` for upper ones).
+fn tighter(
+    current: Bound<DataValue>,
+    new: Bound<DataValue>,
+    stricter: Ordering,
+) -> Bound<DataValue> {
+    match (&current, &new) {
+        (Bound::Unbounded, _) => new,
+        (_, Bound::Unbounded) => current,
+        (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b) | Bound::Excluded(b)) => {
+            match b.cmp(a) {
+                Ordering::Equal if matches!(new, Bound::Excluded(_)) => new,
+                Ordering::Equal => current,
+                ordering if ordering == stricter => new,
+                _ => current,
+            }
+        }
+    }
+}
+
+/// Returns `true` if no value lies between the bounds, which `BTreeMap::range` would reject.
+fn is_empty_range(lower: &Bound<DataValue>, upper: &Bound<DataValue>) -> bool {
+    match (lower, upper) {
+        (Bound::Included(l), Bound::Included(u)) => l > u,
+        (Bound::Included(l) | Bound::Excluded(l), Bound::Included(u) | Bound::Excluded(u)) => {
+            l >= u
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        QueryParser,
+        ast::Command,
+        core::Database,
+        execution::{ExecutionResult, build_execute_command},
+    };
+
+    fn setup_db_i64_with_data() -> Database<i64> {
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING, age: INT, active: BOOLEAN;",
+            "GENERATE 200 INTO users SEED 11;",
+            "CREATE_INDEX by_age ON users(age);",
+            "CREATE_INDEX by_name ON users(name) USING HASH;",
+        ] {
+            let ast = QueryParser.parse_query(query).unwrap();
+            build_execute_command(&mut db, ast)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+        db
+    }
+
+    fn where_clause(condition: &str) -> Condition {
+        let query = format!("SELECT id FROM users WHERE {condition};");
+        match QueryParser.parse_query(&query).unwrap() {
+            Command::Select(stmt) => stmt.optional_clauses.where_clause.unwrap(),
+            command => panic!("Expected Command::Select, got {command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_plan_picks_access_path() {
+        let db = setup_db_i64_with_data();
+        let table = &db.tables["users"];
+        let plan_for = |condition: &str| plan(table, Some(&where_clause(condition)));
+
+        assert_eq!(plan(table, None), AccessPath::FullScan);
+        assert_eq!(plan_for("active = true"), AccessPath::FullScan);
+        assert_eq!(plan_for("age = 30 OR id = 1"), AccessPath::FullScan);
+        assert_eq!(plan_for("age = \"30\""), AccessPath::FullScan);
+        assert_eq!(plan_for("name > \"B\""), AccessPath::FullScan);
+
+        assert_eq!(
+            plan_for("age = 30 AND id IN (1, 2)"),
+            AccessPath::PrimaryKey {
+                field: "id".to_string(),
+                range: ValueRange::Values(vec![DataValue::Int(1), DataValue::Int(2)]),
+            }
+        );
+        assert_eq!(
+            plan_for("id > 5 AND name = \"Bob\""),
+            AccessPath::Index {
+                name: "by_name".to_string(),
+                field: "name".to_string(),
+                range: ValueRange::Values(vec![DataValue::String("Bob".to_string())]),
+            }
+        );
+        assert_eq!(
+            plan_for("age >= 30 AND age < 40 AND age > 30"),
+            AccessPath::Index {
+                name: "by_age".to_string(),
+                field: "age".to_string(),
+                range: ValueRange::Range {
+                    lower: Bound::Excluded(DataValue::Int(30)),
+                    upper: Bound::Excluded(DataValue::Int(40)),
+                },
+            }
+        );
+        assert_eq!(
+            plan_for("age > 30 AND id <= 10"),
+            AccessPath::PrimaryKey {
+                field: "id".to_string(),
+                range: ValueRange::Range {
+                    lower: Bound::Unbounded,
+                    upper: Bound::Included(DataValue::Int(10)),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_plan_uses_statistics() {
+        let mut db = setup_db_i64_with_data();
+        let condition = where_clause("id > 5 AND age = 40");
+
+        // without statistics a range on the key is preferred over an indexed range
+        let table = db.tables.get_mut("users").unwrap();
+        assert!(matches!(
+            plan(table, Some(&where_clause("id > 5 AND age > 40"))),
+            AccessPath::PrimaryKey { .. }
+        ));
+        assert_eq!(estimate_rows(table, &AccessPath::FullScan), None);
+
+        table.analyze();
+        assert!(matches!(
+            plan(table, Some(&where_clause("id > 5 AND age > 80"))),
+            AccessPath::Index { .. }
+        ));
+        assert!(matches!(
+            plan(table, Some(&condition)),
+            AccessPath::Index { .. }
+        ));
+        assert!(matches!(
+            plan(table, Some(&where_clause("id < 3 AND age > 20"))),
+            AccessPath::PrimaryKey { .. }
+        ));
+        assert_eq!(estimate_rows(table, &AccessPath::FullScan), Some(200.0));
+    }
+
+    #[test]
+    fn test_planned_select_matches_full_scan() {
+        let mut db = setup_db_i64_with_data();
+        let conditions = [
+            "id = 17",
+            "id IN (3, 3, 250, 1)",
+            "id > 190",
+            "id >= 20 AND id < 20",
+            "age = 45 AND active = true",
+            "age > 85 OR age < 20",
+            "age >= 30 AND age <= 31",
+            "age > 60 AND age < 40",
+            "name IN (\"Alice Nowak\", \"Bob Smith\")",
+        ];
+
+        let mut select_ids = |condition: &str| {
+            let query = format!("SELECT id FROM users WHERE {condition};");
+            let ast = QueryParser.parse_query(&query).unwrap();
+            let result = build_execute_command(&mut db, ast)
+                .unwrap()
+                .execute()
+                .unwrap();
+            let ExecutionResult::Data(records) = result else {
+                panic!("Expected ExecutionResult::Data");
+            };
+            records
+                .iter()
+                .map(|record| record.fields["id"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        for condition in conditions {
+            let planned = select_ids(condition);
+            // a `NOT` at the top is never planned, so this one scans the whole table
+            let scanned = select_ids(&format!("NOT NOT ({condition})"));
+            assert_eq!(planned, scanned, "for WHERE {condition}");
+        }
+        assert!(!select_ids("age >= 30 AND age <= 31").is_empty());
+    }
+}
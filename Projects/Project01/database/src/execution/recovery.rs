@@ -0,0 +1,619 @@
+//! Crash recovery through a [`WriteAheadLog`].
+//!
+//! [`execute_logged`] records every command that modifies the database in the
+//! log before applying it, and [`recover`] replays the log into a new database.
+//! A successful `DUMP_TO`, `LOAD_FROM` or `IMPORT_JSON` replaces the whole log
+//! with a single entry loading the resulting snapshot, so the log only holds the
+//! changes made after it. An encrypted `DUMP_TO` leaves the log as it is, while
+//! an encrypted `LOAD_FROM` is refused, as neither its passphrase nor the
+//! decrypted snapshot may be written to the log.
+//!
+//! Once the [`Catalog`] holds several databases, the log records their queries
+//! after a `USE` of the database they apply to, and is no longer replaced by the
+//! snapshot of one of them.
+
+use std::path::{self, Path};
+
+use crate::{
+    DatabaseResult,
+    ast::{Command, DumpSource, LoadFromStmt},
+    core::{Catalog, DataValue, Database, DatabaseKey, WriteAheadLog},
+    execution::{ExecutionResult, Session, build_execute_command, build_logged_command},
+};
+
+/// How an executed command is recorded in the log.
+enum LogEntry {
+    /// The command does not modify the database and is not recorded.
+    Skip,
+    /// The query is appended to the log before the command is applied.
+    Append(String),
+    /// The log is replaced by the query once the command has succeeded.
+    Checkpoint(String),
+    /// The command records its changes in the log itself (see
+    /// `build_logged_command`).
+    Delegate,
+}
+
+/// Builds and executes `command` on `database`, recording it in `wal` if it
+/// modifies the database. Without a log, this is the same as executing the
+/// command built by `build_execute_command`.
+///
+/// A `GENERATE` without a `SEED` is given a random one, which is recorded with the
+/// query (and in the history) so that replaying it generates the same records.
+/// A `READ_FROM` or `MIGRATE` records the queries of its scripts instead, a
+/// `LOAD_CSV` an `INSERT` per inserted record and a `LOAD_TABLE` the content of
+/// its dump, so that the log can be replayed once the files they read have
+/// changed. The paths of the other commands reading files are recorded as
+/// absolute paths. If the compaction of `wal` is disabled,
+/// or `wal` holds the queries of a transaction, a `LOAD_FROM` or `IMPORT_JSON` is
+/// appended to it like any other command and a `DUMP_TO` is not recorded.
+///
+/// # Errors
+/// Returns the error of `build_execute_command` or of the command, or
+/// `DatabaseError::Io` if the log cannot be written.
+pub fn execute_logged<K: DatabaseKey>(
+    database: &mut Database<K>,
+    mut command: Command,
+    wal: Option<&mut WriteAheadLog>,
+) -> DatabaseResult<ExecutionResult> {
+    let Some(wal) = wal else {
+        return build_execute_command(database, command)?.execute();
+    };
+
+    if let Command::Generate(stmt) = &mut command
+        && stmt.seed.is_none()
+    {
+        let seed = rand::random();
+        let query = stmt.query.trim_end().trim_end_matches(';').trim_end();
+        // a line comment ending the query would swallow the `SEED` clause
+        let separator = match query.lines().last() {
+            Some(line) if line.contains("--") => "\n",
+            _ => " ",
+        };
+        stmt.query = format!("{query}{separator}SEED {seed};");
+        stmt.seed = Some(seed);
+    }
+
+    let mut entry = log_entry(&command);
+    if (!wal.compaction() || wal.is_transaction())
+        && let LogEntry::Checkpoint(query) = entry
+    {
+        // the snapshot only holds one of the logged databases, or is taken before
+        // the transaction is committed
+        entry = match command {
+            Command::DumpTo(_) => LogEntry::Skip,
+            _ => LogEntry::Append(query),
+        };
+    }
+    if let LogEntry::Delegate = entry {
+        return build_logged_command(database, command, wal)?.execute();
+    }
+    let mut executable = build_execute_command(database, command)?;
+    if let LogEntry::Append(query) = &entry {
+        wal.append(query)?;
+    }
+    let result = executable.execute()?;
+    if let LogEntry::Checkpoint(query) = &entry {
+        wal.checkpoint(query)?;
+    }
+
+    Ok(result)
+}
+
+/// Replays the queries recorded in `wal` on the databases of `catalog`, starting
+/// with the default one, without recording them again.
+///
+/// As commands are recorded before they are applied, a query that failed when it
+/// was first executed fails again; such failures are reported and the remaining
+/// queries are still replayed, reproducing the state before the exit. The queries
+/// of a committed transaction are replayed as a transaction, which is rolled back
+/// if the log ends before its `COMMIT;` entry.
+///
+/// # Errors
+/// Returns `DatabaseError::Io` if the log cannot be read.
+///
+/// # Returns
+/// Returns `Ok(ExecutionResult::Messages(report))`, holding the number of replayed
+/// queries followed by the error of each failed one and of an incomplete transaction.
+pub fn recover<K: DatabaseKey>(
+    catalog: &mut Catalog<K>,
+    wal: &WriteAheadLog,
+) -> DatabaseResult<ExecutionResult> {
+    let entries = wal.entries()?;
+    let mut session = Session::default();
+
+    let mut errors = Vec::new();
+    for (index, query) in entries.iter().enumerate() {
+        if let Err(error) = session.execute_query(catalog, query, None) {
+            errors.push(format!("[Entry {}] {query}: {error}", index + 1));
+        }
+    }
+    if session.abort(catalog) {
+        errors.push("The last transaction was not fully logged and was rolled back.".to_string());
+    }
+    catalog.set_logged_database(session.database_name());
+
+    let mut report = vec![format!(
+        "Replayed {} logged quer{} from '{}'.",
+        entries.len(),
+        if entries.len() == 1 { "y" } else { "ies" },
+        wal.path().display()
+    )];
+    report.extend(errors);
+    Ok(ExecutionResult::Messages(report))
+}
+
+/// Decides how `command` is recorded in the log.
+fn log_entry(command: &Command) -> LogEntry {
+    match command {
+        Command::Create(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::CreateIndex(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Insert(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Delete(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Generate(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Analyze(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Update(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Truncate(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::Rename(stmt) => LogEntry::Append(stmt.query.clone()),
+        Command::LoadCsv(_)
+        | Command::ReadFrom(_)
+        | Command::Migrate(_)
+        | Command::LoadTable(_) => LogEntry::Delegate,
+        // an encrypted snapshot is not loaded by the log, which would need its passphrase
+        Command::DumpTo(stmt) if stmt.passphrase.is_some() => LogEntry::Skip,
+        Command::DumpTo(stmt) => {
+            LogEntry::Checkpoint(format!("LOAD_FROM {};", path_literal(&stmt.path)))
+        }
+        Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::File(path),
+            passphrase: None,
+        }) => LogEntry::Checkpoint(format!("LOAD_FROM {};", path_literal(path))),
+        Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::Bytes(bytes),
+            ..
+        }) => LogEntry::Checkpoint(format!("LOAD_FROM {};", DataValue::Bytes(bytes.clone()))),
+        // refused by the command, which knows it is logged
+        Command::LoadFrom(_) => LogEntry::Delegate,
+        Command::ImportJson(stmt) => {
+            LogEntry::Checkpoint(format!("IMPORT_JSON {};", path_literal(&stmt.path)))
+        }
+        Command::Select(_)
+        | Command::CompoundSelect(_)
+        | Command::Explain(_)
+        | Command::ShowTables(_)
+        | Command::SaveAs(_)
+        | Command::DumpTable(_)
+        | Command::Verify(_)
+        | Command::ExportJson(_) => LogEntry::Skip,
+        // a transaction is recorded by the `TransactionManager` once it is committed
+        Command::Transaction(_) => LogEntry::Skip,
+        // the catalog is recorded by the `Session`
+        Command::CreateDatabase(_) | Command::Use(_) => LogEntry::Skip,
+    }
+}
+
+/// Returns the string literal of `path` as an absolute path, so that the log can
+/// be replayed from another working directory.
+fn path_literal(path: &str) -> String {
+    let path = path::absolute(path)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| Path::new(path).to_string_lossy().into_owned());
+    DataValue::String(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write, path::PathBuf, sync::Arc};
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DEFAULT_DATABASE, DatabaseError, EncryptionError, InMemoryStorage},
+    };
+
+    fn temp_path(name: &str, extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.{extension}", std::process::id()))
+    }
+
+    fn execute(db: &mut Database<i64>, wal: &mut WriteAheadLog, query: &str) -> DatabaseResult<()> {
+        let ast = QueryParser.parse_query(query)?;
+        execute_logged(db, ast, Some(wal)).map(|_| ())
+    }
+
+    fn recovered(wal: &WriteAheadLog) -> (Database<i64>, Vec<String>) {
+        let mut catalog = Catalog::default();
+        let ExecutionResult::Messages(report) = recover(&mut catalog, wal).unwrap() else {
+            panic!("Expected Messages");
+        };
+        (
+            catalog.get(DEFAULT_DATABASE).unwrap().try_clone().unwrap(),
+            report,
+        )
+    }
+
+    #[test]
+    fn test_recover_replays_logged_commands() {
+        let path = temp_path("recovery_replay", "wal");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+            "INSERT id = 1, name = \"Alice\" INTO users;",
+            "GENERATE 20 INTO users -- with random names\n;",
+            "SELECT name FROM users;",
+            "UPDATE users SET name = \"Bob\" WHERE id = 1;",
+        ] {
+            execute(&mut db, &mut wal, query).unwrap();
+        }
+        assert!(
+            execute(
+                &mut db,
+                &mut wal,
+                "INSERT id = 1, name = \"Carol\" INTO users;"
+            )
+            .is_err()
+        );
+        assert!(execute(&mut db, &mut wal, "DELETE 1 FROM orders;").is_err());
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 5);
+        assert!(entries[2].starts_with("GENERATE 20 INTO users -- with random names\nSEED "));
+
+        let (recovered, report) = recovered(&WriteAheadLog::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(report.len(), 2);
+        assert!(report[0].starts_with("Replayed 5 logged queries"));
+        assert!(report[1].starts_with("[Entry 5] INSERT id = 1, name = \"Carol\""));
+    }
+
+    #[test]
+    fn test_dump_to_checkpoints_log() {
+        let path = temp_path("recovery_checkpoint", "wal");
+        let dump = temp_path("recovery_checkpoint", "bin");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;".to_string(),
+            "INSERT id = 1, name = \"Alice\" INTO users;".to_string(),
+            format!("DUMP_TO \"{}\";", dump.to_string_lossy().replace('\\', "/")),
+            "INSERT id = 2, name = \"Bob\" INTO users;".to_string(),
+        ] {
+            execute(&mut db, &mut wal, &query).unwrap();
+        }
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].starts_with("LOAD_FROM \""));
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&dump).unwrap();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(recovered.tables["users"].rows.len(), 2);
+    }
+
+    #[test]
+    fn test_interrupted_dump_keeps_logged_snapshot() {
+        let path = temp_path("recovery_interrupted_dump", "wal");
+        let dump = temp_path("recovery_interrupted_dump", "bin");
+        let dump_path = dump.to_string_lossy().replace('\\', "/");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;".to_string(),
+            "INSERT id = 1, name = \"Alice\" INTO users;".to_string(),
+            format!("DUMP_TO \"{dump_path}\";"),
+            "INSERT id = 2, name = \"Bob\" INTO users;".to_string(),
+        ] {
+            execute(&mut db, &mut wal, &query).unwrap();
+        }
+
+        // the process dies while the next dump to the same file is written
+        let mut writer = db.storage().create(&dump).unwrap();
+        writer.write_all(b"half a snapshot").unwrap();
+        writer.flush().unwrap();
+        std::mem::forget(writer);
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&dump).unwrap();
+        let mut temp = dump.into_os_string();
+        temp.push(".tmp");
+        fs::remove_file(temp).unwrap();
+
+        assert_eq!(report.len(), 1, "{report:?}");
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_encrypted_load_from_is_refused() {
+        let path = temp_path("recovery_encrypted", "wal");
+        let dump = temp_path("recovery_encrypted", "bin");
+        let dump_path = dump.to_string_lossy().replace('\\', "/");
+        let passphrase = "correct horse battery staple";
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut source = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;".to_string(),
+            "INSERT id = 1, name = \"Alice\" INTO users;".to_string(),
+            format!("DUMP_TO \"{dump_path}\" ENCRYPTED WITH \"{passphrase}\";"),
+        ] {
+            source.execute(&query).unwrap();
+        }
+
+        let mut db = Database::<i64>::default();
+        execute(&mut db, &mut wal, "CREATE orders KEY id FIELDS id: INT;").unwrap();
+        let result = execute(
+            &mut db,
+            &mut wal,
+            &format!("LOAD_FROM \"{dump_path}\" WITH \"{passphrase}\";"),
+        );
+        fs::remove_file(&dump).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(DatabaseError::Encryption(EncryptionError::Logged { .. }))
+        ));
+        assert!(db.tables.contains_key("orders"));
+        assert!(!db.tables.contains_key("users"));
+
+        let bytes = fs::read(&path).unwrap();
+        assert!(!bytes.windows(5).any(|window| window == b"Alice"));
+        assert!(
+            !bytes
+                .windows(passphrase.len())
+                .any(|window| window == passphrase.as_bytes())
+        );
+        assert_eq!(
+            wal.entries().unwrap(),
+            ["CREATE orders KEY id FIELDS id: INT;"]
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_bytes_is_logged_as_is() {
+        let path = temp_path("recovery_load_bytes", "wal");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut source = Database::<i64>::default();
+        source
+            .execute("CREATE users KEY id FIELDS id: INT;")
+            .unwrap();
+        source.execute("INSERT id = 1 INTO users;").unwrap();
+        let dump = temp_path("recovery_load_bytes", "bin");
+        let dump_path = dump.to_string_lossy().replace('\\', "/");
+        source
+            .execute(&format!("DUMP_TO \"{dump_path}\";"))
+            .unwrap();
+        let query = format!("LOAD_FROM {};", DataValue::Bytes(fs::read(&dump).unwrap()));
+        fs::remove_file(&dump).unwrap();
+
+        let mut db = Database::<i64>::default();
+        execute(&mut db, &mut wal, &query).unwrap();
+        assert_eq!(wal.entries().unwrap(), [query]);
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, source.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_read_from_logs_queries_of_script() {
+        let path = temp_path("recovery_read_from", "wal");
+        let script = temp_path("recovery_read_from", "sql");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        fs::write(
+            &script,
+            "CREATE users KEY id FIELDS id: INT, name: STRING;\n\
+             SELECT name FROM users;\n\
+             BEGIN;\n\
+             INSERT id = 1, name = \"Alice\" INTO users;\n\
+             COMMIT;\n",
+        )
+        .unwrap();
+
+        let mut db = Database::<i64>::default();
+        let query = format!(
+            "READ_FROM \"{}\";",
+            script.to_string_lossy().replace('\\', "/")
+        );
+        execute(&mut db, &mut wal, &query).unwrap();
+        fs::remove_file(&script).unwrap();
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(
+            entries,
+            [
+                "CREATE users KEY id FIELDS id: INT, name: STRING;",
+                "BEGIN;",
+                "INSERT id = 1, name = \"Alice\" INTO users;",
+                "COMMIT;",
+            ]
+        );
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(recovered.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_load_csv_logs_inserted_records() {
+        let path = temp_path("recovery_load_csv", "wal");
+        let csv = temp_path("recovery_load_csv", "csv");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        fs::write(
+            &csv,
+            "id,name\n1,Alice\n2,\"Smith, \"\"Jr\"\" \\\"\n1,Duplicate\n",
+        )
+        .unwrap();
+
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            &mut wal,
+            "CREATE users KEY id FIELDS id: INT, name: STRING, score: FLOAT DEFAULT 0.0;",
+        )
+        .unwrap();
+        let query = format!(
+            "LOAD_CSV \"{}\" INTO users;",
+            csv.to_string_lossy().replace('\\', "/")
+        );
+        execute(&mut db, &mut wal, &query).unwrap();
+        fs::write(&csv, "id,name\n3,Carol\n").unwrap();
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[1], "BEGIN;");
+        assert_eq!(
+            entries[2],
+            "INSERT id = 1, name = \"Alice\", score = 0.0 INTO users;"
+        );
+        assert_eq!(entries[4], "COMMIT;");
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&csv).unwrap();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(
+            *recovered.tables["users"].rows.get(&2).unwrap().fields["name"],
+            DataValue::String("Smith, \"Jr\" \\".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_table_logs_dump() {
+        let path = temp_path("recovery_load_table", "wal");
+        let dump = temp_path("recovery_load_table", "bin");
+        let dump_path = dump.to_string_lossy().replace('\\', "/");
+        let _ = fs::remove_file(&path);
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut source = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, name: STRING;".to_string(),
+            "INSERT id = 1, name = \"Alice\" INTO users;".to_string(),
+            format!("DUMP_TABLE users TO \"{dump_path}\";"),
+        ] {
+            source.execute(&query).unwrap();
+        }
+
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            &mut wal,
+            &format!("LOAD_TABLE FROM \"{dump_path}\" ON CONFLICT UPDATE;"),
+        )
+        .unwrap();
+        fs::remove_file(&dump).unwrap();
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].starts_with("LOAD_TABLE FROM 0x"));
+        assert!(entries[0].ends_with(" ON CONFLICT UPDATE;"));
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, source.tables["users"].rows);
+    }
+
+    #[test]
+    fn test_migrate_logs_queries_of_applied_scripts() {
+        let path = temp_path("recovery_migrate", "wal");
+        let dir = temp_path("recovery_migrate", "d");
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("1_create_users.sql"),
+            "CREATE users KEY id FIELDS id: INT, name: STRING;\n\
+             INSERT id = 1, name = \"Alice\" INTO users;",
+        )
+        .unwrap();
+        fs::write(dir.join("2_fails.sql"), "INSERT id = 1 INTO orders;").unwrap();
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+
+        let mut db = Database::<i64>::default();
+        let query = format!("MIGRATE \"{}\";", dir.to_string_lossy().replace('\\', "/"));
+        assert!(execute(&mut db, &mut wal, &query).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 6);
+        assert!(entries[0].starts_with("CREATE __migrations "));
+        assert_eq!(entries[1], "BEGIN;");
+        assert_eq!(entries[3], "INSERT id = 1, name = \"Alice\" INTO users;");
+        assert!(entries[4].starts_with("INSERT version = 1, "));
+        assert_eq!(entries[5], "COMMIT;");
+
+        let (recovered, report) = recovered(&wal);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(recovered.tables["users"].rows, db.tables["users"].rows);
+        assert_eq!(
+            recovered.tables["__migrations"].rows,
+            db.tables["__migrations"].rows
+        );
+    }
+
+    #[test]
+    fn test_read_only_script_is_not_logged() {
+        let script = temp_path("recovery_read_only", "sql");
+        fs::write(&script, "SELECT name FROM users;\nSHOW TABLES;\n").unwrap();
+        let mut wal =
+            WriteAheadLog::open_with("wal", Arc::new(InMemoryStorage::default())).unwrap();
+
+        let mut db = Database::<i64>::default();
+        execute(
+            &mut db,
+            &mut wal,
+            "CREATE users KEY id FIELDS id: INT, name: STRING;",
+        )
+        .unwrap();
+        let query = format!(
+            "READ_FROM \"{}\";",
+            script.to_string_lossy().replace('\\', "/")
+        );
+        execute(&mut db, &mut wal, &query).unwrap();
+        fs::remove_file(&script).unwrap();
+
+        assert_eq!(wal.entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_execute_without_log() {
+        let mut db = Database::<i64>::default();
+        let ast = QueryParser
+            .parse_query("CREATE users KEY id FIELDS id: INT;")
+            .unwrap();
+        assert!(matches!(
+            execute_logged(&mut db, ast, None),
+            Ok(ExecutionResult::Success)
+        ));
+        assert!(db.tables.contains_key("users"));
+    }
+}
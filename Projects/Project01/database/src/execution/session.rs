@@ -0,0 +1,299 @@
+//! Sessions executing queries on the databases of a [`Catalog`].
+//!
+//! A session (the REPL, the TUI, a script or a client of the server) starts in
+//! the [`DEFAULT_DATABASE`] and executes every command on its current database,
+//! which `USE` changes. `CREATE_DATABASE` adds a database to the catalog and is
+//! recorded in the write-ahead log like any other change; the queries of another
+//! database are recorded after a `USE` of it, so that [`recover`] replays them on
+//! the right database. Neither can be executed inside a transaction.
+//!
+//! [`recover`]: crate::execution::recover
+
+use crate::{
+    DatabaseResult,
+    ast::{Command, UseStmt},
+    core::{
+        Catalog, CatalogError, DEFAULT_DATABASE, Database, DatabaseKey, TransactionError,
+        WriteAheadLog,
+    },
+    execution::{ExecutionResult, TransactionManager, parse_cached},
+};
+
+/// Executes the queries of a session on its current database, keeping track of
+/// its transaction.
+pub struct Session<K: DatabaseKey> {
+    /// The name of the current database.
+    database: String,
+    /// The executor of the queries, tracking the transaction in progress.
+    transactions: TransactionManager<K>,
+}
+
+impl<K: DatabaseKey> Default for Session<K> {
+    fn default() -> Self {
+        Self {
+            database: DEFAULT_DATABASE.to_string(),
+            transactions: TransactionManager::default(),
+        }
+    }
+}
+
+impl<K: DatabaseKey> Session<K> {
+    /// Returns the name of the current database.
+    pub fn database_name(&self) -> &str {
+        &self.database
+    }
+
+    /// Returns the current database of `catalog`.
+    ///
+    /// # Panics
+    /// Panics if `catalog` is not the catalog the session was used with.
+    pub fn database<'c>(&self, catalog: &'c Catalog<K>) -> &'c Database<K> {
+        catalog
+            .get(&self.database)
+            .expect("the current database of a session is in its catalog")
+    }
+
+    /// Returns the current database of `catalog` mutably.
+    ///
+    /// # Panics
+    /// Panics if `catalog` is not the catalog the session was used with.
+    pub fn database_mut<'c>(&self, catalog: &'c mut Catalog<K>) -> &'c mut Database<K> {
+        catalog
+            .get_mut(&self.database)
+            .expect("the current database of a session is in its catalog")
+    }
+
+    /// Returns `true` if a transaction is in progress.
+    pub fn in_transaction(&self) -> bool {
+        self.transactions.in_transaction()
+    }
+
+    /// Executes `command` on the current database of `catalog`, as
+    /// [`TransactionManager::execute`] does, recording it in `wal`.
+    ///
+    /// # Errors
+    /// * `CatalogError::AlreadyExists` - `CREATE_DATABASE` of an existing database.
+    /// * `CatalogError::NotFound` - `USE` of a database missing from the catalog.
+    /// * `TransactionError::NotAllowed` - `CREATE_DATABASE` or `USE` inside a
+    ///   transaction.
+    /// * `DatabaseError::Io` - The command cannot be recorded in `wal`.
+    /// * The errors of [`TransactionManager::execute`].
+    pub fn execute(
+        &mut self,
+        catalog: &mut Catalog<K>,
+        command: Command,
+        wal: Option<&mut WriteAheadLog>,
+    ) -> DatabaseResult<ExecutionResult> {
+        match command {
+            Command::CreateDatabase(stmt) => {
+                self.check_outside_transaction("CREATE_DATABASE")?;
+                if catalog.get(&stmt.name).is_some() {
+                    return Err(CatalogError::AlreadyExists { name: stmt.name }.into());
+                }
+                if let Some(wal) = wal {
+                    wal.append(&stmt.query)?;
+                }
+                catalog.create(&stmt.name)?;
+                Ok(ExecutionResult::Success)
+            }
+            Command::Use(stmt) => {
+                self.check_outside_transaction("USE")?;
+                if catalog.get(&stmt.name).is_none() {
+                    return Err(CatalogError::NotFound { name: stmt.name }.into());
+                }
+                self.database = stmt.name;
+                Ok(ExecutionResult::Success)
+            }
+            command => {
+                let mut wal = wal;
+                if let Some(wal) = wal.as_deref_mut() {
+                    wal.set_compaction(catalog.len() == 1);
+                    if catalog.logged_database() != self.database {
+                        let stmt = UseStmt {
+                            name: self.database.clone(),
+                        };
+                        wal.append(&stmt.to_string())?;
+                        catalog.set_logged_database(&self.database);
+                    }
+                }
+
+                let database = self.database_mut(catalog);
+                self.transactions.execute(database, command, wal)
+            }
+        }
+    }
+
+    /// Parses `query` and executes it as [`Session::execute`] does, reusing the
+    /// AST of an identical earlier query, as [`Database::execute`] does.
+    ///
+    /// # Errors
+    /// Returns the parsing error of `query`, or the errors of [`Session::execute`].
+    pub fn execute_query(
+        &mut self,
+        catalog: &mut Catalog<K>,
+        query: &str,
+        wal: Option<&mut WriteAheadLog>,
+    ) -> DatabaseResult<ExecutionResult> {
+        let command = parse_cached(query)?;
+        self.execute(catalog, command, wal)
+    }
+
+    /// Ends the transaction in progress, if any, undoing its changes.
+    ///
+    /// # Returns
+    /// `true` if a transaction was rolled back.
+    pub fn abort(&mut self, catalog: &mut Catalog<K>) -> bool {
+        let database = self.database_mut(catalog);
+        self.transactions.abort(database)
+    }
+
+    /// Returns `TransactionError::NotAllowed` for `statement` if a transaction
+    /// is in progress.
+    fn check_outside_transaction(&self, statement: &str) -> DatabaseResult<()> {
+        if self.in_transaction() {
+            return Err(TransactionError::NotAllowed {
+                statement: statement.to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{DatabaseError, InMemoryStorage},
+        execution::{build_execute_command, recover},
+    };
+
+    fn execute(
+        session: &mut Session<i64>,
+        catalog: &mut Catalog<i64>,
+        wal: Option<&mut WriteAheadLog>,
+        query: &str,
+    ) -> DatabaseResult<ExecutionResult> {
+        session.execute_query(catalog, query, wal)
+    }
+
+    #[test]
+    fn test_create_and_use_database() {
+        let mut session = Session::default();
+        let mut catalog = Catalog::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT;",
+            "CREATE_DATABASE analytics;",
+            "USE analytics;",
+            "CREATE events KEY id FIELDS id: INT;",
+            "INSERT id = 1 INTO events;",
+        ] {
+            execute(&mut session, &mut catalog, None, query).unwrap();
+        }
+
+        assert_eq!(session.database_name(), "analytics");
+        assert_eq!(catalog.names().collect::<Vec<_>>(), ["analytics", "main"]);
+        assert!(!session.database(&catalog).tables.contains_key("users"));
+        assert!(!catalog.get("main").unwrap().tables.contains_key("events"));
+
+        execute(&mut session, &mut catalog, None, "USE main;").unwrap();
+        assert!(session.database(&catalog).tables.contains_key("users"));
+    }
+
+    #[test]
+    fn test_catalog_errors() {
+        let mut session = Session::default();
+        let mut catalog = Catalog::<i64>::default();
+        execute(
+            &mut session,
+            &mut catalog,
+            None,
+            "CREATE_DATABASE analytics;",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            execute(
+                &mut session,
+                &mut catalog,
+                None,
+                "CREATE_DATABASE analytics;"
+            ),
+            Err(DatabaseError::Catalog(CatalogError::AlreadyExists { .. }))
+        ));
+        assert!(matches!(
+            execute(&mut session, &mut catalog, None, "USE sales;"),
+            Err(DatabaseError::Catalog(CatalogError::NotFound { .. }))
+        ));
+
+        execute(&mut session, &mut catalog, None, "BEGIN;").unwrap();
+        assert!(matches!(
+            execute(&mut session, &mut catalog, None, "USE analytics;"),
+            Err(DatabaseError::Transaction(
+                TransactionError::NotAllowed { .. }
+            ))
+        ));
+        assert_eq!(session.database_name(), DEFAULT_DATABASE);
+
+        assert!(matches!(
+            QueryParser
+                .parse_query("USE analytics;")
+                .and_then(|ast| build_execute_command(session.database_mut(&mut catalog), ast)),
+            Err(DatabaseError::Catalog(CatalogError::Unsupported { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_databases_are_recovered() {
+        let storage = Arc::new(InMemoryStorage::default());
+        let mut wal = WriteAheadLog::open_with("db.wal", storage.clone()).unwrap();
+        let mut session = Session::default();
+        let mut catalog = Catalog::<i64>::new(Database::with_storage(storage.clone()));
+        for query in [
+            "CREATE users KEY id FIELDS id: INT;",
+            "CREATE_DATABASE analytics;",
+            "USE analytics;",
+            "CREATE events KEY id FIELDS id: INT;",
+            "BEGIN;",
+            "INSERT id = 1 INTO events;",
+            "COMMIT;",
+            "DUMP_TO \"analytics.bin\";",
+            "USE main;",
+            "INSERT id = 7 INTO users;",
+        ] {
+            execute(&mut session, &mut catalog, Some(&mut wal), query).unwrap();
+        }
+
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries[1], "CREATE_DATABASE analytics;");
+        assert_eq!(entries[2], "USE analytics;");
+        assert_eq!(entries[entries.len() - 2], "USE main;");
+
+        let mut recovered = Catalog::<i64>::default();
+        recover(&mut recovered, &wal).unwrap();
+        assert_eq!(
+            recovered.get("main").unwrap().tables["users"].rows,
+            catalog.get("main").unwrap().tables["users"].rows
+        );
+        assert!(!recovered.get("main").unwrap().tables.contains_key("events"));
+        assert_eq!(
+            recovered.get("analytics").unwrap().tables["events"].rows,
+            catalog.get("analytics").unwrap().tables["events"].rows
+        );
+        assert_eq!(recovered.logged_database(), "main");
+
+        // the next session starts in the default database, whose queries need no `USE`
+        let mut session = Session::default();
+        execute(
+            &mut session,
+            &mut recovered,
+            Some(&mut wal),
+            "INSERT id = 8 INTO users;",
+        )
+        .unwrap();
+        assert_eq!(wal.entries().unwrap().len(), entries.len() + 1);
+    }
+}
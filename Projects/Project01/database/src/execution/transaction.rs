@@ -0,0 +1,338 @@
+//! Transactions started with `BEGIN` and ended with `COMMIT` or `ROLLBACK`.
+//!
+//! `BEGIN` takes a snapshot of the database, as `MIGRATE` does for each script,
+//! which `ROLLBACK` restores. A query failing inside the transaction restores it
+//! as well and ends the transaction, so that no partial changes are left behind.
+//!
+//! The queries of a transaction are recorded in an in-memory log, which `COMMIT`
+//! appends to the write-ahead log with a single write, between a `BEGIN;` and a
+//! `COMMIT;` entry. [`recover`] replays such entries as a transaction, so that a
+//! commit cut short by a crash is rolled back. The commands replacing the log
+//! (`DUMP_TO`, `LOAD_FROM` and `IMPORT_JSON`) cannot be executed inside a
+//! transaction.
+//!
+//! [`recover`]: crate::execution::recover
+
+use crate::{
+    DatabaseResult,
+    ast::{Command, TransactionStmt},
+    core::{Database, DatabaseKey, TransactionError, WriteAheadLog},
+    execution::{ExecutionResult, execute_logged},
+};
+
+/// A transaction in progress.
+struct Transaction<K: DatabaseKey> {
+    /// The database as it was before `BEGIN`.
+    snapshot: Database<K>,
+    /// The queries executed in the transaction, as they are recorded in the
+    /// write-ahead log.
+    log: WriteAheadLog,
+}
+
+/// Executes the queries of a session (the REPL, the TUI or a script), keeping
+/// track of its transaction.
+pub struct TransactionManager<K: DatabaseKey> {
+    /// The transaction in progress, if any.
+    transaction: Option<Transaction<K>>,
+}
+
+impl<K: DatabaseKey> Default for TransactionManager<K> {
+    fn default() -> Self {
+        Self { transaction: None }
+    }
+}
+
+impl<K: DatabaseKey> TransactionManager<K> {
+    /// Returns `true` if a transaction is in progress.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Executes `command` on `database`, as [`execute_logged`] does outside of
+    /// a transaction.
+    ///
+    /// # Errors
+    /// * `TransactionError::AlreadyInProgress` - `BEGIN` inside a transaction.
+    /// * `TransactionError::NotInProgress` - `COMMIT` or `ROLLBACK` outside of one.
+    /// * `TransactionError::NotAllowed` - `DUMP_TO`, `LOAD_FROM` or `IMPORT_JSON`
+    ///   inside a transaction.
+    /// * `TransactionError::RolledBack` - The command failed inside a transaction,
+    ///   which was rolled back.
+    /// * `DatabaseError::Io` - `BEGIN` cannot take the snapshot of an on-disk table,
+    ///   or the committed queries cannot be written to `wal`, in which case the
+    ///   transaction is rolled back.
+    /// * The errors of [`execute_logged`] outside of a transaction.
+    pub fn execute(
+        &mut self,
+        database: &mut Database<K>,
+        command: Command,
+        wal: Option<&mut WriteAheadLog>,
+    ) -> DatabaseResult<ExecutionResult> {
+        let command = match command {
+            Command::Transaction(TransactionStmt::Begin) => return self.begin(database),
+            Command::Transaction(TransactionStmt::Commit) => return self.commit(database, wal),
+            Command::Transaction(TransactionStmt::Rollback) => return self.rollback(database),
+            command => command,
+        };
+
+        let Some(transaction) = &mut self.transaction else {
+            return execute_logged(database, command, wal);
+        };
+
+        let statement = match &command {
+            Command::DumpTo(_) => Some("DUMP_TO"),
+            Command::LoadFrom(_) => Some("LOAD_FROM"),
+            Command::ImportJson(_) => Some("IMPORT_JSON"),
+            _ => None,
+        };
+        if let Some(statement) = statement {
+            return Err(TransactionError::NotAllowed {
+                statement: statement.to_string(),
+            }
+            .into());
+        }
+
+        let result = execute_logged(database, command, Some(&mut transaction.log));
+        if let Err(error) = result {
+            self.rollback(database)?;
+            return Err(TransactionError::RolledBack {
+                source: Box::new(error),
+            }
+            .into());
+        }
+        result
+    }
+
+    /// Ends the transaction in progress, if any, undoing its changes.
+    ///
+    /// # Returns
+    /// `true` if a transaction was rolled back.
+    pub fn abort(&mut self, database: &mut Database<K>) -> bool {
+        self.rollback(database).is_ok()
+    }
+
+    /// Starts a transaction.
+    fn begin(&mut self, database: &Database<K>) -> DatabaseResult<ExecutionResult> {
+        if self.transaction.is_some() {
+            return Err(TransactionError::AlreadyInProgress.into());
+        }
+
+        let log = WriteAheadLog::for_transaction()?;
+        self.transaction = Some(Transaction {
+            snapshot: database.try_clone()?,
+            log,
+        });
+        Ok(ExecutionResult::Success)
+    }
+
+    /// Ends the transaction, recording its queries in `wal`.
+    fn commit(
+        &mut self,
+        database: &mut Database<K>,
+        wal: Option<&mut WriteAheadLog>,
+    ) -> DatabaseResult<ExecutionResult> {
+        let transaction = self.take("COMMIT")?;
+
+        let entries = transaction.log.entries()?;
+        if let Some(wal) = wal
+            && let Err(error) = append_committed(wal, &entries)
+        {
+            *database = transaction.snapshot;
+            return Err(error);
+        }
+
+        Ok(ExecutionResult::Success)
+    }
+
+    /// Ends the transaction, restoring the database to its state before `BEGIN`.
+    fn rollback(&mut self, database: &mut Database<K>) -> DatabaseResult<ExecutionResult> {
+        let transaction = self.take("ROLLBACK")?;
+        *database = transaction.snapshot;
+        Ok(ExecutionResult::Success)
+    }
+
+    /// Takes the transaction in progress, to be ended by `statement`.
+    fn take(&mut self, statement: &str) -> DatabaseResult<Transaction<K>> {
+        self.transaction.take().ok_or_else(|| {
+            TransactionError::NotInProgress {
+                statement: statement.to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+/// Appends the queries of a committed transaction to `wal` with a single write,
+/// between a `BEGIN;` and a `COMMIT;` entry, so that [`recover`] rolls them back
+/// if the write is cut short. The queries are appended as they are to the log of
+/// an enclosing transaction, which is committed as a whole. Nothing is appended
+/// for a transaction without queries.
+///
+/// [`recover`]: crate::execution::recover
+///
+/// # Errors
+/// Returns `DatabaseError::Io` if the entries cannot be written.
+pub(crate) fn append_committed(wal: &mut WriteAheadLog, entries: &[String]) -> DatabaseResult<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if wal.is_transaction() {
+        return wal.append_all(entries);
+    }
+
+    let begin = TransactionStmt::Begin.to_string();
+    let commit = TransactionStmt::Commit.to_string();
+    let queries = std::iter::once(&begin)
+        .chain(entries)
+        .chain(std::iter::once(&commit));
+    wal.append_all(queries)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use super::*;
+    use crate::{
+        QueryParser,
+        core::{Catalog, DEFAULT_DATABASE, DatabaseError, InMemoryStorage, StorageBackend},
+        execution::recover,
+    };
+
+    fn execute(
+        transactions: &mut TransactionManager<i64>,
+        db: &mut Database<i64>,
+        wal: Option<&mut WriteAheadLog>,
+        query: &str,
+    ) -> DatabaseResult<ExecutionResult> {
+        let ast = QueryParser.parse_query(query)?;
+        transactions.execute(db, ast, wal)
+    }
+
+    fn setup() -> (TransactionManager<i64>, Database<i64>) {
+        let mut transactions = TransactionManager::default();
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT, age: INT CHECK (age >= 0);",
+            "INSERT id = 1, age = 30 INTO users;",
+        ] {
+            execute(&mut transactions, &mut db, None, query).unwrap();
+        }
+        (transactions, db)
+    }
+
+    #[test]
+    fn test_commit_and_rollback() {
+        let (mut transactions, mut db) = setup();
+        for query in [
+            "BEGIN;",
+            "INSERT id = 2, age = 40 INTO users;",
+            "CREATE orders KEY id FIELDS id: INT;",
+        ] {
+            execute(&mut transactions, &mut db, None, query).unwrap();
+        }
+        assert!(transactions.in_transaction());
+        assert_eq!(db.tables["users"].rows.len(), 2);
+
+        execute(&mut transactions, &mut db, None, "ROLLBACK;").unwrap();
+        assert!(!transactions.in_transaction());
+        assert_eq!(db.tables["users"].rows.len(), 1);
+        assert!(!db.tables.contains_key("orders"));
+
+        for query in ["BEGIN;", "DELETE 1 FROM users;", "COMMIT;"] {
+            execute(&mut transactions, &mut db, None, query).unwrap();
+        }
+        assert!(db.tables["users"].rows.is_empty());
+    }
+
+    #[test]
+    fn test_failed_query_rolls_back_transaction() {
+        let (mut transactions, mut db) = setup();
+        for query in ["BEGIN;", "INSERT id = 2, age = 40 INTO users;"] {
+            execute(&mut transactions, &mut db, None, query).unwrap();
+        }
+
+        assert!(matches!(
+            execute(
+                &mut transactions,
+                &mut db,
+                None,
+                "INSERT id = 3, age = -1 INTO users;"
+            ),
+            Err(DatabaseError::Transaction(
+                TransactionError::RolledBack { .. }
+            ))
+        ));
+        assert!(!transactions.in_transaction());
+        assert_eq!(db.tables["users"].rows.len(), 1);
+        assert_eq!(db.iter_history().count(), 2);
+    }
+
+    #[test]
+    fn test_transaction_errors() {
+        let (mut transactions, mut db) = setup();
+        assert!(matches!(
+            execute(&mut transactions, &mut db, None, "COMMIT;"),
+            Err(DatabaseError::Transaction(
+                TransactionError::NotInProgress { .. }
+            ))
+        ));
+
+        execute(&mut transactions, &mut db, None, "BEGIN;").unwrap();
+        assert!(matches!(
+            execute(&mut transactions, &mut db, None, "BEGIN;"),
+            Err(DatabaseError::Transaction(
+                TransactionError::AlreadyInProgress
+            ))
+        ));
+        assert!(matches!(
+            execute(&mut transactions, &mut db, None, "DUMP_TO \"db.bin\";"),
+            Err(DatabaseError::Transaction(
+                TransactionError::NotAllowed { .. }
+            ))
+        ));
+        assert!(transactions.in_transaction());
+    }
+
+    #[test]
+    fn test_commit_is_logged_as_transaction() {
+        let storage = Arc::new(InMemoryStorage::default());
+        let mut wal = WriteAheadLog::open_with("db.wal", storage.clone()).unwrap();
+        let mut transactions = TransactionManager::default();
+        let mut db = Database::<i64>::default();
+        for query in [
+            "CREATE users KEY id FIELDS id: INT;",
+            "BEGIN;",
+            "INSERT id = 1 INTO users;",
+            "INSERT id = 2 INTO users;",
+        ] {
+            execute(&mut transactions, &mut db, Some(&mut wal), query).unwrap();
+        }
+        assert_eq!(wal.entries().unwrap().len(), 1);
+
+        execute(&mut transactions, &mut db, Some(&mut wal), "COMMIT;").unwrap();
+        let entries = wal.entries().unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[1], "BEGIN;");
+        assert_eq!(entries[4], "COMMIT;");
+
+        let mut recovered = Catalog::<i64>::default();
+        recover(&mut recovered, &wal).unwrap();
+        let database = recovered.get(DEFAULT_DATABASE).unwrap();
+        assert_eq!(database.tables["users"].rows, db.tables["users"].rows);
+
+        // a commit cut short by a crash, without its `COMMIT;` entry
+        let bytes = storage.read(Path::new("db.wal")).unwrap();
+        storage
+            .truncate(Path::new("db.wal"), bytes.len() as u64 - 11)
+            .unwrap();
+        let wal = WriteAheadLog::open_with("db.wal", storage).unwrap();
+        let mut recovered = Catalog::<i64>::default();
+        let Ok(ExecutionResult::Messages(report)) = recover(&mut recovered, &wal) else {
+            panic!("Expected Messages");
+        };
+        let database = recovered.get(DEFAULT_DATABASE).unwrap();
+        assert!(database.tables["users"].rows.is_empty());
+        assert!(report[1].contains("rolled back"));
+    }
+}
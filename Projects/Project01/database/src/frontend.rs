@@ -0,0 +1,125 @@
+//! The `frontend` module.
+//!
+//! This module runs the frontend selected on the command line. It creates the
+//! database for the chosen key type, loads the `--load` file into it, makes it the
+//! default database of a catalog, replays the `--wal` write-ahead log on top of it,
+//! and hands the catalog over to the REPL, the TUI, the script executor or the
+//! server, which record every change in that log. Both binaries are thin
+//! wrappers around [run], so embedders can reuse the same dispatch.
+
+mod exec;
+mod repl;
+mod server;
+mod terminal;
+
+pub use exec::run_script;
+pub use repl::run_repl;
+pub use server::{TRANSACTION_TIMEOUT, run_server, serve};
+pub use terminal::run_tui;
+
+use std::path::Path;
+
+use miette::{Report, Result};
+
+use crate::{
+    DatabaseResult,
+    ast::{Command, DumpSource, LoadFromStmt, ReadFromStmt},
+    cli::{Frontend, LoadFormat, Options},
+    core::{Catalog, Database, DatabaseKey, DatabaseType, Uuid, WriteAheadLog},
+    execution::{build_execute_command, recover},
+};
+
+/// Runs the frontend described by `options`, or the REPL if none was selected.
+///
+/// # Errors
+/// Returns an error if the `--load` file cannot be loaded or the frontend fails.
+pub fn run(options: Options) -> Result<()> {
+    match options.database_type {
+        DatabaseType::Int => run_with_key::<i64>(options),
+        DatabaseType::String => run_with_key::<String>(options),
+        DatabaseType::Uuid => run_with_key::<Uuid>(options),
+    }
+}
+
+fn run_with_key<K: DatabaseKey + Send + 'static>(options: Options) -> Result<()> {
+    let mut database = Database::<K>::default();
+
+    if let Some(path) = &options.load {
+        load(&mut database, path, options.format).map_err(Report::new)?;
+    }
+
+    let mut catalog = Catalog::new(database);
+    let wal = match &options.wal {
+        Some(path) => {
+            let wal = WriteAheadLog::open(path).map_err(Report::new)?;
+            let report = recover(&mut catalog, &wal).map_err(Report::new)?;
+            eprint!("{report}");
+            Some(wal)
+        }
+        None => None,
+    };
+
+    match options.frontend.unwrap_or(Frontend::Repl) {
+        Frontend::Repl => run_repl(catalog, wal),
+        Frontend::Tui => run_tui(catalog, wal),
+        Frontend::Exec { file } => run_script(catalog, &file, wal),
+        Frontend::Serve { port } => run_server(catalog, port, wal),
+    }
+}
+
+/// Loads the file at `path` into `database`, as `LOAD_FROM` does for a binary
+/// snapshot or `READ_FROM` does for a script.
+///
+/// # Errors
+/// Returns the error of the underlying command.
+pub fn load<K: DatabaseKey>(
+    database: &mut Database<K>,
+    path: &Path,
+    format: LoadFormat,
+) -> DatabaseResult<()> {
+    let path = path.to_string_lossy().into_owned();
+    let command = match format {
+        LoadFormat::Binary => Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::File(path),
+            passphrase: None,
+        }),
+        LoadFormat::Script => Command::ReadFrom(ReadFromStmt { path }),
+    };
+
+    build_execute_command(database, command)?.execute()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_load_script() {
+        let path = std::env::temp_dir().join(format!("database_load_{}.sql", std::process::id()));
+        fs::write(
+            &path,
+            "CREATE users KEY id FIELDS id: INT, name: STRING;\nINSERT id = 1, name = \"Alice\" INTO users;\n",
+        )
+        .unwrap();
+
+        let mut database = Database::<i64>::default();
+        load(&mut database, &path, LoadFormat::Script).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(database.tables["users"].rows.len(), 1);
+    }
+
+    #[test]
+    fn test_load_missing_file_fails() {
+        let mut database = Database::<i64>::default();
+        let result = load(
+            &mut database,
+            Path::new("does/not/exist.bin"),
+            LoadFormat::Binary,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use miette::{Report, Result};
+
+use crate::{
+    ast::{Command, ReadFromStmt},
+    core::{Catalog, DatabaseKey, WriteAheadLog},
+    execution::Session,
+};
+
+/// Executes the queries in the script at `path` on the default database of
+/// `catalog`, as `READ_FROM` does, and prints the execution log. The script is
+/// recorded in `wal`, if given.
+///
+/// # Errors
+/// Returns the first error raised by the script, after which no further queries run.
+pub fn run_script<K: DatabaseKey>(
+    mut catalog: Catalog<K>,
+    path: &Path,
+    mut wal: Option<WriteAheadLog>,
+) -> Result<()> {
+    let command = Command::ReadFrom(ReadFromStmt {
+        path: path.to_string_lossy().into_owned(),
+    });
+
+    let result = Session::default()
+        .execute(&mut catalog, command, wal.as_mut())
+        .map_err(Report::new)?;
+    print!("{result}");
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::io::{self, Write};
+
+use miette::{IntoDiagnostic, Report, Result};
+
+use crate::{
+    DatabaseResult, QueryParser,
+    ast::{Command, SelectStmt},
+    core::{Catalog, Database, DatabaseKey, WriteAheadLog},
+    execution::{Session, build_stream_command, parse_cached},
+};
+
+/// Starts and runs the interactive Read-Eval-Print Loop (REPL) on the databases
+/// of `catalog`, recording every change in `wal`, if given. The prompt shows the
+/// name of the current database.
+pub fn run_repl<K: DatabaseKey>(
+    mut catalog: Catalog<K>,
+    mut wal: Option<WriteAheadLog>,
+) -> Result<()> {
+    let mut session = Session::default();
+    let stdin = io::stdin();
+    let mut query_buffer = String::new();
+
+    loop {
+        if query_buffer.is_empty() {
+            print!("{}> ", session.database_name());
+        } else {
+            print!("{}-> ", session.database_name());
+        }
+        io::stdout().flush().unwrap();
+
+        let mut line_input = String::new();
+        match stdin.read_line(&mut line_input).into_diagnostic()? {
+            0 => {
+                break;
+            }
+            _ => {
+                query_buffer.push_str(&line_input);
+                let statements = QueryParser.split_statements(&query_buffer);
+                if statements.last().is_some_and(|query| !query.ends_with(';')) {
+                    continue;
+                }
+
+                for query in statements {
+                    parse_and_execute(&mut session, &mut catalog, wal.as_mut(), query);
+                }
+                query_buffer.clear();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A helper function to orchestrate the full query processing pipeline.
+///
+/// This function takes a raw query string and:
+/// 1.  Parses it into an AST with the parser shared with `Session::execute_query`,
+///     reusing the AST if the query was seen before.
+/// 2.  Calls `session` to validate and execute the command on its current database,
+///     recording it in `wal` if it modifies the database (once committed inside a
+///     transaction).
+///
+/// All results (success or error) are printed directly to `stdout` or `stderr`.
+/// Errors at any stage are printed, but do not stop the REPL. The records of a
+/// `SELECT` are printed as they are produced (see `print_select`).
+fn parse_and_execute<K: DatabaseKey>(
+    session: &mut Session<K>,
+    catalog: &mut Catalog<K>,
+    wal: Option<&mut WriteAheadLog>,
+    query: &str,
+) {
+    match parse_cached(query) {
+        Ok(Command::Select(stmt)) => {
+            if let Err(e) = print_select(session.database_mut(catalog), stmt) {
+                eprintln!("{:?}", Report::new(e));
+            }
+        }
+        Ok(ast) => {
+            println!("Executing query...");
+            match session.execute(catalog, ast, wal) {
+                Ok(result) => {
+                    println!("{result}");
+                }
+                Err(e) => eprintln!("{:?}", Report::new(e)),
+            }
+        }
+        Err(e) => eprintln!("{:?}", Report::new(e)),
+    }
+}
+
+/// Executes a `SELECT` and prints its records one at a time, without collecting
+/// them first, followed by their count.
+///
+/// # Errors
+/// Returns the error raised by the query; the records before it are already printed.
+fn print_select<K: DatabaseKey>(
+    database: &mut Database<K>,
+    stmt: SelectStmt,
+) -> DatabaseResult<()> {
+    let executable_command = build_stream_command(database, stmt)?;
+    println!("Executing query...");
+
+    let mut count = 0;
+    for record in executable_command.execute_stream()? {
+        count += 1;
+        println!("{count:>3}: {}", record?);
+    }
+
+    if count == 0 {
+        println!("No data");
+    } else {
+        println!("Returned {count} record(s)");
+    }
+    Ok(())
+}
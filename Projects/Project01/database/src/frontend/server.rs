@@ -0,0 +1,264 @@
+use std::{
+    io::{self, BufReader, BufWriter},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use crate::{
+    core::{Catalog, DatabaseKey, WriteAheadLog},
+    execution::Session,
+    protocol::{self, MAX_QUERY_LEN, Response},
+};
+
+/// How long a client with a transaction in progress may wait before sending its
+/// next query, after which the transaction is rolled back and the client is
+/// disconnected, so that it does not hold the catalog forever.
+pub const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The state shared by the connections of a server.
+struct Shared<K: DatabaseKey> {
+    catalog: Catalog<K>,
+    wal: Option<WriteAheadLog>,
+}
+
+/// Listens on `port` of the loopback interface and serves the queries of every
+/// client connecting to it, as described in the `protocol` module.
+///
+/// # Errors
+/// Returns an error if the port cannot be bound; the server runs until the
+/// process is stopped otherwise.
+pub fn run_server<K: DatabaseKey + Send + 'static>(
+    catalog: Catalog<K>,
+    port: u16,
+    wal: Option<WriteAheadLog>,
+) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Cannot serve on port {port}"))?;
+    eprintln!("Listening on {}", listener.local_addr().into_diagnostic()?);
+
+    serve(listener, catalog, wal);
+    Ok(())
+}
+
+/// Accepts the clients of `listener`, serving each of them on its own thread.
+///
+/// The connections share `catalog` and `wal`, and every query holds the catalog
+/// for the time it runs. Each client has its own session, starting in the
+/// default database. A client with a transaction in progress holds the catalog
+/// until the transaction ends, so that the others never see its changes before
+/// `COMMIT`. The transaction is rolled back if the client disconnects, or if it
+/// sends no query for [`TRANSACTION_TIMEOUT`], in which case it is disconnected.
+pub fn serve<K: DatabaseKey + Send + 'static>(
+    listener: TcpListener,
+    catalog: Catalog<K>,
+    wal: Option<WriteAheadLog>,
+) {
+    serve_with_timeout(listener, catalog, wal, TRANSACTION_TIMEOUT);
+}
+
+/// Serves the clients of `listener` as [`serve`] does, rolling back the transactions
+/// left idle for `timeout`.
+fn serve_with_timeout<K: DatabaseKey + Send + 'static>(
+    listener: TcpListener,
+    catalog: Catalog<K>,
+    wal: Option<WriteAheadLog>,
+    timeout: Duration,
+) {
+    let shared = Arc::new(Mutex::new(Shared { catalog, wal }));
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("Cannot accept a connection: {error}");
+                continue;
+            }
+        };
+
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let peer = stream.peer_addr().map_or_else(
+                |_| "an unknown address".to_string(),
+                |addr| addr.to_string(),
+            );
+            if let Err(error) = handle_connection(stream, &shared, timeout) {
+                eprintln!("The connection from {peer} failed: {error}");
+            }
+        });
+    }
+}
+
+/// Answers the queries sent over `stream` until the client disconnects, or leaves
+/// a transaction idle for `timeout`.
+fn handle_connection<K: DatabaseKey>(
+    stream: TcpStream,
+    shared: &Mutex<Shared<K>>,
+    timeout: Duration,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+    let mut session = Session::default();
+    let mut guard: Option<MutexGuard<'_, Shared<K>>> = None;
+
+    let result = loop {
+        let query = match protocol::read_frame(&mut reader, MAX_QUERY_LEN) {
+            Ok(Some(query)) => query,
+            Ok(None) => break Ok(()),
+            Err(error)
+                if guard.is_some()
+                    && matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+            {
+                break Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the transaction was idle for too long and was rolled back",
+                ));
+            }
+            Err(error) => break Err(error),
+        };
+
+        let state = guard.get_or_insert_with(|| shared.lock().unwrap_or_else(|e| e.into_inner()));
+        let response = execute(&mut session, state, &query);
+        if !session.in_transaction() {
+            guard = None;
+        }
+
+        let written = reader
+            .get_ref()
+            .set_read_timeout(guard.is_some().then_some(timeout))
+            .and_then(|()| protocol::encode_response(&response).map_err(io::Error::other))
+            .and_then(|payload| protocol::write_frame(&mut writer, &payload));
+        if let Err(error) = written {
+            break Err(error);
+        }
+    };
+
+    if let Some(mut state) = guard {
+        session.abort(&mut state.catalog);
+    }
+    result
+}
+
+/// Parses and executes `query` on the current database of `session`.
+fn execute<K: DatabaseKey>(
+    session: &mut Session<K>,
+    state: &mut Shared<K>,
+    query: &[u8],
+) -> Response {
+    let query =
+        std::str::from_utf8(query).map_err(|_| "The query is not valid UTF-8".to_string())?;
+    session
+        .execute_query(&mut state.catalog, query, state.wal.as_mut())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+    use crate::execution::ExecutionResult;
+
+    fn start() -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Catalog::<i64>::default(), None));
+        addr
+    }
+
+    fn query(stream: &mut TcpStream, query: &str) -> Response {
+        protocol::write_frame(stream, query.as_bytes()).unwrap();
+        let payload = protocol::read_frame(stream, protocol::MAX_RESPONSE_LEN)
+            .unwrap()
+            .unwrap();
+        protocol::decode_response(&payload).unwrap()
+    }
+
+    #[test]
+    fn test_serve_queries() {
+        let addr = start();
+        let mut client = TcpStream::connect(addr).unwrap();
+
+        assert!(matches!(
+            query(&mut client, "CREATE users KEY id FIELDS id: INT;"),
+            Ok(ExecutionResult::Success)
+        ));
+        query(&mut client, "INSERT id = 1 INTO users;").unwrap();
+        let Ok(ExecutionResult::Data(records)) = query(&mut client, "SELECT id FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert_eq!(records.len(), 1);
+
+        let error = query(&mut client, "SELECT id FROM orders;").unwrap_err();
+        assert!(error.contains("orders"));
+        assert!(query(&mut client, "NOT A QUERY").is_err());
+
+        let mut other = TcpStream::connect(addr).unwrap();
+        let Ok(ExecutionResult::Data(records)) = query(&mut other, "SELECT id FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert_eq!(records.len(), 1);
+    }
+
+    #[test]
+    fn test_idle_transaction_is_rolled_back() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            serve_with_timeout(
+                listener,
+                Catalog::<i64>::default(),
+                None,
+                Duration::from_millis(100),
+            );
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        for statement in [
+            "CREATE users KEY id FIELDS id: INT;",
+            "BEGIN;",
+            "INSERT id = 1 INTO users;",
+        ] {
+            query(&mut client, statement).unwrap();
+        }
+
+        let mut other = TcpStream::connect(addr).unwrap();
+        let Ok(ExecutionResult::Data(records)) = query(&mut other, "SELECT id FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert!(records.is_empty());
+
+        // the server has disconnected the client
+        assert!(!matches!(
+            protocol::read_frame(&mut client, protocol::MAX_RESPONSE_LEN),
+            Ok(Some(_))
+        ));
+    }
+
+    #[test]
+    fn test_transaction_is_rolled_back_on_disconnect() {
+        let addr = start();
+        let mut client = TcpStream::connect(addr).unwrap();
+        for statement in [
+            "CREATE users KEY id FIELDS id: INT;",
+            "BEGIN;",
+            "INSERT id = 1 INTO users;",
+        ] {
+            query(&mut client, statement).unwrap();
+        }
+        drop(client);
+
+        let mut other = TcpStream::connect(addr).unwrap();
+        let Ok(ExecutionResult::Data(records)) = query(&mut other, "SELECT id FROM users;") else {
+            panic!("Expected Data");
+        };
+        assert!(records.is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use std::{io, time::Duration};
+
+use crossterm::{
+    event::{self, Event, KeyEventKind},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use miette::{IntoDiagnostic, Result};
+use ratatui::prelude::*;
+
+use crate::{
+    core::{Catalog, DatabaseKey, WriteAheadLog},
+    tui::{App, ui},
+};
+
+/// Takes over the terminal and runs the TUI on the databases of `catalog` until
+/// the user quits, recording every change in `wal`, if given.
+pub fn run_tui<K: DatabaseKey>(catalog: Catalog<K>, wal: Option<WriteAheadLog>) -> Result<()> {
+    enable_raw_mode().into_diagnostic()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen).into_diagnostic()?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend).into_diagnostic()?;
+
+    let mut app = App::new(catalog);
+    if let Some(wal) = wal {
+        app = app.with_wal(wal);
+    }
+
+    loop {
+        terminal.draw(|f| ui(f, &mut app)).into_diagnostic()?;
+
+        if event::poll(Duration::from_millis(250)).into_diagnostic()?
+            && let Event::Key(key) = event::read().into_diagnostic()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key_event(key);
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    disable_raw_mode().into_diagnostic()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).into_diagnostic()?;
+    terminal.show_cursor().into_diagnostic()?;
+
+    Ok(())
+}
@@ -4,15 +4,21 @@
 //! including parsing, execution, and data modeling. It exposes a public
 //! API for frontends (like the CLI or TUI) to consume.
 
+// lets the code generated by `#[derive(FromRecord)]` name this crate from inside it
+extern crate self as database;
+
 mod ast;
 mod cli;
 mod parsing;
+mod protocol;
 
+pub mod client;
 pub mod core;
 pub mod execution;
+pub mod frontend;
 pub mod tui;
 
-pub use cli::Cli;
-pub use parsing::{QueryParser, QueryRule};
+pub use cli::{Cli, ConfigError, DEFAULT_PORT, Frontend, LoadFormat, Options};
+pub use parsing::{DEFAULT_QUERY_CACHE_CAPACITY, QueryCache, QueryParser, QueryRule};
 
 pub use core::DatabaseResult;
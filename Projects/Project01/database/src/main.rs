@@ -1,20 +1,9 @@
-mod repl;
-
-use database::{Cli, core::DatabaseType};
+use database::{Cli, frontend};
 use miette::Result;
 
-use crate::repl::run_repl;
-
 fn main() -> Result<()> {
     miette::set_panic_hook();
 
-    let cli = Cli;
-    let database_type = cli.get_type();
-
-    match database_type {
-        DatabaseType::Int => run_repl::<i64>()?,
-        DatabaseType::String => run_repl::<String>()?,
-    }
-
-    Ok(())
+    let options = Cli.parse()?;
+    frontend::run(options)
 }
@@ -2,10 +2,13 @@
 //!
 //! This module is responsible for all query parsing. It contains the
 //! `pest` parser logic (driven by the grammar) and the AST (Abstract Syntax Tree)
-//! builder which converts the `pest` pairs into structured `ast::Command` objects.
+//! builder which converts the `pest` pairs into structured `ast::Command` objects,
+//! and a cache of parsed queries for frontends that run the same queries repeatedly.
 
 mod parsing_error;
+mod query_cache;
 mod query_parser;
 
 pub use parsing_error::ParsingError;
+pub use query_cache::{DEFAULT_QUERY_CACHE_CAPACITY, QueryCache};
 pub use query_parser::{QueryParser, Rule as QueryRule};
@@ -3,16 +3,33 @@ use std::{
     str::ParseBoolError,
 };
 
-use miette::Diagnostic;
+use miette::{Diagnostic, SourceSpan};
+use pest::error::{Error as PestError, ErrorVariant, InputLocation, LineColLocation};
 use thiserror::Error;
 
-use crate::QueryRule;
+use crate::{
+    QueryRule,
+    core::{ParseUuidError, did_you_mean},
+};
 
 /// Represents errors that can occur during the parsing phase.
 #[derive(Error, Debug, Diagnostic)]
 pub enum ParsingError {
-    #[error(transparent)]
-    Pest(#[from] Box<pest::error::Error<QueryRule>>),
+    /// The input violates the grammar. Rendered as a `miette` report, it shows
+    /// the input with the offending token underlined, and the expected tokens or
+    /// the keyword a misspelled one is likely to be.
+    #[error("Unexpected {found} at line {line}, column {column}")]
+    Syntax {
+        found: String,
+        line: usize,
+        column: usize,
+        #[source_code]
+        input: String,
+        #[label("unexpected {found}")]
+        span: SourceSpan,
+        #[help]
+        expected: Option<String>,
+    },
 
     #[error(transparent)]
     Int(#[from] ParseIntError),
@@ -23,6 +40,141 @@ pub enum ParsingError {
     #[error(transparent)]
     Bool(#[from] ParseBoolError),
 
+    #[error(transparent)]
+    Uuid(#[from] ParseUuidError),
+
     #[error("Unexpected rule: {rule:?}")]
     UnexpectedRule { rule: QueryRule },
 }
+
+/// The tokens skipped between the others, which are never worth expecting.
+const SKIPPED_TOKENS: [&str; 6] = [" ", "\t", "\r", "\n", "--", "/*"];
+
+impl ParsingError {
+    /// Describes the syntax error `error` of `input`.
+    ///
+    /// The error is located at the farthest position the parser reached, if the
+    /// parse attempts were tracked (see `pest::set_error_detail`), and otherwise
+    /// where `pest` reports it.
+    pub(crate) fn syntax(input: &str, error: &PestError<QueryRule>) -> Self {
+        let (span, found, expected, suggestion) = match error.parse_attempts() {
+            Some(attempts) => {
+                let position = attempts.max_position;
+                // a keyword used as an identifier is rejected once it is read
+                let keyword = attempts.unexpected_tokens().into_iter().find_map(|token| {
+                    let token = token.to_string();
+                    input[..position].ends_with(&token).then_some(token)
+                });
+                let (span, found) = match &keyword {
+                    Some(keyword) => (
+                        SourceSpan::from((position - keyword.len(), keyword.len())),
+                        format!("keyword `{keyword}`"),
+                    ),
+                    None => token_at(input, position),
+                };
+                let tokens = attempts
+                    .expected_tokens()
+                    .into_iter()
+                    .map(|token| token.to_string())
+                    .collect::<Vec<_>>();
+                // a misspelled keyword is read as the word it is made of
+                let suggestion = match keyword {
+                    None => did_you_mean(
+                        &input[span.offset()..span.offset() + span.len()],
+                        tokens
+                            .iter()
+                            .map(String::as_str)
+                            .filter(|token| is_keyword(token)),
+                    ),
+                    Some(_) => None,
+                };
+                (span, found, describe_tokens(&tokens), suggestion)
+            }
+            None => {
+                let position = match error.location {
+                    InputLocation::Pos(position) | InputLocation::Span((position, _)) => position,
+                };
+                let (span, found) = token_at(input, position);
+                let expected = match &error.variant {
+                    ErrorVariant::ParsingError { positives, .. } => positives
+                        .iter()
+                        .filter(|rule| **rule != QueryRule::EOI)
+                        .map(|rule| format!("{rule:?}").replace('_', " "))
+                        .collect(),
+                    ErrorVariant::CustomError { .. } => Vec::new(),
+                };
+                (span, found, expected, None)
+            }
+        };
+
+        let (line, column) = match pest::Position::new(input, span.offset()) {
+            Some(position) => position.line_col(),
+            None => match error.line_col {
+                LineColLocation::Pos(line_col) | LineColLocation::Span(line_col, _) => line_col,
+            },
+        };
+        let expected = match (suggestion, expected.as_slice()) {
+            (Some(suggestion), _) => suggestion,
+            (None, []) => "Expected the end of the query".to_string(),
+            (None, [token]) => format!("Expected {token}"),
+            (None, tokens) => format!("Expected one of: {}", tokens.join(", ")),
+        };
+
+        ParsingError::Syntax {
+            found,
+            line,
+            column,
+            input: input.to_string(),
+            span,
+            expected: Some(expected),
+        }
+    }
+}
+
+/// Returns the span and the description of the token of `input` starting at
+/// `position`: a whole word, a single other character or the end of the input.
+fn token_at(input: &str, position: usize) -> (SourceSpan, String) {
+    let rest = &input[position..];
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let len = match rest.chars().next() {
+        None => return (SourceSpan::from((position, 0)), "end of input".to_string()),
+        Some(c) if is_word(c) => rest.find(|c| !is_word(c)).unwrap_or(rest.len()),
+        Some(c) => c.len_utf8(),
+    };
+    (
+        SourceSpan::from((position, len)),
+        format!("`{}`", &rest[..len]),
+    )
+}
+
+/// Returns `true` if the expected `token` is a keyword.
+fn is_keyword(token: &str) -> bool {
+    token.len() > 1 && token.chars().all(|c| c.is_ascii_uppercase() || c == '_')
+}
+
+/// Describes the `tokens` expected by the parser, naming the character classes
+/// (displayed as ranges, e.g. `a..z`) of identifiers and numbers and the
+/// prefixes of literals instead of listing them.
+fn describe_tokens(tokens: &[String]) -> Vec<String> {
+    let mut identifier = false;
+    let mut number = false;
+    let mut described = Vec::new();
+    for token in tokens {
+        match token.as_str() {
+            token if SKIPPED_TOKENS.contains(&token) => {}
+            "_" | "a..z" | "A..Z" => identifier = true,
+            "0..9" => number = true,
+            "\"" => described.push("a string".to_string()),
+            "u\"" => described.push("a UUID".to_string()),
+            "0x" => described.push("bytes".to_string()),
+            token => described.push(format!("`{token}`")),
+        }
+    }
+
+    if identifier {
+        described.push("an identifier".to_string());
+    } else if number {
+        described.push("a number".to_string());
+    }
+    described
+}
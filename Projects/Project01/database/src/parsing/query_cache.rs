@@ -0,0 +1,143 @@
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+
+use super::QueryParser;
+
+use crate::{DatabaseResult, ast::Command};
+
+/// The number of parsed queries kept by a default `QueryCache`.
+pub const DEFAULT_QUERY_CACHE_CAPACITY: usize = 128;
+
+/// A least-recently-used cache of parsed queries, keyed by the raw query string.
+///
+/// A repeated query is answered with a clone of its cached AST instead of being
+/// parsed again. Queries that fail to parse are not cached.
+pub struct QueryCache {
+    /// The parser used for queries missing from the cache.
+    parser: QueryParser,
+    /// The parsed queries, evicted in least-recently-used order once full.
+    entries: LruCache<String, Command>,
+    /// The number of queries answered from the cache.
+    hits: u64,
+    /// The number of queries that had to be parsed.
+    misses: u64,
+}
+
+impl Default for QueryCache {
+    /// Creates a cache holding up to `DEFAULT_QUERY_CACHE_CAPACITY` queries.
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(DEFAULT_QUERY_CACHE_CAPACITY).unwrap())
+    }
+}
+
+impl QueryCache {
+    /// Creates an empty cache holding up to `capacity` parsed queries.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            parser: QueryParser,
+            entries: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Parses `input` as `QueryParser::parse_query` does, reusing the AST of
+    /// an identical earlier query if it is still cached.
+    ///
+    /// # Errors
+    /// Returns the parsing error of `input`, see `QueryParser::parse_query`.
+    pub fn parse_query(&mut self, input: &str) -> DatabaseResult<Command> {
+        if let Some(command) = self.entries.get(input) {
+            self.hits += 1;
+            return Ok(command.clone());
+        }
+
+        self.misses += 1;
+        let command = self.parser.parse_query(input)?;
+        self.entries.put(input.to_string(), command.clone());
+        Ok(command)
+    }
+
+    /// Returns the number of queries answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of queries that had to be parsed.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the number of parsed queries currently cached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if no parsed query is currently cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every cached query and resets the hit and miss counters.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_queries_hit_the_cache() {
+        let mut cache = QueryCache::default();
+        let query = "SELECT id FROM users;";
+
+        let first = cache.parse_query(query).unwrap();
+        let second = cache.parse_query(query).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first, QueryParser.parse_query(query).unwrap());
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        cache.parse_query("SELECT name FROM users;").unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!((cache.hits(), cache.misses()), (0, 0));
+    }
+
+    #[test]
+    fn test_invalid_queries_are_not_cached() {
+        let mut cache = QueryCache::default();
+
+        assert!(cache.parse_query("SELECT FROM;").is_err());
+        assert!(cache.parse_query("SELECT FROM;").is_err());
+        assert_eq!((cache.hits(), cache.misses()), (0, 2));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_query_is_evicted() {
+        let mut cache = QueryCache::new(NonZeroUsize::new(2).unwrap());
+        let queries = [
+            "SELECT id FROM users;",
+            "SELECT name FROM users;",
+            "SELECT age FROM users;",
+        ];
+
+        cache.parse_query(queries[0]).unwrap();
+        cache.parse_query(queries[1]).unwrap();
+        cache.parse_query(queries[0]).unwrap();
+        cache.parse_query(queries[2]).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.parse_query(queries[0]).unwrap();
+        cache.parse_query(queries[1]).unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (2, 4));
+    }
+}
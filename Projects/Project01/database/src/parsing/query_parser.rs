@@ -1,17 +1,45 @@
-use pest::{Parser, iterators::Pair};
+use std::sync::{LazyLock, Mutex};
+
+use pest::{
+    Parser,
+    iterators::{Pair, Pairs},
+    pratt_parser::{Assoc, Op, PrattParser},
+};
 
 use super::ParsingError;
 
 use crate::{
     DatabaseResult,
     ast::{
-        Assignment, Command, Comparison, Condition, CreateStmt, DeleteStmt, DumpToStmt, Field,
-        InsertStmt, LoadFromStmt, Operator, OptionalClauses, OrderBy, OrderDirection, ReadFromStmt,
-        SaveAsStmt, SelectStmt,
+        Aggregate, AggregateFunction, AnalyzeStmt, ArithmeticOperator, Assignment, Command,
+        Comparison, CompoundSelectStmt, Condition, ConflictAction, CreateDatabaseStmt,
+        CreateIndexStmt, CreateStmt, DeleteStmt, DumpSource, DumpTableStmt, DumpToStmt,
+        ExplainStmt, ExportJsonStmt, Expression, Field, GenerateStmt, ImportJsonStmt, InsertStmt,
+        LoadCsvStmt, LoadFromStmt, LoadTableStmt, MigrateStmt, Operator, OptionalClauses, OrderBy,
+        OrderDirection, Projection, ReadFromStmt, RenameStmt, SaveAsStmt, ScalarFunction,
+        SelectItem, SelectStmt, SetOperator, ShowTablesStmt, TransactionStmt, TruncateStmt,
+        UpdateStmt, UseStmt, VerifyStmt,
     },
-    core::{DataType, DataValue, DatabaseError},
+    core::{DataType, DataValue, DatabaseError, IndexKind, TableStorage, Uuid},
 };
 
+/// The precedences of the operators of conditions, from the loosest: `OR`, `AND`
+/// and `NOT`, so that `NOT a AND b OR c` is `((NOT a) AND b) OR c`.
+static CONDITION_PARSER: LazyLock<PrattParser<Rule>> = LazyLock::new(|| {
+    PrattParser::new()
+        .op(Op::infix(Rule::or_op, Assoc::Left))
+        .op(Op::infix(Rule::and_op, Assoc::Left))
+        .op(Op::prefix(Rule::not_op))
+});
+
+/// The precedences of the arithmetic operators of expressions, from the loosest:
+/// `+` and `-`, then `*` and `/`, all of them chained from left to right.
+static EXPRESSION_PARSER: LazyLock<PrattParser<Rule>> = LazyLock::new(|| {
+    PrattParser::new()
+        .op(Op::infix(Rule::add_op, Assoc::Left))
+        .op(Op::infix(Rule::mul_op, Assoc::Left))
+});
+
 /// The main parser for the database's SQL-like query language.
 #[derive(pest_derive::Parser)]
 #[grammar = "parsing/query_grammar.pest"]
@@ -28,18 +56,119 @@ impl QueryParser {
     /// # Returns
     /// A `DatabaseResult` containing the structured `Command` on success.
     ///
+    /// The query recorded in the command (and thus in the history and the
+    /// write-ahead log) spans from the statement to its semicolon, without the
+    /// comments around it.
+    ///
     /// # Errors
     /// * `DatabaseError::Parse` if the input string violates the grammar (syntax error).
     /// * `DatabaseError::Ast` or other variants if the AST building fails (e.g., conversion error).
     pub fn parse_query(&self, input: &str) -> DatabaseResult<Command> {
-        let pairs = QueryParser::parse(Rule::query, input)
-            .map_err(|e| Box::new(ParsingError::from(Box::new(e))))?;
-        let query = pairs.into_iter().next().unwrap();
-        let query_raw = query.as_span().as_str();
-        let command = query.into_inner().next().unwrap();
+        let pairs = self.parse_rule(Rule::query, input)?;
+        let mut inner = pairs.into_iter().next().unwrap().into_inner();
+        let command = inner.next().unwrap();
+        let end = inner.next().unwrap();
+        let query_raw = &input[command.as_span().start()..end.as_span().end()];
         self.build_command(command, query_raw)
     }
 
+    /// Parses a script of any number of statements, each terminated with a
+    /// semicolon, into their `Command`s.
+    ///
+    /// The whole script is parsed at once, so a semicolon inside a string literal
+    /// or a comment does not end a statement, and a syntax error is reported at
+    /// its position in the script. A script of only whitespace and comments has
+    /// no commands.
+    ///
+    /// # Errors
+    /// The errors of [`QueryParser::parse_query`], for the first statement which
+    /// cannot be parsed.
+    pub fn parse_script(&self, input: &str) -> DatabaseResult<Vec<Command>> {
+        let statements = self.parse_statements(input)?;
+        Ok(statements.into_iter().map(|(_, command)| command).collect())
+    }
+
+    /// Parses a script as [`QueryParser::parse_script`] does, returning every
+    /// `Command` together with its query.
+    pub(crate) fn parse_statements<'a>(
+        &self,
+        input: &'a str,
+    ) -> DatabaseResult<Vec<(&'a str, Command)>> {
+        let pairs = self.parse_rule(Rule::script, input)?;
+        let mut inner = pairs.into_iter().next().unwrap().into_inner();
+
+        let mut statements = Vec::new();
+        while let Some(command) = inner.next() {
+            if command.as_rule() == Rule::EOI {
+                break;
+            }
+            let end = inner.next().unwrap();
+            let query_raw = &input[command.as_span().start()..end.as_span().end()];
+            statements.push((query_raw, self.build_command(command, query_raw)?));
+        }
+        Ok(statements)
+    }
+
+    /// Splits a script into its statements, at the semicolons outside of string
+    /// literals and comments, without parsing them (e.g., to find out whether the
+    /// input typed so far ends with a complete statement).
+    ///
+    /// Every statement ends with its semicolon, except for the text following the
+    /// last one, which is returned as the last statement if it is not only
+    /// whitespace and comments. The comments between the statements are dropped.
+    pub fn split_statements<'a>(&self, input: &'a str) -> Vec<&'a str> {
+        // the `raw_script` rule accepts any input
+        let script = QueryParser::parse(Rule::raw_script, input)
+            .unwrap()
+            .next()
+            .unwrap();
+        script
+            .into_inner()
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(|pair| pair.as_str().trim_end())
+            .collect()
+    }
+
+    /// Parses a condition on its own, written as in a `WHERE` or `CHECK` clause
+    /// (e.g., `age >= 18 AND active = true`).
+    ///
+    /// # Errors
+    /// * `DatabaseError::Parse` if the input is not a single, complete condition.
+    pub fn parse_condition(&self, input: &str) -> DatabaseResult<Condition> {
+        let pairs = self.parse_rule(Rule::standalone_condition, input)?;
+        let condition = pairs
+            .into_iter()
+            .next()
+            .unwrap()
+            .into_inner()
+            .next()
+            .unwrap();
+        self.build_condition(condition)
+    }
+
+    /// Parses `input` with the grammar `rule`.
+    ///
+    /// # Errors
+    /// Returns `ParsingError::Syntax` if the input violates the grammar, locating
+    /// the error with the tokens expected there. They are only tracked when
+    /// parsing the input again after the error, as tracking them slows the parser
+    /// down. As the tracking is enabled for every thread, the inputs are parsed
+    /// again one at a time, so that none of them disables it for another.
+    fn parse_rule<'a>(&self, rule: Rule, input: &'a str) -> DatabaseResult<Pairs<'a, Rule>> {
+        static ERROR_DETAIL: Mutex<()> = Mutex::new(());
+
+        QueryParser::parse(rule, input).map_err(|_| {
+            let _guard = ERROR_DETAIL
+                .lock()
+                .unwrap_or_else(|error| error.into_inner());
+            pest::set_error_detail(true);
+            let result = QueryParser::parse(rule, input);
+            pest::set_error_detail(false);
+            let error = result.expect_err("the input was rejected by the same rule");
+            DatabaseError::from(Box::new(ParsingError::syntax(input, &error)))
+        })
+    }
+
     /// Builds a specific `Command` from its corresponding `Pair`.
     ///
     /// # Arguments
@@ -49,9 +178,81 @@ impl QueryParser {
         let pair_rule = pair.as_rule();
         let command = match &pair_rule {
             Rule::create_stmt => Command::Create(self.build_create_stmt(pair, query)?),
+            Rule::create_index_stmt => {
+                let mut inner = pair.into_inner();
+                let index_name = inner.next().unwrap().as_str().to_string();
+                let table_name = inner.next().unwrap().as_str().to_string();
+                let field = inner.next().unwrap().as_str().to_string();
+                let kind = match inner.next() {
+                    Some(using_clause) => {
+                        let index_kind = using_clause.into_inner().next().unwrap();
+                        match index_kind.into_inner().next().unwrap().as_rule() {
+                            Rule::KW_HASH => IndexKind::Hash,
+                            _ => IndexKind::BTree,
+                        }
+                    }
+                    None => IndexKind::default(),
+                };
+                Command::CreateIndex(CreateIndexStmt {
+                    index_name,
+                    table_name,
+                    field,
+                    kind,
+                    query: query.to_string(),
+                })
+            }
             Rule::insert_stmt => Command::Insert(self.build_insert_stmt(pair, query)?),
             Rule::delete_stmt => Command::Delete(self.build_delete_stmt(pair, query)?),
+            Rule::generate_stmt => Command::Generate(self.build_generate_stmt(pair, query)?),
+            Rule::load_csv_stmt => {
+                let mut inner = pair.into_inner();
+                let file_path = inner.next().unwrap().into_inner().next().unwrap();
+                let path = self.build_string_literal(file_path);
+                let table_name = inner.next().unwrap().as_str().to_string();
+                Command::LoadCsv(LoadCsvStmt {
+                    path,
+                    table_name,
+                    query: query.to_string(),
+                })
+            }
             Rule::select_stmt => Command::Select(self.build_select_stmt(pair, query)?),
+            Rule::compound_select_stmt => {
+                Command::CompoundSelect(self.build_compound_select_stmt(pair, query)?)
+            }
+            Rule::explain_stmt => {
+                let select_pair = pair.into_inner().next().unwrap();
+                let select = self.build_select_stmt(select_pair.clone(), select_pair.as_str())?;
+                Command::Explain(ExplainStmt {
+                    select,
+                    query: query.to_string(),
+                })
+            }
+            Rule::update_stmt => Command::Update(self.build_update_stmt(pair, query)?),
+            Rule::rename_stmt => {
+                let mut inner = pair.into_inner();
+                let table_name = inner.next().unwrap().as_str().to_string();
+                let new_name = inner.next().unwrap().as_str().to_string();
+                Command::Rename(RenameStmt {
+                    table_name,
+                    new_name,
+                    query: query.to_string(),
+                })
+            }
+            Rule::analyze_stmt => {
+                let table_name = pair.into_inner().next().unwrap().as_str().to_string();
+                Command::Analyze(AnalyzeStmt {
+                    table_name,
+                    query: query.to_string(),
+                })
+            }
+            Rule::show_tables_stmt => Command::ShowTables(ShowTablesStmt),
+            Rule::truncate_stmt => {
+                let table_name = pair.into_inner().next().unwrap().as_str().to_string();
+                Command::Truncate(TruncateStmt {
+                    table_name,
+                    query: query.to_string(),
+                })
+            }
             Rule::save_as_stmt => {
                 let path = self.build_file_path(pair);
                 Command::SaveAs(SaveAsStmt { path })
@@ -60,13 +261,72 @@ impl QueryParser {
                 let path = self.build_file_path(pair);
                 Command::ReadFrom(ReadFromStmt { path })
             }
-            Rule::dump_to_stmt => {
+            Rule::migrate_stmt => {
                 let path = self.build_file_path(pair);
-                Command::DumpTo(DumpToStmt { path })
+                Command::Migrate(MigrateStmt { path })
+            }
+            Rule::dump_to_stmt => {
+                let path = self.build_file_path(pair.clone());
+                let passphrase = self.build_passphrase(pair);
+                Command::DumpTo(DumpToStmt { path, passphrase })
             }
             Rule::load_from_stmt => {
+                let source = self.build_dump_source(pair.clone())?;
+                let passphrase = self.build_passphrase(pair);
+                Command::LoadFrom(LoadFromStmt { source, passphrase })
+            }
+            Rule::transaction_stmt => {
+                Command::Transaction(match pair.into_inner().next().unwrap().as_rule() {
+                    Rule::KW_BEGIN => TransactionStmt::Begin,
+                    Rule::KW_COMMIT => TransactionStmt::Commit,
+                    _ => TransactionStmt::Rollback,
+                })
+            }
+            Rule::create_database_stmt => {
+                let name = pair.into_inner().next().unwrap().as_str().to_string();
+                Command::CreateDatabase(CreateDatabaseStmt {
+                    name,
+                    query: query.to_string(),
+                })
+            }
+            Rule::use_stmt => {
+                let name = pair.into_inner().next().unwrap().as_str().to_string();
+                Command::Use(UseStmt { name })
+            }
+            Rule::verify_stmt => {
+                let path = self.build_file_path(pair.clone());
+                let passphrase = self.build_passphrase(pair);
+                Command::Verify(VerifyStmt { path, passphrase })
+            }
+            Rule::dump_table_stmt => {
+                let mut inner = pair.into_inner();
+                let table_name = inner.next().unwrap().as_str().to_string();
+                let file_path = inner.next().unwrap().into_inner().next().unwrap();
+                let path = self.build_string_literal(file_path);
+                Command::DumpTable(DumpTableStmt { table_name, path })
+            }
+            Rule::load_table_stmt => {
+                let source = self.build_dump_source(pair.clone())?;
+                let on_conflict = match pair.into_inner().nth(1) {
+                    Some(clause) => {
+                        let action = clause.into_inner().next().unwrap();
+                        Some(ConflictAction::try_from(action.as_str())?)
+                    }
+                    None => None,
+                };
+                Command::LoadTable(LoadTableStmt {
+                    source,
+                    on_conflict,
+                    query: query.to_string(),
+                })
+            }
+            Rule::export_json_stmt => {
+                let path = self.build_file_path(pair);
+                Command::ExportJson(ExportJsonStmt { path })
+            }
+            Rule::import_json_stmt => {
                 let path = self.build_file_path(pair);
-                Command::LoadFrom(LoadFromStmt { path })
+                Command::ImportJson(ImportJsonStmt { path })
             }
             rule => {
                 return Err(DatabaseError::from(Box::new(
@@ -84,6 +344,30 @@ impl QueryParser {
         self.build_string_literal(string_literal)
     }
 
+    /// Extracts the `DumpSource` of a `LOAD_FROM` or `LOAD_TABLE` statement: the path
+    /// of its `file_path` or the content of its `bytes_literal`.
+    fn build_dump_source(&self, pair: Pair<'_, Rule>) -> DatabaseResult<DumpSource> {
+        let source = pair.into_inner().next().unwrap();
+        match source.as_rule() {
+            Rule::bytes_literal => Ok(DumpSource::Bytes(self.build_bytes_literal(source)?)),
+            _ => {
+                let string_literal = source.into_inner().next().unwrap();
+                Ok(DumpSource::File(self.build_string_literal(string_literal)))
+            }
+        }
+    }
+
+    /// Extracts the passphrase of the optional `ENCRYPTED WITH` (of `DUMP_TO`) or
+    /// `WITH` (of `LOAD_FROM` and `VERIFY`) clause that follows the file path.
+    fn build_passphrase(&self, pair: Pair<'_, Rule>) -> Option<String> {
+        let mut clause = pair.into_inner().nth(1)?;
+        if clause.as_rule() == Rule::encrypted_clause {
+            clause = clause.into_inner().next().unwrap();
+        }
+        let string_literal = clause.into_inner().next().unwrap();
+        Some(self.build_string_literal(string_literal))
+    }
+
     /// Converts inner literal (int, float, string, or bool)
     /// and returns the appropriate `DataValue` variant.
     ///
@@ -109,6 +393,15 @@ impl QueryParser {
                 Ok(DataValue::Float(literal))
             }
             Rule::string_literal => Ok(DataValue::String(self.build_string_literal(inner))),
+            Rule::uuid_literal => {
+                let text = inner.as_str();
+                let uuid = text[2..text.len() - 1]
+                    .parse::<Uuid>()
+                    .map_err(ParsingError::from)
+                    .map_err(Box::new)?;
+                Ok(DataValue::Uuid(uuid))
+            }
+            Rule::bytes_literal => Ok(DataValue::Bytes(self.build_bytes_literal(inner)?)),
             Rule::bool_literal => {
                 let literal = inner
                     .as_str()
@@ -123,15 +416,38 @@ impl QueryParser {
         }
     }
 
-    /// Extracts the content of a `string_literal` `Pair`, removing the surrounding quotes.
+    /// Decodes the hex digits of a `bytes_literal` `Pair`, following its `0x` prefix.
+    fn build_bytes_literal(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Vec<u8>> {
+        let digits = &pair.as_str()[2..];
+        let bytes = (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParsingError::from)
+            .map_err(Box::new)?;
+        Ok(bytes)
+    }
+
+    /// Extracts the content of a `string_literal` `Pair`, removing the surrounding quotes
+    /// and unescaping the quotes and backslashes preceded by a backslash.
     fn build_string_literal(&self, pair: Pair<'_, Rule>) -> String {
         let s = pair.as_str();
-        s[1..s.len() - 1].to_string()
+        let mut content = String::with_capacity(s.len() - 2);
+        let mut chars = s[1..s.len() - 1].chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => content.push(chars.next_if(|c| matches!(c, '"' | '\\')).unwrap_or(c)),
+                c => content.push(c),
+            }
+        }
+        content
     }
 
     /// Builds a `CreateStmt` from a `create_stmt` `Pair`.
     ///
-    /// It parses the table name, key field, and the list of field definitions.
+    /// It parses the table name, key field, the list of field definitions
+    /// together with their optional `DEFAULT` values, the `CHECK` clauses and the
+    /// optional `STORAGE` clause.
     fn build_create_stmt(&self, pair: Pair<'_, Rule>, query: &str) -> DatabaseResult<CreateStmt> {
         let mut inner = pair.into_inner();
         let table_name = inner.next().unwrap().as_str().to_string();
@@ -143,34 +459,108 @@ impl QueryParser {
             .map(|field_def_pair| {
                 let mut field_inner = field_def_pair.into_inner();
                 let name = field_inner.next().unwrap().as_str().to_string();
-                let data_type_pair = field_inner.next().unwrap();
+                let type_pair = field_inner.next().unwrap();
 
-                let data_type = DataType::try_from(data_type_pair.as_str())?;
+                let (data_type, variants) = match type_pair.as_rule() {
+                    Rule::enum_type => {
+                        let variants = type_pair
+                            .into_inner()
+                            .map(|variant| self.build_string_literal(variant))
+                            .collect();
+                        (DataType::String, Some(variants))
+                    }
+                    _ => (DataType::try_from(type_pair.as_str())?, None),
+                };
+                let default = field_inner
+                    .next()
+                    .map(|value_pair| self.build_value(value_pair))
+                    .transpose()?;
 
-                let field_def = Field { name, data_type };
+                let field_def = Field {
+                    name,
+                    data_type,
+                    variants,
+                    default,
+                };
 
                 Ok(field_def)
             })
             .collect::<DatabaseResult<Vec<_>>>()?;
 
+        let mut checks = Vec::new();
+        let mut storage = TableStorage::default();
+        for clause in inner {
+            match clause.as_rule() {
+                Rule::check_clause => {
+                    checks.push(self.build_condition(clause.into_inner().next().unwrap())?)
+                }
+                _ => {
+                    let storage_kind = clause.into_inner().next().unwrap();
+                    storage = match storage_kind.into_inner().next().unwrap().as_rule() {
+                        Rule::KW_DISK => TableStorage::Disk,
+                        _ => TableStorage::Memory,
+                    };
+                }
+            }
+        }
+
         Ok(CreateStmt {
             table_name,
             key_field,
             fields,
+            checks,
+            storage,
             query: query.to_string(),
         })
     }
 
     /// Builds an `InsertStmt` from an `insert_stmt` `Pair`.
     ///
-    /// It parses the table name and the list of value assignments.
+    /// It parses the table name, the list of value assignments and the optional
+    /// `ON CONFLICT` and `RETURNING` clauses.
     fn build_insert_stmt(&self, pair: Pair<'_, Rule>, query: &str) -> DatabaseResult<InsertStmt> {
         let mut inner = pair.into_inner();
         let assignment_list = inner.next().unwrap();
         let table_name = inner.next().unwrap().as_str().to_string();
 
-        let assignments = assignment_list
-            .into_inner()
+        let assignments = self.build_assignment_list(assignment_list)?;
+        let mut on_conflict = None;
+        let mut returning = None;
+
+        for optional_pair in inner {
+            match optional_pair.as_rule() {
+                Rule::on_conflict_clause => {
+                    let action = optional_pair.into_inner().next().unwrap();
+                    on_conflict = Some(ConflictAction::try_from(action.as_str())?);
+                }
+                Rule::returning_clause => returning = Some(self.build_returning(optional_pair)),
+                rule => {
+                    return Err(DatabaseError::from(Box::new(
+                        ParsingError::UnexpectedRule { rule },
+                    )));
+                }
+            }
+        }
+
+        Ok(InsertStmt {
+            table_name,
+            assignments,
+            on_conflict,
+            returning,
+            query: query.to_string(),
+        })
+    }
+
+    /// Collects the field names of a `returning_clause` `Pair`.
+    fn build_returning(&self, pair: Pair<'_, Rule>) -> Vec<String> {
+        pair.into_inner()
+            .map(|identifier| identifier.as_str().to_string())
+            .collect()
+    }
+
+    /// Builds the `Assignment`s of an `assignment_list` `Pair`.
+    fn build_assignment_list(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Vec<Assignment>> {
+        pair.into_inner()
             .map(|assignment_pair| {
                 let mut assignment_inner = assignment_pair.into_inner();
                 let field_name = assignment_inner.next().unwrap().as_str().to_string();
@@ -181,26 +571,99 @@ impl QueryParser {
                     value: self.build_value(value)?,
                 })
             })
-            .collect::<DatabaseResult<Vec<_>>>()?;
+            .collect()
+    }
 
-        Ok(InsertStmt {
+    /// Builds an `UpdateStmt` from an `update_stmt` `Pair`.
+    ///
+    /// It parses the table name, the list of assignments and the optional `WHERE`
+    /// and `RETURNING` clauses.
+    fn build_update_stmt(&self, pair: Pair<'_, Rule>, query: &str) -> DatabaseResult<UpdateStmt> {
+        let mut inner = pair.into_inner();
+        let table_name = inner.next().unwrap().as_str().to_string();
+        let assignments = self.build_assignment_list(inner.next().unwrap())?;
+        let mut where_clause = None;
+        let mut returning = None;
+
+        for optional_pair in inner {
+            match optional_pair.as_rule() {
+                Rule::where_clause => {
+                    let condition_pair = optional_pair.into_inner().next().unwrap();
+                    where_clause = Some(self.build_condition(condition_pair)?);
+                }
+                Rule::returning_clause => returning = Some(self.build_returning(optional_pair)),
+                rule => {
+                    return Err(DatabaseError::from(Box::new(
+                        ParsingError::UnexpectedRule { rule },
+                    )));
+                }
+            }
+        }
+
+        Ok(UpdateStmt {
             table_name,
             assignments,
+            where_clause,
+            returning,
             query: query.to_string(),
         })
     }
 
     /// Builds a `DeleteStmt` from a `delete_stmt` `Pair`.
     ///
-    /// It parses the target table name and the primary key value.
+    /// It parses the target table name, the primary key value and the optional
+    /// `RETURNING` fields.
     fn build_delete_stmt(&self, pair: Pair<'_, Rule>, query: &str) -> DatabaseResult<DeleteStmt> {
         let mut inner = pair.into_inner();
         let key_value = inner.next().unwrap();
         let table_name = inner.next().unwrap().as_str().to_string();
+        let returning = inner
+            .next()
+            .map(|returning_clause| self.build_returning(returning_clause));
 
         Ok(DeleteStmt {
             table_name,
             key_value: self.build_value(key_value)?,
+            returning,
+            query: query.to_string(),
+        })
+    }
+
+    /// Builds a `GenerateStmt` from a `generate_stmt` `Pair`.
+    ///
+    /// It parses the record count, the target table name and the optional seed.
+    fn build_generate_stmt(
+        &self,
+        pair: Pair<'_, Rule>,
+        query: &str,
+    ) -> DatabaseResult<GenerateStmt> {
+        let mut inner = pair.into_inner();
+        let count = inner
+            .next()
+            .unwrap()
+            .as_str()
+            .parse::<usize>()
+            .map_err(ParsingError::from)
+            .map_err(Box::new)?;
+        let table_name = inner.next().unwrap().as_str().to_string();
+        let seed = inner
+            .next()
+            .map(|seed_clause| {
+                seed_clause
+                    .into_inner()
+                    .next()
+                    .unwrap()
+                    .as_str()
+                    .parse::<u64>()
+                    .map_err(ParsingError::from)
+                    .map_err(Box::new)
+            })
+            .transpose()?;
+
+        Ok(GenerateStmt {
+            table_name,
+            count,
+            seed,
             query: query.to_string(),
         })
     }
@@ -217,8 +680,13 @@ impl QueryParser {
 
         let fields = field_list_pair
             .into_inner()
-            .map(|id_pair| id_pair.as_str().to_string())
-            .collect();
+            .map(|select_item_pair| {
+                let mut item_inner = select_item_pair.into_inner();
+                let projection = self.build_projection(item_inner.next().unwrap())?;
+                let alias = item_inner.next().map(|alias| alias.as_str().to_string());
+                Ok(SelectItem { projection, alias })
+            })
+            .collect::<DatabaseResult<Vec<_>>>()?;
 
         let table_name = from_clause_pair
             .into_inner()
@@ -237,6 +705,65 @@ impl QueryParser {
         })
     }
 
+    /// Builds a `CompoundSelectStmt` from a `compound_select_stmt` `Pair`.
+    ///
+    /// Every `SELECT` keeps its own part of the query as its `query`.
+    fn build_compound_select_stmt(
+        &self,
+        pair: Pair<'_, Rule>,
+        query: &str,
+    ) -> DatabaseResult<CompoundSelectStmt> {
+        let mut inner = pair.into_inner();
+        let first_pair = inner.next().unwrap();
+        let first = self.build_select_stmt(first_pair.clone(), first_pair.as_str().trim_end())?;
+
+        let mut rest = Vec::new();
+        while let Some(operator_pair) = inner.next() {
+            let operator = SetOperator::try_from(operator_pair.as_str())?;
+            let select_pair = inner.next().unwrap();
+            rest.push((
+                operator,
+                self.build_select_stmt(select_pair.clone(), select_pair.as_str().trim_end())?,
+            ));
+        }
+
+        Ok(CompoundSelectStmt {
+            first,
+            rest,
+            query: query.to_string(),
+        })
+    }
+
+    /// Builds a `Projection` from a `projection` `Pair`, which is a plain column
+    /// name, an aggregate such as `COUNT(*)` or a `CASE` expression.
+    fn build_projection(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Projection> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::identifier => Ok(Projection::Field(inner.as_str().to_string())),
+            Rule::case_expression => Ok(Projection::Expression(self.build_case(inner)?)),
+            Rule::aggregate => {
+                let mut aggregate_inner = inner.into_inner();
+                let function_pair = aggregate_inner.next().unwrap();
+                if function_pair.as_rule() == Rule::count_all {
+                    return Ok(Projection::Aggregate(Aggregate {
+                        function: AggregateFunction::Count,
+                        field: None,
+                    }));
+                }
+
+                let function = AggregateFunction::try_from(function_pair.as_str())?;
+                let field = aggregate_inner.next().unwrap().as_str().to_string();
+                Ok(Projection::Aggregate(Aggregate {
+                    function,
+                    field: Some(field),
+                }))
+            }
+            rule => Err(DatabaseError::from(Box::new(
+                ParsingError::UnexpectedRule { rule },
+            ))),
+        }
+    }
+
     /// Parses the optional clauses (`WHERE`, `ORDER BY`, `LIMIT`) for a `SELECT` statement.
     ///
     /// It iterates over the remaining pairs from the `select_stmt` and populates
@@ -255,10 +782,13 @@ impl QueryParser {
                 }
                 Rule::orderby_clause => {
                     let mut order_by_inner = optional_pair.into_inner();
-                    let column = order_by_inner.next().unwrap().as_str().to_string();
+                    let expression = self.build_expression(order_by_inner.next().unwrap())?;
                     let direction_pair = order_by_inner.next().unwrap();
                     let direction = OrderDirection::try_from(direction_pair.as_str())?;
-                    clauses.order_by = Some(OrderBy { column, direction });
+                    clauses.order_by = Some(OrderBy {
+                        expression,
+                        direction,
+                    });
                 }
                 Rule::limit_clause => {
                     let int_pair = optional_pair.into_inner().next().unwrap();
@@ -270,6 +800,16 @@ impl QueryParser {
                             .map_err(Box::new)?,
                     );
                 }
+                Rule::offset_clause => {
+                    let int_pair = optional_pair.into_inner().next().unwrap();
+                    clauses.offset = Some(
+                        int_pair
+                            .as_str()
+                            .parse::<i64>()
+                            .map_err(ParsingError::from)
+                            .map_err(Box::new)?,
+                    );
+                }
                 rule => {
                     return Err(DatabaseError::from(Box::new(
                         ParsingError::UnexpectedRule { rule },
@@ -281,10 +821,80 @@ impl QueryParser {
         Ok(clauses)
     }
 
+    /// Builds an `Expression` tree from an `expression` `Pair`, applying the
+    /// precedences of [`EXPRESSION_PARSER`].
+    fn build_expression(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Expression> {
+        EXPRESSION_PARSER
+            .map_primary(|factor| self.build_factor(factor))
+            .map_infix(|left, op, right| {
+                Ok(Expression::Binary {
+                    left: Box::new(left?),
+                    op: ArithmeticOperator::try_from(op.as_str())?,
+                    right: Box::new(right?),
+                })
+            })
+            .parse(pair.into_inner())
+    }
+
+    /// Builds the base case of an expression: a column, a literal, a function
+    /// call or a parenthesized expression.
+    fn build_factor(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Expression> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::identifier => Ok(Expression::Column(inner.as_str().to_string())),
+            Rule::value => Ok(Expression::Literal(self.build_value(inner)?)),
+            Rule::expression => self.build_expression(inner),
+            Rule::case_expression => self.build_case(inner),
+            Rule::function_call => {
+                let mut call_inner = inner.into_inner();
+                let function = ScalarFunction::try_from(call_inner.next().unwrap().as_str())?;
+                let argument = self.build_expression(call_inner.next().unwrap())?;
+                Ok(Expression::Function {
+                    function,
+                    argument: Box::new(argument),
+                })
+            }
+            rule => Err(DatabaseError::from(Box::new(
+                ParsingError::UnexpectedRule { rule },
+            ))),
+        }
+    }
+
+    /// Builds an `Expression::Case` from a `case_expression` `Pair`.
+    ///
+    /// Every `when_branch` pairs a condition with its result; the last inner
+    /// pair is the `ELSE` result.
+    fn build_case(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Expression> {
+        let mut branches = Vec::new();
+        let mut otherwise = None;
+
+        for inner in pair.into_inner() {
+            match inner.as_rule() {
+                Rule::when_branch => {
+                    let mut branch_inner = inner.into_inner();
+                    let condition = self.build_condition(branch_inner.next().unwrap())?;
+                    let result = self.build_expression(branch_inner.next().unwrap())?;
+                    branches.push((condition, result));
+                }
+                Rule::expression => otherwise = Some(self.build_expression(inner)?),
+                rule => {
+                    return Err(DatabaseError::from(Box::new(
+                        ParsingError::UnexpectedRule { rule },
+                    )));
+                }
+            }
+        }
+
+        Ok(Expression::Case {
+            branches,
+            otherwise: Box::new(otherwise.unwrap()),
+        })
+    }
+
     /// Builds the base case for a `WHERE` condition.
     ///
-    /// This handles either a parenthesized `(condition)` or a simple
-    /// `identifier op value` comparison.
+    /// This handles either a parenthesized `(condition)`, a simple
+    /// `identifier op value` comparison or an `identifier IN (values)` test.
     fn build_primary_condition(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Condition> {
         let mut inner = pair.into_inner();
         let first_child = inner.next().unwrap();
@@ -294,6 +904,15 @@ impl QueryParser {
             Rule::identifier => {
                 let field = first_child.as_str().to_string();
                 let operator = inner.next().unwrap();
+
+                if operator.as_rule() == Rule::in_list {
+                    let values = operator
+                        .into_inner()
+                        .map(|value| self.build_value(value))
+                        .collect::<DatabaseResult<Vec<_>>>()?;
+                    return Ok(Condition::In { field, values });
+                }
+
                 let value = inner.next().unwrap();
 
                 Ok(Condition::Comparison(Comparison {
@@ -308,42 +927,28 @@ impl QueryParser {
         }
     }
 
-    /// Builds a `Condition` tree for `AND` expressions.
-    ///
-    /// It correctly handles precedence by chaining multiple `AND`s
-    /// from left to right.
-    fn build_and_condition(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Condition> {
-        let mut inner = pair.into_inner();
-        let first_child = inner.next().unwrap();
-        let mut left = self.build_primary_condition(first_child)?;
-
-        for right_pair in inner {
-            let right = self.build_primary_condition(right_pair)?;
-            left = Condition::And {
-                left: Box::new(left),
-                right: Box::new(right),
-            };
-        }
-        Ok(left)
-    }
-
-    /// Builds a `Condition` tree for `OR` expressions.
-    ///
-    /// This is the top-level condition builder, which chains `and_condition`
-    /// pairs with `OR` operators, correctly managing precedence (AND > OR).
+    /// Builds a `Condition` tree from a `condition` `Pair`, applying the
+    /// precedences of [`CONDITION_PARSER`].
     fn build_condition(&self, pair: Pair<'_, Rule>) -> DatabaseResult<Condition> {
-        let mut inner = pair.into_inner();
-        let first_child = inner.next().unwrap();
-        let mut left = self.build_and_condition(first_child)?;
-
-        for right_pair in inner {
-            let right = self.build_and_condition(right_pair)?;
-            left = Condition::Or {
-                left: Box::new(left),
-                right: Box::new(right),
-            };
-        }
-        Ok(left)
+        CONDITION_PARSER
+            .map_primary(|primary| self.build_primary_condition(primary))
+            .map_prefix(|op, operand| match op.as_rule() {
+                Rule::not_op => Ok(Condition::Not(Box::new(operand?))),
+                rule => Err(DatabaseError::from(Box::new(
+                    ParsingError::UnexpectedRule { rule },
+                ))),
+            })
+            .map_infix(|left, op, right| {
+                let (left, right) = (Box::new(left?), Box::new(right?));
+                match op.as_rule() {
+                    Rule::and_op => Ok(Condition::And { left, right }),
+                    Rule::or_op => Ok(Condition::Or { left, right }),
+                    rule => Err(DatabaseError::from(Box::new(
+                        ParsingError::UnexpectedRule { rule },
+                    ))),
+                }
+            })
+            .parse(pair.into_inner())
     }
 }
 
@@ -352,9 +957,10 @@ mod tests {
     use super::*;
     use crate::{
         ast::{
-            Assignment, Command, Comparison, Condition, CreateStmt, DeleteStmt, DumpToStmt, Field,
-            InsertStmt, LoadFromStmt, Operator, OptionalClauses, OrderBy, OrderDirection,
-            ReadFromStmt, SaveAsStmt, SelectStmt,
+            Assignment, Command, Comparison, Condition, CreateStmt, DeleteStmt, DumpSource,
+            DumpToStmt, ExportJsonStmt, Field, GenerateStmt, ImportJsonStmt, InsertStmt,
+            LoadCsvStmt, LoadFromStmt, Operator, OptionalClauses, OrderBy, OrderDirection,
+            ReadFromStmt, RenameStmt, SaveAsStmt, SelectStmt, TruncateStmt, UpdateStmt,
         },
         core::{DataType, DataValue},
     };
@@ -391,55 +997,265 @@ mod tests {
                 Field {
                     name: "id".to_string(),
                     data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
                 Field {
                     name: "name".to_string(),
                     data_type: DataType::String,
+                    variants: None,
+                    default: None,
                 },
             ],
+            checks: Vec::new(),
+            storage: TableStorage::default(),
             query: query.to_string(),
         });
         assert_eq!(parse_helper(query), expected);
     }
 
     #[test]
-    fn test_parse_insert() {
-        let query = "INSERT id = 123, name = \"Alice\", active = true INTO users;";
-        let expected = Command::Insert(InsertStmt {
+    fn test_parse_create_with_defaults() {
+        let query = "CREATE users KEY id FIELDS id: INT, age: INT DEFAULT 0, city: STRING DEFAULT \"Warsaw\";";
+        let expected = Command::Create(CreateStmt {
             table_name: "users".to_string(),
-            assignments: vec![
-                Assignment {
-                    field_name: "id".to_string(),
-                    value: DataValue::Int(123),
+            key_field: "id".to_string(),
+            fields: vec![
+                Field {
+                    name: "id".to_string(),
+                    data_type: DataType::Int,
+                    variants: None,
+                    default: None,
                 },
-                Assignment {
-                    field_name: "name".to_string(),
-                    value: DataValue::String("Alice".to_string()),
+                Field {
+                    name: "age".to_string(),
+                    data_type: DataType::Int,
+                    variants: None,
+                    default: Some(DataValue::Int(0)),
                 },
-                Assignment {
-                    field_name: "active".to_string(),
-                    value: DataValue::Boolean(true),
+                Field {
+                    name: "city".to_string(),
+                    data_type: DataType::String,
+                    variants: None,
+                    default: Some(DataValue::String("Warsaw".to_string())),
                 },
             ],
+            checks: Vec::new(),
+            storage: TableStorage::default(),
             query: query.to_string(),
         });
         assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("CREATE users KEY id FIELDS id: INT DEFAULT;");
     }
 
     #[test]
-    fn test_parse_delete() {
-        let query = "DELETE \"user-key-1\" FROM users;";
-        let expected = Command::Delete(DeleteStmt {
-            table_name: "users".to_string(),
-            key_value: DataValue::String("user-key-1".to_string()),
-            query: query.to_string(),
-        });
-        assert_eq!(parse_helper(query), expected);
+    fn test_parse_create_with_enum() {
+        let query =
+            "CREATE tasks KEY id FIELDS id: INT, status: ENUM(\"new\", \"done\") DEFAULT \"new\";";
+        let Command::Create(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Create");
+        };
+        assert_eq!(
+            stmt.fields[1],
+            Field {
+                name: "status".to_string(),
+                data_type: DataType::String,
+                variants: Some(vec!["new".to_string(), "done".to_string()]),
+                default: Some(DataValue::String("new".to_string())),
+            }
+        );
+
+        parse_helper_fails("CREATE tasks KEY id FIELDS id: INT, status: ENUM();");
+        parse_helper_fails("CREATE tasks KEY id FIELDS id: INT, status: ENUM(1, 2);");
     }
 
     #[test]
-    fn test_parse_select_all_clauses() {
-        let query = "SELECT id, name FROM users WHERE (id > 10 OR name = \"Bob\") AND active = true ORDER_BY name DESC LIMIT 5;";
+    fn test_parse_create_with_checks() {
+        let query = "CREATE users KEY id FIELDS id: INT, age: INT CHECK (age >= 0 AND age < 150) CHECK (NOT id = 0);";
+        let Command::Create(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Create");
+        };
+        let comparison = |field: &str, op, value| {
+            Condition::Comparison(Comparison {
+                field: field.to_string(),
+                op,
+                value: DataValue::Int(value),
+            })
+        };
+        assert_eq!(
+            stmt.checks,
+            [
+                Condition::And {
+                    left: Box::new(comparison("age", Operator::GtEq, 0)),
+                    right: Box::new(comparison("age", Operator::Lt, 150)),
+                },
+                Condition::Not(Box::new(comparison("id", Operator::Eq, 0))),
+            ]
+        );
+
+        parse_helper_fails("CREATE users KEY id FIELDS id: INT CHECK id > 0;");
+        parse_helper_fails("CREATE users KEY id FIELDS id: INT CHECK ();");
+    }
+
+    #[test]
+    fn test_parse_create_with_storage() {
+        let query = "CREATE users KEY id FIELDS id: INT, age: INT CHECK (age >= 0) STORAGE DISK;";
+        let Command::Create(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Create");
+        };
+        assert_eq!(stmt.checks.len(), 1);
+        assert_eq!(stmt.storage, TableStorage::Disk);
+
+        let Command::Create(stmt) =
+            parse_helper("CREATE users KEY id FIELDS id: INT STORAGE MEMORY;")
+        else {
+            panic!("Expected Command::Create");
+        };
+        assert_eq!(stmt.storage, TableStorage::Memory);
+
+        parse_helper_fails("CREATE users KEY id FIELDS id: INT STORAGE;");
+        parse_helper_fails("CREATE users KEY id FIELDS id: INT STORAGE DISK CHECK (id > 0);");
+    }
+
+    #[test]
+    fn test_parse_insert() {
+        let query = "INSERT id = 123, name = \"Alice\", active = true INTO users;";
+        let expected = Command::Insert(InsertStmt {
+            table_name: "users".to_string(),
+            assignments: vec![
+                Assignment {
+                    field_name: "id".to_string(),
+                    value: DataValue::Int(123),
+                },
+                Assignment {
+                    field_name: "name".to_string(),
+                    value: DataValue::String("Alice".to_string()),
+                },
+                Assignment {
+                    field_name: "active".to_string(),
+                    value: DataValue::Boolean(true),
+                },
+            ],
+            on_conflict: None,
+            returning: None,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+    }
+
+    #[test]
+    fn test_parse_insert_on_conflict() {
+        for (action, expected_action) in [
+            ("REPLACE", ConflictAction::Replace),
+            ("UPDATE", ConflictAction::Update),
+        ] {
+            let query = format!("INSERT id = 1 INTO users ON CONFLICT {action};");
+            let expected = Command::Insert(InsertStmt {
+                table_name: "users".to_string(),
+                assignments: vec![Assignment {
+                    field_name: "id".to_string(),
+                    value: DataValue::Int(1),
+                }],
+                on_conflict: Some(expected_action),
+                returning: None,
+                query: query.clone(),
+            });
+            assert_eq!(parse_helper(&query), expected);
+        }
+
+        parse_helper_fails("INSERT id = 1 INTO users ON CONFLICT;");
+        parse_helper_fails("INSERT id = 1 INTO users ON CONFLICT IGNORE;");
+    }
+
+    #[test]
+    fn test_parse_bytes_literal() {
+        let query = "INSERT id = 1, data = 0x00ff7A, empty = 0x INTO files;";
+        let Command::Insert(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Insert");
+        };
+        assert_eq!(
+            stmt.assignments[1].value,
+            DataValue::Bytes(vec![0x00, 0xff, 0x7a])
+        );
+        assert_eq!(stmt.assignments[2].value, DataValue::Bytes(Vec::new()));
+        assert_eq!(stmt.assignments[1].value.to_string(), "0x00ff7a");
+
+        parse_helper_fails("INSERT id = 1, data = 0xabc INTO files;");
+        parse_helper_fails("INSERT id = 1, data = 0xzz INTO files;");
+    }
+
+    #[test]
+    fn test_parse_escaped_string_literal() {
+        let query = r#"INSERT id = 1, name = "Smith, \"Jr\" \\ C:\dir" INTO users;"#;
+        let Command::Insert(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Insert");
+        };
+        let name = DataValue::String(r#"Smith, "Jr" \ C:\dir"#.to_string());
+        assert_eq!(stmt.assignments[1].value, name);
+        assert_eq!(name.to_string(), r#""Smith, \"Jr\" \\ C:\\dir""#);
+
+        parse_helper_fails(r#"INSERT id = 1, name = "C:\" INTO users;"#);
+    }
+
+    #[test]
+    fn test_parse_uuid_literal() {
+        let query = "DELETE u\"123e4567-E89B-12d3-a456-426614174000\" FROM sessions;";
+        let Command::Delete(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Delete");
+        };
+        let uuid = "123e4567-e89b-12d3-a456-426614174000"
+            .parse::<Uuid>()
+            .unwrap();
+        assert_eq!(stmt.key_value, DataValue::Uuid(uuid));
+        assert_eq!(
+            stmt.key_value.to_string(),
+            "u\"123e4567-e89b-12d3-a456-426614174000\""
+        );
+
+        parse_helper_fails("DELETE u\"123e4567-e89b-12d3-a456\" FROM sessions;");
+        parse_helper_fails("DELETE u\"123e4567e89b12d3a456426614174000\" FROM sessions;");
+    }
+
+    #[test]
+    fn test_parse_delete() {
+        let query = "DELETE \"user-key-1\" FROM users;";
+        let expected = Command::Delete(DeleteStmt {
+            table_name: "users".to_string(),
+            key_value: DataValue::String("user-key-1".to_string()),
+            returning: None,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let query = "GENERATE 10000 INTO users;";
+        let expected = Command::Generate(GenerateStmt {
+            table_name: "users".to_string(),
+            count: 10000,
+            seed: None,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "GENERATE 5 INTO users SEED 42;";
+        let expected = Command::Generate(GenerateStmt {
+            table_name: "users".to_string(),
+            count: 5,
+            seed: Some(42),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("GENERATE -5 INTO users;");
+        parse_helper_fails("GENERATE INTO users;");
+    }
+
+    #[test]
+    fn test_parse_select_all_clauses() {
+        let query = "SELECT id, name FROM users WHERE (id > 10 OR name = \"Bob\") AND active = true ORDER_BY name DESC LIMIT 5 OFFSET 10;";
 
         let expected_condition = Condition::And {
             left: Box::new(Condition::Or {
@@ -462,15 +1278,19 @@ mod tests {
         };
 
         let expected = Command::Select(SelectStmt {
-            fields: vec!["id".to_string(), "name".to_string()],
+            fields: vec![
+                Projection::Field("id".to_string()).into(),
+                Projection::Field("name".to_string()).into(),
+            ],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses {
                 where_clause: Some(expected_condition),
                 order_by: Some(OrderBy {
-                    column: "name".to_string(),
+                    expression: Expression::Column("name".to_string()),
                     direction: OrderDirection::Desc,
                 }),
                 limit: Some(5),
+                offset: Some(10),
             },
             query: query.to_string(),
         });
@@ -481,7 +1301,7 @@ mod tests {
     fn test_parse_select_simple() {
         let query = "SELECT name FROM users;";
         let expected = Command::Select(SelectStmt {
-            fields: vec!["name".to_string()],
+            fields: vec![Projection::Field("name".to_string()).into()],
             table_name: "users".to_string(),
             optional_clauses: OptionalClauses::default(),
             query: query.to_string(),
@@ -489,22 +1309,691 @@ mod tests {
         assert_eq!(parse_helper(query), expected);
     }
 
+    #[test]
+    fn test_parse_select_offset_without_limit() {
+        let query = "SELECT name FROM users OFFSET 20;";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![Projection::Field("name".to_string()).into()],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses {
+                offset: Some(20),
+                ..Default::default()
+            },
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("SELECT name FROM users OFFSET 20 LIMIT 5;");
+    }
+
+    #[test]
+    fn test_parse_select_not_precedence() {
+        let comparison = |field: &str, value: i64| {
+            Box::new(Condition::Comparison(Comparison {
+                field: field.to_string(),
+                op: Operator::Eq,
+                value: DataValue::Int(value),
+            }))
+        };
+        let query = "SELECT id FROM users WHERE NOT a = 1 AND b = 2 OR NOT NOT (c = 3 OR d = 4);";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![Projection::Field("id".to_string()).into()],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses {
+                where_clause: Some(Condition::Or {
+                    left: Box::new(Condition::And {
+                        left: Box::new(Condition::Not(comparison("a", 1))),
+                        right: comparison("b", 2),
+                    }),
+                    right: Box::new(Condition::Not(Box::new(Condition::Not(Box::new(
+                        Condition::Or {
+                            left: comparison("c", 3),
+                            right: comparison("d", 4),
+                        },
+                    ))))),
+                }),
+                ..Default::default()
+            },
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("SELECT id FROM users WHERE a = 1 NOT;");
+        parse_helper_fails("SELECT id FROM users WHERE NOT;");
+    }
+
+    #[test]
+    fn test_parse_select_like() {
+        let query = "SELECT id FROM users WHERE name LIKE \"J_n%\";";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![Projection::Field("id".to_string()).into()],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses {
+                where_clause: Some(Condition::Comparison(Comparison {
+                    field: "name".to_string(),
+                    op: Operator::Like,
+                    value: DataValue::String("J_n%".to_string()),
+                })),
+                ..Default::default()
+            },
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("SELECT id FROM users WHERE name LIKES \"J%\";");
+    }
+
+    #[test]
+    fn test_parse_select_in() {
+        let query = "SELECT id FROM users WHERE status IN (\"active\", \"pending\") OR id IN (1);";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![Projection::Field("id".to_string()).into()],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses {
+                where_clause: Some(Condition::Or {
+                    left: Box::new(Condition::In {
+                        field: "status".to_string(),
+                        values: vec![
+                            DataValue::String("active".to_string()),
+                            DataValue::String("pending".to_string()),
+                        ],
+                    }),
+                    right: Box::new(Condition::In {
+                        field: "id".to_string(),
+                        values: vec![DataValue::Int(1)],
+                    }),
+                }),
+                ..Default::default()
+            },
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("SELECT id FROM users WHERE id IN ();");
+        parse_helper_fails("SELECT id FROM users WHERE id IN 1, 2;");
+    }
+
+    #[test]
+    fn test_parse_select_aggregates() {
+        let query = "SELECT COUNT(*), SUM(age), MAX(name) FROM users WHERE age > 18;";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![
+                Projection::Aggregate(Aggregate {
+                    function: AggregateFunction::Count,
+                    field: None,
+                })
+                .into(),
+                Projection::Aggregate(Aggregate {
+                    function: AggregateFunction::Sum,
+                    field: Some("age".to_string()),
+                })
+                .into(),
+                Projection::Aggregate(Aggregate {
+                    function: AggregateFunction::Max,
+                    field: Some("name".to_string()),
+                })
+                .into(),
+            ],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses {
+                where_clause: Some(Condition::Comparison(Comparison {
+                    field: "age".to_string(),
+                    op: Operator::Gt,
+                    value: DataValue::Int(18),
+                })),
+                ..Default::default()
+            },
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+    }
+
+    #[test]
+    fn test_parse_select_identifiers_starting_with_keywords() {
+        let query = "SELECT COUNTRY, MINUTES FROM SETTINGS;";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![
+                Projection::Field("COUNTRY".to_string()).into(),
+                Projection::Field("MINUTES".to_string()).into(),
+            ],
+            table_name: "SETTINGS".to_string(),
+            optional_clauses: OptionalClauses::default(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+    }
+
+    #[test]
+    fn test_parse_select_aliases() {
+        let query = "SELECT id, name AS username, COUNT(*) AS total FROM users;";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![
+                Projection::Field("id".to_string()).into(),
+                SelectItem {
+                    projection: Projection::Field("name".to_string()),
+                    alias: Some("username".to_string()),
+                },
+                SelectItem {
+                    projection: Projection::Aggregate(Aggregate {
+                        function: AggregateFunction::Count,
+                        field: None,
+                    }),
+                    alias: Some("total".to_string()),
+                },
+            ],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses::default(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("SELECT name AS FROM users;");
+        parse_helper_fails("SELECT name AS ASC FROM users;");
+    }
+
+    #[test]
+    fn test_parse_select_order_by_expression() {
+        let order_by = |query: &str| match parse_helper(query) {
+            Command::Select(stmt) => stmt.optional_clauses.order_by.unwrap().expression,
+            command => panic!("Expected Command::Select, got {command:?}"),
+        };
+        let column = |name: &str| Box::new(Expression::Column(name.to_string()));
+
+        assert_eq!(
+            order_by("SELECT id FROM users ORDER_BY age * -1 DESC;"),
+            Expression::Binary {
+                left: column("age"),
+                op: ArithmeticOperator::Mul,
+                right: Box::new(Expression::Literal(DataValue::Int(-1))),
+            }
+        );
+        assert_eq!(
+            order_by("SELECT id FROM users ORDER_BY LENGTH(name) ASC;"),
+            Expression::Function {
+                function: ScalarFunction::Length,
+                argument: column("name"),
+            }
+        );
+
+        // `*` binds tighter than `+`, and operators of equal precedence associate to the left
+        let precedence = order_by("SELECT id FROM users ORDER_BY a + b * c - d ASC;");
+        assert_eq!(precedence.to_string(), "((a + (b * c)) - d)");
+        let chained = order_by("SELECT id FROM users ORDER_BY a * b / c + d - e ASC;");
+        assert_eq!(chained.to_string(), "((((a * b) / c) + d) - e)");
+        let parenthesized = order_by("SELECT id FROM users ORDER_BY (a + b) * UPPER(c) ASC;");
+        assert_eq!(parenthesized.to_string(), "((a + b) * UPPER(c))");
+
+        parse_helper_fails("SELECT id FROM users ORDER_BY age * ASC;");
+        parse_helper_fails("SELECT id FROM users ORDER_BY LENGTH name ASC;");
+    }
+
+    #[test]
+    fn test_parse_select_case() {
+        let query = "SELECT id, CASE WHEN age >= 18 THEN \"adult\" ELSE \"minor\" END AS category FROM users;";
+        let expected = Command::Select(SelectStmt {
+            fields: vec![
+                Projection::Field("id".to_string()).into(),
+                SelectItem {
+                    projection: Projection::Expression(Expression::Case {
+                        branches: vec![(
+                            Condition::Comparison(Comparison {
+                                field: "age".to_string(),
+                                op: Operator::GtEq,
+                                value: DataValue::Int(18),
+                            }),
+                            Expression::Literal(DataValue::String("adult".to_string())),
+                        )],
+                        otherwise: Box::new(Expression::Literal(DataValue::String(
+                            "minor".to_string(),
+                        ))),
+                    }),
+                    alias: Some("category".to_string()),
+                },
+            ],
+            table_name: "users".to_string(),
+            optional_clauses: OptionalClauses::default(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "SELECT CASE WHEN a = 1 OR b = 2 THEN x WHEN NOT c IN (3) THEN LENGTH(y) ELSE 0 END FROM t ORDER_BY CASE WHEN a < 0 THEN 0 - a ELSE a END ASC;";
+        let Command::Select(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Select");
+        };
+        assert_eq!(
+            stmt.fields[0].output_name(),
+            "CASE WHEN (a = 1 OR b = 2) THEN x WHEN NOT c IN (3) THEN LENGTH(y) ELSE 0 END"
+        );
+        assert_eq!(
+            stmt.optional_clauses
+                .order_by
+                .unwrap()
+                .expression
+                .to_string(),
+            "CASE WHEN a < 0 THEN (0 - a) ELSE a END"
+        );
+
+        parse_helper_fails("SELECT CASE WHEN age > 1 THEN 1 END FROM users;");
+        parse_helper_fails("SELECT CASE ELSE 1 END FROM users;");
+        parse_helper_fails("SELECT CASE WHEN age THEN 1 ELSE 0 END FROM users;");
+    }
+
+    #[test]
+    fn test_parse_compound_select() {
+        let query = "SELECT id FROM users WHERE age > 30 UNION SELECT id FROM admins INTERSECT SELECT id FROM users LIMIT 5;";
+        let select = |table: &str, optional_clauses: OptionalClauses, query: &str| SelectStmt {
+            fields: vec![Projection::Field("id".to_string()).into()],
+            table_name: table.to_string(),
+            optional_clauses,
+            query: query.to_string(),
+        };
+        let expected = Command::CompoundSelect(CompoundSelectStmt {
+            first: select(
+                "users",
+                OptionalClauses {
+                    where_clause: Some(Condition::Comparison(Comparison {
+                        field: "age".to_string(),
+                        op: Operator::Gt,
+                        value: DataValue::Int(30),
+                    })),
+                    ..Default::default()
+                },
+                "SELECT id FROM users WHERE age > 30",
+            ),
+            rest: vec![
+                (
+                    SetOperator::Union,
+                    select(
+                        "admins",
+                        OptionalClauses::default(),
+                        "SELECT id FROM admins",
+                    ),
+                ),
+                (
+                    SetOperator::Intersect,
+                    select(
+                        "users",
+                        OptionalClauses {
+                            limit: Some(5),
+                            ..Default::default()
+                        },
+                        "SELECT id FROM users LIMIT 5",
+                    ),
+                ),
+            ],
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "SELECT name FROM users EXCEPT SELECT name FROM admins;";
+        assert!(matches!(
+            parse_helper(query),
+            Command::CompoundSelect(CompoundSelectStmt { rest, .. }) if rest[0].0 == SetOperator::Except
+        ));
+
+        parse_helper_fails("SELECT id FROM users UNION;");
+        parse_helper_fails("SELECT id FROM users UNION DELETE 1 FROM users;");
+    }
+
+    #[test]
+    fn test_parse_select_aggregates_fail() {
+        parse_helper_fails("SELECT SUM(*) FROM users;");
+        parse_helper_fails("SELECT COUNT() FROM users;");
+        parse_helper_fails("SELECT MEDIAN(age) FROM users;");
+    }
+
+    #[test]
+    fn test_parse_create_index() {
+        let query = "CREATE_INDEX idx ON users(age);";
+        let expected = Command::CreateIndex(CreateIndexStmt {
+            index_name: "idx".to_string(),
+            table_name: "users".to_string(),
+            field: "age".to_string(),
+            kind: IndexKind::BTree,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        for (query, kind) in [
+            (
+                "CREATE_INDEX idx ON users(age) USING BTREE;",
+                IndexKind::BTree,
+            ),
+            (
+                "CREATE_INDEX idx ON users(age) USING HASH;",
+                IndexKind::Hash,
+            ),
+        ] {
+            match parse_helper(query) {
+                Command::CreateIndex(stmt) => assert_eq!(stmt.kind, kind),
+                command => panic!("Expected Command::CreateIndex, got {command:?}"),
+            }
+        }
+
+        parse_helper_fails("CREATE_INDEX idx ON users(age) USING;");
+        parse_helper_fails("CREATE_INDEX idx ON users(age) USING TREE;");
+        parse_helper_fails("CREATE_INDEX ON users(age);");
+        parse_helper_fails("CREATE_INDEX idx users(age);");
+        parse_helper_fails("CREATE_INDEX idx ON users(age, name);");
+    }
+
+    #[test]
+    fn test_parse_analyze() {
+        let query = "ANALYZE users;";
+        let expected = Command::Analyze(AnalyzeStmt {
+            table_name: "users".to_string(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("ANALYZE;");
+        parse_helper_fails("ANALYZE users, orders;");
+    }
+
+    #[test]
+    fn test_parse_show_tables() {
+        assert_eq!(
+            parse_helper("SHOW TABLES;"),
+            Command::ShowTables(ShowTablesStmt)
+        );
+
+        parse_helper_fails("SHOW;");
+        parse_helper_fails("SHOW TABLES users;");
+    }
+
+    #[test]
+    fn test_parse_explain() {
+        let query = "EXPLAIN SELECT id FROM users WHERE id = 1;";
+        let Command::Explain(stmt) = parse_helper(query) else {
+            panic!("Expected Command::Explain");
+        };
+        assert_eq!(stmt.query, query);
+        assert_eq!(stmt.select.table_name, "users");
+        assert_eq!(stmt.select.query, "SELECT id FROM users WHERE id = 1");
+        assert!(stmt.select.optional_clauses.where_clause.is_some());
+
+        parse_helper_fails("EXPLAIN;");
+        parse_helper_fails("EXPLAIN DELETE 1 FROM users;");
+        parse_helper_fails("EXPLAIN SELECT id FROM users UNION SELECT id FROM admins;");
+    }
+
+    #[test]
+    fn test_parse_rename() {
+        let query = "RENAME users TO customers;";
+        let expected = Command::Rename(RenameStmt {
+            table_name: "users".to_string(),
+            new_name: "customers".to_string(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("RENAME users customers;");
+        parse_helper_fails("RENAME users TO;");
+    }
+
+    #[test]
+    fn test_parse_returning() {
+        let returning = Some(vec!["id".to_string(), "name".to_string()]);
+
+        let query = "INSERT id = 1 INTO users ON CONFLICT UPDATE RETURNING id, name;";
+        match parse_helper(query) {
+            Command::Insert(stmt) => {
+                assert_eq!(stmt.on_conflict, Some(ConflictAction::Update));
+                assert_eq!(stmt.returning, returning);
+            }
+            command => panic!("Expected an INSERT, got {command:?}"),
+        }
+
+        let query = "UPDATE users SET age = 1 WHERE id = 1 RETURNING id, name;";
+        match parse_helper(query) {
+            Command::Update(stmt) => {
+                assert!(stmt.where_clause.is_some());
+                assert_eq!(stmt.returning, returning);
+            }
+            command => panic!("Expected an UPDATE, got {command:?}"),
+        }
+
+        let query = "DELETE 1 FROM users RETURNING id, name;";
+        match parse_helper(query) {
+            Command::Delete(stmt) => assert_eq!(stmt.returning, returning),
+            command => panic!("Expected a DELETE, got {command:?}"),
+        }
+
+        parse_helper_fails("DELETE 1 FROM users RETURNING;");
+        parse_helper_fails("INSERT id = 1 INTO users RETURNING id ON CONFLICT UPDATE;");
+    }
+
+    #[test]
+    fn test_parse_truncate() {
+        let query = "TRUNCATE users;";
+        let expected = Command::Truncate(TruncateStmt {
+            table_name: "users".to_string(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("TRUNCATE;");
+        parse_helper_fails("TRUNCATE users, orders;");
+    }
+
+    #[test]
+    fn test_parse_transaction() {
+        for (query, stmt) in [
+            ("BEGIN;", TransactionStmt::Begin),
+            ("COMMIT;", TransactionStmt::Commit),
+            ("ROLLBACK;", TransactionStmt::Rollback),
+        ] {
+            assert_eq!(parse_helper(query), Command::Transaction(stmt));
+            assert_eq!(stmt.to_string(), query);
+        }
+
+        parse_helper_fails("BEGIN users;");
+        parse_helper_fails("COMMIT ROLLBACK;");
+    }
+
+    #[test]
+    fn test_parse_create_database_and_use() {
+        assert_eq!(
+            parse_helper("CREATE_DATABASE analytics;"),
+            Command::CreateDatabase(CreateDatabaseStmt {
+                name: "analytics".to_string(),
+                query: "CREATE_DATABASE analytics;".to_string(),
+            })
+        );
+
+        let stmt = UseStmt {
+            name: "analytics".to_string(),
+        };
+        assert_eq!(stmt.to_string(), "USE analytics;");
+        assert_eq!(parse_helper("USE analytics;"), Command::Use(stmt));
+
+        parse_helper_fails("CREATE_DATABASE;");
+        parse_helper_fails("USE;");
+        parse_helper_fails("USE analytics main;");
+    }
+
+    #[test]
+    fn test_parse_update() {
+        let query = "UPDATE users SET name = \"Bob\", age = 22 WHERE id = 1;";
+        let expected = Command::Update(UpdateStmt {
+            table_name: "users".to_string(),
+            assignments: vec![
+                Assignment {
+                    field_name: "name".to_string(),
+                    value: DataValue::String("Bob".to_string()),
+                },
+                Assignment {
+                    field_name: "age".to_string(),
+                    value: DataValue::Int(22),
+                },
+            ],
+            where_clause: Some(Condition::Comparison(Comparison {
+                field: "id".to_string(),
+                op: Operator::Eq,
+                value: DataValue::Int(1),
+            })),
+            returning: None,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "UPDATE users SET active = false;";
+        match parse_helper(query) {
+            Command::Update(stmt) => assert!(stmt.where_clause.is_none()),
+            command => panic!("Expected an UPDATE, got {command:?}"),
+        }
+
+        parse_helper_fails("UPDATE users SET WHERE id = 1;");
+        parse_helper_fails("UPDATE users name = 1;");
+    }
+
     #[test]
     fn test_parse_dump_to() {
         let query = "DUMP_TO \"data/backup.bin\";";
         let expected = Command::DumpTo(DumpToStmt {
             path: "data/backup.bin".to_string(),
+            passphrase: None,
         });
         assert_eq!(parse_helper(query), expected);
+
+        let query = "DUMP_TO \"data/backup.bin\" ENCRYPTED WITH \"s3cret\";";
+        let expected = Command::DumpTo(DumpToStmt {
+            path: "data/backup.bin".to_string(),
+            passphrase: Some("s3cret".to_string()),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("DUMP_TO \"data/backup.bin\" WITH \"s3cret\";");
+        parse_helper_fails("DUMP_TO \"data/backup.bin\" ENCRYPTED;");
     }
 
     #[test]
     fn test_parse_load_from() {
         let query = "LOAD_FROM \"data/backup.bin\";";
         let expected = Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::File("data/backup.bin".to_string()),
+            passphrase: None,
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "LOAD_FROM \"data/backup.bin\" WITH \"s3cret\";";
+        let expected = Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::File("data/backup.bin".to_string()),
+            passphrase: Some("s3cret".to_string()),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "LOAD_FROM 0x0102;";
+        let expected = Command::LoadFrom(LoadFromStmt {
+            source: DumpSource::Bytes(vec![0x01, 0x02]),
+            passphrase: None,
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("LOAD_FROM \"data/backup.bin\" ENCRYPTED WITH \"s3cret\";");
+        parse_helper_fails("LOAD_FROM 0x0102 WITH \"s3cret\";");
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        let query = "VERIFY \"data/backup.bin\";";
+        let expected = Command::Verify(VerifyStmt {
             path: "data/backup.bin".to_string(),
+            passphrase: None,
         });
         assert_eq!(parse_helper(query), expected);
+
+        let query = "VERIFY \"data/backup.bin\" WITH \"s3cret\";";
+        let expected = Command::Verify(VerifyStmt {
+            path: "data/backup.bin".to_string(),
+            passphrase: Some("s3cret".to_string()),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("VERIFY;");
+    }
+
+    #[test]
+    fn test_parse_dump_and_load_table() {
+        let query = "DUMP_TABLE users TO \"data/users.bin\";";
+        let expected = Command::DumpTable(DumpTableStmt {
+            table_name: "users".to_string(),
+            path: "data/users.bin".to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "LOAD_TABLE FROM \"data/users.bin\";";
+        let expected = Command::LoadTable(LoadTableStmt {
+            source: DumpSource::File("data/users.bin".to_string()),
+            on_conflict: None,
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "LOAD_TABLE FROM \"data/users.bin\" ON CONFLICT UPDATE;";
+        let expected = Command::LoadTable(LoadTableStmt {
+            source: DumpSource::File("data/users.bin".to_string()),
+            on_conflict: Some(ConflictAction::Update),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("DUMP_TABLE \"data/users.bin\";");
+        let query = "LOAD_TABLE FROM 0x00ff ON CONFLICT REPLACE;";
+        let expected = Command::LoadTable(LoadTableStmt {
+            source: DumpSource::Bytes(vec![0x00, 0xff]),
+            on_conflict: Some(ConflictAction::Replace),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("LOAD_TABLE users FROM \"data/users.bin\";");
+        parse_helper_fails("LOAD_TABLE FROM 0xfff;");
+        parse_helper_fails("LOAD_TABLE FROM \"data/users.bin\" ON CONFLICT;");
+    }
+
+    #[test]
+    fn test_parse_load_csv() {
+        let query = "LOAD_CSV \"data/users.csv\" INTO users;";
+        let expected = Command::LoadCsv(LoadCsvStmt {
+            path: "data/users.csv".to_string(),
+            table_name: "users".to_string(),
+            query: query.to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("LOAD_CSV \"data/users.csv\";");
+        parse_helper_fails("LOAD_CSV users INTO users;");
+    }
+
+    #[test]
+    fn test_parse_json_export_and_import() {
+        let query = "EXPORT_JSON \"data/backup.json\";";
+        let expected = Command::ExportJson(ExportJsonStmt {
+            path: "data/backup.json".to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        let query = "IMPORT_JSON \"data/backup.json\";";
+        let expected = Command::ImportJson(ImportJsonStmt {
+            path: "data/backup.json".to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("EXPORT_JSON data.json;");
+    }
+
+    #[test]
+    fn test_parse_condition() {
+        let condition = QueryParser
+            .parse_condition("age >= 18 AND NOT name = \"Bob\"")
+            .unwrap();
+        assert_eq!(
+            QueryParser.parse_condition(&condition.to_string()).unwrap(),
+            condition
+        );
+        assert!(QueryParser.parse_condition("age >= 18;").is_err());
+        assert!(QueryParser.parse_condition("").is_err());
     }
 
     #[test]
@@ -525,6 +2014,142 @@ mod tests {
         assert_eq!(parse_helper(query), expected);
     }
 
+    #[test]
+    fn test_parse_migrate() {
+        let query = "MIGRATE \"migrations/\";";
+        let expected = Command::Migrate(MigrateStmt {
+            path: "migrations/".to_string(),
+        });
+        assert_eq!(parse_helper(query), expected);
+
+        parse_helper_fails("MIGRATE;");
+        parse_helper_fails("MIGRATE migrations;");
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let query = "-- fills the table\nGENERATE /* a few */ 5 INTO users; -- done";
+        let Command::Generate(stmt) = parse_helper(query) else {
+            panic!("Expected Generate");
+        };
+        assert_eq!(stmt.count, 5);
+        assert_eq!(stmt.query, "GENERATE /* a few */ 5 INTO users;");
+
+        let query = "SELECT name -- the names\nFROM users /* ; */ WHERE name = \"--\";";
+        assert!(matches!(parse_helper(query), Command::Select(_)));
+
+        parse_helper_fails("SELECT name FROM users; -- the names\n;");
+        parse_helper_fails("SELECT name FROM users /* unterminated;");
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = "-- the schema\n\
+            CREATE users KEY id FIELDS id: INT, name: STRING;\n\
+            INSERT id = 1, name = \"a;b\" INTO users; /* ; */ GENERATE 5 INTO users;\n";
+        let commands = QueryParser.parse_script(script).unwrap();
+        assert_eq!(commands.len(), 3);
+        assert!(matches!(commands[0], Command::Create(_)));
+        let Command::Insert(stmt) = &commands[1] else {
+            panic!("Expected Insert");
+        };
+        assert_eq!(stmt.query, "INSERT id = 1, name = \"a;b\" INTO users;");
+        assert!(matches!(&commands[2], Command::Generate(stmt) if stmt.count == 5));
+
+        assert!(
+            QueryParser
+                .parse_script(" -- nothing\n")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(QueryParser.parse_script("SELECT name FROM users").is_err());
+
+        let Err(DatabaseError::Parse(error)) =
+            QueryParser.parse_script("SELECT name FROM users;\nSELECT FROM users;")
+        else {
+            panic!("Expected a parsing error");
+        };
+        let ParsingError::Syntax {
+            line, column, span, ..
+        } = *error
+        else {
+            panic!("Expected a syntax error");
+        };
+        assert_eq!((line, column), (2, 8));
+        assert_eq!(span.offset(), 31);
+    }
+
+    fn syntax_error(query: &str) -> (String, usize, usize, String) {
+        match QueryParser.parse_query(query) {
+            Err(DatabaseError::Parse(error)) => match *error {
+                ParsingError::Syntax {
+                    found,
+                    span,
+                    expected,
+                    ..
+                } => (found, span.offset(), span.len(), expected.unwrap()),
+                error => panic!("Expected a syntax error, got {error:?}"),
+            },
+            result => panic!("Expected a parsing error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let (found, offset, len, expected) = syntax_error("DELETE 1 FORM users;");
+        assert_eq!((found.as_str(), offset, len), ("`FORM`", 9, 4));
+        assert_eq!(expected, "Expected `FROM`");
+
+        let (found, offset, len, expected) = syntax_error("SELECT FROM users;");
+        assert_eq!((found.as_str(), offset, len), ("keyword `FROM`", 7, 4));
+        assert_eq!(expected, "Expected an identifier");
+
+        let (found, offset, len, expected) = syntax_error("CREATE users;");
+        assert_eq!((found.as_str(), offset, len), ("`;`", 12, 1));
+        assert_eq!(expected, "Expected one of: `KEY`, an identifier");
+
+        let (found, offset, _, expected) = syntax_error("SELECT name FROM users");
+        assert_eq!((found.as_str(), offset), ("end of input", 22));
+        assert!(expected.contains("`;`") && expected.contains("`WHERE`"));
+
+        let (found, _, _, expected) = syntax_error("SELCT name FROM users;");
+        assert_eq!(found, "`SELCT`");
+        assert_eq!(expected, "Did you mean 'SELECT'?");
+        let (_, _, _, expected) = syntax_error("SELECT name FROM users ORDR_BY age;");
+        assert_eq!(expected, "Did you mean 'ORDER_BY'?");
+
+        let (found, _, _, expected) = syntax_error("SELECT name FROM users;;");
+        assert_eq!(found, "`;`");
+        assert_eq!(expected, "Expected the end of the query");
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let script = "-- the schema; and its data\n\
+            CREATE users KEY id FIELDS id: INT, name: STRING;\n\
+            /* two users; */ INSERT id = 1, name = \"a;b\" INTO users; INSERT id = 2 -- ;\n\
+            INTO users;\n\
+            -- the end";
+        assert_eq!(
+            QueryParser.split_statements(script),
+            vec![
+                "CREATE users KEY id FIELDS id: INT, name: STRING;",
+                "INSERT id = 1, name = \"a;b\" INTO users;",
+                "INSERT id = 2 -- ;\nINTO users;",
+            ]
+        );
+
+        assert_eq!(
+            QueryParser.split_statements("SELECT name; SELECT id /* ; */"),
+            vec!["SELECT name;", "SELECT id /* ; */"]
+        );
+        assert!(
+            QueryParser
+                .split_statements(" -- nothing\n/* here */ ")
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_invalid_query_fails() {
         parse_helper_fails("SELECT FROM users;");
@@ -0,0 +1,171 @@
+//! The wire format spoken by the server started with `serve`.
+//!
+//! Every message is a frame: its length as a little-endian `u32`, followed by
+//! that many bytes. A client sends each query as a frame holding its UTF-8 text,
+//! and the server answers every query, in order, with a frame holding the
+//! `bincode` encoding of a [`Response`].
+
+use std::io::{self, Read, Write};
+
+use bincode::config;
+
+use crate::{DatabaseResult, execution::ExecutionResult};
+
+/// The largest query accepted by the server, in bytes.
+pub const MAX_QUERY_LEN: u32 = 16 * 1024 * 1024;
+/// The largest response sent by the server and accepted by a client, in bytes.
+/// A result encoding to more than that is answered with an error instead.
+pub const MAX_RESPONSE_LEN: u32 = 64 * 1024 * 1024;
+
+/// The answer to a query: its result, or the message of the error it raised.
+pub type Response = Result<ExecutionResult, String>;
+
+/// Writes `payload` as a single frame and flushes `writer`.
+///
+/// # Errors
+/// Returns an error of kind `io::ErrorKind::InvalidInput` if the payload is too
+/// long for a frame, or the error of the writer.
+pub fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame is too long"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Reads a single frame of at most `max_len` bytes. The payload is buffered as
+/// it arrives, so a peer announcing a long frame it never sends does not make the
+/// reader allocate for all of it upfront.
+///
+/// # Errors
+/// Returns an error of kind `io::ErrorKind::InvalidData` if the frame is longer
+/// than `max_len`, of kind `io::ErrorKind::UnexpectedEof` if the stream ends
+/// inside it, or the error of the reader.
+///
+/// # Returns
+/// The payload of the frame, or `None` if the stream ends before it.
+pub fn read_frame(reader: &mut impl Read, max_len: u32) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; size_of::<u32>()];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+
+    let len = u32::from_le_bytes(len);
+    if len > max_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the limit of {max_len} bytes"),
+        ));
+    }
+
+    let mut payload = Vec::new();
+    reader.take(u64::from(len)).read_to_end(&mut payload)?;
+    if payload.len() < len as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("the stream ended inside a frame of {len} bytes"),
+        ));
+    }
+    Ok(Some(payload))
+}
+
+/// Encodes `response` as the payload of a frame. A response longer than
+/// [`MAX_RESPONSE_LEN`] is replaced by an error saying so, which clients accept.
+///
+/// # Errors
+/// Returns `DatabaseError::Encode` if `bincode` fails to serialize the response.
+pub fn encode_response(response: &Response) -> DatabaseResult<Vec<u8>> {
+    encode_response_within(response, MAX_RESPONSE_LEN)
+}
+
+fn encode_response_within(response: &Response, max_len: u32) -> DatabaseResult<Vec<u8>> {
+    let payload = bincode::encode_to_vec(response, config::standard())?;
+    if payload.len() <= max_len as usize {
+        return Ok(payload);
+    }
+
+    let too_long: Response = Err(format!(
+        "the response of {} bytes exceeds the limit of {max_len} bytes",
+        payload.len()
+    ));
+    Ok(bincode::encode_to_vec(&too_long, config::standard())?)
+}
+
+/// Decodes the payload of a frame written by [`encode_response`].
+///
+/// # Errors
+/// Returns `DatabaseError::Decode` if the payload is not a valid response.
+pub fn decode_response(payload: &[u8]) -> DatabaseResult<Response> {
+    let (response, _) = bincode::decode_from_slice(payload, config::standard())?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, b"SELECT id FROM users;").unwrap();
+        write_frame(&mut bytes, b"").unwrap();
+
+        let mut reader = Cursor::new(&bytes);
+        assert_eq!(
+            read_frame(&mut reader, MAX_QUERY_LEN).unwrap().unwrap(),
+            b"SELECT id FROM users;"
+        );
+        assert_eq!(
+            read_frame(&mut reader, MAX_QUERY_LEN).unwrap().unwrap(),
+            b""
+        );
+        assert!(read_frame(&mut reader, MAX_QUERY_LEN).unwrap().is_none());
+
+        let error = read_frame(&mut Cursor::new(&bytes), 4).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = read_frame(&mut Cursor::new(&bytes[..10]), MAX_QUERY_LEN).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        // a frame announcing the largest allowed length but ending right away
+        let mut truncated = MAX_QUERY_LEN.to_le_bytes().to_vec();
+        truncated.extend_from_slice(b"abc");
+        let error = read_frame(&mut Cursor::new(&truncated), MAX_QUERY_LEN).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_long_response_is_replaced_by_error() {
+        let response: Response = Ok(ExecutionResult::Messages(vec!["x".repeat(100)]));
+        let payload = encode_response_within(&response, 64).unwrap();
+        assert!(payload.len() <= 64);
+        let Err(message) = decode_response(&payload).unwrap() else {
+            panic!("expected an error response");
+        };
+        assert!(
+            message.contains("exceeds the limit of 64 bytes"),
+            "{message}"
+        );
+
+        let payload = encode_response_within(&response, 1024).unwrap();
+        assert!(decode_response(&payload).unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_responses() {
+        for response in [
+            Ok(ExecutionResult::RowsAffected(3)),
+            Ok(ExecutionResult::Messages(vec!["done".to_string()])),
+            Err("Table 'users' not found".to_string()),
+        ] {
+            let payload = encode_response(&response).unwrap();
+            assert_eq!(
+                format!("{:?}", decode_response(&payload).unwrap()),
+                format!("{response:?}")
+            );
+        }
+    }
+}
@@ -1,19 +1,32 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use miette::{GraphicalReportHandler, GraphicalTheme};
 
 use crate::{
     QueryParser,
-    core::{Database, DatabaseKey},
-    execution::{ExecutionResult, build_execute_command},
+    core::{Catalog, Database, DatabaseError, DatabaseKey, WriteAheadLog},
+    execution::{ExecutionResult, Session},
     tui::ui::ActiveTab,
 };
 
+/// Renders `error` as a `miette` report without colors, which the terminal
+/// would not display inside the TUI, showing the query with the location of a
+/// parsing error underlined.
+fn render_error(error: &DatabaseError) -> String {
+    let mut rendered = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .render_report(&mut rendered, error)
+        .map_or_else(|_| error.to_string(), |_| rendered)
+}
+
 /// Represents the main state structure for the TUI application.
 pub struct App<K: DatabaseKey> {
-    /// A persistent instance of the query parser.
-    parser: QueryParser,
-
-    /// The active database instance, generic over the key type `K`.
-    pub database: Database<K>,
+    /// The databases, generic over the key type `K`.
+    pub catalog: Catalog<K>,
+    /// The write-ahead log recording every change to `catalog`, if any.
+    pub wal: Option<WriteAheadLog>,
+    /// The executor of the submitted queries, tracking the current database and
+    /// the transaction in progress.
+    pub session: Session<K>,
     /// The string buffer for the multi-line query input field.
     pub input: String,
     /// The current byte index of the cursor within the `input` string.
@@ -29,9 +42,17 @@ pub struct App<K: DatabaseKey> {
 impl<K: DatabaseKey> Default for App<K> {
     /// Provides a default implementation to create an initial `App` state.
     fn default() -> Self {
+        Self::new(Catalog::<K>::default())
+    }
+}
+
+impl<K: DatabaseKey> App<K> {
+    /// Creates the initial `App` state around an existing `catalog`.
+    pub fn new(catalog: Catalog<K>) -> Self {
         Self {
-            database: Database::<K>::default(),
-            parser: QueryParser,
+            catalog,
+            wal: None,
+            session: Session::default(),
             input: String::new(),
             cursor_position: 0,
             active_tab: ActiveTab::default(),
@@ -39,9 +60,18 @@ impl<K: DatabaseKey> Default for App<K> {
             should_quit: false,
         }
     }
-}
 
-impl<K: DatabaseKey> App<K> {
+    /// Returns the current database of the session.
+    pub fn database(&self) -> &Database<K> {
+        self.session.database(&self.catalog)
+    }
+
+    /// Records every change to the databases in `wal`.
+    pub fn with_wal(mut self, wal: WriteAheadLog) -> Self {
+        self.wal = Some(wal);
+        self
+    }
+
     /// Parses, validates, and executes the query currently in the `input` buffer.
     pub fn execute_current_query(&mut self) {
         let query_to_parse = self.input.trim();
@@ -50,17 +80,11 @@ impl<K: DatabaseKey> App<K> {
         }
 
         let result = self
-            .parser
-            .parse_query(query_to_parse)
-            .map_err(|e| format!("Parsing error:\n{}", miette::Report::new(e)))
-            .and_then(|ast| {
-                build_execute_command(&mut self.database, ast)
-                    .map_err(|e| format!("Validation error\n{}", miette::Report::new(e)))
-            })
-            .and_then(|mut executable| {
-                executable
-                    .execute()
-                    .map_err(|e| format!("Execution error:\n{}", miette::Report::new(e)))
+            .session
+            .execute_query(&mut self.catalog, query_to_parse, self.wal.as_mut())
+            .map_err(|e| match e {
+                DatabaseError::Parse(_) => format!("Parsing error:\n{}", render_error(&e)),
+                _ => format!("Execution error:\n{}", render_error(&e)),
             });
 
         self.last_result = Some(result);
@@ -78,7 +102,8 @@ impl<K: DatabaseKey> App<K> {
             KeyCode::Left if self.active_tab.is_queries() => self.on_left(),
             KeyCode::Right if self.active_tab.is_queries() => self.on_right(),
             KeyCode::Enter if self.active_tab.is_queries() => {
-                if self.input.trim().ends_with(';') {
+                let statements = QueryParser.split_statements(&self.input);
+                if statements.last().is_some_and(|query| query.ends_with(';')) {
                     self.execute_current_query();
                 } else {
                     self.on_key('\n');
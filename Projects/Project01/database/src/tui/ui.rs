@@ -1,12 +1,14 @@
 mod active_tab;
 mod database_state_tab;
 mod queries_tab;
+mod statistics_tab;
 mod widgets;
 
 pub use active_tab::ActiveTab;
 pub use database_state_tab::create_database_state_tab;
 pub use queries_tab::create_queries_tab;
-pub use widgets::{create_records_table, create_schema_table};
+pub use statistics_tab::create_statistics_tab;
+pub use widgets::{create_records_table, create_schema_table, create_statistics_table};
 
 use ratatui::{
     Frame,
@@ -24,9 +26,13 @@ pub fn ui<K: DatabaseKey>(f: &mut Frame, app: &mut App<K>) {
         .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(f.area());
 
-    let titles = vec![" [1] Queries ", " [2] Database State "];
+    let titles = vec![" [1] Queries ", " [2] Database State ", " [3] Statistics "];
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title(" Database "))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Database: {} ", app.session.database_name())),
+        )
         .select(usize::from(app.active_tab.clone()))
         .highlight_style(
             Style::default()
@@ -38,5 +44,6 @@ pub fn ui<K: DatabaseKey>(f: &mut Frame, app: &mut App<K>) {
     match app.active_tab {
         ActiveTab::Queries => create_queries_tab(f, app, chunks[1]),
         ActiveTab::DatabaseState => create_database_state_tab(f, app, chunks[1]),
+        ActiveTab::Statistics => create_statistics_tab(f, app, chunks[1]),
     };
 }
@@ -6,16 +6,19 @@ pub enum ActiveTab {
     Queries,
     /// The "Database State" tab, which displays the current table schemas and data.
     DatabaseState,
+    /// The "Statistics" tab, which displays the statistics computed by `ANALYZE`.
+    Statistics,
 }
 
 impl ActiveTab {
     /// Returns the next tab in the cycle.
     ///
-    /// This allows for simple toggling, e.g., `Queries` -> `DatabaseState` -> `Queries`.
+    /// This allows for simple toggling, e.g., `Queries` -> `DatabaseState` -> `Statistics` -> `Queries`.
     pub fn next(&self) -> Self {
         match self {
             Self::Queries => Self::DatabaseState,
-            Self::DatabaseState => Self::Queries,
+            Self::DatabaseState => Self::Statistics,
+            Self::Statistics => Self::Queries,
         }
     }
 
@@ -37,6 +40,7 @@ impl From<ActiveTab> for usize {
         match value {
             ActiveTab::Queries => 0,
             ActiveTab::DatabaseState => 1,
+            ActiveTab::Statistics => 2,
         }
     }
 }
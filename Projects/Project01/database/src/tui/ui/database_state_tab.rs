@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -10,7 +12,12 @@ use super::{create_records_table, create_schema_table};
 
 /// Renders the "Database State" tab view.
 pub fn create_database_state_tab<K: DatabaseKey>(f: &mut Frame, app: &App<K>, area: Rect) {
-    let table_names: Vec<&String> = app.database.tables.keys().collect();
+    let table_names: Vec<&str> = app
+        .database()
+        .schemas()
+        .into_iter()
+        .map(|schema| schema.name)
+        .collect();
     if table_names.is_empty() {
         f.render_widget(
             Paragraph::new("Database is empty.").block(Block::default().borders(Borders::ALL)),
@@ -29,7 +36,7 @@ pub fn create_database_state_tab<K: DatabaseKey>(f: &mut Frame, app: &App<K>, ar
         .split(area);
 
     for (i, table_name) in table_names.into_iter().enumerate() {
-        let table = app.database.tables.get(table_name).unwrap();
+        let table = app.database().tables.get(table_name).unwrap();
 
         let table_layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -38,11 +45,17 @@ pub fn create_database_state_tab<K: DatabaseKey>(f: &mut Frame, app: &App<K>, ar
 
         let schema_title = format!(" Schema: {} (Key: {}) ", table.name, table.key_field);
         let schema_block = Block::default().borders(Borders::ALL).title(schema_title);
-        create_schema_table(f, schema_block, &table.schema, table_layout[0]);
+        create_schema_table(
+            f,
+            schema_block,
+            &table.schema,
+            &table.enums,
+            table_layout[0],
+        );
 
         let data_title = " Rows ".to_string();
         let data_block = Block::default().borders(Borders::ALL).title(data_title);
-        let records: Vec<_> = table.rows.values().cloned().collect();
+        let records: Vec<_> = table.rows.values().map(Cow::into_owned).collect();
         create_records_table(f, data_block, &records, table_layout[1]);
     }
 }
@@ -0,0 +1,60 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{core::DatabaseKey, tui::App};
+
+use super::create_statistics_table;
+
+/// Renders the "Statistics" tab view.
+///
+/// Every table is listed with the statistics of its last `ANALYZE`,
+/// or a hint to run it if it has never been analyzed.
+pub fn create_statistics_tab<K: DatabaseKey>(f: &mut Frame, app: &App<K>, area: Rect) {
+    let table_names: Vec<&str> = app
+        .database()
+        .schemas()
+        .into_iter()
+        .map(|schema| schema.name)
+        .collect();
+    if table_names.is_empty() {
+        f.render_widget(
+            Paragraph::new("Database is empty.").block(Block::default().borders(Borders::ALL)),
+            area,
+        );
+        return;
+    }
+    let constraints: Vec<Constraint> = table_names
+        .iter()
+        .map(|_| Constraint::Ratio(1, table_names.len() as u32))
+        .collect();
+    let table_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    for (i, table_name) in table_names.into_iter().enumerate() {
+        let table = app.database().tables.get(table_name).unwrap();
+
+        match &table.statistics {
+            Some(statistics) => {
+                let title = format!(
+                    " Statistics: {} ({} record(s)) ",
+                    table.name, statistics.row_count
+                );
+                let block = Block::default().borders(Borders::ALL).title(title);
+                create_statistics_table(f, block, statistics, table_chunks[i]);
+            }
+            None => {
+                let title = format!(" Statistics: {} ", table.name);
+                let text = format!("Not analyzed yet, run 'ANALYZE {};'.", table.name);
+                f.render_widget(
+                    Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title)),
+                    table_chunks[i],
+                );
+            }
+        }
+    }
+}
@@ -8,13 +8,19 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-use crate::core::{DataType, Record};
+use crate::core::{DataType, DataValue, Record, TableStatistics};
+
+/// The number of bytes of a `Bytes` value shown before it is truncated.
+const MAX_SHOWN_BYTES: usize = 16;
 
 /// Renders a `Table` widget displaying the schema of a database table.
+///
+/// `ENUM` columns are listed with their variants instead of their `STRING` type.
 pub fn create_schema_table(
     f: &mut Frame,
     block: Block,
     schema: &HashMap<String, DataType>,
+    enums: &HashMap<String, Vec<String>>,
     area: Rect,
 ) {
     let headers = ["Column", "Type"];
@@ -30,9 +36,19 @@ pub fn create_schema_table(
     let rows: Vec<Row> = sorted_schema
         .iter()
         .map(|(name, dtype)| {
+            let type_name = match enums.get(*name) {
+                Some(variants) => {
+                    let variants = variants
+                        .iter()
+                        .map(|variant| format!("{variant:?}"))
+                        .collect::<Vec<_>>();
+                    format!("Enum({})", variants.join(", "))
+                }
+                None => format!("{dtype:?}"),
+            };
             let cells = vec![
                 Cell::from(name.as_str()),
-                Cell::from(format!("{dtype:?}")).style(Style::default().fg(Color::Yellow)),
+                Cell::from(type_name).style(Style::default().fg(Color::Yellow)),
             ];
             Row::new(cells)
         })
@@ -47,6 +63,55 @@ pub fn create_schema_table(
     f.render_widget(t, area);
 }
 
+/// Renders a `Table` widget displaying the per-column statistics of a table.
+pub fn create_statistics_table(
+    f: &mut Frame,
+    block: Block,
+    statistics: &TableStatistics,
+    area: Rect,
+) {
+    let headers = ["Column", "Distinct", "Min", "Max"];
+    let header_cells: Vec<Cell> = headers
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)))
+        .collect();
+    let header_row = Row::new(header_cells).bottom_margin(1);
+
+    let mut sorted_columns: Vec<_> = statistics.columns.iter().collect();
+    sorted_columns.sort_by(|a, b| a.0.cmp(b.0));
+
+    let bound_cell = |value: &Option<DataValue>| match value {
+        Some(value) => Cell::from(format_value(value)),
+        None => Cell::from("NULL").style(Style::default().fg(Color::DarkGray)),
+    };
+    let rows: Vec<Row> = sorted_columns
+        .iter()
+        .map(|(name, column)| {
+            let cells = vec![
+                Cell::from(name.as_str()),
+                Cell::from(column.distinct_count.to_string())
+                    .style(Style::default().fg(Color::Yellow)),
+                bound_cell(&column.min),
+                bound_cell(&column.max),
+            ];
+            Row::new(cells)
+        })
+        .collect();
+
+    let widths_constraints = [
+        Constraint::Percentage(25),
+        Constraint::Percentage(15),
+        Constraint::Percentage(30),
+        Constraint::Percentage(30),
+    ];
+
+    let t = Table::new(rows, widths_constraints)
+        .header(header_row)
+        .block(block);
+
+    f.render_widget(t, area);
+}
+
 /// Renders a `Table` widget displaying the data (rows) from a `SELECT` query.
 pub fn create_records_table(f: &mut Frame, block: Block, records: &[Record], area: Rect) {
     if records.is_empty() {
@@ -70,7 +135,7 @@ pub fn create_records_table(f: &mut Frame, block: Block, records: &[Record], are
             let cells: Vec<Cell> = headers
                 .iter()
                 .map(|header| match record.fields.get(header) {
-                    Some(val) => Cell::from(format!("{val}")),
+                    Some(val) => Cell::from(format_value(val)),
                     None => Cell::from("NULL").style(Style::default().fg(Color::DarkGray)),
                 })
                 .collect();
@@ -109,7 +174,7 @@ fn calculate_column_widths(headers: &[String], records: &[Record]) -> Vec<Constr
             let value_width = record
                 .fields
                 .get(header)
-                .map_or(4, |val| val.to_string().width());
+                .map_or(4, |val| format_value(val).width());
 
             if value_width > widths[i] {
                 widths[i] = value_width;
@@ -122,3 +187,15 @@ fn calculate_column_widths(headers: &[String], records: &[Record]) -> Vec<Constr
         .map(|w| Constraint::Length(w as u16 + 2))
         .collect()
 }
+
+/// Formats a value for a table cell, truncating long `Bytes` values to their
+/// first bytes followed by the total length.
+fn format_value(value: &DataValue) -> String {
+    match value {
+        DataValue::Bytes(bytes) if bytes.len() > MAX_SHOWN_BYTES => {
+            let shown = DataValue::Bytes(bytes[..MAX_SHOWN_BYTES].to_vec());
+            format!("{shown}… ({} bytes)", bytes.len())
+        }
+        value => value.to_string(),
+    }
+}
@@ -3,14 +3,38 @@
 #include <string.h>
 
 typedef struct RedBlackTree RedBlackTree;
+typedef struct ByteTree ByteTree;
 
 extern RedBlackTree *tree_create();
 extern int tree_insert(RedBlackTree *tree, uint64_t key, const char *val);
+extern int tree_update(RedBlackTree *tree, uint64_t key, const char *val);
 extern int tree_contains(RedBlackTree *tree, uint64_t key);
 extern int tree_get(RedBlackTree *tree, uint64_t key, char *buf, size_t buflen);
 extern int tree_remove(RedBlackTree *tree, uint64_t key);
 extern void tree_free(RedBlackTree *tree);
 extern void tree_print_structure(RedBlackTree *tree);
+extern int tree_to_string(RedBlackTree *tree, char *buf, size_t buflen);
+extern const char *tree_last_error_message(RedBlackTree *tree);
+extern size_t tree_len(RedBlackTree *tree);
+extern int tree_kth(RedBlackTree *tree, size_t index, uint64_t *key, char *buf, size_t buflen);
+extern size_t tree_rank(RedBlackTree *tree, uint64_t key);
+
+extern ByteTree *tree_create_bytes();
+extern int tree_insert_bytes(ByteTree *tree, const uint8_t *key, size_t key_len, const char *val);
+extern int tree_contains_bytes(ByteTree *tree, const uint8_t *key, size_t key_len);
+extern int tree_get_bytes(ByteTree *tree, const uint8_t *key, size_t key_len, char *buf, size_t buflen);
+extern int tree_remove_bytes(ByteTree *tree, const uint8_t *key, size_t key_len);
+extern const char *tree_last_error_message_bytes(ByteTree *tree);
+extern void tree_free_bytes(ByteTree *tree);
+
+enum TreeError
+{
+    TREE_ERROR_NULL_POINTER = -1,
+    TREE_ERROR_ALLOCATION_FAILED = -2,
+    TREE_ERROR_NOT_FOUND = -3,
+    TREE_ERROR_INVALID_UTF8 = -4,
+    TREE_ERROR_BUFFER_TOO_SMALL = -5,
+};
 
 /// Exemplary usage of the Red-Black Tree in C language.
 /// Output can be verified using: https://www.cs.usfca.edu/~galles/visualization/RedBlack.html
@@ -67,7 +91,7 @@ int main()
     uint64_t check_key = 7;
     char buffer[128];
 
-    if (tree_get(tree, check_key, buffer, sizeof(buffer)) != -1)
+    if (tree_get(tree, check_key, buffer, sizeof(buffer)) == 0)
     {
         printf("\tTree correctly contains key %llu: '%s'\n", check_key, buffer);
     }
@@ -76,6 +100,12 @@ int main()
         printf("\tError: Key %llu missing!\n", check_key);
     }
 
+    uint64_t missing_key = 42;
+    if (tree_get(tree, missing_key, buffer, sizeof(buffer)) == TREE_ERROR_NOT_FOUND)
+    {
+        printf("\tLooking up key %llu failed: %s\n", missing_key, tree_last_error_message(tree));
+    }
+
     printf("\n[5] Removing node (key 4):\n");
     tree_remove(tree, 4);
     tree_print_structure(tree);
@@ -91,7 +121,47 @@ int main()
     tree_print_structure(tree);
     printf("\n");
 
+    printf("\n[8] Rendering structure into a buffer:\n");
+    char structure[1024];
+    if (tree_to_string(tree, structure, sizeof(structure)) == 0)
+    {
+        printf("%s", structure);
+    }
+    printf("\n");
+
+    printf("\n[9] Order statistics:\n");
+    size_t count = tree_len(tree);
+    uint64_t median_key = 0;
+    if (tree_kth(tree, count / 2, &median_key, buffer, sizeof(buffer)) == 0)
+    {
+        printf("\tMedian of %zu keys: [%lu] %s\n", count, (unsigned long)median_key, buffer);
+    }
+    printf("\tKeys smaller than 6: %zu\n", tree_rank(tree, 6));
+
     tree_free(tree);
 
+    printf("\n[10] Byte-string keys:\n");
+    ByteTree *byte_tree = tree_create_bytes();
+    if (!byte_tree)
+    {
+        printf("Error: Allocation failed\n");
+        return 1;
+    }
+
+    const uint8_t composite_key[] = {'u', 's', 'e', 'r', 0, 42};
+    tree_insert_bytes(byte_tree, composite_key, sizeof(composite_key), "Composite");
+    tree_insert_bytes(byte_tree, (const uint8_t *)"apple", 5, "Fruit");
+
+    if (tree_get_bytes(byte_tree, composite_key, sizeof(composite_key), buffer, sizeof(buffer)) == 0)
+    {
+        printf("\tComposite key maps to '%s'\n", buffer);
+    }
+    if (tree_get_bytes(byte_tree, (const uint8_t *)"user", 4, buffer, sizeof(buffer)) == TREE_ERROR_NOT_FOUND)
+    {
+        printf("\tPrefix 'user' is a different key: %s\n", tree_last_error_message_bytes(byte_tree));
+    }
+
+    tree_free_bytes(byte_tree);
+
     return 0;
 }
\ No newline at end of file
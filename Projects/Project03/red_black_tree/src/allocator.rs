@@ -0,0 +1,142 @@
+//! Raw memory allocation used by all containers of the crate.
+//!
+//! By default memory comes from the Rust global allocator ([`std::alloc`]), so custom
+//! `#[global_allocator]`s and tools like Miri or AddressSanitizer see every allocation.
+//! With the `libc-compat` feature `malloc`/`free` are used instead. Either default can be
+//! replaced once, before the first allocation, with [`set_allocator_hooks`].
+
+use core::ffi::c_void;
+use std::{alloc::Layout, ptr, sync::OnceLock};
+
+use crate::TreeError;
+
+pub type AllocateFn = unsafe extern "C" fn(size: usize, align: usize) -> *mut c_void;
+pub type DeallocateFn = unsafe extern "C" fn(ptr: *mut c_void, size: usize, align: usize);
+
+/// Functions used to obtain and release memory.
+///
+/// `allocate` returns null on failure. `deallocate` receives the same `size`
+/// and `align` that were used to allocate the block.
+#[derive(Debug, Clone, Copy)]
+pub struct AllocatorHooks {
+    pub allocate: AllocateFn,
+    pub deallocate: DeallocateFn,
+}
+
+impl Default for AllocatorHooks {
+    fn default() -> Self {
+        Self {
+            allocate: default_allocate,
+            deallocate: default_deallocate,
+        }
+    }
+}
+
+static HOOKS: OnceLock<AllocatorHooks> = OnceLock::new();
+
+/// Installs custom allocation hooks.
+///
+/// Fails with [`TreeError::AllocatorInUse`] once anything has been allocated
+/// (or hooks were already installed), since memory must be released by the
+/// allocator that provided it.
+pub fn set_allocator_hooks(hooks: AllocatorHooks) -> Result<(), TreeError> {
+    HOOKS.set(hooks).map_err(|_| TreeError::AllocatorInUse)
+}
+
+fn hooks() -> &'static AllocatorHooks {
+    HOOKS.get_or_init(AllocatorHooks::default)
+}
+
+/// Allocates uninitialized memory for `layout`, returning null on failure.
+pub(crate) fn allocate(layout: Layout) -> *mut u8 {
+    unsafe { (hooks().allocate)(layout.size(), layout.align()) as *mut u8 }
+}
+
+/// Allocates uninitialized memory for a single `T`, returning null on failure.
+pub(crate) fn allocate_value<T>() -> *mut T {
+    allocate(Layout::new::<T>()) as *mut T
+}
+
+/// Releases memory obtained from [`allocate`].
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`allocate`] with the same `layout`
+/// and must not be used afterwards.
+pub(crate) unsafe fn deallocate(ptr: *mut u8, layout: Layout) {
+    unsafe { (hooks().deallocate)(ptr as *mut c_void, layout.size(), layout.align()) }
+}
+
+/// Releases memory obtained from [`allocate_value`].
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`allocate_value`] for the same `T`
+/// and must not be used afterwards. The value is not dropped.
+pub(crate) unsafe fn deallocate_value<T>(ptr: *mut T) {
+    unsafe { deallocate(ptr as *mut u8, Layout::new::<T>()) }
+}
+
+#[cfg(not(feature = "libc-compat"))]
+unsafe extern "C" fn default_allocate(size: usize, align: usize) -> *mut c_void {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) if layout.size() > 0 => unsafe { std::alloc::alloc(layout) as *mut c_void },
+        _ => ptr::null_mut(),
+    }
+}
+
+#[cfg(not(feature = "libc-compat"))]
+unsafe extern "C" fn default_deallocate(ptr: *mut c_void, size: usize, align: usize) {
+    if let Ok(layout) = Layout::from_size_align(size, align)
+        && !ptr.is_null()
+    {
+        unsafe { std::alloc::dealloc(ptr as *mut u8, layout) }
+    }
+}
+
+/// `malloc` guarantees an alignment suitable for any fundamental type,
+/// which covers every layout allocated by this crate.
+#[cfg(feature = "libc-compat")]
+unsafe extern "C" fn default_allocate(size: usize, _align: usize) -> *mut c_void {
+    if size == 0 {
+        return ptr::null_mut();
+    }
+    unsafe { libc::malloc(size) }
+}
+
+#[cfg(feature = "libc-compat")]
+unsafe extern "C" fn default_deallocate(ptr: *mut c_void, _size: usize, _align: usize) {
+    unsafe { libc::free(ptr) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_and_deallocate() {
+        let layout = Layout::array::<u64>(4).unwrap();
+        let data = allocate(layout) as *mut u64;
+        assert!(!data.is_null());
+        assert_eq!(data as usize % layout.align(), 0);
+
+        unsafe {
+            for i in 0..4 {
+                data.add(i).write(i as u64);
+            }
+            assert_eq!(*data.add(3), 3);
+            deallocate(data as *mut u8, layout);
+        }
+    }
+
+    #[test]
+    fn test_hooks_cannot_change_after_allocation() {
+        let value = allocate_value::<u64>();
+        assert!(!value.is_null());
+
+        let result = set_allocator_hooks(AllocatorHooks::default());
+        assert!(matches!(result, Err(TreeError::AllocatorInUse)));
+
+        unsafe { deallocate_value(value) };
+    }
+}
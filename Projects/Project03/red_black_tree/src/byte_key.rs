@@ -0,0 +1,132 @@
+use core::{
+    fmt::{self, Display},
+    slice,
+};
+use std::{alloc::Layout, ptr};
+
+use crate::{TreeError, allocator};
+
+/// Length-prefixed byte string used as a tree key, ordered like `memcmp`
+/// (shorter keys come first when one is a prefix of the other).
+pub struct ByteKey {
+    data: *mut u8,
+    size: usize,
+}
+
+impl ByteKey {
+    pub fn new(value: &[u8]) -> Option<Self> {
+        let size = value.len();
+        unsafe {
+            let data = allocator::allocate(Self::layout(size));
+            if data.is_null() {
+                return None;
+            }
+
+            ptr::copy_nonoverlapping(value.as_ptr(), data, size);
+            Some(ByteKey { data, size })
+        }
+    }
+
+    /// Creates a [`ByteKey`] by copying `size` bytes starting at `value`.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be valid for reads of `size` bytes. It may be null only if `size` is `0`.
+    pub unsafe fn from_raw_parts(value: *const u8, size: usize) -> Result<Self, TreeError> {
+        unsafe {
+            let bytes = Self::bytes_from_raw_parts(value, size)?;
+            Self::new(bytes).ok_or(TreeError::AllocationFailed)
+        }
+    }
+
+    /// Borrows `size` bytes starting at `value` without copying them.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be valid for reads of `size` bytes for the returned lifetime.
+    /// It may be null only if `size` is `0`.
+    pub unsafe fn bytes_from_raw_parts<'a>(
+        value: *const u8,
+        size: usize,
+    ) -> Result<&'a [u8], TreeError> {
+        if size == 0 {
+            return Ok(&[]);
+        }
+        if value.is_null() {
+            return Err(TreeError::NullPointer);
+        }
+        unsafe { Ok(slice::from_raw_parts(value, size)) }
+    }
+
+    /// At least one byte, so that empty keys are allocated as well.
+    fn layout(size: usize) -> Layout {
+        Layout::array::<u8>(size.max(1)).expect("key size overflow")
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.data, self.size) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl Display for ByteKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_bytes().escape_ascii())
+    }
+}
+
+impl Drop for ByteKey {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.data.is_null() {
+                allocator::deallocate(self.data, Self::layout(self.size));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        let key = ByteKey::new(b"ab\x00cd").expect("Allocation failed");
+
+        assert_eq!(key.as_bytes(), b"ab\x00cd");
+        assert_eq!(key.len(), 5);
+    }
+
+    #[test]
+    fn test_empty() {
+        let key = ByteKey::new(b"").expect("Allocation failed");
+
+        assert_eq!(key.as_bytes(), b"");
+        assert!(key.is_empty());
+    }
+
+    #[test]
+    fn test_from_raw_parts() {
+        let content = b"abcdef";
+        unsafe {
+            let key = ByteKey::from_raw_parts(content.as_ptr(), 3).expect("Allocation failed");
+            assert_eq!(key.as_bytes(), b"abc");
+
+            let result = ByteKey::from_raw_parts(ptr::null(), 3);
+            assert!(matches!(result, Err(TreeError::NullPointer)));
+        }
+    }
+
+    #[test]
+    fn test_display_escapes_bytes() {
+        let key = ByteKey::new(b"key\xff").expect("Allocation failed");
+        assert_eq!(key.to_string(), "key\\xff");
+    }
+}
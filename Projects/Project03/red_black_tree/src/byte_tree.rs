@@ -0,0 +1,210 @@
+//! C API of the Red-Black Tree keyed by arbitrary byte strings.
+//!
+//! Keys are passed as a pointer and a length, compared like `memcmp`,
+//! so they may contain null bytes and do not have to be valid UTF-8.
+
+use core::ffi::c_char;
+
+use crate::{
+    ByteKey, CharContainer, RedBlackTree, TreeError, copy_to_buffer, create_tree, free_tree,
+    last_error_message, report,
+};
+
+pub type ByteTree = RedBlackTree<ByteKey>;
+
+/// Creates a new, empty Red-Black Tree keyed by byte strings.
+///
+/// # Safety
+///
+/// This function allocates memory using the crate allocator (see [`crate::tree_set_allocator`]).
+/// The caller owns the returned pointer and is responsible for eventually
+/// freeing it by calling [`tree_free_bytes`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_create_bytes() -> *mut ByteTree {
+    unsafe { create_tree() }
+}
+
+/// Inserts a key-value pair into the tree.
+///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `ByteTree`.
+/// * `key` must be valid for reads of `key_len` bytes (it may be null only if `key_len` is `0`).
+/// * `value` must be a valid, non-null pointer to a **null-terminated** C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_insert_bytes(
+    tree: *mut ByteTree,
+    key: *const u8,
+    key_len: usize,
+    value: *const c_char,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = ByteKey::from_raw_parts(key, key_len)
+            .and_then(|key| Ok((key, CharContainer::from_c_str(value)?)))
+            .and_then(|(key, data)| (*tree).insert(key, data))
+            .map(|_| 0);
+        report(tree, result)
+    }
+}
+
+/// Checks if the tree contains a specific key.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `ByteTree`.
+/// * `key` must be valid for reads of `key_len` bytes (it may be null only if `key_len` is `0`).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_contains_bytes(
+    tree: *mut ByteTree,
+    key: *const u8,
+    key_len: usize,
+) -> i32 {
+    if tree.is_null() {
+        return 0;
+    }
+    unsafe {
+        match ByteKey::bytes_from_raw_parts(key, key_len) {
+            Ok(key) if (*tree).contains(key) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Retrieves a value associated with a key into a provided buffer.
+///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `ByteTree`.
+/// * `key` must be valid for reads of `key_len` bytes (it may be null only if `key_len` is `0`).
+/// * `buffer` must be a valid pointer to a writable memory region of at least `buffer_size` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_get_bytes(
+    tree: *mut ByteTree,
+    key: *const u8,
+    key_len: usize,
+    buffer: *mut c_char,
+    buffer_size: usize,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = if buffer.is_null() {
+            Err(TreeError::NullPointer)
+        } else {
+            ByteKey::bytes_from_raw_parts(key, key_len).and_then(|key| match (*tree).get(key) {
+                Some(value) => copy_to_buffer(value, buffer, buffer_size).map(|_| 0),
+                None => Err(TreeError::NotFound),
+            })
+        };
+        report(tree, result)
+    }
+}
+
+/// Removes a key from the tree.
+///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `ByteTree`.
+/// * `key` must be valid for reads of `key_len` bytes (it may be null only if `key_len` is `0`).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_remove_bytes(
+    tree: *mut ByteTree,
+    key: *const u8,
+    key_len: usize,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = ByteKey::bytes_from_raw_parts(key, key_len)
+            .and_then(|key| (*tree).remove(key))
+            .map(|_| 0);
+        report(tree, result)
+    }
+}
+
+/// Returns a static, null-terminated description of the error reported
+/// by the most recent call on `tree`, or `"no error"` if it succeeded.
+///
+/// # Safety
+///
+/// * `tree` must be a valid pointer to an initialized `ByteTree` (or null).
+/// * The returned pointer must not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_last_error_message_bytes(tree: *const ByteTree) -> *const c_char {
+    unsafe { last_error_message(tree) }
+}
+
+/// Frees the memory associated with the tree.
+///
+/// # Safety
+///
+/// * `tree` must be a valid pointer previously returned by `tree_create_bytes` (or null).
+/// * After calling this function, the `tree` pointer becomes invalid (dangling)
+///   and must not be used again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_free_bytes(tree: *mut ByteTree) {
+    unsafe { free_tree(tree) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ffi::CStr;
+
+    #[test]
+    fn test_byte_keys_over_ffi() {
+        unsafe {
+            let tree = tree_create_bytes();
+            let mut buffer = [0 as c_char; 16];
+            let composite = b"user\x00\x2a";
+
+            assert_eq!(
+                tree_insert_bytes(tree, composite.as_ptr(), composite.len(), c"Alice".as_ptr()),
+                0
+            );
+            assert_eq!(
+                tree_insert_bytes(tree, b"user".as_ptr(), 4, c"Bob".as_ptr()),
+                0
+            );
+
+            assert_eq!(
+                tree_contains_bytes(tree, composite.as_ptr(), composite.len()),
+                1
+            );
+            assert_eq!(tree_contains_bytes(tree, b"use".as_ptr(), 3), 0);
+
+            let code = tree_get_bytes(
+                tree,
+                composite.as_ptr(),
+                composite.len(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            );
+            assert_eq!(code, 0);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str(), Ok("Alice"));
+
+            assert_eq!(tree_remove_bytes(tree, b"user".as_ptr(), 4), 0);
+            assert_eq!(tree_remove_bytes(tree, b"user".as_ptr(), 4), -3);
+            let message = CStr::from_ptr(tree_last_error_message_bytes(tree));
+            assert_eq!(message.to_str(), Ok("key not found"));
+
+            assert_eq!(
+                tree_insert_bytes(tree, core::ptr::null(), 1, c"X".as_ptr()),
+                -1
+            );
+
+            tree_free_bytes(tree);
+        }
+    }
+}
@@ -1,8 +1,10 @@
-use core::{
-    ffi::{c_char, c_void},
-    slice,
+use core::{ffi::c_char, slice};
+use std::{
+    alloc::Layout,
+    ptr::{self, null_mut},
 };
-use std::ptr::{self, null_mut};
+
+use crate::{TreeError, allocator};
 
 pub struct CharContainer {
     data: *mut u8,
@@ -19,10 +21,15 @@ impl Default for CharContainer {
 }
 
 impl CharContainer {
+    /// Room for the content and the null terminator.
+    fn layout(size: usize) -> Layout {
+        Layout::array::<u8>(size + 1).expect("string size overflow")
+    }
+
     pub fn new(value: &str) -> Option<Self> {
         let size = value.len();
         unsafe {
-            let data = libc::malloc(size + 1) as *mut u8;
+            let data = allocator::allocate(Self::layout(size));
             if data.is_null() {
                 return None;
             }
@@ -36,6 +43,10 @@ impl CharContainer {
 
     /// Creates a [`CharContainer`] from a C-style string pointer.
     ///
+    /// Fails with [`TreeError::NullPointer`] for a null `value`, with [`TreeError::InvalidUtf8`]
+    /// if the string is not valid UTF-8 and with [`TreeError::AllocationFailed`] if the copy
+    /// could not be allocated.
+    ///
     /// # Safety
     ///
     /// This function is unsafe because it dereferences a raw pointer.
@@ -43,9 +54,9 @@ impl CharContainer {
     /// 1. The `value` pointer is valid and points to readable memory.
     /// 2. The string pointed to by `value` is **null-terminated**. If the string
     ///    does not have a null terminator, this function will read out of bounds.
-    pub unsafe fn from_c_str(value: *const c_char) -> Option<Self> {
+    pub unsafe fn from_c_str(value: *const c_char) -> Result<Self, TreeError> {
         if value.is_null() {
-            return None;
+            return Err(TreeError::NullPointer);
         }
         let mut size = 0;
 
@@ -55,13 +66,18 @@ impl CharContainer {
                 size += 1;
             }
 
-            let data = libc::malloc(size + 1) as *mut u8;
+            let bytes = slice::from_raw_parts(value as *const u8, size);
+            if str::from_utf8(bytes).is_err() {
+                return Err(TreeError::InvalidUtf8);
+            }
+
+            let data = allocator::allocate(Self::layout(size));
             if data.is_null() {
-                return None;
+                return Err(TreeError::AllocationFailed);
             }
 
             ptr::copy_nonoverlapping(value as *const u8, data, size + 1);
-            Some(CharContainer { data, size })
+            Ok(CharContainer { data, size })
         }
     }
 }
@@ -75,11 +91,17 @@ impl From<&CharContainer> for &str {
     }
 }
 
+impl AsRef<str> for CharContainer {
+    fn as_ref(&self) -> &str {
+        self.into()
+    }
+}
+
 impl Drop for CharContainer {
     fn drop(&mut self) {
         unsafe {
             if !self.data.is_null() {
-                libc::free(self.data as *mut c_void);
+                allocator::deallocate(self.data, Self::layout(self.size));
             }
         }
     }
@@ -154,6 +176,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_c_str_errors() {
+        let invalid = c"ab\xff";
+
+        unsafe {
+            let result = CharContainer::from_c_str(invalid.as_ptr());
+            assert!(matches!(result, Err(TreeError::InvalidUtf8)));
+
+            let result = CharContainer::from_c_str(ptr::null());
+            assert!(matches!(result, Err(TreeError::NullPointer)));
+        }
+    }
+
     #[test]
     fn test_internal_terminator() {
         let content = "abc";
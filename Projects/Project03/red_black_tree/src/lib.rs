@@ -1,12 +1,18 @@
+mod allocator;
+mod byte_key;
+mod byte_tree;
 mod char_container;
 mod macros;
 mod tree;
 
+pub use allocator::{AllocateFn, AllocatorHooks, DeallocateFn, set_allocator_hooks};
+pub use byte_key::ByteKey;
+pub use byte_tree::ByteTree;
 pub use char_container::CharContainer;
-pub use tree::RedBlackTree;
+pub use tree::{IntoIter, Iter, RedBlackTree, TreeError, TreeKey, TreeUpdate};
 
-use core::ffi::{c_char, c_void};
-use std::{mem, ptr};
+use core::ffi::c_char;
+use std::ptr;
 
 const RESET: &str = "\x1b[0m";
 const RED: &str = "\x1b[31m";
@@ -16,13 +22,22 @@ const BOLD: &str = "\x1b[1m";
 ///
 /// # Safety
 ///
-/// This function allocates memory using [`libc::malloc`].
+/// This function allocates memory using the crate allocator (see [`tree_set_allocator`]).
 /// The caller owns the returned pointer and is responsible for eventually
 /// freeing it by calling [`tree_free`].
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tree_create() -> *mut RedBlackTree {
+    unsafe { create_tree() }
+}
+
+/// Allocates and initializes an empty tree with the crate allocator.
+///
+/// # Safety
+///
+/// The returned pointer (if non-null) must be released with [`free_tree`].
+unsafe fn create_tree<K>() -> *mut RedBlackTree<K> {
     unsafe {
-        let ptr = libc::malloc(mem::size_of::<RedBlackTree>()) as *mut RedBlackTree;
+        let ptr = allocator::allocate_value::<RedBlackTree<K>>();
         if !ptr.is_null() {
             ptr.write(RedBlackTree::default());
         }
@@ -30,8 +45,89 @@ pub unsafe extern "C" fn tree_create() -> *mut RedBlackTree {
     }
 }
 
+/// Drops and frees a tree allocated by [`create_tree`].
+///
+/// # Safety
+///
+/// `tree` must be a valid pointer previously returned by [`create_tree`] (or null).
+unsafe fn free_tree<K>(tree: *mut RedBlackTree<K>) {
+    if tree.is_null() {
+        return;
+    }
+    unsafe {
+        core::ptr::drop_in_place(tree);
+        allocator::deallocate_value(tree);
+    }
+}
+
+/// Returns a static message describing the most recent error reported on `tree`.
+///
+/// # Safety
+///
+/// `tree` must be a valid pointer to an initialized `RedBlackTree` (or null).
+unsafe fn last_error_message<K>(tree: *const RedBlackTree<K>) -> *const c_char {
+    if tree.is_null() {
+        return TreeError::NullPointer.message().as_ptr();
+    }
+    unsafe {
+        match (*tree).last_error() {
+            Some(error) => error.message().as_ptr(),
+            None => c"no error".as_ptr(),
+        }
+    }
+}
+
+/// Replaces the functions used to allocate and free all memory of the library.
+///
+/// Returns `0` on success or a negative [`TreeError`] code. It fails with
+/// [`TreeError::AllocatorInUse`] once anything was allocated or the hooks were already set,
+/// so it must be called before the first `tree_create`.
+///
+/// # Safety
+///
+/// * `allocate` must return null or a block of at least `size` bytes aligned to `align`.
+/// * `deallocate` must accept every block returned by `allocate` (with the same `size` and `align`).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_set_allocator(
+    allocate: Option<AllocateFn>,
+    deallocate: Option<DeallocateFn>,
+) -> i32 {
+    let (Some(allocate), Some(deallocate)) = (allocate, deallocate) else {
+        return TreeError::NullPointer.code();
+    };
+    match set_allocator_hooks(AllocatorHooks {
+        allocate,
+        deallocate,
+    }) {
+        Ok(_) => 0,
+        Err(error) => error.code(),
+    }
+}
+
+/// Records the outcome of an FFI call on `tree` and converts it into a status code.
+///
+/// # Safety
+///
+/// `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
+unsafe fn report<K>(tree: *const RedBlackTree<K>, result: Result<i32, TreeError>) -> i32 {
+    unsafe {
+        match result {
+            Ok(code) => {
+                (*tree).set_last_error(None);
+                code
+            }
+            Err(error) => {
+                (*tree).set_last_error(Some(error));
+                error.code()
+            }
+        }
+    }
+}
+
 /// Inserts a key-value pair into the tree.
 ///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
 /// # Safety
 ///
 /// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
@@ -43,18 +139,44 @@ pub unsafe extern "C" fn tree_insert(
     key: u64,
     value: *const c_char,
 ) -> i32 {
-    if tree.is_null() || value.is_null() {
-        return -1;
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
     }
     unsafe {
-        if let Some(data) = CharContainer::from_c_str(value) {
-            match (*tree).insert(key, data) {
-                Ok(_) => 0,
-                Err(_) => -2,
-            }
-        } else {
-            -2 // alloc
-        }
+        let result = CharContainer::from_c_str(value)
+            .and_then(|data| (*tree).insert(key, data))
+            .map(|_| 0);
+        report(tree, result)
+    }
+}
+
+/// Inserts a key-value pair into the tree, reporting whether the key was new.
+///
+/// Returns `0` if the key was inserted, `1` if the value of an existing key was replaced
+/// or a negative [`TreeError`] code.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
+/// * `value` must be a valid, non-null pointer to a **null-terminated** C string.
+/// * The memory pointed to by `tree` and `value` must be accessible.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_update(
+    tree: *mut RedBlackTree,
+    key: u64,
+    value: *const c_char,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = CharContainer::from_c_str(value)
+            .and_then(|data| (*tree).update(key, data))
+            .map(|outcome| match outcome {
+                TreeUpdate::Inserted => 0,
+                TreeUpdate::Replaced => 1,
+            });
+        report(tree, result)
     }
 }
 
@@ -80,6 +202,8 @@ pub unsafe extern "C" fn tree_contains(tree: *mut RedBlackTree, key: u64) -> i32
 
 /// Retrieves a value associated with a key into a provided buffer.
 ///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
 /// # Safety
 ///
 /// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
@@ -93,28 +217,47 @@ pub unsafe extern "C" fn tree_get(
     buffer: *mut c_char,
     buffer_size: usize,
 ) -> i32 {
-    if tree.is_null() || buffer.is_null() {
-        return -1;
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
     }
     unsafe {
-        match (*tree).get(key) {
-            Some(value) => {
-                let src = value.as_ptr();
-                let size = value.len();
-                if size + 1 > buffer_size {
-                    return -2; // alloc
-                }
-                ptr::copy_nonoverlapping(src, buffer as *mut u8, size);
-                *buffer.add(size) = 0;
-                0
+        let result = if buffer.is_null() {
+            Err(TreeError::NullPointer)
+        } else {
+            match (*tree).get(key) {
+                Some(value) => copy_to_buffer(value, buffer, buffer_size).map(|_| 0),
+                None => Err(TreeError::NotFound),
             }
-            None => 1,
-        }
+        };
+        report(tree, result)
+    }
+}
+
+/// Copies `value` into `buffer` as a null-terminated string.
+///
+/// # Safety
+///
+/// `buffer` must be a valid pointer to a writable memory region of at least `buffer_size` bytes.
+unsafe fn copy_to_buffer(
+    value: &str,
+    buffer: *mut c_char,
+    buffer_size: usize,
+) -> Result<(), TreeError> {
+    let size = value.len();
+    if size + 1 > buffer_size {
+        return Err(TreeError::BufferTooSmall);
     }
+    unsafe {
+        ptr::copy_nonoverlapping(value.as_ptr(), buffer as *mut u8, size);
+        *buffer.add(size) = 0;
+    }
+    Ok(())
 }
 
 /// Removes a key from the tree.
 ///
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
 /// # Safety
 ///
 /// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
@@ -122,14 +265,93 @@ pub unsafe extern "C" fn tree_get(
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tree_remove(tree: *mut RedBlackTree, key: u64) -> i32 {
     if tree.is_null() {
-        return -1;
+        return TreeError::NullPointer.code();
     }
     unsafe {
-        match (*tree).remove(key) {
-            Ok(_) => 0,
-            Err(_) => 1,
-        }
+        let result = (*tree).remove(key).map(|_| 0);
+        report(tree, result)
+    }
+}
+
+/// Returns the number of keys stored in the tree (`0` for a null tree).
+///
+/// # Safety
+///
+/// * `tree` must be a valid pointer to an initialized `RedBlackTree` (or null).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_len(tree: *const RedBlackTree) -> usize {
+    if tree.is_null() {
+        return 0;
+    }
+    unsafe { (*tree).len() }
+}
+
+/// Retrieves the entry with the given zero-based position in ascending key order.
+///
+/// The key is written to `key_out` and, unless `buffer` is null, the value is copied
+/// into `buffer`. Returns `0` on success or a negative [`TreeError`] code
+/// ([`TreeError::NotFound`] if `index` is out of range).
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
+/// * `key_out` must be a valid pointer to a writable `uint64_t`.
+/// * `buffer` must be null or a valid pointer to a writable memory region of at least `buffer_size` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_kth(
+    tree: *mut RedBlackTree,
+    index: usize,
+    key_out: *mut u64,
+    buffer: *mut c_char,
+    buffer_size: usize,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = if key_out.is_null() {
+            Err(TreeError::NullPointer)
+        } else {
+            match (*tree).kth(index) {
+                Some((key, value)) => {
+                    *key_out = *key;
+                    if buffer.is_null() {
+                        Ok(0)
+                    } else {
+                        copy_to_buffer(value, buffer, buffer_size).map(|_| 0)
+                    }
+                }
+                None => Err(TreeError::NotFound),
+            }
+        };
+        report(tree, result)
+    }
+}
+
+/// Returns the number of keys in the tree strictly smaller than `key`
+/// (`0` for a null tree).
+///
+/// # Safety
+///
+/// * `tree` must be a valid pointer to an initialized `RedBlackTree` (or null).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_rank(tree: *const RedBlackTree, key: u64) -> usize {
+    if tree.is_null() {
+        return 0;
     }
+    unsafe { (*tree).rank(key) }
+}
+
+/// Returns a static, null-terminated description of the error reported
+/// by the most recent call on `tree`, or `"no error"` if it succeeded.
+///
+/// # Safety
+///
+/// * `tree` must be a valid pointer to an initialized `RedBlackTree` (or null).
+/// * The returned pointer must not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_last_error_message(tree: *const RedBlackTree) -> *const c_char {
+    unsafe { last_error_message(tree) }
 }
 
 /// Frees the memory associated with the tree.
@@ -141,13 +363,7 @@ pub unsafe extern "C" fn tree_remove(tree: *mut RedBlackTree, key: u64) -> i32 {
 ///   and must not be used again.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tree_free(tree: *mut RedBlackTree) {
-    if tree.is_null() {
-        return;
-    }
-    unsafe {
-        core::ptr::drop_in_place(tree);
-        libc::free(tree as *mut c_void);
-    }
+    unsafe { free_tree(tree) }
 }
 
 /// Prints the structure of the tree to stdout for debugging purposes.
@@ -166,3 +382,109 @@ pub unsafe extern "C" fn tree_print_structure(tree: *const RedBlackTree) {
         (*tree).print_structure();
     }
 }
+
+/// Renders the structure of the tree into a provided buffer as a null-terminated string.
+///
+/// Uses the same layout as [`tree_print_structure`], but without ANSI escape codes.
+/// Returns `0` on success or a negative [`TreeError`] code.
+///
+/// # Safety
+///
+/// * `tree` must be a valid, non-null pointer to an initialized `RedBlackTree`.
+/// * `buffer` must be a valid pointer to a writable memory region of at least `buffer_size` bytes.
+/// * Ensure `buffer_size` is large enough to hold the rendering plus a null terminator.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tree_to_string(
+    tree: *const RedBlackTree,
+    buffer: *mut c_char,
+    buffer_size: usize,
+) -> i32 {
+    if tree.is_null() {
+        return TreeError::NullPointer.code();
+    }
+    unsafe {
+        let result = if buffer.is_null() {
+            Err(TreeError::NullPointer)
+        } else {
+            let rendering = (*tree).structure_string(false);
+            copy_to_buffer(&rendering, buffer, buffer_size).map(|_| 0)
+        };
+        report(tree, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ffi::CStr;
+
+    fn error_message(tree: *const RedBlackTree) -> &'static str {
+        unsafe { CStr::from_ptr(tree_last_error_message(tree)) }
+            .to_str()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_order_statistics() {
+        unsafe {
+            let tree = tree_create();
+            for key in [30, 10, 20] {
+                tree_insert(tree, key, c"value".as_ptr());
+            }
+
+            let mut key = 0;
+            let mut buffer = [0 as c_char; 8];
+            assert_eq!(tree_len(tree), 3);
+            assert_eq!(
+                tree_kth(tree, 1, &mut key, buffer.as_mut_ptr(), buffer.len()),
+                0
+            );
+            assert_eq!(key, 20);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str(), Ok("value"));
+            assert_eq!(tree_kth(tree, 2, &mut key, ptr::null_mut(), 0), 0);
+            assert_eq!(key, 30);
+            assert_eq!(tree_kth(tree, 3, &mut key, ptr::null_mut(), 0), -3);
+
+            assert_eq!(tree_rank(tree, 25), 2);
+            assert_eq!(tree_rank(tree, 10), 0);
+
+            tree_free(tree);
+        }
+    }
+
+    #[test]
+    fn test_error_codes() {
+        unsafe {
+            let tree = tree_create();
+            let mut buffer = [0 as c_char; 4];
+
+            assert_eq!(tree_insert(tree, 1, c"One".as_ptr()), 0);
+            assert_eq!(error_message(tree), "no error");
+
+            assert_eq!(tree_insert(tree, 2, ptr::null()), -1);
+            assert_eq!(error_message(tree), "null pointer");
+
+            let invalid = c"\xff".as_ptr();
+            assert_eq!(tree_insert(tree, 2, invalid), -4);
+            assert_eq!(error_message(tree), "value is not valid UTF-8");
+
+            assert_eq!(tree_get(tree, 7, buffer.as_mut_ptr(), buffer.len()), -3);
+            assert_eq!(error_message(tree), "key not found");
+
+            assert_eq!(tree_remove(tree, 7), -3);
+
+            assert_eq!(tree_insert(tree, 3, c"Three".as_ptr()), 0);
+            assert_eq!(tree_get(tree, 3, buffer.as_mut_ptr(), buffer.len()), -5);
+            assert_eq!(error_message(tree), "buffer too small");
+
+            assert_eq!(tree_get(tree, 1, buffer.as_mut_ptr(), buffer.len()), 0);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str(), Ok("One"));
+
+            tree_free(tree);
+        }
+
+        assert_eq!(error_message(ptr::null()), "null pointer");
+        assert_eq!(unsafe { tree_len(ptr::null()) }, 0);
+        assert_eq!(unsafe { tree_remove(ptr::null_mut(), 1) }, -1);
+    }
+}
@@ -1,12 +1,15 @@
+/// Creates a [`RedBlackTree`](crate::RedBlackTree) from `key => value` pairs.
+///
+/// Keys can be any expressions of a single [`TreeKey`](crate::TreeKey) type,
+/// values any expressions implementing `AsRef<str>` (`&str`, `String`, [`CharContainer`](crate::CharContainer), ...).
 #[macro_export]
 macro_rules! red_black_tree {
     ( $( $key:expr => $val:expr ),* $(,)? ) => {
         {
+            #[allow(unused_mut)]
             let mut tree = $crate::RedBlackTree::default();
             $(
-                if let Some(value) = $crate::CharContainer::new($val) {
-                    let _ = tree.insert($key, value);
-                }
+                ::core::iter::Extend::extend(&mut tree, ::core::iter::once(($key, $val)));
             )*
             tree
         }
@@ -28,4 +31,25 @@ mod tests {
         assert_eq!(tree.get(3), Some("c"));
         assert!(!tree.contains(4));
     }
+
+    #[test]
+    fn test_macro_arbitrary_expressions() {
+        let base = 10;
+        let name = String::from("ten");
+        let tree = red_black_tree! {
+            base => name,
+            base * 2 => format!("{}", base * 2),
+            base + 1 => "eleven",
+        };
+
+        assert_eq!(tree.get(10), Some("ten"));
+        assert_eq!(tree.get(20), Some("20"));
+        assert_eq!(tree.get(11), Some("eleven"));
+    }
+
+    #[test]
+    fn test_macro_empty() {
+        let tree: crate::RedBlackTree = red_black_tree! {};
+        assert!(tree.iter().next().is_none());
+    }
 }
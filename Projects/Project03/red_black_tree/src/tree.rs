@@ -1,60 +1,220 @@
+mod node_arena;
 mod tree_color;
 mod tree_error;
+mod tree_iter;
+mod tree_key;
 mod tree_node;
+mod tree_update;
 
+use node_arena::NodeArena;
 pub use tree_color::TreeColor;
 pub use tree_error::TreeError;
+pub use tree_iter::{IntoIter, Iter};
+pub use tree_key::TreeKey;
 pub use tree_node::TreeNode;
+pub use tree_update::TreeUpdate;
 
-use core::ffi::c_void;
 use std::{
-    mem,
+    cell::Cell,
+    cmp::Ordering,
+    fmt,
     ptr::{self},
 };
 
 use crate::{BOLD, CharContainer, RED, RESET};
 
-pub struct RedBlackTree {
-    root: *mut TreeNode,
+pub struct RedBlackTree<K = u64> {
+    root: *mut TreeNode<K>,
+    last_error: Cell<Option<TreeError>>,
+    arena: NodeArena<K>,
 }
 
-impl Default for RedBlackTree {
+impl<K> Default for RedBlackTree<K> {
     fn default() -> Self {
         Self {
             root: ptr::null_mut(),
+            last_error: Cell::new(None),
+            arena: NodeArena::default(),
         }
     }
 }
 
-impl RedBlackTree {
-    fn create_node(key: u64, value: CharContainer) -> *mut TreeNode {
+impl<K> RedBlackTree<K> {
+    /// Error reported by the most recent FFI call on this tree, if it failed.
+    pub fn last_error(&self) -> Option<TreeError> {
+        self.last_error.get()
+    }
+
+    pub(crate) fn set_last_error(&self, error: Option<TreeError>) {
+        self.last_error.set(error);
+    }
+
+    fn create_node(&mut self, key: K, value: CharContainer) -> *mut TreeNode<K> {
+        self.arena.allocate(TreeNode::new(key, value))
+    }
+
+    unsafe fn free_node(&mut self, node: *mut TreeNode<K>) {
+        if node.is_null() {
+            return;
+        }
+
+        unsafe {
+            ptr::drop_in_place(node);
+            self.release_node(node);
+        }
+    }
+
+    /// Releases the memory of `node` without dropping its key and value.
+    unsafe fn release_node(&mut self, node: *mut TreeNode<K>) {
+        unsafe {
+            self.arena.release(node);
+        }
+    }
+
+    /// Returns the number of entries in the tree.
+    pub fn len(&self) -> usize {
+        unsafe { Self::subtree_size(self.root) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_null()
+    }
+
+    /// Returns the entry with the given zero-based position in ascending key order.
+    pub fn kth(&self, index: usize) -> Option<(&K, &str)> {
         unsafe {
-            let size = mem::size_of::<TreeNode>();
-            let data = libc::malloc(size) as *mut TreeNode;
-            if !data.is_null() {
-                data.write(TreeNode::new(key, value));
+            let node = self.select(index);
+            if node.is_null() {
+                None
+            } else {
+                Some((&(*node).key, (&(*node).value).into()))
             }
-            data
         }
     }
 
-    unsafe fn free_node(node: *mut TreeNode) {
+    /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
+    /// chapter 14.1 "Dynamic order statistics", page 341
+    unsafe fn select(&self, mut index: usize) -> *mut TreeNode<K> {
+        let mut node = self.root;
+        unsafe {
+            while !node.is_null() {
+                let left_size = Self::subtree_size((*node).left);
+                if index < left_size {
+                    node = (*node).left;
+                } else if index == left_size {
+                    break;
+                } else {
+                    index -= left_size + 1;
+                    node = (*node).right;
+                }
+            }
+            node
+        }
+    }
+
+    unsafe fn subtree_size(node: *mut TreeNode<K>) -> usize {
         if node.is_null() {
-            return;
+            0
+        } else {
+            unsafe { (*node).size }
         }
+    }
 
+    /// Decrements the subtree sizes of `node` and all of its ancestors.
+    unsafe fn decrement_sizes(mut node: *mut TreeNode<K>) {
         unsafe {
-            Self::free_node((*node).left);
-            Self::free_node((*node).right);
-            ptr::drop_in_place(node);
-            libc::free(node as *mut c_void);
+            while !node.is_null() {
+                (*node).size -= 1;
+                node = (*node).parent;
+            }
+        }
+    }
+
+    /// Returns an iterator over the entries of the tree in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter::new(self.root)
+    }
+
+    /// Frees the whole subtree rooted at `node` without recursion.
+    ///
+    /// Whenever the current node has a left child, it is rotated to the right,
+    /// so that the subtree gradually turns into a right-leaning chain which can
+    /// be freed node by node. Runs in O(n) time and O(1) extra space.
+    unsafe fn free_subtree(&mut self, mut node: *mut TreeNode<K>) {
+        unsafe {
+            while !node.is_null() {
+                let left = (*node).left;
+                if left.is_null() {
+                    let next = (*node).right;
+                    self.free_node(node);
+                    node = next;
+                } else {
+                    (*node).left = (*left).right;
+                    (*left).right = node;
+                    node = left;
+                }
+            }
+        }
+    }
+}
+
+impl<K: TreeKey> RedBlackTree<K> {
+    /// Inserts a key-value pair into the tree, overwriting the value of an existing key.
+    pub fn insert(&mut self, key: K, val: CharContainer) -> Result<(), TreeError> {
+        self.update(key, val).map(|_| ())
+    }
+
+    /// Inserts a key-value pair into the tree and reports whether the key was
+    /// newly inserted or its previous value was replaced.
+    pub fn update(&mut self, key: K, val: CharContainer) -> Result<TreeUpdate, TreeError> {
+        unsafe {
+            let node = self.find(key.borrow_key());
+            if !node.is_null() {
+                (*node).value = val;
+                return Ok(TreeUpdate::Replaced);
+            }
+            self.insert_node(key, val)?;
+        }
+        Ok(TreeUpdate::Inserted)
+    }
+
+    /// Returns a mutable reference to the value associated with `key`.
+    pub fn get_mut(&mut self, key: K::Borrowed<'_>) -> Option<&mut CharContainer> {
+        unsafe {
+            let node = self.find(key);
+            if node.is_null() {
+                None
+            } else {
+                Some(&mut (*node).value)
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value associated with `key`,
+    /// inserting the value produced by `f` first if the key is missing.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> Result<&mut CharContainer, TreeError>
+    where
+        F: FnOnce() -> CharContainer,
+    {
+        unsafe {
+            let mut node = self.find(key.borrow_key());
+            if node.is_null() {
+                node = self.insert_node(key, f())?;
+            }
+            Ok(&mut (*node).value)
         }
     }
 
     /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
     /// chapter 13.3 "Red-Black Trees - Insertion", page 315, 316
-    pub fn insert(&mut self, key: u64, val: CharContainer) -> Result<(), TreeError> {
-        let node = RedBlackTree::create_node(key, val);
+    ///
+    /// The caller must ensure that `key` is not yet present in the tree.
+    unsafe fn insert_node(
+        &mut self,
+        key: K,
+        val: CharContainer,
+    ) -> Result<*mut TreeNode<K>, TreeError> {
+        let node = self.create_node(key, val);
         if node.is_null() {
             return Err(TreeError::AllocationFailed);
         }
@@ -65,21 +225,18 @@ impl RedBlackTree {
 
             while !current.is_null() {
                 parent = current;
-                if (*node).key < (*current).key {
+                (*current).size += 1;
+                if Self::is_less(node, current) {
                     current = (*current).left;
-                } else if (*node).key > (*current).key {
-                    current = (*current).right;
                 } else {
-                    (*current).value = (*node).value.clone();
-                    Self::free_node(node);
-                    return Ok(());
+                    current = (*current).right;
                 }
             }
 
             (*node).parent = parent;
             if parent.is_null() {
                 self.root = node;
-            } else if (*node).key < (*parent).key {
+            } else if Self::is_less(node, parent) {
                 (*parent).left = node;
             } else {
                 (*parent).right = node;
@@ -87,10 +244,14 @@ impl RedBlackTree {
 
             self.insert_fixup(node);
         }
-        Ok(())
+        Ok(node)
+    }
+
+    unsafe fn is_less(node: *mut TreeNode<K>, other: *mut TreeNode<K>) -> bool {
+        unsafe { K::compare((*node).key.borrow_key(), &(*other).key) == Ordering::Less }
     }
 
-    unsafe fn insert_fixup(&mut self, mut current: *mut TreeNode) {
+    unsafe fn insert_fixup(&mut self, mut current: *mut TreeNode<K>) {
         unsafe {
             while !(*current).parent.is_null() && (*(*current).parent).color == TreeColor::Red {
                 let parent = (*current).parent;
@@ -105,7 +266,7 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn fix_insert_left_case(&mut self, mut current: *mut TreeNode) -> *mut TreeNode {
+    unsafe fn fix_insert_left_case(&mut self, mut current: *mut TreeNode<K>) -> *mut TreeNode<K> {
         unsafe {
             let parent = (*current).parent;
             let grandparent = (*parent).parent;
@@ -132,7 +293,7 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn fix_insert_right_case(&mut self, mut current: *mut TreeNode) -> *mut TreeNode {
+    unsafe fn fix_insert_right_case(&mut self, mut current: *mut TreeNode<K>) -> *mut TreeNode<K> {
         unsafe {
             let parent = (*current).parent;
             let grandparent = (*parent).parent;
@@ -161,7 +322,7 @@ impl RedBlackTree {
 
     /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
     /// chapter 13.2 "Red-Black Trees - Rotations", page 313, 314
-    unsafe fn rotate_left(&mut self, node: *mut TreeNode) {
+    unsafe fn rotate_left(&mut self, node: *mut TreeNode<K>) {
         unsafe {
             let right_child = (*node).right;
             (*node).right = (*right_child).left;
@@ -181,10 +342,13 @@ impl RedBlackTree {
 
             (*right_child).left = node;
             (*node).parent = right_child;
+
+            (*right_child).size = (*node).size;
+            (*node).size = Self::subtree_size((*node).left) + Self::subtree_size((*node).right) + 1;
         }
     }
 
-    unsafe fn rotate_right(&mut self, node: *mut TreeNode) {
+    unsafe fn rotate_right(&mut self, node: *mut TreeNode<K>) {
         unsafe {
             let left_child = (*node).left;
             (*node).left = (*left_child).right;
@@ -204,10 +368,13 @@ impl RedBlackTree {
 
             (*left_child).right = node;
             (*node).parent = left_child;
+
+            (*left_child).size = (*node).size;
+            (*node).size = Self::subtree_size((*node).left) + Self::subtree_size((*node).right) + 1;
         }
     }
 
-    pub fn get(&self, key: u64) -> Option<&str> {
+    pub fn get(&self, key: K::Borrowed<'_>) -> Option<&str> {
         unsafe {
             let node = self.find(key);
             if node.is_null() {
@@ -221,7 +388,7 @@ impl RedBlackTree {
 
     /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
     /// chapter 13.2 "Red-Black Trees - Deletion", page 324
-    pub fn remove(&mut self, key: u64) -> Result<(), TreeError> {
+    pub fn remove(&mut self, key: K::Borrowed<'_>) -> Result<(), TreeError> {
         unsafe {
             let node = self.find(key);
             if node.is_null() {
@@ -232,17 +399,18 @@ impl RedBlackTree {
         Ok(())
     }
 
-    unsafe fn delete_node_internal(&mut self, z: *mut TreeNode) {
+    unsafe fn delete_node_internal(&mut self, z: *mut TreeNode<K>) {
         unsafe {
             let mut y = z;
             let mut y_original_color = (*y).color;
-            let x: *mut TreeNode;
-            let x_parent: *mut TreeNode;
+            let x: *mut TreeNode<K>;
+            let x_parent: *mut TreeNode<K>;
 
             if (*z).left.is_null() {
                 x = (*z).right;
                 let actual_parent = (*z).parent;
 
+                Self::decrement_sizes(actual_parent);
                 self.transplant(z, (*z).right);
 
                 x_parent = if !x.is_null() {
@@ -254,6 +422,7 @@ impl RedBlackTree {
                 x = (*z).left;
                 let actual_parent = (*z).parent;
 
+                Self::decrement_sizes(actual_parent);
                 self.transplant(z, (*z).left);
 
                 x_parent = if !x.is_null() {
@@ -266,6 +435,9 @@ impl RedBlackTree {
                 y_original_color = (*y).color;
                 x = (*y).right;
 
+                // `y` moves into the place of `z`, so every node above the old position of `y` loses one descendant
+                Self::decrement_sizes((*y).parent);
+
                 if (*y).parent == z {
                     x_parent = y;
                 } else {
@@ -279,20 +451,20 @@ impl RedBlackTree {
                 (*y).left = (*z).left;
                 (*(*y).left).parent = y;
                 (*y).color = (*z).color;
+                (*y).size = (*z).size;
             }
 
             if y_original_color == TreeColor::Black {
                 self.delete_fixup(x, x_parent);
             }
 
-            ptr::drop_in_place(&mut (*z).value);
-            libc::free(z as *mut c_void);
+            self.free_node(z);
         }
     }
 
     /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
     /// chapter 13.2 "Red-Black Trees - Deletion", page 323
-    unsafe fn transplant(&mut self, target: *mut TreeNode, replacement: *mut TreeNode) {
+    unsafe fn transplant(&mut self, target: *mut TreeNode<K>, replacement: *mut TreeNode<K>) {
         unsafe {
             if (*target).parent.is_null() {
                 self.root = replacement;
@@ -307,7 +479,7 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn minimum(&self, mut node: *mut TreeNode) -> *mut TreeNode {
+    unsafe fn minimum(&self, mut node: *mut TreeNode<K>) -> *mut TreeNode<K> {
         unsafe {
             while !(*node).left.is_null() {
                 node = (*node).left;
@@ -316,27 +488,47 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn find(&self, key: u64) -> *mut TreeNode {
+    unsafe fn find(&self, key: K::Borrowed<'_>) -> *mut TreeNode<K> {
         let mut node = self.root;
         unsafe {
-            while !node.is_null() && key != (*node).key {
-                if key < (*node).key {
-                    node = (*node).left;
-                } else {
-                    node = (*node).right;
+            while !node.is_null() {
+                match K::compare(key, &(*node).key) {
+                    Ordering::Less => node = (*node).left,
+                    Ordering::Greater => node = (*node).right,
+                    Ordering::Equal => break,
                 }
             }
             node
         }
     }
 
-    pub fn contains(&self, key: u64) -> bool {
+    pub fn contains(&self, key: K::Borrowed<'_>) -> bool {
         unsafe { !self.find(key).is_null() }
     }
 
+    /// Returns the number of keys in the tree strictly smaller than `key`,
+    /// i.e. the position `key` has (or would have) in ascending key order.
+    pub fn rank(&self, key: K::Borrowed<'_>) -> usize {
+        let mut rank = 0;
+        let mut node = self.root;
+        unsafe {
+            while !node.is_null() {
+                match K::compare(key, &(*node).key) {
+                    Ordering::Less => node = (*node).left,
+                    Ordering::Equal => return rank + Self::subtree_size((*node).left),
+                    Ordering::Greater => {
+                        rank += Self::subtree_size((*node).left) + 1;
+                        node = (*node).right;
+                    }
+                }
+            }
+        }
+        rank
+    }
+
     /// Algorithm translated to Rust from "Introduction To Algorithms Third Edition"
     /// chapter 13.2 "Red-Black Trees - Deletion", page 326
-    unsafe fn delete_fixup(&mut self, mut current: *mut TreeNode, mut parent: *mut TreeNode) {
+    unsafe fn delete_fixup(&mut self, mut current: *mut TreeNode<K>, mut parent: *mut TreeNode<K>) {
         unsafe {
             while current != self.root
                 && (current.is_null() || (*current).color == TreeColor::Black)
@@ -353,7 +545,7 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn fix_delete_left(&mut self, parent: &mut *mut TreeNode) -> *mut TreeNode {
+    unsafe fn fix_delete_left(&mut self, parent: &mut *mut TreeNode<K>) -> *mut TreeNode<K> {
         unsafe {
             let mut sibling = (*(*parent)).right;
 
@@ -415,7 +607,7 @@ impl RedBlackTree {
         }
     }
 
-    unsafe fn fix_delete_right(&mut self, parent: &mut *mut TreeNode) -> *mut TreeNode {
+    unsafe fn fix_delete_right(&mut self, parent: &mut *mut TreeNode<K>) -> *mut TreeNode<K> {
         unsafe {
             let mut sibling = (*(*parent)).left;
 
@@ -476,51 +668,111 @@ impl RedBlackTree {
     }
 
     pub fn print_structure(&self) {
+        print!("{}", self.structure_string(true));
+    }
+
+    /// Renders the structure of the tree (the same layout as [`RedBlackTree::print_structure`])
+    /// into a [`String`]. When `colored` is `false`, no ANSI escape codes are emitted.
+    pub fn structure_string(&self, colored: bool) -> String {
+        let mut output = String::new();
+        let _ = self.write_structure(&mut output, colored);
+        output
+    }
+
+    fn write_structure<W: fmt::Write>(&self, out: &mut W, colored: bool) -> fmt::Result {
+        let mut result = Ok(());
         unsafe {
-            Self::print_node_internal(self.root, 0);
+            self.for_each_with_level_desc(|node, level| {
+                if result.is_err() {
+                    return;
+                }
+
+                let indent = "    ".repeat(level);
+                let content: &str = (&(*node).value).into();
+                let key = &(*node).key;
+                let color = (*node).color;
+
+                result = if colored {
+                    let color_code = match color {
+                        TreeColor::Red => RED,
+                        TreeColor::Black => BOLD,
+                    };
+                    writeln!(
+                        out,
+                        "{indent}{color_code}[{key}] {content}{RESET} ({color})"
+                    )
+                } else {
+                    writeln!(out, "{indent}[{key}] {content} ({color})")
+                };
+            });
         }
+        result
     }
 
+    /// Visits every node in descending key order (right subtree, node, left subtree)
+    /// together with its depth, walking the parent pointers instead of recursing.
+    ///
     /// Idea for the printing algorithm taken from:
     /// https://www.geeksforgeeks.org/dsa/print-binary-tree-2-dimensions/
-    unsafe fn print_node_internal(node: *mut TreeNode, level: usize) {
-        if node.is_null() {
+    unsafe fn for_each_with_level_desc<F>(&self, mut visit: F)
+    where
+        F: FnMut(*mut TreeNode<K>, usize),
+    {
+        if self.root.is_null() {
             return;
         }
 
         unsafe {
-            Self::print_node_internal((*node).right, level + 1);
-            let indent = "    ".repeat(level);
-            let color_code = match (*node).color {
-                TreeColor::Red => RED,
-                TreeColor::Black => BOLD,
-            };
+            let mut node = self.root;
+            let mut level = 0;
+            while !(*node).right.is_null() {
+                node = (*node).right;
+                level += 1;
+            }
 
-            let content: &str = (&(*node).value).into();
+            loop {
+                visit(node, level);
 
-            println!(
-                "{indent}{color_code}[{}] {content}{RESET} ({})",
-                (*node).key,
-                (*node).color,
-            );
+                if !(*node).left.is_null() {
+                    node = (*node).left;
+                    level += 1;
+                    while !(*node).right.is_null() {
+                        node = (*node).right;
+                        level += 1;
+                    }
+                    continue;
+                }
+
+                let mut parent = (*node).parent;
+                while !parent.is_null() && node == (*parent).left {
+                    node = parent;
+                    parent = (*node).parent;
+                    level -= 1;
+                }
 
-            Self::print_node_internal((*node).left, level + 1);
+                if parent.is_null() {
+                    return;
+                }
+                node = parent;
+                level -= 1;
+            }
         }
     }
 }
 
-impl Drop for RedBlackTree {
+impl<K> Drop for RedBlackTree<K> {
     fn drop(&mut self) {
         unsafe {
-            Self::free_node(self.root);
+            self.free_subtree(self.root);
         }
+        self.root = ptr::null_mut();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::CharContainer;
+    use crate::{ByteKey, CharContainer};
 
     fn create_value(s: &str) -> CharContainer {
         CharContainer::new(s).expect("Allocation failed")
@@ -528,7 +780,7 @@ mod tests {
 
     #[test]
     fn test_new_tree_is_empty() {
-        let tree = RedBlackTree::default();
+        let tree: RedBlackTree = RedBlackTree::default();
         assert!(tree.root.is_null());
     }
 
@@ -556,6 +808,53 @@ mod tests {
         assert_eq!(tree.get(1), Some("Uno"));
     }
 
+    #[test]
+    fn test_update_reports_outcome() {
+        let mut tree = RedBlackTree::default();
+
+        let outcome = tree.update(1, create_value("One")).unwrap();
+        assert_eq!(outcome, TreeUpdate::Inserted);
+
+        let outcome = tree.update(1, create_value("Uno")).unwrap();
+        assert_eq!(outcome, TreeUpdate::Replaced);
+        assert_eq!(tree.get(1), Some("Uno"));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut tree = RedBlackTree::default();
+        tree.insert(1, create_value("One")).unwrap();
+
+        if let Some(value) = tree.get_mut(1) {
+            *value = create_value("Uno");
+        }
+
+        assert_eq!(tree.get(1), Some("Uno"));
+        assert!(tree.get_mut(2).is_none());
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut tree = RedBlackTree::default();
+        tree.insert(1, create_value("One")).unwrap();
+
+        let existing = tree.get_or_insert_with(1, || create_value("Uno")).unwrap();
+        let content: &str = (&*existing).into();
+        assert_eq!(content, "One");
+
+        for i in 2..=10 {
+            let inserted = tree
+                .get_or_insert_with(i, || create_value(&format!("{i}")))
+                .unwrap();
+            let content: &str = (&*inserted).into();
+            assert_eq!(content, format!("{i}"));
+        }
+
+        for i in 2..=10 {
+            assert_eq!(tree.get(i), Some(format!("{i}").as_str()));
+        }
+    }
+
     #[test]
     fn test_insert_multiple_balanced() {
         // Inserting 1, 2, 3 should trigger rotation
@@ -626,6 +925,192 @@ mod tests {
         assert!(matches!(result, Err(TreeError::NotFound)));
     }
 
+    #[test]
+    fn test_drop_large_tree() {
+        let mut tree = RedBlackTree::default();
+        for i in 0..100_000 {
+            tree.insert(i, create_value("value")).unwrap();
+        }
+        drop(tree);
+    }
+
+    #[test]
+    fn test_for_each_with_level_desc() {
+        let mut tree = RedBlackTree::default();
+        for i in 1..=10 {
+            tree.insert(i, create_value(&format!("{i}"))).unwrap();
+        }
+
+        let mut visited = Vec::new();
+        unsafe {
+            tree.for_each_with_level_desc(|node, level| {
+                visited.push(((*node).key, level));
+            });
+        }
+
+        let keys: Vec<u64> = visited.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, (1..=10).rev().collect::<Vec<_>>());
+
+        // 4 is the root after inserting 1..=10
+        assert!(visited.contains(&(4, 0)));
+        assert!(visited.contains(&(2, 1)));
+        assert!(visited.contains(&(6, 1)));
+    }
+
+    #[test]
+    fn test_structure_string_plain() {
+        let mut tree = RedBlackTree::default();
+        tree.insert(1, create_value("One")).unwrap();
+        tree.insert(2, create_value("Two")).unwrap();
+        tree.insert(3, create_value("Three")).unwrap();
+
+        let expected = "    [3] Three (R)\n[2] Two (B)\n    [1] One (R)\n";
+        assert_eq!(tree.structure_string(false), expected);
+    }
+
+    #[test]
+    fn test_structure_string_colored() {
+        let mut tree = RedBlackTree::default();
+        tree.insert(1, create_value("One")).unwrap();
+
+        let expected = format!("{BOLD}[1] One{RESET} (B)\n");
+        assert_eq!(tree.structure_string(true), expected);
+        assert_eq!(RedBlackTree::<u64>::default().structure_string(true), "");
+    }
+
+    #[test]
+    fn test_byte_keys_memcmp_order() {
+        let mut tree = RedBlackTree::default();
+        for key in [&b"b"[..], b"ab", b"a", b"a\x00", b""] {
+            let key = ByteKey::new(key).expect("Allocation failed");
+            tree.insert(key, create_value("value")).unwrap();
+        }
+
+        assert!(tree.contains(b"a\x00"));
+        assert!(!tree.contains(b"a\x01"));
+        assert_eq!(tree.get(b""), Some("value"));
+
+        let mut keys = Vec::new();
+        unsafe {
+            tree.for_each_with_level_desc(|node, _| keys.push((*node).key.as_bytes().to_vec()));
+        }
+        keys.reverse();
+        assert_eq!(keys, [&b""[..], b"a", b"a\x00", b"ab", b"b"]);
+
+        assert!(tree.remove(b"ab").is_ok());
+        assert!(!tree.contains(b"ab"));
+    }
+
+    #[test]
+    fn test_iter_ascending() {
+        let tree: RedBlackTree = [(3, "Three"), (1, "One"), (2, "Two")].into_iter().collect();
+
+        let entries: Vec<(u64, &str)> = tree.iter().map(|(key, value)| (*key, value)).collect();
+        assert_eq!(entries, [(1, "One"), (2, "Two"), (3, "Three")]);
+
+        let keys: Vec<u64> = (&tree).into_iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_into_iter_ascending() {
+        let mut tree = RedBlackTree::default();
+        tree.extend((1..=100).rev().map(|i| (i, format!("{i}"))));
+
+        let mut expected = 1;
+        for (key, value) in tree {
+            let content: &str = (&value).into();
+            assert_eq!(key, expected);
+            assert_eq!(content, format!("{key}"));
+            expected += 1;
+        }
+        assert_eq!(expected, 101);
+    }
+
+    #[test]
+    fn test_into_iter_partially_consumed() {
+        let tree: RedBlackTree<ByteKey> = ["c", "a", "b"]
+            .into_iter()
+            .map(|s| (ByteKey::new(s.as_bytes()).unwrap(), s))
+            .collect();
+
+        let mut iter = tree.into_iter();
+        let (key, _) = iter.next().unwrap();
+        assert_eq!(key.as_bytes(), b"a");
+        drop(iter);
+    }
+
+    #[test]
+    fn test_removed_node_slot_is_reused() {
+        let mut tree = RedBlackTree::default();
+        tree.insert(1, create_value("One")).unwrap();
+        tree.insert(2, create_value("Two")).unwrap();
+
+        let removed = unsafe { tree.find(2) };
+        tree.remove(2).unwrap();
+        tree.insert(3, create_value("Three")).unwrap();
+
+        unsafe {
+            assert_eq!(tree.find(3), removed);
+        }
+        assert_eq!(tree.get(3), Some("Three"));
+    }
+
+    /// Verifies the subtree sizes of the whole tree, returning the size of `node`.
+    unsafe fn check_sizes(node: *mut TreeNode<u64>) -> usize {
+        if node.is_null() {
+            return 0;
+        }
+        unsafe {
+            let size = check_sizes((*node).left) + check_sizes((*node).right) + 1;
+            assert_eq!((*node).size, size, "wrong size of node {}", (*node).key);
+            size
+        }
+    }
+
+    #[test]
+    fn test_sizes_maintained() {
+        let mut tree = RedBlackTree::default();
+        // a fixed permutation of 0..200
+        let keys: Vec<u64> = (0..200).map(|i| (i * 73) % 200).collect();
+
+        for &key in &keys {
+            tree.insert(key, create_value("value")).unwrap();
+            unsafe { check_sizes(tree.root) };
+        }
+        assert_eq!(tree.len(), 200);
+
+        for &key in keys.iter().step_by(3) {
+            tree.remove(key).unwrap();
+            unsafe { check_sizes(tree.root) };
+        }
+        assert_eq!(tree.len(), 200 - keys.iter().step_by(3).count());
+
+        tree.insert(keys[1], create_value("replaced")).unwrap();
+        unsafe { check_sizes(tree.root) };
+    }
+
+    #[test]
+    fn test_kth_and_rank() {
+        let tree: RedBlackTree = (1..=50).rev().map(|i| (i * 10, format!("{i}"))).collect();
+
+        assert_eq!(tree.kth(0), Some((&10, "1")));
+        assert_eq!(tree.kth(24), Some((&250, "25")));
+        assert_eq!(tree.kth(49), Some((&500, "50")));
+        assert_eq!(tree.kth(50), None);
+
+        assert_eq!(tree.rank(10), 0);
+        assert_eq!(tree.rank(5), 0);
+        assert_eq!(tree.rank(255), 25);
+        assert_eq!(tree.rank(500), 49);
+        assert_eq!(tree.rank(1000), 50);
+
+        for i in 0..tree.len() {
+            let (key, _) = tree.kth(i).unwrap();
+            assert_eq!(tree.rank(*key), i);
+        }
+    }
+
     #[test]
     fn test_complex_scenario_1_to_10() {
         let mut tree = RedBlackTree::default();
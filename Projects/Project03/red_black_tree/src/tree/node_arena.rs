@@ -0,0 +1,176 @@
+use std::{mem::ManuallyDrop, ptr};
+
+use super::TreeNode;
+use crate::allocator;
+
+/// Number of nodes allocated at once by a single allocation.
+pub const CHUNK_CAPACITY: usize = 64;
+
+/// A slot is either occupied by a node or links to the next free slot.
+union Slot<K> {
+    node: ManuallyDrop<TreeNode<K>>,
+    next_free: *mut Slot<K>,
+}
+
+struct Chunk<K> {
+    next: *mut Chunk<K>,
+    slots: [Slot<K>; CHUNK_CAPACITY],
+}
+
+/// Pool allocator handing out tree nodes from large chunks.
+///
+/// Released nodes are kept on a free list and reused by later allocations.
+/// Chunks are returned to the system only when the arena is dropped, so every
+/// node must be dropped (or moved out) by the owner before that happens.
+pub struct NodeArena<K> {
+    chunks: *mut Chunk<K>,
+    free_list: *mut Slot<K>,
+    /// Number of never-used slots left in the most recent chunk.
+    unused: usize,
+}
+
+impl<K> Default for NodeArena<K> {
+    fn default() -> Self {
+        Self {
+            chunks: ptr::null_mut(),
+            free_list: ptr::null_mut(),
+            unused: 0,
+        }
+    }
+}
+
+impl<K> NodeArena<K> {
+    /// Moves `node` into a free slot, returning null if a new chunk could not be allocated.
+    pub fn allocate(&mut self, node: TreeNode<K>) -> *mut TreeNode<K> {
+        let slot = self.take_slot();
+        if slot.is_null() {
+            return ptr::null_mut();
+        }
+
+        unsafe {
+            let node_ptr = ptr::addr_of_mut!((*slot).node).cast::<TreeNode<K>>();
+            node_ptr.write(node);
+            node_ptr
+        }
+    }
+
+    /// Returns the slot of `node` to the free list without dropping the node.
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been returned by [`NodeArena::allocate`] of this arena
+    /// and must not be used afterwards.
+    pub unsafe fn release(&mut self, node: *mut TreeNode<K>) {
+        let slot = node as *mut Slot<K>;
+        unsafe {
+            (*slot).next_free = self.free_list;
+        }
+        self.free_list = slot;
+    }
+
+    fn take_slot(&mut self) -> *mut Slot<K> {
+        unsafe {
+            if !self.free_list.is_null() {
+                let slot = self.free_list;
+                self.free_list = (*slot).next_free;
+                return slot;
+            }
+
+            if self.unused == 0 && !self.grow() {
+                return ptr::null_mut();
+            }
+
+            self.unused -= 1;
+            let index = CHUNK_CAPACITY - 1 - self.unused;
+            ptr::addr_of_mut!((*self.chunks).slots[index])
+        }
+    }
+
+    fn grow(&mut self) -> bool {
+        unsafe {
+            let chunk = allocator::allocate_value::<Chunk<K>>();
+            if chunk.is_null() {
+                return false;
+            }
+
+            (*chunk).next = self.chunks;
+            self.chunks = chunk;
+            self.unused = CHUNK_CAPACITY;
+            true
+        }
+    }
+
+    #[cfg(test)]
+    fn chunk_count(&self) -> usize {
+        let mut count = 0;
+        let mut chunk = self.chunks;
+        while !chunk.is_null() {
+            count += 1;
+            chunk = unsafe { (*chunk).next };
+        }
+        count
+    }
+}
+
+impl<K> Drop for NodeArena<K> {
+    fn drop(&mut self) {
+        unsafe {
+            while !self.chunks.is_null() {
+                let next = (*self.chunks).next;
+                allocator::deallocate_value(self.chunks);
+                self.chunks = next;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CharContainer;
+
+    fn create_node(key: u64) -> TreeNode<u64> {
+        TreeNode::new(key, CharContainer::new("value").expect("Allocation failed"))
+    }
+
+    #[test]
+    fn test_allocates_in_chunks() {
+        let mut arena = NodeArena::default();
+        let mut nodes = Vec::new();
+
+        for i in 0..CHUNK_CAPACITY as u64 + 1 {
+            let node = arena.allocate(create_node(i));
+            assert!(!node.is_null());
+            nodes.push(node);
+        }
+
+        assert_eq!(arena.chunk_count(), 2);
+        unsafe {
+            for (i, node) in nodes.iter().enumerate() {
+                assert_eq!((**node).key, i as u64);
+                ptr::drop_in_place(*node);
+                arena.release(*node);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reuses_released_slots() {
+        let mut arena = NodeArena::default();
+        let first = arena.allocate(create_node(1));
+        let second = arena.allocate(create_node(2));
+
+        unsafe {
+            ptr::drop_in_place(first);
+            arena.release(first);
+
+            let third = arena.allocate(create_node(3));
+            assert_eq!(third, first);
+            assert_eq!((*third).key, 3);
+            assert_eq!(arena.chunk_count(), 1);
+
+            ptr::drop_in_place(second);
+            ptr::drop_in_place(third);
+        }
+    }
+}
@@ -1,5 +1,39 @@
-#[derive(Debug)]
+use core::ffi::CStr;
+use std::fmt::Display;
+
+/// Errors reported by the tree. The discriminants are the codes returned over FFI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i32)]
 pub enum TreeError {
-    AllocationFailed,
-    NotFound,
+    NullPointer = -1,
+    AllocationFailed = -2,
+    NotFound = -3,
+    InvalidUtf8 = -4,
+    BufferTooSmall = -5,
+    AllocatorInUse = -6,
 }
+
+impl TreeError {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    pub fn message(self) -> &'static CStr {
+        match self {
+            Self::NullPointer => c"null pointer",
+            Self::AllocationFailed => c"allocation failed",
+            Self::NotFound => c"key not found",
+            Self::InvalidUtf8 => c"value is not valid UTF-8",
+            Self::BufferTooSmall => c"buffer too small",
+            Self::AllocatorInUse => c"allocator already in use",
+        }
+    }
+}
+
+impl Display for TreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message().to_string_lossy())
+    }
+}
+
+impl std::error::Error for TreeError {}
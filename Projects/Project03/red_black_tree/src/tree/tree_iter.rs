@@ -0,0 +1,139 @@
+use std::{marker::PhantomData, ptr};
+
+use crate::{CharContainer, RedBlackTree, TreeKey};
+
+use super::TreeNode;
+
+/// Borrowing iterator over the entries of a [`RedBlackTree`] in ascending key order.
+pub struct Iter<'a, K> {
+    next: *mut TreeNode<K>,
+    marker: PhantomData<&'a TreeNode<K>>,
+}
+
+impl<'a, K> Iter<'a, K> {
+    pub(super) fn new(root: *mut TreeNode<K>) -> Self {
+        Self {
+            next: unsafe { leftmost(root) },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K> Iterator for Iter<'a, K> {
+    type Item = (&'a K, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+
+        unsafe {
+            let node = self.next;
+            self.next = successor(node);
+            Some((&(*node).key, (&(*node).value).into()))
+        }
+    }
+}
+
+/// Owning iterator over the entries of a [`RedBlackTree`] in ascending key order.
+///
+/// Every yielded node is unlinked and freed immediately; the entries that
+/// were not consumed are freed when the iterator is dropped.
+pub struct IntoIter<K> {
+    tree: RedBlackTree<K>,
+}
+
+impl<K> Iterator for IntoIter<K> {
+    type Item = (K, CharContainer);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let node = leftmost(self.tree.root);
+            if node.is_null() {
+                return None;
+            }
+
+            // the leftmost node has no left child, so it can be replaced by its right subtree
+            let parent = (*node).parent;
+            let right = (*node).right;
+            if !right.is_null() {
+                (*right).parent = parent;
+            }
+            if parent.is_null() {
+                self.tree.root = right;
+            } else {
+                (*parent).left = right;
+            }
+
+            let key = ptr::read(&(*node).key);
+            let value = ptr::read(&(*node).value);
+            self.tree.release_node(node);
+            Some((key, value))
+        }
+    }
+}
+
+unsafe fn leftmost<K>(mut node: *mut TreeNode<K>) -> *mut TreeNode<K> {
+    unsafe {
+        if node.is_null() {
+            return node;
+        }
+        while !(*node).left.is_null() {
+            node = (*node).left;
+        }
+        node
+    }
+}
+
+unsafe fn successor<K>(mut node: *mut TreeNode<K>) -> *mut TreeNode<K> {
+    unsafe {
+        if !(*node).right.is_null() {
+            return leftmost((*node).right);
+        }
+
+        let mut parent = (*node).parent;
+        while !parent.is_null() && node == (*parent).right {
+            node = parent;
+            parent = (*node).parent;
+        }
+        parent
+    }
+}
+
+impl<K> IntoIterator for RedBlackTree<K> {
+    type Item = (K, CharContainer);
+    type IntoIter = IntoIter<K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { tree: self }
+    }
+}
+
+impl<'a, K> IntoIterator for &'a RedBlackTree<K> {
+    type Item = (&'a K, &'a str);
+    type IntoIter = Iter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Values are copied into new [`CharContainer`]s; entries whose value
+/// could not be allocated are skipped.
+impl<K: TreeKey, V: AsRef<str>> Extend<(K, V)> for RedBlackTree<K> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            if let Some(value) = CharContainer::new(value.as_ref()) {
+                let _ = self.insert(key, value);
+            }
+        }
+    }
+}
+
+impl<K: TreeKey, V: AsRef<str>> FromIterator<(K, V)> for RedBlackTree<K> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut tree = Self::default();
+        tree.extend(iter);
+        tree
+    }
+}
@@ -0,0 +1,41 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::ByteKey;
+
+/// Type that can be used as a key of the [`RedBlackTree`](crate::RedBlackTree).
+///
+/// Lookups take a borrowed form of the key ([`TreeKey::Borrowed`]), so that
+/// searching for an owned key such as [`ByteKey`] does not require an allocation.
+pub trait TreeKey: Display {
+    type Borrowed<'a>: Copy
+    where
+        Self: 'a;
+
+    fn borrow_key(&self) -> Self::Borrowed<'_>;
+
+    fn compare(key: Self::Borrowed<'_>, other: &Self) -> Ordering;
+}
+
+impl TreeKey for u64 {
+    type Borrowed<'a> = u64;
+
+    fn borrow_key(&self) -> u64 {
+        *self
+    }
+
+    fn compare(key: u64, other: &Self) -> Ordering {
+        key.cmp(other)
+    }
+}
+
+impl TreeKey for ByteKey {
+    type Borrowed<'a> = &'a [u8];
+
+    fn borrow_key(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn compare(key: &[u8], other: &Self) -> Ordering {
+        key.cmp(other.as_bytes())
+    }
+}
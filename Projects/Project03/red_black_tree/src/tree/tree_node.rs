@@ -4,21 +4,24 @@ use crate::CharContainer;
 
 use super::TreeColor;
 
-pub struct TreeNode {
-    pub key: u64,
+pub struct TreeNode<K = u64> {
+    pub key: K,
     pub value: CharContainer,
     pub color: TreeColor,
-    pub left: *mut TreeNode,
-    pub right: *mut TreeNode,
-    pub parent: *mut TreeNode,
+    /// Number of nodes in the subtree rooted at this node (including itself).
+    pub size: usize,
+    pub left: *mut TreeNode<K>,
+    pub right: *mut TreeNode<K>,
+    pub parent: *mut TreeNode<K>,
 }
 
-impl TreeNode {
-    pub fn new(key: u64, value: CharContainer) -> Self {
+impl<K> TreeNode<K> {
+    pub fn new(key: K, value: CharContainer) -> Self {
         Self {
             key,
             value,
             color: TreeColor::default(),
+            size: 1,
             left: ptr::null_mut(),
             right: ptr::null_mut(),
             parent: ptr::null_mut(),